@@ -3,15 +3,16 @@ use std::iter;
 use plonky2::field::extension::Extendable;
 use plonky2::field::types::Field;
 use plonky2::hash::hash_types::RichField;
+use starky::config::StarkConfig;
+use starky::cross_table_lookup::{CrossTableLookup, TableWithColumns};
+use starky::stark::Stark;
 
 use crate::arithmetic::arithmetic_stark;
 use crate::arithmetic::arithmetic_stark::ArithmeticStark;
 use crate::byte_packing::byte_packing_stark::{self, BytePackingStark};
-use crate::config::StarkConfig;
 use crate::cpu::cpu_stark;
 use crate::cpu::cpu_stark::CpuStark;
 use crate::cpu::membus::NUM_GP_CHANNELS;
-use crate::cross_table_lookup::{CrossTableLookup, TableWithColumns};
 use crate::keccak::keccak_stark;
 use crate::keccak::keccak_stark::KeccakStark;
 use crate::keccak_sponge::columns::KECCAK_RATE_BYTES;
@@ -21,7 +22,6 @@ use crate::logic;
 use crate::logic::LogicStark;
 use crate::memory::memory_stark;
 use crate::memory::memory_stark::MemoryStark;
-use crate::stark::Stark;
 
 /// Structure containing all STARKs and the cross-table lookups.
 #[derive(Clone)]
@@ -120,22 +120,22 @@ fn ctl_arithmetic<F: Field>() -> CrossTableLookup<F> {
 /// `CrossTableLookup` for `BytePackingStark`, to connect it with the `Cpu` module.
 fn ctl_byte_packing<F: Field>() -> CrossTableLookup<F> {
     let cpu_packing_looking = TableWithColumns::new(
-        Table::Cpu,
+        Table::Cpu as usize,
         cpu_stark::ctl_data_byte_packing(),
         Some(cpu_stark::ctl_filter_byte_packing()),
     );
     let cpu_unpacking_looking = TableWithColumns::new(
-        Table::Cpu,
+        Table::Cpu as usize,
         cpu_stark::ctl_data_byte_unpacking(),
         Some(cpu_stark::ctl_filter_byte_unpacking()),
     );
     let cpu_push_packing_looking = TableWithColumns::new(
-        Table::Cpu,
+        Table::Cpu as usize,
         cpu_stark::ctl_data_byte_packing_push(),
         Some(cpu_stark::ctl_filter_byte_packing_push()),
     );
     let byte_packing_looked = TableWithColumns::new(
-        Table::BytePacking,
+        Table::BytePacking as usize,
         byte_packing_stark::ctl_looked_data(),
         Some(byte_packing_stark::ctl_looked_filter()),
     );
@@ -154,12 +154,12 @@ fn ctl_byte_packing<F: Field>() -> CrossTableLookup<F> {
 /// Its consistency with the 'output' CTL is ensured through a timestamp column on the `KeccakStark` side.
 fn ctl_keccak_inputs<F: Field>() -> CrossTableLookup<F> {
     let keccak_sponge_looking = TableWithColumns::new(
-        Table::KeccakSponge,
+        Table::KeccakSponge as usize,
         keccak_sponge_stark::ctl_looking_keccak_inputs(),
         Some(keccak_sponge_stark::ctl_looking_keccak_filter()),
     );
     let keccak_looked = TableWithColumns::new(
-        Table::Keccak,
+        Table::Keccak as usize,
         keccak_stark::ctl_data_inputs(),
         Some(keccak_stark::ctl_filter_inputs()),
     );
@@ -170,12 +170,12 @@ fn ctl_keccak_inputs<F: Field>() -> CrossTableLookup<F> {
 /// `KeccakStarkSponge` looks into `KeccakStark` to give the outputs of the sponge.
 fn ctl_keccak_outputs<F: Field>() -> CrossTableLookup<F> {
     let keccak_sponge_looking = TableWithColumns::new(
-        Table::KeccakSponge,
+        Table::KeccakSponge as usize,
         keccak_sponge_stark::ctl_looking_keccak_outputs(),
         Some(keccak_sponge_stark::ctl_looking_keccak_filter()),
     );
     let keccak_looked = TableWithColumns::new(
-        Table::Keccak,
+        Table::Keccak as usize,
         keccak_stark::ctl_data_outputs(),
         Some(keccak_stark::ctl_filter_outputs()),
     );
@@ -185,12 +185,12 @@ fn ctl_keccak_outputs<F: Field>() -> CrossTableLookup<F> {
 /// `CrossTableLookup` for `KeccakSpongeStark` to connect it with the `Cpu` module.
 fn ctl_keccak_sponge<F: Field>() -> CrossTableLookup<F> {
     let cpu_looking = TableWithColumns::new(
-        Table::Cpu,
+        Table::Cpu as usize,
         cpu_stark::ctl_data_keccak_sponge(),
         Some(cpu_stark::ctl_filter_keccak_sponge()),
     );
     let keccak_sponge_looked = TableWithColumns::new(
-        Table::KeccakSponge,
+        Table::KeccakSponge as usize,
         keccak_sponge_stark::ctl_looked_data(),
         Some(keccak_sponge_stark::ctl_looked_filter()),
     );
@@ -200,53 +200,56 @@ fn ctl_keccak_sponge<F: Field>() -> CrossTableLookup<F> {
 /// `CrossTableLookup` for `LogicStark` to connect it with the `Cpu` and `KeccakSponge` modules.
 fn ctl_logic<F: Field>() -> CrossTableLookup<F> {
     let cpu_looking = TableWithColumns::new(
-        Table::Cpu,
+        Table::Cpu as usize,
         cpu_stark::ctl_data_logic(),
         Some(cpu_stark::ctl_filter_logic()),
     );
     let mut all_lookers = vec![cpu_looking];
     for i in 0..keccak_sponge_stark::num_logic_ctls() {
         let keccak_sponge_looking = TableWithColumns::new(
-            Table::KeccakSponge,
+            Table::KeccakSponge as usize,
             keccak_sponge_stark::ctl_looking_logic(i),
             Some(keccak_sponge_stark::ctl_looking_logic_filter()),
         );
         all_lookers.push(keccak_sponge_looking);
     }
-    let logic_looked =
-        TableWithColumns::new(Table::Logic, logic::ctl_data(), Some(logic::ctl_filter()));
+    let logic_looked = TableWithColumns::new(
+        Table::Logic as usize,
+        logic::ctl_data(),
+        Some(logic::ctl_filter()),
+    );
     CrossTableLookup::new(all_lookers, logic_looked)
 }
 
 /// `CrossTableLookup` for `MemoryStark` to connect it with all the modules which need memory accesses.
 fn ctl_memory<F: Field>() -> CrossTableLookup<F> {
     let cpu_memory_code_read = TableWithColumns::new(
-        Table::Cpu,
+        Table::Cpu as usize,
         cpu_stark::ctl_data_code_memory(),
         Some(cpu_stark::ctl_filter_code_memory()),
     );
     let cpu_memory_gp_ops = (0..NUM_GP_CHANNELS).map(|channel| {
         TableWithColumns::new(
-            Table::Cpu,
+            Table::Cpu as usize,
             cpu_stark::ctl_data_gp_memory(channel),
             Some(cpu_stark::ctl_filter_gp_memory(channel)),
         )
     });
     let cpu_push_write_ops = TableWithColumns::new(
-        Table::Cpu,
+        Table::Cpu as usize,
         cpu_stark::ctl_data_partial_memory::<F>(),
         Some(cpu_stark::ctl_filter_partial_memory()),
     );
     let keccak_sponge_reads = (0..KECCAK_RATE_BYTES).map(|i| {
         TableWithColumns::new(
-            Table::KeccakSponge,
+            Table::KeccakSponge as usize,
             keccak_sponge_stark::ctl_looking_memory(i),
             Some(keccak_sponge_stark::ctl_looking_memory_filter(i)),
         )
     });
     let byte_packing_ops = (0..32).map(|i| {
         TableWithColumns::new(
-            Table::BytePacking,
+            Table::BytePacking as usize,
             byte_packing_stark::ctl_looking_memory(i),
             Some(byte_packing_stark::ctl_looking_memory_filter(i)),
         )
@@ -258,7 +261,7 @@ fn ctl_memory<F: Field>() -> CrossTableLookup<F> {
         .chain(byte_packing_ops)
         .collect();
     let memory_looked = TableWithColumns::new(
-        Table::Memory,
+        Table::Memory as usize,
         memory_stark::ctl_data(),
         Some(memory_stark::ctl_filter()),
     );
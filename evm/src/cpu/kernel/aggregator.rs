@@ -40,9 +40,11 @@ pub(crate) fn combined_kernel() -> Kernel {
         include_str!("asm/core/transfer.asm"),
         include_str!("asm/core/util.asm"),
         include_str!("asm/core/access_lists.asm"),
+        include_str!("asm/core/beacon_roots.asm"),
         include_str!("asm/core/log.asm"),
         include_str!("asm/core/selfdestruct_list.asm"),
         include_str!("asm/core/touched_addresses.asm"),
+        include_str!("asm/core/transient_storage.asm"),
         include_str!("asm/core/withdrawals.asm"),
         include_str!("asm/core/precompiles/main.asm"),
         include_str!("asm/core/precompiles/ecrec.asm"),
@@ -54,6 +56,22 @@ pub(crate) fn combined_kernel() -> Kernel {
         include_str!("asm/core/precompiles/bn_mul.asm"),
         include_str!("asm/core/precompiles/snarkv.asm"),
         include_str!("asm/core/precompiles/blake2_f.asm"),
+        include_str!("asm/core/precompiles/bls_g1_add.asm"),
+        include_str!("asm/core/precompiles/bls_g1_msm.asm"),
+        include_str!("asm/core/precompiles/bls_g2_add.asm"),
+        include_str!("asm/core/precompiles/bls_g2_msm.asm"),
+        include_str!("asm/core/precompiles/bls_pairing.asm"),
+        include_str!("asm/core/precompiles/bls_util.asm"),
+        include_str!("asm/core/precompiles/kzg_peval.asm"),
+        include_str!("asm/curve/bls381/curve_arithmetic/curve_add.asm"),
+        include_str!("asm/curve/bls381/curve_arithmetic/final_exponent.asm"),
+        include_str!("asm/curve/bls381/curve_arithmetic/miller_loop.asm"),
+        include_str!("asm/curve/bls381/curve_arithmetic/msm.asm"),
+        include_str!("asm/curve/bls381/curve_arithmetic/pairing.asm"),
+        include_str!("asm/curve/bls381/field_arithmetic/degree_12_mul.asm"),
+        include_str!("asm/curve/bls381/field_arithmetic/frobenius.asm"),
+        include_str!("asm/curve/bls381/field_arithmetic/inverse.asm"),
+        include_str!("asm/curve/bls381/kzg.asm"),
         include_str!("asm/curve/bls381/util.asm"),
         include_str!("asm/curve/bn254/curve_arithmetic/constants.asm"),
         include_str!("asm/curve/bn254/curve_arithmetic/curve_add.asm"),
@@ -150,11 +168,13 @@ pub(crate) fn combined_kernel() -> Kernel {
         include_str!("asm/journal/account_created.asm"),
         include_str!("asm/journal/revert.asm"),
         include_str!("asm/journal/log.asm"),
+        include_str!("asm/journal/transient_storage_change.asm"),
         include_str!("asm/transactions/common_decoding.asm"),
         include_str!("asm/transactions/router.asm"),
         include_str!("asm/transactions/type_0.asm"),
         include_str!("asm/transactions/type_1.asm"),
         include_str!("asm/transactions/type_2.asm"),
+        include_str!("asm/transactions/type_3.asm"),
         include_str!("asm/util/assertions.asm"),
         include_str!("asm/util/basic_macros.asm"),
         include_str!("asm/util/keccak.asm"),
@@ -0,0 +1,98 @@
+/// EIP-4788: before the first transaction of a block, the beacon roots contract storage is
+/// updated as if by a system call to the contract with the parent beacon block root:
+///
+/// def set_beacon_root():
+///     timestamp_idx = timestamp % HISTORY_BUFFER_LENGTH
+///     storage[timestamp_idx] = timestamp
+///     storage[timestamp_idx + HISTORY_BUFFER_LENGTH] = parent_beacon_block_root
+///
+/// If the contract has no code, the call fails silently and the state is left unchanged.
+
+%macro set_beacon_root
+    // stack: (empty)
+    PUSH %%after
+    %jump(set_beacon_root)
+%%after:
+    // stack: (empty)
+%endmacro
+
+global set_beacon_root:
+    // stack: retdest
+    PUSH @BEACON_ROOTS_ADDRESS %mpt_read_state_trie
+    // stack: account_ptr, retdest
+    DUP1 ISZERO %jumpi(set_beacon_root_skip)
+    DUP1 %add_const(3) %mload_trie_data
+    // stack: code_hash, account_ptr, retdest
+    %eq_const(@EMPTY_STRING_HASH) %jumpi(set_beacon_root_skip)
+    // stack: account_ptr, retdest
+    %timestamp
+    PUSH @HISTORY_BUFFER_LENGTH DUP2 MOD
+    // stack: timestamp_idx, timestamp, account_ptr, retdest
+    %stack (timestamp_idx, timestamp, account_ptr) ->
+        (account_ptr, timestamp_idx, timestamp, set_beacon_root_contd, account_ptr, timestamp_idx)
+    %jump(beacon_roots_sstore)
+set_beacon_root_contd:
+    // stack: account_ptr, timestamp_idx, retdest
+    SWAP1 %add_const(@HISTORY_BUFFER_LENGTH)
+    %mload_global_metadata(@GLOBAL_METADATA_PARENT_BEACON_BLOCK_ROOT)
+    // stack: parent_beacon_block_root, root_idx, account_ptr, retdest
+    %stack (parent_beacon_block_root, root_idx, account_ptr) -> (account_ptr, root_idx, parent_beacon_block_root)
+    %jump(beacon_roots_sstore)
+
+set_beacon_root_skip:
+    // stack: account_ptr, retdest
+    POP
+    JUMP
+
+// Sets the given slot of the storage trie of the account at account_ptr to value. As with
+// SSTORE, a zero value deletes the slot, and writing the current value is a no-op.
+beacon_roots_sstore:
+    // stack: account_ptr, slot, value, retdest
+    SWAP1 %slot_to_storage_key SWAP1
+    // stack: account_ptr, storage_key, value, retdest
+    PUSH beacon_roots_sstore_read
+    DUP3 PUSH 64 // storage_key has 64 nibbles
+    DUP4 %add_const(2) %mload_trie_data
+    // stack: storage_root_ptr, 64, storage_key, beacon_roots_sstore_read, account_ptr, storage_key, value, retdest
+    %jump(mpt_read)
+beacon_roots_sstore_read:
+    // stack: value_ptr, account_ptr, storage_key, value, retdest
+    // A null value_ptr derefs to 0 since @SEGMENT_TRIE_DATA[0] = 0.
+    %mload_trie_data
+    // stack: current_value, account_ptr, storage_key, value, retdest
+    DUP4 EQ %jumpi(beacon_roots_sstore_noop)
+    // stack: account_ptr, storage_key, value, retdest
+    DUP3 ISZERO %jumpi(beacon_roots_sstore_delete)
+
+    // First we write the value to MPT data, and get a pointer to it.
+    %get_trie_data_size
+    // stack: value_ptr, account_ptr, storage_key, value, retdest
+    %stack (value_ptr, account_ptr, storage_key, value) -> (value, value_ptr, account_ptr, storage_key)
+    %append_to_trie_data
+    // stack: value_ptr, account_ptr, storage_key, retdest
+    DUP2 %add_const(2) %mload_trie_data
+    // stack: storage_root_ptr, value_ptr, account_ptr, storage_key, retdest
+    %stack (storage_root_ptr, value_ptr, account_ptr, storage_key) ->
+        (storage_root_ptr, 64, storage_key, value_ptr, beacon_roots_sstore_update, account_ptr)
+    %jump(mpt_insert)
+
+beacon_roots_sstore_delete:
+    // stack: account_ptr, storage_key, value, retdest
+    DUP1 %add_const(2) %mload_trie_data
+    // stack: storage_root_ptr, account_ptr, storage_key, value, retdest
+    %stack (storage_root_ptr, account_ptr, storage_key, value) ->
+        (storage_root_ptr, 64, storage_key, beacon_roots_sstore_update, account_ptr)
+    %jump(mpt_delete)
+
+beacon_roots_sstore_update:
+    // stack: new_storage_root_ptr, account_ptr, retdest
+    SWAP1 %add_const(2)
+    // stack: account_storage_root_ptr_ptr, new_storage_root_ptr, retdest
+    %mstore_trie_data
+    // stack: retdest
+    JUMP
+
+beacon_roots_sstore_noop:
+    // stack: account_ptr, storage_key, value, retdest
+    %pop3
+    JUMP
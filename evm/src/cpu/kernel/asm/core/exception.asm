@@ -234,7 +234,9 @@ min_stack_len_for_opcode:
     BYTES 0  // 0x46, CHAINID
     BYTES 0  // 0x47, SELFBALANCE
     BYTES 0  // 0x48, BASEFEE
-    %rep 7  // 0x49-0x4f, invalid
+    BYTES 1  // 0x49, BLOBHASH
+    BYTES 0  // 0x4a, BLOBBASEFEE
+    %rep 5  // 0x4b-0x4f, invalid
         BYTES 0
     %endrep
 
@@ -250,9 +252,9 @@ min_stack_len_for_opcode:
     BYTES 0  // 0x59, MSIZE
     BYTES 0  // 0x5a, GAS
     BYTES 0  // 0x5b, JUMPDEST
-    %rep 3  // 0x5c-0x5e, invalid
-        BYTES 0
-    %endrep
+    BYTES 1  // 0x5c, TLOAD
+    BYTES 2  // 0x5d, TSTORE
+    BYTES 3  // 0x5e, MCOPY
 
     %rep 33 // 0x5f-0x7f, PUSH0-PUSH32
         BYTES 0
@@ -367,11 +369,11 @@ gas_cost_for_opcode:
         BYTES 0
     %endrep
 
-    %rep 25 //0x30-0x48, only syscalls
+    %rep 27 //0x30-0x4a, only syscalls
     BYTES 0  
     %endrep
 
-    %rep 7  // 0x49-0x4f, invalid
+    %rep 5  // 0x4b-0x4f, invalid
         BYTES 0
     %endrep
 
@@ -387,9 +389,9 @@ gas_cost_for_opcode:
     BYTES 0  // 0x59, MSIZE
     BYTES 0  // 0x5a, GAS
     BYTES @GAS_JUMPDEST  // 0x5b, JUMPDEST
-    %rep 3  // 0x5c-0x5e, invalid
-        BYTES 0
-    %endrep
+    BYTES 0  // 0x5c, TLOAD
+    BYTES 0  // 0x5d, TSTORE
+    BYTES 0  // 0x5e, MCOPY
 
     BYTES @GAS_BASE // 0x5f, PUSH0
     %rep 32 // 0x60-0x7f, PUSH1-PUSH32
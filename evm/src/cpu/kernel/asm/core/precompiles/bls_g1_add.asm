@@ -0,0 +1,31 @@
+// EIP-2537 G1 addition precompile.
+// The input is two G1 points, which must be on the curve but need not be in the subgroup.
+global precompile_bls_g1_add:
+    // stack: address, retdest, new_ctx, (old stack)
+    %pop2
+    // stack: new_ctx, (old stack)
+    %set_new_ctx_parent_pc(after_precompile)
+    // stack: new_ctx, (old stack)
+    DUP1
+    SET_CONTEXT
+    %checkpoint // Checkpoint
+    %increment_call_depth
+    // stack: (empty)
+    PUSH 0x100000000 // = 2^32 (is_kernel = true)
+    // stack: kexit_info
+
+    %charge_gas_const(@BLS_G1_ADD_GAS)
+
+    %calldatasize
+    %eq_const(256) ISZERO %jumpi(fault_exception)
+
+    // Load the points into R and S, and compute R + S.
+    %stack () -> (0, 0)
+    %bls_load_g1
+    %stack () -> (128, 8)
+    %bls_load_g1
+    PUSH bls_g1_add_return
+    %jump(bls_add)
+bls_g1_add_return:
+    // stack: kexit_info
+    %bls_return_g1
@@ -0,0 +1,53 @@
+// EIP-2537 G1 multi-scalar multiplication precompile.
+// The input is k >= 1 terms, each consisting of a G1 point in the subgroup and a 32-byte scalar.
+global precompile_bls_g1_msm:
+    // stack: address, retdest, new_ctx, (old stack)
+    %pop2
+    // stack: new_ctx, (old stack)
+    %set_new_ctx_parent_pc(after_precompile)
+    // stack: new_ctx, (old stack)
+    DUP1
+    SET_CONTEXT
+    %checkpoint // Checkpoint
+    %increment_call_depth
+    // stack: (empty)
+    PUSH 0x100000000 // = 2^32 (is_kernel = true)
+    // stack: kexit_info
+
+    PUSH 160 %calldatasize DUP2 DUP2
+    // stack: calldata_size, 160, calldata_size, 160, kexit_info
+    MOD %jumpi(fault_exception) // calldata_size should be a multiple of 160
+    DIV
+    // stack: k, kexit_info
+    DUP1 ISZERO %jumpi(fault_exception)
+    DUP1 %bls_msm_gas(bls_g1_msm_discounts, @BLS_G1_MSM_GAS)
+    %stack (gas, k, kexit_info) -> (gas, kexit_info, k)
+    %charge_gas
+    SWAP1
+    // stack: k, kexit_info
+    PUSH 0
+bls_g1_msm_loading_loop:
+    // stack: i, k, kexit_info
+    DUP2 DUP2 EQ %jumpi(bls_g1_msm_loading_done)
+    DUP1 %mul_const(9) %add_const(@BLS_INP)
+    DUP2 %mul_const(160)
+    // stack: offset, ptr, i, k, kexit_info
+    DUP2 DUP2 %bls_load_g1
+    %add_const(128)
+    %stack (offset) -> (@SEGMENT_CALLDATA, offset, 32)
+    GET_CONTEXT
+    %mload_packing
+    // stack: scalar, ptr, i, k, kexit_info
+    DUP2 %add_const(8) %mstore_current_general
+    // stack: ptr, i, k, kexit_info
+    %bls_in_subgroup
+    ISZERO %jumpi(fault_exception)
+    // stack: i, k, kexit_info
+    %increment
+    %jump(bls_g1_msm_loading_loop)
+bls_g1_msm_loading_done:
+    %stack (i, k) -> (k, @BLS_INP, bls_g1_msm_return)
+    %jump(bls_msm)
+bls_g1_msm_return:
+    // stack: kexit_info
+    %bls_return_g1
@@ -0,0 +1,56 @@
+// EIP-2537 pairing check precompile.
+// The input is k >= 1 pairs of a G1 point and a G2 point, both in the subgroup. The output is 1
+// if the product of the pairings of the pairs is the unit, and 0 otherwise.
+global precompile_bls_pairing:
+    // stack: address, retdest, new_ctx, (old stack)
+    %pop2
+    // stack: new_ctx, (old stack)
+    %set_new_ctx_parent_pc(after_precompile)
+    // stack: new_ctx, (old stack)
+    DUP1
+    SET_CONTEXT
+    %checkpoint // Checkpoint
+    %increment_call_depth
+    // stack: (empty)
+    PUSH 0x100000000 // = 2^32 (is_kernel = true)
+    // stack: kexit_info
+
+    PUSH 384 %calldatasize DUP2 DUP2
+    // stack: calldata_size, 384, calldata_size, 384, kexit_info
+    MOD %jumpi(fault_exception) // calldata_size should be a multiple of 384
+    DIV
+    // stack: k, kexit_info
+    DUP1 ISZERO %jumpi(fault_exception)
+    DUP1 %mul_const(@BLS_PAIRING_DYNAMIC_GAS) %add_const(@BLS_PAIRING_STATIC_GAS)
+    %stack (gas, k, kexit_info) -> (gas, kexit_info, k)
+    %charge_gas
+    SWAP1
+    // stack: k, kexit_info
+    PUSH 0
+bls_pairing_loading_loop:
+    // stack: i, k, kexit_info
+    DUP2 DUP2 EQ %jumpi(bls_pairing_loading_done)
+    DUP1 %mul_const(16) %add_const(@BLS_INP)
+    DUP2 %mul_const(384)
+    // stack: offset, ptr, i, k, kexit_info
+    DUP2 DUP2 %bls_load_g1
+    DUP2 %add_const(8) DUP2 %add_const(128) %bls_load_g2
+    POP
+    // stack: ptr, i, k, kexit_info
+    DUP1 %bls_in_subgroup
+    ISZERO %jumpi(fault_exception)
+    %add_const(8) %bls_in_subgroup
+    ISZERO %jumpi(fault_exception)
+    // stack: i, k, kexit_info
+    %increment
+    %jump(bls_pairing_loading_loop)
+bls_pairing_loading_done:
+    %stack (i, k) -> (k, @BLS_INP, bls_pairing_return)
+    %jump(bls381_pairing)
+bls_pairing_return:
+    // stack: result, kexit_info
+    // Store the result bool (repr. by a U256) to the parent's return data using `mstore_unpacking`.
+    %mstore_parent_context_metadata(@CTX_METADATA_RETURNDATA_SIZE, 32)
+    %mload_context_metadata(@CTX_METADATA_PARENT_CONTEXT)
+    %stack (parent_ctx, result) -> (parent_ctx, @SEGMENT_RETURNDATA, 0, result, 32, pop_and_return_success)
+    %jump(mstore_unpacking)
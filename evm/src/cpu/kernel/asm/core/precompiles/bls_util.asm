@@ -0,0 +1,404 @@
+/// Helpers shared by the EIP-2537 BLS12-381 precompiles.
+///
+/// Field elements are encoded as 64 big-endian bytes, whose top 16 bytes must be zero and whose
+/// value must be less than the base field order. Fp2 elements c0 + c1 i are encoded as c0 || c1,
+/// G1 points as x || y and G2 points as x || y, with the point at infinity encoded as zeros.
+
+// Loads the field element at the given call data offset into the words (lo, hi) at dst.
+global bls_load_fp:
+    // stack: offset, dst, retdest
+    DUP1 %stack (offset) -> (@SEGMENT_CALLDATA, offset, 16)
+    GET_CONTEXT
+    %mload_packing
+    // stack: top, offset, dst, retdest
+    %jumpi(fault_exception)
+    DUP1 %add_const(16) %stack (offset) -> (@SEGMENT_CALLDATA, offset, 16)
+    GET_CONTEXT
+    %mload_packing
+    // stack: hi, offset, dst, retdest
+    SWAP1 %add_const(32) %stack (offset) -> (@SEGMENT_CALLDATA, offset, 32)
+    GET_CONTEXT
+    %mload_packing
+    // stack: lo, hi, dst, retdest
+    DUP2 DUP2 %lt_bls_base
+    ISZERO %jumpi(fault_exception)
+    // stack: lo, hi, dst, retdest
+    DUP3 %mstore_current_general
+    SWAP1 %increment %mstore_current_general
+    // stack: retdest
+    JUMP
+
+%macro bls_load_fp
+    // stack: offset, dst
+    %stack (offset, dst) -> (offset, dst, %%after)
+    %jump(bls_load_fp)
+%%after:
+%endmacro
+
+// Loads the G1 point at the given call data offset into the 8 words at dst, as described in
+// curve_add.asm, and checks that it is on the curve.
+global bls_load_g1:
+    // stack: offset, dst, retdest
+    DUP2 DUP2 %bls_load_fp
+    DUP2 %add_const(4) DUP2 %add_const(64) %bls_load_fp
+    // stack: offset, dst, retdest
+    PUSH 0 DUP3 %add_const(2) %mstore_current_general
+    PUSH 0 DUP3 %add_const(3) %mstore_current_general
+    PUSH 0 DUP3 %add_const(6) %mstore_current_general
+    PUSH 0 DUP3 %add_const(7) %mstore_current_general
+    POP
+    // stack: dst, retdest
+    %bls_is_on_curve_g1
+    ISZERO %jumpi(fault_exception)
+    // stack: retdest
+    JUMP
+
+// Loads the G2 point at the given call data offset into the 8 words at dst, and checks that
+// it is on the curve.
+global bls_load_g2:
+    // stack: offset, dst, retdest
+    DUP2 DUP2 %bls_load_fp
+    DUP2 %add_const(2) DUP2 %add_const(64) %bls_load_fp
+    DUP2 %add_const(4) DUP2 %add_const(128) %bls_load_fp
+    DUP2 %add_const(6) DUP2 %add_const(192) %bls_load_fp
+    POP
+    // stack: dst, retdest
+    %bls_is_on_curve_g2
+    ISZERO %jumpi(fault_exception)
+    // stack: retdest
+    JUMP
+
+%macro bls_load_g1
+    // stack: offset, dst
+    %stack (offset, dst) -> (offset, dst, %%after)
+    %jump(bls_load_g1)
+%%after:
+%endmacro
+
+%macro bls_load_g2
+    // stack: offset, dst
+    %stack (offset, dst) -> (offset, dst, %%after)
+    %jump(bls_load_g2)
+%%after:
+%endmacro
+
+// Writes the field element stored as (lo, hi) at ptr to the parent's return data at the given
+// offset, and returns the offset following it.
+global bls_return_fp:
+    // stack: parent_ctx, ptr, offset, retdest
+    DUP2 %increment %mload_current_general
+    // stack: hi, parent_ctx, ptr, offset, retdest
+    %stack (hi, parent_ctx, ptr, offset) -> (parent_ctx, @SEGMENT_RETURNDATA, offset, hi, 32, ptr, parent_ctx)
+    %mstore_unpacking
+    // stack: offset, ptr, parent_ctx, retdest
+    SWAP1 %mload_current_general
+    // stack: lo, offset, parent_ctx, retdest
+    %stack (lo, offset, parent_ctx) -> (parent_ctx, @SEGMENT_RETURNDATA, offset, lo, 32)
+    %mstore_unpacking
+    // stack: offset, retdest
+    SWAP1
+    JUMP
+
+// Returns the G1 point in R = [0..8] to the parent context.
+%macro bls_return_g1
+    // stack: kexit_info
+    %mstore_parent_context_metadata(@CTX_METADATA_RETURNDATA_SIZE, 128)
+    %mload_context_metadata(@CTX_METADATA_PARENT_CONTEXT)
+    %stack (parent_ctx) -> (parent_ctx, 0, 0, %%y, parent_ctx)
+    %jump(bls_return_fp)
+%%y:
+    // stack: offset, parent_ctx, kexit_info
+    %stack (offset, parent_ctx) -> (parent_ctx, 4, offset, pop_and_return_success)
+    %jump(bls_return_fp)
+%endmacro
+
+// Returns the G2 point in R = [0..8] to the parent context.
+%macro bls_return_g2
+    // stack: kexit_info
+    %mstore_parent_context_metadata(@CTX_METADATA_RETURNDATA_SIZE, 256)
+    %mload_context_metadata(@CTX_METADATA_PARENT_CONTEXT)
+    %stack (parent_ctx) -> (parent_ctx, 0, 0, %%x_im, parent_ctx)
+    %jump(bls_return_fp)
+%%x_im:
+    %stack (offset, parent_ctx) -> (parent_ctx, 2, offset, %%y_re, parent_ctx)
+    %jump(bls_return_fp)
+%%y_re:
+    %stack (offset, parent_ctx) -> (parent_ctx, 4, offset, %%y_im, parent_ctx)
+    %jump(bls_return_fp)
+%%y_im:
+    // stack: offset, parent_ctx, kexit_info
+    %stack (offset, parent_ctx) -> (parent_ctx, 6, offset, pop_and_return_success)
+    %jump(bls_return_fp)
+%endmacro
+
+// The MSM gas cost is k * base_gas * discount(k) / 1000, where the discounts are given by the
+// tables below for k <= 128, and by the last entry for larger k.
+%macro bls_msm_gas(discounts, base_gas)
+    // stack: k
+    DUP1 %min_const(128) %decrement
+    %mload_kernel_code_u32($discounts)
+    MUL
+    %mul_const($base_gas)
+    %div_const(1000)
+    // stack: gas
+%endmacro
+
+global bls_g1_msm_discounts:
+    BYTES 0x00, 0x00, 0x03, 0xE8
+    BYTES 0x00, 0x00, 0x03, 0xB5
+    BYTES 0x00, 0x00, 0x03, 0x50
+    BYTES 0x00, 0x00, 0x03, 0x1D
+    BYTES 0x00, 0x00, 0x02, 0xFC
+    BYTES 0x00, 0x00, 0x02, 0xEE
+    BYTES 0x00, 0x00, 0x02, 0xE2
+    BYTES 0x00, 0x00, 0x02, 0xD8
+    BYTES 0x00, 0x00, 0x02, 0xCF
+    BYTES 0x00, 0x00, 0x02, 0xC8
+    BYTES 0x00, 0x00, 0x02, 0xC1
+    BYTES 0x00, 0x00, 0x02, 0xBA
+    BYTES 0x00, 0x00, 0x02, 0xB4
+    BYTES 0x00, 0x00, 0x02, 0xAF
+    BYTES 0x00, 0x00, 0x02, 0xAA
+    BYTES 0x00, 0x00, 0x02, 0xA5
+    BYTES 0x00, 0x00, 0x02, 0xA1
+    BYTES 0x00, 0x00, 0x02, 0x9D
+    BYTES 0x00, 0x00, 0x02, 0x99
+    BYTES 0x00, 0x00, 0x02, 0x95
+    BYTES 0x00, 0x00, 0x02, 0x92
+    BYTES 0x00, 0x00, 0x02, 0x8E
+    BYTES 0x00, 0x00, 0x02, 0x8B
+    BYTES 0x00, 0x00, 0x02, 0x88
+    BYTES 0x00, 0x00, 0x02, 0x85
+    BYTES 0x00, 0x00, 0x02, 0x82
+    BYTES 0x00, 0x00, 0x02, 0x80
+    BYTES 0x00, 0x00, 0x02, 0x7D
+    BYTES 0x00, 0x00, 0x02, 0x7B
+    BYTES 0x00, 0x00, 0x02, 0x78
+    BYTES 0x00, 0x00, 0x02, 0x76
+    BYTES 0x00, 0x00, 0x02, 0x73
+    BYTES 0x00, 0x00, 0x02, 0x71
+    BYTES 0x00, 0x00, 0x02, 0x6F
+    BYTES 0x00, 0x00, 0x02, 0x6D
+    BYTES 0x00, 0x00, 0x02, 0x6B
+    BYTES 0x00, 0x00, 0x02, 0x69
+    BYTES 0x00, 0x00, 0x02, 0x67
+    BYTES 0x00, 0x00, 0x02, 0x65
+    BYTES 0x00, 0x00, 0x02, 0x63
+    BYTES 0x00, 0x00, 0x02, 0x61
+    BYTES 0x00, 0x00, 0x02, 0x60
+    BYTES 0x00, 0x00, 0x02, 0x5E
+    BYTES 0x00, 0x00, 0x02, 0x5C
+    BYTES 0x00, 0x00, 0x02, 0x5B
+    BYTES 0x00, 0x00, 0x02, 0x59
+    BYTES 0x00, 0x00, 0x02, 0x57
+    BYTES 0x00, 0x00, 0x02, 0x56
+    BYTES 0x00, 0x00, 0x02, 0x54
+    BYTES 0x00, 0x00, 0x02, 0x53
+    BYTES 0x00, 0x00, 0x02, 0x51
+    BYTES 0x00, 0x00, 0x02, 0x50
+    BYTES 0x00, 0x00, 0x02, 0x4F
+    BYTES 0x00, 0x00, 0x02, 0x4D
+    BYTES 0x00, 0x00, 0x02, 0x4C
+    BYTES 0x00, 0x00, 0x02, 0x4A
+    BYTES 0x00, 0x00, 0x02, 0x49
+    BYTES 0x00, 0x00, 0x02, 0x48
+    BYTES 0x00, 0x00, 0x02, 0x46
+    BYTES 0x00, 0x00, 0x02, 0x45
+    BYTES 0x00, 0x00, 0x02, 0x44
+    BYTES 0x00, 0x00, 0x02, 0x43
+    BYTES 0x00, 0x00, 0x02, 0x41
+    BYTES 0x00, 0x00, 0x02, 0x40
+    BYTES 0x00, 0x00, 0x02, 0x3F
+    BYTES 0x00, 0x00, 0x02, 0x3E
+    BYTES 0x00, 0x00, 0x02, 0x3D
+    BYTES 0x00, 0x00, 0x02, 0x3C
+    BYTES 0x00, 0x00, 0x02, 0x3A
+    BYTES 0x00, 0x00, 0x02, 0x39
+    BYTES 0x00, 0x00, 0x02, 0x38
+    BYTES 0x00, 0x00, 0x02, 0x37
+    BYTES 0x00, 0x00, 0x02, 0x36
+    BYTES 0x00, 0x00, 0x02, 0x35
+    BYTES 0x00, 0x00, 0x02, 0x34
+    BYTES 0x00, 0x00, 0x02, 0x33
+    BYTES 0x00, 0x00, 0x02, 0x32
+    BYTES 0x00, 0x00, 0x02, 0x31
+    BYTES 0x00, 0x00, 0x02, 0x30
+    BYTES 0x00, 0x00, 0x02, 0x2F
+    BYTES 0x00, 0x00, 0x02, 0x2E
+    BYTES 0x00, 0x00, 0x02, 0x2D
+    BYTES 0x00, 0x00, 0x02, 0x2C
+    BYTES 0x00, 0x00, 0x02, 0x2B
+    BYTES 0x00, 0x00, 0x02, 0x2A
+    BYTES 0x00, 0x00, 0x02, 0x29
+    BYTES 0x00, 0x00, 0x02, 0x28
+    BYTES 0x00, 0x00, 0x02, 0x27
+    BYTES 0x00, 0x00, 0x02, 0x26
+    BYTES 0x00, 0x00, 0x02, 0x25
+    BYTES 0x00, 0x00, 0x02, 0x24
+    BYTES 0x00, 0x00, 0x02, 0x23
+    BYTES 0x00, 0x00, 0x02, 0x23
+    BYTES 0x00, 0x00, 0x02, 0x22
+    BYTES 0x00, 0x00, 0x02, 0x21
+    BYTES 0x00, 0x00, 0x02, 0x20
+    BYTES 0x00, 0x00, 0x02, 0x1F
+    BYTES 0x00, 0x00, 0x02, 0x1E
+    BYTES 0x00, 0x00, 0x02, 0x1D
+    BYTES 0x00, 0x00, 0x02, 0x1C
+    BYTES 0x00, 0x00, 0x02, 0x1C
+    BYTES 0x00, 0x00, 0x02, 0x1B
+    BYTES 0x00, 0x00, 0x02, 0x1A
+    BYTES 0x00, 0x00, 0x02, 0x19
+    BYTES 0x00, 0x00, 0x02, 0x18
+    BYTES 0x00, 0x00, 0x02, 0x18
+    BYTES 0x00, 0x00, 0x02, 0x17
+    BYTES 0x00, 0x00, 0x02, 0x16
+    BYTES 0x00, 0x00, 0x02, 0x15
+    BYTES 0x00, 0x00, 0x02, 0x14
+    BYTES 0x00, 0x00, 0x02, 0x14
+    BYTES 0x00, 0x00, 0x02, 0x13
+    BYTES 0x00, 0x00, 0x02, 0x12
+    BYTES 0x00, 0x00, 0x02, 0x11
+    BYTES 0x00, 0x00, 0x02, 0x10
+    BYTES 0x00, 0x00, 0x02, 0x10
+    BYTES 0x00, 0x00, 0x02, 0x0F
+    BYTES 0x00, 0x00, 0x02, 0x0E
+    BYTES 0x00, 0x00, 0x02, 0x0D
+    BYTES 0x00, 0x00, 0x02, 0x0D
+    BYTES 0x00, 0x00, 0x02, 0x0C
+    BYTES 0x00, 0x00, 0x02, 0x0B
+    BYTES 0x00, 0x00, 0x02, 0x0A
+    BYTES 0x00, 0x00, 0x02, 0x0A
+    BYTES 0x00, 0x00, 0x02, 0x09
+    BYTES 0x00, 0x00, 0x02, 0x08
+    BYTES 0x00, 0x00, 0x02, 0x08
+    BYTES 0x00, 0x00, 0x02, 0x07
+
+global bls_g2_msm_discounts:
+    BYTES 0x00, 0x00, 0x03, 0xE8
+    BYTES 0x00, 0x00, 0x03, 0xE8
+    BYTES 0x00, 0x00, 0x03, 0x9B
+    BYTES 0x00, 0x00, 0x03, 0x74
+    BYTES 0x00, 0x00, 0x03, 0x57
+    BYTES 0x00, 0x00, 0x03, 0x40
+    BYTES 0x00, 0x00, 0x03, 0x2C
+    BYTES 0x00, 0x00, 0x03, 0x1C
+    BYTES 0x00, 0x00, 0x03, 0x0E
+    BYTES 0x00, 0x00, 0x03, 0x02
+    BYTES 0x00, 0x00, 0x02, 0xF7
+    BYTES 0x00, 0x00, 0x02, 0xED
+    BYTES 0x00, 0x00, 0x02, 0xE4
+    BYTES 0x00, 0x00, 0x02, 0xDC
+    BYTES 0x00, 0x00, 0x02, 0xD4
+    BYTES 0x00, 0x00, 0x02, 0xCD
+    BYTES 0x00, 0x00, 0x02, 0xC7
+    BYTES 0x00, 0x00, 0x02, 0xC0
+    BYTES 0x00, 0x00, 0x02, 0xBB
+    BYTES 0x00, 0x00, 0x02, 0xB5
+    BYTES 0x00, 0x00, 0x02, 0xB0
+    BYTES 0x00, 0x00, 0x02, 0xAB
+    BYTES 0x00, 0x00, 0x02, 0xA7
+    BYTES 0x00, 0x00, 0x02, 0xA2
+    BYTES 0x00, 0x00, 0x02, 0x9E
+    BYTES 0x00, 0x00, 0x02, 0x9A
+    BYTES 0x00, 0x00, 0x02, 0x97
+    BYTES 0x00, 0x00, 0x02, 0x93
+    BYTES 0x00, 0x00, 0x02, 0x8F
+    BYTES 0x00, 0x00, 0x02, 0x8C
+    BYTES 0x00, 0x00, 0x02, 0x89
+    BYTES 0x00, 0x00, 0x02, 0x86
+    BYTES 0x00, 0x00, 0x02, 0x83
+    BYTES 0x00, 0x00, 0x02, 0x80
+    BYTES 0x00, 0x00, 0x02, 0x7D
+    BYTES 0x00, 0x00, 0x02, 0x7A
+    BYTES 0x00, 0x00, 0x02, 0x78
+    BYTES 0x00, 0x00, 0x02, 0x75
+    BYTES 0x00, 0x00, 0x02, 0x73
+    BYTES 0x00, 0x00, 0x02, 0x70
+    BYTES 0x00, 0x00, 0x02, 0x6E
+    BYTES 0x00, 0x00, 0x02, 0x6C
+    BYTES 0x00, 0x00, 0x02, 0x6A
+    BYTES 0x00, 0x00, 0x02, 0x67
+    BYTES 0x00, 0x00, 0x02, 0x65
+    BYTES 0x00, 0x00, 0x02, 0x63
+    BYTES 0x00, 0x00, 0x02, 0x61
+    BYTES 0x00, 0x00, 0x02, 0x5F
+    BYTES 0x00, 0x00, 0x02, 0x5E
+    BYTES 0x00, 0x00, 0x02, 0x5C
+    BYTES 0x00, 0x00, 0x02, 0x5A
+    BYTES 0x00, 0x00, 0x02, 0x58
+    BYTES 0x00, 0x00, 0x02, 0x56
+    BYTES 0x00, 0x00, 0x02, 0x55
+    BYTES 0x00, 0x00, 0x02, 0x53
+    BYTES 0x00, 0x00, 0x02, 0x51
+    BYTES 0x00, 0x00, 0x02, 0x50
+    BYTES 0x00, 0x00, 0x02, 0x4E
+    BYTES 0x00, 0x00, 0x02, 0x4D
+    BYTES 0x00, 0x00, 0x02, 0x4B
+    BYTES 0x00, 0x00, 0x02, 0x4A
+    BYTES 0x00, 0x00, 0x02, 0x48
+    BYTES 0x00, 0x00, 0x02, 0x47
+    BYTES 0x00, 0x00, 0x02, 0x46
+    BYTES 0x00, 0x00, 0x02, 0x44
+    BYTES 0x00, 0x00, 0x02, 0x43
+    BYTES 0x00, 0x00, 0x02, 0x42
+    BYTES 0x00, 0x00, 0x02, 0x40
+    BYTES 0x00, 0x00, 0x02, 0x3F
+    BYTES 0x00, 0x00, 0x02, 0x3E
+    BYTES 0x00, 0x00, 0x02, 0x3D
+    BYTES 0x00, 0x00, 0x02, 0x3B
+    BYTES 0x00, 0x00, 0x02, 0x3A
+    BYTES 0x00, 0x00, 0x02, 0x39
+    BYTES 0x00, 0x00, 0x02, 0x38
+    BYTES 0x00, 0x00, 0x02, 0x37
+    BYTES 0x00, 0x00, 0x02, 0x36
+    BYTES 0x00, 0x00, 0x02, 0x35
+    BYTES 0x00, 0x00, 0x02, 0x33
+    BYTES 0x00, 0x00, 0x02, 0x32
+    BYTES 0x00, 0x00, 0x02, 0x31
+    BYTES 0x00, 0x00, 0x02, 0x30
+    BYTES 0x00, 0x00, 0x02, 0x2F
+    BYTES 0x00, 0x00, 0x02, 0x2E
+    BYTES 0x00, 0x00, 0x02, 0x2D
+    BYTES 0x00, 0x00, 0x02, 0x2C
+    BYTES 0x00, 0x00, 0x02, 0x2B
+    BYTES 0x00, 0x00, 0x02, 0x2A
+    BYTES 0x00, 0x00, 0x02, 0x29
+    BYTES 0x00, 0x00, 0x02, 0x28
+    BYTES 0x00, 0x00, 0x02, 0x28
+    BYTES 0x00, 0x00, 0x02, 0x27
+    BYTES 0x00, 0x00, 0x02, 0x26
+    BYTES 0x00, 0x00, 0x02, 0x25
+    BYTES 0x00, 0x00, 0x02, 0x24
+    BYTES 0x00, 0x00, 0x02, 0x23
+    BYTES 0x00, 0x00, 0x02, 0x22
+    BYTES 0x00, 0x00, 0x02, 0x21
+    BYTES 0x00, 0x00, 0x02, 0x21
+    BYTES 0x00, 0x00, 0x02, 0x20
+    BYTES 0x00, 0x00, 0x02, 0x1F
+    BYTES 0x00, 0x00, 0x02, 0x1E
+    BYTES 0x00, 0x00, 0x02, 0x1D
+    BYTES 0x00, 0x00, 0x02, 0x1D
+    BYTES 0x00, 0x00, 0x02, 0x1C
+    BYTES 0x00, 0x00, 0x02, 0x1B
+    BYTES 0x00, 0x00, 0x02, 0x1A
+    BYTES 0x00, 0x00, 0x02, 0x19
+    BYTES 0x00, 0x00, 0x02, 0x19
+    BYTES 0x00, 0x00, 0x02, 0x18
+    BYTES 0x00, 0x00, 0x02, 0x17
+    BYTES 0x00, 0x00, 0x02, 0x17
+    BYTES 0x00, 0x00, 0x02, 0x16
+    BYTES 0x00, 0x00, 0x02, 0x15
+    BYTES 0x00, 0x00, 0x02, 0x14
+    BYTES 0x00, 0x00, 0x02, 0x14
+    BYTES 0x00, 0x00, 0x02, 0x13
+    BYTES 0x00, 0x00, 0x02, 0x12
+    BYTES 0x00, 0x00, 0x02, 0x12
+    BYTES 0x00, 0x00, 0x02, 0x11
+    BYTES 0x00, 0x00, 0x02, 0x10
+    BYTES 0x00, 0x00, 0x02, 0x10
+    BYTES 0x00, 0x00, 0x02, 0x0F
+    BYTES 0x00, 0x00, 0x02, 0x0E
+    BYTES 0x00, 0x00, 0x02, 0x0E
+    BYTES 0x00, 0x00, 0x02, 0x0D
+    BYTES 0x00, 0x00, 0x02, 0x0C
+    BYTES 0x00, 0x00, 0x02, 0x0C
@@ -0,0 +1,98 @@
+// EIP-4844 point evaluation precompile.
+// The input is versioned_hash (32 bytes) || z (32 bytes) || y (32 bytes) || commitment (48 bytes) || proof (48 bytes).
+global precompile_kzg_peval:
+    // stack: address, retdest, new_ctx, (old stack)
+    %pop2
+    // stack: new_ctx, (old stack)
+    %set_new_ctx_parent_pc(after_precompile)
+    // stack: new_ctx, (old stack)
+    DUP1
+    SET_CONTEXT
+    %checkpoint // Checkpoint
+    %increment_call_depth
+    // stack: (empty)
+    PUSH 0x100000000 // = 2^32 (is_kernel = true)
+    // stack: kexit_info
+
+    %charge_gas_const(@KZG_PEVAL_GAS)
+
+    %calldatasize
+    %eq_const(192) ISZERO %jumpi(fault_exception)
+
+    // Copy the commitment to the kernel general segment (sha2 expects it there) and hash it.
+    PUSH kzg_peval_contd
+    PUSH 48
+    PUSH 0
+    PUSH sha2
+    PUSH 48
+    PUSH 96
+    PUSH @SEGMENT_CALLDATA
+    GET_CONTEXT
+    PUSH 1
+    PUSH @SEGMENT_KERNEL_GENERAL
+    GET_CONTEXT
+    // stack: ctx, @SEGMENT_KERNEL_GENERAL, 1, ctx, @SEGMENT_CALLDATA, 96, 48, sha2, 0, 48, kzg_peval_contd, kexit_info
+    %jump(memcpy_bytes)
+
+kzg_peval_contd:
+    // stack: hash, kexit_info
+    // The versioned hash is the commitment hash, with its first byte replaced by the KZG version.
+    %shl_const(8) %shr_const(8)
+    PUSH @VERSIONED_HASH_VERSION_KZG %shl_const(248)
+    ADD
+    // stack: expected_versioned_hash, kexit_info
+    %stack () -> (@SEGMENT_CALLDATA, 0, 32)
+    GET_CONTEXT
+    %mload_packing
+    // stack: versioned_hash, expected_versioned_hash, kexit_info
+    EQ ISZERO %jumpi(fault_exception)
+    // stack: kexit_info
+
+    // Load z, y, commitment and proof from the call data using `mload_packing`.
+    // The 48-byte points are split into their leading 16 bytes and trailing 32 bytes.
+    %stack () -> (@SEGMENT_CALLDATA, 160, 32)
+    GET_CONTEXT
+    %mload_packing
+    // stack: proof_lo, kexit_info
+    %stack () -> (@SEGMENT_CALLDATA, 144, 16)
+    GET_CONTEXT
+    %mload_packing
+    // stack: proof_hi, proof_lo, kexit_info
+    %stack () -> (@SEGMENT_CALLDATA, 112, 32)
+    GET_CONTEXT
+    %mload_packing
+    // stack: commitment_lo, proof_hi, proof_lo, kexit_info
+    %stack () -> (@SEGMENT_CALLDATA, 96, 16)
+    GET_CONTEXT
+    %mload_packing
+    // stack: commitment_hi, commitment_lo, proof_hi, proof_lo, kexit_info
+    %stack () -> (@SEGMENT_CALLDATA, 64, 32)
+    GET_CONTEXT
+    %mload_packing
+    // stack: y, commitment_hi, commitment_lo, proof_hi, proof_lo, kexit_info
+    %stack () -> (@SEGMENT_CALLDATA, 32, 32)
+    GET_CONTEXT
+    %mload_packing
+    // stack: z, y, commitment_hi, commitment_lo, proof_hi, proof_lo, kexit_info
+
+    // z and y must be canonical elements of the BLS12-381 scalar field.
+    DUP1 %ge_const(@BLS_SCALAR) %jumpi(fault_exception)
+    DUP2 %ge_const(@BLS_SCALAR) %jumpi(fault_exception)
+
+    // The point decompression and the pairing check are done by `verify_kzg_proof`.
+    %stack (z, y, commitment: 2, proof: 2) -> (z, y, commitment, proof, kzg_peval_verified)
+    %jump(verify_kzg_proof)
+kzg_peval_verified:
+    // stack: is_valid, kexit_info
+    ISZERO %jumpi(fault_exception)
+    // stack: kexit_info
+
+    // Store FIELD_ELEMENTS_PER_BLOB and BLS_SCALAR to the parent's return data using `mstore_unpacking`.
+    %mstore_parent_context_metadata(@CTX_METADATA_RETURNDATA_SIZE, 64)
+    %mload_context_metadata(@CTX_METADATA_PARENT_CONTEXT)
+    %stack (parent_ctx) -> (parent_ctx, @SEGMENT_RETURNDATA, 0, @FIELD_ELEMENTS_PER_BLOB, 32, kzg_peval_contd2, parent_ctx)
+    %jump(mstore_unpacking)
+kzg_peval_contd2:
+    POP
+    %stack (parent_ctx) -> (parent_ctx, @SEGMENT_RETURNDATA, 32, @BLS_SCALAR, 32, pop_and_return_success)
+    %jump(mstore_unpacking)
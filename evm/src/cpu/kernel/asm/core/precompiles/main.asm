@@ -18,7 +18,13 @@ global handle_precompiles:
     DUP1 %eq_const(@BN_ADD) %jumpi(precompile_bn_add)
     DUP1 %eq_const(@BN_MUL) %jumpi(precompile_bn_mul)
     DUP1 %eq_const(@SNARKV) %jumpi(precompile_snarkv)
-    %eq_const(@BLAKE2_F) %jumpi(precompile_blake2_f)
+    DUP1 %eq_const(@BLAKE2_F) %jumpi(precompile_blake2_f)
+    DUP1 %eq_const(@KZG_PEVAL) %jumpi(precompile_kzg_peval)
+    DUP1 %eq_const(@BLS_G1_ADD) %jumpi(precompile_bls_g1_add)
+    DUP1 %eq_const(@BLS_G1_MSM) %jumpi(precompile_bls_g1_msm)
+    DUP1 %eq_const(@BLS_G2_ADD) %jumpi(precompile_bls_g2_add)
+    DUP1 %eq_const(@BLS_G2_MSM) %jumpi(precompile_bls_g2_msm)
+    %eq_const(@BLS_PAIRING) %jumpi(precompile_bls_pairing)
     // stack: retdest
     JUMP
 
@@ -7,6 +7,8 @@
 // Post stack: success, leftover_gas
 global process_normalized_txn:
     // stack: retdest
+    // Transient storage is discarded at the end of every transaction, see EIP-1153.
+    PUSH 0 %mstore_global_metadata(@GLOBAL_METADATA_TRANSIENT_STORAGE_LEN)
     %compute_fees
     // stack: retdest
 
@@ -41,7 +43,7 @@ global process_normalized_txn:
     DUP1 %ext_code_empty %assert_nonzero(invalid_txn_1)
     // stack: sender, retdest
 
-    // Assert sender balance >= gas_limit * gas_price + value.
+    // Assert sender balance >= gas_limit * gas_price + value + blob_gas * max_fee_per_blob_gas.
     %balance
     // stack: sender_balance, retdest
     %mload_txn_field(@TXN_FIELD_COMPUTED_FEE_PER_GAS)
@@ -49,6 +51,10 @@ global process_normalized_txn:
     MUL
     %mload_txn_field(@TXN_FIELD_VALUE)
     ADD
+    %blob_gas
+    %mload_txn_field(@TXN_FIELD_MAX_FEE_PER_BLOB_GAS)
+    MUL
+    ADD
     %assert_le(invalid_txn)
     // stack: retdest
 
@@ -66,10 +72,22 @@ global process_normalized_txn:
     %assert_eq(invalid_txn)
     // stack: retdest
 
+    // Assert max_fee_per_blob_gas >= blob_base_fee for blob transactions.
+    %mload_txn_field(@TXN_FIELD_BLOB_VERSIONED_HASHES_LEN) ISZERO %jumpi(buy_gas)
+    %blob_base_fee
+    %mload_txn_field(@TXN_FIELD_MAX_FEE_PER_BLOB_GAS)
+    %assert_ge(invalid_txn)
+    // stack: retdest
+
 global buy_gas:
     %mload_txn_field(@TXN_FIELD_COMPUTED_FEE_PER_GAS)
     %mload_txn_field(@TXN_FIELD_GAS_LIMIT)
     MUL
+    // The blob fee is burned upfront, and isn't refunded (EIP-4844).
+    %blob_gas
+    %blob_base_fee
+    MUL
+    ADD
     // stack: gas_cost, retdest
     %mload_txn_field(@TXN_FIELD_ORIGIN)
     // stack: sender_addr, gas_cost, retdest
@@ -97,6 +115,12 @@ global warm_precompiles:
     PUSH @BN_MUL %insert_accessed_addresses_no_return
     PUSH @SNARKV %insert_accessed_addresses_no_return
     PUSH @BLAKE2_F %insert_accessed_addresses_no_return
+    PUSH @KZG_PEVAL %insert_accessed_addresses_no_return
+    PUSH @BLS_G1_ADD %insert_accessed_addresses_no_return
+    PUSH @BLS_G1_MSM %insert_accessed_addresses_no_return
+    PUSH @BLS_G2_ADD %insert_accessed_addresses_no_return
+    PUSH @BLS_G2_MSM %insert_accessed_addresses_no_return
+    PUSH @BLS_PAIRING %insert_accessed_addresses_no_return
 
 // EIP-3651
 global warm_coinbase:
@@ -389,6 +413,14 @@ process_message_txn_fail:
     // stack: (empty)
 %endmacro
 
+// The blob gas of the current transaction, which is zero for non-blob transactions.
+%macro blob_gas
+    // stack: (empty)
+    %mload_txn_field(@TXN_FIELD_BLOB_VERSIONED_HASHES_LEN)
+    %mul_const(@GAS_PER_BLOB)
+    // stack: blob_gas
+%endmacro
+
 %macro non_intrinisic_gas
     // stack: (empty)
     %mload_txn_field(@TXN_FIELD_INTRINSIC_GAS)
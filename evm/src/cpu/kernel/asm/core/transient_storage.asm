@@ -0,0 +1,110 @@
+/// Transient storage, see EIP-1153.
+/// Transient storage is stored in an array of (address, slot, value) triples, in the
+/// SEGMENT_TRANSIENT_STORAGE segment of the kernel memory (context=0). The length of the array is
+/// stored in the global metadata, and is reset to zero at the start of every transaction.
+/// Searching is done by doing a linear search through the array, as for the access lists.
+/// Entries are never removed: a slot reverted to its previous value keeps its place in the array.
+
+%macro search_transient_storage
+    %stack (addr, slot) -> (addr, slot, %%after)
+    %jump(search_transient_storage)
+%%after:
+    // stack: i
+%endmacro
+
+/// Returns the index of the (addr, slot, value) triple in the transient storage array,
+/// or the length of the array if the slot hasn't been written in this transaction.
+global search_transient_storage:
+    // stack: addr, slot, retdest
+    %mload_global_metadata(@GLOBAL_METADATA_TRANSIENT_STORAGE_LEN)
+    // stack: len, addr, slot, retdest
+    PUSH 0
+search_transient_storage_loop:
+    %stack (i, len, addr, slot, retdest) -> (i, len, i, len, addr, slot, retdest)
+    EQ %jumpi(search_transient_storage_done)
+    // stack: i, len, addr, slot, retdest
+    DUP1 %increment %mload_kernel(@SEGMENT_TRANSIENT_STORAGE)
+    // stack: loaded_slot, i, len, addr, slot, retdest
+    DUP2 %mload_kernel(@SEGMENT_TRANSIENT_STORAGE)
+    // stack: loaded_addr, loaded_slot, i, len, addr, slot, retdest
+    DUP5 EQ
+    // stack: loaded_addr==addr, loaded_slot, i, len, addr, slot, retdest
+    SWAP1 DUP6 EQ
+    // stack: loaded_slot==slot, loaded_addr==addr, i, len, addr, slot, retdest
+    MUL // AND
+    %jumpi(search_transient_storage_done)
+    // stack: i, len, addr, slot, retdest
+    %add_const(3)
+    %jump(search_transient_storage_loop)
+
+search_transient_storage_done:
+    %stack (i, len, addr, slot, retdest) -> (retdest, i)
+    JUMP
+
+// Read a word from the current account's transient storage.
+//
+// Pre stack: kexit_info, slot
+// Post stack: value
+
+global sys_tload:
+    // stack: kexit_info, slot
+    %charge_gas_const(@GAS_WARMACCESS)
+    // stack: kexit_info, slot
+    SWAP1 %address
+    // stack: addr, slot, kexit_info
+    %search_transient_storage
+    // stack: i, kexit_info
+    DUP1 %mload_global_metadata(@GLOBAL_METADATA_TRANSIENT_STORAGE_LEN)
+    // stack: len, i, i, kexit_info
+    EQ %jumpi(tload_missing_slot)
+    // stack: i, kexit_info
+    %add_const(2) %mload_kernel(@SEGMENT_TRANSIENT_STORAGE)
+    // stack: value, kexit_info
+    SWAP1
+    EXIT_KERNEL
+
+tload_missing_slot:
+    // stack: i, kexit_info
+    %stack (i, kexit_info) -> (kexit_info, 0)
+    EXIT_KERNEL
+
+// Write a word to the current account's transient storage.
+//
+// Pre stack: kexit_info, slot, value
+// Post stack: (empty)
+
+global sys_tstore:
+    %check_static
+    %charge_gas_const(@GAS_WARMACCESS)
+    // stack: kexit_info, slot, value
+    DUP2 %address
+    // stack: addr, slot, kexit_info, slot, value
+    %search_transient_storage
+    // stack: i, kexit_info, slot, value
+    DUP1 %mload_global_metadata(@GLOBAL_METADATA_TRANSIENT_STORAGE_LEN)
+    // stack: len, i, i, kexit_info, slot, value
+    EQ %jumpi(tstore_new_slot)
+
+    // stack: i, kexit_info, slot, value
+    DUP1 %add_const(2) %mload_kernel(@SEGMENT_TRANSIENT_STORAGE)
+    // stack: prev_value, i, kexit_info, slot, value
+    DUP4 %address %journal_add_transient_storage_change
+    // stack: i, kexit_info, slot, value
+    %stack (i, kexit_info, slot, value) -> (i, value, kexit_info)
+    %add_const(2) %mstore_kernel(@SEGMENT_TRANSIENT_STORAGE)
+    // stack: kexit_info
+    EXIT_KERNEL
+
+tstore_new_slot:
+    // stack: len, kexit_info, slot, value
+    PUSH 0 DUP4 %address %journal_add_transient_storage_change
+    // stack: len, kexit_info, slot, value
+    %address DUP2 %mstore_kernel(@SEGMENT_TRANSIENT_STORAGE) // Store the address at the end of the array.
+    DUP3 DUP2 %increment %mstore_kernel(@SEGMENT_TRANSIENT_STORAGE) // Store the slot after that.
+    DUP4 DUP2 %add_const(2) %mstore_kernel(@SEGMENT_TRANSIENT_STORAGE) // Store the value after that.
+    // stack: len, kexit_info, slot, value
+    %add_const(3)
+    %mstore_global_metadata(@GLOBAL_METADATA_TRANSIENT_STORAGE_LEN) // Store the new length.
+    // stack: kexit_info, slot, value
+    %stack (kexit_info, slot, value) -> (kexit_info)
+    EXIT_KERNEL
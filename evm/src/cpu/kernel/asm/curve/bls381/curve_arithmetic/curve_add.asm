@@ -0,0 +1,182 @@
+/// BLS12-381 curve arithmetic shared by G1 and G2.
+///
+/// Points are affine pairs (x, y) of Fp2 elements stored in the current context's kernel
+/// general segment, with G1 points embedded by setting their imaginary parts to zero.
+/// The point at infinity is represented by (0, 0), which is not on either curve.
+///
+/// The routines below work on two fixed slots
+///     R = [0..8]   the accumulator
+///     S = [8..16]  the operand, which is left unchanged
+/// and compute R = R + S or R = 2R.
+
+// Adds S to R.
+global bls_add:
+    // stack: retdest
+    %load_fp381_2(8)  %is_zero_fp381_2
+    %load_fp381_2(12) %is_zero_fp381_2
+    AND
+    // stack: S == O, retdest
+    %jumpi(bls_add_return)
+    %load_fp381_2(0) %is_zero_fp381_2
+    %load_fp381_2(4) %is_zero_fp381_2
+    AND
+    // stack: R == O, retdest
+    %jumpi(bls_add_fst_zero)
+    %load_fp381_2(0) %load_fp381_2(8) %eq_fp381_2
+    // stack: x_R == x_S, retdest
+    %jumpi(bls_add_equal_first_coord)
+    // stack: retdest
+    %load_fp381_2(0) %load_fp381_2(8) %sub_fp381_2
+    // stack: x_S - x_R, retdest
+    %inv_fp381_2
+    %load_fp381_2(4) %load_fp381_2(12) %sub_fp381_2
+    // stack: y_S - y_R, 1/(x_S - x_R), retdest
+    %mul_fp381_2
+    // stack: lambda, retdest
+    %load_fp381_2(8)
+    %stack (x_S: 4, lambda: 4) -> (lambda, x_S)
+    // stack: lambda, x_S, retdest
+    %jump(bls_add_with_lambda)
+
+// Given the slope lambda of the line through R and another point with first coordinate x_S,
+// sets R to the third intersection of the line with the curve, reflected over the x-axis.
+bls_add_with_lambda:
+    // stack: lambda, x_S, retdest
+    %stack (lambda: 4) -> (lambda, lambda, lambda)
+    %mul_fp381_2
+    // stack: lambda^2, lambda, x_S, retdest
+    %stack (lambda2: 4, lambda: 4, x_S: 4) -> (x_S, lambda2, lambda)
+    %load_fp381_2(0)
+    %add_fp381_2
+    // stack: x_R + x_S, lambda^2, lambda, retdest
+    %stack (a: 4, b: 4) -> (b, a)
+    %sub_fp381_2
+    // stack: x, lambda, retdest
+    DUP4 DUP4 DUP4 DUP4
+    %load_fp381_2(0)
+    %sub_fp381_2
+    // stack: x_R - x, x, lambda, retdest
+    %stack (d: 4, x: 4, lambda: 4) -> (d, lambda, x)
+    %mul_fp381_2
+    // stack: lambda * (x_R - x), x, retdest
+    %load_fp381_2(4)
+    %stack (a: 4, b: 4) -> (b, a)
+    %sub_fp381_2
+    // stack: y, x, retdest
+    %store_fp381_2(4)
+    %store_fp381_2(0)
+    // stack: retdest
+    JUMP
+
+bls_add_fst_zero:
+    // stack: retdest
+    %load_fp381_2(8)  %store_fp381_2(0)
+    %load_fp381_2(12) %store_fp381_2(4)
+bls_add_return:
+    // stack: retdest
+    JUMP
+
+bls_add_equal_first_coord:
+    // stack: retdest
+    %load_fp381_2(4) %load_fp381_2(12) %eq_fp381_2
+    // stack: y_R == y_S, retdest
+    %jumpi(bls_double)
+    // Otherwise S = -R, so R + S = O.
+    %jump(bls_set_zero)
+
+// Doubles R.
+global bls_double:
+    // stack: retdest
+    // If y_R = 0, either R is the point at infinity or a point of order 2, and 2R = O.
+    %load_fp381_2(4) %is_zero_fp381_2
+    %jumpi(bls_set_zero)
+    // stack: retdest
+    %load_fp381_2(4)
+    DUP4 DUP4 DUP4 DUP4
+    %add_fp381_2
+    // stack: 2 * y_R, retdest
+    %inv_fp381_2
+    %load_fp381_2(0)
+    %stack (x: 4) -> (x, x)
+    %mul_fp381_2
+    %stack (x2: 4) -> (x2, x2, x2)
+    %add_fp381_2
+    %add_fp381_2
+    // stack: 3 * x_R^2, 1/(2 * y_R), retdest
+    %mul_fp381_2
+    // stack: lambda, retdest
+    %load_fp381_2(0)
+    %stack (x_R: 4, lambda: 4) -> (lambda, x_R)
+    // stack: lambda, x_R, retdest
+    %jump(bls_add_with_lambda)
+
+global bls_set_zero:
+    // stack: retdest
+    %zero_fp381_2 %store_fp381_2(0)
+    %zero_fp381_2 %store_fp381_2(4)
+    JUMP
+
+// Returns 1 if the point at ptr is the point at infinity or satisfies y^2 = x^3 + b, and 0 otherwise.
+// The coefficient b is 4 for G1 and 4(1 + i) for G2.
+global bls_is_on_curve:
+    // stack: ptr, b, retdest
+    DUP1 %load_fp381_2 %is_zero_fp381_2
+    DUP2 %add_const(4) %load_fp381_2 %is_zero_fp381_2
+    AND
+    // stack: is_infinity, ptr, b, retdest
+    %jumpi(bls_is_on_curve_infinity)
+    // stack: ptr, b, retdest
+    DUP1 %load_fp381_2
+    %stack (x: 4) -> (x, x, x)
+    %mul_fp381_2
+    %mul_fp381_2
+    // stack: x^3, ptr, b, retdest
+    %stack (x3: 4, ptr, b: 4) -> (x3, b, ptr)
+    %add_fp381_2
+    // stack: x^3 + b, ptr, retdest
+    DUP5 %add_const(4) %load_fp381_2
+    %stack (y: 4) -> (y, y)
+    %mul_fp381_2
+    // stack: y^2, x^3 + b, ptr, retdest
+    %eq_fp381_2
+    // stack: is_on_curve, ptr, retdest
+    %stack (is_on_curve, ptr, retdest) -> (retdest, is_on_curve)
+    JUMP
+bls_is_on_curve_infinity:
+    // stack: ptr, b, retdest
+    %stack (ptr, b: 4, retdest) -> (retdest, 1)
+    JUMP
+
+%macro bls_is_on_curve_g1
+    // stack: ptr
+    %stack (ptr) -> (ptr, 4, 0, 0, 0, %%after)
+    %jump(bls_is_on_curve)
+%%after:
+    // stack: is_on_curve
+%endmacro
+
+%macro bls_is_on_curve_g2
+    // stack: ptr
+    %stack (ptr) -> (ptr, 4, 0, 4, 0, %%after)
+    %jump(bls_is_on_curve)
+%%after:
+    // stack: is_on_curve
+%endmacro
+
+// Loads the 8 words of the point at ptr onto the stack.
+%macro load_bls_point
+    // stack: ptr
+    DUP1 %add_const(4) %load_fp381_2
+    %stack (y: 4, ptr) -> (ptr, y)
+    %load_fp381_2
+    // stack: x, y
+%endmacro
+
+%macro store_bls_point
+    // stack: ptr, x, y
+    %stack (ptr, x: 4) -> (ptr, x, ptr)
+    %store_fp381_2
+    %add_const(4)
+    %store_fp381_2
+    // stack:
+%endmacro
@@ -0,0 +1,87 @@
+/// As in the native bls_final_exponent, we first exponentiate by (p^6 - 1)(p^2 + 1) via
+///     y = y_6 / y
+///     y = y_2 * y
+/// and then by (p^4 - p^2 + 1)/N = (d3)p^3 + (d2)p^2 + (d1)p + d0 via
+///     y = (y_3)^d3 * (y_2)^d2 * (y_1)^d1 * y^d0
+/// where the four powers are computed simultaneously by square-and-multiply.
+///
+/// The element at inp is raised to the final exponent and stored at out, which may alias inp.
+/// The Frobenius images y, y_1, y_2, y_3 are kept in [196..220], [124..148], [148..172] and [172..196].
+
+global bls381_final_exponent:
+    // stack: inp, out, retdest
+    %stack (inp, out) -> (inp, 124, inp, out)
+    %inv_fp381_12
+    // stack: inp, out, retdest  {124: f^-1}
+    DUP2 SWAP1
+    %frob_fp381_12_6
+    // stack: out, retdest  {out: f_6, 124: f^-1}
+    %stack (out) -> (out, 124, out, out)
+    %mul_fp381_12
+    // stack: out, retdest  {out: y}
+    %stack (out) -> (out, 124, out)
+    %frob_fp381_12
+    %stack () -> (124, 124)
+    %frob_fp381_12
+    %stack (out) -> (124, out, out, out)
+    %mul_fp381_12
+    // stack: out, retdest  {out: y}
+    %stack (out) -> (out, 196, out)
+    %move_fp381_12
+    %stack () -> (196, 124)
+    %frob_fp381_12
+    %stack () -> (124, 148)
+    %frob_fp381_12
+    %stack () -> (148, 172)
+    %frob_fp381_12
+    // stack: out, retdest  {196: y, 124: y_1, 148: y_2, 172: y_3}
+    DUP1 %unit_fp381_12
+    PUSH 381
+final_exp_loop:
+    // stack: j, out, retdest
+    DUP1 ISZERO %jumpi(final_exp_end)
+    %decrement
+    DUP2 DUP1 DUP1
+    %mul_fp381_12
+    %final_exp_step(
+        0xf7a34148de09bf34665a045e22ec661f33813d5206aa1800aaaa0000aaaaaaac,
+        0x1a0111ea397fe69a2b688550f8cebd66,
+        196
+    )
+    %final_exp_step(
+        0x26a48d1bb889d46dc49f25e1a737f5e29d586d584eacaaaa73ffffffffff5554,
+        0,
+        124
+    )
+    %final_exp_step(
+        0x64774b84f38512bf38158e5c24aff488b27c92a7df51e7fe1ea8ffff5554aaab,
+        0x1a0111ea397fe69a4b1ba7b6434bacd7,
+        148
+    )
+    %final_exp_step(
+        0x396c8c005555e1568c00aaab0000aaaa,
+        0,
+        172
+    )
+    %jump(final_exp_loop)
+final_exp_end:
+    // stack: j, out, retdest
+    %pop2
+    JUMP
+
+// Multiplies out by the element at y_ptr if bit j of the digit d = d_lo + d_hi * 2^256 is set.
+%macro final_exp_step(d_lo, d_hi, y_ptr)
+    // stack: j, out
+    DUP1 %lt_const(256) %jumpi(%%lo)
+    DUP1 %sub_const(256) PUSH $d_hi SWAP1 SHR
+    %jump(%%bit)
+%%lo:
+    PUSH $d_lo DUP2 SHR
+%%bit:
+    // stack: d >> j, j, out
+    %and_const(1) ISZERO %jumpi(%%skip)
+    %stack (j, out) -> (out, $y_ptr, out, j, out)
+    %mul_fp381_12
+%%skip:
+    // stack: j, out
+%endmacro
@@ -0,0 +1,112 @@
+/// Computes the Miller loop of the pair (P, Q) stored at ptr as 16 words, with P in G1 embedded
+/// as in curve_add.asm and Q in G2, and stores the resulting Fp12 element at out.
+///
+/// def bls381_miller(P, Q):
+///     R = P
+///     out = 1
+///     for i in reversed(range(254)):
+///         line = tangent(R, Q)
+///         R = 2R
+///         out = line * out * out
+///         if BLS_SCALAR >> i & 1:
+///             line = cord(P, R, Q)
+///             R = R + P
+///             out = line * out
+///
+/// The points are kept in S = [8..16] (P), R = [0..8] and Q = [16..24], and the sparse line
+/// functions are written to the Fp12 element at [32..56].
+
+global bls381_miller:
+    // stack: ptr, out, retdest
+    DUP1 %load_bls_point PUSH 8 %store_bls_point
+    DUP1 %load_bls_point PUSH 0 %store_bls_point
+    %add_const(8) %load_bls_point PUSH 16 %store_bls_point
+    // stack: out, retdest
+    DUP1 %unit_fp381_12
+    // The coefficients of w^2, w^4 and w^5 of the line functions are always zero.
+    %zero_fp381_2 %store_fp381_2(36)
+    %zero_fp381_2 %store_fp381_2(40)
+    %zero_fp381_2 %store_fp381_2(52)
+    PUSH 253
+miller_loop:
+    // stack: i, out, retdest
+    %bls_tangent
+    PUSH miller_doubled
+    %jump(bls_double)
+miller_doubled:
+    // stack: i, out, retdest
+    DUP2 DUP1 DUP1
+    %mul_fp381_12
+    %stack (i, out) -> (out, 32, out, i, out)
+    %mul_fp381_12
+    // stack: i, out, retdest
+    PUSH @BLS_SCALAR DUP2 SHR %and_const(1)
+    ISZERO %jumpi(miller_next)
+    %bls_cord
+    PUSH miller_added
+    %jump(bls_add)
+miller_added:
+    // stack: i, out, retdest
+    %stack (i, out) -> (out, 32, out, i, out)
+    %mul_fp381_12
+miller_next:
+    // stack: i, out, retdest
+    DUP1 ISZERO %jumpi(miller_end)
+    %decrement
+    %jump(miller_loop)
+miller_end:
+    // stack: i, out, retdest
+    %pop2
+    JUMP
+
+/// The tangent line at R evaluated at Q, as in the native bls_tangent:
+///     line = (Qy * 2Ry) + (Qx * -3Rx^2) w + (Ry^2 - 12) w^3
+%macro bls_tangent
+    %load_fp381_2(4)
+    DUP4 DUP4 DUP4 DUP4
+    %add_fp381_2
+    %load_fp381_2(20)
+    %mul_fp381_2
+    %store_fp381_2(32)
+    %load_fp381_2(0)
+    %stack (x: 4) -> (x, x)
+    %mul_fp381_2
+    %stack (x2: 4) -> (x2, x2, x2)
+    %add_fp381_2
+    %add_fp381_2
+    %neg_fp381_2
+    %load_fp381_2(16)
+    %mul_fp381_2
+    %store_fp381_2(44)
+    %stack () -> (12, 0, 0, 0)
+    %load_fp381_2(4)
+    %stack (y: 4) -> (y, y)
+    %mul_fp381_2
+    %sub_fp381_2
+    %store_fp381_2(48)
+%endmacro
+
+/// The line through P and R evaluated at Q, as in the native bls_cord:
+///     line = (Qy * (Px - Rx)) + (Qx * (Ry - Py)) w + (Py * Rx - Ry * Px) w^3
+%macro bls_cord
+    %load_fp381_2(0)
+    %load_fp381_2(8)
+    %sub_fp381_2
+    %load_fp381_2(20)
+    %mul_fp381_2
+    %store_fp381_2(32)
+    %load_fp381_2(12)
+    %load_fp381_2(4)
+    %sub_fp381_2
+    %load_fp381_2(16)
+    %mul_fp381_2
+    %store_fp381_2(44)
+    %load_fp381_2(4)
+    %load_fp381_2(8)
+    %mul_fp381_2
+    %load_fp381_2(0)
+    %load_fp381_2(12)
+    %mul_fp381_2
+    %sub_fp381_2
+    %store_fp381_2(48)
+%endmacro
@@ -0,0 +1,95 @@
+/// Multi-scalar multiplication sum_i s_i * P_i over G1 or G2.
+///
+/// The k terms are read from the current context's kernel general segment starting at inp,
+/// each taking 9 words: the point P_i (see curve_add.asm) followed by the scalar s_i.
+/// The result is left in R = [0..8].
+///
+/// The scalars are processed simultaneously from their most significant bit, so that all
+/// terms share the same doublings:
+///
+/// def bls_msm(terms):
+///     R = O
+///     for j in reversed(range(256)):
+///         R = 2R
+///         for (P, s) in terms:
+///             if s >> j & 1:
+///                 R = R + P
+
+global bls_msm:
+    // stack: k, inp, retdest
+    PUSH bls_msm_start
+    %jump(bls_set_zero)
+bls_msm_start:
+    PUSH 256
+bls_msm_loop:
+    // stack: j, k, inp, retdest
+    DUP1 ISZERO %jumpi(bls_msm_end)
+    %decrement
+    // stack: j, k, inp, retdest
+    PUSH bls_msm_terms
+    %jump(bls_double)
+bls_msm_terms:
+    // stack: j, k, inp, retdest
+    PUSH 0
+bls_msm_terms_loop:
+    // stack: i, j, k, inp, retdest
+    DUP3 DUP2 EQ %jumpi(bls_msm_terms_end)
+    // stack: i, j, k, inp, retdest
+    DUP1 %mul_const(9) DUP5 ADD
+    // stack: ptr, i, j, k, inp, retdest
+    DUP1 %add_const(8) %mload_current_general
+    // stack: s, ptr, i, j, k, inp, retdest
+    DUP4 SHR %and_const(1)
+    // stack: s >> j & 1, ptr, i, j, k, inp, retdest
+    ISZERO %jumpi(bls_msm_skip)
+    // stack: ptr, i, j, k, inp, retdest
+    %load_bls_point
+    PUSH 8
+    %store_bls_point
+    // stack: i, j, k, inp, retdest
+    PUSH bls_msm_next
+    %jump(bls_add)
+bls_msm_skip:
+    // stack: ptr, i, j, k, inp, retdest
+    POP
+bls_msm_next:
+    // stack: i, j, k, inp, retdest
+    %increment
+    %jump(bls_msm_terms_loop)
+bls_msm_terms_end:
+    // stack: i, j, k, inp, retdest
+    POP
+    %jump(bls_msm_loop)
+bls_msm_end:
+    // stack: j, k, inp, retdest
+    %pop3
+    JUMP
+
+/// Returns 1 if the point at ptr lies in the prime order subgroup, i.e. if its multiple by
+/// the group order is the point at infinity, and 0 otherwise. Overwrites R, S and [16..25].
+global bls_in_subgroup:
+    // stack: ptr, retdest
+    %load_bls_point
+    PUSH 16
+    %store_bls_point
+    PUSH @BLS_SCALAR
+    %mstore_current_general(24)
+    // stack: retdest
+    %stack () -> (1, 16, bls_in_subgroup_contd)
+    %jump(bls_msm)
+bls_in_subgroup_contd:
+    // stack: retdest
+    %load_fp381_2(0) %is_zero_fp381_2
+    %load_fp381_2(4) %is_zero_fp381_2
+    AND
+    // stack: is_in_subgroup, retdest
+    SWAP1
+    JUMP
+
+%macro bls_in_subgroup
+    // stack: ptr
+    %stack (ptr) -> (ptr, %%after)
+    %jump(bls_in_subgroup)
+%%after:
+    // stack: is_in_subgroup
+%endmacro
@@ -0,0 +1,52 @@
+/// Returns 1 if the product of the pairings of the k pairs stored at inp is the unit, and 0
+/// otherwise. Each pair takes 16 words, laid out as in bls381_miller. The points are assumed
+/// to be valid, and pairs where either point is the point at infinity are skipped.
+///
+/// def bls381_pairing(pairs):
+///     acc = 1
+///     for (P, Q) in pairs:
+///         if P != O and Q != O:
+///             acc = acc * bls381_miller(P, Q)
+///     return bls381_final_exponent(acc) == 1
+///
+/// The Miller loop outputs are stored in [220..244], and the accumulator in [244..268].
+
+global bls381_pairing:
+    // stack: k, inp, retdest
+    PUSH 244 %unit_fp381_12
+    PUSH 0
+bls381_pairing_loop:
+    // stack: i, k, inp, retdest
+    DUP2 DUP2 EQ %jumpi(bls381_pairing_final)
+    DUP1 %mul_const(16) DUP4 ADD
+    // stack: ptr, i, k, inp, retdest
+    DUP1 %load_bls_point OR OR OR OR OR OR OR ISZERO
+    DUP2 %add_const(8) %load_bls_point OR OR OR OR OR OR OR ISZERO
+    OR
+    // stack: P == O || Q == O, ptr, i, k, inp, retdest
+    %jumpi(bls381_pairing_skip)
+    %stack (ptr) -> (ptr, 220, bls381_pairing_miller_done)
+    %jump(bls381_miller)
+bls381_pairing_miller_done:
+    // stack: i, k, inp, retdest
+    %stack () -> (244, 220, 244)
+    %mul_fp381_12
+    %increment
+    %jump(bls381_pairing_loop)
+bls381_pairing_skip:
+    // stack: ptr, i, k, inp, retdest
+    POP
+    %increment
+    %jump(bls381_pairing_loop)
+bls381_pairing_final:
+    // stack: i, k, inp, retdest
+    %pop3
+    %stack () -> (244, 244, bls381_pairing_check)
+    %jump(bls381_final_exponent)
+bls381_pairing_check:
+    // stack: retdest
+    PUSH 244
+    %is_unit_fp381_12
+    // stack: is_unit, retdest
+    SWAP1
+    JUMP
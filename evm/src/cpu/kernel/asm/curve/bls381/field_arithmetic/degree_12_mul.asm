@@ -0,0 +1,150 @@
+/// Fp12 elements are stored in the current context's kernel general segment in the same
+/// layout as the native tower Fp12 = Fp6[w]/(w^2 - v), Fp6 = Fp2[v]/(v^3 - xi), i.e. as the
+/// 24 words of z0.t0, z0.t1, z0.t2, z1.t0, z1.t1, z1.t2. Since w^6 = xi, the element is also
+///     sum_n d_n w^n  where  d_{2m} = z0.t_m  and  d_{2m+1} = z1.t_m
+/// and the multiplication below works with the coefficients d_n of this flat representation.
+
+// Offset of the coefficient d_n of w^n in the tower layout.
+%macro bls_fp12_offset
+    // stack: n
+    %mload_kernel_code(bls_fp12_offsets)
+    // stack: offset
+%endmacro
+
+bls_fp12_offsets:
+    BYTES 0, 12, 4, 16, 8, 20
+
+/// Schoolbook multiplication of a and b into out, which may alias either input.
+///
+/// def mul_fp381_12(a, b):
+///     t = [0] * 11
+///     for j in range(6):
+///         if b[j] != 0:
+///             for i in range(6):
+///                 t[i + j] += a[i] * b[j]
+///     return [t[n] + xi * t[n + 6] for n in range(5)] + [t[5]]
+///
+/// Skipping the zero coefficients of b makes multiplying by the sparse line functions cheap.
+/// The coefficients t are accumulated in [56..100].
+
+global mul_fp381_12:
+    // stack: a, b, out, retdest
+    PUSH 56
+mul_fp381_12_zero:
+    // stack: ptr, a, b, out, retdest
+    PUSH 0 DUP2 %mstore_current_general
+    %increment
+    DUP1 %eq_const(100) ISZERO %jumpi(mul_fp381_12_zero)
+    POP
+    PUSH 0
+mul_fp381_12_outer:
+    // stack: j, a, b, out, retdest
+    DUP1 %eq_const(6) %jumpi(mul_fp381_12_reduce)
+    DUP1 %bls_fp12_offset DUP4 ADD %load_fp381_2
+    // stack: b_j, j, a, b, out, retdest
+    DUP4 DUP4 DUP4 DUP4 %is_zero_fp381_2 %jumpi(mul_fp381_12_skip)
+    PUSH 0
+mul_fp381_12_inner:
+    // stack: i, b_j, j, a, b, out, retdest
+    DUP1 %eq_const(6) %jumpi(mul_fp381_12_inner_end)
+    DUP1 %bls_fp12_offset DUP8 ADD %load_fp381_2
+    // stack: a_i, i, b_j, j, a, b, out, retdest
+    DUP9 DUP9 DUP9 DUP9
+    %mul_fp381_2
+    // stack: a_i * b_j, i, b_j, j, a, b, out, retdest
+    DUP5 DUP11 ADD %mul_const(4) %add_const(56)
+    // stack: ptr, a_i * b_j, i, b_j, j, a, b, out, retdest
+    DUP1 %load_fp381_2
+    %stack (t: 4, ptr, v: 4) -> (t, v, ptr)
+    %add_fp381_2
+    %stack (t: 4, ptr) -> (ptr, t)
+    %store_fp381_2
+    // stack: i, b_j, j, a, b, out, retdest
+    %increment
+    %jump(mul_fp381_12_inner)
+mul_fp381_12_inner_end:
+    // stack: i, b_j, j, a, b, out, retdest
+    POP
+mul_fp381_12_skip:
+    // stack: b_j, j, a, b, out, retdest
+    %pop4
+    %increment
+    %jump(mul_fp381_12_outer)
+mul_fp381_12_reduce:
+    // stack: j, a, b, out, retdest
+    %stack (j, a, b, out) -> (out)
+    // stack: out, retdest
+    %mul_fp381_12_reduce(56, 80, 0)
+    %mul_fp381_12_reduce(60, 84, 12)
+    %mul_fp381_12_reduce(64, 88, 4)
+    %mul_fp381_12_reduce(68, 92, 16)
+    %mul_fp381_12_reduce(72, 96, 8)
+    %load_fp381_2(76)
+    DUP5 %add_const(20) %store_fp381_2
+    // stack: out, retdest
+    POP
+    JUMP
+
+// Stores t_lo + xi * t_hi at out + offset.
+%macro mul_fp381_12_reduce(lo, hi, offset)
+    // stack: out
+    %load_fp381_2($hi)
+    %mul_fp381_2_by_xi
+    %load_fp381_2($lo)
+    %add_fp381_2
+    // stack: t_lo + xi * t_hi, out
+    DUP5 %add_const($offset) %store_fp381_2
+    // stack: out
+%endmacro
+
+%macro mul_fp381_12
+    // stack: a, b, out
+    %stack (a, b, out) -> (a, b, out, %%after)
+    %jump(mul_fp381_12)
+%%after:
+%endmacro
+
+// Copies the Fp12 element at inp to out.
+%macro move_fp381_12
+    // stack: inp, out
+    PUSH 0
+%%loop:
+    // stack: i, inp, out
+    DUP2 DUP2 ADD %mload_current_general
+    DUP4 DUP3 ADD %mstore_current_general
+    %increment
+    DUP1 %eq_const(24) ISZERO %jumpi(%%loop)
+    // stack: i, inp, out
+    %pop3
+%endmacro
+
+// Stores the unit of Fp12 at ptr.
+%macro unit_fp381_12
+    // stack: ptr
+    PUSH 1 DUP2 %mstore_current_general
+    PUSH 1
+%%loop:
+    // stack: i, ptr
+    PUSH 0 DUP3 DUP3 ADD %mstore_current_general
+    %increment
+    DUP1 %eq_const(24) ISZERO %jumpi(%%loop)
+    // stack: i, ptr
+    %pop2
+%endmacro
+
+// Returns 1 if the Fp12 element at ptr is the unit, and 0 otherwise.
+%macro is_unit_fp381_12
+    // stack: ptr
+    DUP1 %mload_current_general %eq_const(1)
+    PUSH 1
+%%loop:
+    // stack: i, acc, ptr
+    DUP3 DUP2 ADD %mload_current_general ISZERO
+    %stack (is_zero, i, acc) -> (is_zero, acc, i)
+    AND SWAP1
+    %increment
+    DUP1 %eq_const(24) ISZERO %jumpi(%%loop)
+    // stack: i, acc, ptr
+    %stack (i, acc, ptr) -> (acc)
+    // stack: is_unit
+%endmacro
@@ -0,0 +1,88 @@
+/// The Frobenius map x -> x^p on Fp12, in the flat representation of degree_12_mul.asm, is
+///     sum_n d_n w^n -> sum_n conj(d_n) gamma_n w^n
+/// where gamma_n = xi^(n(p - 1)/6), since w^6 = xi.
+
+global frob_fp381_12:
+    // stack: inp, out, retdest
+    DUP1 %load_fp381_2
+    %conj_fp381_2
+    DUP6 %store_fp381_2
+    %frob_fp381_12_coeff(
+        12,
+        0x0fd603fd3cbd5f4f7b2443d784bab9c4f67ea53d63e7813d8d0775ed92235fb8,
+        0x1904d3bf02bb0667c231beb4202c0d1f,
+        0x54a14787b6c7b36fec0c8ec971f63c5f282d5ac14d6c7ec22cf78a126ddc4af3,
+        0x00fc3e2b36c4e03288e9e902231f9fb8
+    )
+    %frob_fp381_12_coeff(
+        4,
+        0,
+        0,
+        0xaa0d857d89759ad4897d29650fb85f9b409427eb4f49fffd8bfd00000000aaac,
+        0x1a0111ea397fe699ec02408663d4de85
+    )
+    %frob_fp381_12_coeff(
+        16,
+        0x48395dabc2d3435e77f76e17009241c5ee67992f72ec05f4c81084fbede3cc09,
+        0x06af0e0437ff400b6831e36d6bd17ffe,
+        0x48395dabc2d3435e77f76e17009241c5ee67992f72ec05f4c81084fbede3cc09,
+        0x06af0e0437ff400b6831e36d6bd17ffe
+    )
+    %frob_fp381_12_coeff(
+        8,
+        0xaa0d857d89759ad4897d29650fb85f9b409427eb4f49fffd8bfd00000000aaad,
+        0x1a0111ea397fe699ec02408663d4de85,
+        0,
+        0
+    )
+    %frob_fp381_12_coeff(
+        20,
+        0xf39816240c0b8fee8beadf4d8e9c0566c63a3e6e257f87329b18fae980078116,
+        0x05b2cfd9013a5fd8df47fa6b48b1e045,
+        0x70df3560e77982d0db45f3536814f0bd5871c1908bd478cd1ee605167ff82995,
+        0x144e4211384586c16bd3ad4afa99cc91
+    )
+    // stack: inp, out, retdest
+    %pop2
+    JUMP
+
+// Stores conj(d) * gamma at out + offset, where d is the coefficient at inp + offset and
+// gamma = (re_lo, re_hi, im_lo, im_hi).
+%macro frob_fp381_12_coeff(offset, re_lo, re_hi, im_lo, im_hi)
+    // stack: inp, out
+    DUP1 %add_const($offset) %load_fp381_2
+    %conj_fp381_2
+    %stack () -> ($re_lo, $re_hi, $im_lo, $im_hi)
+    %mul_fp381_2
+    // stack: conj(d) * gamma, inp, out
+    DUP6 %add_const($offset) %store_fp381_2
+    // stack: inp, out
+%endmacro
+
+%macro frob_fp381_12
+    // stack: inp, out
+    %stack (inp, out) -> (inp, out, %%after)
+    %jump(frob_fp381_12)
+%%after:
+%endmacro
+
+// x -> x^(p^6) negates the odd coefficients, i.e. the z1 half of the tower layout.
+%macro frob_fp381_12_6
+    // stack: inp, out
+    PUSH 0
+%%loop_z0:
+    // stack: i, inp, out
+    DUP2 DUP2 ADD %mload_current_general
+    DUP4 DUP3 ADD %mstore_current_general
+    %increment
+    DUP1 %eq_const(12) ISZERO %jumpi(%%loop_z0)
+%%loop_z1:
+    // stack: i, inp, out
+    DUP1 DUP3 ADD %load_fp381_2
+    %neg_fp381_2
+    DUP7 DUP6 ADD %store_fp381_2
+    %add_const(4)
+    DUP1 %eq_const(24) ISZERO %jumpi(%%loop_z1)
+    // stack: i, inp, out
+    %pop3
+%endmacro
@@ -0,0 +1,98 @@
+// Non-deterministically provide the inverse f^-1 of the Fp12 element f at inp, and store it
+// at out, which must not alias inp. The result is checked to be canonical, and to give the unit
+// when multiplied with f.
+global inv_fp381_12:
+    // stack: inp, out, retdest
+    %prover_inv_fp381_12
+    // stack: inp, out, retdest  {out: f^-1}
+    DUP2 %assert_canonical_fp381_12
+    %stack (inp, out) -> (inp, out, 100, check_inv_fp381_12)
+    // stack: inp, out, 100, check_inv_fp381_12, retdest
+    %jump(mul_fp381_12)
+check_inv_fp381_12:
+    // stack: retdest  {100: f * f^-1}
+    PUSH 100
+    %is_unit_fp381_12
+    %assert_nonzero
+    // stack: retdest
+    JUMP
+
+// Checks that the 12 base field components of the Fp12 element at ptr are less than p.
+%macro assert_canonical_fp381_12
+    // stack: ptr
+    DUP1 %add_const(24)
+    SWAP1
+%%loop:
+    // stack: ptr, end
+    DUP1 %increment %mload_current_general
+    DUP2 %mload_current_general
+    // stack: lo, hi, ptr, end
+    %lt_bls_base
+    %assert_nonzero
+    %add_const(2)
+    DUP2 DUP2 LT %jumpi(%%loop)
+    // stack: ptr, end
+    %pop2
+%endmacro
+
+%macro inv_fp381_12
+    // stack: inp, out
+    %stack (inp, out) -> (inp, out, %%after)
+    %jump(inv_fp381_12)
+%%after:
+%endmacro
+
+// The prover reads f from inp, which stays on top of the stack while each component of f^-1
+// is stored to out.
+%macro prover_inv_fp381_12
+    // stack: inp, out
+    PROVER_INPUT(ffe::bls381_base::component_0)
+    DUP3 %mstore_current_general
+    PROVER_INPUT(ffe::bls381_base::component_1)
+    DUP3 %add_const(1) %mstore_current_general
+    PROVER_INPUT(ffe::bls381_base::component_2)
+    DUP3 %add_const(2) %mstore_current_general
+    PROVER_INPUT(ffe::bls381_base::component_3)
+    DUP3 %add_const(3) %mstore_current_general
+    PROVER_INPUT(ffe::bls381_base::component_4)
+    DUP3 %add_const(4) %mstore_current_general
+    PROVER_INPUT(ffe::bls381_base::component_5)
+    DUP3 %add_const(5) %mstore_current_general
+    PROVER_INPUT(ffe::bls381_base::component_6)
+    DUP3 %add_const(6) %mstore_current_general
+    PROVER_INPUT(ffe::bls381_base::component_7)
+    DUP3 %add_const(7) %mstore_current_general
+    PROVER_INPUT(ffe::bls381_base::component_8)
+    DUP3 %add_const(8) %mstore_current_general
+    PROVER_INPUT(ffe::bls381_base::component_9)
+    DUP3 %add_const(9) %mstore_current_general
+    PROVER_INPUT(ffe::bls381_base::component_10)
+    DUP3 %add_const(10) %mstore_current_general
+    PROVER_INPUT(ffe::bls381_base::component_11)
+    DUP3 %add_const(11) %mstore_current_general
+    PROVER_INPUT(ffe::bls381_base::component_12)
+    DUP3 %add_const(12) %mstore_current_general
+    PROVER_INPUT(ffe::bls381_base::component_13)
+    DUP3 %add_const(13) %mstore_current_general
+    PROVER_INPUT(ffe::bls381_base::component_14)
+    DUP3 %add_const(14) %mstore_current_general
+    PROVER_INPUT(ffe::bls381_base::component_15)
+    DUP3 %add_const(15) %mstore_current_general
+    PROVER_INPUT(ffe::bls381_base::component_16)
+    DUP3 %add_const(16) %mstore_current_general
+    PROVER_INPUT(ffe::bls381_base::component_17)
+    DUP3 %add_const(17) %mstore_current_general
+    PROVER_INPUT(ffe::bls381_base::component_18)
+    DUP3 %add_const(18) %mstore_current_general
+    PROVER_INPUT(ffe::bls381_base::component_19)
+    DUP3 %add_const(19) %mstore_current_general
+    PROVER_INPUT(ffe::bls381_base::component_20)
+    DUP3 %add_const(20) %mstore_current_general
+    PROVER_INPUT(ffe::bls381_base::component_21)
+    DUP3 %add_const(21) %mstore_current_general
+    PROVER_INPUT(ffe::bls381_base::component_22)
+    DUP3 %add_const(22) %mstore_current_general
+    PROVER_INPUT(ffe::bls381_base::component_23)
+    DUP3 %add_const(23) %mstore_current_general
+    // stack: inp, out
+%endmacro
@@ -0,0 +1,188 @@
+/// Returns 1 if the KZG proof that the polynomial committed to by the commitment evaluates to y
+/// at z is valid, and 0 otherwise. The commitment and the proof are compressed G1 points, each
+/// split into its leading 16 bytes and trailing 32 bytes, and z and y are assumed to be canonical
+/// elements of the scalar field. This amounts to checking
+///     e([y]G1 - commitment, G2) * e(proof, [tau]G2 - [z]G2) == 1
+///
+/// The two MSMs computing [y]G1 - commitment and [tau - z]G2 read their terms from @KZG_G1_TERMS
+/// and @KZG_G2_TERMS, and the pairs of the pairing check are stored at @KZG_PAIRS.
+global verify_kzg_proof:
+    // stack: z, y, commitment_hi, commitment_lo, proof_hi, proof_lo, retdest
+    PUSH @BLS_SCALAR SUB
+    PUSH @KZG_G2_TERMS %add_const(17) %mstore_current_general
+    // stack: y, commitment_hi, commitment_lo, proof_hi, proof_lo, retdest
+    PUSH @KZG_G1_TERMS %add_const(8) %mstore_current_general
+    PUSH @BLS_SCALAR %decrement
+    PUSH @KZG_G1_TERMS %add_const(17) %mstore_current_general
+    PUSH 1
+    PUSH @KZG_G2_TERMS %add_const(8) %mstore_current_general
+    // stack: commitment_hi, commitment_lo, proof_hi, proof_lo, retdest
+    PUSH @KZG_G1_TERMS %add_const(9)
+    %stack (ptr, hi, lo) -> (hi, lo, ptr)
+    %bls_decompress_g1
+    ISZERO %jumpi(verify_kzg_proof_invalid_commitment)
+    PUSH @KZG_G1_TERMS %add_const(9)
+    %bls_in_subgroup
+    ISZERO %jumpi(verify_kzg_proof_invalid_commitment)
+    // stack: proof_hi, proof_lo, retdest
+    PUSH @KZG_PAIRS %add_const(16)
+    %stack (ptr, hi, lo) -> (hi, lo, ptr)
+    %bls_decompress_g1
+    ISZERO %jumpi(verify_kzg_proof_invalid)
+    PUSH @KZG_PAIRS %add_const(16)
+    %bls_in_subgroup
+    ISZERO %jumpi(verify_kzg_proof_invalid)
+    // stack: retdest
+    %bls_g1_generator
+    PUSH @KZG_G1_TERMS %store_bls_point
+    %kzg_setup_g2
+    PUSH @KZG_G2_TERMS %store_bls_point
+    %bls_g2_generator
+    PUSH @KZG_G2_TERMS %add_const(9) %store_bls_point
+    %bls_g2_generator
+    PUSH @KZG_PAIRS %add_const(8) %store_bls_point
+    // stack: retdest
+    %stack () -> (2, @KZG_G1_TERMS, verify_kzg_proof_g1_msm_done)
+    %jump(bls_msm)
+verify_kzg_proof_g1_msm_done:
+    // stack: retdest
+    PUSH 0 %load_bls_point
+    PUSH @KZG_PAIRS %store_bls_point
+    %stack () -> (2, @KZG_G2_TERMS, verify_kzg_proof_g2_msm_done)
+    %jump(bls_msm)
+verify_kzg_proof_g2_msm_done:
+    // stack: retdest
+    PUSH 0 %load_bls_point
+    PUSH @KZG_PAIRS %add_const(24) %store_bls_point
+    %stack (retdest) -> (2, @KZG_PAIRS, retdest)
+    %jump(bls381_pairing)
+verify_kzg_proof_invalid_commitment:
+    // stack: proof_hi, proof_lo, retdest
+    %pop2
+verify_kzg_proof_invalid:
+    // stack: retdest
+    PUSH 0 SWAP1
+    JUMP
+
+/// Decodes the G1 point with the 48-byte compressed serialization (hi, lo), split into its
+/// leading 16 bytes and trailing 32 bytes, and stores it at ptr. The three most significant bits
+/// of the serialization are the compression, infinity and sign flags respectively, and the sign
+/// flag is set iff y is the larger of the two roots, i.e. y > (p - 1) / 2.
+/// Returns 1 if the encoding is valid, and 0 otherwise. The point is not checked to lie in the
+/// prime order subgroup.
+global bls_decompress_g1:
+    // stack: hi, lo, ptr, retdest
+    // G1 points are embedded in Fp2 with zero imaginary parts.
+    PUSH 0 DUP4 %add_const(2) %mstore_current_general
+    PUSH 0 DUP4 %add_const(3) %mstore_current_general
+    PUSH 0 DUP4 %add_const(6) %mstore_current_general
+    PUSH 0 DUP4 %add_const(7) %mstore_current_general
+    // stack: hi, lo, ptr, retdest
+    DUP1 %shr_const(126)
+    // stack: flags, hi, lo, ptr, retdest
+    DUP1 %eq_const(3) %jumpi(bls_decompress_g1_infinity)
+    // The compression flag must be set.
+    %eq_const(2) ISZERO %jumpi(bls_decompress_g1_invalid)
+    // stack: hi, lo, ptr, retdest
+    DUP1 %shr_const(125) %and_const(1)
+    SWAP1 %and_const(0x1fffffffffffffffffffffffffffffff)
+    // stack: x1, sign, x0, ptr, retdest
+    %stack (x1, sign, x0) -> (x0, x1, x0, x1, sign)
+    %lt_bls_base ISZERO %jumpi(bls_decompress_g1_invalid_x)
+    // stack: x0, x1, sign, ptr, retdest
+    DUP1 DUP5 %mstore_current_general
+    DUP2 DUP5 %increment %mstore_current_general
+    DUP2 DUP2 DUP2 DUP2
+    %mul_fp381
+    %mul_fp381
+    %stack (x3: 2) -> (x3, 4, 0)
+    %add_fp381
+    // stack: x^3 + 4, sign, ptr, retdest
+    %sqrt_fp381
+    // stack: is_square, y0, y1, sign, ptr, retdest
+    ISZERO %jumpi(bls_decompress_g1_invalid_x)
+    // stack: y0, y1, sign, ptr, retdest
+    DUP2 DUP2
+    %stack (y: 2) -> (0, 0, y)
+    %sub_fp381
+    // stack: -y0, -y1, y0, y1, sign, ptr, retdest
+    DUP4 DUP4 DUP4 DUP4 %lt_u512
+    // stack: -y < y, -y0, -y1, y0, y1, sign, ptr, retdest
+    DUP6 EQ %jumpi(bls_decompress_g1_store_y)
+    %stack (neg_y: 2, y: 2) -> (y, neg_y)
+bls_decompress_g1_store_y:
+    // stack: other root, y0, y1, sign, ptr, retdest
+    %pop2
+    DUP4 %add_const(4) %mstore_current_general
+    DUP3 %add_const(5) %mstore_current_general
+    // stack: sign, ptr, retdest
+    %stack (sign, ptr, retdest) -> (retdest, 1)
+    JUMP
+bls_decompress_g1_infinity:
+    // stack: flags, hi, lo, ptr, retdest
+    POP
+    // The sign flag and all the bits of x must be zero.
+    DUP1 %eq_const(0xc0000000000000000000000000000000)
+    DUP3 ISZERO AND
+    ISZERO %jumpi(bls_decompress_g1_invalid)
+    // stack: hi, lo, ptr, retdest
+    %pop2
+    PUSH 0 DUP2 %mstore_current_general
+    PUSH 0 DUP2 %increment %mstore_current_general
+    PUSH 0 DUP2 %add_const(4) %mstore_current_general
+    PUSH 0 DUP2 %add_const(5) %mstore_current_general
+    // stack: ptr, retdest
+    %stack (ptr, retdest) -> (retdest, 1)
+    JUMP
+bls_decompress_g1_invalid_x:
+    // stack: x0, x1, sign, ptr, retdest
+    POP
+bls_decompress_g1_invalid:
+    // stack: hi, lo, ptr, retdest
+    %stack (hi, lo, ptr, retdest) -> (retdest, 0)
+    JUMP
+
+%macro bls_decompress_g1
+    // stack: hi, lo, ptr
+    %stack (hi, lo, ptr) -> (hi, lo, ptr, %%after)
+    %jump(bls_decompress_g1)
+%%after:
+    // stack: is_valid
+%endmacro
+
+%macro bls_g1_generator
+    PUSH 0
+    PUSH 0
+    PUSH 0x08b3f481e3aaa0f1a09e30ed741d8ae4
+    PUSH 0xfcf5e095d5d00af600db18cb2c04b3edd03cc744a2888ae40caa232946c5e7e1
+    PUSH 0
+    PUSH 0
+    PUSH 0x17f1d3a73197d7942695638c4fa9ac0f
+    PUSH 0xc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb
+    // stack: x, y
+%endmacro
+
+%macro bls_g2_generator
+    PUSH 0x0606c4a02ea734cc32acd2b02bc28b99
+    PUSH 0xcb3e287e85a763af267492ab572e99ab3f370d275cec1da1aaa9075ff05f79be
+    PUSH 0x0ce5d527727d6e118cc9cdc6da2e351a
+    PUSH 0xadfd9baa8cbdd3a76d429a695160d12c923ac9cc3baca289e193548608b82801
+    PUSH 0x13e02b6052719f607dacd3a088274f65
+    PUSH 0x596bd0d09920b61ab5da61bbdc7f5049334cf11213945d57e5ac7d055d042b7e
+    PUSH 0x024aa2b2f08f0a91260805272dc51051
+    PUSH 0xc6e47ad4fa403b02b4510b647ae3d1770bac0326a805bbefd48056c8c121bdb8
+    // stack: x, y
+%endmacro
+
+// The point [tau]G2 of the trusted setup from the Ethereum KZG ceremony.
+%macro kzg_setup_g2
+    PUSH 0x1666c54b0a32529503432fcae0181b4b
+    PUSH 0xef79de09fc63671fda5ed1ba9bfa07899495346f3d7ac9cd23048ef30d0a154f
+    PUSH 0x014353bdb96b626dd7d5ee8599d1fca2
+    PUSH 0x131569490e28de18e82451a496a9c9794ce26d105941f383ee689bfbbb832a99
+    PUSH 0x15bfd7dd8cdeb128843bc287230af389
+    PUSH 0x26187075cbfbefa81009a2ce615ac53d2914e5870cb452d2afaaab24f3499f72
+    PUSH 0x185cbfee53492714734429b7b38608e2
+    PUSH 0x3926c911cceceac9a36851477ba4c60b087041de621000edc98edada20c1def2
+    // stack: x, y
+%endmacro
@@ -1,43 +1,150 @@
+/// Arithmetic on elements of the BLS12-381 base field, which are represented on the stack by two
+/// words (x_lo, x_hi) with x = x_lo + 2^256 * x_hi. All inputs are assumed to be canonical, i.e.
+/// less than the field order p, and all outputs are canonical.
+
+// Adds the two-word values x and y, whose sum must fit in two words.
+%macro add_u512
+    // stack: x0, x1, y0, y1
+    %stack (x0, x1, y0, y1) -> (x0, y0, x0, x1, y1)
+    ADD
+    // stack: s0, x0, x1, y1
+    SWAP1 DUP2 LT
+    // stack: carry, s0, x1, y1
+    %stack (carry, s0, x1, y1) -> (carry, x1, y1, s0)
+    ADD ADD
+    // stack: s1, s0
+    SWAP1
+    // stack: s0, s1
+%endmacro
+
+// Subtracts the two-word value y from x, which must be at least y.
+%macro sub_u512
+    // stack: x0, x1, y0, y1
+    DUP3 DUP2 LT
+    // stack: borrow, x0, x1, y0, y1
+    %stack (borrow, x0, x1, y0, y1) -> (y1, borrow, x1, x0, y0)
+    ADD SWAP1 SUB
+    // stack: d1, x0, y0
+    %stack (d1, x0, y0) -> (x0, y0, d1)
+    SUB
+    // stack: d0, d1
+%endmacro
+
+// Returns 1 if the two-word value x is less than y, and 0 otherwise.
+%macro lt_u512
+    // stack: x0, x1, y0, y1
+    %stack (x0, x1, y0, y1) -> (x1, y1, x0, y0, x1, y1)
+    EQ
+    // stack: x1 == y1, x0, y0, x1, y1
+    %stack (eq, x0, y0) -> (x0, y0, eq)
+    LT AND
+    // stack: x1 == y1 && x0 < y0, x1, y1
+    %stack (lo_lt, x1, y1) -> (x1, y1, lo_lt)
+    LT OR
+    // stack: x < y
+%endmacro
+
+// Reduces the two-word value x modulo m, given c = 2^256 mod m.
+%macro reduce_u512(m, c)
+    // stack: x0, x1
+    PUSH $m DUP1 SWAP3
+    // stack: x1, m, x0, m
+    PUSH $c MULMOD
+    // stack: x1 * c mod m, x0, m
+    ADDMOD
+    // stack: x mod m
+%endmacro
+
+global add_fp381:
+    // stack: x0, x1, y0, y1, retdest
+    %add_u512
+    // stack: s0, s1, retdest
+    // s = x + y < 2p, so subtracting p once if s >= p gives the canonical sum.
+    DUP2 DUP2 %lt_bls_base ISZERO
+    DUP1 %mul_const(@BLS_BASE_HI) SWAP1 %mul_const(@BLS_BASE_LO)
+    // stack: (s >= p) * p, s0, s1, retdest
+    %stack (d: 2, s: 2) -> (s, d)
+    %sub_u512
+    // stack: z0, z1, retdest
+    %stack (z: 2, retdest) -> (retdest, z)
+    JUMP
+
 %macro add_fp381
-    // stack:         x0, x1, y0, y1
-    PROVER_INPUT(sf::bls381_base::add_hi)
-    // stack:     z1, x0, x1, y0, y1
-    SWAP4
-    // stack:     y1, x0, x1, y0, z1
-    PROVER_INPUT(sf::bls381_base::add_lo)
-    // stack: z0, y1, x0, x1, y0, z1
-    SWAP4
-    // stack: y0, y1, x0, x1, z0, z1
-    %pop4
-    // stack:                 z0, z1
+    // stack: x0, x1, y0, y1
+    %stack (x: 2, y: 2) -> (x, y, %%after)
+    %jump(add_fp381)
+%%after:
+    // stack: z0, z1
 %endmacro
 
+global sub_fp381:
+    // stack: x0, x1, y0, y1, retdest
+    // If x < y, p is added to x before subtracting y.
+    DUP4 DUP4 DUP4 DUP4 %lt_u512
+    DUP1 %mul_const(@BLS_BASE_HI) SWAP1 %mul_const(@BLS_BASE_LO)
+    // stack: (x < y) * p, x0, x1, y0, y1, retdest
+    %add_u512
+    %sub_u512
+    // stack: z0, z1, retdest
+    %stack (z: 2, retdest) -> (retdest, z)
+    JUMP
+
 %macro sub_fp381
-    // stack:         x0, x1, y0, y1
-    PROVER_INPUT(sf::bls381_base::sub_hi)
-    // stack:     z1, x0, x1, y0, y1
-    SWAP4
-    // stack:     y1, x0, x1, y0, z1
-    PROVER_INPUT(sf::bls381_base::sub_lo)
-    // stack: z0, y1, x0, x1, y0, z1
-    SWAP4
-    // stack: y0, y1, x0, x1, z0, z1
-    %pop4
-    // stack:                 z0, z1
+    // stack: x0, x1, y0, y1
+    %stack (x: 2, y: 2) -> (x, y, %%after)
+    %jump(sub_fp381)
+%%after:
+    // stack: z0, z1
 %endmacro
 
-%macro mul_fp381
-    // stack:         x0, x1, y0, y1
+// The product z = x * y mod p and the quotient q = x * y / p are provided by the prover, and
+// the kernel checks that z < p, q < 2^384 and x * y = q * p + z. Both sides of the equation are
+// less than 2^766, so it is enough to check it modulo 2^256 and modulo the secp256k1 base and
+// scalar field orders, whose product is greater than 2^767.
+global mul_fp381:
+    // stack: x0, x1, y0, y1, retdest
     PROVER_INPUT(sf::bls381_base::mul_hi)
-    // stack:     z1, x0, x1, y0, y1
-    SWAP4
-    // stack:     y1, x0, x1, y0, z1
     PROVER_INPUT(sf::bls381_base::mul_lo)
-    // stack: z0, y1, x0, x1, y0, z1
-    SWAP4
-    // stack: y0, y1, x0, x1, z0, z1
-    %pop4
-    // stack:                 z0, z1
+    PROVER_INPUT(sf::bls381_base::mul_quotient_hi)
+    PROVER_INPUT(sf::bls381_base::mul_quotient_lo)
+    // stack: q0, q1, z0, z1, x0, x1, y0, y1, retdest
+    DUP4 DUP4 %lt_bls_base %assert_nonzero
+    DUP2 %shr_const(128) %assert_zero
+    // Check the product modulo 2^256, where only the low words matter.
+    DUP7 DUP6 MUL
+    // stack: x0 * y0, q0, q1, z0, z1, x0, x1, y0, y1, retdest
+    DUP4 DUP3 %mul_const(@BLS_BASE_LO) ADD
+    // stack: q0 * p_lo + z0, x0 * y0, q0, q1, z0, z1, x0, x1, y0, y1, retdest
+    %assert_eq
+    %check_mul_fp381_mod(@SECP_BASE, @TWO_256_MOD_SECP_BASE, @BLS_BASE_MOD_SECP_BASE)
+    %check_mul_fp381_mod(@SECP_SCALAR, @TWO_256_MOD_SECP_SCALAR, @BLS_BASE_MOD_SECP_SCALAR)
+    // stack: q0, q1, z0, z1, x0, x1, y0, y1, retdest
+    %stack (q: 2, z: 2, x: 2, y: 2, retdest) -> (retdest, z)
+    JUMP
+
+// Checks that x * y = q * p + z modulo m, given c = 2^256 mod m and pm = p mod m.
+%macro check_mul_fp381_mod(m, c, pm)
+    // stack: q0, q1, z0, z1, x0, x1, y0, y1
+    DUP8 DUP8 %reduce_u512($m, $c)
+    DUP7 DUP7 %reduce_u512($m, $c)
+    // stack: x mod m, y mod m, q0, q1, z0, z1, x0, x1, y0, y1
+    PUSH $m SWAP2 MULMOD
+    // stack: x * y mod m, q0, q1, z0, z1, x0, x1, y0, y1
+    DUP5 DUP5 %reduce_u512($m, $c)
+    DUP4 DUP4 %reduce_u512($m, $c)
+    // stack: q mod m, z mod m, x * y mod m, q0, q1, z0, z1, x0, x1, y0, y1
+    PUSH $m SWAP1 PUSH $pm MULMOD
+    PUSH $m SWAP2 ADDMOD
+    // stack: q * p + z mod m, x * y mod m, q0, q1, z0, z1, x0, x1, y0, y1
+    %assert_eq
+%endmacro
+
+%macro mul_fp381
+    // stack: x0, x1, y0, y1
+    %stack (x: 2, y: 2) -> (x, y, %%after)
+    %jump(mul_fp381)
+%%after:
+    // stack: z0, z1
 %endmacro
 
 %macro add_fp381_2
@@ -99,3 +206,172 @@ global mul_fp381_2:
     // stack:                                      z_re, z_im, jumpdest
     %stack (z_re: 2, z_im: 2, jumpdest) -> (jumpdest, z_re, z_im)
     JUMP
+
+%macro mul_fp381_2
+    // stack: x_re, x_im, y_re, y_im
+    %stack (x: 4, y: 4) -> (x, y, %%after)
+    %jump(mul_fp381_2)
+%%after:
+    // stack: z_re, z_im
+%endmacro
+
+%macro neg_fp381_2
+    // stack:      x_re, x_im
+    %stack (x: 4) -> (0, 0, 0, 0, x)
+    // stack: 0, 0, x_re, x_im
+    %sub_fp381_2
+    // stack:     -x_re, -x_im
+%endmacro
+
+%macro conj_fp381_2
+    // stack:         x_re, x_im
+    %stack (x_re: 2, x_im: 2) -> (0, 0, x_im, x_re)
+    // stack:    0, x_im, x_re
+    %sub_fp381
+    // stack:       -x_im, x_re
+    %stack (z_im: 2, x_re: 2) -> (x_re, z_im)
+    // stack:        x_re, -x_im
+%endmacro
+
+// Multiplication by the non-residue xi = 1 + i used to build the degree 6 extension
+%macro mul_fp381_2_by_xi
+    // stack:                   x_re, x_im
+    %stack (x_re: 2, x_im: 2) -> (x_re, x_im, x_re, x_im)
+    // stack:       x_re, x_im, x_re, x_im
+    %add_fp381
+    // stack:       x_re + x_im, x_re, x_im
+    %stack (z_im: 2, x_re: 2, x_im: 2) -> (x_re, x_im, z_im)
+    // stack:       x_re, x_im, z_im
+    %sub_fp381
+    // stack:                   z_re, z_im
+%endmacro
+
+// The inverse is provided by the prover and checked by multiplying it with x.
+%macro inv_fp381
+    // stack:               x0, x1
+    PROVER_INPUT(sf::bls381_base::inv_hi)
+    // stack:           y1, x0, x1
+    PROVER_INPUT(sf::bls381_base::inv_lo)
+    // stack:       y0, y1, x0, x1
+    DUP2 DUP2 %lt_bls_base
+    %assert_nonzero
+    %stack (y: 2, x: 2) -> (x, y, y)
+    // stack: x0, x1, y0, y1, y0, y1
+    %mul_fp381
+    // stack:       z0, z1, y0, y1
+    %eq_const(1) SWAP1 ISZERO AND
+    %assert_nonzero
+    // stack:               y0, y1
+%endmacro
+
+// Since p = 3 mod 4, -1 is not a square, so exactly one of x and -x is a square when x is nonzero.
+// The prover provides a square root w of whichever one it is, and the kernel checks that w is
+// canonical and that w^2 = x or w^2 = -x. Returns whether x is a square, along with w.
+%macro sqrt_fp381
+    // stack:                           x0, x1
+    PROVER_INPUT(sf::bls381_base::sqrt_hi)
+    PROVER_INPUT(sf::bls381_base::sqrt_lo)
+    // stack:                   w0, w1, x0, x1
+    DUP2 DUP2 %lt_bls_base
+    %assert_nonzero
+    DUP2 DUP2 DUP2 DUP2
+    %mul_fp381
+    // stack:           s0, s1, w0, w1, x0, x1
+    %stack (s: 2, w: 2, x: 2) -> (s, x, s, x, w)
+    %add_fp381 OR ISZERO
+    // stack: s == -x, s0, s1, x0, x1, w0, w1
+    %stack (is_neg, s0, s1, x0, x1) -> (s0, x0, s1, x1, is_neg)
+    EQ SWAP2 EQ AND
+    // stack:           s == x, s == -x, w0, w1
+    SWAP1 DUP2 OR
+    %assert_nonzero
+    // stack:               is_square, w0, w1
+%endmacro
+
+// 1 / (x_re + x_im i) = (x_re - x_im i) / (x_re^2 + x_im^2)
+%macro inv_fp381_2
+    // stack:                               x_re, x_im
+    %stack (x_re: 2, x_im: 2) -> (x_im, x_im, x_re, x_re, x_re, x_im)
+    // stack:     x_im, x_im, x_re, x_re, x_re, x_im
+    %mul_fp381
+    %stack (v: 2, x_re: 2, x_re_: 2) -> (x_re, x_re_, v)
+    %mul_fp381
+    %add_fp381
+    // stack:                         n, x_re, x_im
+    %inv_fp381
+    // stack:                      1/n, x_re, x_im
+    %stack (n: 2, x_re: 2, x_im: 2) -> (x_re, n, x_im, n)
+    %mul_fp381
+    // stack:               z_re, x_im, 1/n
+    %stack (z_re: 2, x_im: 2, n: 2) -> (0, 0, x_im, n, z_re)
+    %sub_fp381
+    %mul_fp381
+    // stack:                         z_im, z_re
+    %stack (z_im: 2, z_re: 2) -> (z_re, z_im)
+    // stack:                         z_re, z_im
+%endmacro
+
+%macro is_zero_fp381_2
+    // stack: x_re, x_im
+    OR OR OR ISZERO
+    // stack: x == 0
+%endmacro
+
+%macro eq_fp381_2
+    // stack:       x0, x1, x2, x3, y0, y1, y2, y3
+    %stack (x0, x1, x2, x3, y0, y1, y2, y3) -> (x0, y0, x1, y1, x2, y2, x3, y3)
+    XOR SWAP2 XOR OR
+    SWAP2 XOR OR
+    SWAP2 XOR OR
+    ISZERO
+    // stack: x == y
+%endmacro
+
+// Fp2 elements are stored in the current context's kernel general segment as four words
+// re_lo, re_hi, im_lo, im_hi.
+%macro load_fp381_2
+    // stack:                    ptr
+    DUP1 %add_const(3) %mload_current_general
+    DUP2 %add_const(2) %mload_current_general
+    DUP3 %add_const(1) %mload_current_general
+    // stack: x_re_hi, x_im, ptr
+    %stack (x_re_hi, x_im: 2, ptr) -> (ptr, x_re_hi, x_im)
+    %mload_current_general
+    // stack:             x_re, x_im
+%endmacro
+
+%macro load_fp381_2(ptr)
+    PUSH $ptr
+    %load_fp381_2
+%endmacro
+
+%macro store_fp381_2
+    // stack: ptr, x_re, x_im
+    SWAP1 DUP2 %mstore_current_general
+    %increment SWAP1 DUP2 %mstore_current_general
+    %increment SWAP1 DUP2 %mstore_current_general
+    %increment %mstore_current_general
+    // stack:
+%endmacro
+
+%macro store_fp381_2(ptr)
+    PUSH $ptr
+    %store_fp381_2
+%endmacro
+
+%macro zero_fp381_2
+    // stack:
+    PUSH 0 PUSH 0 PUSH 0 PUSH 0
+    // stack: 0, 0
+%endmacro
+
+// Checks that the field element (x_lo, x_hi) is canonical, i.e. less than the BLS12-381 base field order.
+%macro lt_bls_base
+    // stack: x_lo, x_hi
+    DUP2 %eq_const(@BLS_BASE_HI)
+    // stack: x_hi == p_hi, x_lo, x_hi
+    SWAP1 %lt_const(@BLS_BASE_LO) AND
+    // stack: x_hi == p_hi && x_lo < p_lo, x_hi
+    SWAP1 %lt_const(@BLS_BASE_HI) OR
+    // stack: x < p
+%endmacro
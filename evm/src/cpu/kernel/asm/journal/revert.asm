@@ -17,6 +17,7 @@
     DUP1 %eq_const(@JOURNAL_ENTRY_REFUND)            %jumpi(revert_refund)
     DUP1 %eq_const(@JOURNAL_ENTRY_ACCOUNT_CREATED)   %jumpi(revert_account_created)
     DUP1 %eq_const(@JOURNAL_ENTRY_LOG)               %jumpi(revert_log)
+    DUP1 %eq_const(@JOURNAL_ENTRY_TRANSIENT_STORAGE_CHANGE) %jumpi(revert_transient_storage_change)
     PANIC // This should never happen.
 %%after:
     // stack: journal_size-1
@@ -0,0 +1,20 @@
+// struct TransientStorageChange { address, slot, prev_value }
+
+%macro journal_add_transient_storage_change
+    %journal_add_3(@JOURNAL_ENTRY_TRANSIENT_STORAGE_CHANGE)
+%endmacro
+
+global revert_transient_storage_change:
+    // stack: entry_type, ptr, retdest
+    POP
+    %journal_load_3
+    // stack: address, slot, prev_value, retdest
+    %search_transient_storage
+    // stack: i, prev_value, retdest
+    DUP1 %mload_global_metadata(@GLOBAL_METADATA_TRANSIENT_STORAGE_LEN)
+    // stack: len, i, i, prev_value, retdest
+    EQ %jumpi(panic) // The slot was written after this entry was added, so it must be present.
+    // stack: i, prev_value, retdest
+    %add_const(2) %mstore_kernel(@SEGMENT_TRANSIENT_STORAGE)
+    // stack: retdest
+    JUMP
@@ -14,6 +14,10 @@ global hash_initial_tries:
     %mpt_hash_txn_trie     %mload_global_metadata(@GLOBAL_METADATA_TXN_TRIE_DIGEST_BEFORE)      %assert_eq
     %mpt_hash_receipt_trie %mload_global_metadata(@GLOBAL_METADATA_RECEIPT_TRIE_DIGEST_BEFORE)  %assert_eq
 
+    // Before the first transaction of the block, store the parent beacon block root (EIP-4788).
+    %mload_global_metadata(@GLOBAL_METADATA_TXN_NUMBER_BEFORE) %jumpi(start_txn)
+    %set_beacon_root
+
 global start_txn:
     // stack: (empty)
     // The special case of an empty trie (i.e. for the first transaction)
@@ -29,10 +33,14 @@ global start_txn:
     SWAP2
     // stack: init_gas_used, txn_counter, num_nibbles, txn_nb
 
-    // If the prover has no txn for us to process, halt.
-    PROVER_INPUT(no_txn)
+global txn_loop:
+    // stack: prev_gas_used, txn_counter, num_nibbles, txn_nb
+    // If the prover has no more txns for us to process, halt.
+    PROVER_INPUT(end_of_txns)
     %jumpi(execute_withdrawals)
 
+    %reset_txn_global_metadata
+
     // Call route_txn. When we return, we will process the txn receipt.
     PUSH txn_after
     // stack: retdest, prev_gas_used, txn_counter, num_nibbles, txn_nb
@@ -45,6 +53,7 @@ global txn_after:
     %process_receipt
     // stack: new_cum_gas, txn_counter, num_nibbles, txn_nb
     SWAP3 %increment SWAP3
+    %jump(txn_loop)
 
 global execute_withdrawals:
     // stack: cum_gas, txn_counter, num_nibbles, txn_nb
@@ -55,6 +64,10 @@ global hash_final_tries:
     %mload_global_metadata(@GLOBAL_METADATA_BLOCK_GAS_USED_AFTER) %assert_eq
     DUP3 %mload_global_metadata(@GLOBAL_METADATA_TXN_NUMBER_AFTER) %assert_eq
     %pop3
+    // Check that the blob gas used by the transactions is the block's, and fits in a block.
+    %mload_global_metadata(@GLOBAL_METADATA_BLOB_GAS_USED)
+    DUP1 %mload_global_metadata(@GLOBAL_METADATA_BLOCK_BLOB_GAS_USED) %assert_eq
+    %assert_le_const(@MAX_BLOB_GAS_PER_BLOCK)
     %check_metadata_block_bloom
     %mpt_hash_state_trie   %mload_global_metadata(@GLOBAL_METADATA_STATE_TRIE_DIGEST_AFTER)     %assert_eq
     %mpt_hash_txn_trie     %mload_global_metadata(@GLOBAL_METADATA_TXN_TRIE_DIGEST_AFTER)       %assert_eq
@@ -120,3 +133,45 @@ check_bloom_loop_end:
     %jump(check_metadata_block_bloom)
 %%after:
 %endmacro
+
+// Resets the global metadata which only lives for the duration of a transaction, so that
+// the state of a transaction doesn't leak into the next one.
+%macro reset_txn_global_metadata
+    PUSH 0 %mstore_global_metadata(@GLOBAL_METADATA_REFUND_COUNTER)
+    PUSH 0 %mstore_global_metadata(@GLOBAL_METADATA_ACCESSED_ADDRESSES_LEN)
+    PUSH 0 %mstore_global_metadata(@GLOBAL_METADATA_ACCESSED_STORAGE_KEYS_LEN)
+    PUSH 0 %mstore_global_metadata(@GLOBAL_METADATA_SELFDESTRUCT_LIST_LEN)
+    PUSH 0 %mstore_global_metadata(@GLOBAL_METADATA_TOUCHED_ADDRESSES_LEN)
+    PUSH 0 %mstore_global_metadata(@GLOBAL_METADATA_JOURNAL_LEN)
+    PUSH 0 %mstore_global_metadata(@GLOBAL_METADATA_JOURNAL_DATA_LEN)
+    PUSH 0 %mstore_global_metadata(@GLOBAL_METADATA_CURRENT_CHECKPOINT)
+    PUSH 0 %mstore_global_metadata(@GLOBAL_METADATA_ACCESS_LIST_DATA_COST)
+    PUSH 0 %mstore_global_metadata(@GLOBAL_METADATA_CONTRACT_CREATION)
+    PUSH 0 %mstore_global_metadata(@GLOBAL_METADATA_IS_PRECOMPILE_FROM_EOA)
+    PUSH 0 %mstore_global_metadata(@GLOBAL_METADATA_LOGS_LEN)
+    PUSH 0 %mstore_global_metadata(@GLOBAL_METADATA_LOGS_DATA_LEN)
+    PUSH 0 %mstore_global_metadata(@GLOBAL_METADATA_LOGS_PAYLOAD_LEN)
+    PUSH 0 %mstore_global_metadata(@GLOBAL_METADATA_ACCESS_LIST_RLP_START)
+    PUSH 0 %mstore_global_metadata(@GLOBAL_METADATA_ACCESS_LIST_RLP_LEN)
+    // Some fields are only set by some transaction types, e.g. the chain ID, which legacy
+    // transactions may omit, and the blob fields of type-3 transactions, so all of them are
+    // cleared.
+    PUSH 0 %mstore_txn_field(@TXN_FIELD_CHAIN_ID_PRESENT)
+    PUSH 0 %mstore_txn_field(@TXN_FIELD_CHAIN_ID)
+    PUSH 0 %mstore_txn_field(@TXN_FIELD_NONCE)
+    PUSH 0 %mstore_txn_field(@TXN_FIELD_MAX_PRIORITY_FEE_PER_GAS)
+    PUSH 0 %mstore_txn_field(@TXN_FIELD_MAX_FEE_PER_GAS)
+    PUSH 0 %mstore_txn_field(@TXN_FIELD_GAS_LIMIT)
+    PUSH 0 %mstore_txn_field(@TXN_FIELD_INTRINSIC_GAS)
+    PUSH 0 %mstore_txn_field(@TXN_FIELD_TO)
+    PUSH 0 %mstore_txn_field(@TXN_FIELD_VALUE)
+    PUSH 0 %mstore_txn_field(@TXN_FIELD_DATA_LEN)
+    PUSH 0 %mstore_txn_field(@TXN_FIELD_Y_PARITY)
+    PUSH 0 %mstore_txn_field(@TXN_FIELD_R)
+    PUSH 0 %mstore_txn_field(@TXN_FIELD_S)
+    PUSH 0 %mstore_txn_field(@TXN_FIELD_ORIGIN)
+    PUSH 0 %mstore_txn_field(@TXN_FIELD_COMPUTED_FEE_PER_GAS)
+    PUSH 0 %mstore_txn_field(@TXN_FIELD_COMPUTED_PRIORITY_FEE_PER_GAS)
+    PUSH 0 %mstore_txn_field(@TXN_FIELD_MAX_FEE_PER_BLOB_GAS)
+    PUSH 0 %mstore_txn_field(@TXN_FIELD_BLOB_VERSIONED_HASHES_LEN)
+%endmacro
@@ -278,6 +278,90 @@ zero_hash:
     PUSH 0 SWAP1
     JUMP
 
+global sys_blobhash:
+    // stack: kexit_info, index
+    %charge_gas_const(@GAS_VERYLOW)
+    SWAP1
+    // stack: index, kexit_info
+    %blobhash
+    // stack: blobhash, kexit_info
+    SWAP1
+    EXIT_KERNEL
+
+global blobhash:
+    // stack: index, retdest
+    // Out-of-range indices yield 0, as do all indices for non-blob transactions.
+    %mload_txn_field(@TXN_FIELD_BLOB_VERSIONED_HASHES_LEN)
+    // stack: len, index, retdest
+    DUP2 LT ISZERO %jumpi(zero_blobhash) // if index >= len
+    // stack: index, retdest
+    %mload_kernel(@SEGMENT_TXN_BLOB_VERSIONED_HASHES)
+    SWAP1 JUMP
+
+%macro blobhash
+    // stack: index
+    %stack (index) -> (index, %%after)
+    %jump(blobhash)
+%%after:
+%endmacro
+
+zero_blobhash:
+    // stack: index, retdest
+    POP
+    PUSH 0 SWAP1
+    JUMP
+
+global sys_blobbasefee:
+    // stack: kexit_info
+    %charge_gas_const(@GAS_BASE)
+    // stack: kexit_info
+    %blob_base_fee
+    // stack: blob_base_fee, kexit_info
+    SWAP1
+    EXIT_KERNEL
+
+// Computes the blob base fee from the block's excess blob gas, as specified in EIP-4844:
+//     fake_exponential(MIN_BLOB_BASE_FEE, excess_blob_gas, BLOB_BASE_FEE_UPDATE_FRACTION)
+// which approximates MIN_BLOB_BASE_FEE * e^(excess_blob_gas / BLOB_BASE_FEE_UPDATE_FRACTION)
+// with a Taylor expansion.
+global blob_base_fee:
+    // stack: retdest
+    %mload_global_metadata(@GLOBAL_METADATA_BLOCK_EXCESS_BLOB_GAS)
+    // stack: numerator, retdest
+    PUSH @BLOB_BASE_FEE_UPDATE_FRACTION
+    %mul_const(@MIN_BLOB_BASE_FEE)
+    // stack: accum = factor * denominator, numerator, retdest
+    PUSH 0 // output
+    PUSH 1 // i
+    // stack: i, output, accum, numerator, retdest
+blob_base_fee_loop:
+    DUP3 ISZERO %jumpi(blob_base_fee_end)
+    // stack: i, output, accum, numerator, retdest
+    SWAP1 DUP3 ADD SWAP1
+    // stack: i, output + accum, accum, numerator, retdest
+    DUP1 %mul_const(@BLOB_BASE_FEE_UPDATE_FRACTION)
+    // stack: denominator * i, i, output, accum, numerator, retdest
+    DUP5 DUP5 MUL
+    // stack: accum * numerator, denominator * i, i, output, accum, numerator, retdest
+    DIV
+    // stack: accum', i, output, accum, numerator, retdest
+    SWAP3 POP
+    // stack: i, output, accum', numerator, retdest
+    %increment
+    %jump(blob_base_fee_loop)
+blob_base_fee_end:
+    // stack: i, output, accum, numerator, retdest
+    %stack (i, output, accum, numerator) -> (output)
+    %div_const(@BLOB_BASE_FEE_UPDATE_FRACTION)
+    // stack: blob_base_fee, retdest
+    SWAP1 JUMP
+
+%macro blob_base_fee
+    PUSH %%after
+    %jump(blob_base_fee)
+%%after:
+%endmacro
+
 %macro update_mem_words
     // stack: num_words, kexit_info
     %mem_words
@@ -151,6 +151,52 @@ global sys_returndatacopy:
 
     %wcopy(@SEGMENT_RETURNDATA, @CTX_METADATA_RETURNDATA_SIZE)
 
+// Pre stack: kexit_info, dest_offset, offset, size
+// Post stack: (empty)
+global sys_mcopy:
+    // stack: kexit_info, dest_offset, offset, size
+    %wcopy_charge_gas
+
+    // Memory is expanded to cover both the source and the destination ranges.
+    %stack (kexit_info, dest_offset, offset, size) -> (offset, size, kexit_info, dest_offset, offset, size)
+    %add_or_fault
+    // stack: expanded_num_bytes, kexit_info, dest_offset, offset, size
+    DUP1 %ensure_reasonable_offset
+    %update_mem_bytes
+    %stack (kexit_info, dest_offset, offset, size) -> (dest_offset, size, kexit_info, dest_offset, offset, size)
+    %add_or_fault
+    // stack: expanded_num_bytes, kexit_info, dest_offset, offset, size
+    DUP1 %ensure_reasonable_offset
+    %update_mem_bytes
+
+    // If the destination starts within the source range, copying forward would overwrite source
+    // bytes before they are read, so we copy through a temporary buffer instead.
+    // stack: kexit_info, dest_offset, offset, size
+    DUP3 DUP3 GT
+    // stack: dest_offset > offset, kexit_info, dest_offset, offset, size
+    DUP5 DUP5 ADD DUP4 LT
+    // stack: dest_offset < offset + size, dest_offset > offset, kexit_info, dest_offset, offset, size
+    MUL // AND
+    %jumpi(mcopy_with_overlap)
+
+    // stack: kexit_info, dest_offset, offset, size
+    GET_CONTEXT
+    %stack (ctx, kexit_info, dest_offset, offset, size) ->
+        (ctx, @SEGMENT_MAIN_MEMORY, dest_offset, ctx, @SEGMENT_MAIN_MEMORY, offset, size, wcopy_after, kexit_info)
+    %jump(memcpy_bytes)
+
+mcopy_with_overlap:
+    // stack: kexit_info, dest_offset, offset, size
+    GET_CONTEXT
+    %stack (ctx, kexit_info, dest_offset, offset, size) ->
+        (ctx, @SEGMENT_KERNEL_GENERAL, 0, ctx, @SEGMENT_MAIN_MEMORY, offset, size, mcopy_from_buffer, ctx, kexit_info, dest_offset, size)
+    %jump(memcpy_bytes)
+mcopy_from_buffer:
+    // stack: ctx, kexit_info, dest_offset, size
+    %stack (ctx, kexit_info, dest_offset, size) ->
+        (ctx, @SEGMENT_MAIN_MEMORY, dest_offset, ctx, @SEGMENT_KERNEL_GENERAL, 0, size, wcopy_after, kexit_info)
+    %jump(memcpy_bytes)
+
 // Pre stack: kexit_info, dest_offset, offset, size
 // Post stack: (empty)
 global sys_codecopy:
@@ -128,13 +128,13 @@ global encode_txn:
 global encode_receipt:
     // stack: rlp_pos, value_ptr, retdest
     // There is a double encoding! What we compute is:
-    // either RLP(RLP(receipt)) for Legacy transactions or RLP(txn_type||RLP(receipt)) for transactions of type 1 or 2.
+    // either RLP(RLP(receipt)) for Legacy transactions or RLP(txn_type||RLP(receipt)) for transactions of type 1, 2 or 3.
     // First encode the wrapper prefix.
     DUP2 %mload_trie_data
     // stack: first_value, rlp_pos, value_ptr, retdest
     // The first value is either the transaction type or the payload length.
     // Since the receipt contains at least the 256-bytes long bloom filter, payload_len > 3.
-    DUP1 %lt_const(3) %jumpi(encode_nonzero_receipt_type)
+    DUP1 %lt_const(4) %jumpi(encode_nonzero_receipt_type)
     // If we are here, then the first byte is the payload length.
     %rlp_list_len
     // stack: rlp_receipt_len, rlp_pos, value_ptr, retdest
@@ -134,6 +134,15 @@
 %%after:
 %endmacro
 
+// Decode the max fee per blob gas and store it.
+%macro decode_and_store_max_fee_per_blob_gas
+    // stack: pos
+    %decode_rlp_scalar
+    %stack (pos, max_fee_per_blob_gas) -> (max_fee_per_blob_gas, pos)
+    %mstore_txn_field(@TXN_FIELD_MAX_FEE_PER_BLOB_GAS)
+    // stack: pos
+%endmacro
+
 %macro decode_and_store_y_parity
     // stack: pos
     %decode_rlp_scalar
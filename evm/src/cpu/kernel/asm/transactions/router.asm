@@ -15,8 +15,12 @@ global route_txn:
 read_txn_from_memory:
     // stack: retdest
 
+    // Only type 3 transactions carry blobs.
+    PUSH 0 %mstore_txn_field(@TXN_FIELD_BLOB_VERSIONED_HASHES_LEN)
+    PUSH 0 %mstore_txn_field(@TXN_FIELD_MAX_FEE_PER_BLOB_GAS)
+
     // We will peak at the first byte to determine what type of transaction this is.
-    // Note that type 1 and 2 transactions have a first byte of 1 and 2, respectively.
+    // Note that type 1, 2 and 3 transactions have a first byte of 1, 2 and 3, respectively.
     // Type 0 (legacy) transactions have no such prefix, but their RLP will have a
     // first byte >= 0xc0, so there is no overlap.
 
@@ -34,7 +38,14 @@ read_txn_from_memory:
     %jumpi(process_type_2_txn)
     // stack: retdest
 
-    // At this point, since it's not a type 1 or 2 transaction,
+    PUSH 0
+    %mload_kernel(@SEGMENT_RLP_RAW)
+    %eq_const(3)
+    // stack: first_byte == 3, retdest
+    %jumpi(process_type_3_txn)
+    // stack: retdest
+
+    // At this point, since it's not a type 1, 2 or 3 transaction,
     // it must be a legacy (aka type 0) transaction.
     %jump(process_type_0_txn)
 
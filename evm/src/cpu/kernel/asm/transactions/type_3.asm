@@ -0,0 +1,142 @@
+// Type 3 transactions, introduced by EIP 4844, have the format
+//     0x03 || rlp([chain_id, nonce, max_priority_fee_per_gas, max_fee_per_gas,
+//                  gas_limit, to, value, data, access_list, max_fee_per_blob_gas,
+//                  blob_versioned_hashes, y_parity, r, s])
+//
+// The signed data is
+//     keccak256(0x03 || rlp([chain_id, nonce, max_priority_fee_per_gas,
+//                            max_fee_per_gas, gas_limit, to, value, data,
+//                            access_list, max_fee_per_blob_gas, blob_versioned_hashes]))
+
+global process_type_3_txn:
+    // stack: retdest
+    PUSH 1 // initial pos, skipping over the 0x03 byte
+    // stack: pos, retdest
+    %decode_rlp_list_len
+    // We don't actually need the length.
+    %stack (pos, len) -> (pos)
+
+    // The unsigned fields are contiguous in the raw RLP, so we keep track of where they start.
+    DUP1
+    // stack: pos, payload_start, retdest
+    %store_chain_id_present_true
+    %decode_and_store_chain_id
+    %decode_and_store_nonce
+    %decode_and_store_max_priority_fee
+    %decode_and_store_max_fee
+    %decode_and_store_gas_limit
+    %decode_and_store_to
+    // Blob transactions cannot be contract creations.
+    %is_contract_creation %jumpi(invalid_txn_2)
+    %decode_and_store_value
+    %decode_and_store_data
+    %decode_and_store_access_list
+    %decode_and_store_max_fee_per_blob_gas
+
+    // stack: pos, payload_start, retdest
+    // Decode the blob versioned hashes and store them in @SEGMENT_TXN_BLOB_VERSIONED_HASHES.
+    %decode_rlp_list_len
+    %stack (pos, len) -> (len, pos, pos, 0)
+    ADD SWAP1
+    // stack: pos, end_pos, num_hashes, payload_start, retdest
+decode_blob_versioned_hashes_loop:
+    DUP2 DUP2 EQ %jumpi(decode_blob_versioned_hashes_end)
+    // stack: pos, end_pos, num_hashes, payload_start, retdest
+    %decode_rlp_scalar
+    // stack: pos, hash, end_pos, num_hashes, payload_start, retdest
+    SWAP1
+    // stack: hash, pos, end_pos, num_hashes, payload_start, retdest
+    // Each hash must start with the KZG version byte.
+    DUP1 %shr_const(248) %eq_const(@VERSIONED_HASH_VERSION_KZG) ISZERO %jumpi(invalid_blob_versioned_hash)
+    DUP4 %mstore_kernel(@SEGMENT_TXN_BLOB_VERSIONED_HASHES)
+    // stack: pos, end_pos, num_hashes, payload_start, retdest
+    SWAP2 %increment SWAP2
+    %jump(decode_blob_versioned_hashes_loop)
+
+invalid_blob_versioned_hash:
+    // stack: hash, pos, end_pos, num_hashes, payload_start, retdest
+    %pop3
+    %jump(invalid_txn_2)
+
+decode_blob_versioned_hashes_end:
+    // stack: pos, end_pos, num_hashes, payload_start, retdest
+    %stack (pos, end_pos, num_hashes) -> (num_hashes, pos, pos)
+    %mstore_txn_field(@TXN_FIELD_BLOB_VERSIONED_HASHES_LEN)
+    // stack: pos, payload_end, payload_start, retdest
+    // There must be at least one blob, and no more than fit in a block.
+    %mload_txn_field(@TXN_FIELD_BLOB_VERSIONED_HASHES_LEN) ISZERO %jumpi(invalid_txn_3)
+    %mload_txn_field(@TXN_FIELD_BLOB_VERSIONED_HASHES_LEN) %mul_const(@GAS_PER_BLOB)
+    %gt_const(@MAX_BLOB_GAS_PER_BLOCK) %jumpi(invalid_txn_3)
+    // Add the blob gas of this transaction to that of the block, checked in `hash_final_tries`.
+    %blob_gas
+    %mload_global_metadata(@GLOBAL_METADATA_BLOB_GAS_USED)
+    ADD
+    %mstore_global_metadata(@GLOBAL_METADATA_BLOB_GAS_USED)
+
+    %decode_and_store_y_parity
+    %decode_and_store_r
+    %decode_and_store_s
+
+    // stack: pos, payload_end, payload_start, retdest
+    POP
+    // stack: payload_end, payload_start, retdest
+
+// From EIP-4844:
+// The signature_y_parity, signature_r, signature_s elements of this transaction represent a secp256k1 signature over
+// keccak256(0x03 || rlp([chain_id, nonce, max_priority_fee_per_gas, max_fee_per_gas, gas_limit, to, value, data, access_list, max_fee_per_blob_gas, blob_versioned_hashes]))
+type_3_compute_signed_data:
+    // Instead of re-encoding the unsigned fields, we copy their raw RLP from the transaction.
+    DUP2 SWAP1 SUB
+    // stack: payload_len, payload_start, retdest
+    %alloc_rlp_block
+    // stack: rlp_start, payload_len, payload_start, retdest
+    %stack (rlp_start, payload_len, payload_start) ->
+        (
+            0, @SEGMENT_RLP_RAW, rlp_start,
+            0, @SEGMENT_RLP_RAW, payload_start,
+            payload_len,
+            after_serializing_payload,
+            rlp_start, payload_len)
+    %jump(memcpy_bytes)
+after_serializing_payload:
+    // stack: rlp_start, payload_len, retdest
+    SWAP1 DUP2 ADD
+    // stack: rlp_pos, rlp_start, retdest
+    %prepend_rlp_list_prefix
+    // stack: prefix_start_pos, rlp_len, retdest
+
+    // Store a `3` in front of the RLP
+    %decrement
+    %stack (pos) -> (3, 0, @SEGMENT_RLP_RAW, pos, pos)
+    MSTORE_GENERAL
+    // stack: pos, rlp_len, retdest
+
+    // Hash the RLP + the leading `3`
+    SWAP1 %increment SWAP1
+    PUSH @SEGMENT_RLP_RAW
+    PUSH 0 // context
+    // stack: ADDR: 3, len, retdest
+    KECCAK_GENERAL
+    // stack: hash, retdest
+
+    %mload_txn_field(@TXN_FIELD_S)
+    %mload_txn_field(@TXN_FIELD_R)
+    %mload_txn_field(@TXN_FIELD_Y_PARITY) %add_const(27) // ecrecover interprets v as y_parity + 27
+
+    PUSH store_origin
+    // stack: store_origin, v, r, s, hash, retdest
+    SWAP4
+    // stack: hash, v, r, s, store_origin, retdest
+    %jump(ecrecover)
+
+store_origin:
+    // stack: address, retdest
+    // If ecrecover returned u256::MAX, that indicates failure.
+    DUP1
+    %eq_const(0xffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff)
+    %jumpi(panic)
+
+    // stack: address, retdest
+    %mstore_txn_field(@TXN_FIELD_ORIGIN)
+    // stack: retdest
+    %jump(process_normalized_txn)
@@ -36,6 +36,7 @@ pub const STACK_LENGTH_INCREASING_OPCODES_USER: U256 = u256_from_set_index_range
     0x3a..=0x3a, // GASPRICE
     0x3d..=0x3d, // RETURNDATASIZE
     0x41..=0x48, // COINBASE, TIMESTAMP, NUMBER, DIFFICULTY, GASLIMIT, CHAINID, SELFBALANCE, BASEFEE
+    0x4a..=0x4a, // BLOBBASEFEE
     0x58..=0x5a, // PC, MSIZE, GAS
     0x5f..=0x8f, // PUSH*, DUP*
 ]);
@@ -44,8 +45,7 @@ pub const INVALID_OPCODES_USER: U256 = u256_from_set_index_ranges(&[
     0x0c..=0x0f,
     0x1e..=0x1f,
     0x21..=0x2f,
-    0x49..=0x4f,
-    0x5c..=0x5e,
+    0x4b..=0x4f,
     0xa5..=0xef,
     0xf6..=0xf9,
     0xfb..=0xfc,
@@ -85,10 +85,20 @@ pub(crate) enum GlobalMetadata {
     LogsPayloadLen = 43,
     TxnNumberBefore = 44,
     TxnNumberAfter = 45,
+    /// Length of the transient storage array, i.e. three times the number of written slots.
+    TransientStorageLen = 46,
+
+    BlockBlobGasUsed = 47,
+    BlockExcessBlobGas = 48,
+    /// The blob gas used by the type-3 transactions processed so far.
+    BlobGasUsed = 49,
+    /// The parent beacon block root, stored in the beacon roots contract before the first
+    /// transaction of the block (EIP-4788).
+    ParentBeaconBlockRoot = 50,
 }
 
 impl GlobalMetadata {
-    pub(crate) const COUNT: usize = 46;
+    pub(crate) const COUNT: usize = 51;
 
     pub(crate) fn all() -> [Self; Self::COUNT] {
         [
@@ -138,6 +148,11 @@ impl GlobalMetadata {
             Self::BlockCurrentHash,
             Self::TxnNumberBefore,
             Self::TxnNumberAfter,
+            Self::TransientStorageLen,
+            Self::BlockBlobGasUsed,
+            Self::BlockExcessBlobGas,
+            Self::BlobGasUsed,
+            Self::ParentBeaconBlockRoot,
         ]
     }
 
@@ -190,6 +205,11 @@ impl GlobalMetadata {
             Self::LogsPayloadLen => "GLOBAL_METADATA_LOGS_PAYLOAD_LEN",
             Self::TxnNumberBefore => "GLOBAL_METADATA_TXN_NUMBER_BEFORE",
             Self::TxnNumberAfter => "GLOBAL_METADATA_TXN_NUMBER_AFTER",
+            Self::TransientStorageLen => "GLOBAL_METADATA_TRANSIENT_STORAGE_LEN",
+            Self::BlockBlobGasUsed => "GLOBAL_METADATA_BLOCK_BLOB_GAS_USED",
+            Self::BlockExcessBlobGas => "GLOBAL_METADATA_BLOCK_EXCESS_BLOB_GAS",
+            Self::BlobGasUsed => "GLOBAL_METADATA_BLOB_GAS_USED",
+            Self::ParentBeaconBlockRoot => "GLOBAL_METADATA_PARENT_BEACON_BLOCK_ROOT",
         }
     }
 }
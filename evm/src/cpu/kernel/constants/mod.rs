@@ -25,6 +25,7 @@ pub(crate) fn evm_constants() -> HashMap<String, U256> {
         .iter()
         .chain(EC_CONSTANTS.iter())
         .chain(HASH_CONSTANTS.iter())
+        .chain(BEACON_ROOTS_CONSTANTS.iter())
         .cloned();
     for (name, value) in hex_constants {
         c.insert(name.into(), U256::from_big_endian(&value));
@@ -50,10 +51,18 @@ pub(crate) fn evm_constants() -> HashMap<String, U256> {
         c.insert(name.into(), U256::from(value));
     }
 
+    for (name, value) in BLOB_CONSTANTS {
+        c.insert(name.into(), U256::from(value));
+    }
+
     for (name, value) in SNARKV_POINTERS {
         c.insert(name.into(), U256::from(value));
     }
 
+    for (name, value) in BLS_POINTERS {
+        c.insert(name.into(), U256::from(value));
+    }
+
     c.insert(MAX_NONCE.0.into(), U256::from(MAX_NONCE.1));
     c.insert(CALL_STACK_LIMIT.0.into(), U256::from(CALL_STACK_LIMIT.1));
 
@@ -107,7 +116,7 @@ const HASH_CONSTANTS: [(&str, [u8; 32]); 2] = [
     ),
 ];
 
-const EC_CONSTANTS: [(&str, [u8; 32]); 20] = [
+const EC_CONSTANTS: [(&str, [u8; 32]); 27] = [
     (
         "U256_MAX",
         hex!("ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff"),
@@ -128,6 +137,18 @@ const EC_CONSTANTS: [(&str, [u8; 32]); 20] = [
         "BN_SCALAR",
         hex!("30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001"),
     ),
+    (
+        "BLS_SCALAR",
+        hex!("73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001"),
+    ),
+    (
+        "BLS_BASE_LO",
+        hex!("64774b84f38512bf6730d2a0f6b0f6241eabfffeb153ffffb9feffffffffaaab"),
+    ),
+    (
+        "BLS_BASE_HI",
+        hex!("000000000000000000000000000000001a0111ea397fe69a4b1ba7b6434bacd7"),
+    ),
     (
         "BN_GLV_BETA",
         hex!("000000000000000059e26bcea0d48bacd4f263f1acdb5c4f5763473177fffffe"),
@@ -165,6 +186,23 @@ const EC_CONSTANTS: [(&str, [u8; 32]); 20] = [
         "SECP_SCALAR",
         hex!("fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141"),
     ),
+    // Residues used to check BLS12-381 base field products modulo the secp256k1 field orders.
+    (
+        "TWO_256_MOD_SECP_BASE",
+        hex!("00000000000000000000000000000000000000000000000000000001000003d1"),
+    ),
+    (
+        "BLS_BASE_MOD_SECP_BASE",
+        hex!("64774b84f38512bf6730d2a110b208719641457e6d8eba8ea1d5bb6dd3ce4b32"),
+    ),
+    (
+        "TWO_256_MOD_SECP_SCALAR",
+        hex!("000000000000000000000000000000014551231950b75fc4402da1732fc9bebf"),
+    ),
+    (
+        "BLS_BASE_MOD_SECP_SCALAR",
+        hex!("8582e52ab1615fccaec4ebdb28d6e4b5ed62a5246e5e71af630cfda98d8c3114"),
+    ),
     (
         "SECP_GLV_BETA",
         hex!("7ae96a2b657c07106e64479eac3434e99cf0497512f58995c1396c28719501ee"),
@@ -232,7 +270,7 @@ const GAS_CONSTANTS: [(&str, u16); 36] = [
 
 const REFUND_CONSTANTS: [(&str, u16); 2] = [("REFUND_SCLEAR", 4_800), ("MAX_REFUND_QUOTIENT", 5)];
 
-const PRECOMPILES: [(&str, u16); 9] = [
+const PRECOMPILES: [(&str, u16); 15] = [
     ("ECREC", 1),
     ("SHA256", 2),
     ("RIP160", 3),
@@ -242,9 +280,15 @@ const PRECOMPILES: [(&str, u16); 9] = [
     ("BN_MUL", 7),
     ("SNARKV", 8),
     ("BLAKE2_F", 9),
+    ("KZG_PEVAL", 10),
+    ("BLS_G1_ADD", 11),
+    ("BLS_G1_MSM", 12),
+    ("BLS_G2_ADD", 13),
+    ("BLS_G2_MSM", 14),
+    ("BLS_PAIRING", 15),
 ];
 
-const PRECOMPILES_GAS: [(&str, u16); 13] = [
+const PRECOMPILES_GAS: [(&str, u16); 20] = [
     ("ECREC_GAS", 3_000),
     ("SHA256_STATIC_GAS", 60),
     ("SHA256_DYNAMIC_GAS", 12),
@@ -258,15 +302,54 @@ const PRECOMPILES_GAS: [(&str, u16); 13] = [
     ("SNARKV_STATIC_GAS", 45_000),
     ("SNARKV_DYNAMIC_GAS", 34_000),
     ("BLAKE2_F__GAS", 1),
+    ("KZG_PEVAL_GAS", 50_000),
+    ("BLS_G1_ADD_GAS", 375),
+    ("BLS_G1_MSM_GAS", 12_000),
+    ("BLS_G2_ADD_GAS", 600),
+    ("BLS_G2_MSM_GAS", 22_500),
+    ("BLS_PAIRING_STATIC_GAS", 37_700),
+    ("BLS_PAIRING_DYNAMIC_GAS", 32_600),
 ];
 
 const SNARKV_POINTERS: [(&str, u64); 2] = [("SNARKV_INP", 112), ("SNARKV_OUT", 100)];
 
+/// Start of the inputs of the BLS12-381 precompiles in the kernel general segment, past the
+/// scratch space used by the curve and pairing routines. The KZG point evaluation precompile
+/// stores the terms of its two MSMs and the pairs of its pairing check in the same space.
+const BLS_POINTERS: [(&str, u64); 4] = [
+    ("BLS_INP", 512),
+    ("KZG_G1_TERMS", 512),
+    ("KZG_G2_TERMS", 530),
+    ("KZG_PAIRS", 548),
+];
+
 const CODE_SIZE_LIMIT: [(&str, u64); 3] = [
     ("MAX_CODE_SIZE", 0x6000),
     ("MAX_INITCODE_SIZE", 0xc000),
     ("INITCODE_WORD_COST", 2),
 ];
 
+/// EIP-4844 constants.
+const BLOB_CONSTANTS: [(&str, u64); 6] = [
+    ("GAS_PER_BLOB", 0x20000),
+    ("MAX_BLOB_GAS_PER_BLOCK", 0xc0000),
+    ("MIN_BLOB_BASE_FEE", 1),
+    ("BLOB_BASE_FEE_UPDATE_FRACTION", 3_338_477),
+    ("VERSIONED_HASH_VERSION_KZG", 1),
+    ("FIELD_ELEMENTS_PER_BLOB", 4096),
+];
+
+/// EIP-4788 constants.
+const BEACON_ROOTS_CONSTANTS: [(&str, [u8; 32]); 2] = [
+    (
+        "BEACON_ROOTS_ADDRESS",
+        hex!("000000000000000000000000000f3df6d732807ef1319fb7b8bb8522d0beac02"),
+    ),
+    (
+        "HISTORY_BUFFER_LENGTH",
+        hex!("0000000000000000000000000000000000000000000000000000000000001fff"),
+    ),
+];
+
 const MAX_NONCE: (&str, u64) = ("MAX_NONCE", 0xffffffffffffffff);
 const CALL_STACK_LIMIT: (&str, u64) = ("CALL_STACK_LIMIT", 1024);
@@ -23,10 +23,16 @@ pub(crate) enum NormalizedTxnField {
     /// This is not technically a transaction field, as it depends on the block's base fee.
     ComputedFeePerGas = 15,
     ComputedPriorityFeePerGas = 16,
+
+    /// The max fee per blob gas of a type-3 transaction.
+    MaxFeePerBlobGas = 17,
+    /// The number of blob versioned hashes of a type-3 transaction, zero for other types.
+    /// The hashes themselves are stored in another segment.
+    BlobVersionedHashesLen = 18,
 }
 
 impl NormalizedTxnField {
-    pub(crate) const COUNT: usize = 16;
+    pub(crate) const COUNT: usize = 18;
 
     pub(crate) fn all() -> [Self; Self::COUNT] {
         [
@@ -46,6 +52,8 @@ impl NormalizedTxnField {
             Self::Origin,
             Self::ComputedFeePerGas,
             Self::ComputedPriorityFeePerGas,
+            Self::MaxFeePerBlobGas,
+            Self::BlobVersionedHashesLen,
         ]
     }
 
@@ -70,6 +78,8 @@ impl NormalizedTxnField {
             NormalizedTxnField::ComputedPriorityFeePerGas => {
                 "TXN_FIELD_COMPUTED_PRIORITY_FEE_PER_GAS"
             }
+            NormalizedTxnField::MaxFeePerBlobGas => "TXN_FIELD_MAX_FEE_PER_BLOB_GAS",
+            NormalizedTxnField::BlobVersionedHashesLen => "TXN_FIELD_BLOB_VERSIONED_HASHES_LEN",
         }
     }
 }
@@ -400,7 +400,9 @@ impl<'a> Interpreter<'a> {
             0x45 => self.run_gaslimit(),                                // "GASLIMIT",
             0x46 => self.run_chainid(),                                 // "CHAINID",
             0x48 => self.run_basefee(),                                 // "BASEFEE",
-            0x49 => self.run_prover_input()?,                           // "PROVER_INPUT",
+            0x49 if self.kernel_mode => self.run_prover_input()?,       // "PROVER_INPUT",
+            0x49 => self.run_syscall(opcode, 1, false)?,                // "BLOBHASH",
+            0x4a => self.run_syscall(opcode, 0, true)?,                 // "BLOBBASEFEE",
             0x50 => self.run_pop(),                                     // "POP",
             0x51 => self.run_mload(),                                   // "MLOAD",
             0x52 => self.run_mstore(),                                  // "MSTORE",
@@ -413,6 +415,9 @@ impl<'a> Interpreter<'a> {
             0x59 => self.run_msize(),                                   // "MSIZE",
             0x5a => self.run_syscall(opcode, 0, true)?,                 // "GAS",
             0x5b => self.run_jumpdest(),                                // "JUMPDEST",
+            0x5c => self.run_syscall(opcode, 1, false)?,                // "TLOAD",
+            0x5d => self.run_syscall(opcode, 2, false)?,                // "TSTORE",
+            0x5e => self.run_syscall(opcode, 3, false)?,                // "MCOPY",
             x if (0x5f..0x80).contains(&x) => self.run_push(x - 0x5f),  // "PUSH"
             x if (0x80..0x90).contains(&x) => self.run_dup(x - 0x7f)?,  // "DUP"
             x if (0x90..0xa0).contains(&x) => self.run_swap(x - 0x8f)?, // "SWAP"
@@ -1373,6 +1378,7 @@ fn get_mnemonic(opcode: u8) -> &'static str {
         0x46 => "CHAINID",
         0x48 => "BASEFEE",
         0x49 => "PROVER_INPUT",
+        0x4a => "BLOBBASEFEE",
         0x50 => "POP",
         0x51 => "MLOAD",
         0x52 => "MSTORE",
@@ -1385,6 +1391,9 @@ fn get_mnemonic(opcode: u8) -> &'static str {
         0x59 => "MSIZE",
         0x5a => "GAS",
         0x5b => "JUMPDEST",
+        0x5c => "TLOAD",
+        0x5d => "TSTORE",
+        0x5e => "MCOPY",
         0x5f => "PUSH0",
         0x60 => "PUSH1",
         0x61 => "PUSH2",
@@ -64,6 +64,7 @@ pub fn get_opcode(mnemonic: &str) -> u8 {
         "CHAINID" => 0x46,
         "BASEFEE" => 0x48,
         "PROVER_INPUT" => 0x49,
+        "BLOBBASEFEE" => 0x4a,
         "POP" => 0x50,
         "MLOAD" => 0x51,
         "MSTORE" => 0x52,
@@ -76,6 +77,9 @@ pub fn get_opcode(mnemonic: &str) -> u8 {
         "MSIZE" => 0x59,
         "GAS" => 0x5a,
         "JUMPDEST" => 0x5b,
+        "TLOAD" => 0x5c,
+        "TSTORE" => 0x5d,
+        "MCOPY" => 0x5e,
         "DUP1" => 0x80,
         "DUP2" => 0x81,
         "DUP3" => 0x82,
@@ -0,0 +1,70 @@
+use anyhow::Result;
+use ethereum_types::{H256, U256};
+use rand::{thread_rng, Rng};
+
+use crate::cpu::kernel::aggregator::KERNEL;
+use crate::cpu::kernel::constants::global_metadata::GlobalMetadata;
+use crate::cpu::kernel::constants::txn_fields::NormalizedTxnField;
+use crate::cpu::kernel::interpreter::Interpreter;
+use crate::memory::segments::Segment;
+
+fn run_blob_base_fee(excess_blob_gas: U256) -> Result<U256> {
+    let blob_base_fee_label = KERNEL.global_labels["blob_base_fee"];
+    let retdest = 0xDEADBEEFu32.into();
+
+    let mut interpreter = Interpreter::new_with_kernel(blob_base_fee_label, vec![retdest]);
+    interpreter.set_global_metadata_field(GlobalMetadata::BlockExcessBlobGas, excess_blob_gas);
+    interpreter.run()?;
+
+    Ok(interpreter.stack()[0])
+}
+
+#[test]
+fn test_blob_base_fee() -> Result<()> {
+    // Expected values are given by the EIP-4844 `fake_exponential` reference implementation.
+    let cases = [
+        (0u64, 1u64),
+        (3_338_477, 2),
+        (10 * 3_338_477, 22026),
+        (0x3c0000 * 10, 130392),
+    ];
+    for (excess_blob_gas, expected) in cases {
+        assert_eq!(
+            run_blob_base_fee(excess_blob_gas.into())?,
+            expected.into(),
+            "Wrong blob base fee for excess blob gas {excess_blob_gas}"
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_blobhash() -> Result<()> {
+    let mut rng = thread_rng();
+
+    let blobhash_label = KERNEL.global_labels["blobhash"];
+    let retdest: U256 = 0xDEADBEEFu32.into();
+
+    let num_hashes = rng.gen_range(1..=6);
+    let hashes: Vec<U256> = (0..num_hashes)
+        .map(|_| U256::from_big_endian(&rng.gen::<H256>().0))
+        .collect();
+
+    for index in 0..=num_hashes {
+        let mut interpreter =
+            Interpreter::new_with_kernel(blobhash_label, vec![retdest, index.into()]);
+        interpreter.set_memory_segment(Segment::TxnBlobVersionedHashes, hashes.clone());
+        interpreter.set_txn_field(
+            NormalizedTxnField::BlobVersionedHashesLen,
+            num_hashes.into(),
+        );
+        interpreter.run()?;
+
+        // Out-of-range indices yield 0.
+        let expected = hashes.get(index).copied().unwrap_or_default();
+        assert_eq!(interpreter.stack()[0], expected);
+    }
+
+    Ok(())
+}
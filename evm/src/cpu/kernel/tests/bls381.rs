@@ -1,13 +1,64 @@
 use anyhow::Result;
-use ethereum_types::U256;
+use ethereum_types::{U256, U512};
+use hex_literal::hex;
 use rand::Rng;
 
 use crate::cpu::kernel::interpreter::{
     run_interpreter_with_memory, InterpreterMemoryInitialization,
 };
-use crate::extension_tower::{Fp2, Stack, BLS381};
+use crate::curve_pairings::{
+    bls_final_exponent, bls_miller_loop, bls_tangent, verify_kzg_proof, Curve, CyclicGroup,
+    BLS_SCALAR,
+};
+use crate::extension_tower::{FieldExt, Fp12, Fp2, Stack, BLS381, BLS_BASE};
 use crate::memory::segments::Segment::KernelGeneral;
 
+fn run_bls_fp_op(label: &str, x: BLS381, y: BLS381) -> BLS381 {
+    let mut stack = vec![x.lo(), x.hi(), y.lo(), y.hi()];
+    stack.push(U256::from(0xdeadbeefu32));
+    let setup = InterpreterMemoryInitialization {
+        label: label.to_string(),
+        stack,
+        segment: KernelGeneral,
+        memory: vec![],
+    };
+    let interpreter = run_interpreter_with_memory(setup).unwrap();
+    let stack = interpreter.stack();
+    BLS381 {
+        val: U512::from(stack[1]) + (U512::from(stack[0]) << 256),
+    }
+}
+
+#[test]
+fn test_bls_fp_arithmetic() -> Result<()> {
+    let mut rng = rand::thread_rng();
+    let minus_one = -BLS381::UNIT;
+    let mut cases = vec![
+        (BLS381::ZERO, BLS381::ZERO),
+        (BLS381::ZERO, minus_one),
+        (minus_one, BLS381::UNIT),
+        (minus_one, minus_one),
+        // Operands whose low words carry or borrow.
+        (
+            BLS381 {
+                val: U512::from(U256::MAX),
+            },
+            BLS381 {
+                val: U512::from(U256::MAX),
+            },
+        ),
+    ];
+    cases.extend((0..4).map(|_| (rng.gen::<BLS381>(), rng.gen::<BLS381>())));
+
+    for (x, y) in cases {
+        assert_eq!(run_bls_fp_op("add_fp381", x, y), x + y);
+        assert_eq!(run_bls_fp_op("sub_fp381", x, y), x - y);
+        assert_eq!(run_bls_fp_op("mul_fp381", x, y), x * y);
+    }
+
+    Ok(())
+}
+
 #[test]
 fn test_bls_fp2_mul() -> Result<()> {
     let mut rng = rand::thread_rng();
@@ -30,3 +81,443 @@ fn test_bls_fp2_mul() -> Result<()> {
     assert_eq!(output, x * y);
     Ok(())
 }
+
+/// Encodes a G1 point in its 48-byte compressed serialization.
+fn compress_g1(p: Curve<BLS381>) -> [u8; 48] {
+    let mut bytes = [0u8; 64];
+    if p == Curve::<BLS381>::unit() {
+        bytes[16] = 0xc0;
+    } else {
+        p.x.val.to_big_endian(&mut bytes);
+        bytes[16] |= 0x80;
+        if p.y.val > (BLS_BASE - 1) >> 1 {
+            bytes[16] |= 0x20;
+        }
+    }
+    bytes[16..].try_into().unwrap()
+}
+
+/// Splits a compressed point into its leading 16 bytes and trailing 32 bytes.
+fn split_compressed(bytes: [u8; 48]) -> (U256, U256) {
+    (
+        U256::from_big_endian(&bytes[..16]),
+        U256::from_big_endian(&bytes[16..]),
+    )
+}
+
+fn run_bls_decompress_g1(bytes: [u8; 48]) -> Option<Curve<BLS381>> {
+    let ptr: usize = 512;
+    let (hi, lo) = split_compressed(bytes);
+    let setup = InterpreterMemoryInitialization {
+        label: "bls_decompress_g1".to_string(),
+        stack: vec![hi, lo, U256::from(ptr), U256::from(0xdeadbeefu32)],
+        segment: KernelGeneral,
+        memory: vec![],
+    };
+    let interpreter = run_interpreter_with_memory(setup).unwrap();
+    if interpreter.stack()[0].is_zero() {
+        return None;
+    }
+    let output = interpreter.extract_kernel_memory(KernelGeneral, ptr..ptr + 8);
+    let p = Curve::<BLS381> {
+        x: BLS381::from_stack(&output[0..2]),
+        y: BLS381::from_stack(&output[4..6]),
+    };
+    assert_eq!(output, embed_g1(p).to_stack());
+    Some(p)
+}
+
+#[test]
+fn test_bls_decompress_g1() -> Result<()> {
+    let mut rng = rand::thread_rng();
+    let p = rng.gen::<Curve<BLS381>>();
+    assert_eq!(run_bls_decompress_g1(compress_g1(p)), Some(p));
+    assert_eq!(run_bls_decompress_g1(compress_g1(-p)), Some(-p));
+    let o = Curve::<BLS381>::unit();
+    assert_eq!(run_bls_decompress_g1(compress_g1(o)), Some(o));
+
+    // The compression flag must be set, and the point at infinity must have all other bits zero.
+    let mut bytes = compress_g1(p);
+    bytes[0] &= 0x7f;
+    assert_eq!(run_bls_decompress_g1(bytes), None);
+    let mut bytes = compress_g1(o);
+    bytes[47] = 1;
+    assert_eq!(run_bls_decompress_g1(bytes), None);
+    let mut bytes = compress_g1(o);
+    bytes[0] |= 0x20;
+    assert_eq!(run_bls_decompress_g1(bytes), None);
+
+    // x must be canonical and x^3 + 4 must be a square.
+    let mut bytes = [0u8; 64];
+    BLS_BASE.to_big_endian(&mut bytes);
+    bytes[16] |= 0x80;
+    assert_eq!(run_bls_decompress_g1(bytes[16..].try_into().unwrap()), None);
+    let x = std::iter::repeat_with(|| rng.gen::<BLS381>())
+        .find(|&x| (x * x * x + BLS381::new(4)).sqrt().is_none())
+        .unwrap();
+    let mut bytes = [0u8; 64];
+    x.val.to_big_endian(&mut bytes);
+    bytes[16] |= 0x80;
+    assert_eq!(run_bls_decompress_g1(bytes[16..].try_into().unwrap()), None);
+    Ok(())
+}
+
+fn run_verify_kzg_proof(commitment: [u8; 48], z: U256, y: U256, proof: [u8; 48]) -> bool {
+    let (commitment_hi, commitment_lo) = split_compressed(commitment);
+    let (proof_hi, proof_lo) = split_compressed(proof);
+    let setup = InterpreterMemoryInitialization {
+        label: "verify_kzg_proof".to_string(),
+        stack: vec![
+            z,
+            y,
+            commitment_hi,
+            commitment_lo,
+            proof_hi,
+            proof_lo,
+            U256::from(0xdeadbeefu32),
+        ],
+        segment: KernelGeneral,
+        memory: vec![],
+    };
+    let interpreter = run_interpreter_with_memory(setup).unwrap();
+    let output = interpreter.stack()[0];
+    assert!(output <= U256::one());
+    output == U256::one()
+}
+
+#[test]
+fn test_kzg_point_evaluation() -> Result<()> {
+    let mut rng = rand::thread_rng();
+    // Compressed encodings of the BLS12-381 G1 generator and of the point at infinity.
+    let g1 = hex!("97f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb");
+    let mut infinity = [0u8; 48];
+    infinity[0] = 0xc0;
+    assert_eq!(
+        Curve::<BLS381>::from_compressed(g1),
+        Some(Curve::<BLS381>::GENERATOR)
+    );
+    assert_eq!(compress_g1(Curve::<BLS381>::GENERATOR), g1);
+
+    // A point on the curve outside of the prime order subgroup.
+    let not_in_subgroup = std::iter::repeat_with(|| rng.gen::<BLS381>())
+        .find_map(|x| {
+            let y = (x * x * x + BLS381::new(4)).sqrt()?;
+            Some(compress_g1(Curve { x, y }))
+        })
+        .unwrap();
+
+    // The zero polynomial evaluates to 0 everywhere, and the constant polynomial c is committed
+    // to by [c]G1, both with a trivial proof.
+    let z = U256(rng.gen()) % BLS_SCALAR;
+    let c: i32 = rng.gen();
+    let c_scalar = if c < 0 {
+        BLS_SCALAR - U256::from(c.unsigned_abs())
+    } else {
+        U256::from(c)
+    };
+    let commitment = compress_g1(Curve::<BLS381>::GENERATOR * c);
+    let cases = [
+        (infinity, z, U256::zero(), infinity, true),
+        (infinity, z, U256::one(), infinity, false),
+        (g1, z, U256::one(), infinity, true),
+        (g1, z, U256::one(), g1, false),
+        (commitment, z, c_scalar, infinity, true),
+        (commitment, z, (c_scalar + 1) % BLS_SCALAR, infinity, false),
+        (not_in_subgroup, z, U256::one(), infinity, false),
+        (g1, z, U256::one(), not_in_subgroup, false),
+    ];
+    for (commitment, z, y, proof, is_valid) in cases {
+        assert_eq!(verify_kzg_proof(commitment, z, y, proof), is_valid);
+        assert_eq!(run_verify_kzg_proof(commitment, z, y, proof), is_valid);
+    }
+    Ok(())
+}
+
+/// Embeds a G1 point into the Fp2 coordinates used by the kernel's curve routines.
+fn embed_g1(p: Curve<BLS381>) -> Curve<Fp2<BLS381>> {
+    Curve {
+        x: Fp2 {
+            re: p.x,
+            im: BLS381::ZERO,
+        },
+        y: Fp2 {
+            re: p.y,
+            im: BLS381::ZERO,
+        },
+    }
+}
+
+fn run_bls_mul_fp12(f: Fp12<BLS381>, g: Fp12<BLS381>) -> Fp12<BLS381> {
+    let in0: usize = 512;
+    let in1: usize = 536;
+    let out: usize = 560;
+
+    let setup = InterpreterMemoryInitialization {
+        label: "mul_fp381_12".to_string(),
+        stack: vec![
+            U256::from(in0),
+            U256::from(in1),
+            U256::from(out),
+            U256::from(0xdeadbeefu32),
+        ],
+        segment: KernelGeneral,
+        memory: vec![(in0, f.to_stack()), (in1, g.to_stack())],
+    };
+    let interpreter = run_interpreter_with_memory(setup).unwrap();
+    let output = interpreter.extract_kernel_memory(KernelGeneral, out..out + 24);
+    Fp12::<BLS381>::from_stack(&output)
+}
+
+#[test]
+fn test_bls_mul_fp12() -> Result<()> {
+    let mut rng = rand::thread_rng();
+    let f: Fp12<BLS381> = rng.gen::<Fp12<BLS381>>();
+    let g: Fp12<BLS381> = rng.gen::<Fp12<BLS381>>();
+    let p: Curve<BLS381> = rng.gen::<Curve<BLS381>>();
+    let q: Curve<Fp2<BLS381>> = rng.gen::<Curve<Fp2<BLS381>>>();
+    let h = bls_tangent(p, q);
+
+    assert_eq!(run_bls_mul_fp12(f, g), f * g);
+    assert_eq!(run_bls_mul_fp12(f, h), f * h);
+    assert_eq!(run_bls_mul_fp12(f, f), f * f);
+
+    Ok(())
+}
+
+#[test]
+fn test_bls_frob_fp12() -> Result<()> {
+    let ptr: usize = 512;
+    let out: usize = 536;
+    let mut rng = rand::thread_rng();
+    let f: Fp12<BLS381> = rng.gen::<Fp12<BLS381>>();
+
+    let setup = InterpreterMemoryInitialization {
+        label: "frob_fp381_12".to_string(),
+        stack: vec![U256::from(ptr), U256::from(out), U256::from(0xdeadbeefu32)],
+        segment: KernelGeneral,
+        memory: vec![(ptr, f.to_stack())],
+    };
+    let interpreter = run_interpreter_with_memory(setup).unwrap();
+    let output = interpreter.extract_kernel_memory(KernelGeneral, out..out + 24);
+
+    assert_eq!(Fp12::<BLS381>::from_stack(&output), f.frob(1));
+
+    Ok(())
+}
+
+#[test]
+fn test_bls_inv_fp12() -> Result<()> {
+    let ptr: usize = 512;
+    let inv: usize = 536;
+    let mut rng = rand::thread_rng();
+    let f: Fp12<BLS381> = rng.gen::<Fp12<BLS381>>();
+
+    let setup = InterpreterMemoryInitialization {
+        label: "inv_fp381_12".to_string(),
+        stack: vec![U256::from(ptr), U256::from(inv), U256::from(0xdeadbeefu32)],
+        segment: KernelGeneral,
+        memory: vec![(ptr, f.to_stack())],
+    };
+    let interpreter = run_interpreter_with_memory(setup).unwrap();
+    let output = interpreter.extract_kernel_memory(KernelGeneral, inv..inv + 24);
+
+    assert_eq!(Fp12::<BLS381>::from_stack(&output), f.inv());
+
+    Ok(())
+}
+
+#[test]
+fn test_bls_final_exponent() -> Result<()> {
+    let ptr: usize = 512;
+    let mut rng = rand::thread_rng();
+    let f: Fp12<BLS381> = rng.gen::<Fp12<BLS381>>();
+
+    let setup = InterpreterMemoryInitialization {
+        label: "bls381_final_exponent".to_string(),
+        stack: vec![U256::from(ptr), U256::from(ptr), U256::from(0xdeadbeefu32)],
+        segment: KernelGeneral,
+        memory: vec![(ptr, f.to_stack())],
+    };
+    let interpreter = run_interpreter_with_memory(setup).unwrap();
+    let output = interpreter.extract_kernel_memory(KernelGeneral, ptr..ptr + 24);
+
+    assert_eq!(output, bls_final_exponent(f).to_stack());
+
+    Ok(())
+}
+
+#[test]
+fn test_bls_miller() -> Result<()> {
+    let ptr: usize = 512;
+    let out: usize = 528;
+    let mut rng = rand::thread_rng();
+    let p: Curve<BLS381> = rng.gen::<Curve<BLS381>>();
+    let q: Curve<Fp2<BLS381>> = rng.gen::<Curve<Fp2<BLS381>>>();
+
+    let mut input = embed_g1(p).to_stack();
+    input.extend(q.to_stack());
+
+    let setup = InterpreterMemoryInitialization {
+        label: "bls381_miller".to_string(),
+        stack: vec![U256::from(ptr), U256::from(out), U256::from(0xdeadbeefu32)],
+        segment: KernelGeneral,
+        memory: vec![(ptr, input)],
+    };
+    let interpreter = run_interpreter_with_memory(setup).unwrap();
+    let output = interpreter.extract_kernel_memory(KernelGeneral, out..out + 24);
+
+    assert_eq!(output, bls_miller_loop(p, q).to_stack());
+
+    Ok(())
+}
+
+#[test]
+fn test_bls_pairing() -> Result<()> {
+    let ptr: usize = 512;
+    let mut rng = rand::thread_rng();
+    let k: usize = rng.gen_range(1..4);
+    let mut acc: i32 = 0;
+    let mut input: Vec<U256> = vec![];
+    for _ in 1..k {
+        let m: i32 = rng.gen_range(-8..8);
+        let n: i32 = rng.gen_range(-8..8);
+        acc -= m * n;
+
+        input.extend(embed_g1(Curve::<BLS381>::int(m)).to_stack());
+        input.extend(Curve::<Fp2<BLS381>>::int(n).to_stack());
+    }
+    input.extend(embed_g1(Curve::<BLS381>::int(acc)).to_stack());
+    input.extend(Curve::<Fp2<BLS381>>::GENERATOR.to_stack());
+
+    let run = |input: Vec<U256>| {
+        let setup = InterpreterMemoryInitialization {
+            label: "bls381_pairing".to_string(),
+            stack: vec![U256::from(k), U256::from(ptr), U256::from(0xdeadbeefu32)],
+            segment: KernelGeneral,
+            memory: vec![(ptr, input)],
+        };
+        run_interpreter_with_memory(setup).unwrap().stack()[0]
+    };
+    assert_eq!(run(input.clone()), U256::one());
+
+    // Replacing the last G1 point by another multiple of the generator breaks the relation.
+    let n = input.len();
+    input[n - 16..n - 8].copy_from_slice(&embed_g1(Curve::<BLS381>::int(acc + 1)).to_stack());
+    assert_eq!(run(input), U256::zero());
+
+    Ok(())
+}
+
+fn run_bls_msm(terms: &[(Curve<Fp2<BLS381>>, U256)]) -> Curve<Fp2<BLS381>> {
+    let ptr: usize = 512;
+    let input = terms
+        .iter()
+        .flat_map(|(p, s)| {
+            let mut term = p.to_stack();
+            term.push(*s);
+            term
+        })
+        .collect();
+
+    let setup = InterpreterMemoryInitialization {
+        label: "bls_msm".to_string(),
+        stack: vec![
+            U256::from(terms.len()),
+            U256::from(ptr),
+            U256::from(0xdeadbeefu32),
+        ],
+        segment: KernelGeneral,
+        memory: vec![(ptr, input)],
+    };
+    let interpreter = run_interpreter_with_memory(setup).unwrap();
+    let output = interpreter.extract_kernel_memory(KernelGeneral, 0..8);
+    Curve::<Fp2<BLS381>>::from_stack(&output)
+}
+
+#[test]
+fn test_bls_msm() -> Result<()> {
+    let mut rng = rand::thread_rng();
+
+    let g1_terms: Vec<(Curve<BLS381>, U256)> = (0..3)
+        .map(|_| (rng.gen::<Curve<BLS381>>(), U256(rng.gen())))
+        .collect();
+    let expected = g1_terms
+        .iter()
+        .fold(Curve::<BLS381>::unit(), |acc, &(p, s)| {
+            acc + p.mul_scalar(s)
+        });
+    let embedded: Vec<_> = g1_terms.iter().map(|&(p, s)| (embed_g1(p), s)).collect();
+    assert_eq!(run_bls_msm(&embedded), embed_g1(expected));
+
+    let g2_terms: Vec<(Curve<Fp2<BLS381>>, U256)> = (0..2)
+        .map(|_| (rng.gen::<Curve<Fp2<BLS381>>>(), U256(rng.gen())))
+        .collect();
+    let expected = g2_terms
+        .iter()
+        .fold(Curve::<Fp2<BLS381>>::unit(), |acc, &(p, s)| {
+            acc + p.mul_scalar(s)
+        });
+    assert_eq!(run_bls_msm(&g2_terms), expected);
+
+    // Adding a point to its negation and doubling through the MSM.
+    let p = rng.gen::<Curve<Fp2<BLS381>>>();
+    let one = U256::one();
+    assert_eq!(run_bls_msm(&[(p, one), (-p, one)]), Curve::unit());
+    assert_eq!(run_bls_msm(&[(p, one), (p, one)]), p + p);
+    assert_eq!(run_bls_msm(&[(p, BLS_SCALAR)]), Curve::unit());
+
+    Ok(())
+}
+
+fn run_bls_check(label: &str, p: Curve<Fp2<BLS381>>, b: [u32; 4]) -> U256 {
+    let ptr: usize = 512;
+    let mut stack = vec![U256::from(ptr)];
+    if label == "bls_is_on_curve" {
+        stack.extend(b.map(U256::from));
+    }
+    stack.push(U256::from(0xdeadbeefu32));
+
+    let setup = InterpreterMemoryInitialization {
+        label: label.to_string(),
+        stack,
+        segment: KernelGeneral,
+        memory: vec![(ptr, p.to_stack())],
+    };
+    run_interpreter_with_memory(setup).unwrap().stack()[0]
+}
+
+#[test]
+fn test_bls_point_checks() -> Result<()> {
+    let mut rng = rand::thread_rng();
+    let g1_b = [4, 0, 0, 0];
+    let g2_b = [4, 0, 4, 0];
+    let p = embed_g1(rng.gen::<Curve<BLS381>>());
+    let q = rng.gen::<Curve<Fp2<BLS381>>>();
+    let not_on_curve = Curve {
+        x: q.x,
+        y: q.y + Fp2::<BLS381>::UNIT,
+    };
+
+    assert_eq!(run_bls_check("bls_is_on_curve", p, g1_b), U256::one());
+    assert_eq!(run_bls_check("bls_is_on_curve", q, g2_b), U256::one());
+    assert_eq!(run_bls_check("bls_is_on_curve", q, g1_b), U256::zero());
+    assert_eq!(
+        run_bls_check("bls_is_on_curve", not_on_curve, g2_b),
+        U256::zero()
+    );
+    assert_eq!(
+        run_bls_check("bls_is_on_curve", Curve::unit(), g2_b),
+        U256::one()
+    );
+
+    assert_eq!(run_bls_check("bls_in_subgroup", p, g1_b), U256::one());
+    assert_eq!(run_bls_check("bls_in_subgroup", q, g2_b), U256::one());
+    // Points on the G1 curve outside of the prime order subgroup, e.g. (0, 2), are rejected.
+    let x = BLS381::ZERO;
+    let y = BLS381::new(2);
+    let point = embed_g1(Curve { x, y });
+    assert_eq!(run_bls_check("bls_is_on_curve", point, g1_b), U256::one());
+    assert_eq!(run_bls_check("bls_in_subgroup", point, g1_b), U256::zero());
+
+    Ok(())
+}
@@ -0,0 +1,106 @@
+use anyhow::Result;
+use ethereum_types::{Address, U256};
+use rand::{thread_rng, Rng};
+
+use crate::cpu::kernel::aggregator::KERNEL;
+use crate::cpu::kernel::constants::global_metadata::GlobalMetadata::TransientStorageLen;
+use crate::cpu::kernel::constants::journal_entry::JournalEntry;
+use crate::cpu::kernel::interpreter::Interpreter;
+use crate::memory::segments::Segment::{GlobalMetadata, JournalData, TransientStorage};
+use crate::witness::memory::MemoryAddress;
+
+/// Fills the transient storage array of `interpreter` with the given `(address, slot, value)` triples.
+fn set_transient_storage(interpreter: &mut Interpreter<'_>, triples: &[(Address, U256, U256)]) {
+    for (i, &(addr, slot, value)) in triples.iter().enumerate() {
+        let memory = &mut interpreter.generation_state.memory;
+        memory.set(
+            MemoryAddress::new(0, TransientStorage, 3 * i),
+            U256::from(addr.0.as_slice()),
+        );
+        memory.set(MemoryAddress::new(0, TransientStorage, 3 * i + 1), slot);
+        memory.set(MemoryAddress::new(0, TransientStorage, 3 * i + 2), value);
+    }
+    interpreter.generation_state.memory.set(
+        MemoryAddress::new(0, GlobalMetadata, TransientStorageLen as usize),
+        U256::from(3 * triples.len()),
+    );
+}
+
+#[test]
+fn test_search_transient_storage() -> Result<()> {
+    let search_transient_storage = KERNEL.global_labels["search_transient_storage"];
+
+    let retaddr = 0xdeadbeefu32.into();
+    let mut rng = thread_rng();
+    let n = rng.gen_range(1..10);
+    let triples = (0..n)
+        .map(|i| (rng.gen::<Address>(), U256::from(i), U256(rng.gen())))
+        .collect::<Vec<_>>();
+    let i = rng.gen_range(0..n);
+
+    // Test for a slot which has been written.
+    let (addr, slot, _) = triples[i];
+    let initial_stack = vec![retaddr, slot, U256::from(addr.0.as_slice())];
+    let mut interpreter = Interpreter::new_with_kernel(search_transient_storage, initial_stack);
+    set_transient_storage(&mut interpreter, &triples);
+    interpreter.run()?;
+    assert_eq!(interpreter.stack(), &[U256::from(3 * i)]);
+
+    // Test for the same slot of another address.
+    let initial_stack = vec![retaddr, slot, U256::from(rng.gen::<Address>().0.as_slice())];
+    let mut interpreter = Interpreter::new_with_kernel(search_transient_storage, initial_stack);
+    set_transient_storage(&mut interpreter, &triples);
+    interpreter.run()?;
+    assert_eq!(interpreter.stack(), &[U256::from(3 * n)]);
+
+    Ok(())
+}
+
+#[test]
+fn test_revert_transient_storage_change() -> Result<()> {
+    let revert_transient_storage_change = KERNEL.global_labels["revert_transient_storage_change"];
+
+    let retaddr = 0xdeadbeefu32.into();
+    let mut rng = thread_rng();
+    let n = rng.gen_range(1..10);
+    let triples = (0..n)
+        .map(|i| (rng.gen::<Address>(), U256::from(i), U256(rng.gen())))
+        .collect::<Vec<_>>();
+    let i = rng.gen_range(0..n);
+    let (addr, slot, _) = triples[i];
+    let prev_value = U256(rng.gen());
+
+    // Journal entry `TransientStorageChange { addr, slot, prev_value }` at `ptr`.
+    let ptr = 5;
+    let entry_type = U256::from(JournalEntry::TransientStorageChange as usize);
+    let initial_stack = vec![retaddr, U256::from(ptr), entry_type];
+    let mut interpreter =
+        Interpreter::new_with_kernel(revert_transient_storage_change, initial_stack);
+    set_transient_storage(&mut interpreter, &triples);
+    for (j, value) in [entry_type, U256::from(addr.0.as_slice()), slot, prev_value]
+        .into_iter()
+        .enumerate()
+    {
+        interpreter
+            .generation_state
+            .memory
+            .set(MemoryAddress::new(0, JournalData, ptr + j), value);
+    }
+    interpreter.run()?;
+    assert!(interpreter.stack().is_empty());
+
+    // Only the value of the reverted slot changed.
+    for (j, &(_, _, value)) in triples.iter().enumerate() {
+        let expected = if j == i { prev_value } else { value };
+        assert_eq!(
+            interpreter.generation_state.memory.get(MemoryAddress::new(
+                0,
+                TransientStorage,
+                3 * j + 2
+            )),
+            expected
+        );
+    }
+
+    Ok(())
+}
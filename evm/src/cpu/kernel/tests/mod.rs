@@ -2,6 +2,7 @@ mod account_code;
 mod balance;
 mod bignum;
 mod blake2_f;
+mod blob;
 mod block_hash;
 mod bls381;
 mod bn254;
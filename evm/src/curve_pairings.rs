@@ -1,11 +1,11 @@
 use std::ops::{Add, Mul, Neg};
 
-use ethereum_types::U256;
+use ethereum_types::{U256, U512};
 use rand::distributions::Standard;
 use rand::prelude::Distribution;
 use rand::Rng;
 
-use crate::extension_tower::{FieldExt, Fp12, Fp2, Fp6, Stack, BN254};
+use crate::extension_tower::{FieldExt, Fp12, Fp2, Fp6, Stack, BLS381, BLS_BASE, BN254};
 
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub(crate) struct Curve<T>
@@ -511,3 +511,465 @@ const BN_EXPS0: [bool; 65] = [
     true, true, true, false, true, false, true, true, false, false, true, false, false, false,
     true, true, true, true, false, false, true, true, false,
 ];
+
+/// The order of the BLS12-381 prime order subgroups, which is also the modulus of the scalar field
+pub const BLS_SCALAR: U256 = U256([
+    0xffffffff00000001,
+    0x53bda402fffe5bfe,
+    0x3339d80809a1d805,
+    0x73eda753299d7d48,
+]);
+
+impl<T: FieldExt> Curve<T> {
+    /// Standard double-and-add scalar multiplication by an arbitrary U256
+    pub(crate) fn mul_scalar(self, scalar: U256) -> Self {
+        let mut x = self;
+        let mut result = Curve::<T>::unit();
+        for i in 0..scalar.bits() {
+            if scalar.bit(i) {
+                result = result + x;
+            }
+            x = x + x;
+        }
+        result
+    }
+}
+
+/// The BLS curve consists of pairs
+///     (x, y): (BLS381, BLS381) | y^2 = x^3 + 4
+/// with generator given as follows
+impl CyclicGroup for Curve<BLS381> {
+    const GENERATOR: Curve<BLS381> = Curve {
+        x: BLS381 {
+            val: U512([
+                0xfb3af00adb22c6bb,
+                0x6c55e83ff97a1aef,
+                0xa14e3a3f171bac58,
+                0xc3688c4f9774b905,
+                0x2695638c4fa9ac0f,
+                0x17f1d3a73197d794,
+                0x0000000000000000,
+                0x0000000000000000,
+            ]),
+        },
+        y: BLS381 {
+            val: U512([
+                0x0caa232946c5e7e1,
+                0xd03cc744a2888ae4,
+                0x00db18cb2c04b3ed,
+                0xfcf5e095d5d00af6,
+                0xa09e30ed741d8ae4,
+                0x08b3f481e3aaa0f1,
+                0x0000000000000000,
+                0x0000000000000000,
+            ]),
+        },
+    };
+}
+
+/// The twisted curve consists of pairs
+///     (x, y): (Fp2<BLS381>, Fp2<BLS381>) | y^2 = x^3 + 4(1 + i)
+/// with generator given as follows
+impl CyclicGroup for Curve<Fp2<BLS381>> {
+    const GENERATOR: Curve<Fp2<BLS381>> = Curve {
+        x: Fp2 {
+            re: BLS381 {
+                val: U512([
+                    0xd48056c8c121bdb8,
+                    0x0bac0326a805bbef,
+                    0xb4510b647ae3d177,
+                    0xc6e47ad4fa403b02,
+                    0x260805272dc51051,
+                    0x024aa2b2f08f0a91,
+                    0x0000000000000000,
+                    0x0000000000000000,
+                ]),
+            },
+            im: BLS381 {
+                val: U512([
+                    0xe5ac7d055d042b7e,
+                    0x334cf11213945d57,
+                    0xb5da61bbdc7f5049,
+                    0x596bd0d09920b61a,
+                    0x7dacd3a088274f65,
+                    0x13e02b6052719f60,
+                    0x0000000000000000,
+                    0x0000000000000000,
+                ]),
+            },
+        },
+        y: Fp2 {
+            re: BLS381 {
+                val: U512([
+                    0xe193548608b82801,
+                    0x923ac9cc3baca289,
+                    0x6d429a695160d12c,
+                    0xadfd9baa8cbdd3a7,
+                    0x8cc9cdc6da2e351a,
+                    0x0ce5d527727d6e11,
+                    0x0000000000000000,
+                    0x0000000000000000,
+                ]),
+            },
+            im: BLS381 {
+                val: U512([
+                    0xaaa9075ff05f79be,
+                    0x3f370d275cec1da1,
+                    0x267492ab572e99ab,
+                    0xcb3e287e85a763af,
+                    0x32acd2b02bc28b99,
+                    0x0606c4a02ea734cc,
+                    0x0000000000000000,
+                    0x0000000000000000,
+                ]),
+            },
+        },
+    };
+}
+
+#[cfg(test)]
+impl Curve<BLS381> {
+    /// Decodes a point from its 48-byte compressed serialization, where the three most
+    /// significant bits are the compression, infinity and sign flags respectively.
+    /// Returns None if the encoding is invalid or the point is not in the prime order subgroup.
+    pub(crate) fn from_compressed(bytes: [u8; 48]) -> Option<Self> {
+        let compressed = bytes[0] & 0x80 != 0;
+        let infinity = bytes[0] & 0x40 != 0;
+        let sign = bytes[0] & 0x20 != 0;
+        if !compressed {
+            return None;
+        }
+
+        let mut x_bytes = bytes;
+        x_bytes[0] &= 0x1f;
+        if infinity {
+            let is_zero = !sign && x_bytes.iter().all(|&b| b == 0);
+            return is_zero.then(Curve::<BLS381>::unit);
+        }
+
+        let x = U512::from_big_endian(&x_bytes);
+        if x >= BLS_BASE {
+            return None;
+        }
+        let x = BLS381 { val: x };
+        let mut y = (x * x * x + BLS381::new(4)).sqrt()?;
+        if (y.val > (BLS_BASE - 1) >> 1) != sign {
+            y = -y;
+        }
+
+        let point = Curve { x, y };
+        (point.mul_scalar(BLS_SCALAR) == Curve::<BLS381>::unit()).then_some(point)
+    }
+}
+
+// The tate pairing takes a point each from the curve and its twist and outputs an Fp12 element
+pub(crate) fn bls_tate(p: Curve<BLS381>, q: Curve<Fp2<BLS381>>) -> Fp12<BLS381> {
+    let miller_output = bls_miller_loop(p, q);
+    bls_final_exponent(miller_output)
+}
+
+#[cfg(test)]
+/// Checks whether the product of the tate pairings of the given pairs is the identity,
+/// sharing a single final exponentiation across all pairs
+pub(crate) fn bls_pairing_check(pairs: &[(Curve<BLS381>, Curve<Fp2<BLS381>>)]) -> bool {
+    let mut acc = Fp12::<BLS381>::UNIT;
+    for &(p, q) in pairs {
+        if p != Curve::<BLS381>::unit() && q != Curve::<Fp2<BLS381>>::unit() {
+            acc = acc * bls_miller_loop(p, q);
+        }
+    }
+    bls_final_exponent(acc) == Fp12::<BLS381>::UNIT
+}
+
+/// Same as bn_miller_loop, where the loop traverses the bits of BLS_SCALAR after the leading one
+pub(crate) fn bls_miller_loop(p: Curve<BLS381>, q: Curve<Fp2<BLS381>>) -> Fp12<BLS381> {
+    let mut r = p;
+    let mut acc: Fp12<BLS381> = Fp12::<BLS381>::UNIT;
+    let mut line: Fp12<BLS381>;
+
+    for i in (0..BLS_SCALAR.bits() - 1).rev() {
+        line = bls_tangent(r, q);
+        r = r + r;
+        acc = line * acc * acc;
+        if BLS_SCALAR.bit(i) {
+            line = bls_cord(p, r, q);
+            r = r + p;
+            acc = line * acc;
+        }
+    }
+    acc
+}
+
+/// The sloped line function for doubling a point
+pub(crate) fn bls_tangent(p: Curve<BLS381>, q: Curve<Fp2<BLS381>>) -> Fp12<BLS381> {
+    let cx = -BLS381::new(3) * p.x * p.x;
+    let cy = BLS381::new(2) * p.y;
+    bls_sparse_embed(q.y * cy, q.x * cx, p.y * p.y - BLS381::new(12))
+}
+
+/// The sloped line function for adding two points
+pub(crate) fn bls_cord(
+    p1: Curve<BLS381>,
+    p2: Curve<BLS381>,
+    q: Curve<Fp2<BLS381>>,
+) -> Fp12<BLS381> {
+    let cx = p2.y - p1.y;
+    let cy = p1.x - p2.x;
+    bls_sparse_embed(q.y * cy, q.x * cx, p1.y * p2.x - p2.y * p1.x)
+}
+
+/// The BLS twist is a multiplicative (M-type) one, so untwisting scales the coordinates
+/// of the twisted point by z^-2 and z^-3. After clearing denominators by z^3, the nonzero
+/// coefficients of the tangent and cord functions are embedded into an Fp12 as follows.
+pub(crate) fn bls_sparse_embed(g00: Fp2<BLS381>, g10: Fp2<BLS381>, g110: BLS381) -> Fp12<BLS381> {
+    let g0 = Fp6 {
+        t0: g00,
+        t1: Fp2::<BLS381>::ZERO,
+        t2: Fp2::<BLS381>::ZERO,
+    };
+
+    let g1 = Fp6 {
+        t0: g10,
+        t1: Fp2 {
+            re: g110,
+            im: BLS381::ZERO,
+        },
+        t2: Fp2::<BLS381>::ZERO,
+    };
+
+    Fp12 { z0: g0, z1: g1 }
+}
+
+/// As for BN254, we first exponentiate by (p^6 - 1)(p^2 + 1) via
+///     y = y_6 / y
+///     y = y_2 * y
+/// We then write (p^4 - p^2 + 1)/N in base p as
+///     (p^4 - p^2 + 1)/N = (d3)p^3 + (d2)p^2 + (d1)p + d0
+/// where 0 <= d0, d1, d2, d3 < p. Then the final power is given by
+///     y = (y^d3)_3 * (y^d2)_2 * (y^d1)_1 * y^d0
+pub(crate) fn bls_final_exponent(f: Fp12<BLS381>) -> Fp12<BLS381> {
+    let mut y = f.frob(6) / f;
+    y = y.frob(2) * y;
+
+    let mut acc = Fp12::<BLS381>::UNIT;
+    for (i, d) in BLS_EXP_DIGITS.into_iter().enumerate() {
+        acc = acc * bls_pow(y, d).frob(i);
+    }
+    acc
+}
+
+fn bls_pow(f: Fp12<BLS381>, exp: U512) -> Fp12<BLS381> {
+    let mut sq = f;
+    let mut acc = Fp12::<BLS381>::UNIT;
+    for i in 0..exp.bits() {
+        if exp.bit(i) {
+            acc = acc * sq;
+        }
+        sq = sq * sq;
+    }
+    acc
+}
+
+/// The base p digits d0, d1, d2, d3 of (p^4 - p^2 + 1)/N, defined above bls_final_exponent
+const BLS_EXP_DIGITS: [U512; 4] = [
+    U512([
+        0xaaaa0000aaaaaaac,
+        0x33813d5206aa1800,
+        0x665a045e22ec661f,
+        0xf7a34148de09bf34,
+        0x2b688550f8cebd66,
+        0x1a0111ea397fe69a,
+        0x0000000000000000,
+        0x0000000000000000,
+    ]),
+    U512([
+        0x73ffffffffff5554,
+        0x9d586d584eacaaaa,
+        0xc49f25e1a737f5e2,
+        0x26a48d1bb889d46d,
+        0x0000000000000000,
+        0x0000000000000000,
+        0x0000000000000000,
+        0x0000000000000000,
+    ]),
+    U512([
+        0x1ea8ffff5554aaab,
+        0xb27c92a7df51e7fe,
+        0x38158e5c24aff488,
+        0x64774b84f38512bf,
+        0x4b1ba7b6434bacd7,
+        0x1a0111ea397fe69a,
+        0x0000000000000000,
+        0x0000000000000000,
+    ]),
+    U512([
+        0x8c00aaab0000aaaa,
+        0x396c8c005555e156,
+        0x0000000000000000,
+        0x0000000000000000,
+        0x0000000000000000,
+        0x0000000000000000,
+        0x0000000000000000,
+        0x0000000000000000,
+    ]),
+];
+
+#[cfg(test)]
+/// The point [tau]G2 of the trusted setup from the Ethereum KZG ceremony
+const KZG_SETUP_G2: Curve<Fp2<BLS381>> = Curve {
+    x: Fp2 {
+        re: BLS381 {
+            val: U512([
+                0xc98edada20c1def2,
+                0x087041de621000ed,
+                0xa36851477ba4c60b,
+                0x3926c911cceceac9,
+                0x734429b7b38608e2,
+                0x185cbfee53492714,
+                0x0000000000000000,
+                0x0000000000000000,
+            ]),
+        },
+        im: BLS381 {
+            val: U512([
+                0xafaaab24f3499f72,
+                0x2914e5870cb452d2,
+                0x1009a2ce615ac53d,
+                0x26187075cbfbefa8,
+                0x843bc287230af389,
+                0x15bfd7dd8cdeb128,
+                0x0000000000000000,
+                0x0000000000000000,
+            ]),
+        },
+    },
+    y: Fp2 {
+        re: BLS381 {
+            val: U512([
+                0xee689bfbbb832a99,
+                0x4ce26d105941f383,
+                0xe82451a496a9c979,
+                0x131569490e28de18,
+                0xd7d5ee8599d1fca2,
+                0x014353bdb96b626d,
+                0x0000000000000000,
+                0x0000000000000000,
+            ]),
+        },
+        im: BLS381 {
+            val: U512([
+                0x23048ef30d0a154f,
+                0x9495346f3d7ac9cd,
+                0xda5ed1ba9bfa0789,
+                0xef79de09fc63671f,
+                0x03432fcae0181b4b,
+                0x1666c54b0a325295,
+                0x0000000000000000,
+                0x0000000000000000,
+            ]),
+        },
+    },
+};
+
+/// Verifies a KZG proof that the polynomial committed to by `commitment` evaluates to `y` at `z`,
+/// where both the commitment and the proof are compressed G1 points. This amounts to checking
+///     e(commitment - [y]G1, -G2) * e(proof, [tau]G2 - [z]G2) == 1
+/// This is the native reference for the kernel's `verify_kzg_proof`.
+#[cfg(test)]
+pub(crate) fn verify_kzg_proof(commitment: [u8; 48], z: U256, y: U256, proof: [u8; 48]) -> bool {
+    let (Some(commitment), Some(proof)) = (
+        Curve::<BLS381>::from_compressed(commitment),
+        Curve::<BLS381>::from_compressed(proof),
+    ) else {
+        return false;
+    };
+    let g1 = Curve::<BLS381>::GENERATOR;
+    let g2 = Curve::<Fp2<BLS381>>::GENERATOR;
+
+    bls_pairing_check(&[
+        (commitment + -g1.mul_scalar(y), -g2),
+        (proof, KZG_SETUP_G2 + -g2.mul_scalar(z)),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use num::BigUint;
+    use plonky2::curve::bn254::Bn254;
+    use plonky2::curve::bn254_pairing as plonky2_pairing;
+    use plonky2::curve::curve_types::AffinePoint;
+    use plonky2::field::bn254_base::Bn254Base;
+    use plonky2::field::types::Field;
+    use rand::Rng;
+
+    use super::*;
+
+    fn to_base(x: BN254) -> Bn254Base {
+        let mut bytes = [0u8; 32];
+        x.val.to_little_endian(&mut bytes);
+        Bn254Base::from_noncanonical_biguint(BigUint::from_bytes_le(&bytes))
+    }
+
+    fn to_fp2(x: Fp2<BN254>) -> plonky2_pairing::Fp2 {
+        plonky2_pairing::Fp2::new(to_base(x.re), to_base(x.im))
+    }
+
+    fn to_g1(p: Curve<BN254>) -> AffinePoint<Bn254> {
+        AffinePoint::nonzero(to_base(p.x), to_base(p.y))
+    }
+
+    fn to_g2(q: Curve<Fp2<BN254>>) -> plonky2_pairing::G2Point {
+        plonky2_pairing::G2Point {
+            x: to_fp2(q.x),
+            y: to_fp2(q.y),
+            zero: false,
+        }
+    }
+
+    /// Maps `z0 + z1 w`, with `z_i = t0 + t1 v + t2 v^2` and `v = w^2`, to the flat basis.
+    fn to_fp12(x: Fp12<BN254>) -> plonky2_pairing::Fp12 {
+        let mut result = plonky2_pairing::Fp12::ZERO;
+        for (k, c) in [
+            (0, x.z0.t0),
+            (1, x.z1.t0),
+            (2, x.z0.t1),
+            (3, x.z1.t1),
+            (4, x.z0.t2),
+            (5, x.z1.t2),
+        ] {
+            let c = plonky2_pairing::Fp12::from_fp2(to_fp2(c), k);
+            for (r, c) in result.coeffs.iter_mut().zip(c.coeffs) {
+                *r += c;
+            }
+        }
+        result
+    }
+
+    /// Checks that the native pairing of `plonky2`, which its pairing circuit mirrors, agrees
+    /// with this one.
+    #[test]
+    fn test_bn_pairing_matches_plonky2() {
+        let mut rng = rand::thread_rng();
+        let p1: Curve<BN254> = rng.gen();
+        let p2: Curve<BN254> = rng.gen();
+        let q: Curve<Fp2<BN254>> = rng.gen();
+        let f = bn_miller_loop(p1, q);
+
+        assert_eq!(
+            to_fp12(bn_tangent(p1, q)),
+            plonky2_pairing::bn_tangent(to_g1(p1), to_g2(q))
+        );
+        assert_eq!(
+            to_fp12(bn_cord(p1, p2, q)),
+            plonky2_pairing::bn_chord(to_g1(p1), to_g1(p2), to_g2(q))
+        );
+        assert_eq!(
+            to_fp12(f),
+            plonky2_pairing::bn_miller_loop(to_g1(p1), to_g2(q))
+        );
+        assert_eq!(
+            to_fp12(bn_final_exponent(f)),
+            plonky2_pairing::bn_final_exponent(to_fp12(f))
+        );
+    }
+}
@@ -26,13 +26,14 @@ use plonky2::util::serialization::{
 };
 use plonky2::util::timing::TimingTree;
 use plonky2_util::log2_ceil;
-
-use crate::all_stark::{all_cross_table_lookups, AllStark, Table, NUM_TABLES};
-use crate::config::StarkConfig;
-use crate::cross_table_lookup::{
+use starky::config::StarkConfig;
+use starky::cross_table_lookup::{
     get_grand_product_challenge_set_target, verify_cross_table_lookups_circuit, CrossTableLookup,
     GrandProductChallengeSet,
 };
+use starky::stark::Stark;
+
+use crate::all_stark::{all_cross_table_lookups, AllStark, Table, NUM_TABLES};
 use crate::generation::GenerationInputs;
 use crate::get_challenges::observe_public_values_target;
 use crate::proof::{
@@ -45,7 +46,6 @@ use crate::recursive_verifier::{
     get_memory_extra_looking_products_circuit, recursive_stark_circuit, set_public_value_targets,
     PlonkWrapperCircuit, PublicInputs, StarkWrapperCircuit,
 };
-use crate::stark::Stark;
 use crate::util::h256_limbs;
 
 /// The recursion threshold. We end a chain of recursive proofs once we reach this size.
@@ -510,11 +510,11 @@ where
             .collect_vec();
 
         // Verify the CTL checks.
-        verify_cross_table_lookups_circuit::<F, D>(
+        verify_cross_table_lookups_circuit::<F, D, NUM_TABLES>(
             &mut builder,
-            all_cross_table_lookups(),
+            &all_cross_table_lookups(),
             pis.map(|p| p.ctl_zs_first),
-            extra_looking_products,
+            Some(&extra_looking_products),
             stark_config,
         );
 
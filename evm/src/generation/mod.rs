@@ -9,13 +9,13 @@ use plonky2::hash::hash_types::RichField;
 use plonky2::timed;
 use plonky2::util::timing::TimingTree;
 use serde::{Deserialize, Serialize};
+use starky::config::StarkConfig;
 use GlobalMetadata::{
     ReceiptTrieRootDigestAfter, ReceiptTrieRootDigestBefore, StateTrieRootDigestAfter,
     StateTrieRootDigestBefore, TransactionTrieRootDigestAfter, TransactionTrieRootDigestBefore,
 };
 
 use crate::all_stark::{AllStark, NUM_TABLES};
-use crate::config::StarkConfig;
 use crate::cpu::bootstrap_kernel::generate_bootstrap_kernel;
 use crate::cpu::columns::CpuColumnsView;
 use crate::cpu::kernel::aggregator::KERNEL;
@@ -46,8 +46,8 @@ pub struct GenerationInputs {
     pub gas_used_after: U256,
     pub block_bloom_after: [U256; 8],
 
-    // A None would yield an empty proof, otherwise this contains the encoding of a transaction.
-    pub signed_txn: Option<Vec<u8>>,
+    // The encodings of the transactions to execute, in order. An empty list yields an empty proof.
+    pub signed_txns: Vec<Vec<u8>>,
     // Withdrawal pairs `(addr, amount)`. At the end of the txs, `amount` is added to `addr`'s balance. See EIP-4895.
     pub withdrawals: Vec<(Address, U256)>,
     pub tries: TrieInputs,
@@ -118,12 +118,24 @@ fn apply_metadata_and_tries_memops<F: RichField + Extendable<D>, const D: usize>
             h2u(inputs.block_hashes.cur_hash),
         ),
         (GlobalMetadata::BlockGasUsed, metadata.block_gas_used),
+        (
+            GlobalMetadata::BlockBlobGasUsed,
+            metadata.block_blob_gas_used,
+        ),
+        (
+            GlobalMetadata::BlockExcessBlobGas,
+            metadata.block_excess_blob_gas,
+        ),
+        (
+            GlobalMetadata::ParentBeaconBlockRoot,
+            metadata.parent_beacon_block_root.into_uint(),
+        ),
         (GlobalMetadata::BlockGasUsedBefore, inputs.gas_used_before),
         (GlobalMetadata::BlockGasUsedAfter, inputs.gas_used_after),
         (GlobalMetadata::TxnNumberBefore, inputs.txn_number_before),
         (
             GlobalMetadata::TxnNumberAfter,
-            inputs.txn_number_before + if inputs.signed_txn.is_some() { 1 } else { 0 },
+            inputs.txn_number_before + inputs.signed_txns.len(),
         ),
         (
             GlobalMetadata::StateTrieRootDigestBefore,
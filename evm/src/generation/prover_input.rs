@@ -8,15 +8,17 @@ use num_bigint::BigUint;
 use plonky2::field::types::Field;
 use serde::{Deserialize, Serialize};
 
-use crate::extension_tower::{FieldExt, Fp12, BLS381, BN254};
+use crate::extension_tower::{FieldExt, Fp12, Stack, BLS381, BLS_BASE, BN254};
 use crate::generation::prover_input::EvmField::{
     Bls381Base, Bls381Scalar, Bn254Base, Bn254Scalar, Secp256k1Base, Secp256k1Scalar,
 };
 use crate::generation::prover_input::FieldOp::{Inverse, Sqrt};
 use crate::generation::state::GenerationState;
 use crate::memory::segments::Segment;
-use crate::memory::segments::Segment::BnPairing;
-use crate::util::{biguint_to_mem_vec, mem_vec_to_biguint, u256_to_usize};
+use crate::memory::segments::Segment::{BnPairing, KernelGeneral};
+use crate::util::{
+    biguint_to_mem_vec, biguint_to_u256, mem_vec_to_biguint, u256_to_biguint, u256_to_usize,
+};
 use crate::witness::errors::ProgramError;
 use crate::witness::errors::ProverInputError::*;
 use crate::witness::util::{current_context_peek, stack_peek};
@@ -35,7 +37,7 @@ impl From<Vec<String>> for ProverInputFn {
 impl<F: Field> GenerationState<F> {
     pub(crate) fn prover_input(&mut self, input_fn: &ProverInputFn) -> Result<U256, ProgramError> {
         match input_fn.0[0].as_str() {
-            "no_txn" => self.no_txn(),
+            "end_of_txns" => self.run_end_of_txns(),
             "ff" => self.run_ff(input_fn),
             "sf" => self.run_sf(input_fn),
             "ffe" => self.run_ffe(input_fn),
@@ -49,8 +51,14 @@ impl<F: Field> GenerationState<F> {
         }
     }
 
-    fn no_txn(&mut self) -> Result<U256, ProgramError> {
-        Ok(U256::from(self.inputs.signed_txn.is_none() as u8))
+    /// Returns 1 if all transactions have been executed. Otherwise, returns 0 and moves on to
+    /// the next transaction.
+    fn run_end_of_txns(&mut self) -> Result<U256, ProgramError> {
+        let end = self.next_txn_index == self.inputs.signed_txns.len();
+        if !end {
+            self.next_txn_index += 1;
+        }
+        Ok(U256::from(end as u8))
     }
 
     /// Finite field operations.
@@ -67,21 +75,23 @@ impl<F: Field> GenerationState<F> {
     fn run_sf(&self, input_fn: &ProverInputFn) -> Result<U256, ProgramError> {
         let field = EvmField::from_str(input_fn.0[1].as_str())
             .map_err(|_| ProgramError::ProverInputError(InvalidFunction))?;
-        let inputs: [U256; 4] = match field {
-            Bls381Base => (0..4)
-                .map(|i| stack_peek(self, i))
-                .collect::<Result<Vec<U256>, _>>()?
-                .try_into()
-                .unwrap(),
-            _ => todo!(),
+        // The outputs of an operation are pushed one after the other on top of its inputs, so
+        // `depth` is the number of outputs already pushed.
+        let inputs = |depth: usize, n: usize| -> Result<Vec<U256>, ProgramError> {
+            match field {
+                Bls381Base => (depth..depth + n).map(|i| stack_peek(self, i)).collect(),
+                _ => todo!(),
+            }
         };
         let res = match input_fn.0[2].as_str() {
-            "add_lo" => field.add_lo(inputs),
-            "add_hi" => field.add_hi(inputs),
-            "mul_lo" => field.mul_lo(inputs),
-            "mul_hi" => field.mul_hi(inputs),
-            "sub_lo" => field.sub_lo(inputs),
-            "sub_hi" => field.sub_hi(inputs),
+            "mul_hi" => field.mul_hi(inputs(0, 4)?.try_into().unwrap()),
+            "mul_lo" => field.mul_lo(inputs(1, 4)?.try_into().unwrap()),
+            "mul_quotient_hi" => field.mul_quotient_hi(inputs(2, 4)?.try_into().unwrap()),
+            "mul_quotient_lo" => field.mul_quotient_lo(inputs(3, 4)?.try_into().unwrap()),
+            "inv_hi" => field.inv_hi(inputs(0, 2)?.try_into().unwrap()),
+            "inv_lo" => field.inv_lo(inputs(1, 2)?.try_into().unwrap()),
+            "sqrt_hi" => field.sqrt_fp381(inputs(0, 2)?.try_into().unwrap())?.hi(),
+            "sqrt_lo" => field.sqrt_fp381(inputs(1, 2)?.try_into().unwrap())?.lo(),
             _ => return Err(ProgramError::ProverInputError(InvalidFunction)),
         };
 
@@ -99,13 +109,25 @@ impl<F: Field> GenerationState<F> {
             .unwrap()
             .parse::<usize>()
             .unwrap();
-        let ptr = stack_peek(self, 11 - n).map(u256_to_usize)??;
 
-        let f: [U256; 12] = match field {
-            Bn254Base => std::array::from_fn(|i| current_context_peek(self, BnPairing, ptr + i)),
+        match field {
+            Bn254Base => {
+                let ptr = stack_peek(self, 11 - n).map(u256_to_usize)??;
+                let f: [U256; 12] =
+                    std::array::from_fn(|i| current_context_peek(self, BnPairing, ptr + i));
+                Ok(field.field_extension_inverse(n, f))
+            }
+            // The BLS12-381 inverse is stored component by component, with the pointer to the
+            // input staying on top of the stack.
+            Bls381Base => {
+                let ptr = stack_peek(self, 0).map(u256_to_usize)??;
+                let f: Vec<U256> = (0..24)
+                    .map(|i| current_context_peek(self, KernelGeneral, ptr + i))
+                    .collect();
+                Ok(Fp12::<BLS381>::from_stack(&f).inv().to_stack()[n])
+            }
             _ => todo!(),
-        };
-        Ok(field.field_extension_inverse(n, f))
+        }
     }
 
     /// MPT data.
@@ -316,24 +338,8 @@ impl EvmField {
         modexp(x, q, n)
     }
 
-    fn add_lo(&self, inputs: [U256; 4]) -> U256 {
-        let [y1, x0, x1, y0] = inputs;
-        let x = U512::from(x0) + (U512::from(x1) << 256);
-        let y = U512::from(y0) + (U512::from(y1) << 256);
-        let z = BLS381 { val: x } + BLS381 { val: y };
-        z.lo()
-    }
-
-    fn add_hi(&self, inputs: [U256; 4]) -> U256 {
-        let [x0, x1, y0, y1] = inputs;
-        let x = U512::from(x0) + (U512::from(x1) << 256);
-        let y = U512::from(y0) + (U512::from(y1) << 256);
-        let z = BLS381 { val: x } + BLS381 { val: y };
-        z.hi()
-    }
-
     fn mul_lo(&self, inputs: [U256; 4]) -> U256 {
-        let [y1, x0, x1, y0] = inputs;
+        let [x0, x1, y0, y1] = inputs;
         let x = U512::from(x0) + (U512::from(x1) << 256);
         let y = U512::from(y0) + (U512::from(y1) << 256);
         let z = BLS381 { val: x } * BLS381 { val: y };
@@ -348,20 +354,47 @@ impl EvmField {
         z.hi()
     }
 
-    fn sub_lo(&self, inputs: [U256; 4]) -> U256 {
-        let [y1, x0, x1, y0] = inputs;
+    /// The quotient of the integer product `x * y` by the BLS12-381 base field order.
+    fn mul_quotient(&self, inputs: [U256; 4]) -> BigUint {
+        let [x0, x1, y0, y1] = inputs;
+        let x = u256_to_biguint(x0) + (u256_to_biguint(x1) << 256);
+        let y = u256_to_biguint(y0) + (u256_to_biguint(y1) << 256);
+        let mut p = [0u8; 64];
+        BLS_BASE.to_little_endian(&mut p);
+        x * y / BigUint::from_bytes_le(&p)
+    }
+
+    fn mul_quotient_lo(&self, inputs: [U256; 4]) -> U256 {
+        let q = self.mul_quotient(inputs);
+        biguint_to_u256(q % (BigUint::from(1u8) << 256))
+    }
+
+    fn mul_quotient_hi(&self, inputs: [U256; 4]) -> U256 {
+        let q = self.mul_quotient(inputs);
+        biguint_to_u256(q >> 256)
+    }
+
+    fn inv_lo(&self, inputs: [U256; 2]) -> U256 {
+        let [x0, x1] = inputs;
         let x = U512::from(x0) + (U512::from(x1) << 256);
-        let y = U512::from(y0) + (U512::from(y1) << 256);
-        let z = BLS381 { val: x } - BLS381 { val: y };
-        z.lo()
+        BLS381 { val: x }.inv().lo()
     }
 
-    fn sub_hi(&self, inputs: [U256; 4]) -> U256 {
-        let [x0, x1, y0, y1] = inputs;
+    fn inv_hi(&self, inputs: [U256; 2]) -> U256 {
+        let [x0, x1] = inputs;
         let x = U512::from(x0) + (U512::from(x1) << 256);
-        let y = U512::from(y0) + (U512::from(y1) << 256);
-        let z = BLS381 { val: x } - BLS381 { val: y };
-        z.hi()
+        BLS381 { val: x }.inv().hi()
+    }
+
+    /// A square root of x if x is a square, and a square root of -x otherwise.
+    fn sqrt_fp381(&self, inputs: [U256; 2]) -> Result<BLS381, ProgramError> {
+        let [x0, x1] = inputs;
+        let x = BLS381 {
+            val: U512::from(x0) + (U512::from(x1) << 256),
+        };
+        x.sqrt()
+            .or_else(|| (-x).sqrt())
+            .ok_or(ProgramError::ProverInputError(InvalidInput))
     }
 
     fn field_extension_inverse(&self, n: usize, f: [U256; 12]) -> U256 {
@@ -37,6 +37,9 @@ pub(crate) struct GenerationState<F: Field> {
     /// via `pop()`.
     pub(crate) rlp_prover_inputs: Vec<U256>,
 
+    /// The index of the next transaction of `inputs.signed_txns` to be executed.
+    pub(crate) next_txn_index: usize,
+
     pub(crate) withdrawal_prover_inputs: Vec<U256>,
 
     /// The state trie only stores state keys, which are hashes of addresses, but sometimes it is
@@ -52,7 +55,7 @@ pub(crate) struct GenerationState<F: Field> {
 
 impl<F: Field> GenerationState<F> {
     pub(crate) fn new(inputs: GenerationInputs, kernel_code: &[u8]) -> Result<Self, ProgramError> {
-        log::debug!("Input signed_txn: {:?}", &inputs.signed_txn);
+        log::debug!("Input signed_txns: {:?}", &inputs.signed_txns);
         log::debug!("Input state_trie: {:?}", &inputs.tries.state_trie);
         log::debug!(
             "Input transactions_trie: {:?}",
@@ -62,8 +65,7 @@ impl<F: Field> GenerationState<F> {
         log::debug!("Input storage_tries: {:?}", &inputs.tries.storage_tries);
         log::debug!("Input contract_code: {:?}", &inputs.contract_code);
         let mpt_prover_inputs = all_mpt_prover_inputs_reversed(&inputs.tries)?;
-        let rlp_prover_inputs =
-            all_rlp_prover_inputs_reversed(inputs.signed_txn.as_ref().unwrap_or(&vec![]));
+        let rlp_prover_inputs = all_rlp_prover_inputs_reversed(&inputs.signed_txns);
         let withdrawal_prover_inputs = all_withdrawals_prover_inputs_reversed(&inputs.withdrawals);
         let bignum_modmul_result_limbs = Vec::new();
 
@@ -74,6 +76,7 @@ impl<F: Field> GenerationState<F> {
             traces: Traces::default(),
             mpt_prover_inputs,
             rlp_prover_inputs,
+            next_txn_index: 0,
             withdrawal_prover_inputs,
             state_key_to_address: HashMap::new(),
             bignum_modmul_result_limbs,
@@ -5,9 +5,9 @@ use plonky2::hash::hash_types::RichField;
 use plonky2::iop::challenger::{Challenger, RecursiveChallenger};
 use plonky2::plonk::circuit_builder::CircuitBuilder;
 use plonky2::plonk::config::{AlgebraicHasher, GenericConfig};
+use starky::config::StarkConfig;
+use starky::cross_table_lookup::get_grand_product_challenge_set;
 
-use crate::config::StarkConfig;
-use crate::cross_table_lookup::get_grand_product_challenge_set;
 use crate::proof::*;
 use crate::util::{h256_limbs, u256_limbs, u256_to_u32, u256_to_u64};
 use crate::witness::errors::ProgramError;
@@ -74,6 +74,13 @@ fn observe_block_metadata<
     for i in 0..8 {
         challenger.observe_elements(&u256_limbs(block_metadata.block_bloom[i]));
     }
+    let blob_gas_used = u256_to_u64(block_metadata.block_blob_gas_used)?;
+    challenger.observe_element(blob_gas_used.0);
+    challenger.observe_element(blob_gas_used.1);
+    let excess_blob_gas = u256_to_u64(block_metadata.block_excess_blob_gas)?;
+    challenger.observe_element(excess_blob_gas.0);
+    challenger.observe_element(excess_blob_gas.1);
+    challenger.observe_elements(&h256_limbs::<F>(block_metadata.parent_beacon_block_root));
 
     Ok(())
 }
@@ -98,6 +105,9 @@ fn observe_block_metadata_target<
     challenger.observe_elements(&block_metadata.block_base_fee);
     challenger.observe_elements(&block_metadata.block_gas_used);
     challenger.observe_elements(&block_metadata.block_bloom);
+    challenger.observe_elements(&block_metadata.block_blob_gas_used);
+    challenger.observe_elements(&block_metadata.block_excess_blob_gas);
+    challenger.observe_elements(&block_metadata.parent_beacon_block_root);
 }
 
 fn observe_extra_block_data<
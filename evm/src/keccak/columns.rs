@@ -1,6 +1,6 @@
 use plonky2::field::types::Field;
+use starky::cross_table_lookup::Column;
 
-use crate::cross_table_lookup::Column;
 use crate::keccak::keccak_stark::{NUM_INPUTS, NUM_ROUNDS};
 
 /// A register which is set to 1 if we are in the `i`th round, otherwise 0.
@@ -9,12 +9,8 @@
 pub mod all_stark;
 pub mod arithmetic;
 pub mod byte_packing;
-pub mod config;
-pub mod constraint_consumer;
 pub mod cpu;
-pub mod cross_table_lookup;
 pub mod curve_pairings;
-pub mod evaluation_frame;
 pub mod extension_tower;
 pub mod fixed_recursive_verifier;
 pub mod generation;
@@ -22,20 +18,15 @@ mod get_challenges;
 pub mod keccak;
 pub mod keccak_sponge;
 pub mod logic;
-pub mod lookup;
 pub mod memory;
 pub mod proof;
 pub mod prover;
 pub mod recursive_verifier;
-pub mod stark;
 pub mod util;
 pub mod vanishing_poly;
 pub mod verifier;
 pub mod witness;
 
-#[cfg(test)]
-mod stark_testing;
-
 use eth_trie_utils::partial_trie::HashedPartialTrie;
 // Set up Jemalloc
 #[cfg(not(target_env = "msvc"))]
@@ -69,10 +69,14 @@ pub enum Segment {
     ContextCheckpoints = 35,
     /// List of 256 previous block hashes.
     BlockHashes = 36,
+    /// List of (address, slot, value) triples written with `TSTORE` in the current transaction.
+    TransientStorage = 37,
+    /// The versioned hashes of the blobs of the current type-3 transaction.
+    TxnBlobVersionedHashes = 38,
 }
 
 impl Segment {
-    pub(crate) const COUNT: usize = 37;
+    pub(crate) const COUNT: usize = 39;
 
     pub(crate) fn all() -> [Self; Self::COUNT] {
         [
@@ -113,6 +117,8 @@ impl Segment {
             Self::TouchedAddresses,
             Self::ContextCheckpoints,
             Self::BlockHashes,
+            Self::TransientStorage,
+            Self::TxnBlobVersionedHashes,
         ]
     }
 
@@ -156,6 +162,8 @@ impl Segment {
             Segment::TouchedAddresses => "SEGMENT_TOUCHED_ADDRESSES",
             Segment::ContextCheckpoints => "SEGMENT_CONTEXT_CHECKPOINTS",
             Segment::BlockHashes => "SEGMENT_BLOCK_HASHES",
+            Segment::TransientStorage => "SEGMENT_TRANSIENT_STORAGE",
+            Segment::TxnBlobVersionedHashes => "SEGMENT_TXN_BLOB_VERSIONED_HASHES",
         }
     }
 
@@ -198,6 +206,8 @@ impl Segment {
             Segment::TouchedAddresses => 256,
             Segment::ContextCheckpoints => 256,
             Segment::BlockHashes => 256,
+            Segment::TransientStorage => 256,
+            Segment::TxnBlobVersionedHashes => 256,
         }
     }
 }
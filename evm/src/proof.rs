@@ -15,10 +15,10 @@ use plonky2::plonk::config::{GenericConfig, Hasher};
 use plonky2::util::serialization::{Buffer, IoResult, Read, Write};
 use plonky2_maybe_rayon::*;
 use serde::{Deserialize, Serialize};
+use starky::config::StarkConfig;
+use starky::cross_table_lookup::GrandProductChallengeSet;
 
 use crate::all_stark::NUM_TABLES;
-use crate::config::StarkConfig;
-use crate::cross_table_lookup::GrandProductChallengeSet;
 
 /// A STARK proof for each table, plus some metadata used to create recursive wrapper proofs.
 #[derive(Debug, Clone)]
@@ -126,6 +126,13 @@ pub struct BlockMetadata {
     /// The block bloom of this block, represented as the consecutive
     /// 32-byte chunks of a block's final bloom filter string.
     pub block_bloom: [U256; 8],
+    /// The total blob gas used in this block. It must fit in a `u64`.
+    pub block_blob_gas_used: U256,
+    /// The excess blob gas of this block, from which the blob base fee
+    /// is derived. It must fit in a `u64`.
+    pub block_excess_blob_gas: U256,
+    /// The root of the parent beacon block (EIP-4788).
+    pub parent_beacon_block_root: H256,
 }
 
 /// Additional block data that are specific to the local transaction being proven,
@@ -203,6 +210,9 @@ impl PublicValuesTarget {
             block_base_fee,
             block_gas_used,
             block_bloom,
+            block_blob_gas_used,
+            block_excess_blob_gas,
+            parent_beacon_block_root,
         } = self.block_metadata;
 
         buffer.write_target_array(&block_beneficiary)?;
@@ -215,6 +225,9 @@ impl PublicValuesTarget {
         buffer.write_target_array(&block_base_fee)?;
         buffer.write_target_array(&block_gas_used)?;
         buffer.write_target_array(&block_bloom)?;
+        buffer.write_target_array(&block_blob_gas_used)?;
+        buffer.write_target_array(&block_excess_blob_gas)?;
+        buffer.write_target_array(&parent_beacon_block_root)?;
 
         let BlockHashesTarget {
             prev_hashes,
@@ -268,6 +281,9 @@ impl PublicValuesTarget {
             block_base_fee: buffer.read_target_array()?,
             block_gas_used: buffer.read_target_array()?,
             block_bloom: buffer.read_target_array()?,
+            block_blob_gas_used: buffer.read_target_array()?,
+            block_excess_blob_gas: buffer.read_target_array()?,
+            parent_beacon_block_root: buffer.read_target_array()?,
         };
 
         let block_hashes = BlockHashesTarget {
@@ -470,11 +486,17 @@ pub(crate) struct BlockMetadataTarget {
     pub(crate) block_gas_used: [Target; 2],
     /// `Target`s for the block bloom of this block.
     pub(crate) block_bloom: [Target; 64],
+    /// `Target`s for the blob gas used of this block.
+    pub(crate) block_blob_gas_used: [Target; 2],
+    /// `Target`s for the excess blob gas of this block.
+    pub(crate) block_excess_blob_gas: [Target; 2],
+    /// `Target`s for the parent beacon block root of this block.
+    pub(crate) parent_beacon_block_root: [Target; 8],
 }
 
 impl BlockMetadataTarget {
     /// Number of `Target`s required for the block metadata.
-    pub const SIZE: usize = 87;
+    pub const SIZE: usize = 99;
 
     /// Extracts block metadata `Target`s from the provided public input `Target`s.
     /// The provided `pis` should start with the block metadata.
@@ -489,6 +511,9 @@ impl BlockMetadataTarget {
         let block_base_fee = pis[19..21].try_into().unwrap();
         let block_gas_used = pis[21..23].try_into().unwrap();
         let block_bloom = pis[23..87].try_into().unwrap();
+        let block_blob_gas_used = pis[87..89].try_into().unwrap();
+        let block_excess_blob_gas = pis[89..91].try_into().unwrap();
+        let parent_beacon_block_root = pis[91..99].try_into().unwrap();
 
         Self {
             block_beneficiary,
@@ -501,6 +526,9 @@ impl BlockMetadataTarget {
             block_base_fee,
             block_gas_used,
             block_bloom,
+            block_blob_gas_used,
+            block_excess_blob_gas,
+            parent_beacon_block_root,
         }
     }
 
@@ -539,6 +567,27 @@ impl BlockMetadataTarget {
             block_bloom: core::array::from_fn(|i| {
                 builder.select(condition, bm0.block_bloom[i], bm1.block_bloom[i])
             }),
+            block_blob_gas_used: core::array::from_fn(|i| {
+                builder.select(
+                    condition,
+                    bm0.block_blob_gas_used[i],
+                    bm1.block_blob_gas_used[i],
+                )
+            }),
+            block_excess_blob_gas: core::array::from_fn(|i| {
+                builder.select(
+                    condition,
+                    bm0.block_excess_blob_gas[i],
+                    bm1.block_excess_blob_gas[i],
+                )
+            }),
+            parent_beacon_block_root: core::array::from_fn(|i| {
+                builder.select(
+                    condition,
+                    bm0.parent_beacon_block_root[i],
+                    bm1.parent_beacon_block_root[i],
+                )
+            }),
         }
     }
 
@@ -570,6 +619,18 @@ impl BlockMetadataTarget {
         for i in 0..64 {
             builder.connect(bm0.block_bloom[i], bm1.block_bloom[i])
         }
+        for i in 0..2 {
+            builder.connect(bm0.block_blob_gas_used[i], bm1.block_blob_gas_used[i])
+        }
+        for i in 0..2 {
+            builder.connect(bm0.block_excess_blob_gas[i], bm1.block_excess_blob_gas[i])
+        }
+        for i in 0..8 {
+            builder.connect(
+                bm0.parent_beacon_block_root[i],
+                bm1.parent_beacon_block_root[i],
+            )
+        }
     }
 }
 
@@ -16,27 +16,28 @@ use plonky2::util::timing::TimingTree;
 use plonky2::util::transpose;
 use plonky2_maybe_rayon::*;
 use plonky2_util::{log2_ceil, log2_strict};
-
-use crate::all_stark::{AllStark, Table, NUM_TABLES};
-use crate::config::StarkConfig;
-use crate::constraint_consumer::ConstraintConsumer;
-use crate::cpu::kernel::aggregator::KERNEL;
-use crate::cross_table_lookup::{
+use starky::config::StarkConfig;
+use starky::constraint_consumer::ConstraintConsumer;
+use starky::cross_table_lookup::{
     cross_table_lookup_data, get_grand_product_challenge_set, CtlCheckVars, CtlData,
     GrandProductChallengeSet,
 };
-use crate::evaluation_frame::StarkEvaluationFrame;
+use starky::evaluation_frame::StarkEvaluationFrame;
+use starky::lookup::{lookup_helper_columns, Lookup, LookupCheckVars};
+use starky::stark::Stark;
+#[cfg(test)]
+use {
+    crate::verifier::testutils::get_memory_extra_looking_values,
+    starky::cross_table_lookup::debug_utils::check_ctls, std::collections::BTreeMap,
+};
+
+use crate::all_stark::{AllStark, Table, NUM_TABLES};
+use crate::cpu::kernel::aggregator::KERNEL;
 use crate::generation::outputs::GenerationOutputs;
 use crate::generation::{generate_traces, GenerationInputs};
 use crate::get_challenges::observe_public_values;
-use crate::lookup::{lookup_helper_columns, Lookup, LookupCheckVars};
 use crate::proof::{AllProof, PublicValues, StarkOpeningSet, StarkProof, StarkProofWithMetadata};
-use crate::stark::Stark;
 use crate::vanishing_poly::eval_vanishing_poly;
-#[cfg(test)]
-use crate::{
-    cross_table_lookup::testutils::check_ctls, verifier::testutils::get_memory_extra_looking_values,
-};
 
 /// Generate traces, then create all STARK proofs.
 pub fn prove<F, C, const D: usize>(
@@ -135,7 +136,7 @@ where
     let ctl_data_per_table = timed!(
         timing,
         "compute CTL data",
-        cross_table_lookup_data::<F, D>(
+        cross_table_lookup_data::<F, D, NUM_TABLES>(
             &trace_poly_values,
             &all_stark.cross_table_lookups,
             &ctl_challenges,
@@ -162,7 +163,10 @@ where
         check_ctls(
             &trace_poly_values,
             &all_stark.cross_table_lookups,
-            &get_memory_extra_looking_values(&public_values),
+            &BTreeMap::from([(
+                Table::Memory as usize,
+                get_memory_extra_looking_values(&public_values),
+            )]),
         );
     }
 
@@ -588,6 +592,7 @@ where
             let vars = S::EvaluationFrame::from_values(
                 &get_trace_values_packed(i_start),
                 &get_trace_values_packed(i_next_start),
+                &[],
             );
             // Get the local and next row evaluations for the permutation argument, as well as the associated challenges.
             let lookup_vars = lookup_challenges.map(|challenges| LookupCheckVars {
@@ -720,6 +725,7 @@ fn check_constraints<'a, F, C, S, const D: usize>(
             let vars = S::EvaluationFrame::from_values(
                 &trace_subgroup_evals[i],
                 &trace_subgroup_evals[i_next],
+                &[],
             );
             // Get the local and next row evaluations for the current STARK's permutation argument.
             let lookup_vars = lookup_challenges.map(|challenges| LookupCheckVars {
@@ -24,16 +24,17 @@ use plonky2::util::serialization::{
 };
 use plonky2::with_context;
 use plonky2_util::log2_ceil;
+use starky::config::StarkConfig;
+use starky::constraint_consumer::RecursiveConstraintConsumer;
+use starky::cross_table_lookup::{
+    CrossTableLookup, CtlCheckVarsTarget, GrandProductChallenge, GrandProductChallengeSet,
+};
+use starky::evaluation_frame::StarkEvaluationFrame;
+use starky::lookup::LookupCheckVarsTarget;
+use starky::stark::Stark;
 
 use crate::all_stark::Table;
-use crate::config::StarkConfig;
-use crate::constraint_consumer::RecursiveConstraintConsumer;
 use crate::cpu::kernel::constants::global_metadata::GlobalMetadata;
-use crate::cross_table_lookup::{
-    CrossTableLookup, CtlCheckVarsTarget, GrandProductChallenge, GrandProductChallengeSet,
-};
-use crate::evaluation_frame::StarkEvaluationFrame;
-use crate::lookup::LookupCheckVarsTarget;
 use crate::memory::segments::Segment;
 use crate::memory::VALUE_LIMBS;
 use crate::proof::{
@@ -42,7 +43,6 @@ use crate::proof::{
     StarkProofChallengesTarget, StarkProofTarget, StarkProofWithMetadata, TrieRoots,
     TrieRootsTarget,
 };
-use crate::stark::Stark;
 use crate::util::{h256_limbs, u256_limbs, u256_to_u32, u256_to_u64};
 use crate::vanishing_poly::eval_vanishing_poly_circuit;
 use crate::witness::errors::ProgramError;
@@ -227,8 +227,11 @@ where
     let zero_target = builder.zero();
 
     let num_lookup_columns = stark.num_lookup_helper_columns(inner_config);
-    let num_ctl_zs =
-        CrossTableLookup::num_ctl_zs(cross_table_lookups, table, inner_config.num_challenges);
+    let num_ctl_zs = CrossTableLookup::num_ctl_zs(
+        cross_table_lookups,
+        table as usize,
+        inner_config.num_challenges,
+    );
     let proof_target =
         add_virtual_stark_proof(&mut builder, stark, inner_config, degree_bits, num_ctl_zs);
     builder.register_public_inputs(
@@ -249,12 +252,12 @@ where
             .collect(),
     };
 
-    let ctl_vars = CtlCheckVarsTarget::from_proof(
-        table,
-        &proof_target,
+    let ctl_vars = CtlCheckVarsTarget::from_ctl_zs_openings(
+        table as usize,
+        &proof_target.openings.auxiliary_polys[num_lookup_columns..],
+        &proof_target.openings.auxiliary_polys_next[num_lookup_columns..],
         cross_table_lookups,
         &ctl_challenges_target,
-        num_lookup_columns,
     );
 
     let init_challenger_state_target =
@@ -336,7 +339,7 @@ fn verify_stark_proof_with_challenges_circuit<
         ctl_zs_first,
         quotient_polys,
     } = &proof.openings;
-    let vars = S::EvaluationFrameTarget::from_values(local_values, next_values);
+    let vars = S::EvaluationFrameTarget::from_values(local_values, next_values, &[]);
 
     let degree_bits = proof.recover_degree_bits(inner_config);
     let zeta_pow_deg = builder.exp_power_of_2_extension(challenges.stark_zeta, degree_bits);
@@ -458,9 +461,9 @@ pub(crate) fn get_memory_extra_looking_products_circuit<
     ];
 
     // This contains the `block_beneficiary`, `block_random`, `block_base_fee`,
-    // `block_gaslimit`, `block_gas_used` as well as `cur_hash`, `gas_used_before`
-    // and `gas_used_after`.
-    let block_fields_arrays: [(usize, &[Target]); 8] = [
+    // `block_gaslimit`, `block_gas_used`, `block_blob_gas_used`, `block_excess_blob_gas`,
+    // `parent_beacon_block_root` as well as `cur_hash`, `gas_used_before` and `gas_used_after`.
+    let block_fields_arrays: [(usize, &[Target]); 11] = [
         (
             GlobalMetadata::BlockBeneficiary as usize,
             &public_values.block_metadata.block_beneficiary,
@@ -481,6 +484,18 @@ pub(crate) fn get_memory_extra_looking_products_circuit<
             GlobalMetadata::BlockGasUsed as usize,
             &public_values.block_metadata.block_gas_used,
         ),
+        (
+            GlobalMetadata::BlockBlobGasUsed as usize,
+            &public_values.block_metadata.block_blob_gas_used,
+        ),
+        (
+            GlobalMetadata::BlockExcessBlobGas as usize,
+            &public_values.block_metadata.block_excess_blob_gas,
+        ),
+        (
+            GlobalMetadata::ParentBeaconBlockRoot as usize,
+            &public_values.block_metadata.parent_beacon_block_root,
+        ),
         (
             GlobalMetadata::BlockCurrentHash as usize,
             &public_values.block_hashes.cur_hash,
@@ -709,6 +724,9 @@ pub(crate) fn add_virtual_block_metadata<F: RichField + Extendable<D>, const D:
     let block_base_fee = builder.add_virtual_public_input_arr();
     let block_gas_used = builder.add_virtual_public_input_arr();
     let block_bloom = builder.add_virtual_public_input_arr();
+    let block_blob_gas_used = builder.add_virtual_public_input_arr();
+    let block_excess_blob_gas = builder.add_virtual_public_input_arr();
+    let parent_beacon_block_root = builder.add_virtual_public_input_arr();
     BlockMetadataTarget {
         block_beneficiary,
         block_timestamp,
@@ -720,6 +738,9 @@ pub(crate) fn add_virtual_block_metadata<F: RichField + Extendable<D>, const D:
         block_base_fee,
         block_gas_used,
         block_bloom,
+        block_blob_gas_used,
+        block_excess_blob_gas,
+        parent_beacon_block_root,
     }
 }
 
@@ -974,6 +995,30 @@ where
         limbs.copy_from_slice(&u256_limbs(block_metadata.block_bloom[i]));
     }
     witness.set_target_arr(&block_metadata_target.block_bloom, &block_bloom_limbs);
+    // Blob gas used fits in 2 limbs
+    let blob_gas_used = u256_to_u64(block_metadata.block_blob_gas_used)?;
+    witness.set_target(
+        block_metadata_target.block_blob_gas_used[0],
+        blob_gas_used.0,
+    );
+    witness.set_target(
+        block_metadata_target.block_blob_gas_used[1],
+        blob_gas_used.1,
+    );
+    // Excess blob gas fits in 2 limbs
+    let excess_blob_gas = u256_to_u64(block_metadata.block_excess_blob_gas)?;
+    witness.set_target(
+        block_metadata_target.block_excess_blob_gas[0],
+        excess_blob_gas.0,
+    );
+    witness.set_target(
+        block_metadata_target.block_excess_blob_gas[1],
+        excess_blob_gas.1,
+    );
+    witness.set_target_arr(
+        &block_metadata_target.parent_beacon_block_root,
+        &h256_limbs(block_metadata.parent_beacon_block_root),
+    );
 
     Ok(())
 }
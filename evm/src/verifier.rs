@@ -9,22 +9,22 @@ use plonky2::fri::verifier::verify_fri_proof;
 use plonky2::hash::hash_types::RichField;
 use plonky2::plonk::config::GenericConfig;
 use plonky2::plonk::plonk_common::reduce_with_powers;
+use starky::config::StarkConfig;
+use starky::constraint_consumer::ConstraintConsumer;
+use starky::cross_table_lookup::{
+    verify_cross_table_lookups, CtlCheckVars, GrandProductChallenge, GrandProductChallengeSet,
+};
+use starky::evaluation_frame::StarkEvaluationFrame;
+use starky::lookup::LookupCheckVars;
+use starky::stark::Stark;
 
 use crate::all_stark::{AllStark, Table, NUM_TABLES};
-use crate::config::StarkConfig;
-use crate::constraint_consumer::ConstraintConsumer;
 use crate::cpu::kernel::constants::global_metadata::GlobalMetadata;
-use crate::cross_table_lookup::{
-    verify_cross_table_lookups, CtlCheckVars, GrandProductChallenge, GrandProductChallengeSet,
-};
-use crate::evaluation_frame::StarkEvaluationFrame;
-use crate::lookup::LookupCheckVars;
 use crate::memory::segments::Segment;
 use crate::memory::VALUE_LIMBS;
 use crate::proof::{
     AllProof, AllProofChallenges, PublicValues, StarkOpeningSet, StarkProof, StarkProofChallenges,
 };
-use crate::stark::Stark;
 use crate::util::h2u;
 use crate::vanishing_poly::eval_vanishing_poly;
 
@@ -55,12 +55,15 @@ where
         cross_table_lookups,
     } = all_stark;
 
-    let ctl_vars_per_table = CtlCheckVars::from_proofs(
-        &all_proof.stark_proofs,
-        cross_table_lookups,
-        &ctl_challenges,
-        &num_lookup_columns,
-    );
+    let ctl_zs: [_; NUM_TABLES] = core::array::from_fn(|i| {
+        let openings = &all_proof.stark_proofs[i].proof.openings;
+        (
+            &openings.auxiliary_polys[num_lookup_columns[i]..],
+            &openings.auxiliary_polys_next[num_lookup_columns[i]..],
+        )
+    });
+    let ctl_vars_per_table =
+        CtlCheckVars::from_ctl_zs_openings(ctl_zs, cross_table_lookups, &ctl_challenges);
 
     verify_stark_proof_with_challenges(
         arithmetic_stark,
@@ -130,12 +133,12 @@ where
         .map(|i| get_memory_extra_looking_products(&public_values, ctl_challenges.challenges[i]))
         .collect_vec();
 
-    verify_cross_table_lookups::<F, D>(
+    verify_cross_table_lookups::<F, D, NUM_TABLES>(
         cross_table_lookups,
         all_proof
             .stark_proofs
             .map(|p| p.proof.openings.ctl_zs_first),
-        extra_looking_products,
+        Some(&extra_looking_products),
         config,
     )
 }
@@ -194,6 +197,21 @@ where
             GlobalMetadata::BlockGasUsed,
             public_values.block_metadata.block_gas_used,
         ),
+        (
+            GlobalMetadata::BlockBlobGasUsed,
+            public_values.block_metadata.block_blob_gas_used,
+        ),
+        (
+            GlobalMetadata::BlockExcessBlobGas,
+            public_values.block_metadata.block_excess_blob_gas,
+        ),
+        (
+            GlobalMetadata::ParentBeaconBlockRoot,
+            public_values
+                .block_metadata
+                .parent_beacon_block_root
+                .into_uint(),
+        ),
         (
             GlobalMetadata::TxnNumberBefore,
             public_values.extra_block_data.txn_number_before,
@@ -312,7 +330,7 @@ pub(crate) fn verify_stark_proof_with_challenges<
         ctl_zs_first,
         quotient_polys,
     } = &proof.openings;
-    let vars = S::EvaluationFrame::from_values(local_values, next_values);
+    let vars = S::EvaluationFrame::from_values(local_values, next_values, &[]);
 
     let degree_bits = proof.recover_degree_bits(config);
     let (l_0, l_last) = eval_l_0_and_l_last(degree_bits, challenges.stark_zeta);
@@ -509,6 +527,21 @@ pub(crate) mod testutils {
                 GlobalMetadata::BlockGasUsed,
                 public_values.block_metadata.block_gas_used,
             ),
+            (
+                GlobalMetadata::BlockBlobGasUsed,
+                public_values.block_metadata.block_blob_gas_used,
+            ),
+            (
+                GlobalMetadata::BlockExcessBlobGas,
+                public_values.block_metadata.block_excess_blob_gas,
+            ),
+            (
+                GlobalMetadata::ParentBeaconBlockRoot,
+                public_values
+                    .block_metadata
+                    .parent_beacon_block_root
+                    .into_uint(),
+            ),
             (
                 GlobalMetadata::TxnNumberBefore,
                 public_values.extra_block_data.txn_number_before,
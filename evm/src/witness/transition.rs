@@ -108,6 +108,8 @@ pub(crate) fn decode(registers: RegistersState, opcode: u8) -> Result<Operation,
         (0x47, _) => Ok(Operation::Syscall(opcode, 0, true)), // SELFBALANCE
         (0x48, _) => Ok(Operation::Syscall(opcode, 0, true)), // BASEFEE
         (0x49, true) => Ok(Operation::ProverInput),
+        (0x49, false) => Ok(Operation::Syscall(opcode, 1, false)), // BLOBHASH
+        (0x4a, _) => Ok(Operation::Syscall(opcode, 0, true)),      // BLOBBASEFEE
         (0x50, _) => Ok(Operation::Pop),
         (0x51, _) => Ok(Operation::Syscall(opcode, 1, false)), // MLOAD
         (0x52, _) => Ok(Operation::Syscall(opcode, 2, false)), // MSTORE
@@ -120,6 +122,9 @@ pub(crate) fn decode(registers: RegistersState, opcode: u8) -> Result<Operation,
         (0x59, _) => Ok(Operation::Syscall(opcode, 0, true)), // MSIZE
         (0x5a, _) => Ok(Operation::Syscall(opcode, 0, true)), // GAS
         (0x5b, _) => Ok(Operation::Jumpdest),
+        (0x5c, _) => Ok(Operation::Syscall(opcode, 1, false)), // TLOAD
+        (0x5d, _) => Ok(Operation::Syscall(opcode, 2, false)), // TSTORE
+        (0x5e, _) => Ok(Operation::Syscall(opcode, 3, false)), // MCOPY
         (0x5f..=0x7f, _) => Ok(Operation::Push(opcode - 0x5f)),
         (0x80..=0x8f, _) => Ok(Operation::Dup(opcode & 0xf)),
         (0x90..=0x9f, _) => Ok(Operation::Swap(opcode & 0xf)),
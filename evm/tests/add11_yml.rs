@@ -12,13 +12,13 @@ use plonky2::field::goldilocks_field::GoldilocksField;
 use plonky2::plonk::config::KeccakGoldilocksConfig;
 use plonky2::util::timing::TimingTree;
 use plonky2_evm::all_stark::AllStark;
-use plonky2_evm::config::StarkConfig;
 use plonky2_evm::generation::mpt::{AccountRlp, LegacyReceiptRlp};
 use plonky2_evm::generation::{GenerationInputs, TrieInputs};
 use plonky2_evm::proof::{BlockHashes, BlockMetadata, TrieRoots};
 use plonky2_evm::prover::prove;
 use plonky2_evm::verifier::verify_proof;
 use plonky2_evm::Node;
+use starky::config::StarkConfig;
 
 type F = GoldilocksField;
 const D: usize = 2;
@@ -89,6 +89,9 @@ fn add11_yml() -> anyhow::Result<()> {
         block_base_fee: 0xa.into(),
         block_gas_used: 0xa868u64.into(),
         block_bloom: [0.into(); 8],
+        block_blob_gas_used: 0.into(),
+        block_excess_blob_gas: 0.into(),
+        parent_beacon_block_root: H256::default(),
     };
 
     let mut contract_code = HashMap::new();
@@ -151,7 +154,7 @@ fn add11_yml() -> anyhow::Result<()> {
         receipts_root: receipts_trie.hash(),
     };
     let inputs = GenerationInputs {
-        signed_txn: Some(txn.to_vec()),
+        signed_txns: vec![txn.to_vec()],
         withdrawals: vec![],
         tries: tries_before,
         trie_roots_after,
@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use env_logger::{try_init_from_env, Env, DEFAULT_FILTER_ENV};
+use eth_trie_utils::nibbles::Nibbles;
+use eth_trie_utils::partial_trie::{HashedPartialTrie, PartialTrie};
+use ethereum_types::{Address, BigEndianHash, H256, U256};
+use hex_literal::hex;
+use keccak_hash::keccak;
+use plonky2::field::goldilocks_field::GoldilocksField;
+use plonky2::plonk::config::PoseidonGoldilocksConfig;
+use plonky2::util::timing::TimingTree;
+use plonky2_evm::all_stark::AllStark;
+use plonky2_evm::generation::mpt::AccountRlp;
+use plonky2_evm::generation::{GenerationInputs, TrieInputs};
+use plonky2_evm::proof::{BlockHashes, BlockMetadata, TrieRoots};
+use plonky2_evm::prover::prove;
+use plonky2_evm::verifier::verify_proof;
+use plonky2_evm::Node;
+use starky::config::StarkConfig;
+
+type F = GoldilocksField;
+const D: usize = 2;
+type C = PoseidonGoldilocksConfig;
+
+const HISTORY_BUFFER_LENGTH: u64 = 8191;
+
+/// Execute 0 txns, checking that the parent beacon block root is stored in the beacon roots
+/// contract storage before the block's transactions (EIP-4788).
+#[test]
+fn test_beacon_roots() -> anyhow::Result<()> {
+    init_logger();
+
+    let all_stark = AllStark::<F, D>::default();
+    let config = StarkConfig::standard_fast_config();
+
+    let timestamp = 1_700_000_000u64;
+    let parent_beacon_block_root = H256(hex!(
+        "4d1d2e2f7b2e0e1a9c04c8bde4b2c6e3a1b5f0e9d8c7b6a5f4e3d2c1b0a99887"
+    ));
+    let block_metadata = BlockMetadata {
+        block_timestamp: timestamp.into(),
+        parent_beacon_block_root,
+        ..BlockMetadata::default()
+    };
+
+    let beacon_roots_address = Address::from(hex!("000F3df6D732807Ef1319fB7B8bB8522d0Beac02"));
+    let beacon_roots_state_key = keccak(beacon_roots_address);
+    let beacon_roots_nibbles = Nibbles::from_bytes_be(beacon_roots_state_key.as_bytes()).unwrap();
+
+    // The ring buffers already hold the entries of an older block, with the same timestamp index.
+    let timestamp_idx = timestamp % HISTORY_BUFFER_LENGTH;
+    let old_timestamp = timestamp - HISTORY_BUFFER_LENGTH;
+    let mut storage_before = HashedPartialTrie::from(Node::Empty);
+    insert_storage(
+        &mut storage_before,
+        timestamp_idx.into(),
+        old_timestamp.into(),
+    );
+    insert_storage(
+        &mut storage_before,
+        (timestamp_idx + HISTORY_BUFFER_LENGTH).into(),
+        U256::MAX,
+    );
+
+    let mut storage_after = HashedPartialTrie::from(Node::Empty);
+    insert_storage(&mut storage_after, timestamp_idx.into(), timestamp.into());
+    insert_storage(
+        &mut storage_after,
+        (timestamp_idx + HISTORY_BUFFER_LENGTH).into(),
+        parent_beacon_block_root.into_uint(),
+    );
+
+    let beacon_roots_account = |storage: &HashedPartialTrie| AccountRlp {
+        nonce: 1.into(),
+        balance: 0.into(),
+        storage_root: storage.hash(),
+        code_hash: keccak(beacon_roots_bytecode()),
+    };
+
+    let mut state_trie_before = HashedPartialTrie::from(Node::Empty);
+    state_trie_before.insert(
+        beacon_roots_nibbles,
+        rlp::encode(&beacon_roots_account(&storage_before)).to_vec(),
+    );
+    let mut state_trie_after = HashedPartialTrie::from(Node::Empty);
+    state_trie_after.insert(
+        beacon_roots_nibbles,
+        rlp::encode(&beacon_roots_account(&storage_after)).to_vec(),
+    );
+
+    let transactions_trie = HashedPartialTrie::from(Node::Empty);
+    let receipts_trie = HashedPartialTrie::from(Node::Empty);
+    let storage_tries = vec![(beacon_roots_state_key, storage_before)];
+
+    let mut contract_code = HashMap::new();
+    contract_code.insert(keccak(vec![]), vec![]);
+    contract_code.insert(keccak(beacon_roots_bytecode()), beacon_roots_bytecode());
+
+    let trie_roots_after = TrieRoots {
+        state_root: state_trie_after.hash(),
+        transactions_root: transactions_trie.hash(),
+        receipts_root: receipts_trie.hash(),
+    };
+
+    let inputs = GenerationInputs {
+        signed_txns: vec![],
+        withdrawals: vec![],
+        tries: TrieInputs {
+            state_trie: state_trie_before,
+            transactions_trie,
+            receipts_trie,
+            storage_tries,
+        },
+        trie_roots_after,
+        contract_code,
+        genesis_state_trie_root: HashedPartialTrie::from(Node::Empty).hash(),
+        block_metadata,
+        txn_number_before: 0.into(),
+        gas_used_before: 0.into(),
+        gas_used_after: 0.into(),
+        block_bloom_before: [0.into(); 8],
+        block_bloom_after: [0.into(); 8],
+        block_hashes: BlockHashes {
+            prev_hashes: vec![H256::default(); 256],
+            cur_hash: H256::default(),
+        },
+        addresses: vec![],
+    };
+
+    let mut timing = TimingTree::new("prove", log::Level::Debug);
+    let proof = prove::<F, C, D>(&all_stark, &config, inputs, &mut timing)?;
+    timing.filter(Duration::from_millis(100)).print();
+
+    verify_proof(&all_stark, proof, &config)
+}
+
+/// The runtime code of the beacon roots contract, as deployed by EIP-4788.
+fn beacon_roots_bytecode() -> Vec<u8> {
+    hex!("3373fffffffffffffffffffffffffffffffffffffffe14604d57602036146024575f5ffd5b5f35801560495762001fff810690815414603c575f5ffd5b62001fff01545f5260205ff35b5f5ffd5b62001fff42064281555f359062001fff015500").into()
+}
+
+fn insert_storage(trie: &mut HashedPartialTrie, slot: U256, value: U256) {
+    let mut bytes = [0; 32];
+    slot.to_big_endian(&mut bytes);
+    let key = keccak(bytes);
+    let nibbles = Nibbles::from_bytes_be(key.as_bytes()).unwrap();
+    let r = rlp::encode(&value);
+    let r = r.freeze().to_vec();
+    trie.insert(nibbles, r);
+}
+
+fn init_logger() {
+    let _ = try_init_from_env(Env::default().filter_or(DEFAULT_FILTER_ENV, "info"));
+}
@@ -0,0 +1,191 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::time::Duration;
+
+use env_logger::{try_init_from_env, Env, DEFAULT_FILTER_ENV};
+use eth_trie_utils::nibbles::Nibbles;
+use eth_trie_utils::partial_trie::{HashedPartialTrie, PartialTrie};
+use ethereum_types::{Address, H256, U256};
+use hex_literal::hex;
+use keccak_hash::keccak;
+use plonky2::field::goldilocks_field::GoldilocksField;
+use plonky2::plonk::config::KeccakGoldilocksConfig;
+use plonky2::util::timing::TimingTree;
+use plonky2_evm::all_stark::AllStark;
+use plonky2_evm::generation::mpt::{AccountRlp, LegacyReceiptRlp};
+use plonky2_evm::generation::{GenerationInputs, TrieInputs};
+use plonky2_evm::proof::{BlockHashes, BlockMetadata, TrieRoots};
+use plonky2_evm::prover::prove;
+use plonky2_evm::verifier::verify_proof;
+use plonky2_evm::Node;
+use starky::config::StarkConfig;
+
+type F = GoldilocksField;
+const D: usize = 2;
+type C = KeccakGoldilocksConfig;
+
+const BENEFICIARY: [u8; 20] = hex!("deadbeefdeadbeefdeadbeefdeadbeefdeadbeef");
+const SENDER: [u8; 20] = hex!("2c7536e3605d9c16a7a3d7b1898e529396a65c23");
+const TO: [u8; 20] = hex!("095e7baea6a6c7c4c2dfeb977efac326af552d87");
+
+const SENDER_BALANCE_BEFORE: u64 = 1000000000000000000;
+const GAS_PRICE: u64 = 10;
+const TXN_VALUE: u64 = 0xa;
+
+/// The blob gas of a transaction carrying a single blob.
+const BLOB_GAS: u64 = 0x20000;
+
+/// A type-3 transaction sending `TXN_VALUE` from `SENDER` to `TO`, with a single blob, a max fee
+/// per gas of `GAS_PRICE` and a max fee per blob gas of 1.
+const TXN: [u8; 136] = hex!("03f8850180800a82520894095e7baea6a6c7c4c2dfeb977efac326af552d870a80c001e1a001c1dfa573acb2783172c35d98a582e999384acb5250c35634b461d8d795cde201a01be3717f1d8a7a87b4c4c6dbd2f98baac666532bad63b1c26e3e9ce9ee041540a00f2f83cec56de225ddb3e4816ccdaa376df6633c668e2830d6151d9ac9f7375b");
+
+/// Test a transfer carried by a blob transaction, whose blob fee is burned.
+#[test]
+fn test_blob_txn() -> anyhow::Result<()> {
+    init_logger();
+
+    let all_stark = AllStark::<F, D>::default();
+    let config = StarkConfig::standard_fast_config();
+
+    let inputs = blob_txn_inputs(BLOB_GAS.into());
+
+    let mut timing = TimingTree::new("prove", log::Level::Debug);
+    let proof = prove::<F, C, D>(&all_stark, &config, inputs, &mut timing)?;
+    timing.filter(Duration::from_millis(100)).print();
+
+    verify_proof(&all_stark, proof, &config)
+}
+
+/// Test that the blob gas used by the block must match that of its transactions.
+#[test]
+fn test_blob_txn_wrong_blob_gas_used() {
+    init_logger();
+
+    let all_stark = AllStark::<F, D>::default();
+    let config = StarkConfig::standard_fast_config();
+
+    let inputs = blob_txn_inputs((2 * BLOB_GAS).into());
+
+    let mut timing = TimingTree::new("prove", log::Level::Debug);
+    assert!(prove::<F, C, D>(&all_stark, &config, inputs, &mut timing).is_err());
+}
+
+fn blob_txn_inputs(block_blob_gas_used: U256) -> GenerationInputs {
+    let sender_state_key = keccak(SENDER);
+    let to_state_key = keccak(TO);
+
+    let sender_nibbles = Nibbles::from_bytes_be(sender_state_key.as_bytes()).unwrap();
+    let to_nibbles = Nibbles::from_bytes_be(to_state_key.as_bytes()).unwrap();
+
+    let sender_account_before = AccountRlp {
+        balance: SENDER_BALANCE_BEFORE.into(),
+        ..AccountRlp::default()
+    };
+    let to_account_before = AccountRlp::default();
+
+    let state_trie_before: HashedPartialTrie = Node::Leaf {
+        nibbles: sender_nibbles,
+        value: rlp::encode(&sender_account_before).to_vec(),
+    }
+    .into();
+    let genesis_state_trie_root = state_trie_before.hash();
+
+    let tries_before = TrieInputs {
+        state_trie: state_trie_before,
+        transactions_trie: HashedPartialTrie::from(Node::Empty),
+        receipts_trie: HashedPartialTrie::from(Node::Empty),
+        storage_tries: vec![],
+    };
+
+    let gas_used = 21_000;
+
+    let block_metadata = BlockMetadata {
+        block_beneficiary: Address::from(BENEFICIARY),
+        block_timestamp: 0x03e8.into(),
+        block_number: 1.into(),
+        block_difficulty: 0x020000.into(),
+        block_gaslimit: 0xff112233u32.into(),
+        block_chain_id: 1.into(),
+        block_base_fee: GAS_PRICE.into(),
+        block_gas_used: gas_used.into(),
+        block_blob_gas_used,
+        ..BlockMetadata::default()
+    };
+
+    let mut contract_code = HashMap::new();
+    contract_code.insert(keccak(vec![]), vec![]);
+
+    // With no excess blob gas, the blob base fee is 1.
+    let expected_state_trie_after: HashedPartialTrie = {
+        let sender_account_after = AccountRlp {
+            balance: sender_account_before.balance - TXN_VALUE - gas_used * GAS_PRICE - BLOB_GAS,
+            nonce: sender_account_before.nonce + 1,
+            ..sender_account_before
+        };
+        let to_account_after = AccountRlp {
+            balance: TXN_VALUE.into(),
+            ..to_account_before
+        };
+
+        let mut children = core::array::from_fn(|_| Node::Empty.into());
+        children[sender_nibbles.get_nibble(0) as usize] = Node::Leaf {
+            nibbles: sender_nibbles.truncate_n_nibbles_front(1),
+            value: rlp::encode(&sender_account_after).to_vec(),
+        }
+        .into();
+        children[to_nibbles.get_nibble(0) as usize] = Node::Leaf {
+            nibbles: to_nibbles.truncate_n_nibbles_front(1),
+            value: rlp::encode(&to_account_after).to_vec(),
+        }
+        .into();
+        Node::Branch {
+            children,
+            value: vec![],
+        }
+        .into()
+    };
+
+    let receipt_0 = LegacyReceiptRlp {
+        status: true,
+        cum_gas_used: gas_used.into(),
+        bloom: vec![0; 256].into(),
+        logs: vec![],
+    };
+    let mut receipts_trie = HashedPartialTrie::from(Node::Empty);
+    receipts_trie.insert(Nibbles::from_str("0x80").unwrap(), receipt_0.encode(3));
+    let transactions_trie: HashedPartialTrie = Node::Leaf {
+        nibbles: Nibbles::from_str("0x80").unwrap(),
+        value: TXN.to_vec(),
+    }
+    .into();
+
+    let trie_roots_after = TrieRoots {
+        state_root: expected_state_trie_after.hash(),
+        transactions_root: transactions_trie.hash(),
+        receipts_root: receipts_trie.hash(),
+    };
+
+    GenerationInputs {
+        signed_txns: vec![TXN.to_vec()],
+        withdrawals: vec![],
+        tries: tries_before,
+        trie_roots_after,
+        contract_code,
+        genesis_state_trie_root,
+        block_metadata,
+        txn_number_before: 0.into(),
+        gas_used_before: 0.into(),
+        gas_used_after: gas_used.into(),
+        block_bloom_before: [0.into(); 8],
+        block_bloom_after: [0.into(); 8],
+        block_hashes: BlockHashes {
+            prev_hashes: vec![H256::default(); 256],
+            cur_hash: H256::default(),
+        },
+        addresses: vec![],
+    }
+}
+
+fn init_logger() {
+    let _ = try_init_from_env(Env::default().filter_or(DEFAULT_FILTER_ENV, "info"));
+}
@@ -11,13 +11,13 @@ use plonky2::field::goldilocks_field::GoldilocksField;
 use plonky2::plonk::config::KeccakGoldilocksConfig;
 use plonky2::util::timing::TimingTree;
 use plonky2_evm::all_stark::AllStark;
-use plonky2_evm::config::StarkConfig;
 use plonky2_evm::generation::mpt::{AccountRlp, LegacyReceiptRlp, LogRlp};
 use plonky2_evm::generation::{GenerationInputs, TrieInputs};
 use plonky2_evm::proof::{BlockHashes, BlockMetadata, TrieRoots};
 use plonky2_evm::prover::prove;
 use plonky2_evm::verifier::verify_proof;
 use plonky2_evm::Node;
+use starky::config::StarkConfig;
 
 type F = GoldilocksField;
 const D: usize = 2;
@@ -95,6 +95,9 @@ fn test_erc20() -> anyhow::Result<()> {
         block_base_fee: 0xa.into(),
         block_gas_used: gas_used,
         block_bloom: bloom,
+        block_blob_gas_used: 0.into(),
+        block_excess_blob_gas: 0.into(),
+        parent_beacon_block_root: H256::default(),
     };
 
     let contract_code = [giver_bytecode(), token_bytecode(), vec![]]
@@ -159,7 +162,7 @@ fn test_erc20() -> anyhow::Result<()> {
         receipts_root: receipts_trie.hash(),
     };
     let inputs = GenerationInputs {
-        signed_txn: Some(txn.to_vec()),
+        signed_txns: vec![txn.to_vec()],
         withdrawals: vec![],
         tries: tries_before,
         trie_roots_after,
@@ -15,7 +15,6 @@ use plonky2::field::goldilocks_field::GoldilocksField;
 use plonky2::plonk::config::PoseidonGoldilocksConfig;
 use plonky2::util::timing::TimingTree;
 use plonky2_evm::all_stark::AllStark;
-use plonky2_evm::config::StarkConfig;
 use plonky2_evm::fixed_recursive_verifier::AllRecursiveCircuits;
 use plonky2_evm::generation::mpt::transaction_testing::{AddressOption, LegacyTransactionRlp};
 use plonky2_evm::generation::mpt::{AccountRlp, LegacyReceiptRlp, LogRlp};
@@ -24,6 +23,7 @@ use plonky2_evm::proof::{BlockHashes, BlockMetadata, TrieRoots};
 use plonky2_evm::prover::prove;
 use plonky2_evm::verifier::verify_proof;
 use plonky2_evm::Node;
+use starky::config::StarkConfig;
 
 type F = GoldilocksField;
 const D: usize = 2;
@@ -142,6 +142,9 @@ fn test_log_opcodes() -> anyhow::Result<()> {
         block_base_fee: 0xa.into(),
         block_gas_used: 0.into(),
         block_bloom: [0.into(); 8],
+        block_blob_gas_used: 0.into(),
+        block_excess_blob_gas: 0.into(),
+        parent_beacon_block_root: H256::default(),
     };
 
     let mut contract_code = HashMap::new();
@@ -229,7 +232,7 @@ fn test_log_opcodes() -> anyhow::Result<()> {
             .unwrap(),
     ];
     let inputs = GenerationInputs {
-        signed_txn: Some(txn.to_vec()),
+        signed_txns: vec![txn.to_vec()],
         withdrawals: vec![],
         tries: tries_before,
         trie_roots_after,
@@ -377,6 +380,9 @@ fn test_log_with_aggreg() -> anyhow::Result<()> {
             U256::from_dec_str("2722259584404615024560450425766186844160").unwrap(),
         ],
         block_random: Default::default(),
+        block_blob_gas_used: 0.into(),
+        block_excess_blob_gas: 0.into(),
+        parent_beacon_block_root: H256::default(),
     };
 
     let beneficiary_account_after = AccountRlp {
@@ -437,7 +443,7 @@ fn test_log_with_aggreg() -> anyhow::Result<()> {
     };
 
     let inputs_first = GenerationInputs {
-        signed_txn: Some(txn.to_vec()),
+        signed_txns: vec![txn.to_vec()],
         withdrawals: vec![],
         tries: tries_before,
         trie_roots_after: tries_after,
@@ -582,7 +588,7 @@ fn test_log_with_aggreg() -> anyhow::Result<()> {
         U256::from_dec_str("2722259584404615024560450425766186844160").unwrap(),
     ];
     let inputs = GenerationInputs {
-        signed_txn: Some(txn_2.to_vec()),
+        signed_txns: vec![txn_2.to_vec()],
         withdrawals: vec![],
         tries: tries_before,
         trie_roots_after,
@@ -0,0 +1,498 @@
+#![allow(clippy::upper_case_acronyms)]
+
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::time::Duration;
+
+use bytes::Bytes;
+use env_logger::{try_init_from_env, Env, DEFAULT_FILTER_ENV};
+use eth_trie_utils::nibbles::Nibbles;
+use eth_trie_utils::partial_trie::{HashedPartialTrie, PartialTrie};
+use ethereum_types::{Address, H256, U256};
+use hex_literal::hex;
+use keccak_hash::keccak;
+use plonky2::field::goldilocks_field::GoldilocksField;
+use plonky2::plonk::config::PoseidonGoldilocksConfig;
+use plonky2::util::timing::TimingTree;
+use plonky2_evm::all_stark::AllStark;
+use plonky2_evm::fixed_recursive_verifier::AllRecursiveCircuits;
+use plonky2_evm::generation::mpt::{AccountRlp, LegacyReceiptRlp, LogRlp};
+use plonky2_evm::generation::{GenerationInputs, TrieInputs};
+use plonky2_evm::proof::{BlockHashes, BlockMetadata, TrieRoots};
+use plonky2_evm::prover::prove;
+use plonky2_evm::verifier::verify_proof;
+use plonky2_evm::Node;
+use starky::config::StarkConfig;
+
+type F = GoldilocksField;
+const D: usize = 2;
+type C = PoseidonGoldilocksConfig;
+
+/// Execute two transactions in a single proof: a simple transfer, followed by a call to a
+/// contract carrying out two LOG opcodes.
+#[test]
+fn test_two_txns() -> anyhow::Result<()> {
+    init_logger();
+
+    let all_stark = AllStark::<F, D>::default();
+    let config = StarkConfig::standard_fast_config();
+
+    let inputs = two_txns_inputs();
+
+    let mut timing = TimingTree::new("prove", log::Level::Debug);
+    let proof = prove::<F, C, D>(&all_stark, &config, inputs, &mut timing)?;
+    timing.filter(Duration::from_millis(100)).print();
+
+    assert_eq!(
+        proof.public_values.extra_block_data.txn_number_after,
+        2.into()
+    );
+
+    verify_proof(&all_stark, proof, &config)
+}
+
+/// Prove two transactions in a single proof, followed by an empty proof, and aggregate them.
+#[test]
+#[ignore] // Too slow to run on CI.
+fn test_two_txns_with_aggreg() -> anyhow::Result<()> {
+    init_logger();
+
+    let all_stark = AllStark::<F, D>::default();
+    let config = StarkConfig::standard_fast_config();
+
+    let inputs_first = two_txns_inputs();
+
+    // The second proof starts from the final state of the first one, and executes no transaction.
+    let tries_before = TrieInputs {
+        state_trie: expected_state_trie_after(),
+        transactions_trie: expected_transactions_trie(),
+        receipts_trie: expected_receipts_trie(),
+        storage_tries: vec![],
+    };
+    let mut contract_code = HashMap::new();
+    contract_code.insert(keccak(vec![]), vec![]);
+    let inputs_second = GenerationInputs {
+        signed_txns: vec![],
+        withdrawals: vec![],
+        tries: tries_before,
+        trie_roots_after: inputs_first.trie_roots_after.clone(),
+        contract_code,
+        genesis_state_trie_root: inputs_first.genesis_state_trie_root,
+        block_metadata: inputs_first.block_metadata.clone(),
+        txn_number_before: 2.into(),
+        gas_used_before: inputs_first.gas_used_after,
+        gas_used_after: inputs_first.gas_used_after,
+        block_bloom_before: inputs_first.block_bloom_after,
+        block_bloom_after: inputs_first.block_bloom_after,
+        block_hashes: inputs_first.block_hashes.clone(),
+        addresses: vec![],
+    };
+
+    // Preprocess all circuits.
+    let all_circuits = AllRecursiveCircuits::<F, C, D>::new(
+        &all_stark,
+        &[16..17, 12..19, 15..19, 14..15, 10..11, 12..14, 18..21],
+        &config,
+    );
+
+    let mut timing = TimingTree::new("prove root first", log::Level::Info);
+    let (root_proof_first, public_values_first) =
+        all_circuits.prove_root(&all_stark, &config, inputs_first, &mut timing)?;
+    timing.filter(Duration::from_millis(100)).print();
+    all_circuits.verify_root(root_proof_first.clone())?;
+
+    let mut timing = TimingTree::new("prove root second", log::Level::Info);
+    let (root_proof_second, public_values_second) =
+        all_circuits.prove_root(&all_stark, &config, inputs_second, &mut timing)?;
+    timing.filter(Duration::from_millis(100)).print();
+    all_circuits.verify_root(root_proof_second.clone())?;
+
+    let (agg_proof, updated_agg_public_values) = all_circuits.prove_aggregation(
+        false,
+        &root_proof_first,
+        public_values_first,
+        false,
+        &root_proof_second,
+        public_values_second,
+    )?;
+    all_circuits.verify_aggregation(&agg_proof)?;
+    let (block_proof, _block_public_values) =
+        all_circuits.prove_block(None, &agg_proof, updated_agg_public_values)?;
+    all_circuits.verify_block(&block_proof)
+}
+
+/// Execute a legacy transaction without a chain ID after one with a chain ID, so that the chain ID
+/// of the first transaction must not leak into the second one.
+#[test]
+fn test_legacy_txn_without_chain_id_after_chain_id() -> anyhow::Result<()> {
+    init_logger();
+
+    let all_stark = AllStark::<F, D>::default();
+    let config = StarkConfig::standard_fast_config();
+
+    let inputs = two_transfers_inputs([(&TRANSFER_LEGACY_CHAIN_ID, 0), (&TRANSFER_LEGACY, 0)], 0);
+
+    let mut timing = TimingTree::new("prove", log::Level::Debug);
+    let proof = prove::<F, C, D>(&all_stark, &config, inputs, &mut timing)?;
+    timing.filter(Duration::from_millis(100)).print();
+
+    verify_proof(&all_stark, proof, &config)
+}
+
+/// Execute a blob transaction after a dynamic fee transaction.
+#[test]
+fn test_blob_txn_after_dynamic_fee_txn() -> anyhow::Result<()> {
+    init_logger();
+
+    let all_stark = AllStark::<F, D>::default();
+    let config = StarkConfig::standard_fast_config();
+
+    let inputs = two_transfers_inputs([(&TRANSFER_DYNAMIC_FEE, 2), (&TRANSFER_BLOB, 3)], BLOB_GAS);
+
+    let mut timing = TimingTree::new("prove", log::Level::Debug);
+    let proof = prove::<F, C, D>(&all_stark, &config, inputs, &mut timing)?;
+    timing.filter(Duration::from_millis(100)).print();
+
+    verify_proof(&all_stark, proof, &config)
+}
+
+const BENEFICIARY: [u8; 20] = hex!("2adc25665018aa1fe0e6bc666dac8fc2697ff9ba");
+const SENDER: [u8; 20] = hex!("af1276cbb260bb13deddb4209ae99ae6e497f446");
+const TO_FIRST: [u8; 20] = hex!("095e7baea6a6c7c4c2dfeb977efac326af552d87");
+const TO_SECOND: [u8; 20] = hex!("095e7baea6a6c7c4c2dfeb977efac326af552e89");
+
+const SENDER_BALANCE_BEFORE: u64 = 1000000000000000000;
+const GAS_PRICE: u64 = 10;
+const TXN_VALUE: u64 = 0xa;
+
+/// Gas used by the second transaction.
+const LOG_TXN_GAS_USED: u64 = 21_000
+    + 3 + 3 + 3 // PUSHs and MSTORE
+    + 3 + 3 + 375 // PUSHs and LOG0
+    + 3 + 3 + 3 + 3 + 375 + 375*2 + 8*5 // PUSHs and LOG2
+    + 3; // Memory expansion
+
+/// Transfers `TXN_VALUE` from `SENDER` to `TO_FIRST`.
+const TXN_FIRST: [u8; 97] = hex!("f85f800a82520894095e7baea6a6c7c4c2dfeb977efac326af552d870a8026a0122f370ed4023a6c253350c6bfb87d7d7eb2cd86447befee99e0a26b70baec20a07100ab1b3977f2b4571202b9f4b68850858caf5469222794600b5ce1cfb348ad");
+/// Calls the logging contract at `TO_SECOND`.
+const TXN_SECOND: [u8; 98] = hex!("f860010a830186a094095e7baea6a6c7c4c2dfeb977efac326af552e89808025a04a223955b0bd3827e3740a9a427d0ea43beb5bafa44a0204bf0a3306c8219f7ba0502c32d78f233e9e7ce9f5df3b576556d5d49731e0678fd5a068cdf359557b5b");
+
+/// The sender of the transfers below, each sending `TXN_VALUE` to `TO_FIRST` with 21000 gas at a
+/// gas price of `GAS_PRICE`.
+const TRANSFER_SENDER: [u8; 20] = hex!("2c7536e3605d9c16a7a3d7b1898e529396a65c23");
+
+/// A legacy transfer with nonce 0 and a chain ID.
+const TRANSFER_LEGACY_CHAIN_ID: [u8; 97] = hex!("f85f800a82520894095e7baea6a6c7c4c2dfeb977efac326af552d870a8025a098b7c0aa5c343dcff310ce72ad03801090d2415784ea6e3b04a342f421e26178a04d19e0f1ff59019149320564e1e749a76052e4eb0b45bc8fba8070ef0a54bf3c");
+/// A legacy transfer with nonce 1 and no chain ID.
+const TRANSFER_LEGACY: [u8; 97] = hex!("f85f010a82520894095e7baea6a6c7c4c2dfeb977efac326af552d870a801ca02680bf3a032877119981f55d6598c7e2c5a77f2065015c4051e418d37514812ca01a0da3d9a7f83ab6fb4fcb4d9a250fff35461685673fba6c139f5f80ade4d329");
+/// A type-2 transfer with nonce 0.
+const TRANSFER_DYNAMIC_FEE: [u8; 101] = hex!("02f8620180800a82520894095e7baea6a6c7c4c2dfeb977efac326af552d870a80c001a066adb483cdd722dfa24e6e58848b53160dbd688e45722bfd4369d2130f739f62a02236e23ce873b86d2bc009123f046d4f66f1933c76b1e27ab00e10058d8f9eed");
+/// A type-3 transfer with nonce 1, carrying a single blob with a max fee per blob gas of 1.
+const TRANSFER_BLOB: [u8; 136] = hex!("03f8850101800a82520894095e7baea6a6c7c4c2dfeb977efac326af552d870a80c001e1a001c1dfa573acb2783172c35d98a582e999384acb5250c35634b461d8d795cde280a0e3ece0ca96c4954b60823f9bf17454a8b8dd3ab1a2ee26bd2a681ae4cd5f3b52a03e00d2d41cf7202efc98949957a476d101a7496f4b33f74d9aec772347661aff");
+
+/// The blob gas of a transaction carrying a single blob.
+const BLOB_GAS: u64 = 0x20000;
+
+fn log_code() -> Vec<u8> {
+    vec![
+        0x64, 0xA1, 0xB2, 0xC3, 0xD4, 0xE5, 0x60, 0x0, 0x52, // MSTORE(0x0, 0xA1B2C3D4E5)
+        0x60, 0x0, 0x60, 0x0, 0xA0, // LOG0(0x0, 0x0)
+        0x60, 99, 0x60, 98, 0x60, 5, 0x60, 27, 0xA2, // LOG2(27, 5, 98, 99)
+        0x00,
+    ]
+}
+
+fn nibbles(addr: [u8; 20]) -> Nibbles {
+    Nibbles::from_bytes_be(keccak(addr).as_bytes()).unwrap()
+}
+
+fn state_trie(accounts: [([u8; 20], AccountRlp); 4]) -> HashedPartialTrie {
+    let mut trie = HashedPartialTrie::from(Node::Empty);
+    for (addr, account) in accounts {
+        trie.insert(nibbles(addr), rlp::encode(&account).to_vec());
+    }
+    trie
+}
+
+fn state_trie_before() -> HashedPartialTrie {
+    state_trie([
+        (
+            BENEFICIARY,
+            AccountRlp {
+                nonce: 1.into(),
+                ..AccountRlp::default()
+            },
+        ),
+        (
+            SENDER,
+            AccountRlp {
+                balance: SENDER_BALANCE_BEFORE.into(),
+                ..AccountRlp::default()
+            },
+        ),
+        (TO_FIRST, AccountRlp::default()),
+        (
+            TO_SECOND,
+            AccountRlp {
+                code_hash: keccak(log_code()),
+                ..AccountRlp::default()
+            },
+        ),
+    ])
+}
+
+fn expected_state_trie_after() -> HashedPartialTrie {
+    let sender_balance_after =
+        SENDER_BALANCE_BEFORE - GAS_PRICE * 21000 - TXN_VALUE - GAS_PRICE * LOG_TXN_GAS_USED;
+    state_trie([
+        (
+            BENEFICIARY,
+            AccountRlp {
+                nonce: 1.into(),
+                ..AccountRlp::default()
+            },
+        ),
+        (
+            SENDER,
+            AccountRlp {
+                balance: sender_balance_after.into(),
+                nonce: 2.into(),
+                ..AccountRlp::default()
+            },
+        ),
+        (
+            TO_FIRST,
+            AccountRlp {
+                balance: TXN_VALUE.into(),
+                ..AccountRlp::default()
+            },
+        ),
+        (
+            TO_SECOND,
+            AccountRlp {
+                code_hash: keccak(log_code()),
+                ..AccountRlp::default()
+            },
+        ),
+    ])
+}
+
+fn expected_transactions_trie() -> HashedPartialTrie {
+    let mut trie = HashedPartialTrie::from(Node::Empty);
+    trie.insert(Nibbles::from_str("0x80").unwrap(), TXN_FIRST.to_vec()); // RLP(0) = 0x80
+    trie.insert(Nibbles::from_str("0x01").unwrap(), TXN_SECOND.to_vec()); // RLP(1) = 0x1
+    trie
+}
+
+fn expected_receipts_trie() -> HashedPartialTrie {
+    let receipt_first = LegacyReceiptRlp {
+        status: true,
+        cum_gas_used: 21000u64.into(),
+        bloom: [0x00; 256].to_vec().into(),
+        logs: vec![],
+    };
+
+    let first_log = LogRlp {
+        address: TO_SECOND.into(),
+        topics: vec![],
+        data: Bytes::new(),
+    };
+    let second_log = LogRlp {
+        address: TO_SECOND.into(),
+        topics: vec![
+            hex!("0000000000000000000000000000000000000000000000000000000000000062").into(), // dec: 98
+            hex!("0000000000000000000000000000000000000000000000000000000000000063").into(), // dec: 99
+        ],
+        data: hex!("a1b2c3d4e5").to_vec().into(),
+    };
+    let receipt_second = LegacyReceiptRlp {
+        status: true,
+        cum_gas_used: (21000 + LOG_TXN_GAS_USED).into(),
+        bloom: hex!("00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000008000000000000000001000000000000000000000000000000000000000000000000000000000000000000000004000000000000000000000000000000000000000000000000000000000000000000000000000000000000000002000000000000000000000000000000000000000000000000000000800000000000000008000000000000000000000000000000000000000000000000000000000400000000000000000000000000000000000800002000000000000000000000000000").to_vec().into(),
+        logs: vec![first_log, second_log],
+    };
+
+    let mut trie = HashedPartialTrie::from(Node::Empty);
+    trie.insert(
+        Nibbles::from_str("0x80").unwrap(),
+        rlp::encode(&receipt_first).to_vec(),
+    );
+    trie.insert(
+        Nibbles::from_str("0x01").unwrap(),
+        rlp::encode(&receipt_second).to_vec(),
+    );
+    trie
+}
+
+fn two_txns_inputs() -> GenerationInputs {
+    let block_bloom_after = [
+        0.into(),
+        0.into(),
+        U256::from_dec_str(
+            "55213970774324510299479508399853534522527075462195808724319849722937344",
+        )
+        .unwrap(),
+        U256::from_dec_str("1361129467683753853853498429727072845824").unwrap(),
+        33554432.into(),
+        U256::from_dec_str("9223372036854775808").unwrap(),
+        U256::from_dec_str(
+            "3618502788666131106986593281521497120414687020801267626233049500247285563392",
+        )
+        .unwrap(),
+        U256::from_dec_str("2722259584404615024560450425766186844160").unwrap(),
+    ];
+    let gas_used_after = U256::from(21000 + LOG_TXN_GAS_USED);
+
+    let block_metadata = BlockMetadata {
+        block_beneficiary: Address::from(BENEFICIARY),
+        block_timestamp: 0x03e8.into(),
+        block_number: 0.into(),
+        block_difficulty: 0x020000.into(),
+        block_gaslimit: 0x445566u32.into(),
+        block_chain_id: 1.into(),
+        block_base_fee: 0xa.into(),
+        block_gas_used: gas_used_after,
+        block_bloom: block_bloom_after,
+        ..BlockMetadata::default()
+    };
+
+    let state_trie_before = state_trie_before();
+    let genesis_state_trie_root = state_trie_before.hash();
+
+    let mut contract_code = HashMap::new();
+    contract_code.insert(keccak(vec![]), vec![]);
+    contract_code.insert(keccak(log_code()), log_code());
+
+    let trie_roots_after = TrieRoots {
+        state_root: expected_state_trie_after().hash(),
+        transactions_root: expected_transactions_trie().hash(),
+        receipts_root: expected_receipts_trie().hash(),
+    };
+
+    GenerationInputs {
+        signed_txns: vec![TXN_FIRST.to_vec(), TXN_SECOND.to_vec()],
+        withdrawals: vec![],
+        tries: TrieInputs {
+            state_trie: state_trie_before,
+            transactions_trie: Node::Empty.into(),
+            receipts_trie: Node::Empty.into(),
+            storage_tries: vec![],
+        },
+        trie_roots_after,
+        contract_code,
+        genesis_state_trie_root,
+        block_metadata,
+        txn_number_before: 0.into(),
+        gas_used_before: 0.into(),
+        gas_used_after,
+        block_bloom_before: [0.into(); 8],
+        block_bloom_after,
+        block_hashes: BlockHashes {
+            prev_hashes: vec![H256::default(); 256],
+            cur_hash: H256::default(),
+        },
+        addresses: vec![],
+    }
+}
+
+/// Returns the inputs for executing two transfers from `TRANSFER_SENDER` to `TO_FIRST`, given
+/// with their transaction types, using `blob_gas_used` blob gas in total.
+fn two_transfers_inputs(txns: [(&[u8], u8); 2], blob_gas_used: u64) -> GenerationInputs {
+    let sender_account_before = AccountRlp {
+        balance: SENDER_BALANCE_BEFORE.into(),
+        ..AccountRlp::default()
+    };
+    let mut state_trie_before = HashedPartialTrie::from(Node::Empty);
+    state_trie_before.insert(
+        nibbles(TRANSFER_SENDER),
+        rlp::encode(&sender_account_before).to_vec(),
+    );
+    let genesis_state_trie_root = state_trie_before.hash();
+
+    // With no excess blob gas, the blob base fee is 1.
+    let gas_used_after = 2 * 21000;
+    let sender_account_after = AccountRlp {
+        balance: (SENDER_BALANCE_BEFORE
+            - 2 * TXN_VALUE
+            - GAS_PRICE * gas_used_after
+            - blob_gas_used)
+            .into(),
+        nonce: 2.into(),
+        ..AccountRlp::default()
+    };
+    let to_account_after = AccountRlp {
+        balance: (2 * TXN_VALUE).into(),
+        ..AccountRlp::default()
+    };
+    let mut expected_state_trie_after = HashedPartialTrie::from(Node::Empty);
+    expected_state_trie_after.insert(
+        nibbles(TRANSFER_SENDER),
+        rlp::encode(&sender_account_after).to_vec(),
+    );
+    expected_state_trie_after.insert(nibbles(TO_FIRST), rlp::encode(&to_account_after).to_vec());
+
+    let mut transactions_trie = HashedPartialTrie::from(Node::Empty);
+    let mut receipts_trie = HashedPartialTrie::from(Node::Empty);
+    for (i, (txn, txn_type)) in txns.into_iter().enumerate() {
+        let key = Nibbles::from_str(["0x80", "0x01"][i]).unwrap();
+        let receipt = LegacyReceiptRlp {
+            status: true,
+            cum_gas_used: (21000 * (i + 1)).into(),
+            bloom: vec![0; 256].into(),
+            logs: vec![],
+        };
+        transactions_trie.insert(key, txn.to_vec());
+        receipts_trie.insert(key, receipt.encode(txn_type));
+    }
+
+    let block_metadata = BlockMetadata {
+        block_beneficiary: Address::from(BENEFICIARY),
+        block_timestamp: 0x03e8.into(),
+        block_number: 0.into(),
+        block_difficulty: 0x020000.into(),
+        block_gaslimit: 0x445566u32.into(),
+        block_chain_id: 1.into(),
+        block_base_fee: GAS_PRICE.into(),
+        block_gas_used: gas_used_after.into(),
+        block_blob_gas_used: blob_gas_used.into(),
+        ..BlockMetadata::default()
+    };
+
+    let mut contract_code = HashMap::new();
+    contract_code.insert(keccak(vec![]), vec![]);
+
+    let trie_roots_after = TrieRoots {
+        state_root: expected_state_trie_after.hash(),
+        transactions_root: transactions_trie.hash(),
+        receipts_root: receipts_trie.hash(),
+    };
+
+    GenerationInputs {
+        signed_txns: txns.iter().map(|(txn, _)| txn.to_vec()).collect(),
+        withdrawals: vec![],
+        tries: TrieInputs {
+            state_trie: state_trie_before,
+            transactions_trie: Node::Empty.into(),
+            receipts_trie: Node::Empty.into(),
+            storage_tries: vec![],
+        },
+        trie_roots_after,
+        contract_code,
+        genesis_state_trie_root,
+        block_metadata,
+        txn_number_before: 0.into(),
+        gas_used_before: 0.into(),
+        gas_used_after: gas_used_after.into(),
+        block_bloom_before: [0.into(); 8],
+        block_bloom_after: [0.into(); 8],
+        block_hashes: BlockHashes {
+            prev_hashes: vec![H256::default(); 256],
+            cur_hash: H256::default(),
+        },
+        addresses: vec![],
+    }
+}
+
+fn init_logger() {
+    let _ = try_init_from_env(Env::default().filter_or(DEFAULT_FILTER_ENV, "info"));
+}
@@ -0,0 +1,510 @@
+use core::arch::aarch64::*;
+use core::fmt;
+use core::fmt::{Debug, Formatter};
+use core::iter::{Product, Sum};
+use core::mem::transmute;
+use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use crate::goldilocks_field::GoldilocksField;
+use crate::ops::Square;
+use crate::packed::PackedField;
+use crate::types::{Field, Field64};
+
+/// NEON Goldilocks Field
+///
+/// Ideally `NeonGoldilocksField` would wrap `uint64x2_t`. Unfortunately, `uint64x2_t` has an
+/// alignment of 16B, which would preclude us from casting `[GoldilocksField; 2]` (alignment 8B) to
+/// `NeonGoldilocksField`. We need to ensure that `NeonGoldilocksField` has the same alignment as
+/// `GoldilocksField`. Thus we wrap `[GoldilocksField; 2]` and use the `new` and `get` methods to
+/// convert to and from `uint64x2_t`.
+#[derive(Copy, Clone)]
+#[repr(transparent)]
+pub struct NeonGoldilocksField(pub [GoldilocksField; 2]);
+
+impl NeonGoldilocksField {
+    #[inline]
+    fn new(x: uint64x2_t) -> Self {
+        unsafe { transmute(x) }
+    }
+    #[inline]
+    fn get(&self) -> uint64x2_t {
+        unsafe { transmute(*self) }
+    }
+}
+
+impl Add<Self> for NeonGoldilocksField {
+    type Output = Self;
+    #[inline]
+    fn add(self, rhs: Self) -> Self {
+        Self::new(unsafe { add(self.get(), rhs.get()) })
+    }
+}
+impl Add<GoldilocksField> for NeonGoldilocksField {
+    type Output = Self;
+    #[inline]
+    fn add(self, rhs: GoldilocksField) -> Self {
+        self + Self::from(rhs)
+    }
+}
+impl Add<NeonGoldilocksField> for GoldilocksField {
+    type Output = NeonGoldilocksField;
+    #[inline]
+    fn add(self, rhs: Self::Output) -> Self::Output {
+        Self::Output::from(self) + rhs
+    }
+}
+impl AddAssign<Self> for NeonGoldilocksField {
+    #[inline]
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+impl AddAssign<GoldilocksField> for NeonGoldilocksField {
+    #[inline]
+    fn add_assign(&mut self, rhs: GoldilocksField) {
+        *self = *self + rhs;
+    }
+}
+
+impl Debug for NeonGoldilocksField {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "({:?})", self.get())
+    }
+}
+
+impl Default for NeonGoldilocksField {
+    #[inline]
+    fn default() -> Self {
+        Self::ZEROS
+    }
+}
+
+impl Div<GoldilocksField> for NeonGoldilocksField {
+    type Output = Self;
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    #[inline]
+    fn div(self, rhs: GoldilocksField) -> Self {
+        self * rhs.inverse()
+    }
+}
+impl DivAssign<GoldilocksField> for NeonGoldilocksField {
+    #[allow(clippy::suspicious_op_assign_impl)]
+    #[inline]
+    fn div_assign(&mut self, rhs: GoldilocksField) {
+        *self *= rhs.inverse();
+    }
+}
+
+impl From<GoldilocksField> for NeonGoldilocksField {
+    fn from(x: GoldilocksField) -> Self {
+        Self([x; 2])
+    }
+}
+
+impl Mul<Self> for NeonGoldilocksField {
+    type Output = Self;
+    #[inline]
+    fn mul(self, rhs: Self) -> Self {
+        Self::new(unsafe { mul(self.get(), rhs.get()) })
+    }
+}
+impl Mul<GoldilocksField> for NeonGoldilocksField {
+    type Output = Self;
+    #[inline]
+    fn mul(self, rhs: GoldilocksField) -> Self {
+        self * Self::from(rhs)
+    }
+}
+impl Mul<NeonGoldilocksField> for GoldilocksField {
+    type Output = NeonGoldilocksField;
+    #[inline]
+    fn mul(self, rhs: NeonGoldilocksField) -> Self::Output {
+        Self::Output::from(self) * rhs
+    }
+}
+impl MulAssign<Self> for NeonGoldilocksField {
+    #[inline]
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+impl MulAssign<GoldilocksField> for NeonGoldilocksField {
+    #[inline]
+    fn mul_assign(&mut self, rhs: GoldilocksField) {
+        *self = *self * rhs;
+    }
+}
+
+impl Neg for NeonGoldilocksField {
+    type Output = Self;
+    #[inline]
+    fn neg(self) -> Self {
+        Self::new(unsafe { neg(self.get()) })
+    }
+}
+
+impl Product for NeonGoldilocksField {
+    #[inline]
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.reduce(|x, y| x * y).unwrap_or(Self::ONES)
+    }
+}
+
+unsafe impl PackedField for NeonGoldilocksField {
+    const WIDTH: usize = 2;
+
+    type Scalar = GoldilocksField;
+
+    const ZEROS: Self = Self([GoldilocksField::ZERO; 2]);
+    const ONES: Self = Self([GoldilocksField::ONE; 2]);
+
+    #[inline]
+    fn from_slice(slice: &[Self::Scalar]) -> &Self {
+        assert_eq!(slice.len(), Self::WIDTH);
+        unsafe { &*slice.as_ptr().cast() }
+    }
+    #[inline]
+    fn from_slice_mut(slice: &mut [Self::Scalar]) -> &mut Self {
+        assert_eq!(slice.len(), Self::WIDTH);
+        unsafe { &mut *slice.as_mut_ptr().cast() }
+    }
+    #[inline]
+    fn as_slice(&self) -> &[Self::Scalar] {
+        &self.0[..]
+    }
+    #[inline]
+    fn as_slice_mut(&mut self) -> &mut [Self::Scalar] {
+        &mut self.0[..]
+    }
+
+    #[inline]
+    fn interleave(&self, other: Self, block_len: usize) -> (Self, Self) {
+        let (v0, v1) = (self.get(), other.get());
+        let (res0, res1) = match block_len {
+            1 => unsafe { interleave1(v0, v1) },
+            2 => (v0, v1),
+            _ => panic!("unsupported block_len"),
+        };
+        (Self::new(res0), Self::new(res1))
+    }
+}
+
+impl Square for NeonGoldilocksField {
+    #[inline]
+    fn square(&self) -> Self {
+        Self::new(unsafe { square(self.get()) })
+    }
+}
+
+impl Sub<Self> for NeonGoldilocksField {
+    type Output = Self;
+    #[inline]
+    fn sub(self, rhs: Self) -> Self {
+        Self::new(unsafe { sub(self.get(), rhs.get()) })
+    }
+}
+impl Sub<GoldilocksField> for NeonGoldilocksField {
+    type Output = Self;
+    #[inline]
+    fn sub(self, rhs: GoldilocksField) -> Self {
+        self - Self::from(rhs)
+    }
+}
+impl Sub<NeonGoldilocksField> for GoldilocksField {
+    type Output = NeonGoldilocksField;
+    #[inline]
+    fn sub(self, rhs: NeonGoldilocksField) -> Self::Output {
+        Self::Output::from(self) - rhs
+    }
+}
+impl SubAssign<Self> for NeonGoldilocksField {
+    #[inline]
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+impl SubAssign<GoldilocksField> for NeonGoldilocksField {
+    #[inline]
+    fn sub_assign(&mut self, rhs: GoldilocksField) {
+        *self = *self - rhs;
+    }
+}
+
+impl Sum for NeonGoldilocksField {
+    #[inline]
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.reduce(|x, y| x + y).unwrap_or(Self::ZEROS)
+    }
+}
+
+// Resources:
+// 1. Arm Intrinsics reference for explanation of each intrinsic:
+//    https://developer.arm.com/architectures/instruction-sets/intrinsics/
+// 2. Arm Neoverse software optimization guides for latencies and throughputs, e.g. the Neoverse V1
+//    guide: https://developer.arm.com/documentation/pjdoc466751330-9685/latest/
+
+// Preliminary knowledge:
+// 1. Unlike AVX2, NEON has unsigned 64-bit comparisons (vcgtq_u64 and friends), so we do not need
+//    to shift values by 1 << 63 to emulate them. Comparisons return -1 (all bits 1) for true and 0
+//    for false, so a comparison result can be used directly as a mask.
+//
+// 2. NEON has no 64-bit by 64-bit vector multiplication. Like the AVX2 implementation, we split
+//    the operands into 32-bit halves and combine four 32-bit by 32-bit products. vmlal_u32 fuses
+//    the widening multiplication with an accumulation, and vsraq_n_u64/vsliq_n_u64 fuse shifts with
+//    an addition/insertion, which saves a few instructions in the bignum arithmetic.
+
+const FIELD_ORDER: uint64x2_t = unsafe { transmute([GoldilocksField::ORDER; 2]) };
+const EPSILON: uint64x2_t = unsafe { transmute([GoldilocksField::ORDER.wrapping_neg(); 2]) };
+
+/// Convert to canonical representation.
+#[inline]
+unsafe fn canonicalize(x: uint64x2_t) -> uint64x2_t {
+    // -1 if x >= FIELD_ORDER else 0.
+    let mask = vcgeq_u64(x, FIELD_ORDER);
+    // Adding EPSILON is the same as subtracting FIELD_ORDER.
+    vaddq_u64(x, vandq_u64(mask, EPSILON))
+}
+
+/// Addition u64 + u64 -> u64. Assumes that x + y < 2^64 + FIELD_ORDER. The result is not
+/// necessarily canonical.
+#[inline]
+unsafe fn add_no_double_overflow_64_64(x: uint64x2_t, y: uint64x2_t) -> uint64x2_t {
+    let res_wrapped = vaddq_u64(x, y);
+    let mask = vcgtq_u64(y, res_wrapped); // -1 if overflowed else 0.
+    let wrapback_amt = vandq_u64(mask, EPSILON); // -FIELD_ORDER if overflowed else 0.
+    vaddq_u64(res_wrapped, wrapback_amt)
+}
+
+#[inline]
+unsafe fn add(x: uint64x2_t, y: uint64x2_t) -> uint64x2_t {
+    add_no_double_overflow_64_64(x, canonicalize(y))
+}
+
+#[inline]
+unsafe fn sub(x: uint64x2_t, y: uint64x2_t) -> uint64x2_t {
+    let y = canonicalize(y);
+    let mask = vcgtq_u64(y, x); // -1 if sub will underflow (y > x) else 0.
+    let wrapback_amt = vandq_u64(mask, EPSILON); // -FIELD_ORDER if underflow else 0.
+    let res_wrapped = vsubq_u64(x, y);
+    vsubq_u64(res_wrapped, wrapback_amt)
+}
+
+#[inline]
+unsafe fn neg(y: uint64x2_t) -> uint64x2_t {
+    vsubq_u64(FIELD_ORDER, canonicalize(y))
+}
+
+/// Full 64-bit by 64-bit multiplication.
+#[inline]
+unsafe fn mul64_64(x: uint64x2_t, y: uint64x2_t) -> (uint64x2_t, uint64x2_t) {
+    // Split the operands into their 32-bit halves.
+    let x_lo = vmovn_u64(x);
+    let x_hi = vshrn_n_u64::<32>(x);
+    let y_lo = vmovn_u64(y);
+    let y_hi = vshrn_n_u64::<32>(y);
+
+    let mul_ll = vmull_u32(x_lo, y_lo);
+
+    // Bignum addition
+    // Add the high 32 bits of mul_ll to mul_hl. This cannot overflow.
+    let t0 = vmlal_u32(vshrq_n_u64::<32>(mul_ll), x_hi, y_lo);
+    // Add the low 32 bits of t0 to mul_lh. Again, this cannot overflow.
+    let t1 = vmlal_u32(vandq_u64(t0, EPSILON), x_lo, y_hi);
+    // Add the high 32 bits of t0 to mul_hh.
+    let t2 = vmlal_u32(vshrq_n_u64::<32>(t0), x_hi, y_hi);
+    // Lastly, add the high 32 bits of t1 to t2.
+    let res_hi = vsraq_n_u64::<32>(t2, t1);
+
+    // Form res_lo by inserting the low half of t1 into the high half of mul_ll.
+    let res_lo = vsliq_n_u64::<32>(mul_ll, t1);
+
+    (res_hi, res_lo)
+}
+
+#[inline]
+unsafe fn reduce128(x: (uint64x2_t, uint64x2_t)) -> uint64x2_t {
+    let (hi0, lo0) = x;
+
+    // Subtract the high 32 bits of hi0, since 2^96 = -1 (mod FIELD_ORDER).
+    let hi_hi0 = vshrq_n_u64::<32>(hi0);
+    let borrow_mask = vcgtq_u64(hi_hi0, lo0); // -1 if underflowed else 0.
+    let lo1 = vsubq_u64(vsubq_u64(lo0, hi_hi0), vandq_u64(borrow_mask, EPSILON));
+
+    // Add the low 32 bits of hi0 times EPSILON, since 2^64 = EPSILON (mod FIELD_ORDER).
+    let t1 = vmull_u32(vmovn_u64(hi0), vmovn_u64(EPSILON));
+    add_no_double_overflow_64_64(lo1, t1)
+}
+
+/// Multiply two integers modulo FIELD_ORDER.
+#[inline]
+unsafe fn mul(x: uint64x2_t, y: uint64x2_t) -> uint64x2_t {
+    reduce128(mul64_64(x, y))
+}
+
+/// Square an integer modulo FIELD_ORDER.
+#[inline]
+unsafe fn square(x: uint64x2_t) -> uint64x2_t {
+    reduce128(mul64_64(x, x))
+}
+
+#[inline]
+unsafe fn interleave1(x: uint64x2_t, y: uint64x2_t) -> (uint64x2_t, uint64x2_t) {
+    let a = vtrn1q_u64(x, y);
+    let b = vtrn2q_u64(x, y);
+    (a, b)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::arch::aarch64::neon_goldilocks_field::NeonGoldilocksField;
+    use crate::goldilocks_field::GoldilocksField;
+    use crate::ops::Square;
+    use crate::packed::PackedField;
+    use crate::types::Field;
+
+    fn test_vals_a() -> [GoldilocksField; 2] {
+        [
+            GoldilocksField::from_noncanonical_u64(14479013849828404771),
+            GoldilocksField::from_noncanonical_u64(18446744073709551615),
+        ]
+    }
+    fn test_vals_b() -> [GoldilocksField; 2] {
+        [
+            GoldilocksField::from_noncanonical_u64(17891926589593242302),
+            GoldilocksField::from_noncanonical_u64(18446744069414584321),
+        ]
+    }
+
+    #[test]
+    fn test_add() {
+        let a_arr = test_vals_a();
+        let b_arr = test_vals_b();
+
+        let packed_a = *NeonGoldilocksField::from_slice(&a_arr);
+        let packed_b = *NeonGoldilocksField::from_slice(&b_arr);
+        let packed_res = packed_a + packed_b;
+        let arr_res = packed_res.as_slice();
+
+        let expected = a_arr.iter().zip(b_arr).map(|(&a, b)| a + b);
+        for (exp, &res) in expected.zip(arr_res) {
+            assert_eq!(res, exp);
+        }
+    }
+
+    #[test]
+    fn test_mul() {
+        let a_arr = test_vals_a();
+        let b_arr = test_vals_b();
+
+        let packed_a = *NeonGoldilocksField::from_slice(&a_arr);
+        let packed_b = *NeonGoldilocksField::from_slice(&b_arr);
+        let packed_res = packed_a * packed_b;
+        let arr_res = packed_res.as_slice();
+
+        let expected = a_arr.iter().zip(b_arr).map(|(&a, b)| a * b);
+        for (exp, &res) in expected.zip(arr_res) {
+            assert_eq!(res, exp);
+        }
+    }
+
+    #[test]
+    fn test_square() {
+        let a_arr = test_vals_a();
+
+        let packed_a = *NeonGoldilocksField::from_slice(&a_arr);
+        let packed_res = packed_a.square();
+        let arr_res = packed_res.as_slice();
+
+        let expected = a_arr.iter().map(|&a| a.square());
+        for (exp, &res) in expected.zip(arr_res) {
+            assert_eq!(res, exp);
+        }
+    }
+
+    #[test]
+    fn test_neg() {
+        let a_arr = test_vals_a();
+
+        let packed_a = *NeonGoldilocksField::from_slice(&a_arr);
+        let packed_res = -packed_a;
+        let arr_res = packed_res.as_slice();
+
+        let expected = a_arr.iter().map(|&a| -a);
+        for (exp, &res) in expected.zip(arr_res) {
+            assert_eq!(res, exp);
+        }
+    }
+
+    #[test]
+    fn test_sub() {
+        let a_arr = test_vals_a();
+        let b_arr = test_vals_b();
+
+        let packed_a = *NeonGoldilocksField::from_slice(&a_arr);
+        let packed_b = *NeonGoldilocksField::from_slice(&b_arr);
+        let packed_res = packed_a - packed_b;
+        let arr_res = packed_res.as_slice();
+
+        let expected = a_arr.iter().zip(b_arr).map(|(&a, b)| a - b);
+        for (exp, &res) in expected.zip(arr_res) {
+            assert_eq!(res, exp);
+        }
+    }
+
+    #[test]
+    fn test_interleave_is_involution() {
+        let a_arr = test_vals_a();
+        let b_arr = test_vals_b();
+
+        let packed_a = *NeonGoldilocksField::from_slice(&a_arr);
+        let packed_b = *NeonGoldilocksField::from_slice(&b_arr);
+        {
+            // Interleave, then deinterleave.
+            let (x, y) = packed_a.interleave(packed_b, 1);
+            let (res_a, res_b) = x.interleave(y, 1);
+            assert_eq!(res_a.as_slice(), a_arr);
+            assert_eq!(res_b.as_slice(), b_arr);
+        }
+        {
+            let (x, y) = packed_a.interleave(packed_b, 2);
+            let (res_a, res_b) = x.interleave(y, 2);
+            assert_eq!(res_a.as_slice(), a_arr);
+            assert_eq!(res_b.as_slice(), b_arr);
+        }
+    }
+
+    #[allow(clippy::zero_prefixed_literal)]
+    #[test]
+    fn test_interleave() {
+        let in_a: [GoldilocksField; 2] = [
+            GoldilocksField::from_noncanonical_u64(00),
+            GoldilocksField::from_noncanonical_u64(01),
+        ];
+        let in_b: [GoldilocksField; 2] = [
+            GoldilocksField::from_noncanonical_u64(10),
+            GoldilocksField::from_noncanonical_u64(11),
+        ];
+        let int1_a: [GoldilocksField; 2] = [
+            GoldilocksField::from_noncanonical_u64(00),
+            GoldilocksField::from_noncanonical_u64(10),
+        ];
+        let int1_b: [GoldilocksField; 2] = [
+            GoldilocksField::from_noncanonical_u64(01),
+            GoldilocksField::from_noncanonical_u64(11),
+        ];
+
+        let packed_a = *NeonGoldilocksField::from_slice(&in_a);
+        let packed_b = *NeonGoldilocksField::from_slice(&in_b);
+        {
+            let (x1, y1) = packed_a.interleave(packed_b, 1);
+            assert_eq!(x1.as_slice(), int1_a);
+            assert_eq!(y1.as_slice(), int1_b);
+        }
+        {
+            let (x2, y2) = packed_a.interleave(packed_b, 2);
+            assert_eq!(x2.as_slice(), in_a);
+            assert_eq!(y2.as_slice(), in_b);
+        }
+    }
+}
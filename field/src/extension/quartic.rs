@@ -273,4 +273,24 @@ mod tests {
             >
         );
     }
+
+    mod babybear {
+        use crate::{test_field_arithmetic, test_field_extension};
+
+        test_field_extension!(crate::babybear_field::BabyBearField, 4);
+        test_field_arithmetic!(
+            crate::extension::quartic::QuarticExtension<crate::babybear_field::BabyBearField>
+        );
+    }
+
+    mod mersenne31_complex {
+        use crate::{test_field_arithmetic, test_field_extension};
+
+        test_field_extension!(crate::mersenne31_extensions::Mersenne31ComplexField, 4);
+        test_field_arithmetic!(
+            crate::extension::quartic::QuarticExtension<
+                crate::mersenne31_extensions::Mersenne31ComplexField,
+            >
+        );
+    }
 }
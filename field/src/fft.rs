@@ -211,14 +211,29 @@ mod tests {
 
     use plonky2_util::{log2_ceil, log2_strict};
 
+    use crate::babybear_field::BabyBearField;
     use crate::fft::{fft, fft_with_options, ifft};
     use crate::goldilocks_field::GoldilocksField;
+    use crate::mersenne31_extensions::Mersenne31ComplexField;
     use crate::polynomial::{PolynomialCoeffs, PolynomialValues};
     use crate::types::Field;
 
     #[test]
     fn fft_and_ifft() {
-        type F = GoldilocksField;
+        test_fft_and_ifft::<GoldilocksField>();
+    }
+
+    #[test]
+    fn fft_and_ifft_babybear() {
+        test_fft_and_ifft::<BabyBearField>();
+    }
+
+    #[test]
+    fn fft_and_ifft_mersenne31_complex() {
+        test_fft_and_ifft::<Mersenne31ComplexField>();
+    }
+
+    fn test_fft_and_ifft<F: Field>() {
         let degree = 200usize;
         let degree_padded = degree.next_power_of_two();
 
@@ -0,0 +1,285 @@
+use core::fmt::{self, Debug, Display, Formatter};
+use core::iter::{Product, Sum};
+use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use num::BigUint;
+use serde::{Deserialize, Serialize};
+
+use crate::extension::quartic::QuarticExtension;
+use crate::extension::{Extendable, FieldExtension, Frobenius};
+use crate::mersenne31_field::Mersenne31Field;
+use crate::ops::Square;
+use crate::types::{Field, Sample};
+
+impl Frobenius<1> for Mersenne31Field {}
+
+/// The quadratic extension `F_p[i] / (i^2 + 1)` of the Mersenne31 field, i.e. the "complex"
+/// Mersenne31 numbers.
+///
+/// Since `p = 3 (mod 4)`, `-1` is not a square and this is a field. Its multiplicative group has
+/// order `p^2 - 1 = (p - 1) * 2^31`, which contains the circle group of norm-one elements of order
+/// `p + 1 = 2^31`. This gives a two-adicity of 32, enough for FFTs over the extension.
+///
+/// Mersenne31 has no quartic or quintic binomial extension, since neither 4 nor 5 divides `p - 1`;
+/// larger extensions are instead built on top of this field.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct Mersenne31ComplexField(pub [Mersenne31Field; 2]);
+
+impl Mersenne31ComplexField {
+    /// Returns the complex conjugate `a - b i` of `a + b i`.
+    pub fn conjugate(&self) -> Self {
+        let Self([a, b]) = *self;
+        Self([a, -b])
+    }
+
+    /// Returns the norm `a^2 + b^2` of `a + b i`.
+    pub fn norm(&self) -> Mersenne31Field {
+        let Self([a, b]) = *self;
+        a.square() + b.square()
+    }
+}
+
+impl Default for Mersenne31ComplexField {
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+
+impl FieldExtension<2> for Mersenne31ComplexField {
+    type BaseField = Mersenne31Field;
+
+    fn to_basefield_array(&self) -> [Mersenne31Field; 2] {
+        self.0
+    }
+
+    fn from_basefield_array(arr: [Mersenne31Field; 2]) -> Self {
+        Self(arr)
+    }
+
+    fn from_basefield(x: Mersenne31Field) -> Self {
+        x.into()
+    }
+}
+
+impl From<Mersenne31Field> for Mersenne31ComplexField {
+    fn from(x: Mersenne31Field) -> Self {
+        Self([x, Mersenne31Field::ZERO])
+    }
+}
+
+impl Sample for Mersenne31ComplexField {
+    #[inline]
+    fn sample<R>(rng: &mut R) -> Self
+    where
+        R: rand::RngCore + ?Sized,
+    {
+        Self([Mersenne31Field::sample(rng), Mersenne31Field::sample(rng)])
+    }
+}
+
+impl Field for Mersenne31ComplexField {
+    const ZERO: Self = Self([Mersenne31Field::ZERO; 2]);
+    const ONE: Self = Self([Mersenne31Field::ONE, Mersenne31Field::ZERO]);
+    const TWO: Self = Self([Mersenne31Field::TWO, Mersenne31Field::ZERO]);
+    const NEG_ONE: Self = Self([Mersenne31Field::NEG_ONE, Mersenne31Field::ZERO]);
+
+    // `p^2 - 1 = (p - 1)(p + 1)`, where `p - 1` has a two-adicity of 1 and `p + 1 = 2^31`.
+    const TWO_ADICITY: usize = 32;
+    const CHARACTERISTIC_TWO_ADICITY: usize = Mersenne31Field::TWO_ADICITY;
+
+    // `1 + 12 i`, the first element `a + b i` (ordered by `a`, then `b`) generating the whole
+    // multiplicative group.
+    const MULTIPLICATIVE_GROUP_GENERATOR: Self = Self([Mersenne31Field(1), Mersenne31Field(12)]);
+
+    // MULTIPLICATIVE_GROUP_GENERATOR^((p^2 - 1) / 2^32)
+    const POWER_OF_TWO_GENERATOR: Self =
+        Self([Mersenne31Field(1030187341), Mersenne31Field(980633798)]);
+
+    const BITS: usize = 62;
+
+    fn order() -> BigUint {
+        Mersenne31Field::order() * Mersenne31Field::order()
+    }
+    fn characteristic() -> BigUint {
+        Mersenne31Field::order()
+    }
+
+    fn try_inverse(&self) -> Option<Self> {
+        if self.is_zero() {
+            return None;
+        }
+
+        // (a + b i)^-1 = (a - b i) / (a^2 + b^2)
+        let norm_inv = self.norm().inverse();
+        let Self([a, b]) = self.conjugate();
+        Some(Self([a * norm_inv, b * norm_inv]))
+    }
+
+    fn from_noncanonical_biguint(n: BigUint) -> Self {
+        Mersenne31Field::from_noncanonical_biguint(n).into()
+    }
+
+    fn from_canonical_u64(n: u64) -> Self {
+        Mersenne31Field::from_canonical_u64(n).into()
+    }
+
+    fn from_noncanonical_u128(n: u128) -> Self {
+        Mersenne31Field::from_noncanonical_u128(n).into()
+    }
+
+    fn from_noncanonical_u64(n: u64) -> Self {
+        Mersenne31Field::from_noncanonical_u64(n).into()
+    }
+
+    fn from_noncanonical_i64(n: i64) -> Self {
+        Mersenne31Field::from_noncanonical_i64(n).into()
+    }
+}
+
+impl Display for Mersenne31ComplexField {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} + {}*i", self.0[0], self.0[1])
+    }
+}
+
+impl Debug for Mersenne31ComplexField {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+impl Neg for Mersenne31ComplexField {
+    type Output = Self;
+
+    #[inline]
+    fn neg(self) -> Self {
+        Self([-self.0[0], -self.0[1]])
+    }
+}
+
+impl Add for Mersenne31ComplexField {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, rhs: Self) -> Self {
+        Self([self.0[0] + rhs.0[0], self.0[1] + rhs.0[1]])
+    }
+}
+
+impl AddAssign for Mersenne31ComplexField {
+    #[inline]
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sum for Mersenne31ComplexField {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, |acc, x| acc + x)
+    }
+}
+
+impl Sub for Mersenne31ComplexField {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, rhs: Self) -> Self {
+        Self([self.0[0] - rhs.0[0], self.0[1] - rhs.0[1]])
+    }
+}
+
+impl SubAssign for Mersenne31ComplexField {
+    #[inline]
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl Mul for Mersenne31ComplexField {
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, rhs: Self) -> Self {
+        let Self([a0, a1]) = self;
+        let Self([b0, b1]) = rhs;
+
+        let c0 = a0 * b0 - a1 * b1;
+        let c1 = a0 * b1 + a1 * b0;
+
+        Self([c0, c1])
+    }
+}
+
+impl MulAssign for Mersenne31ComplexField {
+    #[inline]
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl Square for Mersenne31ComplexField {
+    #[inline(always)]
+    fn square(&self) -> Self {
+        let Self([a0, a1]) = *self;
+
+        let c0 = (a0 + a1) * (a0 - a1);
+        let c1 = (a0 * a1).double();
+
+        Self([c0, c1])
+    }
+}
+
+impl Product for Mersenne31ComplexField {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ONE, |acc, x| acc * x)
+    }
+}
+
+impl Div for Mersenne31ComplexField {
+    type Output = Self;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Self::Output {
+        self * rhs.inverse()
+    }
+}
+
+impl DivAssign for Mersenne31ComplexField {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs;
+    }
+}
+
+impl Frobenius<1> for Mersenne31ComplexField {}
+
+impl Extendable<4> for Mersenne31ComplexField {
+    type Extension = QuarticExtension<Self>;
+
+    // `2 + i` is not a square in the complex field, and `4 | p^2 - 1`, so `x^4 - (2 + i)` is
+    // irreducible.
+    const W: Self = Self([Mersenne31Field(2), Mersenne31Field(1)]);
+
+    // DTH_ROOT = W^((p^2 - 1)/4)
+    const DTH_ROOT: Self = Self([Mersenne31Field(0), Mersenne31Field(1)]);
+
+    const EXT_MULTIPLICATIVE_GROUP_GENERATOR: [Self; 4] = [
+        Self([Mersenne31Field(1748087415), Mersenne31Field(896106252)]),
+        Self([Mersenne31Field(1461358280), Mersenne31Field(1680500163)]),
+        Self([Mersenne31Field(160400509), Mersenne31Field(828591754)]),
+        Self([Mersenne31Field(1018633027), Mersenne31Field(1393640229)]),
+    ];
+
+    const EXT_POWER_OF_TWO_GENERATOR: [Self; 4] = [
+        Self::ZERO,
+        Self::ZERO,
+        Self::ZERO,
+        Self([Mersenne31Field(1565747766), Mersenne31Field(584831236)]),
+    ];
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_field_arithmetic;
+
+    test_field_arithmetic!(crate::mersenne31_extensions::Mersenne31ComplexField);
+}
@@ -6,6 +6,7 @@ use plonky2::hash::hash_types::RichField;
 use plonky2::hash::keccak::KeccakHash;
 use plonky2::hash::merkle_tree::MerkleTree;
 use plonky2::hash::poseidon::PoseidonHash;
+use plonky2::hash::poseidon2::Poseidon2Hash;
 use plonky2::plonk::config::Hasher;
 use tynm::type_name;
 
@@ -30,6 +31,7 @@ pub(crate) fn bench_merkle_tree<F: RichField, H: Hasher<F>>(c: &mut Criterion) {
 
 fn criterion_benchmark(c: &mut Criterion) {
     bench_merkle_tree::<GoldilocksField, PoseidonHash>(c);
+    bench_merkle_tree::<GoldilocksField, Poseidon2Hash>(c);
     bench_merkle_tree::<GoldilocksField, KeccakHash<25>>(c);
 }
 
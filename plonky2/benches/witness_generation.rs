@@ -0,0 +1,66 @@
+mod allocator;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use plonky2::field::types::Sample;
+use plonky2::hash::poseidon::PoseidonHash;
+use plonky2::iop::generator::{generate_partial_witness, generate_partial_witness_serial};
+use plonky2::iop::witness::{PartialWitness, WitnessWrite};
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+use plonky2::plonk::circuit_data::CircuitConfig;
+use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+
+const D: usize = 2;
+type C = PoseidonGoldilocksConfig;
+type F = <C as GenericConfig<D>>::F;
+
+/// The length of each chain of hashes.
+const CHAIN_LEN: usize = 16;
+
+pub(crate) fn bench_witness_generation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("witness-generation");
+    group.sample_size(10);
+
+    // Independent chains of Poseidon hashes, each hash depending on the previous one.
+    for num_chains in [16, 64, 256] {
+        let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+        let inputs = builder.add_virtual_targets(4 * num_chains);
+        for chain_inputs in inputs.chunks(4) {
+            let mut hash = builder.hash_n_to_hash_no_pad::<PoseidonHash>(chain_inputs.to_vec());
+            for _ in 1..CHAIN_LEN {
+                hash = builder.hash_n_to_hash_no_pad::<PoseidonHash>(hash.elements.to_vec());
+            }
+            builder.register_public_inputs(&hash.elements);
+        }
+        let data = builder.build_prover::<C>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_target_arr(&inputs, &F::rand_vec(inputs.len()));
+
+        group.bench_with_input(
+            BenchmarkId::new("work-queue", num_chains),
+            &num_chains,
+            |b, _| {
+                b.iter(|| {
+                    generate_partial_witness(pw.clone(), &data.prover_only, &data.common).unwrap()
+                })
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("serial", num_chains),
+            &num_chains,
+            |b, _| {
+                b.iter(|| {
+                    generate_partial_witness_serial(pw.clone(), &data.prover_only, &data.common)
+                        .unwrap()
+                })
+            },
+        );
+    }
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    bench_witness_generation(c);
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,50 @@
+use serde::{Deserialize, Serialize};
+
+use crate::curve::curve_types::{AffinePoint, Curve};
+use crate::field::bn254_base::Bn254Base;
+use crate::field::bn254_scalar::Bn254Scalar;
+use crate::field::types::Field;
+
+/// The BN254 curve, `y^2 = x^3 + 3`, also known as alt_bn128. This is the group `G1` of the
+/// pairing used by Ethereum's precompiles; `G2` lives on a twist, see `bn254_pairing`.
+#[derive(Debug, Copy, Clone, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct Bn254;
+
+impl Curve for Bn254 {
+    type BaseField = Bn254Base;
+    type ScalarField = Bn254Scalar;
+
+    const A: Bn254Base = Bn254Base::ZERO;
+    const B: Bn254Base = Bn254Base([3, 0, 0, 0]);
+    const GENERATOR_AFFINE: AffinePoint<Self> = AffinePoint {
+        x: Bn254Base([1, 0, 0, 0]),
+        y: Bn254Base([2, 0, 0, 0]),
+        zero: false,
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::curve::bn254::Bn254;
+    use crate::curve::curve_types::{Curve, CurveScalar, ProjectivePoint};
+    use crate::field::bn254_scalar::Bn254Scalar;
+    use crate::field::types::{Field, Sample};
+
+    #[test]
+    fn test_generator() {
+        assert!(Bn254::GENERATOR_AFFINE.is_valid());
+        assert!((-Bn254::GENERATOR_AFFINE).is_valid());
+    }
+
+    #[test]
+    fn test_group_order() {
+        let g = Bn254::GENERATOR_PROJECTIVE;
+        let order = CurveScalar::<Bn254>(Bn254Scalar::NEG_ONE) * g + g;
+        assert_eq!(order, ProjectivePoint::ZERO);
+
+        let x = Bn254Scalar::rand();
+        let y = Bn254Scalar::rand();
+        let lhs = CurveScalar(x) * g + CurveScalar(y) * g;
+        assert_eq!(lhs, CurveScalar(x + y) * g);
+    }
+}
@@ -0,0 +1,585 @@
+//! Native reference implementation of the BN254 pairing, mirroring the `bn_tate` code used by the
+//! EVM kernel. It is used to generate witnesses for, and to test, the in-circuit pairing gadgets.
+
+use alloc::vec::Vec;
+use core::ops::{Add, Div, Mul, Neg, Sub};
+
+use num::{BigUint, Integer, One};
+
+use crate::curve::bn254::Bn254;
+use crate::curve::curve_types::{AffinePoint, Curve, CurveScalar};
+use crate::field::bn254_base::Bn254Base;
+use crate::field::bn254_scalar::Bn254Scalar;
+use crate::field::ops::Square;
+use crate::field::types::{Field, PrimeField, Sample};
+
+/// The degree of `Fp12` over the base field.
+pub const FP12_DEGREE: usize = 12;
+
+/// An element `re + im * i` of `Fp2 = Fp[i] / (i^2 + 1)`.
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Fp2 {
+    pub re: Bn254Base,
+    pub im: Bn254Base,
+}
+
+impl Fp2 {
+    pub const ZERO: Self = Self {
+        re: Bn254Base::ZERO,
+        im: Bn254Base::ZERO,
+    };
+    pub const ONE: Self = Self {
+        re: Bn254Base::ONE,
+        im: Bn254Base::ZERO,
+    };
+
+    pub const fn new(re: Bn254Base, im: Bn254Base) -> Self {
+        Self { re, im }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.re.is_zero() && self.im.is_zero()
+    }
+
+    pub fn scale(&self, c: Bn254Base) -> Self {
+        Self::new(self.re * c, self.im * c)
+    }
+
+    pub fn double(&self) -> Self {
+        *self + *self
+    }
+
+    pub fn square(&self) -> Self {
+        *self * *self
+    }
+
+    pub fn inverse(&self) -> Self {
+        let norm_inv = (self.re.square() + self.im.square()).inverse();
+        Self::new(self.re * norm_inv, -self.im * norm_inv)
+    }
+}
+
+impl Add for Fp2 {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self::new(self.re + rhs.re, self.im + rhs.im)
+    }
+}
+
+impl Sub for Fp2 {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self::new(self.re - rhs.re, self.im - rhs.im)
+    }
+}
+
+impl Mul for Fp2 {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Self::new(
+            self.re * rhs.re - self.im * rhs.im,
+            self.re * rhs.im + self.im * rhs.re,
+        )
+    }
+}
+
+impl Div for Fp2 {
+    type Output = Self;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Self {
+        self * rhs.inverse()
+    }
+}
+
+impl Neg for Fp2 {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.re, -self.im)
+    }
+}
+
+impl Sample for Fp2 {
+    fn sample<R>(rng: &mut R) -> Self
+    where
+        R: rand::RngCore + ?Sized,
+    {
+        Self::new(Bn254Base::sample(rng), Bn254Base::sample(rng))
+    }
+}
+
+/// An element of `Fp12`, represented by its coefficients in the basis `1, w, ..., w^11` of
+/// `Fp[w] / (w^12 - 18 w^6 + 82)`.
+///
+/// This is the same field as the usual tower `Fp6 = Fp2[v] / (v^3 - (9 + i))`,
+/// `Fp12 = Fp6[w] / (w^2 - v)`, under the identification `i = w^6 - 9`. A flat basis makes every
+/// product coefficient a signed inner product of the inputs, which is what the circuit needs.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Fp12 {
+    pub coeffs: [Bn254Base; FP12_DEGREE],
+}
+
+impl Fp12 {
+    pub const ZERO: Self = Self {
+        coeffs: [Bn254Base::ZERO; FP12_DEGREE],
+    };
+
+    pub const ONE: Self = {
+        let mut coeffs = [Bn254Base::ZERO; FP12_DEGREE];
+        coeffs[0] = Bn254Base::ONE;
+        Self { coeffs }
+    };
+
+    /// Returns `x * w^k`, for `k < 6`.
+    pub fn from_fp2(x: Fp2, k: usize) -> Self {
+        debug_assert!(k < FP12_DEGREE / 2);
+        let mut coeffs = [Bn254Base::ZERO; FP12_DEGREE];
+        coeffs[k] = x.re - Bn254Base::from_canonical_u64(9) * x.im;
+        coeffs[k + FP12_DEGREE / 2] = x.im;
+        Self { coeffs }
+    }
+
+    /// Returns `w`.
+    fn w() -> Self {
+        let mut coeffs = [Bn254Base::ZERO; FP12_DEGREE];
+        coeffs[1] = Bn254Base::ONE;
+        Self { coeffs }
+    }
+
+    pub fn square(&self) -> Self {
+        *self * *self
+    }
+
+    pub fn exp_biguint(&self, power: &BigUint) -> Self {
+        let mut result = Self::ONE;
+        for i in (0..power.bits()).rev() {
+            result = result.square();
+            if power.bit(i) {
+                result = result * *self;
+            }
+        }
+        result
+    }
+
+    /// Returns the inverse of a nonzero element, by solving the linear system `self * x = 1`.
+    pub fn inverse(&self) -> Self {
+        // Column `j` of the system holds the coefficients of `self * w^j`.
+        let mut columns = Vec::with_capacity(FP12_DEGREE);
+        let mut column = *self;
+        for _ in 0..FP12_DEGREE {
+            columns.push(column);
+            column = column * Self::w();
+        }
+        let mut rows: Vec<Vec<Bn254Base>> = (0..FP12_DEGREE)
+            .map(|i| {
+                let mut row: Vec<_> = columns.iter().map(|c| c.coeffs[i]).collect();
+                row.push(Self::ONE.coeffs[i]);
+                row
+            })
+            .collect();
+
+        for col in 0..FP12_DEGREE {
+            let pivot = (col..FP12_DEGREE)
+                .find(|&r| rows[r][col].is_nonzero())
+                .expect("Cannot invert zero");
+            rows.swap(col, pivot);
+            let pivot_inv = rows[col][col].inverse();
+            for x in rows[col].iter_mut() {
+                *x *= pivot_inv;
+            }
+            for r in 0..FP12_DEGREE {
+                if r != col && rows[r][col].is_nonzero() {
+                    let factor = rows[r][col];
+                    for c in col..=FP12_DEGREE {
+                        let delta = factor * rows[col][c];
+                        rows[r][c] -= delta;
+                    }
+                }
+            }
+        }
+
+        Self {
+            coeffs: core::array::from_fn(|i| rows[i][FP12_DEGREE]),
+        }
+    }
+
+    /// Returns `self^(p^n)`.
+    pub fn frob(&self, n: usize) -> Self {
+        let matrix = frobenius_matrix(n);
+        Self {
+            coeffs: core::array::from_fn(|i| {
+                (0..FP12_DEGREE)
+                    .map(|j| matrix[j].coeffs[i] * self.coeffs[j])
+                    .sum()
+            }),
+        }
+    }
+}
+
+impl Mul for Fp12 {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        let mut product = [Bn254Base::ZERO; 2 * FP12_DEGREE - 1];
+        for (i, &a) in self.coeffs.iter().enumerate() {
+            for (j, &b) in rhs.coeffs.iter().enumerate() {
+                product[i + j] += a * b;
+            }
+        }
+
+        // Reduce using `w^12 = 18 w^6 - 82`.
+        let eighteen = Bn254Base::from_canonical_u64(18);
+        let eighty_two = Bn254Base::from_canonical_u64(82);
+        for k in (FP12_DEGREE..2 * FP12_DEGREE - 1).rev() {
+            let c = product[k];
+            product[k - 6] += eighteen * c;
+            product[k - 12] -= eighty_two * c;
+        }
+
+        Self {
+            coeffs: core::array::from_fn(|i| product[i]),
+        }
+    }
+}
+
+impl Div for Fp12 {
+    type Output = Self;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Self {
+        self * rhs.inverse()
+    }
+}
+
+impl Sample for Fp12 {
+    fn sample<R>(rng: &mut R) -> Self
+    where
+        R: rand::RngCore + ?Sized,
+    {
+        Self {
+            coeffs: core::array::from_fn(|_| Bn254Base::sample(rng)),
+        }
+    }
+}
+
+/// Returns the images `(w^j)^(p^n)` of the basis elements under the `n`-th Frobenius map. Since the
+/// map is linear over `Fp`, these determine it completely.
+pub fn frobenius_matrix(n: usize) -> [Fp12; FP12_DEGREE] {
+    // `x^(p^12) = x` for all `x`, so only `n mod 12` matters.
+    let power = Bn254Base::order().pow((n % FP12_DEGREE) as u32);
+    let image_of_w = Fp12::w().exp_biguint(&power);
+
+    let mut matrix = [Fp12::ONE; FP12_DEGREE];
+    for j in 1..FP12_DEGREE {
+        matrix[j] = matrix[j - 1] * image_of_w;
+    }
+    matrix
+}
+
+/// Returns the coefficient `b = 3 / (9 + i)` of the twist `y^2 = x^3 + b`, on which `G2` lives.
+pub fn g2_curve_b() -> Fp2 {
+    Fp2::new(Bn254Base::from_canonical_u64(3), Bn254Base::ZERO)
+        / Fp2::new(Bn254Base::from_canonical_u64(9), Bn254Base::ONE)
+}
+
+/// A point of `G2`, on the twist `y^2 = x^3 + 3 / (9 + i)` over `Fp2`, in affine coordinates.
+#[derive(Copy, Clone, Debug)]
+pub struct G2Point {
+    pub x: Fp2,
+    pub y: Fp2,
+    pub zero: bool,
+}
+
+impl G2Point {
+    pub const ZERO: Self = Self {
+        x: Fp2::ZERO,
+        y: Fp2::ZERO,
+        zero: true,
+    };
+
+    pub const GENERATOR: Self = Self {
+        x: Fp2::new(
+            Bn254Base([
+                0x46debd5cd992f6ed,
+                0x674322d4f75edadd,
+                0x426a00665e5c4479,
+                0x1800deef121f1e76,
+            ]),
+            Bn254Base([
+                0x97e485b7aef312c2,
+                0xf1aa493335a9e712,
+                0x7260bfb731fb5d25,
+                0x198e9393920d483a,
+            ]),
+        ),
+        y: Fp2::new(
+            Bn254Base([
+                0x4ce6cc0166fa7daa,
+                0xe3d1e7690c43d37b,
+                0x4aab71808dcb408f,
+                0x12c85ea5db8c6deb,
+            ]),
+            Bn254Base([
+                0x55acdadcd122975b,
+                0xbc4b313370b38ef3,
+                0xec9e99ad690c3395,
+                0x090689d0585ff075,
+            ]),
+        ),
+        zero: false,
+    };
+
+    pub fn is_valid(&self) -> bool {
+        self.zero || self.y.square() == self.x.square() * self.x + g2_curve_b()
+    }
+
+    pub fn double(&self) -> Self {
+        if self.zero || self.y.is_zero() {
+            return Self::ZERO;
+        }
+        let three = Bn254Base::from_canonical_u64(3);
+        let lambda = self.x.square().scale(three) / self.y.double();
+        let x3 = lambda.square() - self.x.double();
+        let y3 = lambda * (self.x - x3) - self.y;
+        Self {
+            x: x3,
+            y: y3,
+            zero: false,
+        }
+    }
+
+    /// Multiplies the point by a scalar using double-and-add.
+    pub fn mul_scalar(&self, scalar: Bn254Scalar) -> Self {
+        let scalar = scalar.to_canonical_biguint();
+        let mut result = Self::ZERO;
+        for i in (0..scalar.bits()).rev() {
+            result = result.double();
+            if scalar.bit(i) {
+                result = result + *self;
+            }
+        }
+        result
+    }
+}
+
+impl PartialEq for G2Point {
+    fn eq(&self, other: &Self) -> bool {
+        if self.zero || other.zero {
+            return self.zero == other.zero;
+        }
+        self.x == other.x && self.y == other.y
+    }
+}
+
+impl Eq for G2Point {}
+
+impl Add for G2Point {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        if self.zero {
+            return rhs;
+        }
+        if rhs.zero {
+            return self;
+        }
+        if self.x == rhs.x {
+            return if self.y == rhs.y {
+                self.double()
+            } else {
+                Self::ZERO
+            };
+        }
+        let lambda = (rhs.y - self.y) / (rhs.x - self.x);
+        let x3 = lambda.square() - self.x - rhs.x;
+        let y3 = lambda * (self.x - x3) - self.y;
+        Self {
+            x: x3,
+            y: y3,
+            zero: false,
+        }
+    }
+}
+
+impl Neg for G2Point {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self {
+            x: self.x,
+            y: -self.y,
+            zero: self.zero,
+        }
+    }
+}
+
+/// The pairing of a point of `G1` with a point of `G2`.
+pub fn bn_tate(p: AffinePoint<Bn254>, q: G2Point) -> Fp12 {
+    bn_final_exponent(bn_miller_loop(p, q))
+}
+
+/// The Miller loop over the bits of the group order, accumulating multiples of `p` and evaluating
+/// the tangent and chord lines at `q`.
+pub fn bn_miller_loop(p: AffinePoint<Bn254>, q: G2Point) -> Fp12 {
+    let order = Bn254Scalar::order();
+    let mut r = p;
+    let mut acc = Fp12::ONE;
+    for i in (0..order.bits() - 1).rev() {
+        acc = bn_tangent(r, q) * acc.square();
+        r = r.double();
+        // The last step would add the vertical line through `-p` and `p`, which is eliminated by
+        // the final exponentiation, so it is skipped.
+        if order.bit(i) && i != 0 {
+            acc = bn_chord(p, r, q) * acc;
+            r = (r.to_projective() + p).to_affine();
+        }
+    }
+    acc
+}
+
+/// The line tangent to the curve at `p`, evaluated at `q`.
+pub fn bn_tangent(p: AffinePoint<Bn254>, q: G2Point) -> Fp12 {
+    let cx = -Bn254Base::from_canonical_u64(3) * p.x.square();
+    let cy = p.y.double();
+    bn_sparse_embed(
+        p.y.square() - Bn254Base::from_canonical_u64(9),
+        q.x.scale(cx),
+        q.y.scale(cy),
+    )
+}
+
+/// The line through `p1` and `p2`, evaluated at `q`.
+pub fn bn_chord(p1: AffinePoint<Bn254>, p2: AffinePoint<Bn254>, q: G2Point) -> Fp12 {
+    let cx = p2.y - p1.y;
+    let cy = p1.x - p2.x;
+    bn_sparse_embed(p1.y * p2.x - p2.y * p1.x, q.x.scale(cx), q.y.scale(cy))
+}
+
+/// Returns `g000 + g01 * w^2 + g11 * w^3`, the shape of every line evaluation.
+pub fn bn_sparse_embed(g000: Bn254Base, g01: Fp2, g11: Fp2) -> Fp12 {
+    let mut result = Fp12::from_fp2(g01, 2);
+    let g11 = Fp12::from_fp2(g11, 3);
+    for (r, g) in result.coeffs.iter_mut().zip(g11.coeffs) {
+        *r += g;
+    }
+    result.coeffs[0] = g000;
+    result
+}
+
+/// Returns the base-`p` digits `[d0, d1, d2, d3]` of `(p^4 - p^2 + 1) / r`, the hard part of the
+/// final exponent.
+pub fn bn_hard_part_digits() -> [BigUint; 4] {
+    let p = Bn254Base::order();
+    let r = Bn254Scalar::order();
+    let p2 = &p * &p;
+    let (mut e, rem) = (&p2 * &p2 - &p2 + BigUint::one()).div_rem(&r);
+    debug_assert!(rem == BigUint::default());
+
+    core::array::from_fn(|_| {
+        let (q, d) = e.div_rem(&p);
+        e = q;
+        d
+    })
+}
+
+/// Raises the output of the Miller loop to the power `(p^12 - 1) / r`, which is split as
+/// `(p^6 - 1) (p^2 + 1) (p^4 - p^2 + 1) / r`.
+pub fn bn_final_exponent(f: Fp12) -> Fp12 {
+    let mut y = f.frob(6) / f;
+    y = y.frob(2) * y;
+
+    let [d0, d1, d2, d3] = bn_hard_part_digits();
+    y.exp_biguint(&d3).frob(3)
+        * y.exp_biguint(&d2).frob(2)
+        * y.exp_biguint(&d1).frob(1)
+        * y.exp_biguint(&d0)
+}
+
+/// Returns a random point of `G2`.
+pub fn g2_rand() -> G2Point {
+    G2Point::GENERATOR.mul_scalar(Bn254Scalar::rand())
+}
+
+/// Returns a random point of `G1`.
+pub fn g1_rand() -> AffinePoint<Bn254> {
+    (CurveScalar(Bn254Scalar::rand()) * Bn254::GENERATOR_PROJECTIVE).to_affine()
+}
+
+#[cfg(test)]
+mod tests {
+    use num::BigUint;
+
+    use crate::curve::bn254::Bn254;
+    use crate::curve::bn254_pairing::{
+        bn_final_exponent, bn_hard_part_digits, bn_tate, Fp12, Fp2, G2Point,
+    };
+    use crate::curve::curve_types::{Curve, CurveScalar};
+    use crate::field::bn254_base::Bn254Base;
+    use crate::field::bn254_scalar::Bn254Scalar;
+    use crate::field::types::{Field, PrimeField, Sample};
+
+    #[test]
+    fn test_fp12_arithmetic() {
+        let a = Fp12::rand();
+        let b = Fp12::rand();
+        assert_eq!(a * a.inverse(), Fp12::ONE);
+        assert_eq!((a * b) / b, a);
+
+        let x = Fp2::rand();
+        let y = Fp2::rand();
+        assert_eq!(
+            Fp12::from_fp2(x, 0) * Fp12::from_fp2(y, 0),
+            Fp12::from_fp2(x * y, 0)
+        );
+
+        assert_eq!(a.frob(1), a.exp_biguint(&Bn254Base::order()));
+        assert_eq!(a.frob(12), a);
+        assert_eq!(a.frob(6).frob(6), a);
+        assert_eq!((a * b).frob(2), a.frob(2) * b.frob(2));
+    }
+
+    #[test]
+    fn test_g2_group_order() {
+        let g = G2Point::GENERATOR;
+        assert!(g.is_valid());
+        assert_eq!(g.mul_scalar(Bn254Scalar::NEG_ONE) + g, G2Point::ZERO);
+
+        let x = Bn254Scalar::rand();
+        let y = Bn254Scalar::rand();
+        let sum = g.mul_scalar(x) + g.mul_scalar(y);
+        assert!(sum.is_valid());
+        assert_eq!(sum, g.mul_scalar(x + y));
+    }
+
+    #[test]
+    fn test_final_exponent() {
+        let [_, _, _, d3] = bn_hard_part_digits();
+        assert_eq!(d3, BigUint::from(1u32));
+
+        let f = Fp12::rand();
+        let p = Bn254Base::order();
+        let exponent = (p.pow(12) - 1u32) / Bn254Scalar::order();
+        assert_eq!(bn_final_exponent(f), f.exp_biguint(&exponent));
+    }
+
+    #[test]
+    fn test_bn_tate_bilinearity() {
+        let p = Bn254::GENERATOR_AFFINE;
+        let q = G2Point::GENERATOR;
+        let a = Bn254Scalar::rand();
+        let b = Bn254Scalar::rand();
+
+        let base = bn_tate(p, q);
+        assert_ne!(base, Fp12::ONE);
+        assert_eq!(base.exp_biguint(&Bn254Scalar::order()), Fp12::ONE);
+
+        let a_p = (CurveScalar(a) * Bn254::GENERATOR_PROJECTIVE).to_affine();
+        let b_q = q.mul_scalar(b);
+        let expected = base.exp_biguint(&(a * b).to_canonical_biguint());
+        assert_eq!(bn_tate(a_p, b_q), expected);
+    }
+}
@@ -0,0 +1,89 @@
+use serde::{Deserialize, Serialize};
+
+use crate::curve::curve_types::{base_to_scalar, AffinePoint, Curve, CurveScalar};
+use crate::field::types::{Field, Sample};
+
+#[derive(Copy, Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct ECDSASignature<C: Curve> {
+    pub r: C::ScalarField,
+    pub s: C::ScalarField,
+}
+
+#[derive(Copy, Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct ECDSASecretKey<C: Curve>(pub C::ScalarField);
+
+impl<C: Curve> ECDSASecretKey<C> {
+    pub fn to_public(&self) -> ECDSAPublicKey<C> {
+        ECDSAPublicKey((CurveScalar(self.0) * C::GENERATOR_PROJECTIVE).to_affine())
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct ECDSAPublicKey<C: Curve>(pub AffinePoint<C>);
+
+/// Signs the (already hashed) message `msg` with a fresh random nonce.
+pub fn sign_message<C: Curve>(msg: C::ScalarField, sk: ECDSASecretKey<C>) -> ECDSASignature<C> {
+    loop {
+        let k = C::ScalarField::rand();
+        let rr = (CurveScalar(k) * C::GENERATOR_PROJECTIVE).to_affine();
+        let r = base_to_scalar::<C>(rr.x);
+        if r.is_zero() {
+            continue;
+        }
+
+        let s = k.inverse() * (msg + r * sk.0);
+        if s.is_zero() {
+            continue;
+        }
+
+        return ECDSASignature { r, s };
+    }
+}
+
+/// Checks that `sig` is a valid signature of the (already hashed) message `msg` under `pk`.
+pub fn verify_message<C: Curve>(
+    msg: C::ScalarField,
+    sig: ECDSASignature<C>,
+    pk: ECDSAPublicKey<C>,
+) -> bool {
+    let ECDSASignature { r, s } = sig;
+    if r.is_zero() || s.is_zero() || pk.0.zero || !pk.0.is_valid() {
+        return false;
+    }
+
+    let c = s.inverse();
+    let u1 = msg * c;
+    let u2 = r * c;
+
+    let g = C::GENERATOR_PROJECTIVE;
+    let point = CurveScalar(u1) * g + CurveScalar(u2) * pk.0.to_projective();
+    let point = point.to_affine();
+    if point.zero {
+        return false;
+    }
+
+    base_to_scalar::<C>(point.x) == r
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::curve::ecdsa::{sign_message, verify_message, ECDSASecretKey};
+    use crate::curve::secp256k1::Secp256K1;
+    use crate::field::secp256k1_scalar::Secp256K1Scalar;
+    use crate::field::types::{Field, Sample};
+
+    #[test]
+    fn test_ecdsa_native() {
+        type C = Secp256K1;
+
+        let msg = Secp256K1Scalar::rand();
+        let sk = ECDSASecretKey::<C>(Secp256K1Scalar::rand());
+        let pk = sk.to_public();
+
+        let sig = sign_message(msg, sk);
+        assert!(verify_message(msg, sig, pk));
+
+        let wrong_msg = msg + Secp256K1Scalar::ONE;
+        assert!(!verify_message(wrong_msg, sig, pk));
+    }
+}
@@ -0,0 +1,5 @@
+pub mod bn254;
+pub mod bn254_pairing;
+pub mod curve_types;
+pub mod ecdsa;
+pub mod secp256k1;
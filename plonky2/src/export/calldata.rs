@@ -0,0 +1,734 @@
+//! A flat encoding of plonky2 proofs as `uint64` words, as taken by the contracts generated in
+//! `export::solidity`, together with a reference implementation of the checks those contracts
+//! perform on it.
+//!
+//! Only `KeccakGoldilocksConfig` proofs of circuits without lookups or zero-knowledge are
+//! supported. Every digest is encoded as the 4 field elements the challenger observes it as, i.e.
+//! its 7-byte chunks, and every extension field element as its 2 base field components.
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::iter::once;
+use core::ops::Range;
+
+use anyhow::{ensure, Result};
+use itertools::Itertools;
+
+use crate::export::constraint_program::ConstraintProgram;
+use crate::field::extension::quadratic::QuadraticExtension;
+use crate::field::extension::{flatten, FieldExtension};
+use crate::field::goldilocks_field::GoldilocksField;
+use crate::field::types::{Field, Field64, PrimeField64};
+use crate::fri::proof::FriChallenges;
+use crate::hash::hash_types::{BytesHash, HashOut};
+use crate::hash::merkle_proofs::{verify_merkle_proof_to_cap, MerkleProof};
+use crate::hash::merkle_tree::MerkleCap;
+use crate::hash::poseidon::PoseidonHash;
+use crate::iop::challenger::Challenger;
+use crate::plonk::circuit_data::{CommonCircuitData, VerifierCircuitData};
+use crate::plonk::config::{GenericConfig, GenericHashOut, Hasher, KeccakGoldilocksConfig};
+use crate::plonk::proof::{ProofChallenges, ProofWithPublicInputs};
+use crate::plonk::validate_shape::validate_proof_with_pis_shape;
+use crate::plonk::vars::EvaluationVars;
+use crate::util::reverse_bits;
+
+const D: usize = 2;
+type C = KeccakGoldilocksConfig;
+type F = GoldilocksField;
+type FE = QuadraticExtension<F>;
+type H = <C as GenericConfig<D>>::Hasher;
+
+/// The number of words encoding a digest.
+pub const HASH_WORDS: usize = 4;
+
+/// The word offsets of each part of an encoded proof. Offsets of the per-query data are relative to
+/// the start of the query round.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CalldataLayout {
+    /// The number of digests in each Merkle cap.
+    pub num_cap_hashes: usize,
+    pub wires_cap: usize,
+    pub zs_partial_products_cap: usize,
+    pub quotient_polys_cap: usize,
+    /// The openings at `zeta`, in the order of `OpeningSet::to_fri_openings`, followed by the
+    /// openings of the `Z`s at `g * zeta`.
+    pub openings: usize,
+    pub num_zeta_openings: usize,
+    pub num_next_openings: usize,
+    pub commit_phase_caps: usize,
+    pub query_rounds: usize,
+    pub query_round_len: usize,
+    /// The number of polynomials committed to by each of the four initial oracles.
+    pub oracle_sizes: [usize; 4],
+    /// The number of siblings in the Merkle proofs of the initial oracles.
+    pub initial_siblings: usize,
+    pub reduction_arity_bits: Vec<usize>,
+    /// The number of siblings in the Merkle proof of each FRI reduction step.
+    pub step_siblings: Vec<usize>,
+    pub final_poly: usize,
+    pub final_poly_len: usize,
+    pub pow_witness: usize,
+    pub len: usize,
+}
+
+impl CalldataLayout {
+    pub fn new(common_data: &CommonCircuitData<F, D>) -> Result<Self> {
+        ensure!(
+            common_data.num_lookup_polys == 0,
+            "Lookups are not supported by exported verifiers."
+        );
+        let fri_params = &common_data.fri_params;
+        ensure!(
+            !fri_params.hiding,
+            "Zero-knowledge proofs are not supported by exported verifiers."
+        );
+
+        let cap_height = fri_params.config.cap_height;
+        let num_cap_hashes = 1 << cap_height;
+        let cap_len = num_cap_hashes * HASH_WORDS;
+        let oracle_sizes = [
+            common_data.num_preprocessed_polys(),
+            common_data.config.num_wires,
+            common_data.num_zs_partial_products_polys(),
+            common_data.num_quotient_polys(),
+        ];
+        let num_zeta_openings = oracle_sizes.iter().sum();
+        let num_next_openings = common_data.config.num_challenges;
+
+        let lde_bits = fri_params.lde_bits();
+        let initial_siblings = lde_bits - cap_height;
+        let reduction_arity_bits = fri_params.reduction_arity_bits.clone();
+        let step_siblings = reduction_arity_bits
+            .iter()
+            .scan(lde_bits, |bits, &arity_bits| {
+                *bits -= arity_bits;
+                Some(*bits - cap_height)
+            })
+            .collect::<Vec<_>>();
+        let query_round_len = oracle_sizes
+            .iter()
+            .map(|&size| size + initial_siblings * HASH_WORDS)
+            .chain(
+                reduction_arity_bits
+                    .iter()
+                    .zip(&step_siblings)
+                    .map(|(&arity_bits, &siblings)| (1 << arity_bits) * D + siblings * HASH_WORDS),
+            )
+            .sum();
+
+        let wires_cap = 0;
+        let zs_partial_products_cap = wires_cap + cap_len;
+        let quotient_polys_cap = zs_partial_products_cap + cap_len;
+        let openings = quotient_polys_cap + cap_len;
+        let commit_phase_caps = openings + (num_zeta_openings + num_next_openings) * D;
+        let query_rounds = commit_phase_caps + reduction_arity_bits.len() * cap_len;
+        let final_poly = query_rounds + fri_params.config.num_query_rounds * query_round_len;
+        let final_poly_len = fri_params.final_poly_len();
+        let pow_witness = final_poly + final_poly_len * D;
+
+        Ok(Self {
+            num_cap_hashes,
+            wires_cap,
+            zs_partial_products_cap,
+            quotient_polys_cap,
+            openings,
+            num_zeta_openings,
+            num_next_openings,
+            commit_phase_caps,
+            query_rounds,
+            query_round_len,
+            oracle_sizes,
+            initial_siblings,
+            reduction_arity_bits,
+            step_siblings,
+            final_poly,
+            final_poly_len,
+            pow_witness,
+            len: pow_witness + 1,
+        })
+    }
+
+    pub fn cap_len(&self) -> usize {
+        self.num_cap_hashes * HASH_WORDS
+    }
+
+    /// The offset of the leaf opened from the `i`-th initial oracle, relative to its query round.
+    /// The leaf's Merkle proof follows it.
+    pub fn initial_leaf(&self, i: usize) -> usize {
+        self.oracle_sizes[..i]
+            .iter()
+            .map(|&size| size + self.initial_siblings * HASH_WORDS)
+            .sum()
+    }
+
+    /// The offset of the evaluations opened in the `i`-th reduction step, relative to its query
+    /// round. The step's Merkle proof follows them.
+    pub fn step_evals(&self, i: usize) -> usize {
+        self.initial_leaf(self.oracle_sizes.len())
+            + self.reduction_arity_bits[..i]
+                .iter()
+                .zip(&self.step_siblings)
+                .map(|(&arity_bits, &siblings)| (1 << arity_bits) * D + siblings * HASH_WORDS)
+                .sum::<usize>()
+    }
+}
+
+/// Encodes a proof in the layout given by `CalldataLayout`.
+pub fn proof_to_calldata(
+    proof_with_pis: &ProofWithPublicInputs<F, C, D>,
+    common_data: &CommonCircuitData<F, D>,
+) -> Result<Vec<u64>> {
+    let layout = CalldataLayout::new(common_data)?;
+    validate_proof_with_pis_shape(proof_with_pis, common_data)?;
+
+    let proof = &proof_with_pis.proof;
+    let fri_proof = &proof.opening_proof;
+    let mut calldata = Vec::with_capacity(layout.len);
+    let mut write_elements = |elements: &[F]| {
+        calldata.extend(elements.iter().map(F::to_canonical_u64));
+    };
+
+    for cap in [
+        &proof.wires_cap,
+        &proof.plonk_zs_partial_products_cap,
+        &proof.quotient_polys_cap,
+    ] {
+        cap.0.iter().for_each(|h| write_elements(&h.to_vec()));
+    }
+    for batch in proof.openings.to_fri_openings().batches {
+        write_elements(&flatten::<F, D>(&batch.values));
+    }
+    for cap in &fri_proof.commit_phase_merkle_caps {
+        cap.0.iter().for_each(|h| write_elements(&h.to_vec()));
+    }
+    for round in &fri_proof.query_round_proofs {
+        for (evals, merkle_proof) in &round.initial_trees_proof.evals_proofs {
+            write_elements(evals);
+            merkle_proof
+                .siblings
+                .iter()
+                .for_each(|h| write_elements(&h.to_vec()));
+        }
+        for step in &round.steps {
+            write_elements(&flatten::<F, D>(&step.evals));
+            step.merkle_proof
+                .siblings
+                .iter()
+                .for_each(|h| write_elements(&h.to_vec()));
+        }
+    }
+    write_elements(&flatten::<F, D>(&fri_proof.final_poly.coeffs));
+    write_elements(&[fri_proof.pow_witness]);
+
+    ensure!(calldata.len() == layout.len);
+    Ok(calldata)
+}
+
+/// Performs the checks of the generated verifier contract on an encoded proof. This mirrors the
+/// contract step by step, so that the encoding and the contract's reformulations of the verifier
+/// can be tested without an EVM.
+pub fn verify_calldata(
+    verifier_data: &VerifierCircuitData<F, C, D>,
+    calldata: &[u64],
+    public_inputs: &[u64],
+) -> Result<()> {
+    let common_data = &verifier_data.common;
+    let layout = CalldataLayout::new(common_data)?;
+    ensure!(calldata.len() == layout.len, "Invalid proof length.");
+    ensure!(
+        public_inputs.len() == common_data.num_public_inputs,
+        "Invalid number of public inputs."
+    );
+    ensure!(
+        calldata.iter().chain(public_inputs).all(|&w| w < F::ORDER),
+        "Non-canonical field element."
+    );
+
+    let reader = CalldataReader {
+        calldata,
+        layout: &layout,
+    };
+    let public_inputs = public_inputs
+        .iter()
+        .map(|&w| F::from_canonical_u64(w))
+        .collect::<Vec<_>>();
+    let public_inputs_hash = PoseidonHash::hash_no_pad(&public_inputs);
+    let challenges = reader.get_challenges(verifier_data, public_inputs_hash);
+
+    let openings = reader.extension_elements(
+        layout.openings,
+        layout.num_zeta_openings + layout.num_next_openings,
+    );
+    verify_vanishing_poly(common_data, &openings, public_inputs_hash, &challenges)?;
+    reader.verify_fri(verifier_data, &openings, &challenges)
+}
+
+struct CalldataReader<'a> {
+    calldata: &'a [u64],
+    layout: &'a CalldataLayout,
+}
+
+impl<'a> CalldataReader<'a> {
+    fn elements(&self, range: Range<usize>) -> Vec<F> {
+        self.calldata[range]
+            .iter()
+            .map(|&w| F::from_canonical_u64(w))
+            .collect()
+    }
+
+    fn extension_elements(&self, start: usize, n: usize) -> Vec<FE> {
+        self.elements(start..start + n * D)
+            .chunks(D)
+            .map(|c| FE::from_basefield_array([c[0], c[1]]))
+            .collect()
+    }
+
+    fn hash(&self, start: usize) -> Result<BytesHash<25>> {
+        let mut bytes = [0; 25];
+        for (chunk, &w) in bytes
+            .chunks_mut(7)
+            .zip(&self.calldata[start..start + HASH_WORDS])
+        {
+            ensure!(w >> (8 * chunk.len()) == 0, "Non-canonical digest.");
+            chunk.copy_from_slice(&w.to_le_bytes()[..chunk.len()]);
+        }
+        Ok(BytesHash(bytes))
+    }
+
+    fn hashes(&self, start: usize, n: usize) -> Result<Vec<BytesHash<25>>> {
+        (0..n).map(|i| self.hash(start + i * HASH_WORDS)).collect()
+    }
+
+    fn get_challenges(
+        &self,
+        verifier_data: &VerifierCircuitData<F, C, D>,
+        public_inputs_hash: HashOut<F>,
+    ) -> ProofChallenges<F, D> {
+        let layout = self.layout;
+        let num_challenges = verifier_data.common.config.num_challenges;
+        let cap_len = layout.cap_len();
+        let mut challenger = Challenger::<F, H>::new();
+
+        challenger.observe_elements(&verifier_data.verifier_only.circuit_digest.to_vec());
+        challenger.observe_elements(&public_inputs_hash.elements);
+
+        challenger.observe_elements(&self.elements(layout.wires_cap..layout.wires_cap + cap_len));
+        let plonk_betas = challenger.get_n_challenges(num_challenges);
+        let plonk_gammas = challenger.get_n_challenges(num_challenges);
+        challenger
+            .observe_elements(&self.elements(
+                layout.zs_partial_products_cap..layout.zs_partial_products_cap + cap_len,
+            ));
+        let plonk_alphas = challenger.get_n_challenges(num_challenges);
+        challenger.observe_elements(
+            &self.elements(layout.quotient_polys_cap..layout.quotient_polys_cap + cap_len),
+        );
+        let plonk_zeta = challenger.get_extension_challenge::<D>();
+        challenger.observe_elements(&self.elements(layout.openings..layout.commit_phase_caps));
+
+        let fri_alpha = challenger.get_extension_challenge::<D>();
+        let fri_betas = (0..layout.reduction_arity_bits.len())
+            .map(|i| {
+                let start = layout.commit_phase_caps + i * cap_len;
+                challenger.observe_elements(&self.elements(start..start + cap_len));
+                challenger.get_extension_challenge::<D>()
+            })
+            .collect();
+        challenger.observe_elements(&self.elements(layout.final_poly..layout.len));
+        let fri_pow_response = challenger.get_challenge();
+        let lde_size = verifier_data.common.fri_params.lde_size();
+        let fri_query_indices = (0..verifier_data.common.config.fri_config.num_query_rounds)
+            .map(|_| challenger.get_challenge().to_canonical_u64() as usize % lde_size)
+            .collect();
+
+        ProofChallenges {
+            plonk_betas,
+            plonk_gammas,
+            plonk_alphas,
+            plonk_deltas: vec![],
+            plonk_zeta,
+            fri_challenges: FriChallenges {
+                fri_alpha,
+                fri_betas,
+                fri_pow_response,
+                fri_query_indices,
+            },
+        }
+    }
+
+    fn verify_fri(
+        &self,
+        verifier_data: &VerifierCircuitData<F, C, D>,
+        openings: &[FE],
+        challenges: &ProofChallenges<F, D>,
+    ) -> Result<()> {
+        let layout = self.layout;
+        let common_data = &verifier_data.common;
+        let fri_params = &common_data.fri_params;
+        let FriChallenges {
+            fri_alpha,
+            fri_betas,
+            fri_pow_response,
+            fri_query_indices,
+        } = &challenges.fri_challenges;
+
+        ensure!(
+            fri_pow_response.to_canonical_u64().leading_zeros()
+                >= fri_params.config.proof_of_work_bits,
+            "Invalid proof of work witness."
+        );
+
+        let reduce = |values: &[FE]| {
+            values
+                .iter()
+                .rev()
+                .fold(FE::ZERO, |acc, &v| acc * *fri_alpha + v)
+        };
+        let (zeta_openings, next_openings) = openings.split_at(layout.num_zeta_openings);
+        let reduced_zeta_openings = reduce(zeta_openings);
+        let reduced_next_openings = reduce(next_openings);
+        let zeta = challenges.plonk_zeta;
+        let zeta_next = zeta * FE::from(F::primitive_root_of_unity(common_data.degree_bits()));
+        let alpha_pow_next = fri_alpha.exp_u64(layout.num_next_openings as u64);
+
+        let cap_len = layout.cap_len();
+        let initial_caps = [
+            verifier_data.verifier_only.constants_sigmas_cap.clone(),
+            MerkleCap(self.hashes(layout.wires_cap, layout.num_cap_hashes)?),
+            MerkleCap(self.hashes(layout.zs_partial_products_cap, layout.num_cap_hashes)?),
+            MerkleCap(self.hashes(layout.quotient_polys_cap, layout.num_cap_hashes)?),
+        ];
+        let commit_phase_caps = (0..layout.reduction_arity_bits.len())
+            .map(|i| {
+                self.hashes(
+                    layout.commit_phase_caps + i * cap_len,
+                    layout.num_cap_hashes,
+                )
+                .map(MerkleCap)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let lde_bits = fri_params.lde_bits();
+        let omega = F::primitive_root_of_unity(lde_bits);
+        for (round, &x_index) in fri_query_indices.iter().enumerate() {
+            let base = layout.query_rounds + round * layout.query_round_len;
+            let mut index = x_index;
+            let mut x = F::MULTIPLICATIVE_GROUP_GENERATOR
+                * omega.exp_u64(reverse_bits(index, lde_bits) as u64);
+
+            // Check the initial Merkle proofs and combine the opened values.
+            let mut leaves = Vec::new();
+            for (i, (&size, cap)) in layout.oracle_sizes.iter().zip(&initial_caps).enumerate() {
+                let start = base + layout.initial_leaf(i);
+                let leaf = self.elements(start..start + size);
+                let siblings = self.hashes(start + size, layout.initial_siblings)?;
+                verify_merkle_proof_to_cap::<F, H>(
+                    leaf.clone(),
+                    index,
+                    cap,
+                    &MerkleProof { siblings },
+                )?;
+                leaves.push(leaf);
+            }
+            let zs = &leaves[2][..layout.num_next_openings];
+            let to_ext = |v: &[F]| v.iter().map(|&x| FE::from(x)).collect::<Vec<_>>();
+            let x_ext = FE::from(x);
+            let mut old_eval = (reduce(&to_ext(&leaves.concat())) - reduced_zeta_openings)
+                / (x_ext - zeta)
+                * alpha_pow_next
+                + (reduce(&to_ext(zs)) - reduced_next_openings) / (x_ext - zeta_next);
+
+            for (i, &arity_bits) in layout.reduction_arity_bits.iter().enumerate() {
+                let arity = 1 << arity_bits;
+                let start = base + layout.step_evals(i);
+                let evals = self.extension_elements(start, arity);
+                let coset_index = index >> arity_bits;
+                let x_index_within_coset = index & (arity - 1);
+                ensure!(
+                    evals[x_index_within_coset] == old_eval,
+                    "Inconsistent FRI evaluations."
+                );
+                old_eval =
+                    interpolate_coset(x, x_index_within_coset, arity_bits, &evals, fri_betas[i]);
+
+                let siblings = self.hashes(start + arity * D, layout.step_siblings[i])?;
+                verify_merkle_proof_to_cap::<F, H>(
+                    flatten::<F, D>(&evals),
+                    coset_index,
+                    &commit_phase_caps[i],
+                    &MerkleProof { siblings },
+                )?;
+
+                x = x.exp_power_of_2(arity_bits);
+                index = coset_index;
+            }
+
+            let final_poly = self.extension_elements(layout.final_poly, layout.final_poly_len);
+            let final_eval = final_poly
+                .iter()
+                .rev()
+                .fold(FE::ZERO, |acc, &c| acc * FE::from(x) + c);
+            ensure!(
+                final_eval == old_eval,
+                "Final polynomial evaluation is invalid."
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Evaluates at `beta` the polynomial interpolating the evaluations of a FRI reduction step, in the
+/// closed form used by the contract. The points `c * g^i` of the coset are the roots of
+/// `X^n - c^n`, so their barycentric weights are `c * g^i / (n * c^n)`.
+fn interpolate_coset(
+    x: F,
+    x_index_within_coset: usize,
+    arity_bits: usize,
+    evals: &[FE],
+    beta: FE,
+) -> FE {
+    let arity = 1 << arity_bits;
+    let g = F::primitive_root_of_unity(arity_bits);
+    let coset_start =
+        x * g.exp_u64((arity - reverse_bits(x_index_within_coset, arity_bits)) as u64);
+    let coset_start_pow = coset_start.exp_power_of_2(arity_bits);
+
+    let mut sum = FE::ZERO;
+    let mut point = coset_start;
+    for i in 0..arity {
+        let eval = evals[reverse_bits(i, arity_bits)];
+        sum += eval * FE::from(point) / (beta - FE::from(point));
+        point *= g;
+    }
+    (beta.exp_power_of_2(arity_bits) - FE::from(coset_start_pow))
+        * sum
+        * FE::from((F::from_canonical_usize(arity) * coset_start_pow).inverse())
+}
+
+/// Checks the vanishing polynomial identity at `zeta`, the way the contract does.
+fn verify_vanishing_poly(
+    common_data: &CommonCircuitData<F, D>,
+    openings: &[FE],
+    public_inputs_hash: HashOut<F>,
+    challenges: &ProofChallenges<F, D>,
+) -> Result<()> {
+    let config = &common_data.config;
+    let num_challenges = config.num_challenges;
+    let num_prods = common_data.num_partial_products;
+    let quotient_degree_factor = common_data.quotient_degree_factor;
+
+    let sigmas_start = common_data.num_constants;
+    let wires_start = common_data.num_preprocessed_polys();
+    let zs_start = wires_start + config.num_wires;
+    let partial_products_start = zs_start + num_challenges;
+    let quotient_start = zs_start + common_data.num_zs_partial_products_polys();
+    let next_zs_start = quotient_start + common_data.num_quotient_polys();
+
+    let zeta = challenges.plonk_zeta;
+    let zeta_pow_deg = zeta.exp_power_of_2(common_data.degree_bits());
+    let z_h_zeta = zeta_pow_deg - FE::ONE;
+    let l_0_zeta = if zeta.is_one() {
+        FE::ONE
+    } else {
+        z_h_zeta / ((zeta - FE::ONE) * FE::from(F::from_canonical_usize(common_data.degree())))
+    };
+
+    let mut terms = (0..num_challenges)
+        .map(|i| l_0_zeta * (openings[zs_start + i] - FE::ONE))
+        .collect::<Vec<_>>();
+    for i in 0..num_challenges {
+        let beta = challenges.plonk_betas[i];
+        let gamma = FE::from(challenges.plonk_gammas[i]);
+        let accs = once(openings[zs_start + i])
+            .chain(
+                openings[partial_products_start + i * num_prods..][..num_prods]
+                    .iter()
+                    .copied(),
+            )
+            .chain(once(openings[next_zs_start + i]))
+            .collect::<Vec<_>>();
+        for (k, wires) in (0..config.num_routed_wires)
+            .chunks(quotient_degree_factor)
+            .into_iter()
+            .enumerate()
+        {
+            let (mut numerator, mut denominator) = (FE::ONE, FE::ONE);
+            for j in wires {
+                let wire = openings[wires_start + j];
+                numerator *= wire + zeta * FE::from(common_data.k_is[j] * beta) + gamma;
+                denominator *= wire + openings[sigmas_start + j] * FE::from(beta) + gamma;
+            }
+            terms.push(accs[k] * numerator - accs[k + 1] * denominator);
+        }
+    }
+    let program = ConstraintProgram::from_common_data(common_data)?;
+    terms.extend(program.eval(EvaluationVars {
+        local_constants: &openings[..sigmas_start],
+        local_wires: &openings[wires_start..zs_start],
+        public_inputs_hash: &public_inputs_hash,
+    }));
+
+    for (i, &alpha) in challenges.plonk_alphas.iter().enumerate() {
+        let vanishing = terms
+            .iter()
+            .rev()
+            .fold(FE::ZERO, |acc, &t| acc * FE::from(alpha) + t);
+        let quotient = openings[quotient_start + i * quotient_degree_factor..]
+            [..quotient_degree_factor]
+            .iter()
+            .rev()
+            .fold(FE::ZERO, |acc, &t| acc * zeta_pow_deg + t);
+        ensure!(
+            vanishing == z_h_zeta * quotient,
+            "Mismatch between evaluation and opening of quotient polynomial."
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gates::noop::NoopGate;
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::{CircuitConfig, CircuitData};
+
+    /// A small circuit padded to `2^12` rows, so that FRI performs a few reduction steps.
+    fn test_proof() -> Result<(CircuitData<F, C, D>, ProofWithPublicInputs<F, C, D>)> {
+        let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+        let x = builder.add_virtual_target();
+        let y = builder.add_virtual_target();
+        let z = builder.mul_add(x, y, x);
+        let w = builder.exp_u64(z, 11);
+        builder.register_public_inputs(&[x, z, w]);
+        builder.register_public_input(y);
+        for _ in 0..4000 {
+            builder.add_gate(NoopGate, vec![]);
+        }
+        let data = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_target(x, F::from_canonical_u64(3));
+        pw.set_target(y, F::from_canonical_u64(0xFFFF_FFFF));
+        let proof = data.prove(pw)?;
+        data.verify(proof.clone())?;
+        Ok((data, proof))
+    }
+
+    #[test]
+    fn test_calldata_layout() -> Result<()> {
+        let (data, proof) = test_proof()?;
+        let layout = CalldataLayout::new(&data.common)?;
+        let calldata = proof_to_calldata(&proof, &data.common)?;
+        assert_eq!(calldata.len(), layout.len);
+        assert!(!layout.reduction_arity_bits.is_empty());
+
+        let fri_proof = &proof.proof.opening_proof;
+        assert_eq!(
+            calldata[layout.pow_witness],
+            fri_proof.pow_witness.to_canonical_u64()
+        );
+        assert_eq!(
+            calldata[layout.final_poly],
+            fri_proof.final_poly.coeffs[0].0[0].to_canonical_u64()
+        );
+        let round = fri_proof.query_round_proofs.len() - 1;
+        let base = layout.query_rounds + round * layout.query_round_len;
+        let (leaf, merkle_proof) = &fri_proof.query_round_proofs[round]
+            .initial_trees_proof
+            .evals_proofs[3];
+        assert_eq!(
+            calldata[base + layout.initial_leaf(3)],
+            leaf[0].to_canonical_u64()
+        );
+        assert_eq!(merkle_proof.siblings.len(), layout.initial_siblings);
+        let step = layout.reduction_arity_bits.len() - 1;
+        let step_proof = &fri_proof.query_round_proofs[round].steps[step];
+        assert_eq!(
+            step_proof.merkle_proof.siblings.len(),
+            layout.step_siblings[step]
+        );
+        let reader = CalldataReader {
+            calldata: &calldata,
+            layout: &layout,
+        };
+        let siblings_start = base + layout.step_evals(step) + step_proof.evals.len() * D;
+        assert_eq!(
+            reader.hashes(siblings_start, layout.step_siblings[step])?,
+            step_proof.merkle_proof.siblings
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_calldata_challenges() -> Result<()> {
+        let (data, proof) = test_proof()?;
+        let layout = CalldataLayout::new(&data.common)?;
+        let calldata = proof_to_calldata(&proof, &data.common)?;
+        let reader = CalldataReader {
+            calldata: &calldata,
+            layout: &layout,
+        };
+        let public_inputs_hash = proof.get_public_inputs_hash();
+        let expected = proof.get_challenges(
+            public_inputs_hash,
+            &data.verifier_only.circuit_digest,
+            &data.common,
+        )?;
+        let challenges = reader.get_challenges(&data.verifier_data(), public_inputs_hash);
+
+        assert_eq!(challenges.plonk_betas, expected.plonk_betas);
+        assert_eq!(challenges.plonk_gammas, expected.plonk_gammas);
+        assert_eq!(challenges.plonk_alphas, expected.plonk_alphas);
+        assert_eq!(challenges.plonk_zeta, expected.plonk_zeta);
+        let (fri, expected_fri) = (&challenges.fri_challenges, &expected.fri_challenges);
+        assert_eq!(fri.fri_alpha, expected_fri.fri_alpha);
+        assert_eq!(fri.fri_betas, expected_fri.fri_betas);
+        assert_eq!(fri.fri_pow_response, expected_fri.fri_pow_response);
+        assert_eq!(fri.fri_query_indices, expected_fri.fri_query_indices);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_verify_calldata() -> Result<()> {
+        let (data, proof) = test_proof()?;
+        let verifier_data = data.verifier_data();
+        let layout = CalldataLayout::new(&data.common)?;
+        let calldata = proof_to_calldata(&proof, &data.common)?;
+        let public_inputs = proof
+            .public_inputs
+            .iter()
+            .map(F::to_canonical_u64)
+            .collect::<Vec<_>>();
+        verify_calldata(&verifier_data, &calldata, &public_inputs)?;
+
+        let mut bad_public_inputs = public_inputs.clone();
+        bad_public_inputs[1] += 1;
+        assert!(verify_calldata(&verifier_data, &calldata, &bad_public_inputs).is_err());
+
+        let step_evals = layout.query_rounds + layout.step_evals(0);
+        for i in [
+            layout.wires_cap,
+            layout.openings + 1,
+            layout.query_rounds + layout.initial_leaf(1),
+            step_evals,
+            step_evals + (1 << layout.reduction_arity_bits[0]) * D,
+            layout.final_poly,
+            layout.pow_witness,
+        ] {
+            let mut bad_calldata = calldata.clone();
+            bad_calldata[i] ^= 1;
+            assert!(
+                verify_calldata(&verifier_data, &bad_calldata, &public_inputs).is_err(),
+                "Tampering with word {i} was not detected"
+            );
+        }
+
+        Ok(())
+    }
+}
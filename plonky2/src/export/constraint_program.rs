@@ -0,0 +1,540 @@
+//! A straight-line representation of a circuit's combined gate constraints, suitable for code
+//! generation.
+//!
+//! Rather than asking every `Gate` to describe its constraints symbolically, we trace the recursive
+//! evaluation `evaluate_gate_constraints_circuit` into a scratch `CircuitBuilder`. Every
+//! intermediate value of that evaluation is the output of an operation of an
+//! `ArithmeticExtensionGate`, `MulExtensionGate`, `ReducingExtensionGate` or `PoseidonMdsGate`, and
+//! following the copy constraints back from the outputs yields a DAG of multiply-add operations
+//! over the opened wires, constants and public inputs hash.
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use anyhow::{anyhow, bail, ensure, Result};
+use hashbrown::HashMap;
+
+use crate::field::extension::{Extendable, FieldExtension};
+use crate::field::types::Field;
+use crate::gates::arithmetic_extension::ArithmeticExtensionGate;
+use crate::gates::multiplication_extension::MulExtensionGate;
+use crate::gates::poseidon_mds::PoseidonMdsGate;
+use crate::gates::reducing_extension::ReducingExtensionGate;
+use crate::hash::hash_types::RichField;
+use crate::hash::poseidon::{Poseidon, SPONGE_WIDTH};
+use crate::iop::ext_target::ExtensionTarget;
+use crate::iop::target::Target;
+use crate::iop::wire::Wire;
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::circuit_data::CommonCircuitData;
+use crate::plonk::vanishing_poly::evaluate_gate_constraints_circuit;
+use crate::plonk::vars::{EvaluationTargets, EvaluationVars};
+
+/// A node of a `ConstraintProgram`. Every node holds an element of the extension field; operands
+/// refer to earlier nodes by index.
+#[derive(Copy, Clone, Debug)]
+pub enum ConstraintNode<F: RichField + Extendable<D>, const D: usize> {
+    /// The opening of the `i`-th wire polynomial.
+    Wire(usize),
+    /// The opening of the `i`-th constant polynomial, selectors included.
+    Constant(usize),
+    /// The `i`-th element of the public inputs hash, embedded in the extension field.
+    PublicInputsHash(usize),
+    Literal(F::Extension),
+    /// `const_0 * multiplicand_0 * multiplicand_1 + const_1 * addend`.
+    Arithmetic {
+        const_0: F,
+        const_1: F,
+        multiplicand_0: usize,
+        multiplicand_1: usize,
+        addend: usize,
+    },
+    /// `const_0 * multiplicand_0 * multiplicand_1`.
+    Mul {
+        const_0: F,
+        multiplicand_0: usize,
+        multiplicand_1: usize,
+    },
+}
+
+/// The combined, filtered gate constraints of a circuit as a topologically ordered list of nodes.
+/// Evaluating it at the openings at `zeta` gives the same values as `evaluate_gate_constraints`.
+#[derive(Clone, Debug)]
+pub struct ConstraintProgram<F: RichField + Extendable<D>, const D: usize> {
+    pub nodes: Vec<ConstraintNode<F, D>>,
+    /// The node holding each of the `num_gate_constraints` constraints.
+    pub outputs: Vec<usize>,
+}
+
+/// Where the value of a base field target comes from.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Source<F> {
+    /// Component of an input node.
+    Input {
+        node: usize,
+        component: usize,
+    },
+    /// Component of the output of the arithmetic operation `op` in the gate at `row`.
+    Output {
+        row: usize,
+        op: usize,
+        component: usize,
+    },
+    Constant(F),
+}
+
+/// An operation performed by a gate, in terms of its wires.
+struct Operation<F> {
+    kind: OperationKind<F>,
+    operands: Vec<Range<usize>>,
+    output: Range<usize>,
+}
+
+enum OperationKind<F> {
+    /// `const_0 * operands[0] * operands[1]`, plus `const_1 * operands[2]` if there is an addend.
+    MulAdd { const_0: F, const_1: Option<F> },
+    /// `sum_i coeffs[i] * operands[i]`.
+    LinearCombination(Vec<F>),
+}
+
+struct Tracer<'a, F: RichField + Extendable<D>, const D: usize> {
+    builder: &'a CircuitBuilder<F, D>,
+    parents: HashMap<Target, Target>,
+    sources: HashMap<Target, Source<F>>,
+    nodes: Vec<ConstraintNode<F, D>>,
+    op_nodes: HashMap<(usize, usize), usize>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> ConstraintProgram<F, D> {
+    pub fn from_common_data(common_data: &CommonCircuitData<F, D>) -> Result<Self> {
+        let mut builder = CircuitBuilder::<F, D>::new(common_data.config.clone());
+        let local_constants = builder.add_virtual_extension_targets(common_data.num_constants);
+        let local_wires = builder.add_virtual_extension_targets(common_data.config.num_wires);
+        let public_inputs_hash = builder.add_virtual_hash();
+        let vars = EvaluationTargets {
+            local_constants: &local_constants,
+            local_wires: &local_wires,
+            public_inputs_hash: &public_inputs_hash,
+        };
+        let constraints = evaluate_gate_constraints_circuit(&mut builder, common_data, vars);
+
+        let mut tracer = Tracer::new(&builder);
+        let inputs = local_wires
+            .iter()
+            .enumerate()
+            .map(|(i, t)| (ConstraintNode::Wire(i), t.0.to_vec()))
+            .chain(
+                local_constants
+                    .iter()
+                    .enumerate()
+                    .map(|(i, t)| (ConstraintNode::Constant(i), t.0.to_vec())),
+            )
+            .chain(
+                public_inputs_hash
+                    .elements
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &t)| (ConstraintNode::PublicInputsHash(i), vec![t])),
+            );
+        for (node, targets) in inputs {
+            tracer.add_input(node, &targets);
+        }
+        tracer.add_gate_outputs()?;
+
+        let outputs = constraints
+            .iter()
+            .map(|&c| tracer.resolve(c))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            nodes: tracer.nodes,
+            outputs,
+        })
+    }
+
+    /// Evaluates the program, returning the combined gate constraints.
+    pub fn eval(&self, vars: EvaluationVars<F, D>) -> Vec<F::Extension> {
+        let mut values: Vec<F::Extension> = Vec::with_capacity(self.nodes.len());
+        for node in &self.nodes {
+            let value = match *node {
+                ConstraintNode::Wire(i) => vars.local_wires[i],
+                ConstraintNode::Constant(i) => vars.local_constants[i],
+                ConstraintNode::PublicInputsHash(i) => vars.public_inputs_hash.elements[i].into(),
+                ConstraintNode::Literal(x) => x,
+                ConstraintNode::Arithmetic {
+                    const_0,
+                    const_1,
+                    multiplicand_0,
+                    multiplicand_1,
+                    addend,
+                } => {
+                    (values[multiplicand_0] * values[multiplicand_1]).scalar_mul(const_0)
+                        + values[addend].scalar_mul(const_1)
+                }
+                ConstraintNode::Mul {
+                    const_0,
+                    multiplicand_0,
+                    multiplicand_1,
+                } => (values[multiplicand_0] * values[multiplicand_1]).scalar_mul(const_0),
+            };
+            values.push(value);
+        }
+        self.outputs.iter().map(|&i| values[i]).collect()
+    }
+}
+
+impl<'a, F: RichField + Extendable<D>, const D: usize> Tracer<'a, F, D> {
+    fn new(builder: &'a CircuitBuilder<F, D>) -> Self {
+        let mut tracer = Self {
+            builder,
+            parents: HashMap::new(),
+            sources: HashMap::new(),
+            nodes: Vec::new(),
+            op_nodes: HashMap::new(),
+        };
+        for c in &builder.copy_constraints {
+            let (x, y) = (tracer.find(c.pair.0), tracer.find(c.pair.1));
+            if x != y {
+                tracer.parents.insert(x, y);
+            }
+        }
+        for c in &builder.copy_constraints {
+            for t in [c.pair.0, c.pair.1] {
+                if let Some(c) = builder.target_as_constant(t) {
+                    tracer.add_source(t, Source::Constant(c));
+                }
+            }
+        }
+        tracer
+    }
+
+    fn find(&mut self, mut t: Target) -> Target {
+        let mut path = Vec::new();
+        while let Some(&parent) = self.parents.get(&t) {
+            path.push(t);
+            t = parent;
+        }
+        for p in path {
+            self.parents.insert(p, t);
+        }
+        t
+    }
+
+    fn add_source(&mut self, t: Target, source: Source<F>) {
+        let root = self.find(t);
+        self.sources.insert(root, source);
+    }
+
+    fn add_input(&mut self, node: ConstraintNode<F, D>, targets: &[Target]) {
+        let index = self.nodes.len();
+        self.nodes.push(node);
+        for (component, &t) in targets.iter().enumerate() {
+            self.add_source(
+                t,
+                Source::Input {
+                    node: index,
+                    component,
+                },
+            );
+        }
+    }
+
+    fn add_gate_outputs(&mut self) -> Result<()> {
+        for row in 0..self.builder.gate_instances.len() {
+            for (op, operation) in self.operations(row)?.into_iter().enumerate() {
+                for (component, column) in operation.output.enumerate() {
+                    self.add_source(
+                        Target::Wire(Wire { row, column }),
+                        Source::Output { row, op, component },
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The multiply-add operations performed by the gate at `row`.
+    fn operations(&self, row: usize) -> Result<Vec<Operation<F>>> {
+        let instance = &self.builder.gate_instances[row];
+        let gate = instance.gate_ref.0.as_any();
+        Ok(
+            if let Some(g) = gate.downcast_ref::<ArithmeticExtensionGate<D>>() {
+                (0..g.num_ops)
+                    .map(|i| Operation {
+                        kind: OperationKind::MulAdd {
+                            const_0: instance.constants[0],
+                            const_1: Some(instance.constants[1]),
+                        },
+                        operands: vec![
+                            ArithmeticExtensionGate::<D>::wires_ith_multiplicand_0(i),
+                            ArithmeticExtensionGate::<D>::wires_ith_multiplicand_1(i),
+                            ArithmeticExtensionGate::<D>::wires_ith_addend(i),
+                        ],
+                        output: ArithmeticExtensionGate::<D>::wires_ith_output(i),
+                    })
+                    .collect()
+            } else if let Some(g) = gate.downcast_ref::<MulExtensionGate<D>>() {
+                (0..g.num_ops)
+                    .map(|i| Operation {
+                        kind: OperationKind::MulAdd {
+                            const_0: instance.constants[0],
+                            const_1: None,
+                        },
+                        operands: vec![
+                            MulExtensionGate::<D>::wires_ith_multiplicand_0(i),
+                            MulExtensionGate::<D>::wires_ith_multiplicand_1(i),
+                        ],
+                        output: MulExtensionGate::<D>::wires_ith_output(i),
+                    })
+                    .collect()
+            } else if let Some(g) = gate.downcast_ref::<ReducingExtensionGate<D>>() {
+                // Each step of the reduction is `acc * alpha + coeff`.
+                (0..g.num_coeffs)
+                    .map(|i| Operation {
+                        kind: OperationKind::MulAdd {
+                            const_0: F::ONE,
+                            const_1: Some(F::ONE),
+                        },
+                        operands: vec![
+                            if i == 0 {
+                                ReducingExtensionGate::<D>::wires_old_acc()
+                            } else {
+                                g.wires_accs(i - 1)
+                            },
+                            ReducingExtensionGate::<D>::wires_alpha(),
+                            ReducingExtensionGate::<D>::wires_coeff(i),
+                        ],
+                        output: g.wires_accs(i),
+                    })
+                    .collect()
+            } else if gate.is::<PoseidonMdsGate<F, D>>() {
+                (0..SPONGE_WIDTH)
+                    .map(|r| {
+                        let mut coeffs = vec![F::ZERO; SPONGE_WIDTH];
+                        for i in 0..SPONGE_WIDTH {
+                            coeffs[(i + r) % SPONGE_WIDTH] +=
+                                F::from_canonical_u64(<F as Poseidon>::MDS_MATRIX_CIRC[i]);
+                        }
+                        coeffs[r] += F::from_canonical_u64(<F as Poseidon>::MDS_MATRIX_DIAG[r]);
+                        Operation {
+                            kind: OperationKind::LinearCombination(coeffs),
+                            operands: (0..SPONGE_WIDTH)
+                                .map(PoseidonMdsGate::<F, D>::wires_input)
+                                .collect(),
+                            output: PoseidonMdsGate::<F, D>::wires_output(r),
+                        }
+                    })
+                    .collect()
+            } else {
+                bail!(
+                    "Gate {} was used while evaluating constraints, only arithmetic gates are supported",
+                    instance.gate_ref.0.id()
+                );
+            },
+        )
+    }
+
+    fn source(&mut self, t: Target) -> Result<Source<F>> {
+        if let Some(c) = self.builder.target_as_constant(t) {
+            return Ok(Source::Constant(c));
+        }
+        let root = self.find(t);
+        self.sources
+            .get(&root)
+            .copied()
+            .ok_or_else(|| anyhow!("Target {t:?} is not determined by the openings"))
+    }
+
+    /// Returns the node holding the value of `t`, tracing the operations it depends on.
+    fn resolve(&mut self, t: ExtensionTarget<D>) -> Result<usize> {
+        let sources =
+            t.0.iter()
+                .map(|&t| self.source(t))
+                .collect::<Result<Vec<_>>>()?;
+
+        match sources[0] {
+            Source::Input { node, .. } => {
+                if let ConstraintNode::PublicInputsHash(_) = self.nodes[node] {
+                    ensure!(
+                        sources[1..].iter().all(|s| *s == Source::Constant(F::ZERO)),
+                        "Public inputs hash elements can only be embedded in the extension field"
+                    );
+                    return Ok(node);
+                }
+                ensure!(
+                    sources
+                        .iter()
+                        .enumerate()
+                        .all(|(component, s)| *s == Source::Input { node, component }),
+                    "Extension target mixes components of several openings"
+                );
+                Ok(node)
+            }
+            Source::Output { row, op, .. } => {
+                ensure!(
+                    sources
+                        .iter()
+                        .enumerate()
+                        .all(|(component, s)| *s == Source::Output { row, op, component }),
+                    "Extension target mixes components of several operations"
+                );
+                self.op_node(row, op)
+            }
+            Source::Constant(_) => {
+                let coeffs = sources
+                    .iter()
+                    .map(|s| match s {
+                        Source::Constant(c) => Ok(*c),
+                        _ => Err(anyhow!("Extension target is only partially constant")),
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                let x = F::Extension::from_basefield_array(coeffs.try_into().unwrap());
+                self.nodes.push(ConstraintNode::Literal(x));
+                Ok(self.nodes.len() - 1)
+            }
+        }
+    }
+
+    /// Returns the node of an operation, creating the nodes of the operations it depends on first.
+    /// This uses an explicit stack since dependency chains can be very long.
+    fn op_node(&mut self, row: usize, op: usize) -> Result<usize> {
+        let mut stack = vec![(row, op)];
+        while let Some(&(row, op)) = stack.last() {
+            if self.op_nodes.contains_key(&(row, op)) {
+                stack.pop();
+                continue;
+            }
+
+            let operation = self.operations(row)?.swap_remove(op);
+            let operands: Vec<_> = operation
+                .operands
+                .into_iter()
+                .map(|range| ExtensionTarget::<D>::from_range(row, range))
+                .collect();
+            let mut pending = false;
+            for operand in &operands {
+                for &t in &operand.0 {
+                    if let Source::Output { row, op, .. } = self.source(t)? {
+                        if !self.op_nodes.contains_key(&(row, op)) {
+                            stack.push((row, op));
+                            pending = true;
+                        }
+                    }
+                }
+            }
+            if pending {
+                continue;
+            }
+
+            let operands = operands
+                .into_iter()
+                .map(|t| self.resolve(t))
+                .collect::<Result<Vec<_>>>()?;
+            match operation.kind {
+                OperationKind::MulAdd { const_0, const_1 } => {
+                    let node = match (const_1, &operands[..]) {
+                        (None, &[multiplicand_0, multiplicand_1]) => ConstraintNode::Mul {
+                            const_0,
+                            multiplicand_0,
+                            multiplicand_1,
+                        },
+                        (Some(const_1), &[multiplicand_0, multiplicand_1, addend]) => {
+                            ConstraintNode::Arithmetic {
+                                const_0,
+                                const_1,
+                                multiplicand_0,
+                                multiplicand_1,
+                                addend,
+                            }
+                        }
+                        _ => unreachable!(),
+                    };
+                    self.nodes.push(node);
+                }
+                OperationKind::LinearCombination(coeffs) => {
+                    // Accumulate one term at a time, multiplying by one.
+                    let one = self.nodes.len();
+                    self.nodes.push(ConstraintNode::Literal(F::Extension::ONE));
+                    self.nodes.push(ConstraintNode::Mul {
+                        const_0: coeffs[0],
+                        multiplicand_0: operands[0],
+                        multiplicand_1: one,
+                    });
+                    for (&c, &x) in coeffs.iter().zip(&operands).skip(1) {
+                        let acc = self.nodes.len() - 1;
+                        self.nodes.push(ConstraintNode::Arithmetic {
+                            const_0: c,
+                            const_1: F::ONE,
+                            multiplicand_0: x,
+                            multiplicand_1: one,
+                            addend: acc,
+                        });
+                    }
+                }
+            }
+            self.op_nodes.insert((row, op), self.nodes.len() - 1);
+            stack.pop();
+        }
+        Ok(self.op_nodes[&(row, op)])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::export::constraint_program::ConstraintProgram;
+    use crate::field::types::Sample;
+    use crate::gates::noop::NoopGate;
+    use crate::hash::hash_types::HashOut;
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, KeccakGoldilocksConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::vanishing_poly::evaluate_gate_constraints;
+    use crate::plonk::vars::EvaluationVars;
+
+    #[test]
+    fn test_constraint_program_matches_native() -> Result<()> {
+        const D: usize = 2;
+        type C = KeccakGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type FF = <C as GenericConfig<D>>::FE;
+
+        // A recursion circuit uses most of the standard gates: Poseidon, coset interpolation,
+        // random access, reducing, arithmetic, constants and public inputs.
+        let config = CircuitConfig::standard_recursion_config();
+        let mut inner = CircuitBuilder::<F, D>::new(config.clone());
+        for _ in 0..100 {
+            inner.add_gate(NoopGate, vec![]);
+        }
+        let inner_data = inner.build::<PoseidonGoldilocksConfig>();
+
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let proof = builder.add_virtual_proof_with_pis(&inner_data.common);
+        let vd = builder.add_virtual_verifier_data(inner_data.common.config.fri_config.cap_height);
+        builder.verify_proof::<PoseidonGoldilocksConfig>(&proof, &vd, &inner_data.common);
+        builder.add_virtual_public_input();
+        let data = builder.build::<C>();
+
+        let program = ConstraintProgram::from_common_data(&data.common)?;
+        assert_eq!(program.outputs.len(), data.common.num_gate_constraints);
+
+        for _ in 0..3 {
+            let local_constants = FF::rand_vec(data.common.num_constants);
+            let local_wires = FF::rand_vec(data.common.config.num_wires);
+            let public_inputs_hash = HashOut::rand();
+            let vars = EvaluationVars {
+                local_constants: &local_constants,
+                local_wires: &local_wires,
+                public_inputs_hash: &public_inputs_hash,
+            };
+            assert_eq!(
+                program.eval(vars),
+                evaluate_gate_constraints(&data.common, vars)
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,5 @@
+//! Exporting verifiers for plonky2 proofs to other environments.
+
+pub mod calldata;
+pub mod constraint_program;
+pub mod solidity;
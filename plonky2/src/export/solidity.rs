@@ -0,0 +1,437 @@
+//! Generation of Solidity verifier contracts for plonky2 proofs.
+//!
+//! The generated contract takes proofs in the encoding of `export::calldata`. It re-derives the
+//! challenges with the Keccak-based challenger, checks the vanishing polynomial identity at `zeta`,
+//! evaluating the gate constraints with straight-line code generated from the circuit's
+//! `ConstraintProgram`, and checks the FRI queries against their Merkle proofs. Circuits must use
+//! `KeccakGoldilocksConfig`, without lookups or zero-knowledge.
+//!
+//! Contracts for large circuits may exceed the EIP-170 code size limit, since every gate
+//! constraint is compiled to its own code.
+//!
+//! `SolidityTestVector`s hold encoded proofs for testing a deployed contract, e.g. with Foundry.
+//! They can be checked locally with `calldata::verify_calldata`, which mirrors the contract. The
+//! `plonky2_solidity_tests` crate compiles the contract with `solc` and runs it on `revm`.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use alloc::{format, vec};
+use core::fmt::Write;
+
+use anyhow::{ensure, Result};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use crate::export::calldata::{proof_to_calldata, CalldataLayout, HASH_WORDS};
+use crate::export::constraint_program::{ConstraintNode, ConstraintProgram};
+use crate::field::extension::quadratic::QuadraticExtension;
+use crate::field::goldilocks_field::GoldilocksField;
+use crate::field::types::{Field, PrimeField64};
+use crate::hash::poseidon::{Poseidon, ALL_ROUND_CONSTANTS, N_ROUNDS, SPONGE_WIDTH};
+use crate::plonk::circuit_data::{CommonCircuitData, VerifierCircuitData};
+use crate::plonk::config::{GenericHashOut, KeccakGoldilocksConfig};
+use crate::plonk::proof::ProofWithPublicInputs;
+
+const D: usize = 2;
+type C = KeccakGoldilocksConfig;
+type F = GoldilocksField;
+
+const TEMPLATE: &str = include_str!("verifier_template.sol");
+
+/// Generates a Solidity contract named `contract_name` verifying proofs of the given circuit.
+pub fn generate_solidity_verifier(
+    verifier_data: &VerifierCircuitData<F, C, D>,
+    contract_name: &str,
+) -> Result<String> {
+    let common_data = &verifier_data.common;
+    let layout = CalldataLayout::new(common_data)?;
+    let program = ConstraintProgram::from_common_data(common_data)?;
+    ensure!(
+        program.outputs.len() == common_data.num_gate_constraints,
+        "Unexpected number of gate constraints."
+    );
+
+    Ok(TEMPLATE
+        .replace("{{CONTRACT_NAME}}", contract_name)
+        .replace("{{CONSTANTS}}", &constants(verifier_data, &layout))
+        .replace(
+            "{{GATE_CONSTRAINTS}}",
+            &gate_constraints(common_data, &program),
+        )
+        .replace("{{LOOKUP_FUNCTIONS}}", &lookup_functions(&layout)))
+}
+
+fn constants(verifier_data: &VerifierCircuitData<F, C, D>, layout: &CalldataLayout) -> String {
+    let common_data = &verifier_data.common;
+    let config = &common_data.config;
+    let fri_params = &common_data.fri_params;
+    let num_challenges = config.num_challenges;
+
+    let sigmas = common_data.num_constants;
+    let wires = common_data.num_preprocessed_polys();
+    let zs = wires + config.num_wires;
+    let quotient = zs + common_data.num_zs_partial_products_polys();
+    let num_vanishing_terms =
+        num_challenges * (2 + common_data.num_partial_products) + common_data.num_gate_constraints;
+
+    let mut out = String::new();
+    let mut constant = |name: &str, value: u64| {
+        writeln!(out, "    uint256 internal constant {name} = {value};").unwrap();
+    };
+    constant("NUM_PUBLIC_INPUTS", common_data.num_public_inputs as u64);
+    constant("DEGREE_BITS", common_data.degree_bits() as u64);
+    constant("DEGREE", common_data.degree() as u64);
+    constant(
+        "G",
+        F::primitive_root_of_unity(common_data.degree_bits()).to_canonical_u64(),
+    );
+    constant("LDE_BITS", fri_params.lde_bits() as u64);
+    constant("LDE_SIZE", fri_params.lde_size() as u64);
+    constant(
+        "LDE_ROOT",
+        F::primitive_root_of_unity(fri_params.lde_bits()).to_canonical_u64(),
+    );
+    constant("NUM_CHALLENGES", num_challenges as u64);
+    constant("NUM_ROUTED_WIRES", config.num_routed_wires as u64);
+    constant(
+        "QUOTIENT_DEGREE_FACTOR",
+        common_data.quotient_degree_factor as u64,
+    );
+    constant(
+        "NUM_PARTIAL_PRODUCTS",
+        common_data.num_partial_products as u64,
+    );
+    constant(
+        "NUM_GATE_CONSTRAINTS",
+        common_data.num_gate_constraints as u64,
+    );
+    constant("NUM_VANISHING_TERMS", num_vanishing_terms as u64);
+    constant(
+        "NUM_QUERY_ROUNDS",
+        config.fri_config.num_query_rounds as u64,
+    );
+    constant("POW_BITS", config.fri_config.proof_of_work_bits as u64);
+
+    // The layout of the proof.
+    constant("PROOF_LEN", layout.len as u64);
+    constant("CAP_LEN", layout.cap_len() as u64);
+    constant("WIRES_CAP", layout.wires_cap as u64);
+    constant(
+        "ZS_PARTIAL_PRODUCTS_CAP",
+        layout.zs_partial_products_cap as u64,
+    );
+    constant("QUOTIENT_POLYS_CAP", layout.quotient_polys_cap as u64);
+    constant("OPENINGS", layout.openings as u64);
+    constant("NUM_ZETA_OPENINGS", layout.num_zeta_openings as u64);
+    constant("NUM_NEXT_OPENINGS", layout.num_next_openings as u64);
+    constant("COMMIT_PHASE_CAPS", layout.commit_phase_caps as u64);
+    constant("QUERY_ROUNDS", layout.query_rounds as u64);
+    constant("QUERY_ROUND_LEN", layout.query_round_len as u64);
+    constant("INITIAL_SIBLINGS", layout.initial_siblings as u64);
+    constant(
+        "NUM_REDUCTION_STEPS",
+        layout.reduction_arity_bits.len() as u64,
+    );
+    constant("FINAL_POLY", layout.final_poly as u64);
+    constant("FINAL_POLY_LEN", layout.final_poly_len as u64);
+    constant("POW_WITNESS", layout.pow_witness as u64);
+
+    // Indices into the openings.
+    constant("SIGMAS", sigmas as u64);
+    constant("WIRES", wires as u64);
+    constant("ZS", zs as u64);
+    constant("PARTIAL_PRODUCTS", (zs + num_challenges) as u64);
+    constant("QUOTIENT", quotient as u64);
+    constant("NEXT_ZS", layout.num_zeta_openings as u64);
+
+    let circuit_digest: Vec<F> = verifier_data.verifier_only.circuit_digest.to_vec();
+    for (i, x) in circuit_digest.iter().enumerate() {
+        constant(&format!("CIRCUIT_DIGEST_{i}"), x.to_canonical_u64());
+    }
+
+    let mut bytes_constant = |name: &str, bytes: Vec<u8>| {
+        writeln!(
+            out,
+            "    bytes internal constant {name} = hex\"{}\";",
+            bytes.iter().map(|b| format!("{b:02x}")).join("")
+        )
+        .unwrap();
+    };
+    let words = |words: &[u64]| -> Vec<u8> { words.iter().flat_map(|w| w.to_be_bytes()).collect() };
+    bytes_constant(
+        "CONSTANTS_SIGMAS_CAP",
+        verifier_data
+            .verifier_only
+            .constants_sigmas_cap
+            .0
+            .iter()
+            .flat_map(|h| {
+                let mut word = [0; 32];
+                word[..h.0.len()].copy_from_slice(&h.0);
+                word
+            })
+            .collect(),
+    );
+    bytes_constant(
+        "K_IS",
+        words(
+            &common_data.k_is[..config.num_routed_wires]
+                .iter()
+                .map(F::to_canonical_u64)
+                .collect::<Vec<_>>(),
+        ),
+    );
+    bytes_constant(
+        "POSEIDON_ROUND_CONSTANTS",
+        words(&ALL_ROUND_CONSTANTS[..SPONGE_WIDTH * N_ROUNDS]),
+    );
+    bytes_constant(
+        "POSEIDON_MDS_CIRC",
+        words(&<F as Poseidon>::MDS_MATRIX_CIRC),
+    );
+    bytes_constant(
+        "POSEIDON_MDS_DIAG",
+        words(&<F as Poseidon>::MDS_MATRIX_DIAG),
+    );
+
+    out.truncate(out.trim_end().len());
+    out
+}
+
+/// Generates the body of `evalGateConstraints`, one statement per operation of the program.
+fn gate_constraints(
+    common_data: &CommonCircuitData<F, D>,
+    program: &ConstraintProgram<F, D>,
+) -> String {
+    let wires = common_data.num_preprocessed_polys();
+    let operand = |i: usize| match program.nodes[i] {
+        ConstraintNode::Wire(w) => format!("o[{}]", wires + w),
+        ConstraintNode::Constant(c) => format!("o[{c}]"),
+        ConstraintNode::PublicInputsHash(j) => format!("publicInputsHash[{j}]"),
+        ConstraintNode::Literal(x) => extension_literal(x),
+        ConstraintNode::Arithmetic { .. } | ConstraintNode::Mul { .. } => format!("v[{i}]"),
+    };
+    let is_one = |i: usize| matches!(program.nodes[i], ConstraintNode::Literal(x) if x.is_one());
+    let product = |const_0: F, multiplicand_0: usize, multiplicand_1: usize| {
+        let product = match (is_one(multiplicand_0), is_one(multiplicand_1)) {
+            (true, _) => operand(multiplicand_1),
+            (false, true) => operand(multiplicand_0),
+            (false, false) => format!(
+                "emul({}, {})",
+                operand(multiplicand_0),
+                operand(multiplicand_1)
+            ),
+        };
+        if const_0.is_one() {
+            product
+        } else {
+            format!("escale({product}, {const_0})")
+        }
+    };
+
+    let mut out = String::new();
+    writeln!(
+        out,
+        "        uint256[] memory v = new uint256[]({});",
+        program.nodes.len()
+    )
+    .unwrap();
+    for (i, node) in program.nodes.iter().enumerate() {
+        let value = match *node {
+            ConstraintNode::Arithmetic {
+                const_0,
+                const_1,
+                multiplicand_0,
+                multiplicand_1,
+                addend,
+            } => {
+                let product = product(const_0, multiplicand_0, multiplicand_1);
+                let addend = operand(addend);
+                if const_1.is_one() {
+                    format!("eadd({product}, {addend})")
+                } else if const_1 == F::NEG_ONE {
+                    format!("esub({product}, {addend})")
+                } else {
+                    format!("eadd({product}, escale({addend}, {const_1}))")
+                }
+            }
+            ConstraintNode::Mul {
+                const_0,
+                multiplicand_0,
+                multiplicand_1,
+            } => product(const_0, multiplicand_0, multiplicand_1),
+            _ => continue,
+        };
+        writeln!(out, "        v[{i}] = {value};").unwrap();
+    }
+    writeln!(out, "        c = new uint256[]({});", program.outputs.len()).unwrap();
+    for (i, &node) in program.outputs.iter().enumerate() {
+        writeln!(out, "        c[{i}] = {};", operand(node)).unwrap();
+    }
+
+    out.truncate(out.trim_end().len());
+    out
+}
+
+fn extension_literal(x: QuadraticExtension<F>) -> String {
+    let [a0, a1] = x.0.map(|a| a.to_canonical_u64());
+    if a1 == 0 {
+        format!("{a0}")
+    } else {
+        format!("0x{a1:x}{a0:016x}")
+    }
+}
+
+/// Generates the functions giving the per-oracle and per-step parts of the layout of a query round.
+fn lookup_functions(layout: &CalldataLayout) -> String {
+    let num_oracles = layout.oracle_sizes.len();
+    let num_steps = layout.reduction_arity_bits.len();
+    let functions = [
+        ("oracleSize", "oracle", layout.oracle_sizes.to_vec()),
+        (
+            "initialLeaf",
+            "oracle",
+            (0..num_oracles).map(|i| layout.initial_leaf(i)).collect(),
+        ),
+        (
+            "reductionArityBits",
+            "step",
+            layout.reduction_arity_bits.clone(),
+        ),
+        (
+            "stepEvals",
+            "step",
+            (0..num_steps).map(|i| layout.step_evals(i)).collect(),
+        ),
+        ("stepSiblings", "step", layout.step_siblings.clone()),
+    ];
+
+    functions
+        .iter()
+        .map(|(name, arg, values)| {
+            let mut out = String::new();
+            writeln!(
+                out,
+                "    function {name}(uint256 {arg}) internal pure returns (uint256) {{"
+            )
+            .unwrap();
+            for (i, value) in values.iter().enumerate() {
+                writeln!(out, "        if ({arg} == {i}) {{").unwrap();
+                writeln!(out, "            return {value};").unwrap();
+                writeln!(out, "        }}").unwrap();
+            }
+            writeln!(out, "        revert(\"invalid {arg}\");").unwrap();
+            write!(out, "    }}").unwrap();
+            out
+        })
+        .join("\n\n")
+}
+
+/// An encoded proof together with the expected outcome of verifying it.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SolidityTestVector {
+    pub proof: Vec<u64>,
+    pub public_inputs: Vec<u64>,
+    /// Whether the contract should accept the proof.
+    pub valid: bool,
+}
+
+impl SolidityTestVector {
+    pub fn new(
+        proof_with_pis: &ProofWithPublicInputs<F, C, D>,
+        common_data: &CommonCircuitData<F, D>,
+    ) -> Result<Self> {
+        Ok(Self {
+            proof: proof_to_calldata(proof_with_pis, common_data)?,
+            public_inputs: proof_with_pis
+                .public_inputs
+                .iter()
+                .map(F::to_canonical_u64)
+                .collect(),
+            valid: true,
+        })
+    }
+
+    /// Returns an invalid copy of this vector, with the given word of the proof changed.
+    pub fn tampered(&self, word: usize) -> Self {
+        let mut proof = self.proof.clone();
+        proof[word] ^= 1;
+        Self {
+            proof,
+            public_inputs: self.public_inputs.clone(),
+            valid: false,
+        }
+    }
+}
+
+/// The test vectors for a contract: a valid proof and copies of it tampered at several places.
+pub fn generate_test_vectors(
+    proof_with_pis: &ProofWithPublicInputs<F, C, D>,
+    common_data: &CommonCircuitData<F, D>,
+) -> Result<Vec<SolidityTestVector>> {
+    let layout = CalldataLayout::new(common_data)?;
+    let valid = SolidityTestVector::new(proof_with_pis, common_data)?;
+    let mut tampered_words = vec![
+        layout.wires_cap,
+        layout.openings,
+        layout.query_rounds,
+        layout.query_rounds + layout.initial_leaf(3) + layout.oracle_sizes[3],
+        layout.final_poly,
+        layout.pow_witness,
+    ];
+    if !layout.reduction_arity_bits.is_empty() {
+        tampered_words.push(layout.commit_phase_caps + HASH_WORDS);
+        tampered_words.push(layout.query_rounds + layout.step_evals(0));
+    }
+
+    let mut vectors = vec![valid.clone()];
+    vectors.extend(tampered_words.into_iter().map(|i| valid.tampered(i)));
+    Ok(vectors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::calldata::verify_calldata;
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+
+    #[test]
+    fn test_generate_solidity_verifier() -> Result<()> {
+        let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+        let x = builder.add_virtual_target();
+        let y = builder.exp_u64(x, 5);
+        let z = builder.mul_add(x, y, x);
+        builder.register_public_inputs(&[x, z]);
+        let data = builder.build::<C>();
+        let mut pw = PartialWitness::new();
+        pw.set_target(x, F::from_canonical_u64(12345));
+        let proof = data.prove(pw)?;
+
+        let verifier_data = data.verifier_data();
+        let contract = generate_solidity_verifier(&verifier_data, "TestVerifier")?;
+        assert!(contract.contains("contract TestVerifier {"));
+        assert!(!contract.contains("{{"));
+        assert_eq!(contract.matches('{').count(), contract.matches('}').count());
+        let layout = CalldataLayout::new(&data.common)?;
+        assert!(contract.contains(&format!(
+            "uint256 internal constant PROOF_LEN = {};",
+            layout.len
+        )));
+        for i in 0..data.common.num_gate_constraints {
+            assert!(contract.contains(&format!("        c[{i}] = ")));
+        }
+
+        let vectors = generate_test_vectors(&proof, &data.common)?;
+        let json = serde_json::to_string(&vectors)?;
+        let vectors: Vec<SolidityTestVector> = serde_json::from_str(&json)?;
+        assert!(vectors[0].valid);
+        for vector in &vectors {
+            let result = verify_calldata(&verifier_data, &vector.proof, &vector.public_inputs);
+            assert_eq!(result.is_ok(), vector.valid);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,636 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+pragma solidity ^0.8.19;
+
+// Generated by `plonky2::export::solidity`. Do not edit; regenerate it from the circuit instead.
+
+/// @notice Verifier for the proofs of a single plonky2 circuit using `KeccakGoldilocksConfig`.
+/// Proofs are encoded as described in `plonky2::export::calldata`.
+contract {{CONTRACT_NAME}} {
+    uint256 internal constant P = 0xFFFFFFFF00000001;
+    uint256 internal constant W = 7;
+    uint256 internal constant MASK64 = 0xFFFFFFFFFFFFFFFF;
+    bytes32 internal constant MASK25 = bytes32(~uint256(0) << 56);
+    uint256 internal constant MULTIPLICATIVE_GROUP_GENERATOR = 7;
+    uint256 internal constant POWER_OF_TWO_GENERATOR = 1753635133440165772;
+    uint256 internal constant TWO_ADICITY = 32;
+    uint256 internal constant HASH_WORDS = 4;
+    uint256 internal constant SPONGE_RATE = 8;
+    uint256 internal constant SPONGE_WIDTH = 12;
+    uint256 internal constant POSEIDON_HALF_FULL_ROUNDS = 4;
+    uint256 internal constant POSEIDON_ROUNDS = 30;
+
+{{CONSTANTS}}
+
+    struct Challenger {
+        uint256[12] state;
+        uint256[8] inputs;
+        uint256 numInputs;
+        uint256 numOutputs;
+    }
+
+    struct Challenges {
+        uint256[] betas;
+        uint256[] gammas;
+        uint256[] alphas;
+        uint256 zeta;
+        uint256 friAlpha;
+        uint256[] friBetas;
+        uint256 powResponse;
+        uint256[] queryIndices;
+    }
+
+    /// @dev The state of a FRI query: the index and point queried, and the expected evaluation.
+    struct Query {
+        uint256 index;
+        uint256 x;
+        uint256 eval;
+    }
+
+    /// @notice Verifies a proof, reverting if it is invalid.
+    function verify(uint64[] calldata proof, uint64[] calldata publicInputs) external pure returns (bool) {
+        require(proof.length == PROOF_LEN, "invalid proof length");
+        require(publicInputs.length == NUM_PUBLIC_INPUTS, "invalid number of public inputs");
+        for (uint256 i = 0; i < proof.length; i++) {
+            require(proof[i] < P, "non-canonical field element");
+        }
+        for (uint256 i = 0; i < publicInputs.length; i++) {
+            require(publicInputs[i] < P, "non-canonical field element");
+        }
+
+        uint256[4] memory publicInputsHash = hashPublicInputs(publicInputs);
+        Challenges memory ch = getChallenges(proof, publicInputsHash);
+        uint256[] memory openings = readOpenings(proof);
+        verifyVanishingPoly(openings, publicInputsHash, ch);
+        verifyFri(proof, openings, ch);
+        return true;
+    }
+
+    // Goldilocks field arithmetic. Extension field elements `a0 + a1 X`, with `X^2 = W`, are packed
+    // as `a0 | a1 << 64`.
+
+    function pow(uint256 x, uint256 e) internal pure returns (uint256 r) {
+        r = 1;
+        while (e != 0) {
+            if ((e & 1) == 1) {
+                r = mulmod(r, x, P);
+            }
+            x = mulmod(x, x, P);
+            e >>= 1;
+        }
+    }
+
+    function powPow2(uint256 x, uint256 bits) internal pure returns (uint256) {
+        for (uint256 i = 0; i < bits; i++) {
+            x = mulmod(x, x, P);
+        }
+        return x;
+    }
+
+    function inv(uint256 x) internal pure returns (uint256) {
+        require(x != 0, "division by zero");
+        return pow(x, P - 2);
+    }
+
+    function rootOfUnity(uint256 bits) internal pure returns (uint256 g) {
+        g = POWER_OF_TWO_GENERATOR;
+        for (uint256 i = bits; i < TWO_ADICITY; i++) {
+            g = mulmod(g, g, P);
+        }
+    }
+
+    function ext(uint256 a0, uint256 a1) internal pure returns (uint256) {
+        return a0 | (a1 << 64);
+    }
+
+    function eadd(uint256 a, uint256 b) internal pure returns (uint256) {
+        return ext(addmod(a & MASK64, b & MASK64, P), addmod(a >> 64, b >> 64, P));
+    }
+
+    function esub(uint256 a, uint256 b) internal pure returns (uint256) {
+        return ext(addmod(a & MASK64, P - (b & MASK64), P), addmod(a >> 64, P - (b >> 64), P));
+    }
+
+    function emul(uint256 a, uint256 b) internal pure returns (uint256) {
+        uint256 a0 = a & MASK64;
+        uint256 a1 = a >> 64;
+        uint256 b0 = b & MASK64;
+        uint256 b1 = b >> 64;
+        return ext(
+            addmod(mulmod(a0, b0, P), mulmod(W, mulmod(a1, b1, P), P), P),
+            addmod(mulmod(a0, b1, P), mulmod(a1, b0, P), P)
+        );
+    }
+
+    function escale(uint256 a, uint256 s) internal pure returns (uint256) {
+        return ext(mulmod(a & MASK64, s, P), mulmod(a >> 64, s, P));
+    }
+
+    function einv(uint256 a) internal pure returns (uint256) {
+        uint256 a0 = a & MASK64;
+        uint256 a1 = a >> 64;
+        uint256 normInv = inv(addmod(mulmod(a0, a0, P), P - mulmod(W, mulmod(a1, a1, P), P), P));
+        return ext(mulmod(a0, normInv, P), mulmod(P - a1, normInv, P));
+    }
+
+    function epowPow2(uint256 a, uint256 bits) internal pure returns (uint256) {
+        for (uint256 i = 0; i < bits; i++) {
+            a = emul(a, a);
+        }
+        return a;
+    }
+
+    /// @dev `c0 * m0 * m1 + c1 * a`.
+    function arith(uint256 c0, uint256 c1, uint256 m0, uint256 m1, uint256 a) internal pure returns (uint256) {
+        return eadd(escale(emul(m0, m1), c0), escale(a, c1));
+    }
+
+    function readExt(uint64[] calldata proof, uint256 i) internal pure returns (uint256) {
+        return ext(proof[i], proof[i + 1]);
+    }
+
+    // Encoding helpers.
+
+    function reverseBits(uint256 v, uint256 bits) internal pure returns (uint256 r) {
+        for (uint256 i = 0; i < bits; i++) {
+            r = (r << 1) | (v & 1);
+            v >>= 1;
+        }
+    }
+
+    /// @dev Reverses the bytes of a 64-bit word.
+    function reverse64(uint256 v) internal pure returns (uint256) {
+        v = ((v & 0xFF00FF00FF00FF00) >> 8) | ((v & 0x00FF00FF00FF00FF) << 8);
+        v = ((v & 0xFFFF0000FFFF0000) >> 16) | ((v & 0x0000FFFF0000FFFF) << 16);
+        return (v >> 32) | ((v & 0xFFFFFFFF) << 32);
+    }
+
+    /// @dev Reads the `i`-th big-endian 64-bit word of `b`.
+    function readWord(bytes memory b, uint256 i) internal pure returns (uint256 v) {
+        assembly ("memory-safe") {
+            v := shr(192, mload(add(add(b, 32), mul(i, 8))))
+        }
+    }
+
+    function readHash(bytes memory b, uint256 i) internal pure returns (bytes32 h) {
+        assembly ("memory-safe") {
+            h := mload(add(add(b, 32), mul(i, 32)))
+        }
+    }
+
+    // Truncated Keccak hashing, as in `KeccakHash<25>`. Digests are left-aligned in a `bytes32`.
+
+    /// @dev Keccak-256 of the little-endian encoding of `words`.
+    function keccakWords(uint256[] memory words) internal pure returns (bytes32 h) {
+        uint256 n = words.length;
+        bytes memory buf = new bytes(8 * n + 24);
+        for (uint256 i = 0; i < n; i++) {
+            uint256 v = reverse64(words[i]) << 192;
+            assembly ("memory-safe") {
+                mstore(add(add(buf, 32), mul(i, 8)), v)
+            }
+        }
+        assembly ("memory-safe") {
+            h := keccak256(add(buf, 32), mul(n, 8))
+        }
+    }
+
+    function hashOrNoop(uint256[] memory leaf) internal pure returns (bytes32) {
+        if (8 * leaf.length <= 25) {
+            uint256 h = 0;
+            for (uint256 i = 0; i < leaf.length; i++) {
+                h |= reverse64(leaf[i]) << (192 - 64 * i);
+            }
+            return bytes32(h);
+        }
+        return keccakWords(leaf) & MASK25;
+    }
+
+    function twoToOne(bytes32 left, bytes32 right) internal pure returns (bytes32) {
+        return keccak256(abi.encodePacked(bytes25(left), bytes25(right))) & MASK25;
+    }
+
+    /// @dev Decodes the digest whose 7-byte chunks are the words starting at `start`.
+    function proofHash(uint64[] calldata proof, uint256 start) internal pure returns (bytes32) {
+        uint256 h = 0;
+        for (uint256 j = 0; j < HASH_WORDS; j++) {
+            uint256 w = proof[start + j];
+            require(w >> (j == HASH_WORDS - 1 ? uint256(32) : uint256(56)) == 0, "non-canonical digest");
+            h |= (reverse64(w) >> 8) << (8 * (25 - 7 * j));
+        }
+        return bytes32(h);
+    }
+
+    /// @dev Hashes a leaf up a Merkle proof, returning the digest reached and its index in the cap.
+    function merkleRoot(bytes32 h, uint256 index, uint64[] calldata proof, uint256 siblings, uint256 numSiblings)
+        internal
+        pure
+        returns (bytes32, uint256)
+    {
+        for (uint256 i = 0; i < numSiblings; i++) {
+            bytes32 sibling = proofHash(proof, siblings + HASH_WORDS * i);
+            h = (index & 1) == 1 ? twoToOne(sibling, h) : twoToOne(h, sibling);
+            index >>= 1;
+        }
+        return (h, index);
+    }
+
+    // Poseidon over Goldilocks, used to hash the public inputs.
+
+    function hashPublicInputs(uint64[] calldata publicInputs) internal pure returns (uint256[4] memory out) {
+        uint256[12] memory state;
+        for (uint256 i = 0; i < publicInputs.length; i += SPONGE_RATE) {
+            for (uint256 j = 0; j < SPONGE_RATE && i + j < publicInputs.length; j++) {
+                state[j] = publicInputs[i + j];
+            }
+            poseidon(state);
+        }
+        for (uint256 j = 0; j < 4; j++) {
+            out[j] = state[j];
+        }
+    }
+
+    function poseidon(uint256[12] memory state) internal pure {
+        bytes memory roundConstants = POSEIDON_ROUND_CONSTANTS;
+        for (uint256 r = 0; r < POSEIDON_ROUNDS; r++) {
+            for (uint256 i = 0; i < SPONGE_WIDTH; i++) {
+                state[i] = addmod(state[i], readWord(roundConstants, SPONGE_WIDTH * r + i), P);
+            }
+            if (r < POSEIDON_HALF_FULL_ROUNDS || r >= POSEIDON_ROUNDS - POSEIDON_HALF_FULL_ROUNDS) {
+                for (uint256 i = 0; i < SPONGE_WIDTH; i++) {
+                    state[i] = sbox(state[i]);
+                }
+            } else {
+                state[0] = sbox(state[0]);
+            }
+            mdsLayer(state);
+        }
+    }
+
+    function sbox(uint256 x) internal pure returns (uint256) {
+        uint256 x2 = mulmod(x, x, P);
+        uint256 x4 = mulmod(x2, x2, P);
+        return mulmod(mulmod(x, x2, P), x4, P);
+    }
+
+    function mdsLayer(uint256[12] memory state) internal pure {
+        bytes memory circ = POSEIDON_MDS_CIRC;
+        bytes memory diag = POSEIDON_MDS_DIAG;
+        uint256[12] memory result;
+        for (uint256 r = 0; r < SPONGE_WIDTH; r++) {
+            uint256 acc = mulmod(state[r], readWord(diag, r), P);
+            for (uint256 i = 0; i < SPONGE_WIDTH; i++) {
+                acc = addmod(acc, mulmod(state[(i + r) % SPONGE_WIDTH], readWord(circ, i), P), P);
+            }
+            result[r] = acc;
+        }
+        for (uint256 r = 0; r < SPONGE_WIDTH; r++) {
+            state[r] = result[r];
+        }
+    }
+
+    // The Fiat-Shamir challenger, a duplex sponge over the Keccak pseudo-permutation.
+
+    function keccakPermute(uint256[12] memory state) internal pure {
+        uint256[] memory words = new uint256[](SPONGE_WIDTH);
+        for (uint256 i = 0; i < SPONGE_WIDTH; i++) {
+            words[i] = state[i];
+        }
+        bytes32 h = keccakWords(words);
+        uint256 n = 0;
+        while (true) {
+            for (uint256 k = 0; k < 4 && n < SPONGE_WIDTH; k++) {
+                uint256 w = reverse64((uint256(h) >> (192 - 64 * k)) & MASK64);
+                if (w < P) {
+                    state[n] = w;
+                    n++;
+                }
+            }
+            if (n == SPONGE_WIDTH) {
+                break;
+            }
+            h = keccak256(abi.encodePacked(h));
+        }
+    }
+
+    function duplex(Challenger memory c) internal pure {
+        for (uint256 i = 0; i < c.numInputs; i++) {
+            c.state[i] = c.inputs[i];
+        }
+        c.numInputs = 0;
+        keccakPermute(c.state);
+        c.numOutputs = SPONGE_RATE;
+    }
+
+    function observe(Challenger memory c, uint256 x) internal pure {
+        c.numOutputs = 0;
+        c.inputs[c.numInputs] = x;
+        c.numInputs++;
+        if (c.numInputs == SPONGE_RATE) {
+            duplex(c);
+        }
+    }
+
+    function observeProof(Challenger memory c, uint64[] calldata proof, uint256 start, uint256 len) internal pure {
+        for (uint256 i = start; i < start + len; i++) {
+            observe(c, proof[i]);
+        }
+    }
+
+    function challenge(Challenger memory c) internal pure returns (uint256) {
+        if (c.numInputs != 0 || c.numOutputs == 0) {
+            duplex(c);
+        }
+        c.numOutputs--;
+        return c.state[c.numOutputs];
+    }
+
+    function nChallenges(Challenger memory c, uint256 n) internal pure returns (uint256[] memory r) {
+        r = new uint256[](n);
+        for (uint256 i = 0; i < n; i++) {
+            r[i] = challenge(c);
+        }
+    }
+
+    function extChallenge(Challenger memory c) internal pure returns (uint256) {
+        uint256 a0 = challenge(c);
+        return ext(a0, challenge(c));
+    }
+
+    function getChallenges(uint64[] calldata proof, uint256[4] memory publicInputsHash)
+        internal
+        pure
+        returns (Challenges memory ch)
+    {
+        Challenger memory c;
+        observe(c, CIRCUIT_DIGEST_0);
+        observe(c, CIRCUIT_DIGEST_1);
+        observe(c, CIRCUIT_DIGEST_2);
+        observe(c, CIRCUIT_DIGEST_3);
+        for (uint256 i = 0; i < 4; i++) {
+            observe(c, publicInputsHash[i]);
+        }
+
+        observeProof(c, proof, WIRES_CAP, CAP_LEN);
+        ch.betas = nChallenges(c, NUM_CHALLENGES);
+        ch.gammas = nChallenges(c, NUM_CHALLENGES);
+        observeProof(c, proof, ZS_PARTIAL_PRODUCTS_CAP, CAP_LEN);
+        ch.alphas = nChallenges(c, NUM_CHALLENGES);
+        observeProof(c, proof, QUOTIENT_POLYS_CAP, CAP_LEN);
+        ch.zeta = extChallenge(c);
+        observeProof(c, proof, OPENINGS, 2 * (NUM_ZETA_OPENINGS + NUM_NEXT_OPENINGS));
+
+        ch.friAlpha = extChallenge(c);
+        ch.friBetas = new uint256[](NUM_REDUCTION_STEPS);
+        for (uint256 i = 0; i < NUM_REDUCTION_STEPS; i++) {
+            observeProof(c, proof, COMMIT_PHASE_CAPS + i * CAP_LEN, CAP_LEN);
+            ch.friBetas[i] = extChallenge(c);
+        }
+        observeProof(c, proof, FINAL_POLY, 2 * FINAL_POLY_LEN + 1);
+        ch.powResponse = challenge(c);
+        ch.queryIndices = nChallenges(c, NUM_QUERY_ROUNDS);
+        for (uint256 i = 0; i < NUM_QUERY_ROUNDS; i++) {
+            ch.queryIndices[i] %= LDE_SIZE;
+        }
+    }
+
+    // The vanishing polynomial identity at zeta.
+
+    function readOpenings(uint64[] calldata proof) internal pure returns (uint256[] memory o) {
+        o = new uint256[](NUM_ZETA_OPENINGS + NUM_NEXT_OPENINGS);
+        for (uint256 i = 0; i < o.length; i++) {
+            o[i] = readExt(proof, OPENINGS + 2 * i);
+        }
+    }
+
+    function verifyVanishingPoly(uint256[] memory o, uint256[4] memory publicInputsHash, Challenges memory ch)
+        internal
+        pure
+    {
+        uint256 zetaPowDeg = epowPow2(ch.zeta, DEGREE_BITS);
+        uint256 zH = esub(zetaPowDeg, 1);
+        uint256 l0 = ch.zeta == 1 ? uint256(1) : emul(zH, einv(escale(esub(ch.zeta, 1), DEGREE)));
+
+        uint256[] memory terms = new uint256[](NUM_VANISHING_TERMS);
+        for (uint256 i = 0; i < NUM_CHALLENGES; i++) {
+            terms[i] = emul(l0, esub(o[ZS + i], 1));
+        }
+        uint256 t = NUM_CHALLENGES;
+        for (uint256 i = 0; i < NUM_CHALLENGES; i++) {
+            t = partialProductTerms(o, ch, i, terms, t);
+        }
+        uint256[] memory constraints = evalGateConstraints(o, publicInputsHash);
+        for (uint256 i = 0; i < NUM_GATE_CONSTRAINTS; i++) {
+            terms[t + i] = constraints[i];
+        }
+
+        for (uint256 i = 0; i < NUM_CHALLENGES; i++) {
+            uint256 vanishing = 0;
+            for (uint256 j = NUM_VANISHING_TERMS; j > 0; j--) {
+                vanishing = eadd(escale(vanishing, ch.alphas[i]), terms[j - 1]);
+            }
+            uint256 quotient = 0;
+            for (uint256 j = QUOTIENT_DEGREE_FACTOR; j > 0; j--) {
+                quotient = eadd(emul(quotient, zetaPowDeg), o[QUOTIENT + i * QUOTIENT_DEGREE_FACTOR + j - 1]);
+            }
+            require(vanishing == emul(zH, quotient), "vanishing polynomial mismatch");
+        }
+    }
+
+    function partialProductTerms(
+        uint256[] memory o,
+        Challenges memory ch,
+        uint256 i,
+        uint256[] memory terms,
+        uint256 t
+    ) internal pure returns (uint256) {
+        uint256 prev = o[ZS + i];
+        for (uint256 k = 0; k <= NUM_PARTIAL_PRODUCTS; k++) {
+            uint256 next =
+                k == NUM_PARTIAL_PRODUCTS ? o[NEXT_ZS + i] : o[PARTIAL_PRODUCTS + i * NUM_PARTIAL_PRODUCTS + k];
+            (uint256 numerator, uint256 denominator) = chunkProducts(o, ch.zeta, ch.betas[i], ch.gammas[i], k);
+            terms[t] = esub(emul(prev, numerator), emul(next, denominator));
+            t++;
+            prev = next;
+        }
+        return t;
+    }
+
+    /// @dev The products of the permutation argument's numerators and denominators over the `k`-th
+    /// chunk of routed wires.
+    function chunkProducts(uint256[] memory o, uint256 zeta, uint256 beta, uint256 gamma, uint256 k)
+        internal
+        pure
+        returns (uint256 numerator, uint256 denominator)
+    {
+        bytes memory kIs = K_IS;
+        numerator = 1;
+        denominator = 1;
+        for (uint256 j = k * QUOTIENT_DEGREE_FACTOR; j < (k + 1) * QUOTIENT_DEGREE_FACTOR && j < NUM_ROUTED_WIRES; j++) {
+            uint256 wire = eadd(o[WIRES + j], gamma);
+            numerator = emul(numerator, eadd(wire, escale(zeta, mulmod(readWord(kIs, j), beta, P))));
+            denominator = emul(denominator, eadd(wire, escale(o[SIGMAS + j], beta)));
+        }
+    }
+
+    function evalGateConstraints(uint256[] memory o, uint256[4] memory publicInputsHash)
+        internal
+        pure
+        returns (uint256[] memory c)
+    {
+{{GATE_CONSTRAINTS}}
+    }
+
+    // FRI.
+
+    function verifyFri(uint64[] calldata proof, uint256[] memory o, Challenges memory ch) internal pure {
+        require((ch.powResponse >> (64 - POW_BITS)) == 0, "invalid proof of work");
+
+        // The openings at zeta and g * zeta reduced by alpha, g * zeta, and the power of alpha
+        // shifting the first batch past the second.
+        uint256[4] memory reduced;
+        for (uint256 j = NUM_ZETA_OPENINGS; j > 0; j--) {
+            reduced[0] = eadd(emul(reduced[0], ch.friAlpha), o[j - 1]);
+        }
+        for (uint256 j = NUM_NEXT_OPENINGS; j > 0; j--) {
+            reduced[1] = eadd(emul(reduced[1], ch.friAlpha), o[NUM_ZETA_OPENINGS + j - 1]);
+        }
+        reduced[2] = escale(ch.zeta, G);
+        reduced[3] = 1;
+        for (uint256 j = 0; j < NUM_NEXT_OPENINGS; j++) {
+            reduced[3] = emul(reduced[3], ch.friAlpha);
+        }
+
+        for (uint256 q = 0; q < NUM_QUERY_ROUNDS; q++) {
+            verifyQueryRound(proof, ch, reduced, q);
+        }
+    }
+
+    function verifyQueryRound(uint64[] calldata proof, Challenges memory ch, uint256[4] memory reduced, uint256 q)
+        internal
+        pure
+    {
+        uint256 base = QUERY_ROUNDS + q * QUERY_ROUND_LEN;
+        Query memory query;
+        query.index = ch.queryIndices[q];
+        query.x = mulmod(MULTIPLICATIVE_GROUP_GENERATOR, pow(LDE_ROOT, reverseBits(query.index, LDE_BITS)), P);
+        query.eval = combineInitial(proof, base, query, ch, reduced);
+        for (uint256 s = 0; s < NUM_REDUCTION_STEPS; s++) {
+            verifyReductionStep(proof, base, s, query, ch.friBetas[s]);
+        }
+
+        uint256 finalEval = 0;
+        for (uint256 j = FINAL_POLY_LEN; j > 0; j--) {
+            finalEval = eadd(escale(finalEval, query.x), readExt(proof, FINAL_POLY + 2 * (j - 1)));
+        }
+        require(finalEval == query.eval, "invalid final polynomial evaluation");
+    }
+
+    function reduceLeaf(uint64[] calldata proof, uint256 start, uint256 len, uint256 acc, uint256 alpha)
+        internal
+        pure
+        returns (uint256)
+    {
+        for (uint256 j = len; j > 0; j--) {
+            acc = eadd(emul(acc, alpha), proof[start + j - 1]);
+        }
+        return acc;
+    }
+
+    function combineInitial(
+        uint64[] calldata proof,
+        uint256 base,
+        Query memory query,
+        Challenges memory ch,
+        uint256[4] memory reduced
+    ) internal pure returns (uint256) {
+        uint256 zetaEval = 0;
+        for (uint256 i = 4; i > 0; i--) {
+            uint256 start = base + initialLeaf(i - 1);
+            verifyInitialMerkleProof(proof, i - 1, start, query.index);
+            zetaEval = reduceLeaf(proof, start, oracleSize(i - 1), zetaEval, ch.friAlpha);
+        }
+        uint256 nextEval = reduceLeaf(proof, base + initialLeaf(2), NUM_NEXT_OPENINGS, 0, ch.friAlpha);
+        uint256 zetaTerm = emul(emul(esub(zetaEval, reduced[0]), einv(esub(query.x, ch.zeta))), reduced[3]);
+        return eadd(zetaTerm, emul(esub(nextEval, reduced[1]), einv(esub(query.x, reduced[2]))));
+    }
+
+    function initialCap(uint256 oracle) internal pure returns (uint256) {
+        if (oracle == 1) {
+            return WIRES_CAP;
+        }
+        if (oracle == 2) {
+            return ZS_PARTIAL_PRODUCTS_CAP;
+        }
+        return QUOTIENT_POLYS_CAP;
+    }
+
+    function verifyInitialMerkleProof(uint64[] calldata proof, uint256 oracle, uint256 start, uint256 index)
+        internal
+        pure
+    {
+        uint256 size = oracleSize(oracle);
+        uint256[] memory leaf = new uint256[](size);
+        for (uint256 j = 0; j < size; j++) {
+            leaf[j] = proof[start + j];
+        }
+        (bytes32 root, uint256 capIndex) = merkleRoot(hashOrNoop(leaf), index, proof, start + size, INITIAL_SIBLINGS);
+        bytes32 expected = oracle == 0
+            ? readHash(CONSTANTS_SIGMAS_CAP, capIndex)
+            : proofHash(proof, initialCap(oracle) + HASH_WORDS * capIndex);
+        require(root == expected, "invalid Merkle proof");
+    }
+
+    function verifyReductionStep(uint64[] calldata proof, uint256 base, uint256 s, Query memory query, uint256 beta)
+        internal
+        pure
+    {
+        uint256 arityBits = reductionArityBits(s);
+        uint256 start = base + stepEvals(s);
+        uint256 within = query.index & ((1 << arityBits) - 1);
+        require(readExt(proof, start + 2 * within) == query.eval, "inconsistent FRI evaluations");
+
+        uint256 cosetStart =
+            mulmod(query.x, pow(rootOfUnity(arityBits), (1 << arityBits) - reverseBits(within, arityBits)), P);
+        query.eval = interpolateCoset(proof, start, arityBits, cosetStart, beta);
+        query.index >>= arityBits;
+        verifyStepMerkleProof(proof, s, start, arityBits, query.index);
+        query.x = powPow2(query.x, arityBits);
+    }
+
+    /// @dev Evaluates at `beta` the polynomial interpolating the evaluations of a reduction step on
+    /// the coset of `point`. The coset points are the roots of `X^n - c^n`, where `c = point`, so
+    /// their barycentric weights are `c * g^i / (n * c^n)`.
+    function interpolateCoset(uint64[] calldata proof, uint256 start, uint256 bits, uint256 point, uint256 beta)
+        internal
+        pure
+        returns (uint256)
+    {
+        uint256 g = rootOfUnity(bits);
+        uint256 cosetStartPow = powPow2(point, bits);
+        uint256 sum = 0;
+        for (uint256 i = 0; i < (1 << bits); i++) {
+            uint256 y = readExt(proof, start + 2 * reverseBits(i, bits));
+            sum = eadd(sum, emul(escale(y, point), einv(esub(beta, point))));
+            point = mulmod(point, g, P);
+        }
+        uint256 scale = inv(mulmod(1 << bits, cosetStartPow, P));
+        return escale(emul(esub(epowPow2(beta, bits), cosetStartPow), sum), scale);
+    }
+
+    function verifyStepMerkleProof(uint64[] calldata proof, uint256 s, uint256 start, uint256 bits, uint256 index)
+        internal
+        pure
+    {
+        uint256 len = 2 << bits;
+        uint256[] memory leaf = new uint256[](len);
+        for (uint256 j = 0; j < len; j++) {
+            leaf[j] = proof[start + j];
+        }
+        (bytes32 root, uint256 capIndex) = merkleRoot(hashOrNoop(leaf), index, proof, start + len, stepSiblings(s));
+        require(
+            root == proofHash(proof, COMMIT_PHASE_CAPS + s * CAP_LEN + HASH_WORDS * capIndex), "invalid Merkle proof"
+        );
+    }
+
+{{LOOKUP_FUNCTIONS}}
+}
@@ -0,0 +1,502 @@
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::max;
+
+use num::{BigUint, Integer, Zero};
+
+use crate::field::extension::Extendable;
+use crate::field::types::PrimeField64;
+use crate::hash::hash_types::RichField;
+use crate::iop::generator::{GeneratedValues, SimpleGenerator};
+use crate::iop::target::{BoolTarget, Target};
+use crate::iop::witness::{PartitionWitness, Witness, WitnessWrite};
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::circuit_data::CommonCircuitData;
+use crate::util::serialization::{Buffer, IoResult, Read, Write};
+
+/// The number of bits in each limb of a `BigUintTarget`.
+pub const BIGUINT_LIMB_BITS: usize = 16;
+
+/// An arbitrarily large unsigned integer, represented as little-endian 16-bit limbs.
+///
+/// Limbs are kept small enough that a full schoolbook product of two 256-bit integers can be
+/// accumulated column by column without overflowing the native field. Every `BigUintTarget`
+/// returned by the builder has range-checked limbs.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct BigUintTarget {
+    pub limbs: Vec<Target>,
+}
+
+impl BigUintTarget {
+    pub fn num_limbs(&self) -> usize {
+        self.limbs.len()
+    }
+
+    pub fn get_limb(&self, i: usize) -> Target {
+        self.limbs[i]
+    }
+}
+
+/// Splits `value` into `num_limbs` little-endian 16-bit limbs, panicking if it does not fit.
+pub(crate) fn biguint_to_limbs(value: &BigUint, num_limbs: usize) -> Vec<u64> {
+    let mut digits = value.to_u64_digits();
+    digits.resize(num_limbs.div_ceil(4).max(digits.len()), 0);
+    let mut limbs: Vec<u64> = digits
+        .iter()
+        .flat_map(|&d| (0..4).map(move |i| (d >> (BIGUINT_LIMB_BITS * i)) & 0xffff))
+        .collect();
+    assert!(
+        limbs[num_limbs..].iter().all(|&l| l == 0),
+        "Value does not fit in {num_limbs} limbs"
+    );
+    limbs.truncate(num_limbs);
+    limbs
+}
+
+/// Returns the number of 16-bit limbs needed to represent integers of `num_bits` bits.
+pub(crate) fn num_biguint_limbs(num_bits: usize) -> usize {
+    num_bits.div_ceil(BIGUINT_LIMB_BITS)
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
+    pub fn constant_biguint(&mut self, value: &BigUint) -> BigUintTarget {
+        let num_limbs = num_biguint_limbs(value.bits() as usize).max(1);
+        let limbs = biguint_to_limbs(value, num_limbs)
+            .into_iter()
+            .map(|l| self.constant(F::from_canonical_u64(l)))
+            .collect();
+        BigUintTarget { limbs }
+    }
+
+    pub fn zero_biguint(&mut self) -> BigUintTarget {
+        self.constant_biguint(&BigUint::zero())
+    }
+
+    /// Returns a new `BigUintTarget` with `num_limbs` range-checked limbs.
+    pub fn add_virtual_biguint_target(&mut self, num_limbs: usize) -> BigUintTarget {
+        let limbs = self.add_virtual_targets(num_limbs);
+        for &limb in &limbs {
+            self.range_check_u16(limb);
+        }
+        BigUintTarget { limbs }
+    }
+
+    /// Builds a `BigUintTarget` from little-endian limbs, which are range-checked.
+    pub fn biguint_from_limbs(&mut self, limbs: Vec<Target>) -> BigUintTarget {
+        for &limb in &limbs {
+            self.range_check_u16(limb);
+        }
+        BigUintTarget { limbs }
+    }
+
+    /// Pads `a` with zero limbs up to `num_limbs` limbs.
+    pub fn pad_biguint(&mut self, a: &BigUintTarget, num_limbs: usize) -> BigUintTarget {
+        let mut limbs = a.limbs.clone();
+        while limbs.len() < num_limbs {
+            limbs.push(self.zero());
+        }
+        BigUintTarget { limbs }
+    }
+
+    /// Asserts that `a` and `b` represent the same integer, which may use different limb counts.
+    pub fn connect_biguint(&mut self, a: &BigUintTarget, b: &BigUintTarget) {
+        let min_limbs = a.num_limbs().min(b.num_limbs());
+        for i in 0..min_limbs {
+            self.connect(a.limbs[i], b.limbs[i]);
+        }
+        for &limb in a.limbs[min_limbs..].iter().chain(&b.limbs[min_limbs..]) {
+            self.assert_zero(limb);
+        }
+    }
+
+    /// Returns whether `a` and `b` represent the same integer.
+    pub fn is_equal_biguint(&mut self, a: &BigUintTarget, b: &BigUintTarget) -> BoolTarget {
+        let num_limbs = max(a.num_limbs(), b.num_limbs());
+        let a = self.pad_biguint(a, num_limbs);
+        let b = self.pad_biguint(b, num_limbs);
+        let mut result = self._true();
+        for i in 0..num_limbs {
+            let eq = self.is_equal(a.limbs[i], b.limbs[i]);
+            result = self.and(result, eq);
+        }
+        result
+    }
+
+    /// Splits a native value which is known to be less than `2^48` into a low 16-bit limb and a
+    /// carry which is less than `2^32`.
+    fn split_limb_carry(&mut self, x: Target) -> (Target, Target) {
+        let parts = self.add_virtual_targets(3);
+        self.add_simple_generator(LimbDecompositionGenerator {
+            integer: x,
+            limbs: parts.clone(),
+        });
+        for &part in &parts {
+            self.range_check_u16(part);
+        }
+
+        let base = F::from_canonical_u64(1 << BIGUINT_LIMB_BITS);
+        let carry = self.mul_const_add(base, parts[2], parts[1]);
+        let sum = self.mul_const_add(base, carry, parts[0]);
+        self.connect(x, sum);
+
+        (parts[0], carry)
+    }
+
+    /// Normalizes columns whose values are less than `2^47` into range-checked limbs, with one
+    /// extra limb holding the final carry, which must be less than `2^16`.
+    pub(crate) fn normalize_biguint_columns(&mut self, columns: Vec<Target>) -> BigUintTarget {
+        let mut limbs = Vec::with_capacity(columns.len() + 1);
+        let mut carry = self.zero();
+        for column in columns {
+            let total = self.add(column, carry);
+            let (limb, new_carry) = self.split_limb_carry(total);
+            limbs.push(limb);
+            carry = new_carry;
+        }
+        let (limb, overflow) = self.split_limb_carry(carry);
+        limbs.push(limb);
+        self.assert_zero(overflow);
+        BigUintTarget { limbs }
+    }
+
+    /// Computes `a + b`.
+    pub fn add_biguint(&mut self, a: &BigUintTarget, b: &BigUintTarget) -> BigUintTarget {
+        let num_limbs = max(a.num_limbs(), b.num_limbs());
+        let a = self.pad_biguint(a, num_limbs);
+        let b = self.pad_biguint(b, num_limbs);
+        let columns = (0..num_limbs)
+            .map(|i| self.add(a.limbs[i], b.limbs[i]))
+            .collect();
+        self.normalize_biguint_columns(columns)
+    }
+
+    /// Computes `a - b` modulo `2^(16 * n)`, where `n` is the larger limb count, along with a
+    /// borrow which is set iff `a < b`.
+    fn sub_biguint_with_borrow(
+        &mut self,
+        a: &BigUintTarget,
+        b: &BigUintTarget,
+    ) -> (BigUintTarget, BoolTarget) {
+        let num_limbs = max(a.num_limbs(), b.num_limbs());
+        let a = self.pad_biguint(a, num_limbs);
+        let b = self.pad_biguint(b, num_limbs);
+
+        let base = F::from_canonical_u64(1 << BIGUINT_LIMB_BITS);
+        let mut limbs = Vec::with_capacity(num_limbs);
+        let mut borrow = self.zero();
+        for i in 0..num_limbs {
+            // `a_i - b_i - borrow + 2^16` lies in `[0, 2^17)`, so its high part is a bit.
+            let diff = self.sub(a.limbs[i], b.limbs[i]);
+            let diff = self.sub(diff, borrow);
+            let shifted = self.add_const(diff, base);
+            let (limb, no_borrow) = self.split_limb_carry(shifted);
+            limbs.push(limb);
+            let one = self.one();
+            borrow = self.sub(one, no_borrow);
+        }
+
+        (BigUintTarget { limbs }, BoolTarget::new_unsafe(borrow))
+    }
+
+    /// Computes `a - b`, asserting that `a >= b`.
+    pub fn sub_biguint(&mut self, a: &BigUintTarget, b: &BigUintTarget) -> BigUintTarget {
+        let (diff, borrow) = self.sub_biguint_with_borrow(a, b);
+        self.assert_zero(borrow.target);
+        diff
+    }
+
+    /// Returns whether `a <= b`.
+    pub fn cmp_biguint(&mut self, a: &BigUintTarget, b: &BigUintTarget) -> BoolTarget {
+        let (_, borrow) = self.sub_biguint_with_borrow(b, a);
+        self.not(borrow)
+    }
+
+    /// Asserts that `a < b`.
+    pub fn assert_lt_biguint(&mut self, a: &BigUintTarget, b: &BigUintTarget) {
+        let (_, borrow) = self.sub_biguint_with_borrow(a, b);
+        self.assert_one(borrow.target);
+    }
+
+    /// Computes `a * b`.
+    pub fn mul_biguint(&mut self, a: &BigUintTarget, b: &BigUintTarget) -> BigUintTarget {
+        // Each column is a sum of at most `min(n, m)` products of 16-bit limbs, so columns stay
+        // well below `2^47` as long as `min(n, m) < 2^15`.
+        debug_assert!(a.num_limbs().min(b.num_limbs()) < 1 << 15);
+        let num_columns = a.num_limbs() + b.num_limbs() - 1;
+        let mut columns = vec![self.zero(); num_columns];
+        for (i, &a_limb) in a.limbs.iter().enumerate() {
+            for (j, &b_limb) in b.limbs.iter().enumerate() {
+                columns[i + j] = self.mul_add(a_limb, b_limb, columns[i + j]);
+            }
+        }
+        self.normalize_biguint_columns(columns)
+    }
+
+    /// Computes `sum_i c_i * a_i * b_i` for small coefficients `c_i`, normalizing only once.
+    pub fn inner_product_biguint(
+        &mut self,
+        terms: &[(u64, &BigUintTarget, &BigUintTarget)],
+    ) -> BigUintTarget {
+        let num_columns = terms
+            .iter()
+            .map(|(_, a, b)| a.num_limbs() + b.num_limbs() - 1)
+            .max()
+            .unwrap_or(1);
+        let coefficient_sum: u64 = terms.iter().map(|&(c, _, _)| c).sum();
+        // The result can exceed the product width by a factor of `coefficient_sum`.
+        let extra_columns = num_biguint_limbs(64 - coefficient_sum.leading_zeros() as usize);
+        let mut columns = vec![self.zero(); num_columns + extra_columns];
+
+        let limb_max = (1u128 << BIGUINT_LIMB_BITS) - 1;
+        let mut column_bound = 0u128;
+        for &(c, a, b) in terms {
+            column_bound += c as u128 * a.num_limbs().min(b.num_limbs()) as u128;
+            let c = F::from_canonical_u64(c);
+            for (i, &a_limb) in a.limbs.iter().enumerate() {
+                for (j, &b_limb) in b.limbs.iter().enumerate() {
+                    columns[i + j] = self.arithmetic(c, F::ONE, a_limb, b_limb, columns[i + j]);
+                }
+            }
+        }
+        assert!(
+            column_bound * limb_max * limb_max < 1 << 47,
+            "Too many terms for inner_product_biguint"
+        );
+
+        self.normalize_biguint_columns(columns)
+    }
+
+    /// Computes `a * b` for a boolean `b`.
+    pub fn mul_biguint_by_bool(&mut self, a: &BigUintTarget, b: BoolTarget) -> BigUintTarget {
+        let limbs = a.limbs.iter().map(|&l| self.mul(l, b.target)).collect();
+        BigUintTarget { limbs }
+    }
+
+    /// Returns `(a / b, a % b)`, with a quotient of `num_quotient_limbs` limbs. The caller must
+    /// ensure that the quotient fits, or the witness will be unsatisfiable.
+    pub fn div_rem_biguint_with_quotient_limbs(
+        &mut self,
+        a: &BigUintTarget,
+        b: &BigUintTarget,
+        num_quotient_limbs: usize,
+    ) -> (BigUintTarget, BigUintTarget) {
+        let quotient = self.add_virtual_biguint_target(num_quotient_limbs);
+        let remainder = self.add_virtual_biguint_target(b.num_limbs());
+
+        self.add_simple_generator(BigUintDivRemGenerator {
+            a: a.clone(),
+            b: b.clone(),
+            quotient: quotient.clone(),
+            remainder: remainder.clone(),
+        });
+
+        let product = self.mul_biguint(&quotient, b);
+        let recombined = self.add_biguint(&product, &remainder);
+        self.connect_biguint(&recombined, a);
+        self.assert_lt_biguint(&remainder, b);
+
+        (quotient, remainder)
+    }
+
+    /// Returns `(a / b, a % b)`.
+    pub fn div_rem_biguint(
+        &mut self,
+        a: &BigUintTarget,
+        b: &BigUintTarget,
+    ) -> (BigUintTarget, BigUintTarget) {
+        let num_quotient_limbs = a.num_limbs().saturating_sub(b.num_limbs()) + 1;
+        self.div_rem_biguint_with_quotient_limbs(a, b, num_quotient_limbs)
+    }
+
+    pub fn div_biguint(&mut self, a: &BigUintTarget, b: &BigUintTarget) -> BigUintTarget {
+        self.div_rem_biguint(a, b).0
+    }
+
+    pub fn rem_biguint(&mut self, a: &BigUintTarget, b: &BigUintTarget) -> BigUintTarget {
+        self.div_rem_biguint(a, b).1
+    }
+}
+
+/// Decomposes `integer` into little-endian 16-bit limbs.
+#[derive(Debug, Default)]
+pub struct LimbDecompositionGenerator {
+    integer: Target,
+    limbs: Vec<Target>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D>
+    for LimbDecompositionGenerator
+{
+    fn id(&self) -> String {
+        "LimbDecompositionGenerator".to_string()
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        vec![self.integer]
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let mut integer_value = witness.get_target(self.integer).to_canonical_u64();
+        for &limb in &self.limbs {
+            out_buffer.set_target(limb, F::from_canonical_u64(integer_value & 0xffff));
+            integer_value >>= BIGUINT_LIMB_BITS;
+        }
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_target(self.integer)?;
+        dst.write_target_vec(&self.limbs)
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let integer = src.read_target()?;
+        let limbs = src.read_target_vec()?;
+        Ok(Self { integer, limbs })
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct BigUintDivRemGenerator {
+    a: BigUintTarget,
+    b: BigUintTarget,
+    quotient: BigUintTarget,
+    remainder: BigUintTarget,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D>
+    for BigUintDivRemGenerator
+{
+    fn id(&self) -> String {
+        "BigUintDivRemGenerator".to_string()
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        self.a.limbs.iter().chain(&self.b.limbs).copied().collect()
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let a = witness.get_biguint_target(&self.a);
+        let b = witness.get_biguint_target(&self.b);
+        let (quotient, remainder) = a.div_rem(&b);
+
+        out_buffer.set_biguint_target(&self.quotient, &quotient);
+        out_buffer.set_biguint_target(&self.remainder, &remainder);
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_target_vec(&self.a.limbs)?;
+        dst.write_target_vec(&self.b.limbs)?;
+        dst.write_target_vec(&self.quotient.limbs)?;
+        dst.write_target_vec(&self.remainder.limbs)
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let a = BigUintTarget {
+            limbs: src.read_target_vec()?,
+        };
+        let b = BigUintTarget {
+            limbs: src.read_target_vec()?,
+        };
+        let quotient = BigUintTarget {
+            limbs: src.read_target_vec()?,
+        };
+        let remainder = BigUintTarget {
+            limbs: src.read_target_vec()?,
+        };
+        Ok(Self {
+            a,
+            b,
+            quotient,
+            remainder,
+        })
+    }
+}
+
+/// Returns the integer represented by the given limb values.
+pub(crate) fn biguint_from_limb_values<F: PrimeField64>(limbs: &[F]) -> BigUint {
+    limbs.iter().rev().fold(BigUint::zero(), |acc, limb| {
+        (acc << BIGUINT_LIMB_BITS) + limb.to_canonical_u64()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use num::{BigUint, FromPrimitive, Integer};
+    use rand::rngs::OsRng;
+    use rand::Rng;
+
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::verifier::verify;
+
+    fn rand_biguint(num_u32_digits: usize) -> BigUint {
+        let mut rng = OsRng;
+        BigUint::from_slice(&(0..num_u32_digits).map(|_| rng.gen()).collect::<Vec<u32>>())
+    }
+
+    #[test]
+    fn test_biguint_arithmetic() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let x_value = rand_biguint(8);
+        let y_value = rand_biguint(5);
+        let (div_value, rem_value) = x_value.div_rem(&y_value);
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let x = builder.add_virtual_biguint_target(16);
+        let y = builder.add_virtual_biguint_target(10);
+        pw.set_biguint_target(&x, &x_value);
+        pw.set_biguint_target(&y, &y_value);
+
+        let sum = builder.add_biguint(&x, &y);
+        let diff = builder.sub_biguint(&x, &y);
+        let product = builder.mul_biguint(&x, &y);
+        let (div, rem) = builder.div_rem_biguint(&x, &y);
+        let le = builder.cmp_biguint(&y, &x);
+        let ge = builder.cmp_biguint(&x, &y);
+
+        let expected_sum = builder.constant_biguint(&(&x_value + &y_value));
+        let expected_diff = builder.constant_biguint(&(&x_value - &y_value));
+        let expected_product = builder.constant_biguint(&(&x_value * &y_value));
+        let expected_div = builder.constant_biguint(&div_value);
+        let expected_rem = builder.constant_biguint(&rem_value);
+        builder.connect_biguint(&sum, &expected_sum);
+        builder.connect_biguint(&diff, &expected_diff);
+        builder.connect_biguint(&product, &expected_product);
+        builder.connect_biguint(&div, &expected_div);
+        builder.connect_biguint(&rem, &expected_rem);
+        builder.assert_one(le.target);
+        builder.assert_zero(ge.target);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_biguint_sub_underflow() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let x = builder.constant_biguint(&BigUint::from_u64(1 << 40).unwrap());
+        let y = builder.constant_biguint(&BigUint::from_u64((1 << 40) + 1).unwrap());
+        builder.sub_biguint(&x, &y);
+
+        let data = builder.build::<C>();
+        data.prove(PartialWitness::new()).unwrap();
+    }
+}
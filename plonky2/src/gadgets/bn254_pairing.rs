@@ -0,0 +1,496 @@
+use alloc::vec::Vec;
+
+use num::{BigUint, One};
+
+use crate::curve::bn254::Bn254;
+use crate::curve::bn254_pairing::{bn_hard_part_digits, g2_curve_b, G2Point};
+use crate::field::bn254_base::Bn254Base;
+use crate::field::bn254_scalar::Bn254Scalar;
+use crate::field::extension::Extendable;
+use crate::field::types::{Field, PrimeField};
+use crate::gadgets::bn254_tower::{Fp12Target, Fp2Target, FpTarget};
+use crate::gadgets::curve::AffinePointTarget;
+use crate::gadgets::nonnative::NonNativeTarget;
+use crate::hash::hash_types::RichField;
+use crate::iop::target::BoolTarget;
+use crate::plonk::circuit_builder::CircuitBuilder;
+
+/// A point of BN254's `G2`, in affine coordinates over `Fp2`. As with `AffinePointTarget`, the
+/// point at infinity cannot be represented and the addition formulas are incomplete.
+#[derive(Clone, Debug)]
+pub struct G2Target {
+    pub x: Fp2Target,
+    pub y: Fp2Target,
+}
+
+/// A fixed "nothing up my sleeve" scalar (the leading hexadecimal digits of e), whose multiple of
+/// the generator offsets the accumulator of `g2_scalar_mul`.
+const G2_OFFSET_SCALAR: [u64; 4] = [
+    0x6A784D9045190CFE,
+    0x762E7160F38B4DA5,
+    0xABF7158809CF4F3C,
+    0x2B7E151628AED2A6,
+];
+
+/// The nonzero coefficients of a line evaluation, as produced by `bn_sparse_embed`.
+type SparseLine = [(usize, FpTarget); 5];
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
+    pub fn constant_g2_point(&mut self, point: G2Point) -> G2Target {
+        debug_assert!(!point.zero);
+        G2Target {
+            x: self.constant_fp2(point.x),
+            y: self.constant_fp2(point.y),
+        }
+    }
+
+    /// Returns a new `G2Target` with canonical coordinates. The point is not checked to lie on
+    /// the twist; see `g2_assert_valid`.
+    pub fn add_virtual_g2_target(&mut self) -> G2Target {
+        G2Target {
+            x: self.add_virtual_fp2_target(),
+            y: self.add_virtual_fp2_target(),
+        }
+    }
+
+    pub fn connect_g2(&mut self, lhs: &G2Target, rhs: &G2Target) {
+        self.connect_fp2(&lhs.x, &rhs.x);
+        self.connect_fp2(&lhs.y, &rhs.y);
+    }
+
+    /// Asserts that `p` lies on the twist `y^2 = x^3 + 3 / (9 + i)`.
+    pub fn g2_assert_valid(&mut self, p: &G2Target) {
+        let b = self.constant_fp2(g2_curve_b());
+        let y_squared = self.square_fp2(&p.y);
+        let x_squared = self.square_fp2(&p.x);
+        let x_cubed = self.mul_fp2(&x_squared, &p.x);
+        let rhs = self.add_fp2(&x_cubed, &b);
+        self.connect_fp2(&y_squared, &rhs);
+    }
+
+    pub fn g2_neg(&mut self, p: &G2Target) -> G2Target {
+        G2Target {
+            x: p.x.clone(),
+            y: self.neg_fp2(&p.y),
+        }
+    }
+
+    /// Returns `b ? p1 : p2`.
+    pub fn g2_select(&mut self, b: BoolTarget, p1: &G2Target, p2: &G2Target) -> G2Target {
+        G2Target {
+            x: self.select_fp2(b, &p1.x, &p2.x),
+            y: self.select_fp2(b, &p1.y, &p2.y),
+        }
+    }
+
+    pub fn g2_double(&mut self, p: &G2Target) -> G2Target {
+        let x_squared = self.square_fp2(&p.x);
+        let numerator = self.linear_combination_fp2(&[(3, &x_squared)]);
+        let denominator = self.linear_combination_fp2(&[(2, &p.y)]);
+        let lambda = self.div_fp2(&numerator, &denominator);
+        let lambda_squared = self.square_fp2(&lambda);
+        let x3 = self.linear_combination_fp2(&[(1, &lambda_squared), (-2, &p.x)]);
+        self.g2_finish_add(p, &lambda, x3)
+    }
+
+    /// Adds two points, which must have distinct `x` coordinates.
+    pub fn g2_add(&mut self, p1: &G2Target, p2: &G2Target) -> G2Target {
+        let numerator = self.sub_fp2(&p2.y, &p1.y);
+        let denominator = self.sub_fp2(&p2.x, &p1.x);
+        let lambda = self.div_fp2(&numerator, &denominator);
+        let lambda_squared = self.square_fp2(&lambda);
+        let x3 = self.linear_combination_fp2(&[(1, &lambda_squared), (-1, &p1.x), (-1, &p2.x)]);
+        self.g2_finish_add(p1, &lambda, x3)
+    }
+
+    /// Given the slope `lambda` of the line through `p1` and the result, and the result's `x`
+    /// coordinate, computes the result's `y` coordinate.
+    fn g2_finish_add(&mut self, p1: &G2Target, lambda: &Fp2Target, x3: Fp2Target) -> G2Target {
+        let dx = self.sub_fp2(&p1.x, &x3);
+        let lambda_dx = self.mul_fp2(lambda, &dx);
+        let y3 = self.sub_fp2(&lambda_dx, &p1.y);
+        G2Target { x: x3, y: y3 }
+    }
+
+    /// Computes `n * p` by double-and-add, starting from a fixed offset point so that honest
+    /// inputs never hit the exceptional cases of the incomplete formulas. The result must not be
+    /// the point at infinity.
+    pub fn g2_scalar_mul(&mut self, p: &G2Target, n: &NonNativeTarget<Bn254Scalar>) -> G2Target {
+        let offset_digits: Vec<u32> = G2_OFFSET_SCALAR
+            .iter()
+            .flat_map(|&d| [d as u32, (d >> 32) as u32])
+            .collect();
+        let offset_scalar =
+            Bn254Scalar::from_noncanonical_biguint(BigUint::from_slice(&offset_digits));
+        let offset = G2Point::GENERATOR.mul_scalar(offset_scalar);
+
+        let bits = self.split_nonnative_to_bits(n);
+        let mut acc = self.constant_g2_point(offset);
+        for &bit in bits.iter().rev() {
+            acc = self.g2_double(&acc);
+            let sum = self.g2_add(&acc, p);
+            acc = self.g2_select(bit, &sum, &acc);
+        }
+
+        // The offset was doubled once per bit.
+        let total_offset_scalar = Bn254Scalar::from_noncanonical_biguint(
+            (offset_scalar.to_canonical_biguint() << bits.len()) % Bn254Scalar::order(),
+        );
+        let neg_total_offset =
+            self.constant_g2_point(-G2Point::GENERATOR.mul_scalar(total_offset_scalar));
+        self.g2_add(&acc, &neg_total_offset)
+    }
+
+    /// Returns the nonzero coefficients of `g000 + (cx * q.x) w^2 + (cy * q.y) w^3`, mirroring
+    /// `bn_sparse_embed`.
+    fn bn254_sparse_line(
+        &mut self,
+        g000: FpTarget,
+        cx: &FpTarget,
+        cy: &FpTarget,
+        q: &G2Target,
+    ) -> SparseLine {
+        // An element `re + im * i` of `Fp2`, times `w^k`, is `(re - 9 im) w^k + im w^(k + 6)`.
+        let w2 = self.inner_product_nonnative(&[(1, &q.x.re, cx), (-9, &q.x.im, cx)]);
+        let w8 = self.mul_nonnative(&q.x.im, cx);
+        let w3 = self.inner_product_nonnative(&[(1, &q.y.re, cy), (-9, &q.y.im, cy)]);
+        let w9 = self.mul_nonnative(&q.y.im, cy);
+        [(0, g000), (2, w2), (3, w3), (8, w8), (9, w9)]
+    }
+
+    /// The line tangent to the curve at `p`, evaluated at `q`; see `bn_tangent`.
+    fn bn254_tangent(&mut self, p: &AffinePointTarget<Bn254>, q: &G2Target) -> SparseLine {
+        let nine = self.constant_nonnative(Bn254Base::from_canonical_u64(9));
+        let y_squared = self.mul_nonnative(&p.y, &p.y);
+        let g000 = self.sub_nonnative(&y_squared, &nine);
+        let cx = self.inner_product_nonnative(&[(-3, &p.x, &p.x)]);
+        let cy = self.linear_combination_nonnative(&[(2, &p.y)]);
+        self.bn254_sparse_line(g000, &cx, &cy, q)
+    }
+
+    /// The line through `p1` and `p2`, evaluated at `q`; see `bn_chord`.
+    fn bn254_chord(
+        &mut self,
+        p1: &AffinePointTarget<Bn254>,
+        p2: &AffinePointTarget<Bn254>,
+        q: &G2Target,
+    ) -> SparseLine {
+        let g000 = self.inner_product_nonnative(&[(1, &p1.y, &p2.x), (-1, &p2.y, &p1.x)]);
+        let cx = self.sub_nonnative(&p2.y, &p1.y);
+        let cy = self.sub_nonnative(&p1.x, &p2.x);
+        self.bn254_sparse_line(g000, &cx, &cy, q)
+    }
+
+    fn mul_fp12_by_line(&mut self, acc: &Fp12Target, line: &SparseLine) -> Fp12Target {
+        let line: Vec<_> = line.iter().map(|(k, c)| (*k, c)).collect();
+        self.mul_fp12_sparse(acc, &line)
+    }
+
+    /// A doubling step of the Miller loop: returns `acc^2` times the line tangent at `r`, evaluated
+    /// at `q`, and `2 r`.
+    fn bn254_miller_double_step(
+        &mut self,
+        acc: &Fp12Target,
+        r: &AffinePointTarget<Bn254>,
+        q: &G2Target,
+    ) -> (Fp12Target, AffinePointTarget<Bn254>) {
+        let tangent = self.bn254_tangent(r, q);
+        let acc_squared = self.square_fp12(acc);
+        let acc = self.mul_fp12_by_line(&acc_squared, &tangent);
+        (acc, self.curve_double(r))
+    }
+
+    /// An addition step of the Miller loop: returns `acc` times the line through `p` and `r`,
+    /// evaluated at `q`.
+    fn bn254_miller_add_step(
+        &mut self,
+        acc: &Fp12Target,
+        r: &AffinePointTarget<Bn254>,
+        p: &AffinePointTarget<Bn254>,
+        q: &G2Target,
+    ) -> Fp12Target {
+        let chord = self.bn254_chord(p, r, q);
+        self.mul_fp12_by_line(acc, &chord)
+    }
+
+    /// The Miller loop of the pairing, mirroring `bn_miller_loop`. Both points must be valid and
+    /// nonzero.
+    pub fn bn254_miller_loop(&mut self, p: &AffinePointTarget<Bn254>, q: &G2Target) -> Fp12Target {
+        let order = Bn254Scalar::order();
+        let mut r = p.clone();
+        let mut acc: Option<Fp12Target> = None;
+        for i in (0..order.bits() - 1).rev() {
+            let (new_acc, new_r) = match acc {
+                // The accumulator starts at one, so the first step is just the line itself.
+                None => {
+                    let tangent = self.bn254_tangent(&r, q);
+                    let line: Vec<_> = tangent.iter().map(|(k, c)| (*k, c)).collect();
+                    (self.fp12_from_sparse(&line), self.curve_double(&r))
+                }
+                Some(acc) => self.bn254_miller_double_step(&acc, &r, q),
+            };
+            r = new_r;
+            acc = Some(new_acc);
+
+            // As in `bn_miller_loop`, the vertical line of the last step is skipped.
+            if order.bit(i) && i != 0 {
+                acc = Some(self.bn254_miller_add_step(acc.as_ref().unwrap(), &r, p, q));
+                r = self.curve_add(&r, p);
+            }
+        }
+        acc.unwrap()
+    }
+
+    /// Raises the output of the Miller loop to the power `(p^12 - 1) / r`, mirroring
+    /// `bn_final_exponent`.
+    pub fn bn254_final_exponentiation(&mut self, f: &Fp12Target) -> Fp12Target {
+        // The easy part, `(p^6 - 1) (p^2 + 1)`.
+        let f_p6 = self.frobenius_fp12(f, 6);
+        let y = self.div_fp12(&f_p6, f);
+        let y_p2 = self.frobenius_fp12(&y, 2);
+        let y = self.mul_fp12(&y_p2, &y);
+
+        // The hard part, `p^3 + d2 p^2 + d1 p + d0`. The squarings are shared between the three
+        // exponentiations by the digits.
+        let [d0, d1, d2, d3] = bn_hard_part_digits();
+        assert!(d3.is_one());
+        let digits = [d0, d1, d2];
+        let num_bits = digits.iter().map(|d| d.bits()).max().unwrap();
+        let mut powers: [Option<Fp12Target>; 3] = [None, None, None];
+        let mut square = y.clone();
+        for i in 0..num_bits {
+            for (digit, power) in digits.iter().zip(powers.iter_mut()) {
+                if digit.bit(i) {
+                    *power = Some(match power.take() {
+                        None => square.clone(),
+                        Some(power) => self.mul_fp12(&power, &square),
+                    });
+                }
+            }
+            if i + 1 < num_bits {
+                square = self.square_fp12(&square);
+            }
+        }
+        let [y_d0, y_d1, y_d2] = powers.map(|p| p.unwrap());
+
+        let t3 = self.frobenius_fp12(&y, 3);
+        let t2 = self.frobenius_fp12(&y_d2, 2);
+        let t1 = self.frobenius_fp12(&y_d1, 1);
+        let t32 = self.mul_fp12(&t3, &t2);
+        let t10 = self.mul_fp12(&t1, &y_d0);
+        self.mul_fp12(&t32, &t10)
+    }
+
+    /// Computes the BN254 pairing of `p` and `q`, mirroring `bn_tate`. Both points must be valid
+    /// and nonzero.
+    pub fn bn254_pairing(&mut self, p: &AffinePointTarget<Bn254>, q: &G2Target) -> Fp12Target {
+        let f = self.bn254_miller_loop(p, q);
+        self.bn254_final_exponentiation(&f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::curve::bn254::Bn254;
+    use crate::curve::bn254_pairing::{
+        bn_chord, bn_miller_loop, bn_tangent, bn_tate, g1_rand, g2_rand, G2Point,
+    };
+    use crate::curve::curve_types::{AffinePoint, Curve, CurveScalar};
+    use crate::field::bn254_scalar::Bn254Scalar;
+    use crate::field::types::Sample;
+    use crate::gadgets::bn254_pairing::G2Target;
+    use crate::gadgets::curve::AffinePointTarget;
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::verifier::verify;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    fn g1_input(
+        builder: &mut CircuitBuilder<F, D>,
+        pw: &mut PartialWitness<F>,
+        p: AffinePoint<Bn254>,
+    ) -> AffinePointTarget<Bn254> {
+        let target = builder.add_virtual_affine_point_target();
+        pw.set_nonnative_target(&target.x, p.x);
+        pw.set_nonnative_target(&target.y, p.y);
+        target
+    }
+
+    fn g2_input(
+        builder: &mut CircuitBuilder<F, D>,
+        pw: &mut PartialWitness<F>,
+        p: G2Point,
+    ) -> G2Target {
+        let target = builder.add_virtual_g2_target();
+        for (t, v) in [
+            (&target.x.re, p.x.re),
+            (&target.x.im, p.x.im),
+            (&target.y.re, p.y.re),
+            (&target.y.im, p.y.im),
+        ] {
+            pw.set_nonnative_target(t, v);
+        }
+        target
+    }
+
+    #[test]
+    fn test_g1_ops() -> Result<()> {
+        let config = CircuitConfig::standard_ecc_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let p1 = g1_rand();
+        let p2 = g1_rand();
+        let p1_target = g1_input(&mut builder, &mut pw, p1);
+        let p2_target = g1_input(&mut builder, &mut pw, p2);
+        builder.curve_assert_valid(&p1_target);
+
+        let sum = builder.curve_add(&p1_target, &p2_target);
+        let expected = builder.constant_affine_point((p1.to_projective() + p2).to_affine());
+        builder.connect_affine_point(&sum, &expected);
+        let double = builder.curve_double(&p1_target);
+        let expected = builder.constant_affine_point(p1.double());
+        builder.connect_affine_point(&double, &expected);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    fn test_g2_ops() -> Result<()> {
+        let config = CircuitConfig::standard_ecc_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let p1 = g2_rand();
+        let p2 = g2_rand();
+        let p1_target = g2_input(&mut builder, &mut pw, p1);
+        let p2_target = g2_input(&mut builder, &mut pw, p2);
+        builder.g2_assert_valid(&p1_target);
+
+        let sum = builder.g2_add(&p1_target, &p2_target);
+        let expected = builder.constant_g2_point(p1 + p2);
+        builder.connect_g2(&sum, &expected);
+        let double = builder.g2_double(&p1_target);
+        let expected = builder.constant_g2_point(p1.double());
+        builder.connect_g2(&double, &expected);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    fn test_bn254_lines() -> Result<()> {
+        let config = CircuitConfig::standard_ecc_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let p1 = g1_rand();
+        let p2 = g1_rand();
+        let q = g2_rand();
+        let p1_target = g1_input(&mut builder, &mut pw, p1);
+        let p2_target = g1_input(&mut builder, &mut pw, p2);
+        let q_target = g2_input(&mut builder, &mut pw, q);
+
+        let tangent = builder.bn254_tangent(&p1_target, &q_target);
+        let chord = builder.bn254_chord(&p1_target, &p2_target, &q_target);
+        for (line, expected) in [(tangent, bn_tangent(p1, q)), (chord, bn_chord(p1, p2, q))] {
+            let line: Vec<_> = line.iter().map(|(k, c)| (*k, c)).collect();
+            let line = builder.fp12_from_sparse(&line);
+            let expected = builder.constant_fp12(expected);
+            builder.connect_fp12(&line, &expected);
+        }
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    fn test_bn254_miller_steps() -> Result<()> {
+        let config = CircuitConfig::standard_ecc_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let acc = bn_miller_loop(g1_rand(), g2_rand());
+        let p = g1_rand();
+        let r = g1_rand();
+        let q = g2_rand();
+        let acc_target = builder.add_virtual_fp12_target();
+        for (t, &v) in acc_target.coeffs.iter().zip(&acc.coeffs) {
+            pw.set_nonnative_target(t, v);
+        }
+        let p_target = g1_input(&mut builder, &mut pw, p);
+        let r_target = g1_input(&mut builder, &mut pw, r);
+        let q_target = g2_input(&mut builder, &mut pw, q);
+
+        let (acc_target, r_target) =
+            builder.bn254_miller_double_step(&acc_target, &r_target, &q_target);
+        let acc_target =
+            builder.bn254_miller_add_step(&acc_target, &r_target, &p_target, &q_target);
+
+        let acc = bn_tangent(r, q) * acc.square();
+        let r = r.double();
+        let expected_acc = bn_chord(p, r, q) * acc;
+        let expected_acc = builder.constant_fp12(expected_acc);
+        builder.connect_fp12(&acc_target, &expected_acc);
+        let expected_r = builder.constant_affine_point(r);
+        builder.connect_affine_point(&r_target, &expected_r);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    #[ignore]
+    fn test_g2_scalar_mul() -> Result<()> {
+        let config = CircuitConfig::wide_ecc_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let p = g2_rand();
+        let n = Bn254Scalar::rand();
+        let p_target = g2_input(&mut builder, &mut pw, p);
+        let n_target = builder.add_virtual_nonnative_target();
+        pw.set_nonnative_target(&n_target, n);
+
+        let product = builder.g2_scalar_mul(&p_target, &n_target);
+        let expected = builder.constant_g2_point(p.mul_scalar(n));
+        builder.connect_g2(&product, &expected);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    #[ignore]
+    fn test_bn254_pairing() -> Result<()> {
+        let config = CircuitConfig::wide_ecc_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let p = (CurveScalar(Bn254Scalar::rand()) * Bn254::GENERATOR_PROJECTIVE).to_affine();
+        let q = g2_rand();
+        let p_target = g1_input(&mut builder, &mut pw, p);
+        let q_target = g2_input(&mut builder, &mut pw, q);
+
+        let pairing = builder.bn254_pairing(&p_target, &q_target);
+        let expected = builder.constant_fp12(bn_tate(p, q));
+        builder.connect_fp12(&pairing, &expected);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+}
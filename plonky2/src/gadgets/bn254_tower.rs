@@ -0,0 +1,469 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::curve::bn254_pairing::{frobenius_matrix, Fp12, Fp2, FP12_DEGREE};
+use crate::field::bn254_base::Bn254Base;
+use crate::field::extension::Extendable;
+use crate::field::types::{Field, PrimeField};
+use crate::gadgets::biguint::BigUintTarget;
+use crate::gadgets::nonnative::NonNativeTarget;
+use crate::hash::hash_types::RichField;
+use crate::iop::generator::{GeneratedValues, SimpleGenerator};
+use crate::iop::target::{BoolTarget, Target};
+use crate::iop::witness::{PartitionWitness, Witness, WitnessWrite};
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::circuit_data::CommonCircuitData;
+use crate::util::serialization::{Buffer, IoResult, Read, Write};
+
+/// An element of the BN254 base field.
+pub type FpTarget = NonNativeTarget<Bn254Base>;
+
+/// An element `re + im * i` of `Fp2`; see `curve::bn254_pairing::Fp2`.
+#[derive(Clone, Debug, Default)]
+pub struct Fp2Target {
+    pub re: FpTarget,
+    pub im: FpTarget,
+}
+
+/// An element of `Fp12`, in the flat basis `1, w, ..., w^11` described in
+/// `curve::bn254_pairing::Fp12`.
+#[derive(Clone, Debug, Default)]
+pub struct Fp12Target {
+    pub coeffs: [FpTarget; FP12_DEGREE],
+}
+
+/// Returns the coefficients of `w^s`, for `s < 23`, in the basis `1, w, ..., w^11`, using
+/// `w^12 = 18 w^6 - 82`.
+fn reduced_power_of_w(s: usize) -> [i64; FP12_DEGREE] {
+    let mut coeffs = [0i64; 2 * FP12_DEGREE - 1];
+    coeffs[s] = 1;
+    for k in (FP12_DEGREE..2 * FP12_DEGREE - 1).rev() {
+        let c = coeffs[k];
+        coeffs[k - 6] += 18 * c;
+        coeffs[k - 12] -= 82 * c;
+    }
+    core::array::from_fn(|i| coeffs[i])
+}
+
+/// Returns `x` as a small signed integer, if it is one.
+fn as_small_signed(x: Bn254Base) -> Option<i64> {
+    let small = |v: &num::BigUint| v.to_u64_digits().first().copied().unwrap_or(0);
+    let value = x.to_canonical_biguint();
+    let negated = (-x).to_canonical_biguint();
+    if value.bits() < 32 {
+        Some(small(&value) as i64)
+    } else if negated.bits() < 32 {
+        Some(-(small(&negated) as i64))
+    } else {
+        None
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
+    pub fn constant_fp2(&mut self, x: Fp2) -> Fp2Target {
+        Fp2Target {
+            re: self.constant_nonnative(x.re),
+            im: self.constant_nonnative(x.im),
+        }
+    }
+
+    pub fn add_virtual_fp2_target(&mut self) -> Fp2Target {
+        Fp2Target {
+            re: self.add_virtual_nonnative_target(),
+            im: self.add_virtual_nonnative_target(),
+        }
+    }
+
+    pub fn connect_fp2(&mut self, a: &Fp2Target, b: &Fp2Target) {
+        self.connect_nonnative(&a.re, &b.re);
+        self.connect_nonnative(&a.im, &b.im);
+    }
+
+    /// Returns `b ? x : y`.
+    pub fn select_fp2(&mut self, b: BoolTarget, x: &Fp2Target, y: &Fp2Target) -> Fp2Target {
+        Fp2Target {
+            re: self.select_nonnative(b, &x.re, &y.re),
+            im: self.select_nonnative(b, &x.im, &y.im),
+        }
+    }
+
+    /// Computes `sum_i c_i * x_i` for small signed coefficients `c_i`.
+    pub fn linear_combination_fp2(&mut self, terms: &[(i64, &Fp2Target)]) -> Fp2Target {
+        let re: Vec<_> = terms.iter().map(|&(c, x)| (c, &x.re)).collect();
+        let im: Vec<_> = terms.iter().map(|&(c, x)| (c, &x.im)).collect();
+        Fp2Target {
+            re: self.linear_combination_nonnative(&re),
+            im: self.linear_combination_nonnative(&im),
+        }
+    }
+
+    pub fn add_fp2(&mut self, a: &Fp2Target, b: &Fp2Target) -> Fp2Target {
+        self.linear_combination_fp2(&[(1, a), (1, b)])
+    }
+
+    pub fn sub_fp2(&mut self, a: &Fp2Target, b: &Fp2Target) -> Fp2Target {
+        self.linear_combination_fp2(&[(1, a), (-1, b)])
+    }
+
+    pub fn neg_fp2(&mut self, a: &Fp2Target) -> Fp2Target {
+        self.linear_combination_fp2(&[(-1, a)])
+    }
+
+    /// Computes `a * c` for an element `c` of the base field.
+    pub fn scale_fp2(&mut self, a: &Fp2Target, c: &FpTarget) -> Fp2Target {
+        Fp2Target {
+            re: self.mul_nonnative(&a.re, c),
+            im: self.mul_nonnative(&a.im, c),
+        }
+    }
+
+    pub fn mul_fp2(&mut self, a: &Fp2Target, b: &Fp2Target) -> Fp2Target {
+        Fp2Target {
+            re: self.inner_product_nonnative(&[(1, &a.re, &b.re), (-1, &a.im, &b.im)]),
+            im: self.inner_product_nonnative(&[(1, &a.re, &b.im), (1, &a.im, &b.re)]),
+        }
+    }
+
+    pub fn square_fp2(&mut self, a: &Fp2Target) -> Fp2Target {
+        Fp2Target {
+            re: self.inner_product_nonnative(&[(1, &a.re, &a.re), (-1, &a.im, &a.im)]),
+            im: self.inner_product_nonnative(&[(2, &a.re, &a.im)]),
+        }
+    }
+
+    /// Returns the inverse of `a`, which must be nonzero.
+    pub fn inv_fp2(&mut self, a: &Fp2Target) -> Fp2Target {
+        let norm = self.inner_product_nonnative(&[(1, &a.re, &a.re), (1, &a.im, &a.im)]);
+        let norm_inv = self.inv_nonnative(&norm);
+        Fp2Target {
+            re: self.mul_nonnative(&a.re, &norm_inv),
+            im: self.inner_product_nonnative(&[(-1, &a.im, &norm_inv)]),
+        }
+    }
+
+    /// Returns `a / b`, where `b` must be nonzero.
+    pub fn div_fp2(&mut self, a: &Fp2Target, b: &Fp2Target) -> Fp2Target {
+        let b_inv = self.inv_fp2(b);
+        self.mul_fp2(a, &b_inv)
+    }
+
+    pub fn constant_fp12(&mut self, x: Fp12) -> Fp12Target {
+        Fp12Target {
+            coeffs: x.coeffs.map(|c| self.constant_nonnative(c)),
+        }
+    }
+
+    pub fn add_virtual_fp12_target(&mut self) -> Fp12Target {
+        Fp12Target {
+            coeffs: core::array::from_fn(|_| self.add_virtual_nonnative_target()),
+        }
+    }
+
+    pub fn connect_fp12(&mut self, a: &Fp12Target, b: &Fp12Target) {
+        for (a, b) in a.coeffs.iter().zip(&b.coeffs) {
+            self.connect_nonnative(a, b);
+        }
+    }
+
+    /// Returns the `Fp12` element whose only nonzero coefficients are given by `sparse`.
+    pub fn fp12_from_sparse(&mut self, sparse: &[(usize, &FpTarget)]) -> Fp12Target {
+        let mut result = self.constant_fp12(Fp12::ZERO);
+        for &(k, c) in sparse {
+            result.coeffs[k] = c.clone();
+        }
+        result
+    }
+
+    /// Reduces the products `sum_{i + j = s} a_i b_j`, given for each `s < 23`, to an `Fp12`
+    /// element.
+    fn reduce_fp12_products(&mut self, products: &[Option<BigUintTarget>]) -> Fp12Target {
+        let reductions: Vec<_> = (0..products.len()).map(reduced_power_of_w).collect();
+        Fp12Target {
+            coeffs: core::array::from_fn(|k| {
+                let terms: Vec<_> = products
+                    .iter()
+                    .zip(&reductions)
+                    .filter_map(|(p, r)| p.as_ref().map(|p| (r[k], p)))
+                    .collect();
+                self.reduce_signed_sum_nonnative(&terms)
+            }),
+        }
+    }
+
+    /// Computes `a * b`, where `b` is given by its nonzero coefficients. Line evaluations in the
+    /// Miller loop only have five of them, so this is much cheaper than a full multiplication.
+    pub fn mul_fp12_sparse(&mut self, a: &Fp12Target, b: &[(usize, &FpTarget)]) -> Fp12Target {
+        let products: Vec<_> = (0..2 * FP12_DEGREE - 1)
+            .map(|s| {
+                let terms: Vec<_> = b
+                    .iter()
+                    .filter(|&&(j, _)| j <= s && s - j < FP12_DEGREE)
+                    .map(|&(j, b_j)| (1, &a.coeffs[s - j].value, &b_j.value))
+                    .collect();
+                (!terms.is_empty()).then(|| self.inner_product_biguint(&terms))
+            })
+            .collect();
+        self.reduce_fp12_products(&products)
+    }
+
+    pub fn mul_fp12(&mut self, a: &Fp12Target, b: &Fp12Target) -> Fp12Target {
+        let b: Vec<_> = b.coeffs.iter().enumerate().collect();
+        self.mul_fp12_sparse(a, &b)
+    }
+
+    pub fn square_fp12(&mut self, a: &Fp12Target) -> Fp12Target {
+        let products: Vec<_> = (0..2 * FP12_DEGREE - 1)
+            .map(|s| {
+                let terms: Vec<_> = (s.saturating_sub(FP12_DEGREE - 1)..=s / 2)
+                    .map(|i| {
+                        let c = if 2 * i == s { 1 } else { 2 };
+                        (c, &a.coeffs[i].value, &a.coeffs[s - i].value)
+                    })
+                    .collect();
+                Some(self.inner_product_biguint(&terms))
+            })
+            .collect();
+        self.reduce_fp12_products(&products)
+    }
+
+    /// Computes `a^(p^n)`, which is linear over the base field.
+    pub fn frobenius_fp12(&mut self, a: &Fp12Target, n: usize) -> Fp12Target {
+        let matrix = frobenius_matrix(n);
+        Fp12Target {
+            coeffs: core::array::from_fn(|i| {
+                let mut small_terms = Vec::new();
+                let mut products = Vec::new();
+                for (j, image) in matrix.iter().enumerate() {
+                    let c = image.coeffs[i];
+                    if c.is_zero() {
+                        continue;
+                    }
+                    match as_small_signed(c) {
+                        Some(c) => small_terms.push((c, &a.coeffs[j].value)),
+                        None => {
+                            let c = self.constant_nonnative(c);
+                            products.push((c, &a.coeffs[j].value));
+                        }
+                    }
+                }
+
+                let product_terms: Vec<_> =
+                    products.iter().map(|(c, x)| (1, &c.value, *x)).collect();
+                let product =
+                    (!product_terms.is_empty()).then(|| self.inner_product_biguint(&product_terms));
+                let mut terms = small_terms;
+                if let Some(product) = &product {
+                    terms.push((1, product));
+                }
+                self.reduce_signed_sum_nonnative(&terms)
+            }),
+        }
+    }
+
+    /// Returns `a / b`, where `b` must be nonzero.
+    pub fn div_fp12(&mut self, a: &Fp12Target, b: &Fp12Target) -> Fp12Target {
+        let quotient = self.add_virtual_fp12_target();
+        let coefficients = |x: &Fp12Target| x.coeffs.iter().map(|c| c.value.clone()).collect();
+        self.add_simple_generator(Fp12DivisionGenerator {
+            numerator: coefficients(a),
+            denominator: coefficients(b),
+            quotient: coefficients(&quotient),
+        });
+
+        let product = self.mul_fp12(&quotient, b);
+        self.connect_fp12(&product, a);
+        quotient
+    }
+
+    /// Returns the inverse of `a`, which must be nonzero.
+    pub fn inv_fp12(&mut self, a: &Fp12Target) -> Fp12Target {
+        let one = self.constant_fp12(Fp12::ONE);
+        self.div_fp12(&one, a)
+    }
+}
+
+/// Computes the quotient of two `Fp12` elements, given by the values of their coefficients.
+#[derive(Debug, Default)]
+pub struct Fp12DivisionGenerator {
+    numerator: Vec<BigUintTarget>,
+    denominator: Vec<BigUintTarget>,
+    quotient: Vec<BigUintTarget>,
+}
+
+impl Fp12DivisionGenerator {
+    fn get_fp12<F: RichField>(witness: &PartitionWitness<F>, coeffs: &[BigUintTarget]) -> Fp12 {
+        Fp12 {
+            coeffs: core::array::from_fn(|i| {
+                Bn254Base::from_noncanonical_biguint(witness.get_biguint_target(&coeffs[i]))
+            }),
+        }
+    }
+}
+
+fn write_coefficients(dst: &mut Vec<u8>, coeffs: &[BigUintTarget]) -> IoResult<()> {
+    for c in coeffs {
+        dst.write_target_vec(&c.limbs)?;
+    }
+    Ok(())
+}
+
+fn read_coefficients(src: &mut Buffer) -> IoResult<Vec<BigUintTarget>> {
+    (0..FP12_DEGREE)
+        .map(|_| {
+            Ok(BigUintTarget {
+                limbs: src.read_target_vec()?,
+            })
+        })
+        .collect()
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D> for Fp12DivisionGenerator {
+    fn id(&self) -> String {
+        "Fp12DivisionGenerator".to_string()
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        self.numerator
+            .iter()
+            .chain(&self.denominator)
+            .flat_map(|c| c.limbs.iter().copied())
+            .collect()
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let numerator = Self::get_fp12(witness, &self.numerator);
+        let denominator = Self::get_fp12(witness, &self.denominator);
+        let quotient = numerator / denominator;
+        for (target, value) in self.quotient.iter().zip(quotient.coeffs) {
+            out_buffer.set_biguint_target(target, &value.to_canonical_biguint());
+        }
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        write_coefficients(dst, &self.numerator)?;
+        write_coefficients(dst, &self.denominator)?;
+        write_coefficients(dst, &self.quotient)
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let numerator = read_coefficients(src)?;
+        let denominator = read_coefficients(src)?;
+        let quotient = read_coefficients(src)?;
+        Ok(Self {
+            numerator,
+            denominator,
+            quotient,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::curve::bn254_pairing::{Fp12, Fp2};
+    use crate::field::types::Sample;
+    use crate::gadgets::bn254_tower::{Fp12Target, Fp2Target};
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::verifier::verify;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    fn fp2_input(
+        builder: &mut CircuitBuilder<F, D>,
+        pw: &mut PartialWitness<F>,
+        x: Fp2,
+    ) -> Fp2Target {
+        let target = builder.add_virtual_fp2_target();
+        pw.set_nonnative_target(&target.re, x.re);
+        pw.set_nonnative_target(&target.im, x.im);
+        target
+    }
+
+    fn fp12_input(
+        builder: &mut CircuitBuilder<F, D>,
+        pw: &mut PartialWitness<F>,
+        x: Fp12,
+    ) -> Fp12Target {
+        let target = builder.add_virtual_fp12_target();
+        for (t, c) in target.coeffs.iter().zip(x.coeffs) {
+            pw.set_nonnative_target(t, c);
+        }
+        target
+    }
+
+    #[test]
+    fn test_fp2_arithmetic() -> Result<()> {
+        let config = CircuitConfig::standard_ecc_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let x = Fp2::rand();
+        let y = Fp2::rand();
+        let x_target = fp2_input(&mut builder, &mut pw, x);
+        let y_target = fp2_input(&mut builder, &mut pw, y);
+
+        let cases = [
+            (builder.sub_fp2(&x_target, &y_target), x - y),
+            (builder.mul_fp2(&x_target, &y_target), x * y),
+            (builder.square_fp2(&x_target), x.square()),
+            (builder.div_fp2(&x_target, &y_target), x / y),
+        ];
+        for (target, expected) in cases {
+            let expected = builder.constant_fp2(expected);
+            builder.connect_fp2(&target, &expected);
+        }
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    fn test_fp12_arithmetic() -> Result<()> {
+        let config = CircuitConfig::standard_ecc_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let x = Fp12::rand();
+        let y = Fp12::rand();
+        let x_target = fp12_input(&mut builder, &mut pw, x);
+        let y_target = fp12_input(&mut builder, &mut pw, y);
+
+        // A sparse element with the shape of a line evaluation.
+        let sparse_indices = [0, 2, 3, 8, 9];
+        let mut sparse = Fp12::ZERO;
+        for &k in &sparse_indices {
+            sparse.coeffs[k] = y.coeffs[k];
+        }
+        let sparse_target: Vec<_> = sparse_indices
+            .iter()
+            .map(|&k| (k, &y_target.coeffs[k]))
+            .collect();
+
+        let cases = [
+            (builder.mul_fp12(&x_target, &y_target), x * y),
+            (builder.square_fp12(&x_target), x.square()),
+            (
+                builder.mul_fp12_sparse(&x_target, &sparse_target),
+                x * sparse,
+            ),
+            (builder.frobenius_fp12(&x_target, 1), x.frob(1)),
+            (builder.frobenius_fp12(&x_target, 6), x.frob(6)),
+            (builder.div_fp12(&x_target, &y_target), x / y),
+        ];
+        for (target, expected) in cases {
+            let expected = builder.constant_fp12(expected);
+            builder.connect_fp12(&target, &expected);
+        }
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+}
@@ -0,0 +1,216 @@
+use crate::curve::curve_types::{AffinePoint, Curve};
+use crate::field::extension::Extendable;
+use crate::gadgets::nonnative::NonNativeTarget;
+use crate::hash::hash_types::RichField;
+use crate::iop::target::BoolTarget;
+use crate::plonk::circuit_builder::CircuitBuilder;
+
+/// A point on the curve `C`, represented in affine coordinates. The point at infinity cannot be
+/// represented, so the gadgets below use incomplete formulas; they are unsatisfiable rather than
+/// unsound when an exceptional case is hit.
+#[derive(Clone, Debug)]
+pub struct AffinePointTarget<C: Curve> {
+    pub x: NonNativeTarget<C::BaseField>,
+    pub y: NonNativeTarget<C::BaseField>,
+}
+
+impl<C: Curve> AffinePointTarget<C> {
+    pub fn to_vec(&self) -> [NonNativeTarget<C::BaseField>; 2] {
+        [self.x.clone(), self.y.clone()]
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
+    pub fn constant_affine_point<C: Curve>(
+        &mut self,
+        point: AffinePoint<C>,
+    ) -> AffinePointTarget<C> {
+        debug_assert!(!point.zero);
+        AffinePointTarget {
+            x: self.constant_nonnative(point.x),
+            y: self.constant_nonnative(point.y),
+        }
+    }
+
+    pub fn connect_affine_point<C: Curve>(
+        &mut self,
+        lhs: &AffinePointTarget<C>,
+        rhs: &AffinePointTarget<C>,
+    ) {
+        self.connect_nonnative(&lhs.x, &rhs.x);
+        self.connect_nonnative(&lhs.y, &rhs.y);
+    }
+
+    /// Returns a new `AffinePointTarget` with canonical coordinates. The point is not checked to
+    /// lie on the curve; see `curve_assert_valid`.
+    pub fn add_virtual_affine_point_target<C: Curve>(&mut self) -> AffinePointTarget<C> {
+        let x = self.add_virtual_nonnative_target();
+        let y = self.add_virtual_nonnative_target();
+
+        AffinePointTarget { x, y }
+    }
+
+    /// Asserts that `p` lies on the curve.
+    pub fn curve_assert_valid<C: Curve>(&mut self, p: &AffinePointTarget<C>) {
+        let a = self.constant_nonnative(C::A);
+        let b = self.constant_nonnative(C::B);
+
+        let y_squared = self.mul_nonnative(&p.y, &p.y);
+        let x_squared = self.mul_nonnative(&p.x, &p.x);
+        let x_cubed = self.mul_nonnative(&x_squared, &p.x);
+        let a_x = self.mul_nonnative(&a, &p.x);
+        let rhs = self.add_many_nonnative(&[x_cubed, a_x, b]);
+
+        self.connect_nonnative(&y_squared, &rhs);
+    }
+
+    pub fn curve_neg<C: Curve>(&mut self, p: &AffinePointTarget<C>) -> AffinePointTarget<C> {
+        let neg_y = self.neg_nonnative(&p.y);
+        AffinePointTarget {
+            x: p.x.clone(),
+            y: neg_y,
+        }
+    }
+
+    /// Returns `b ? p1 : p2`.
+    pub fn curve_select<C: Curve>(
+        &mut self,
+        b: BoolTarget,
+        p1: &AffinePointTarget<C>,
+        p2: &AffinePointTarget<C>,
+    ) -> AffinePointTarget<C> {
+        AffinePointTarget {
+            x: self.select_nonnative(b, &p1.x, &p2.x),
+            y: self.select_nonnative(b, &p1.y, &p2.y),
+        }
+    }
+
+    /// Doubles `p`, which must not have order two.
+    pub fn curve_double<C: Curve>(&mut self, p: &AffinePointTarget<C>) -> AffinePointTarget<C> {
+        let AffinePointTarget { x, y } = p;
+        let a = self.constant_nonnative(C::A);
+
+        let x_squared = self.mul_nonnative(x, x);
+        let numerator =
+            self.add_many_nonnative(&[x_squared.clone(), x_squared.clone(), x_squared, a]);
+        let denominator = self.add_nonnative(y, y);
+        let lambda = self.div_nonnative(&numerator, &denominator);
+
+        self.curve_finish_add(&lambda, x, y, x)
+    }
+
+    /// Adds two points with distinct x-coordinates.
+    pub fn curve_add<C: Curve>(
+        &mut self,
+        p1: &AffinePointTarget<C>,
+        p2: &AffinePointTarget<C>,
+    ) -> AffinePointTarget<C> {
+        let numerator = self.sub_nonnative(&p2.y, &p1.y);
+        let denominator = self.sub_nonnative(&p2.x, &p1.x);
+        let lambda = self.div_nonnative(&numerator, &denominator);
+
+        self.curve_finish_add(&lambda, &p1.x, &p1.y, &p2.x)
+    }
+
+    /// Returns `b ? p1 + p2 : p1`.
+    pub fn curve_conditional_add<C: Curve>(
+        &mut self,
+        p1: &AffinePointTarget<C>,
+        p2: &AffinePointTarget<C>,
+        b: BoolTarget,
+    ) -> AffinePointTarget<C> {
+        let sum = self.curve_add(p1, p2);
+        self.curve_select(b, &sum, p1)
+    }
+
+    /// Given the slope `lambda` of the line through `(x1, y1)` and a point with x-coordinate `x2`,
+    /// returns the third intersection of the line with the curve, negated.
+    fn curve_finish_add<C: Curve>(
+        &mut self,
+        lambda: &NonNativeTarget<C::BaseField>,
+        x1: &NonNativeTarget<C::BaseField>,
+        y1: &NonNativeTarget<C::BaseField>,
+        x2: &NonNativeTarget<C::BaseField>,
+    ) -> AffinePointTarget<C> {
+        let lambda_squared = self.mul_nonnative(lambda, lambda);
+        let x1_plus_x2 = self.add_nonnative(x1, x2);
+        let x3 = self.sub_nonnative(&lambda_squared, &x1_plus_x2);
+        let x1_minus_x3 = self.sub_nonnative(x1, &x3);
+        let lambda_times = self.mul_nonnative(lambda, &x1_minus_x3);
+        let y3 = self.sub_nonnative(&lambda_times, y1);
+
+        AffinePointTarget { x: x3, y: y3 }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::curve::curve_types::{AffinePoint, Curve, CurveScalar};
+    use crate::curve::secp256k1::Secp256K1;
+    use crate::field::secp256k1_base::Secp256K1Base;
+    use crate::field::secp256k1_scalar::Secp256K1Scalar;
+    use crate::field::types::{Field, Sample};
+    use crate::iop::witness::PartialWitness;
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::verifier::verify;
+
+    #[test]
+    fn test_curve_ops() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let config = CircuitConfig::standard_ecc_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let g = Secp256K1::GENERATOR_PROJECTIVE;
+        let p = (CurveScalar(Secp256K1Scalar::rand()) * g).to_affine();
+        let q = (CurveScalar(Secp256K1Scalar::rand()) * g).to_affine();
+
+        let p_target = builder.constant_affine_point(p);
+        let q_target = builder.constant_affine_point(q);
+        builder.curve_assert_valid(&p_target);
+
+        let sum = builder.curve_add(&p_target, &q_target);
+        let double = builder.curve_double(&p_target);
+        let neg = builder.curve_neg(&p_target);
+
+        let expected_sum = builder.constant_affine_point((p.to_projective() + q).to_affine());
+        let expected_double = builder.constant_affine_point(p.double());
+        let expected_neg = builder.constant_affine_point(-p);
+        builder.connect_affine_point(&sum, &expected_sum);
+        builder.connect_affine_point(&double, &expected_double);
+        builder.connect_affine_point(&neg, &expected_neg);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(PartialWitness::new())?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_curve_point_is_not_valid() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let config = CircuitConfig::standard_ecc_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let g = Secp256K1::GENERATOR_AFFINE;
+        let not_a_point = AffinePoint::<Secp256K1> {
+            x: g.x,
+            y: g.y + Secp256K1Base::ONE,
+            zero: false,
+        };
+        let target = builder.constant_affine_point(not_a_point);
+        builder.curve_assert_valid(&target);
+
+        let data = builder.build::<C>();
+        data.prove(PartialWitness::new()).unwrap();
+    }
+}
@@ -0,0 +1,193 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use num::BigUint;
+
+use crate::curve::curve_types::{AffinePoint, Curve, CurveScalar};
+use crate::field::extension::Extendable;
+use crate::field::types::Field;
+use crate::gadgets::biguint::BigUintTarget;
+use crate::gadgets::curve::AffinePointTarget;
+use crate::gadgets::nonnative::NonNativeTarget;
+use crate::hash::hash_types::RichField;
+use crate::iop::target::Target;
+use crate::plonk::circuit_builder::CircuitBuilder;
+
+/// The number of scalar bits consumed by each window.
+pub const WINDOW_SIZE: usize = 4;
+
+/// A fixed "nothing up my sleeve" scalar (the leading hexadecimal digits of pi), whose multiple of
+/// the generator is used to offset window tables so that the incomplete addition formulas are not
+/// hit by honest inputs.
+const OFFSET_SCALAR: [u64; 4] = [
+    0x243F6A8885A308D3,
+    0x13198A2E03707344,
+    0xA4093822299F31D0,
+    0x082EFA98EC4E6C89,
+];
+
+fn scalar_from_biguint<C: Curve>(x: BigUint) -> C::ScalarField {
+    C::ScalarField::from_noncanonical_biguint(x % C::ScalarField::order())
+}
+
+/// The scalar of the point added to every entry of the table of the `index`-th term of an MSM.
+/// Each term gets a distinct offset, so that entries of different tables never coincide.
+fn window_offset_scalar(index: usize) -> BigUint {
+    let digits: Vec<u32> = OFFSET_SCALAR
+        .iter()
+        .flat_map(|&d| [d as u32, (d >> 32) as u32])
+        .collect();
+    BigUint::from_slice(&digits) * (index + 1)
+}
+
+fn window_offset<C: Curve>(index: usize) -> AffinePoint<C> {
+    let scalar = scalar_from_biguint::<C>(window_offset_scalar(index));
+    (CurveScalar(scalar) * C::GENERATOR_PROJECTIVE).to_affine()
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
+    /// Returns the table `[G', G' + p, G' + 2p, ..., G' + 15p]`, where `G'` is a fixed offset.
+    pub fn precompute_window<C: Curve>(
+        &mut self,
+        p: &AffinePointTarget<C>,
+    ) -> Vec<AffinePointTarget<C>> {
+        self.precompute_window_with_offset(p, 0)
+    }
+
+    fn precompute_window_with_offset<C: Curve>(
+        &mut self,
+        p: &AffinePointTarget<C>,
+        offset_index: usize,
+    ) -> Vec<AffinePointTarget<C>> {
+        let offset = self.constant_affine_point(window_offset::<C>(offset_index));
+        let mut window = vec![offset];
+        for i in 1..1 << WINDOW_SIZE {
+            let next = self.curve_add(&window[i - 1], p);
+            window.push(next);
+        }
+        window
+    }
+
+    /// Returns `v[access_index]`. The length of `v` must be a power of two.
+    pub fn random_access_curve_points<C: Curve>(
+        &mut self,
+        access_index: Target,
+        v: &[AffinePointTarget<C>],
+    ) -> AffinePointTarget<C> {
+        let num_limbs = v[0].x.value.num_limbs();
+        let mut select_coordinate = |coordinates: Vec<&NonNativeTarget<C::BaseField>>| {
+            let limbs = (0..num_limbs)
+                .map(|i| {
+                    let limbs = coordinates.iter().map(|c| c.value.limbs[i]).collect();
+                    self.random_access(access_index, limbs)
+                })
+                .collect();
+            self.nonnative_from_canonical(BigUintTarget { limbs })
+        };
+
+        let x = select_coordinate(v.iter().map(|p| &p.x).collect());
+        let y = select_coordinate(v.iter().map(|p| &p.y).collect());
+        AffinePointTarget { x, y }
+    }
+
+    /// Computes `sum_i n_i * p_i` with 4-bit windows, sharing the doublings between all terms.
+    pub fn curve_msm_windowed<C: Curve>(
+        &mut self,
+        terms: &[(AffinePointTarget<C>, NonNativeTarget<C::ScalarField>)],
+    ) -> AffinePointTarget<C> {
+        assert!(
+            !terms.is_empty(),
+            "curve_msm_windowed needs at least one term"
+        );
+        let tables: Vec<_> = terms
+            .iter()
+            .enumerate()
+            .map(|(i, (p, _))| self.precompute_window_with_offset(p, i))
+            .collect();
+        let windows: Vec<_> = terms
+            .iter()
+            .map(|(_, n)| self.split_nonnative_to_4_bit_limbs(n))
+            .collect();
+        let num_windows = windows[0].len();
+
+        let mut result: Option<AffinePointTarget<C>> = None;
+        for w in (0..num_windows).rev() {
+            if let Some(mut acc) = result.take() {
+                for _ in 0..WINDOW_SIZE {
+                    acc = self.curve_double(&acc);
+                }
+                result = Some(acc);
+            }
+            for (table, windows) in tables.iter().zip(&windows) {
+                let entry = self.random_access_curve_points(windows[w], table);
+                result = Some(match result {
+                    None => entry,
+                    Some(acc) => self.curve_add(&acc, &entry),
+                });
+            }
+        }
+
+        // Each window of each term added one offset, scaled by the doublings that followed it.
+        let window_multiplier = (BigUint::from(1u32) << (WINDOW_SIZE * num_windows)) - 1u32;
+        let window_multiplier = window_multiplier / ((1u32 << WINDOW_SIZE) - 1);
+        let offsets_sum: BigUint = (0..terms.len()).map(window_offset_scalar).sum();
+        let total_offset_scalar = scalar_from_biguint::<C>(window_multiplier * offsets_sum);
+        let total_offset = CurveScalar(total_offset_scalar) * C::GENERATOR_PROJECTIVE;
+        let neg_total_offset = self.constant_affine_point(-total_offset.to_affine());
+
+        self.curve_add(&result.unwrap(), &neg_total_offset)
+    }
+
+    /// Computes `n * p` with 4-bit windows.
+    pub fn curve_scalar_mul_windowed<C: Curve>(
+        &mut self,
+        p: &AffinePointTarget<C>,
+        n: &NonNativeTarget<C::ScalarField>,
+    ) -> AffinePointTarget<C> {
+        self.curve_msm_windowed(&[(p.clone(), n.clone())])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::curve::curve_types::{Curve, CurveScalar};
+    use crate::curve::secp256k1::Secp256K1;
+    use crate::field::secp256k1_scalar::Secp256K1Scalar;
+    use crate::field::types::Sample;
+    use crate::iop::witness::PartialWitness;
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::verifier::verify;
+
+    #[test]
+    #[ignore]
+    fn test_curve_msm_windowed() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let config = CircuitConfig::standard_ecc_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let g = Secp256K1::GENERATOR_PROJECTIVE;
+        let p = (CurveScalar(Secp256K1Scalar::rand()) * g).to_affine();
+        let n = Secp256K1Scalar::rand();
+        let m = Secp256K1Scalar::rand();
+        let expected = (CurveScalar(n) * g + CurveScalar(m) * p.to_projective()).to_affine();
+
+        let g_target = builder.constant_affine_point(Secp256K1::GENERATOR_AFFINE);
+        let p_target = builder.constant_affine_point(p);
+        let n_target = builder.constant_nonnative(n);
+        let m_target = builder.constant_nonnative(m);
+        let result = builder.curve_msm_windowed(&[(g_target, n_target), (p_target, m_target)]);
+        let expected_target = builder.constant_affine_point(expected);
+        builder.connect_affine_point(&result, &expected_target);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(PartialWitness::new())?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+}
@@ -0,0 +1,187 @@
+use crate::curve::curve_types::Curve;
+use crate::curve::secp256k1::Secp256K1;
+use crate::field::extension::Extendable;
+use crate::field::secp256k1_scalar::Secp256K1Scalar;
+use crate::gadgets::curve::AffinePointTarget;
+use crate::gadgets::nonnative::NonNativeTarget;
+use crate::hash::hash_types::RichField;
+use crate::plonk::circuit_builder::CircuitBuilder;
+
+#[derive(Clone, Debug)]
+pub struct ECDSASecretKeyTarget<C: Curve>(pub NonNativeTarget<C::ScalarField>);
+
+#[derive(Clone, Debug)]
+pub struct ECDSAPublicKeyTarget<C: Curve>(pub AffinePointTarget<C>);
+
+#[derive(Clone, Debug)]
+pub struct ECDSASignatureTarget<C: Curve> {
+    pub r: NonNativeTarget<C::ScalarField>,
+    pub s: NonNativeTarget<C::ScalarField>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
+    pub fn add_virtual_ecdsa_public_key_target<C: Curve>(&mut self) -> ECDSAPublicKeyTarget<C> {
+        ECDSAPublicKeyTarget(self.add_virtual_affine_point_target())
+    }
+
+    pub fn add_virtual_ecdsa_signature_target<C: Curve>(&mut self) -> ECDSASignatureTarget<C> {
+        ECDSASignatureTarget {
+            r: self.add_virtual_nonnative_target(),
+            s: self.add_virtual_nonnative_target(),
+        }
+    }
+
+    /// Asserts that `sig` is a valid secp256k1 ECDSA signature of the (already hashed) message
+    /// `msg` under the public key `pk`.
+    ///
+    /// To batch-verify signatures, call this once per signature; the lookup tables used for range
+    /// checks are shared between all calls.
+    pub fn verify_ecdsa_message(
+        &mut self,
+        msg: NonNativeTarget<Secp256K1Scalar>,
+        sig: ECDSASignatureTarget<Secp256K1>,
+        pk: ECDSAPublicKeyTarget<Secp256K1>,
+    ) {
+        let ECDSASignatureTarget { r, s } = sig;
+
+        self.curve_assert_valid(&pk.0);
+
+        // `s` is checked to be nonzero by the inversion.
+        let c = self.inv_nonnative(&s);
+        let u1 = self.mul_nonnative(&msg, &c);
+        let u2 = self.mul_nonnative(&r, &c);
+
+        let g = self.constant_affine_point(Secp256K1::GENERATOR_AFFINE);
+        let point = self.curve_msm_windowed(&[(g, u1), (pk.0, u2)]);
+
+        let x = self.nonnative_to_canonical_biguint(&point.x);
+        let x = self.biguint_to_nonnative::<Secp256K1Scalar>(&x);
+        self.connect_nonnative(&r, &x);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::curve::ecdsa::{sign_message, ECDSAPublicKey, ECDSASecretKey, ECDSASignature};
+    use crate::curve::secp256k1::Secp256K1;
+    use crate::field::secp256k1_scalar::Secp256K1Scalar;
+    use crate::field::types::{Field, Sample};
+    use crate::gadgets::ecdsa::{ECDSAPublicKeyTarget, ECDSASignatureTarget};
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::{CircuitConfig, CircuitData};
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::proof::ProofWithPublicInputs;
+    use crate::plonk::verifier::verify;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+    type Curve = Secp256K1;
+
+    /// Adds the verification of a signature of a random message under a random key, whose `s` is
+    /// off by one if `tamper` is set.
+    fn add_ecdsa_signature(
+        builder: &mut CircuitBuilder<F, D>,
+        pw: &mut PartialWitness<F>,
+        tamper: bool,
+    ) {
+        let msg = Secp256K1Scalar::rand();
+        let msg_target = builder.add_virtual_nonnative_target();
+        pw.set_nonnative_target(&msg_target, msg);
+
+        let sk = ECDSASecretKey::<Curve>(Secp256K1Scalar::rand());
+        let ECDSAPublicKey(pk) = sk.to_public();
+        let pk_target: ECDSAPublicKeyTarget<Curve> = builder.add_virtual_ecdsa_public_key_target();
+        pw.set_nonnative_target(&pk_target.0.x, pk.x);
+        pw.set_nonnative_target(&pk_target.0.y, pk.y);
+
+        let ECDSASignature { r, s } = sign_message(msg, sk);
+        let s = if tamper { s + Secp256K1Scalar::ONE } else { s };
+        let sig_target: ECDSASignatureTarget<Curve> = builder.add_virtual_ecdsa_signature_target();
+        pw.set_nonnative_target(&sig_target.r, r);
+        pw.set_nonnative_target(&sig_target.s, s);
+
+        builder.verify_ecdsa_message(msg_target, sig_target, pk_target);
+    }
+
+    /// Builds a circuit verifying a signature for each entry of `tampered`, and only generates its
+    /// witness. This is much cheaper than proving, and catches invalid signatures as the recovered
+    /// `r` disagrees with the given one.
+    fn generate_ecdsa_witness(config: CircuitConfig, tampered: &[bool]) {
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let mut pw = PartialWitness::new();
+        for &tamper in tampered {
+            add_ecdsa_signature(&mut builder, &mut pw, tamper);
+        }
+
+        let data = builder.mock_build::<C>();
+        data.generate_witness(pw).unwrap();
+    }
+
+    fn prove_ecdsa_circuit(
+        config: CircuitConfig,
+        num_signatures: usize,
+    ) -> Result<(CircuitData<F, C, D>, ProofWithPublicInputs<F, C, D>)> {
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let mut pw = PartialWitness::new();
+        for _ in 0..num_signatures {
+            add_ecdsa_signature(&mut builder, &mut pw, false);
+        }
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        data.verify(proof.clone())?;
+        Ok((data, proof))
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_ecdsa_witness_invalid_signature() {
+        generate_ecdsa_witness(CircuitConfig::standard_ecc_config(), &[true]);
+    }
+
+    #[test]
+    #[ignore]
+    fn test_ecdsa_witness_batch() {
+        generate_ecdsa_witness(CircuitConfig::standard_ecc_config(), &[false, false]);
+    }
+
+    #[test]
+    #[ignore]
+    fn test_ecdsa_circuit_narrow() -> Result<()> {
+        prove_ecdsa_circuit(CircuitConfig::standard_ecc_config(), 1).map(|_| ())
+    }
+
+    #[test]
+    #[ignore]
+    fn test_ecdsa_circuit_wide() -> Result<()> {
+        prove_ecdsa_circuit(CircuitConfig::wide_ecc_config(), 1).map(|_| ())
+    }
+
+    #[test]
+    #[ignore]
+    fn test_ecdsa_circuit_batch() -> Result<()> {
+        prove_ecdsa_circuit(CircuitConfig::standard_ecc_config(), 2).map(|_| ())
+    }
+
+    #[test]
+    #[ignore]
+    fn test_ecdsa_circuit_recursive() -> Result<()> {
+        let (inner_data, inner_proof) =
+            prove_ecdsa_circuit(CircuitConfig::standard_ecc_config(), 1)?;
+
+        let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+        let mut pw = PartialWitness::new();
+        let proof_target = builder.add_virtual_proof_with_pis(&inner_data.common);
+        pw.set_proof_with_pis_target(&proof_target, &inner_proof);
+        let inner_verifier_data = builder.constant_verifier_data(&inner_data.verifier_only);
+        builder.verify_proof::<C>(&proof_target, &inner_verifier_data, &inner_data.common);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+}
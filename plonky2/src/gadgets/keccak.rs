@@ -0,0 +1,452 @@
+//! Keccak-f[1600] and Keccak-256, as used by Ethereum, over bit targets.
+//!
+//! The permutation state is represented as 25 lanes of 64 bits each, with lane `(x, y)` at index
+//! `x + 5 y` and bits in little-endian order, which is also the order in which message bytes are
+//! absorbed. The `theta` and `chi` steps use `XorGate` and `KeccakChiGate` respectively, while
+//! `rho` and `pi` are free since they only permute bits.
+
+use alloc::vec::Vec;
+
+use crate::field::extension::Extendable;
+use crate::gates::keccak_chi::KeccakChiGate;
+use crate::gates::xor::XorGate;
+use crate::hash::hash_types::RichField;
+use crate::iop::target::{BoolTarget, Target};
+use crate::plonk::circuit_builder::CircuitBuilder;
+
+/// The number of 64-bit lanes in the Keccak-f[1600] state.
+pub const KECCAK_LANES: usize = 25;
+
+/// The number of bits in a Keccak-f[1600] lane.
+pub const KECCAK_LANE_BITS: usize = 64;
+
+/// The number of bytes absorbed per permutation by Keccak-256.
+pub const KECCAK256_RATE_BYTES: usize = 136;
+
+/// The number of bytes in a Keccak-256 digest.
+pub const KECCAK256_DIGEST_BYTES: usize = 32;
+
+const KECCAK_ROUNDS: usize = 24;
+
+const KECCAK_ROUND_CONSTANTS: [u64; KECCAK_ROUNDS] = [
+    0x0000000000000001,
+    0x0000000000008082,
+    0x800000000000808A,
+    0x8000000080008000,
+    0x000000000000808B,
+    0x0000000080000001,
+    0x8000000080008081,
+    0x8000000000008009,
+    0x000000000000008A,
+    0x0000000000000088,
+    0x0000000080008009,
+    0x000000008000000A,
+    0x000000008000808B,
+    0x800000000000008B,
+    0x8000000000008089,
+    0x8000000000008003,
+    0x8000000000008002,
+    0x8000000000000080,
+    0x000000000000800A,
+    0x800000008000000A,
+    0x8000000080008081,
+    0x8000000000008080,
+    0x0000000080000001,
+    0x8000000080008008,
+];
+
+/// The `rho` rotation offsets, indexed by `x + 5 y`.
+#[rustfmt::skip]
+const KECCAK_RHO_OFFSETS: [usize; KECCAK_LANES] = [
+    0, 1, 62, 28, 27,
+    36, 44, 6, 55, 20,
+    3, 10, 43, 25, 39,
+    41, 45, 15, 21, 8,
+    18, 2, 61, 56, 14,
+];
+
+/// A Keccak-f[1600] state, as 25 lanes of little-endian bits.
+pub type KeccakStateTarget = [[BoolTarget; KECCAK_LANE_BITS]; KECCAK_LANES];
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
+    /// Returns the XOR of `bits`, which are assumed to be boolean.
+    ///
+    /// An `XorGate` with `n` inputs has degree `n / 2 + 1`, so more inputs than the config allows
+    /// in a single gate are XORed in several chunks.
+    pub fn xor_many(&mut self, bits: &[BoolTarget]) -> BoolTarget {
+        let max_inputs = self.max_xor_inputs();
+        if bits.len() > max_inputs {
+            let (first, rest) = bits.split_at(max_inputs);
+            let mut acc = self.xor_many(first);
+            for chunk in rest.chunks(max_inputs - 1) {
+                let mut inputs = Vec::with_capacity(chunk.len() + 1);
+                inputs.push(acc);
+                inputs.extend_from_slice(chunk);
+                acc = self.xor_many(&inputs);
+            }
+            return acc;
+        }
+
+        match bits.len() {
+            0 => self._false(),
+            1 => bits[0],
+            num_inputs => {
+                let gate = XorGate::new_from_config(&self.config, num_inputs);
+                let (row, i) = self.find_slot(gate.clone(), &[], &[]);
+                for (j, &bit) in bits.iter().enumerate() {
+                    self.connect(bit.target, Target::wire(row, gate.wire_ith_input(i, j)));
+                }
+
+                BoolTarget::new_unsafe(Target::wire(row, gate.wire_ith_output(i)))
+            }
+        }
+    }
+
+    /// The largest number of bits XORed by a single `XorGate` in this circuit. The gate's degree
+    /// may not exceed `max_quotient_degree_factor`, and each operation takes `num_inputs + 1`
+    /// routed wires.
+    fn max_xor_inputs(&self) -> usize {
+        let max_by_degree = 2 * self.config.max_quotient_degree_factor - 1;
+        let max_by_wires = self.config.num_routed_wires - 1;
+        max_by_degree.min(max_by_wires)
+    }
+
+    /// Returns `a ^ (!b & c)`, for bits `a`, `b` and `c`.
+    fn keccak_chi(&mut self, a: BoolTarget, b: BoolTarget, c: BoolTarget) -> BoolTarget {
+        let gate = KeccakChiGate::new_from_config(&self.config);
+        let (row, i) = self.find_slot(gate, &[], &[]);
+        self.connect(a.target, Target::wire(row, KeccakChiGate::wire_ith_a(i)));
+        self.connect(b.target, Target::wire(row, KeccakChiGate::wire_ith_b(i)));
+        self.connect(c.target, Target::wire(row, KeccakChiGate::wire_ith_c(i)));
+
+        BoolTarget::new_unsafe(Target::wire(row, KeccakChiGate::wire_ith_output(i)))
+    }
+
+    /// Applies the Keccak-f[1600] permutation to `state`, whose bits are assumed to be boolean.
+    pub fn keccak_f(&mut self, mut state: KeccakStateTarget) -> KeccakStateTarget {
+        for round_constant in KECCAK_ROUND_CONSTANTS {
+            // theta
+            let parities: [[BoolTarget; KECCAK_LANE_BITS]; 5] = core::array::from_fn(|x| {
+                core::array::from_fn(|z| {
+                    let column: [BoolTarget; 5] = core::array::from_fn(|y| state[x + 5 * y][z]);
+                    self.xor_many(&column)
+                })
+            });
+            for x in 0..5 {
+                for y in 0..5 {
+                    for z in 0..KECCAK_LANE_BITS {
+                        state[x + 5 * y][z] = self.xor_many(&[
+                            state[x + 5 * y][z],
+                            parities[(x + 4) % 5][z],
+                            parities[(x + 1) % 5][(z + KECCAK_LANE_BITS - 1) % KECCAK_LANE_BITS],
+                        ]);
+                    }
+                }
+            }
+
+            // rho and pi
+            let mut permuted = state;
+            for x in 0..5 {
+                for y in 0..5 {
+                    let offset = KECCAK_RHO_OFFSETS[x + 5 * y];
+                    permuted[y + 5 * ((2 * x + 3 * y) % 5)] = core::array::from_fn(|z| {
+                        state[x + 5 * y][(z + KECCAK_LANE_BITS - offset) % KECCAK_LANE_BITS]
+                    });
+                }
+            }
+
+            // chi
+            for x in 0..5 {
+                for y in 0..5 {
+                    for z in 0..KECCAK_LANE_BITS {
+                        state[x + 5 * y][z] = self.keccak_chi(
+                            permuted[x + 5 * y][z],
+                            permuted[(x + 1) % 5 + 5 * y][z],
+                            permuted[(x + 2) % 5 + 5 * y][z],
+                        );
+                    }
+                }
+            }
+
+            // iota
+            for z in 0..KECCAK_LANE_BITS {
+                if (round_constant >> z) & 1 == 1 {
+                    state[0][z] = self.not(state[0][z]);
+                }
+            }
+        }
+
+        state
+    }
+
+    /// Absorbs a padded block of bits into `state` and permutes it. If `state` is `None`, it is
+    /// taken to be the all-zero initial state.
+    fn keccak256_absorb(
+        &mut self,
+        state: Option<KeccakStateTarget>,
+        block: &[BoolTarget],
+    ) -> KeccakStateTarget {
+        debug_assert_eq!(block.len(), KECCAK256_RATE_BYTES * 8);
+        let _false = self._false();
+        let mut new_state = state.unwrap_or([[_false; KECCAK_LANE_BITS]; KECCAK_LANES]);
+        for (i, &bit) in block.iter().enumerate() {
+            let (lane, z) = (i / KECCAK_LANE_BITS, i % KECCAK_LANE_BITS);
+            new_state[lane][z] = match state {
+                Some(_) => self.xor_many(&[new_state[lane][z], bit]),
+                None => bit,
+            };
+        }
+
+        self.keccak_f(new_state)
+    }
+
+    /// Returns the Keccak-256 digest bytes held by the first lanes of `state`.
+    fn keccak256_digest(&mut self, state: &KeccakStateTarget) -> [Target; KECCAK256_DIGEST_BYTES] {
+        core::array::from_fn(|i| {
+            let lane = &state[8 * i / KECCAK_LANE_BITS];
+            let z = 8 * i % KECCAK_LANE_BITS;
+            self.le_sum(lane[z..z + 8].iter())
+        })
+    }
+
+    /// Computes the Keccak-256 digest of `message`, whose targets are bytes. The bytes are
+    /// range-checked. The digest is returned as 32 bytes.
+    pub fn keccak256(&mut self, message: &[Target]) -> [Target; KECCAK256_DIGEST_BYTES] {
+        let num_blocks = message.len() / KECCAK256_RATE_BYTES + 1;
+        let mut padded = message.to_vec();
+        padded.resize(num_blocks * KECCAK256_RATE_BYTES, self.zero());
+        let mut padding = [0u8; KECCAK256_RATE_BYTES];
+        padding[message.len() % KECCAK256_RATE_BYTES] = 0x01;
+        padding[KECCAK256_RATE_BYTES - 1] |= 0x80;
+        let first_padding_byte = message.len() / KECCAK256_RATE_BYTES * KECCAK256_RATE_BYTES;
+        for i in message.len()..padded.len() {
+            padded[i] = self.constant(F::from_canonical_u8(padding[i - first_padding_byte]));
+        }
+
+        let bits: Vec<BoolTarget> = padded
+            .into_iter()
+            .flat_map(|byte| self.split_le(byte, 8))
+            .collect();
+        let mut state = None;
+        for block in bits.chunks_exact(KECCAK256_RATE_BYTES * 8) {
+            state = Some(self.keccak256_absorb(state, block));
+        }
+
+        self.keccak256_digest(&state.unwrap())
+    }
+
+    /// Computes the Keccak-256 digest of the first `length` bytes of `message`, whose targets are
+    /// bytes. These bytes are range-checked, and `length` is checked to be at most
+    /// `message.len()`. The digest is returned as 32 bytes.
+    ///
+    /// The circuit always absorbs as many blocks as the longest possible message needs, and
+    /// selects the digest after the block holding the end of the padded message.
+    pub fn keccak256_variable_length(
+        &mut self,
+        message: &[Target],
+        length: Target,
+    ) -> [Target; KECCAK256_DIGEST_BYTES] {
+        let max_len = message.len();
+
+        // The bytes past `length` are masked out of the padded message, so they are not covered by
+        // the bit decomposition below and need their own range checks.
+        for &byte in message {
+            self.range_check(byte, 8);
+        }
+
+        // `is_length[i]` is set iff `length == i`. Exactly one of them must be set, which also
+        // checks that `length <= max_len`.
+        let is_length: Vec<BoolTarget> = (0..=max_len)
+            .map(|i| {
+                let i = self.constant(F::from_canonical_usize(i));
+                self.is_equal(length, i)
+            })
+            .collect();
+        let num_set = self.add_many(is_length.iter().map(|b| b.target));
+        self.assert_one(num_set);
+
+        // The message ends in block `b` iff `136 b <= length < 136 b + 136`.
+        let num_blocks = max_len / KECCAK256_RATE_BYTES + 1;
+        let is_last_block: Vec<Target> = (0..num_blocks)
+            .map(|b| {
+                let start = b * KECCAK256_RATE_BYTES;
+                let end = (start + KECCAK256_RATE_BYTES).min(max_len + 1);
+                self.add_many(is_length[start..end].iter().map(|b| b.target))
+            })
+            .collect();
+
+        // Each padded byte is the message byte if `i < length`, plus `0x01` if `i == length`, plus
+        // `0x80` if it is the last byte of the last block.
+        let zero = self.zero();
+        let mut is_before_length = self.one();
+        let padded: Vec<Target> = (0..num_blocks * KECCAK256_RATE_BYTES)
+            .map(|i| {
+                let mut byte = zero;
+                if i < max_len {
+                    is_before_length = self.sub(is_before_length, is_length[i].target);
+                    byte = self.mul(is_before_length, message[i]);
+                }
+                if i <= max_len {
+                    byte = self.add(byte, is_length[i].target);
+                }
+                if i % KECCAK256_RATE_BYTES == KECCAK256_RATE_BYTES - 1 {
+                    let is_last = is_last_block[i / KECCAK256_RATE_BYTES];
+                    byte = self.mul_const_add(F::from_canonical_u8(0x80), is_last, byte);
+                }
+                byte
+            })
+            .collect();
+
+        let bits: Vec<BoolTarget> = padded
+            .into_iter()
+            .flat_map(|byte| self.split_le(byte, 8))
+            .collect();
+        let mut state = None;
+        let mut digest = [zero; KECCAK256_DIGEST_BYTES];
+        for (block, &is_last) in bits
+            .chunks_exact(KECCAK256_RATE_BYTES * 8)
+            .zip(&is_last_block)
+        {
+            let new_state = self.keccak256_absorb(state, block);
+            let block_digest = self.keccak256_digest(&new_state);
+            for (d, byte) in digest.iter_mut().zip(block_digest) {
+                *d = self.mul_add(is_last, byte, *d);
+            }
+            state = Some(new_state);
+        }
+
+        digest
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use keccak_hash::keccak;
+    use rand::rngs::OsRng;
+    use rand::Rng;
+
+    use super::*;
+    use crate::field::types::Field;
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::verifier::verify;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    fn expected_digest(message: &[u8]) -> [F; KECCAK256_DIGEST_BYTES] {
+        keccak(message).0.map(F::from_canonical_u8)
+    }
+
+    #[test]
+    fn test_keccak256() -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let mut pw = PartialWitness::new();
+
+        // Lengths around the padding boundaries, up to two blocks.
+        for len in [0, 135, 136] {
+            let message: Vec<u8> = (0..len).map(|_| OsRng.gen()).collect();
+            let message_targets = builder.add_virtual_targets(len);
+            for (&t, &byte) in message_targets.iter().zip(&message) {
+                pw.set_target(t, F::from_canonical_u8(byte));
+            }
+
+            let digest = builder.keccak256(&message_targets);
+            for (&d, expected) in digest.iter().zip(expected_digest(&message)) {
+                let expected = builder.constant(expected);
+                builder.connect(d, expected);
+            }
+        }
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    fn test_keccak256_variable_length() -> Result<()> {
+        const MAX_LEN: usize = 140;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let mut pw = PartialWitness::new();
+
+        let message: Vec<u8> = (0..MAX_LEN).map(|_| OsRng.gen()).collect();
+        let message_targets = builder.add_virtual_targets(MAX_LEN);
+        for (&t, &byte) in message_targets.iter().zip(&message) {
+            pw.set_target(t, F::from_canonical_u8(byte));
+        }
+
+        for len in [0, 135, MAX_LEN] {
+            let length = builder.add_virtual_target();
+            pw.set_target(length, F::from_canonical_usize(len));
+
+            let digest = builder.keccak256_variable_length(&message_targets, length);
+            for (&d, expected) in digest.iter().zip(expected_digest(&message[..len])) {
+                let expected = builder.constant(expected);
+                builder.connect(d, expected);
+            }
+        }
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    fn test_xor_many() -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let mut pw = PartialWitness::new();
+
+        // Lengths around the single-gate limit, and one needing several chunks.
+        let max_inputs = builder.max_xor_inputs();
+        for len in [max_inputs, max_inputs + 1, 4 * max_inputs + 3] {
+            let bits: Vec<bool> = (0..len).map(|_| OsRng.gen()).collect();
+            let targets: Vec<BoolTarget> = (0..len)
+                .map(|_| builder.add_virtual_bool_target_safe())
+                .collect();
+            for (&t, &bit) in targets.iter().zip(&bits) {
+                pw.set_bool_target(t, bit);
+            }
+
+            let xor = builder.xor_many(&targets);
+            let expected = builder.constant_bool(bits.iter().fold(false, |acc, &b| acc ^ b));
+            builder.connect(xor.target, expected.target);
+        }
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_keccak256_variable_length_non_byte() {
+        const MAX_LEN: usize = 8;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let mut pw = PartialWitness::new();
+
+        // The last byte is past `length`, so it does not affect the digest, but it is still not a
+        // byte and must be rejected.
+        let message_targets = builder.add_virtual_targets(MAX_LEN);
+        for (i, &t) in message_targets.iter().enumerate() {
+            pw.set_target(t, F::from_canonical_usize(i));
+        }
+        pw.set_target(message_targets[MAX_LEN - 1], F::from_canonical_u16(256));
+        let length = builder.constant(F::from_canonical_usize(MAX_LEN - 1));
+        builder.keccak256_variable_length(&message_targets, length);
+
+        let data = builder.build::<C>();
+        data.prove(pw).unwrap();
+    }
+}
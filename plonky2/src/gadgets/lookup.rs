@@ -1,9 +1,10 @@
 use alloc::borrow::ToOwned;
 use alloc::vec;
+use alloc::vec::Vec;
 
 use crate::field::extension::Extendable;
 use crate::gates::lookup::LookupGate;
-use crate::gates::lookup_table::{LookupTable, LookupTableGate};
+use crate::gates::lookup_table::{LookupTable, LookupTableGate, WideLookupTable};
 use crate::gates::noop::NoopGate;
 use crate::hash::hash_types::RichField;
 use crate::iop::target::Target;
@@ -62,22 +63,50 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         self.update_luts_from_fn(f, inputs)
     }
 
+    /// Adds a lookup table with any number of input columns to the list of stored lookup tables `self.luts`. It returns the index of the LUT within `self.luts`.
+    pub fn add_wide_lookup_table(&mut self, table: WideLookupTable) -> usize {
+        self.update_wide_luts(table)
+    }
+
+    /// Adds a lookup table to the list of stored lookup tables `self.luts` based on a function of several field elements, evaluated on each tuple of `inputs`. It returns the index of the LUT within `self.luts`.
+    pub fn add_wide_lookup_table_from_fn(&mut self, f: fn(&[F]) -> F, inputs: &[Vec<F>]) -> usize {
+        self.update_wide_luts(WideLookupTable::from_fn(f, inputs))
+    }
+
     /// Adds a lookup (input, output) pair to the stored lookups. Takes a `Target` input and returns a `Target` output.
     pub fn add_lookup_from_index(&mut self, looking_in: Target, lut_index: usize) -> Target {
+        self.add_wide_lookup_from_index(&[looking_in], lut_index)
+    }
+
+    /// Adds a lookup (inputs, output) pair to the stored lookups. Takes one `Target` per input column of the LUT and returns a `Target` output.
+    pub fn add_wide_lookup_from_index(
+        &mut self,
+        looking_in: &[Target],
+        lut_index: usize,
+    ) -> Target {
         assert!(
             lut_index < self.get_luts_length(),
             "lut number {} not in luts (length = {})",
             lut_index,
             self.get_luts_length()
         );
+        let num_inputs = self.get_lut(lut_index).num_inputs();
+        assert_eq!(
+            looking_in.len(),
+            num_inputs,
+            "lut number {} has {} input columns",
+            lut_index,
+            num_inputs
+        );
         let looking_out = self.add_virtual_target();
-        self.update_lookups(looking_in, looking_out, lut_index);
+        self.update_lookups(looking_in.to_vec(), looking_out, lut_index);
         looking_out
     }
 
     /// We call this function at the end of circuit building right before the PI gate to add all `LookupTableGate` and `LookupGate`.
     /// It also updates `self.lookup_rows` accordingly.
     pub fn add_all_lookups(&mut self) {
+        let width = self.get_lookup_width();
         for lut_index in 0..self.num_luts() {
             assert!(
                 !self.get_lut_lookups(lut_index).is_empty(),
@@ -92,8 +121,8 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
 
                 let lookups = self.get_lut_lookups(lut_index).to_owned();
 
-                let gate = LookupGate::new_from_table(&self.config, lut.clone());
-                let num_slots = LookupGate::num_slots(&self.config);
+                let gate = LookupGate::new_from_table(&self.config, lut.clone(), width);
+                let num_slots = LookupGate::num_slots(&self.config, width);
 
                 // Given the number of lookups and the number of slots for each gate, it is possible
                 // to compute the number of gates that will employ all their slots; such gates can
@@ -110,28 +139,26 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
                 lookup_iter.for_each(|chunk| {
                     let row = self.add_gate(gate.clone(), vec![]);
                     for (i, (looking_in, looking_out)) in chunk.iter().enumerate() {
-                        let gate_in = Target::wire(row, LookupGate::wire_ith_looking_inp(i));
-                        let gate_out = Target::wire(row, LookupGate::wire_ith_looking_out(i));
-                        self.connect(gate_in, *looking_in);
-                        self.connect(gate_out, *looking_out);
+                        self.connect_lookup_slot(row, i, width, looking_in, *looking_out);
                     }
                 });
                 // deal with the last chunk
                 for (looking_in, looking_out) in last_chunk.iter() {
-                    let (gate, i) =
+                    let (row, i) =
                         self.find_slot(gate.clone(), &[F::from_canonical_usize(lut_index)], &[]);
-                    let gate_in = Target::wire(gate, LookupGate::wire_ith_looking_inp(i));
-                    let gate_out = Target::wire(gate, LookupGate::wire_ith_looking_out(i));
-                    self.connect(gate_in, *looking_in);
-                    self.connect(gate_out, *looking_out);
+                    self.connect_lookup_slot(row, i, width, looking_in, *looking_out);
                 }
 
                 // Create LUT gates. Nothing is connected to them.
                 let last_lut_gate = self.num_gates();
-                let num_lut_entries = LookupTableGate::num_slots(&self.config);
+                let num_lut_entries = LookupTableGate::num_slots(&self.config, width);
                 let num_lut_rows = (self.get_luts_idx_length(lut_index) - 1) / num_lut_entries + 1;
-                let gate =
-                    LookupTableGate::new_from_table(&self.config, lut.clone(), last_lut_gate);
+                let gate = LookupTableGate::new_from_table(
+                    &self.config,
+                    lut.clone(),
+                    width,
+                    last_lut_gate,
+                );
                 // Also instances of `LookupTableGate` can be placed with the `add_gate` function
                 // rather than being instantiated slot by slot; note that in this case there is no
                 // need to separately handle the last chunk of LUT entries that cannot fill all the
@@ -153,4 +180,24 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
             }
         }
     }
+
+    /// Connects the `i`-th slot of the `LookupGate` at `row` to the given inputs and output.
+    fn connect_lookup_slot(
+        &mut self,
+        row: usize,
+        i: usize,
+        width: usize,
+        looking_in: &[Target],
+        looking_out: Target,
+    ) {
+        for (col, &input) in looking_in.iter().enumerate() {
+            let gate_in = Target::wire(row, LookupGate::wire_ith_looking_col(width, i, col));
+            self.connect(gate_in, input);
+        }
+        let gate_out = Target::wire(
+            row,
+            LookupGate::wire_ith_looking_col(width, i, looking_in.len()),
+        );
+        self.connect(gate_out, looking_out);
+    }
 }
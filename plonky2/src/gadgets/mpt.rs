@@ -0,0 +1,622 @@
+//! Inclusion proofs for Ethereum Merkle-Patricia tries with 32-byte (hashed) keys, such as the
+//! state and storage tries.
+//!
+//! A proof is the list of RLP-encoded nodes on the path from the root to the leaf holding the
+//! value. Each node is hashed with Keccak-256 and matched against the reference held by its
+//! parent, while the nibbles consumed by branch, extension and leaf nodes are matched against the
+//! key. Nodes shorter than 32 bytes, which Ethereum embeds in their parent rather than hashing,
+//! are not supported; with hashed keys, they only occur in tries holding very short values.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::field::extension::Extendable;
+use crate::gadgets::keccak::KECCAK256_DIGEST_BYTES;
+use crate::hash::hash_types::RichField;
+use crate::iop::target::{BoolTarget, Target};
+use crate::plonk::circuit_builder::CircuitBuilder;
+
+/// The number of nibbles in a trie key.
+const KEY_NIBBLES: usize = 64;
+
+/// The most bytes a hex-prefix encoded path can take.
+const MAX_PATH_BYTES: usize = KEY_NIBBLES / 2 + 1;
+
+/// The number of items in the RLP encoding of a branch node.
+const BRANCH_ITEMS: usize = 17;
+
+/// The RLP prefix of a 32-byte string, i.e. a hash reference to a child node.
+const RLP_HASH_PREFIX: u8 = 0x80 + 32;
+
+/// The output of the RLP lookup table for the prefix `0xb8` of a string of 56 to 255 bytes, whose
+/// length is given by the next byte.
+const RLP_LONG_STRING: u16 = 256;
+
+/// The output of the RLP lookup table for prefixes which cannot start an item of a trie node.
+const RLP_UNSUPPORTED: u16 = 512;
+
+/// Bounds on the size of a Merkle-Patricia trie proof.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct MptProofConfig {
+    /// The maximum number of nodes in a proof.
+    pub max_depth: usize,
+    /// The maximum length of an RLP-encoded node. A branch node with 16 children takes 532 bytes.
+    pub max_node_len: usize,
+    /// The maximum length of a value, which must be less than 256.
+    pub max_value_len: usize,
+}
+
+/// A Merkle-Patricia trie proof: the RLP-encoded nodes on the path from the root to the leaf, each
+/// padded with zeros to `max_node_len` bytes.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MptProofTarget {
+    pub nodes: Vec<Vec<Target>>,
+    pub node_lens: Vec<Target>,
+    /// The number of nodes actually used by the proof.
+    pub depth: Target,
+}
+
+/// The decoding of an RLP list of strings.
+struct RlpListTargets {
+    /// The number of items in the list.
+    num_items: Target,
+    /// One-hot flags for a list header of 1, 2 or 3 bytes.
+    header_len_flags: [Target; 3],
+    /// One-hot flags for the start of the item with index `branch_index`.
+    branch_item_start: Vec<Target>,
+    /// One-hot flags for the start of the second item.
+    second_item_start: Vec<Target>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
+    pub fn add_virtual_mpt_proof_target(&mut self, config: MptProofConfig) -> MptProofTarget {
+        MptProofTarget {
+            nodes: (0..config.max_depth)
+                .map(|_| self.add_virtual_targets(config.max_node_len))
+                .collect(),
+            node_lens: self.add_virtual_targets(config.max_depth),
+            depth: self.add_virtual_target(),
+        }
+    }
+
+    /// Adds a lookup table sending the first byte of an RLP string to the number of bytes that
+    /// follow it, or to `RLP_LONG_STRING` or `RLP_UNSUPPORTED`.
+    fn rlp_lut(&mut self) -> usize {
+        let inputs: Vec<u16> = (0..256).collect();
+        let outputs: Vec<u16> = inputs
+            .iter()
+            .map(|&b| match b {
+                0x00..=0x7f => 0,
+                0x80..=0xb7 => b - 0x80,
+                0xb8 => RLP_LONG_STRING,
+                _ => RLP_UNSUPPORTED,
+            })
+            .collect();
+        self.add_lookup_table_from_table(&inputs, &outputs)
+    }
+
+    /// Checks that `x` is zero if the boolean `condition` is set.
+    fn assert_zero_if(&mut self, condition: Target, x: Target) {
+        let product = self.mul(condition, x);
+        self.assert_zero(product);
+    }
+
+    /// Returns one-hot flags for `x == i`, for `i` in `0..n`.
+    fn one_hot(&mut self, x: Target, n: usize) -> Vec<Target> {
+        (0..n)
+            .map(|i| {
+                let i = self.constant(F::from_canonical_usize(i));
+                self.is_equal(x, i).target
+            })
+            .collect()
+    }
+
+    /// Returns the bytes `bytes[p + j]` for `j` in `0..len`, where `p` is the index of the set flag
+    /// in `positions`. Bytes past the end of `bytes` are taken to be zero.
+    fn select_window(&mut self, positions: &[Target], bytes: &[Target], len: usize) -> Vec<Target> {
+        (0..len)
+            .map(|j| {
+                let terms: Vec<Target> = positions
+                    .iter()
+                    .enumerate()
+                    .filter(|&(p, _)| p + j < bytes.len())
+                    .map(|(p, &flag)| self.mul(flag, bytes[p + j]))
+                    .collect();
+                self.add_many(terms)
+            })
+            .collect()
+    }
+
+    /// Decodes an RLP-encoded list of strings, each at most 255 bytes long, given as `len` bytes
+    /// followed by padding. All bytes, including the padding, are range-checked.
+    ///
+    /// If `condition` is set, checks that the payload length given by the list header is
+    /// `len` minus the header length, and that the last item ends at `len`, so that the item
+    /// lengths sum to the payload length.
+    fn decode_rlp_list(
+        &mut self,
+        bytes: &[Target],
+        len: Target,
+        branch_index: Target,
+        rlp_lut: usize,
+        condition: Target,
+    ) -> RlpListTargets {
+        let zero = self.zero();
+        let one = self.one();
+
+        let is_f8 = self.constant(F::from_canonical_u8(0xf8));
+        let is_f8 = self.is_equal(bytes[0], is_f8).target;
+        let is_f9 = self.constant(F::from_canonical_u8(0xf9));
+        let is_f9 = self.is_equal(bytes[0], is_f9).target;
+        let is_short = self.sub(one, is_f8);
+        let is_short = self.sub(is_short, is_f9);
+        let header_len_flags = [is_short, is_f8, is_f9];
+
+        // The payload length is `bytes[0] - 0xc0` for a 1-byte header, and is given by the next
+        // one or two bytes otherwise.
+        let short_payload_len = self.add_const(bytes[0], -F::from_canonical_u8(0xc0));
+        let payload_len = self.mul(is_short, short_payload_len);
+        let payload_len = self.mul_add(is_f8, bytes[1], payload_len);
+        let long_payload_len = self.mul_const_add(F::from_canonical_u16(256), bytes[1], bytes[2]);
+        let payload_len = self.mul_add(is_f9, long_payload_len, payload_len);
+        let header_len = self.mul_const_add(F::TWO, is_f9, is_f8);
+        let header_len = self.add(header_len, one);
+        let list_len = self.add(header_len, payload_len);
+        let diff = self.sub(list_len, len);
+        self.assert_zero_if(condition, diff);
+
+        let is_len = self.one_hot(len, bytes.len() + 1);
+        let is_past_header = [zero, is_short, self.sub(one, is_f9)];
+
+        // `remaining` counts the bytes left in the current item, and `num_items` the items which
+        // have started so far.
+        let mut remaining = zero;
+        let mut num_items = zero;
+        let mut is_before_len = one;
+        let mut branch_item_start = Vec::with_capacity(bytes.len());
+        let mut second_item_start = Vec::with_capacity(bytes.len());
+        for p in 0..bytes.len() {
+            is_before_len = self.sub(is_before_len, is_len[p]);
+            let in_list = match is_past_header.get(p) {
+                Some(&past_header) => self.mul(past_header, is_before_len),
+                None => is_before_len,
+            };
+
+            let item_len = self.add_lookup_from_index(bytes[p], rlp_lut);
+            let is_long = self.constant(F::from_canonical_u16(RLP_LONG_STRING));
+            let is_long = self.is_equal(item_len, is_long).target;
+            let next_byte = bytes.get(p + 1).copied().unwrap_or(zero);
+            let long_len = self.add_const(next_byte, -F::from_canonical_u16(RLP_LONG_STRING - 1));
+            let item_len = self.mul_add(is_long, long_len, item_len);
+
+            let is_start = self.is_equal(remaining, zero).target;
+            let is_start = self.mul(in_list, is_start);
+            let is_branch_item = self.is_equal(num_items, branch_index).target;
+            branch_item_start.push(self.mul(is_start, is_branch_item));
+            let is_second_item = self.is_equal(num_items, one).target;
+            second_item_start.push(self.mul(is_start, is_second_item));
+
+            let continued = self.sub(remaining, in_list);
+            let started = self.sub(item_len, continued);
+            remaining = self.mul_add(is_start, started, continued);
+            num_items = self.add(num_items, is_start);
+        }
+        self.assert_zero_if(condition, remaining);
+
+        RlpListTargets {
+            num_items,
+            header_len_flags,
+            branch_item_start,
+            second_item_start,
+        }
+    }
+
+    /// Checks that `proof` shows that the first `value_len` bytes of `value` are stored under
+    /// `key` in the trie with root hash `root`. The value is the payload of the leaf's value
+    /// string, e.g. the RLP encoding of an account for the state trie.
+    ///
+    /// All bytes of the proof and key are range-checked, as are the bytes of `value` within
+    /// `value_len`.
+    ///
+    /// Every node on the path must reference its child by hash. Children whose RLP encoding is
+    /// shorter than 32 bytes are embedded in their parent instead, and proofs through them are
+    /// rejected.
+    pub fn verify_mpt_proof(
+        &mut self,
+        proof: &MptProofTarget,
+        root: [Target; KECCAK256_DIGEST_BYTES],
+        key: [Target; KECCAK256_DIGEST_BYTES],
+        value: &[Target],
+        value_len: Target,
+    ) {
+        let max_depth = proof.nodes.len();
+        let max_node_len = proof.nodes[0].len();
+        let max_value_len = value.len();
+        assert!(max_depth > 0, "Proofs must contain at least one node");
+        assert!(max_value_len < 256, "Values must be shorter than 256 bytes");
+
+        let zero = self.zero();
+        let one = self.one();
+        let rlp_lut = self.rlp_lut();
+
+        // The key nibbles, preceded by a zero so that windows can start one nibble early, and
+        // followed by enough zeros for any window to fit.
+        let mut key_nibbles = vec![zero];
+        for &byte in &key {
+            let (high, low) = self.split_nibbles(byte);
+            key_nibbles.extend([high, low]);
+        }
+        key_nibbles.resize(2 * KEY_NIBBLES + 3, zero);
+
+        // `is_last[i]` is set iff node `i` is the leaf.
+        let is_last = self.one_hot(proof.depth, max_depth + 1)[1..].to_vec();
+        let num_set = self.add_many(is_last.iter().copied());
+        self.assert_one(num_set);
+
+        let mut is_active = one;
+        let mut position = zero;
+        let mut expected_hash = root;
+        let mut leaf = vec![zero; max_node_len];
+        let mut leaf_value_start = vec![zero; max_node_len];
+        for i in 0..max_depth {
+            let node = &proof.nodes[i];
+            assert_eq!(node.len(), max_node_len);
+
+            let hash = self.keccak256_variable_length(node, proof.node_lens[i]);
+            for (h, e) in hash.into_iter().zip(expected_hash) {
+                let diff = self.sub(h, e);
+                self.assert_zero_if(is_active, diff);
+            }
+
+            // `path[u]` is the key nibble at `position + u - 1`.
+            let is_position = self.one_hot(position, KEY_NIBBLES + 1);
+            let num_set = self.add_many(is_position.iter().copied());
+            let diff = self.sub(num_set, one);
+            self.assert_zero_if(is_active, diff);
+            let path = self.select_window(&is_position, &key_nibbles, KEY_NIBBLES + 2);
+            let nibble = path[1];
+
+            let list = self.decode_rlp_list(node, proof.node_lens[i], nibble, rlp_lut, is_active);
+            let branch_items = self.constant(F::from_canonical_usize(BRANCH_ITEMS));
+            let is_branch = self.is_equal(list.num_items, branch_items);
+            let two = self.two();
+            let is_short = self.is_equal(list.num_items, two).target;
+            let is_branch_or_short = self.add(is_branch.target, is_short);
+            let diff = self.sub(is_branch_or_short, one);
+            self.assert_zero_if(is_active, diff);
+
+            // Branch nodes reference the child at the next nibble, and extension nodes the child
+            // in their second item.
+            let item_start: Vec<Target> = list
+                .branch_item_start
+                .iter()
+                .zip(&list.second_item_start)
+                .map(|(&branch, &second)| self.select(is_branch, branch, second))
+                .collect();
+
+            // Decode the hex-prefix encoded path of a short node, which is its first item.
+            let first_item =
+                self.select_window(&list.header_len_flags, &node[1..], MAX_PATH_BYTES + 1);
+            let first_item_len = self.add_lookup_from_index(first_item[0], rlp_lut);
+            let is_single_byte = self.is_equal(first_item_len, zero);
+            let path_len = self.select(is_single_byte, one, first_item_len);
+            let hp_bytes: Vec<Target> = (0..MAX_PATH_BYTES)
+                .map(|j| {
+                    let single = if j == 0 { first_item[0] } else { zero };
+                    self.select(is_single_byte, single, first_item[j + 1])
+                })
+                .collect();
+            let hp_nibbles: Vec<Target> = hp_bytes
+                .iter()
+                .flat_map(|&byte| {
+                    let (high, low) = self.split_nibbles(byte);
+                    [high, low]
+                })
+                .collect();
+            let flag = hp_nibbles[0];
+            let [is_flag_1, is_flag_2, is_flag_3] = core::array::from_fn(|f| {
+                let f = self.constant(F::from_canonical_usize(f + 1));
+                self.is_equal(flag, f).target
+            });
+            let is_odd = BoolTarget::new_unsafe(self.add(is_flag_1, is_flag_3));
+            let is_leaf = self.add(is_flag_2, is_flag_3);
+
+            // `hp_nibbles[u + 1]` is path nibble `u - 1 + is_odd`, which must match key nibble
+            // `position + u - 1 + is_odd`, for `1 - is_odd <= u < 2 path_len - 1`.
+            let is_path_len = self.one_hot(path_len, MAX_PATH_BYTES + 1);
+            let mut path_len_at_least = vec![zero; MAX_PATH_BYTES + 2];
+            for l in (0..=MAX_PATH_BYTES).rev() {
+                path_len_at_least[l] = self.add(path_len_at_least[l + 1], is_path_len[l]);
+            }
+            let check_path = self.mul(is_active, is_short);
+            for u in 0..2 * MAX_PATH_BYTES - 1 {
+                let key_nibble = self.select(is_odd, path[u + 1], path[u]);
+                let diff = self.sub(hp_nibbles[u + 1], key_nibble);
+                let in_path = path_len_at_least[u.div_ceil(2) + 1];
+                let in_path = if u == 0 {
+                    self.mul(in_path, is_odd.target)
+                } else {
+                    in_path
+                };
+                let condition = self.mul(check_path, in_path);
+                self.assert_zero_if(condition, diff);
+            }
+            let num_path_nibbles = self.mul_const_add(F::TWO, path_len, is_odd.target);
+            let num_path_nibbles = self.add_const(num_path_nibbles, -F::TWO);
+            let branch_position = self.add(position, one);
+            let short_position = self.add(position, num_path_nibbles);
+            position = self.select(is_branch, branch_position, short_position);
+
+            // The leaf is a short node with the leaf flag set, consuming the rest of the key.
+            let is_leaf_node = is_last[i];
+            let diff = self.sub(is_short, one);
+            self.assert_zero_if(is_leaf_node, diff);
+            let diff = self.sub(is_leaf, one);
+            self.assert_zero_if(is_leaf_node, diff);
+            let end_position = self.constant(F::from_canonical_usize(KEY_NIBBLES));
+            let diff = self.sub(position, end_position);
+            self.assert_zero_if(is_leaf_node, diff);
+            for p in 0..max_node_len {
+                leaf[p] = self.mul_add(is_leaf_node, node[p], leaf[p]);
+                leaf_value_start[p] =
+                    self.mul_add(is_leaf_node, list.second_item_start[p], leaf_value_start[p]);
+            }
+
+            // Other nodes are branch or extension nodes referencing the next node by its hash.
+            is_active = self.sub(is_active, is_leaf_node);
+            let is_short_leaf = self.mul(is_short, is_leaf);
+            self.assert_zero_if(is_active, is_short_leaf);
+            let reference = self.select_window(&item_start, node, KECCAK256_DIGEST_BYTES + 1);
+            let hash_prefix = self.constant(F::from_canonical_u8(RLP_HASH_PREFIX));
+            let diff = self.sub(reference[0], hash_prefix);
+            self.assert_zero_if(is_active, diff);
+            expected_hash = reference[1..].try_into().unwrap();
+        }
+
+        // The value is the payload of the leaf's second item.
+        let value_item = self.select_window(&leaf_value_start, &leaf, max_value_len + 2);
+        let item_len = self.add_lookup_from_index(value_item[0], rlp_lut);
+        let is_single_byte = self.is_equal(item_len, zero);
+        let is_long = self.constant(F::from_canonical_u16(RLP_LONG_STRING));
+        let is_long = self.is_equal(item_len, is_long);
+        let long_len = self.add_const(value_item[1], -F::from_canonical_u16(RLP_LONG_STRING));
+        let expected_len = self.add(item_len, is_single_byte.target);
+        let expected_len = self.mul_add(is_long.target, long_len, expected_len);
+        self.connect(value_len, expected_len);
+
+        let is_value_len = self.one_hot(value_len, max_value_len + 1);
+        let mut in_value = one;
+        for j in 0..max_value_len {
+            in_value = self.sub(in_value, is_value_len[j]);
+            let short_byte = self.select(is_single_byte, value_item[j], value_item[j + 1]);
+            let expected_byte = self.select(is_long, value_item[j + 2], short_byte);
+            let diff = self.sub(value[j], expected_byte);
+            self.assert_zero_if(in_value, diff);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use hex_literal::hex;
+    use keccak_hash::keccak;
+
+    use super::*;
+    use crate::field::types::Field;
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::verifier::verify;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    const CONFIG: MptProofConfig = MptProofConfig {
+        max_depth: 4,
+        max_node_len: 135,
+        max_value_len: 64,
+    };
+
+    /// The root of a trie whose root is a branch node, holding a leaf with an odd path and an
+    /// extension node with an even path. The extension node leads to a branch node holding three
+    /// leaves with even paths. All nodes fit in a single Keccak block.
+    const ROOT: [u8; 32] = hex!("6f2a4b933bb80229a615dce92f6b3a7a1c6e0d5468920a0ff3f59a145d551f06");
+
+    /// The root node of the trie.
+    const ROOT_NODE: &[u8] = &hex!(
+        "f8518080808080a09d949356bb9038a1e90c900dcdd49c43dfb0230c39671d28b4f27a4fc284803080a0558e"
+        "25e1dc0aaab80a91bb16d0c2bf09672c9a948dd50a3568e2fa0b4cffe3ce808080808080808080"
+    );
+
+    /// A key under the extension node, holding a single byte.
+    const SHORT_VALUE_KEY: [u8; 32] =
+        hex!("5a003d5a7794b1ceeb0825425f7c99b6d3f00d2a4764819ebbd8f5122f4c6986");
+    const SHORT_VALUE: &[u8] = &hex!("05");
+    const SHORT_VALUE_PROOF: [&[u8]; 4] = [
+        ROOT_NODE,
+        &hex!("e48200a0a083bcc952cb2cc9b446c0b50deb1d71875593cb7b221707fd79f9260348025e35"),
+        &hex!(
+            "f871a0ceec7ce9d6b11a7a7c8e0be9e4aa431e592d2249800f0d77f6fffff8e1bc8496a0ea1167e4ad18"
+            "bc1aba179ca1b9b625d938f71943ad6200daea9435fa3c8d7639a05343771898ce4ff7e4fb7667f91bf3"
+            "0c48c2c013b6b0a9a85660a75c89b3dd1f8080808080808080808080808080"
+        ),
+        &hex!("e19f203d5a7794b1ceeb0825425f7c99b6d3f00d2a4764819ebbd8f5122f4c698605"),
+    ];
+
+    /// The key of the leaf held by the root, holding a long string.
+    const LONG_VALUE_KEY: [u8; 32] =
+        hex!("7003f4112e4b6885a2bfdcf91633506d8aa7c4e1fe1b3855728facc9e603203d");
+    const LONG_VALUE: &[u8] = &hex!(
+        "282f363d444b525960676e757c838a91989fa6adb4bbc2c9d0d7dee5ecf3fa01080f161d242b323940474e55"
+        "5c636a71787f868d949ba2a9b0b7bec5"
+    );
+    const LONG_VALUE_PROOF: [&[u8]; 2] = [
+        ROOT_NODE,
+        &hex!(
+            "f85fa03003f4112e4b6885a2bfdcf91633506d8aa7c4e1fe1b3855728facc9e603203db83c282f363d44"
+            "4b525960676e757c838a91989fa6adb4bbc2c9d0d7dee5ecf3fa01080f161d242b323940474e555c636a"
+            "71787f868d949ba2a9b0b7bec5"
+        ),
+    ];
+
+    fn rlp_string(bytes: &[u8]) -> Vec<u8> {
+        match bytes.len() {
+            1 if bytes[0] < 0x80 => bytes.to_vec(),
+            len if len < 56 => [&[0x80 + len as u8], bytes].concat(),
+            len => [&[0xb8, len as u8], bytes].concat(),
+        }
+    }
+
+    fn rlp_list(items: &[Vec<u8>]) -> Vec<u8> {
+        let payload = items.concat();
+        let header = match payload.len() {
+            len if len < 56 => vec![0xc0 + len as u8],
+            len if len < 256 => vec![0xf8, len as u8],
+            len => vec![0xf9, (len >> 8) as u8, len as u8],
+        };
+        [header, payload].concat()
+    }
+
+    fn hex_prefix(nibbles: &[u8], is_leaf: bool) -> Vec<u8> {
+        let flag = 2 * is_leaf as u8 + nibbles.len() as u8 % 2;
+        let mut padded = vec![flag];
+        if nibbles.len() % 2 == 0 {
+            padded.push(0);
+        }
+        padded.extend(nibbles);
+        padded.chunks(2).map(|c| (c[0] << 4) | c[1]).collect()
+    }
+
+    fn key_nibbles(key: &[u8]) -> Vec<u8> {
+        key.iter().flat_map(|&b| [b >> 4, b & 0xf]).collect()
+    }
+
+    /// Returns the encoding of a leaf consuming the whole of `key`, i.e. the only node of a
+    /// trie with a single key.
+    fn root_leaf(key: &[u8; 32], value: &[u8]) -> Vec<u8> {
+        rlp_list(&[
+            rlp_string(&hex_prefix(&key_nibbles(key), true)),
+            rlp_string(value),
+        ])
+    }
+
+    /// Adds a circuit verifying the proof of `value` under `key` in the trie with root hash
+    /// `root`, and sets its witness.
+    fn add_mpt_proof(
+        builder: &mut CircuitBuilder<F, D>,
+        pw: &mut PartialWitness<F>,
+        root: &[u8; 32],
+        key: &[u8; 32],
+        value: &[u8],
+        proof: &[Vec<u8>],
+    ) {
+        let proof_target = builder.add_virtual_mpt_proof_target(CONFIG);
+        let root_target = builder.add_virtual_target_arr();
+        let key_target = builder.add_virtual_target_arr();
+        let value_target = builder.add_virtual_targets(CONFIG.max_value_len);
+        let value_len = builder.add_virtual_target();
+        builder.verify_mpt_proof(
+            &proof_target,
+            root_target,
+            key_target,
+            &value_target,
+            value_len,
+        );
+
+        pw.set_mpt_proof_target(&proof_target, proof);
+        for (&t, &b) in root_target.iter().zip(root) {
+            pw.set_target(t, F::from_canonical_u8(b));
+        }
+        for (&t, &b) in key_target.iter().zip(key) {
+            pw.set_target(t, F::from_canonical_u8(b));
+        }
+        for (j, &t) in value_target.iter().enumerate() {
+            pw.set_target(t, F::from_canonical_u8(value.get(j).copied().unwrap_or(0)));
+        }
+        pw.set_target(value_len, F::from_canonical_usize(value.len()));
+    }
+
+    /// Builds and proves a circuit verifying the proof of `value` under `key`.
+    fn prove_mpt_proof(root: &[u8; 32], key: &[u8; 32], value: &[u8], proof: &[Vec<u8>]) {
+        let circuit_config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(circuit_config);
+        let mut pw = PartialWitness::new();
+        add_mpt_proof(&mut builder, &mut pw, root, key, value, proof);
+
+        let data = builder.build::<C>();
+        data.prove(pw).unwrap();
+    }
+
+    #[test]
+    fn test_mpt_proof() -> Result<()> {
+        assert_eq!(keccak(ROOT_NODE).0, ROOT);
+
+        let circuit_config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(circuit_config);
+        let mut pw = PartialWitness::new();
+
+        // The values proven below are a single byte and a long string.
+        for (key, value, proof) in [
+            (SHORT_VALUE_KEY, SHORT_VALUE, &SHORT_VALUE_PROOF[..]),
+            (LONG_VALUE_KEY, LONG_VALUE, &LONG_VALUE_PROOF[..]),
+        ] {
+            let proof: Vec<Vec<u8>> = proof.iter().map(|node| node.to_vec()).collect();
+            add_mpt_proof(&mut builder, &mut pw, &ROOT, &key, value, &proof);
+        }
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    fn test_mpt_proof_single_leaf() {
+        let leaf = root_leaf(&LONG_VALUE_KEY, LONG_VALUE);
+        prove_mpt_proof(&keccak(&leaf).0, &LONG_VALUE_KEY, LONG_VALUE, &[leaf]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_mpt_proof_embedded_node() {
+        // The two keys only differ in their last nibble, so their leaves, and the branch node
+        // holding them, are embedded in the root extension node.
+        let key = SHORT_VALUE_KEY;
+        let leaf = |value| rlp_list(&[rlp_string(&hex_prefix(&[], true)), rlp_string(value)]);
+        let mut branch_items = vec![rlp_string(&[]); BRANCH_ITEMS];
+        branch_items[usize::from(key[31] & 0xf)] = leaf(&[0x05]);
+        branch_items[usize::from((key[31] & 0xf) ^ 1)] = leaf(&[0x06]);
+        let branch = rlp_list(&branch_items);
+        let path = &key_nibbles(&key)[..KEY_NIBBLES - 1];
+        let root = rlp_list(&[rlp_string(&hex_prefix(path, false)), branch.clone()]);
+        assert!(branch.len() < 32);
+
+        prove_mpt_proof(&keccak(&root).0, &key, &[0x05], &[root, branch]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_mpt_proof_truncated_item() {
+        // The value string is cut short, with the list header matching the truncated node, so
+        // that the missing bytes would be read from the padding.
+        let path = rlp_string(&hex_prefix(&key_nibbles(&LONG_VALUE_KEY), true));
+        let value = rlp_string(LONG_VALUE);
+        let leaf = rlp_list(&[path, value[..value.len() - 5].to_vec()]);
+        let mut truncated_value = LONG_VALUE.to_vec();
+        truncated_value[LONG_VALUE.len() - 5..].fill(0);
+
+        prove_mpt_proof(&keccak(&leaf).0, &LONG_VALUE_KEY, &truncated_value, &[leaf]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_mpt_proof_wrong_list_len() {
+        // The list header claims one more byte than the items take.
+        let mut leaf = root_leaf(&SHORT_VALUE_KEY, SHORT_VALUE);
+        leaf[0] += 1;
+
+        prove_mpt_proof(&keccak(&leaf).0, &SHORT_VALUE_KEY, SHORT_VALUE, &[leaf]);
+    }
+}
@@ -0,0 +1,473 @@
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use num::{BigUint, One, Zero};
+
+use crate::field::extension::Extendable;
+use crate::field::types::{Field, PrimeField};
+use crate::gadgets::biguint::{num_biguint_limbs, BigUintTarget, BIGUINT_LIMB_BITS};
+use crate::hash::hash_types::RichField;
+use crate::iop::generator::{GeneratedValues, SimpleGenerator};
+use crate::iop::target::{BoolTarget, Target};
+use crate::iop::witness::{PartitionWitness, Witness, WitnessWrite};
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::circuit_data::CommonCircuitData;
+use crate::util::serialization::{Buffer, IoResult, Read, Write};
+
+/// An element of the prime field `FF`, represented by its canonical value as a `BigUintTarget`.
+///
+/// Every `NonNativeTarget` returned by the builder is range-checked to be less than the order of
+/// `FF`, so two targets represent the same element iff their limbs are equal.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct NonNativeTarget<FF: Field> {
+    pub(crate) value: BigUintTarget,
+    _phantom: PhantomData<FF>,
+}
+
+impl<FF: Field> NonNativeTarget<FF> {
+    pub fn value(&self) -> &BigUintTarget {
+        &self.value
+    }
+}
+
+/// Returns the number of 16-bit limbs used to represent elements of `FF`.
+pub fn num_nonnative_limbs<FF: Field>() -> usize {
+    num_biguint_limbs(FF::BITS)
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
+    fn nonnative_modulus<FF: PrimeField>(&mut self) -> BigUintTarget {
+        self.constant_biguint(&FF::order())
+    }
+
+    /// Wraps a `BigUintTarget` which is known to be the canonical value of an element of `FF`.
+    pub(crate) fn nonnative_from_canonical<FF: PrimeField>(
+        &mut self,
+        value: BigUintTarget,
+    ) -> NonNativeTarget<FF> {
+        NonNativeTarget {
+            value,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Reduces `x` modulo the order of `FF`, with a quotient of `num_quotient_limbs` limbs.
+    fn reduce_nonnative<FF: PrimeField>(
+        &mut self,
+        x: &BigUintTarget,
+        num_quotient_limbs: usize,
+    ) -> NonNativeTarget<FF> {
+        let modulus = self.nonnative_modulus::<FF>();
+        let (_, remainder) =
+            self.div_rem_biguint_with_quotient_limbs(x, &modulus, num_quotient_limbs);
+        self.nonnative_from_canonical(remainder)
+    }
+
+    /// Reduces an arbitrary `BigUintTarget` to an element of `FF`.
+    pub fn biguint_to_nonnative<FF: PrimeField>(
+        &mut self,
+        x: &BigUintTarget,
+    ) -> NonNativeTarget<FF> {
+        let num_quotient_limbs = x.num_limbs().saturating_sub(num_nonnative_limbs::<FF>()) + 1;
+        self.reduce_nonnative(x, num_quotient_limbs)
+    }
+
+    pub fn nonnative_to_canonical_biguint<FF: PrimeField>(
+        &mut self,
+        x: &NonNativeTarget<FF>,
+    ) -> BigUintTarget {
+        x.value.clone()
+    }
+
+    pub fn constant_nonnative<FF: PrimeField>(&mut self, x: FF) -> NonNativeTarget<FF> {
+        let value = self.constant_biguint(&x.to_canonical_biguint());
+        let value = self.pad_biguint(&value, num_nonnative_limbs::<FF>());
+        self.nonnative_from_canonical(value)
+    }
+
+    pub fn zero_nonnative<FF: PrimeField>(&mut self) -> NonNativeTarget<FF> {
+        self.constant_nonnative(FF::ZERO)
+    }
+
+    /// Returns a new `NonNativeTarget` whose limbs are range-checked and whose value is checked
+    /// to be canonical.
+    pub fn add_virtual_nonnative_target<FF: PrimeField>(&mut self) -> NonNativeTarget<FF> {
+        let value = self.add_virtual_biguint_target(num_nonnative_limbs::<FF>());
+        let modulus = self.nonnative_modulus::<FF>();
+        self.assert_lt_biguint(&value, &modulus);
+        self.nonnative_from_canonical(value)
+    }
+
+    pub fn connect_nonnative<FF: PrimeField>(
+        &mut self,
+        a: &NonNativeTarget<FF>,
+        b: &NonNativeTarget<FF>,
+    ) {
+        self.connect_biguint(&a.value, &b.value);
+    }
+
+    pub fn is_equal_nonnative<FF: PrimeField>(
+        &mut self,
+        a: &NonNativeTarget<FF>,
+        b: &NonNativeTarget<FF>,
+    ) -> BoolTarget {
+        self.is_equal_biguint(&a.value, &b.value)
+    }
+
+    /// Returns `b ? x : y`.
+    pub fn select_nonnative<FF: PrimeField>(
+        &mut self,
+        b: BoolTarget,
+        x: &NonNativeTarget<FF>,
+        y: &NonNativeTarget<FF>,
+    ) -> NonNativeTarget<FF> {
+        let limbs = x
+            .value
+            .limbs
+            .iter()
+            .zip(&y.value.limbs)
+            .map(|(&x_limb, &y_limb)| self.select(b, x_limb, y_limb))
+            .collect();
+        self.nonnative_from_canonical(BigUintTarget { limbs })
+    }
+
+    pub fn add_nonnative<FF: PrimeField>(
+        &mut self,
+        a: &NonNativeTarget<FF>,
+        b: &NonNativeTarget<FF>,
+    ) -> NonNativeTarget<FF> {
+        self.add_many_nonnative(&[a.clone(), b.clone()])
+    }
+
+    pub fn add_many_nonnative<FF: PrimeField>(
+        &mut self,
+        terms: &[NonNativeTarget<FF>],
+    ) -> NonNativeTarget<FF> {
+        // The sum is less than `terms.len() * p`, so the quotient fits in a single limb.
+        assert!(
+            terms.len() < 1 << 16,
+            "Too many terms for add_many_nonnative"
+        );
+        let sum = terms.iter().skip(1).fold(terms[0].value.clone(), |acc, t| {
+            self.add_biguint(&acc, &t.value)
+        });
+        self.reduce_nonnative(&sum, 1)
+    }
+
+    pub fn sub_nonnative<FF: PrimeField>(
+        &mut self,
+        a: &NonNativeTarget<FF>,
+        b: &NonNativeTarget<FF>,
+    ) -> NonNativeTarget<FF> {
+        // Since `b` is canonical, `a + (p - b)` is nonnegative and less than `2p`.
+        let modulus = self.nonnative_modulus::<FF>();
+        let neg_b = self.sub_biguint(&modulus, &b.value);
+        let sum = self.add_biguint(&a.value, &neg_b);
+        self.reduce_nonnative(&sum, 1)
+    }
+
+    pub fn neg_nonnative<FF: PrimeField>(
+        &mut self,
+        x: &NonNativeTarget<FF>,
+    ) -> NonNativeTarget<FF> {
+        let zero = self.zero_nonnative();
+        self.sub_nonnative(&zero, x)
+    }
+
+    pub fn mul_nonnative<FF: PrimeField>(
+        &mut self,
+        a: &NonNativeTarget<FF>,
+        b: &NonNativeTarget<FF>,
+    ) -> NonNativeTarget<FF> {
+        let product = self.mul_biguint(&a.value, &b.value);
+        self.reduce_nonnative(&product, num_nonnative_limbs::<FF>())
+    }
+
+    pub fn mul_many_nonnative<FF: PrimeField>(
+        &mut self,
+        terms: &[NonNativeTarget<FF>],
+    ) -> NonNativeTarget<FF> {
+        terms
+            .iter()
+            .skip(1)
+            .fold(terms[0].clone(), |acc, t| self.mul_nonnative(&acc, t))
+    }
+
+    /// Computes `sum_i c_i * x_i` for small signed coefficients `c_i` and arbitrary, possibly
+    /// unreduced, integers `x_i`, with a single reduction at the end.
+    pub fn reduce_signed_sum_nonnative<FF: PrimeField>(
+        &mut self,
+        terms: &[(i64, &BigUintTarget)],
+    ) -> NonNativeTarget<FF> {
+        let modulus = FF::order();
+        let (positive, positive_bound) = self.unsigned_sum_biguint(terms, false);
+        let (negative, negative_bound) = self.unsigned_sum_biguint(terms, true);
+        if negative_bound.is_zero() {
+            let num_quotient_limbs = num_biguint_limbs((positive_bound / modulus).bits() as usize);
+            return self.reduce_nonnative(&positive, num_quotient_limbs.max(1));
+        }
+
+        // Add a multiple of the modulus exceeding any possible value of the negative part.
+        let offset = (negative_bound / &modulus + 1u32) * &modulus;
+        let offset_target = self.constant_biguint(&offset);
+        let shifted = self.add_biguint(&positive, &offset_target);
+        let difference = self.sub_biguint(&shifted, &negative);
+        let num_quotient_limbs =
+            num_biguint_limbs(((positive_bound + offset) / modulus).bits() as usize);
+        self.reduce_nonnative(&difference, num_quotient_limbs.max(1))
+    }
+
+    /// Returns `sum_i |c_i| * x_i` over the terms whose coefficients have the given sign, along
+    /// with an upper bound on its value.
+    fn unsigned_sum_biguint(
+        &mut self,
+        terms: &[(i64, &BigUintTarget)],
+        negative: bool,
+    ) -> (BigUintTarget, BigUint) {
+        let terms: Vec<_> = terms
+            .iter()
+            .filter(|&&(c, _)| c != 0 && (c < 0) == negative)
+            .map(|&(c, x)| (c.unsigned_abs(), x))
+            .collect();
+        let bound: BigUint = terms
+            .iter()
+            .map(|&(c, x)| (BigUint::one() << (BIGUINT_LIMB_BITS * x.num_limbs())) * c)
+            .sum();
+        let coefficient_sum: u64 = terms.iter().map(|&(c, _)| c).sum();
+        assert!(
+            coefficient_sum < 1 << 31,
+            "Coefficients are too large for reduce_signed_sum_nonnative"
+        );
+
+        let num_columns = num_biguint_limbs(bound.bits() as usize).max(1);
+        let mut columns = vec![self.zero(); num_columns];
+        for (c, x) in terms {
+            let c = F::from_canonical_u64(c);
+            for (i, &limb) in x.limbs.iter().enumerate() {
+                columns[i] = self.mul_const_add(c, limb, columns[i]);
+            }
+        }
+        let sum = self.normalize_biguint_columns(columns);
+        (sum, bound)
+    }
+
+    /// Computes `sum_i c_i * x_i` for small signed coefficients `c_i`.
+    pub fn linear_combination_nonnative<FF: PrimeField>(
+        &mut self,
+        terms: &[(i64, &NonNativeTarget<FF>)],
+    ) -> NonNativeTarget<FF> {
+        let terms: Vec<_> = terms.iter().map(|&(c, x)| (c, &x.value)).collect();
+        self.reduce_signed_sum_nonnative(&terms)
+    }
+
+    /// Computes `sum_i c_i * a_i * b_i` for small signed coefficients `c_i`, with a single
+    /// reduction at the end.
+    pub fn inner_product_nonnative<FF: PrimeField>(
+        &mut self,
+        terms: &[(i64, &NonNativeTarget<FF>, &NonNativeTarget<FF>)],
+    ) -> NonNativeTarget<FF> {
+        let mut signed_sums = Vec::new();
+        for (sign, negative) in [(1, false), (-1, true)] {
+            let products: Vec<_> = terms
+                .iter()
+                .filter(|&&(c, _, _)| c != 0 && (c < 0) == negative)
+                .map(|&(c, a, b)| (c.unsigned_abs(), &a.value, &b.value))
+                .collect();
+            if !products.is_empty() {
+                signed_sums.push((sign, self.inner_product_biguint(&products)));
+            }
+        }
+        let terms: Vec<_> = signed_sums.iter().map(|(c, x)| (*c, x)).collect();
+        self.reduce_signed_sum_nonnative(&terms)
+    }
+
+    pub fn mul_nonnative_by_bool<FF: PrimeField>(
+        &mut self,
+        a: &NonNativeTarget<FF>,
+        b: BoolTarget,
+    ) -> NonNativeTarget<FF> {
+        let value = self.mul_biguint_by_bool(&a.value, b);
+        self.nonnative_from_canonical(value)
+    }
+
+    /// Returns the inverse of `x`, which must be nonzero.
+    pub fn inv_nonnative<FF: PrimeField>(
+        &mut self,
+        x: &NonNativeTarget<FF>,
+    ) -> NonNativeTarget<FF> {
+        let inv = self.add_virtual_nonnative_target::<FF>();
+        let modulus = self.nonnative_modulus::<FF>();
+        self.add_simple_generator(NonNativeInverseGenerator {
+            x: x.value.clone(),
+            modulus,
+            inv: inv.value.clone(),
+        });
+
+        let product = self.mul_nonnative(x, &inv);
+        let one = self.constant_nonnative(FF::ONE);
+        self.connect_nonnative(&product, &one);
+
+        inv
+    }
+
+    /// Returns `a / b`, where `b` must be nonzero.
+    pub fn div_nonnative<FF: PrimeField>(
+        &mut self,
+        a: &NonNativeTarget<FF>,
+        b: &NonNativeTarget<FF>,
+    ) -> NonNativeTarget<FF> {
+        let b_inv = self.inv_nonnative(b);
+        self.mul_nonnative(a, &b_inv)
+    }
+
+    /// Splits `x` into little-endian bits.
+    pub fn split_nonnative_to_bits<FF: PrimeField>(
+        &mut self,
+        x: &NonNativeTarget<FF>,
+    ) -> Vec<BoolTarget> {
+        x.value
+            .limbs
+            .iter()
+            .flat_map(|&limb| self.split_le(limb, BIGUINT_LIMB_BITS))
+            .collect()
+    }
+
+    /// Splits `x` into little-endian 4-bit windows.
+    pub fn split_nonnative_to_4_bit_limbs<FF: PrimeField>(
+        &mut self,
+        x: &NonNativeTarget<FF>,
+    ) -> Vec<Target> {
+        let bits = self.split_nonnative_to_bits(x);
+        bits.chunks(4)
+            .map(|chunk| self.le_sum(chunk.iter()))
+            .collect()
+    }
+}
+
+/// Computes the inverse of `x` modulo the prime `modulus`.
+#[derive(Debug, Default)]
+pub struct NonNativeInverseGenerator {
+    x: BigUintTarget,
+    modulus: BigUintTarget,
+    inv: BigUintTarget,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D>
+    for NonNativeInverseGenerator
+{
+    fn id(&self) -> String {
+        "NonNativeInverseGenerator".to_string()
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        self.x
+            .limbs
+            .iter()
+            .chain(&self.modulus.limbs)
+            .copied()
+            .collect()
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let x = witness.get_biguint_target(&self.x);
+        let modulus = witness.get_biguint_target(&self.modulus);
+        let exponent = &modulus - BigUint::one() - BigUint::one();
+        let inv = x.modpow(&exponent, &modulus);
+
+        out_buffer.set_biguint_target(&self.inv, &inv);
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_target_vec(&self.x.limbs)?;
+        dst.write_target_vec(&self.modulus.limbs)?;
+        dst.write_target_vec(&self.inv.limbs)
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let x = BigUintTarget {
+            limbs: src.read_target_vec()?,
+        };
+        let modulus = BigUintTarget {
+            limbs: src.read_target_vec()?,
+        };
+        let inv = BigUintTarget {
+            limbs: src.read_target_vec()?,
+        };
+        Ok(Self { x, modulus, inv })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::field::secp256k1_base::Secp256K1Base;
+    use crate::field::types::{Field, Sample};
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::verifier::verify;
+
+    #[test]
+    fn test_nonnative_arithmetic() -> Result<()> {
+        type FF = Secp256K1Base;
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let x_ff = FF::rand();
+        let y_ff = FF::rand();
+
+        let config = CircuitConfig::standard_ecc_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let x = builder.add_virtual_nonnative_target::<FF>();
+        let y = builder.add_virtual_nonnative_target::<FF>();
+        pw.set_nonnative_target(&x, x_ff);
+        pw.set_nonnative_target(&y, y_ff);
+
+        let sum = builder.add_nonnative(&x, &y);
+        let diff = builder.sub_nonnative(&x, &y);
+        let product = builder.mul_nonnative(&x, &y);
+        let neg = builder.neg_nonnative(&x);
+        let inv = builder.inv_nonnative(&x);
+
+        let expected_sum = builder.constant_nonnative(x_ff + y_ff);
+        let expected_diff = builder.constant_nonnative(x_ff - y_ff);
+        let expected_product = builder.constant_nonnative(x_ff * y_ff);
+        let expected_neg = builder.constant_nonnative(-x_ff);
+        let expected_inv = builder.constant_nonnative(x_ff.inverse());
+        builder.connect_nonnative(&sum, &expected_sum);
+        builder.connect_nonnative(&diff, &expected_diff);
+        builder.connect_nonnative(&product, &expected_product);
+        builder.connect_nonnative(&neg, &expected_neg);
+        builder.connect_nonnative(&inv, &expected_inv);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_nonnative_non_canonical() {
+        type FF = Secp256K1Base;
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let config = CircuitConfig::standard_ecc_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let x = builder.add_virtual_nonnative_target::<FF>();
+        pw.set_biguint_target(x.value(), &FF::order());
+
+        let data = builder.build::<C>();
+        data.prove(pw).unwrap();
+    }
+}
@@ -0,0 +1,321 @@
+//! SHA-256, as specified in FIPS 180-4, built on the lookup-based `u32` gadgets.
+
+use alloc::vec::Vec;
+
+use crate::field::extension::Extendable;
+use crate::gadgets::uint32::U32Target;
+use crate::hash::hash_types::RichField;
+use crate::iop::target::{BoolTarget, Target};
+use crate::plonk::circuit_builder::CircuitBuilder;
+
+/// The number of 32-bit words in a SHA-256 state or digest.
+pub const SHA256_STATE_WORDS: usize = 8;
+
+/// The number of bytes in a SHA-256 message block.
+pub const SHA256_BLOCK_BYTES: usize = 64;
+
+const SHA256_IV: [u32; SHA256_STATE_WORDS] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+#[rustfmt::skip]
+const SHA256_ROUND_CONSTANTS: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
+    /// Computes the SHA-256 digest of `message`, whose targets are bytes. The bytes are
+    /// range-checked.
+    ///
+    /// The digest is returned as eight 32-bit words; its byte encoding is their big-endian
+    /// concatenation.
+    pub fn sha256(&mut self, message: &[Target]) -> [Target; SHA256_STATE_WORDS] {
+        for &byte in message {
+            self.range_check_u8(byte);
+        }
+
+        let bit_len = message.len() as u64 * 8;
+        let num_blocks = (message.len() + 8) / SHA256_BLOCK_BYTES + 1;
+        let mut padded = message.to_vec();
+        padded.push(self.constant(F::from_canonical_u8(0x80)));
+        padded.resize(num_blocks * SHA256_BLOCK_BYTES - 8, self.zero());
+        for byte in bit_len.to_be_bytes() {
+            padded.push(self.constant(F::from_canonical_u8(byte)));
+        }
+
+        let mut state = SHA256_IV.map(|h| self.constant_u32(h));
+        for block in padded.chunks_exact(SHA256_BLOCK_BYTES) {
+            let block = self.sha256_block_words(block);
+            state = self.sha256_compress(state, block);
+        }
+
+        state.map(|word| self.u32_to_target(word))
+    }
+
+    /// Computes the SHA-256 digest of the first `length` bytes of `message`, whose targets are
+    /// bytes. All bytes of `message` are range-checked, and `length` is checked to be at most
+    /// `message.len()`.
+    ///
+    /// The circuit always compresses as many blocks as the longest possible message needs, and
+    /// selects the state after the block holding the end of the padded message.
+    pub fn sha256_variable_length(
+        &mut self,
+        message: &[Target],
+        length: Target,
+    ) -> [Target; SHA256_STATE_WORDS] {
+        let max_len = message.len();
+        assert!(
+            max_len < 1 << 29,
+            "The bit length of the message must fit in 32 bits"
+        );
+        for &byte in message {
+            self.range_check_u8(byte);
+        }
+
+        // `is_length[i]` is set iff `length == i`. Exactly one of them must be set, which also
+        // checks that `length <= max_len`.
+        let is_length: Vec<BoolTarget> = (0..=max_len)
+            .map(|i| {
+                let i = self.constant(F::from_canonical_usize(i));
+                self.is_equal(length, i)
+            })
+            .collect();
+        let num_set = self.add_many(is_length.iter().map(|b| b.target));
+        self.assert_one(num_set);
+
+        // The message ends in block `b` iff `64 b - 8 <= length < 64 b + 56`.
+        let num_blocks = (max_len + 8) / SHA256_BLOCK_BYTES + 1;
+        let is_last_block: Vec<Target> = (0..num_blocks)
+            .map(|b| {
+                let start = (b * SHA256_BLOCK_BYTES).saturating_sub(8);
+                let end = (b * SHA256_BLOCK_BYTES + SHA256_BLOCK_BYTES - 8).min(max_len + 1);
+                self.add_many(is_length[start..end].iter().map(|b| b.target))
+            })
+            .collect();
+
+        let bit_len = self.mul_const(F::from_canonical_u32(8), length);
+        let bit_len = self.split_u32(bit_len);
+
+        // Each padded byte is the message byte if `i < length`, `0x80` if `i == length`, a byte of
+        // the bit length if it is in the last 4 bytes of the last block, and zero otherwise. These
+        // cases are disjoint, so the padded bytes are all in range.
+        let zero = self.zero();
+        let mut is_before_length = self.one();
+        let padded: Vec<Target> = (0..num_blocks * SHA256_BLOCK_BYTES)
+            .map(|i| {
+                let mut byte = zero;
+                if i < max_len {
+                    is_before_length = self.sub(is_before_length, is_length[i].target);
+                    byte = self.mul(is_before_length, message[i]);
+                }
+                if i <= max_len {
+                    byte =
+                        self.mul_const_add(F::from_canonical_u8(0x80), is_length[i].target, byte);
+                }
+                let (block, offset) = (i / SHA256_BLOCK_BYTES, i % SHA256_BLOCK_BYTES);
+                if offset >= SHA256_BLOCK_BYTES - 4 {
+                    let bit_len_byte = bit_len.limbs[SHA256_BLOCK_BYTES - 1 - offset];
+                    byte = self.mul_add(is_last_block[block], bit_len_byte, byte);
+                }
+                byte
+            })
+            .collect();
+
+        let mut state = SHA256_IV.map(|h| self.constant_u32(h));
+        let mut digest = [zero; SHA256_STATE_WORDS];
+        for (block, &is_last) in padded.chunks_exact(SHA256_BLOCK_BYTES).zip(&is_last_block) {
+            let block = self.sha256_block_words(block);
+            state = self.sha256_compress(state, block);
+            for i in 0..SHA256_STATE_WORDS {
+                let word = self.u32_to_target(state[i]);
+                digest[i] = self.mul_add(is_last, word, digest[i]);
+            }
+        }
+
+        digest
+    }
+
+    /// Splits a block of bytes, which must already be known to be in range, into big-endian words.
+    fn sha256_block_words(&mut self, block: &[Target]) -> [U32Target; 16] {
+        debug_assert_eq!(block.len(), SHA256_BLOCK_BYTES);
+        core::array::from_fn(|i| {
+            let mut limbs: [Target; 4] = block[4 * i..4 * i + 4].try_into().unwrap();
+            limbs.reverse();
+            U32Target::new_unsafe(limbs)
+        })
+    }
+
+    /// Applies the SHA-256 compression function to `state` and a message block.
+    pub fn sha256_compress(
+        &mut self,
+        state: [U32Target; SHA256_STATE_WORDS],
+        block: [U32Target; 16],
+    ) -> [U32Target; SHA256_STATE_WORDS] {
+        let mut w = block.to_vec();
+        for t in 16..64 {
+            let s0 = self.sha256_small_sigma(w[t - 15], 7, 18, 3);
+            let s1 = self.sha256_small_sigma(w[t - 2], 17, 19, 10);
+            w.push(self.add_many_u32(&[w[t - 16], s0, w[t - 7], s1]));
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = state;
+        for t in 0..64 {
+            let s1 = self.sha256_big_sigma(e, 6, 11, 25);
+            let ch = self.sha256_ch(e, f, g);
+            let k = self.constant_u32(SHA256_ROUND_CONSTANTS[t]);
+            let s0 = self.sha256_big_sigma(a, 2, 13, 22);
+            let maj = self.sha256_maj(a, b, c);
+
+            let new_e = self.add_many_u32(&[d, h, s1, ch, k, w[t]]);
+            let new_a = self.add_many_u32(&[h, s1, ch, k, w[t], s0, maj]);
+
+            h = g;
+            g = f;
+            f = e;
+            e = new_e;
+            d = c;
+            c = b;
+            b = a;
+            a = new_a;
+        }
+
+        let mut output = state;
+        for (out, x) in output.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+            *out = self.add_u32(*out, x);
+        }
+        output
+    }
+
+    /// `rotr(x, r0) ^ rotr(x, r1) ^ rotr(x, r2)`
+    fn sha256_big_sigma(&mut self, x: U32Target, r0: usize, r1: usize, r2: usize) -> U32Target {
+        let x0 = self.rotate_right_u32(x, r0);
+        let x1 = self.rotate_right_u32(x, r1);
+        let x2 = self.rotate_right_u32(x, r2);
+        let x01 = self.xor_u32(x0, x1);
+        self.xor_u32(x01, x2)
+    }
+
+    /// `rotr(x, r0) ^ rotr(x, r1) ^ (x >> s)`
+    fn sha256_small_sigma(&mut self, x: U32Target, r0: usize, r1: usize, s: usize) -> U32Target {
+        let x0 = self.rotate_right_u32(x, r0);
+        let x1 = self.rotate_right_u32(x, r1);
+        let x2 = self.shift_right_u32(x, s);
+        let x01 = self.xor_u32(x0, x1);
+        self.xor_u32(x01, x2)
+    }
+
+    /// Returns the sum of `x` and `y` limb by limb, which is their bitwise OR (and XOR) if they
+    /// have no bits in common.
+    fn sha256_disjoint_or(&mut self, x: U32Target, y: U32Target) -> U32Target {
+        let limbs = core::array::from_fn(|i| self.add(x.limbs[i], y.limbs[i]));
+        U32Target::new_unsafe(limbs)
+    }
+
+    /// `(e & f) ^ (!e & g)`
+    fn sha256_ch(&mut self, e: U32Target, f: U32Target, g: U32Target) -> U32Target {
+        let e_and_f = self.and_u32(e, f);
+        let not_e = self.not_u32(e);
+        let not_e_and_g = self.and_u32(not_e, g);
+        self.sha256_disjoint_or(e_and_f, not_e_and_g)
+    }
+
+    /// `(a & b) ^ (a & c) ^ (b & c)`, computed as `(a & b) | (c & (a ^ b))`.
+    fn sha256_maj(&mut self, a: U32Target, b: U32Target, c: U32Target) -> U32Target {
+        let a_and_b = self.and_u32(a, b);
+        let a_xor_b = self.xor_u32(a, b);
+        let c_and_a_xor_b = self.and_u32(c, a_xor_b);
+        self.sha256_disjoint_or(a_and_b, c_and_a_xor_b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use rand::rngs::OsRng;
+    use rand::Rng;
+    use sha2::{Digest, Sha256};
+
+    use super::*;
+    use crate::field::types::Field;
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::verifier::verify;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    fn expected_digest(message: &[u8]) -> [F; SHA256_STATE_WORDS] {
+        let digest = Sha256::digest(message);
+        core::array::from_fn(|i| {
+            let word = u32::from_be_bytes(digest[4 * i..4 * i + 4].try_into().unwrap());
+            F::from_canonical_u32(word)
+        })
+    }
+
+    #[test]
+    fn test_sha256() -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let mut pw = PartialWitness::new();
+
+        // Lengths around the padding boundaries, up to two blocks.
+        for len in [0, 55, 56, 100] {
+            let message: Vec<u8> = (0..len).map(|_| OsRng.gen()).collect();
+            let message_targets = builder.add_virtual_targets(len);
+            for (&t, &byte) in message_targets.iter().zip(&message) {
+                pw.set_target(t, F::from_canonical_u8(byte));
+            }
+
+            let digest = builder.sha256(&message_targets);
+            for (&d, expected) in digest.iter().zip(expected_digest(&message)) {
+                let expected = builder.constant(expected);
+                builder.connect(d, expected);
+            }
+        }
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    fn test_sha256_variable_length() -> Result<()> {
+        const MAX_LEN: usize = 70;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let mut pw = PartialWitness::new();
+
+        let message: Vec<u8> = (0..MAX_LEN).map(|_| OsRng.gen()).collect();
+        let message_targets = builder.add_virtual_targets(MAX_LEN);
+        for (&t, &byte) in message_targets.iter().zip(&message) {
+            pw.set_target(t, F::from_canonical_u8(byte));
+        }
+
+        for len in [0, 55, 56, MAX_LEN] {
+            let length = builder.add_virtual_target();
+            pw.set_target(length, F::from_canonical_usize(len));
+
+            let digest = builder.sha256_variable_length(&message_targets, length);
+            for (&d, expected) in digest.iter().zip(expected_digest(&message[..len])) {
+                let expected = builder.constant(expected);
+                builder.connect(d, expected);
+            }
+        }
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+
+        verify(proof, &data.verifier_only, &data.common)
+    }
+}
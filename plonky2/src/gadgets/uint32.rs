@@ -0,0 +1,399 @@
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::field::extension::Extendable;
+use crate::hash::hash_types::RichField;
+use crate::iop::generator::{GeneratedValues, SimpleGenerator};
+use crate::iop::target::Target;
+use crate::iop::witness::{PartitionWitness, Witness, WitnessWrite};
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::circuit_data::CommonCircuitData;
+use crate::util::serialization::{Buffer, IoResult, Read, Write};
+
+/// The number of byte limbs of a `U32Target`.
+pub const NUM_U32_LIMBS: usize = 4;
+
+/// A 32-bit unsigned integer, represented as four little-endian byte limbs.
+///
+/// All bitwise operations are performed limb by limb through lookup tables, so each limb must be
+/// known to lie in `[0, 256)`. Every `U32Target` returned by the builder satisfies this.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+#[allow(clippy::manual_non_exhaustive)]
+pub struct U32Target {
+    pub limbs: [Target; NUM_U32_LIMBS],
+    /// This private field is here to force all instantiations to go through `new_unsafe`.
+    _private: (),
+}
+
+impl U32Target {
+    pub fn new_unsafe(limbs: [Target; NUM_U32_LIMBS]) -> Self {
+        Self {
+            limbs,
+            _private: (),
+        }
+    }
+}
+
+/// The lookup tables used by the `u32` gadgets. They are only added to the circuit when first used.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub(crate) enum U32Lut {
+    /// The identity on `[0, 256)`, used to range-check bytes.
+    Byte,
+    /// Maps `(a << 8) | b` to `a & b`, for bytes `a` and `b`.
+    And,
+    /// Maps a byte to its `k` low bits, for `0 < k < 8`.
+    LowBits(usize),
+    /// The identity on `[0, 2^16)`, used to range-check the limbs of `BigUintTarget`s.
+    U16,
+}
+
+impl U32Lut {
+    fn table(&self) -> (Vec<u16>, Vec<u16>) {
+        match *self {
+            U32Lut::Byte => {
+                let inputs: Vec<u16> = (0..256).collect();
+                (inputs.clone(), inputs)
+            }
+            U32Lut::And => {
+                let inputs: Vec<u16> = (0..=u16::MAX).collect();
+                let outputs = inputs.iter().map(|&x| (x >> 8) & (x & 0xff)).collect();
+                (inputs, outputs)
+            }
+            U32Lut::LowBits(k) => {
+                debug_assert!(0 < k && k < 8);
+                let inputs: Vec<u16> = (0..256).collect();
+                let outputs = inputs.iter().map(|&x| x & ((1 << k) - 1)).collect();
+                (inputs, outputs)
+            }
+            U32Lut::U16 => {
+                let inputs: Vec<u16> = (0..=u16::MAX).collect();
+                (inputs.clone(), inputs)
+            }
+        }
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
+    /// Returns the index of the given `u32` lookup table, adding it to the circuit if needed.
+    fn u32_lut(&mut self, lut: U32Lut) -> usize {
+        if let Some(&index) = self.u32_luts.get(&lut) {
+            return index;
+        }
+        let (inputs, outputs) = lut.table();
+        let index = self.add_lookup_table_from_table(&inputs, &outputs);
+        self.u32_luts.insert(lut, index);
+        index
+    }
+
+    /// Checks that `x < 2^8` with a lookup.
+    pub fn range_check_u8(&mut self, x: Target) {
+        let lut = self.u32_lut(U32Lut::Byte);
+        self.add_lookup_from_index(x, lut);
+    }
+
+    /// Checks that `x < 2^16` with a lookup.
+    pub fn range_check_u16(&mut self, x: Target) {
+        let lut = self.u32_lut(U32Lut::U16);
+        self.add_lookup_from_index(x, lut);
+    }
+
+    /// Splits a byte into its high and low nibbles, checking that it is less than `2^8`.
+    pub fn split_nibbles(&mut self, x: Target) -> (Target, Target) {
+        let lut = self.u32_lut(U32Lut::LowBits(4));
+        let low = self.add_lookup_from_index(x, lut);
+        let high = self.sub(x, low);
+        let high = self.mul_const(F::from_canonical_u8(16).inverse(), high);
+        (high, low)
+    }
+
+    /// Returns a new `U32Target` whose limbs are range-checked.
+    pub fn add_virtual_u32_target(&mut self) -> U32Target {
+        let limbs = self.add_virtual_target_arr::<NUM_U32_LIMBS>();
+        for &limb in &limbs {
+            self.range_check_u8(limb);
+        }
+        U32Target::new_unsafe(limbs)
+    }
+
+    pub fn constant_u32(&mut self, c: u32) -> U32Target {
+        let limbs = c
+            .to_le_bytes()
+            .map(|byte| self.constant(F::from_canonical_u8(byte)));
+        U32Target::new_unsafe(limbs)
+    }
+
+    /// Builds a `U32Target` from its little-endian bytes, which are range-checked.
+    pub fn u32_from_le_bytes(&mut self, bytes: [Target; NUM_U32_LIMBS]) -> U32Target {
+        for &byte in &bytes {
+            self.range_check_u8(byte);
+        }
+        U32Target::new_unsafe(bytes)
+    }
+
+    /// Builds a `U32Target` from its big-endian bytes, which are range-checked.
+    pub fn u32_from_be_bytes(&mut self, mut bytes: [Target; NUM_U32_LIMBS]) -> U32Target {
+        bytes.reverse();
+        self.u32_from_le_bytes(bytes)
+    }
+
+    /// Returns the integer value of `x` as a single target.
+    pub fn u32_to_target(&mut self, x: U32Target) -> Target {
+        let base = F::from_canonical_u32(1 << 8);
+        x.limbs.iter().rev().fold(self.zero(), |acc, &limb| {
+            self.mul_const_add(base, acc, limb)
+        })
+    }
+
+    /// Splits `x` into a `U32Target`, checking that `x < 2^32`.
+    pub fn split_u32(&mut self, x: Target) -> U32Target {
+        let limbs = self.split_bytes(x, NUM_U32_LIMBS);
+        U32Target::new_unsafe(limbs.try_into().unwrap())
+    }
+
+    /// Splits `x` into `num_limbs` little-endian bytes, checking that `x < 2^(8 * num_limbs)`.
+    fn split_bytes(&mut self, x: Target, num_limbs: usize) -> Vec<Target> {
+        let limbs = self.add_virtual_targets(num_limbs);
+        self.add_simple_generator(ByteDecompositionGenerator {
+            integer: x,
+            limbs: limbs.clone(),
+        });
+        for &limb in &limbs {
+            self.range_check_u8(limb);
+        }
+
+        let base = F::from_canonical_u32(1 << 8);
+        let sum = limbs.iter().rev().fold(self.zero(), |acc, &limb| {
+            self.mul_const_add(base, acc, limb)
+        });
+        self.connect(x, sum);
+
+        limbs
+    }
+
+    pub fn connect_u32(&mut self, x: U32Target, y: U32Target) {
+        for i in 0..NUM_U32_LIMBS {
+            self.connect(x.limbs[i], y.limbs[i]);
+        }
+    }
+
+    fn and_u8(&mut self, x: Target, y: Target) -> Target {
+        let lut = self.u32_lut(U32Lut::And);
+        let input = self.mul_const_add(F::from_canonical_u32(1 << 8), x, y);
+        self.add_lookup_from_index(input, lut)
+    }
+
+    fn xor_u8(&mut self, x: Target, y: Target) -> Target {
+        // x ^ y = x + y - 2 (x & y)
+        let and = self.and_u8(x, y);
+        let sum = self.add(x, y);
+        self.mul_const_add(-F::TWO, and, sum)
+    }
+
+    pub fn and_u32(&mut self, x: U32Target, y: U32Target) -> U32Target {
+        let limbs = core::array::from_fn(|i| self.and_u8(x.limbs[i], y.limbs[i]));
+        U32Target::new_unsafe(limbs)
+    }
+
+    pub fn xor_u32(&mut self, x: U32Target, y: U32Target) -> U32Target {
+        let limbs = core::array::from_fn(|i| self.xor_u8(x.limbs[i], y.limbs[i]));
+        U32Target::new_unsafe(limbs)
+    }
+
+    pub fn not_u32(&mut self, x: U32Target) -> U32Target {
+        let max = self.constant(F::from_canonical_u8(u8::MAX));
+        let limbs = x.limbs.map(|limb| self.sub(max, limb));
+        U32Target::new_unsafe(limbs)
+    }
+
+    /// Shifts the bits of `x` right by `n`, where the `n` bits shifted in on the left are
+    /// given by `fill(i)` for the `i`th byte limb past the end.
+    fn shift_right_u32_with_fill(
+        &mut self,
+        x: U32Target,
+        n: usize,
+        fill: impl Fn(usize) -> Option<Target>,
+    ) -> U32Target {
+        debug_assert!(n < 32);
+        let (limb_shift, bit_shift) = (n / 8, n % 8);
+        let zero = self.zero();
+        let shifted: [Target; NUM_U32_LIMBS + 1] = core::array::from_fn(|i| {
+            let j = i + limb_shift;
+            if j < NUM_U32_LIMBS {
+                x.limbs[j]
+            } else {
+                fill(j - NUM_U32_LIMBS).unwrap_or(zero)
+            }
+        });
+        if bit_shift == 0 {
+            return U32Target::new_unsafe(shifted[..NUM_U32_LIMBS].try_into().unwrap());
+        }
+
+        // Split each byte as `high * 2^k + low`, and reassemble each output byte from the high
+        // part of a byte and the low part of the next one.
+        let lut = self.u32_lut(U32Lut::LowBits(bit_shift));
+        let lows = shifted.map(|limb| self.add_lookup_from_index(limb, lut));
+        let inv_pow = F::from_canonical_u32(1 << bit_shift).inverse();
+        let pow = F::from_canonical_u32(1 << (8 - bit_shift));
+        let limbs = core::array::from_fn(|i| {
+            let high = self.sub(shifted[i], lows[i]);
+            let low_next = self.mul_const(pow, lows[i + 1]);
+            self.mul_const_add(inv_pow, high, low_next)
+        });
+        U32Target::new_unsafe(limbs)
+    }
+
+    /// Computes `x >> n`.
+    pub fn shift_right_u32(&mut self, x: U32Target, n: usize) -> U32Target {
+        self.shift_right_u32_with_fill(x, n, |_| None)
+    }
+
+    /// Computes `x.rotate_right(n)`.
+    pub fn rotate_right_u32(&mut self, x: U32Target, n: usize) -> U32Target {
+        let n = n % 32;
+        self.shift_right_u32_with_fill(x, n, |i| Some(x.limbs[i]))
+    }
+
+    /// Computes `x.rotate_left(n)`.
+    pub fn rotate_left_u32(&mut self, x: U32Target, n: usize) -> U32Target {
+        self.rotate_right_u32(x, 32 - n % 32)
+    }
+
+    /// Computes the sum of `terms` modulo `2^32`.
+    pub fn add_many_u32(&mut self, terms: &[U32Target]) -> U32Target {
+        // The carry is range-checked to a byte, so the sum must not reach 2^40.
+        assert!(
+            terms.len() <= 1 << 8,
+            "Too many terms for add_many_u32: {}",
+            terms.len()
+        );
+        let values: Vec<Target> = terms.iter().map(|&t| self.u32_to_target(t)).collect();
+        let sum = self.add_many(values);
+        let limbs = self.split_bytes(sum, NUM_U32_LIMBS + 1);
+        U32Target::new_unsafe(limbs[..NUM_U32_LIMBS].try_into().unwrap())
+    }
+
+    /// Computes `x + y` modulo `2^32`.
+    pub fn add_u32(&mut self, x: U32Target, y: U32Target) -> U32Target {
+        self.add_many_u32(&[x, y])
+    }
+}
+
+/// Decomposes `integer` into little-endian bytes.
+#[derive(Debug, Default)]
+pub struct ByteDecompositionGenerator {
+    integer: Target,
+    limbs: Vec<Target>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D>
+    for ByteDecompositionGenerator
+{
+    fn id(&self) -> String {
+        "ByteDecompositionGenerator".to_string()
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        vec![self.integer]
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let mut integer_value = witness.get_target(self.integer).to_canonical_u64();
+        for &limb in &self.limbs {
+            out_buffer.set_target(limb, F::from_canonical_u64(integer_value & 0xff));
+            integer_value >>= 8;
+        }
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_target(self.integer)?;
+        dst.write_target_vec(&self.limbs)
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let integer = src.read_target()?;
+        let limbs = src.read_target_vec()?;
+        Ok(Self { integer, limbs })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use rand::rngs::OsRng;
+    use rand::Rng;
+
+    use crate::field::types::Field;
+    use crate::iop::witness::PartialWitness;
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::verifier::verify;
+
+    #[test]
+    fn test_u32_ops() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let mut rng = OsRng;
+        let (a, b, c) = (rng.gen::<u32>(), rng.gen::<u32>(), rng.gen::<u32>());
+        let x = builder.constant_u32(a);
+        let y = builder.constant_u32(b);
+        let z = builder.constant_u32(c);
+
+        let and = builder.and_u32(x, y);
+        let xor = builder.xor_u32(x, y);
+        let not = builder.not_u32(x);
+        let sum = builder.add_many_u32(&[x, y, z]);
+        let expected_and = builder.constant_u32(a & b);
+        let expected_xor = builder.constant_u32(a ^ b);
+        let expected_not = builder.constant_u32(!a);
+        let expected_sum = builder.constant_u32(a.wrapping_add(b).wrapping_add(c));
+        builder.connect_u32(and, expected_and);
+        builder.connect_u32(xor, expected_xor);
+        builder.connect_u32(not, expected_not);
+        builder.connect_u32(sum, expected_sum);
+
+        for n in [0, 3, 7, 8, 13, 22, 31] {
+            let rotr = builder.rotate_right_u32(x, n);
+            let rotl = builder.rotate_left_u32(x, n);
+            let shr = builder.shift_right_u32(x, n);
+            let expected_rotr = builder.constant_u32(a.rotate_right(n as u32));
+            let expected_rotl = builder.constant_u32(a.rotate_left(n as u32));
+            let expected_shr = builder.constant_u32(a >> n);
+            builder.connect_u32(rotr, expected_rotr);
+            builder.connect_u32(rotl, expected_rotl);
+            builder.connect_u32(shr, expected_shr);
+        }
+
+        let value = builder.u32_to_target(x);
+        let expected_value = builder.constant(F::from_canonical_u32(a));
+        builder.connect(value, expected_value);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(PartialWitness::new())?;
+
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_split_u32_out_of_range() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let x = builder.constant(F::from_canonical_u64(1 << 32));
+        builder.split_u32(x);
+
+        let data = builder.build::<C>();
+        data.prove(PartialWitness::new()).unwrap();
+    }
+}
@@ -0,0 +1,241 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::field::extension::Extendable;
+use crate::field::packed::PackedField;
+use crate::field::types::Field;
+use crate::gates::gate::Gate;
+use crate::gates::packed_util::PackedEvaluableBase;
+use crate::gates::util::StridedConstraintConsumer;
+use crate::hash::hash_types::RichField;
+use crate::iop::ext_target::ExtensionTarget;
+use crate::iop::generator::{GeneratedValues, SimpleGenerator, WitnessGeneratorRef};
+use crate::iop::target::Target;
+use crate::iop::witness::{PartitionWitness, Witness, WitnessWrite};
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::circuit_data::{CircuitConfig, CommonCircuitData};
+use crate::plonk::vars::{
+    EvaluationTargets, EvaluationVars, EvaluationVarsBase, EvaluationVarsBaseBatch,
+    EvaluationVarsBasePacked,
+};
+use crate::util::serialization::{Buffer, IoResult, Read, Write};
+
+/// A gate which computes the Keccak `chi` step on single bits, i.e. `a ^ (!b & c)`. If the config
+/// supports enough routed wires, it can support several such operations in one gate.
+///
+/// The inputs are assumed to be boolean, in which case the output is boolean too.
+#[derive(Debug, Clone)]
+pub struct KeccakChiGate {
+    /// Number of `chi` operations performed by a `chi` gate.
+    pub num_ops: usize,
+}
+
+impl KeccakChiGate {
+    pub fn new_from_config(config: &CircuitConfig) -> Self {
+        Self {
+            num_ops: Self::num_ops(config),
+        }
+    }
+
+    /// Determine the maximum number of operations that can fit in one gate for the given config.
+    pub(crate) fn num_ops(config: &CircuitConfig) -> usize {
+        let wires_per_op = 4;
+        config.num_routed_wires / wires_per_op
+    }
+
+    pub fn wire_ith_a(i: usize) -> usize {
+        4 * i
+    }
+    pub fn wire_ith_b(i: usize) -> usize {
+        4 * i + 1
+    }
+    pub fn wire_ith_c(i: usize) -> usize {
+        4 * i + 2
+    }
+    pub fn wire_ith_output(i: usize) -> usize {
+        4 * i + 3
+    }
+}
+
+/// `a ^ (!b & c)`, written as a polynomial in boolean `a`, `b` and `c`.
+fn chi<T: Field>(a: T, b: T, c: T) -> T {
+    let not_b_and_c = c - b * c;
+    a + not_b_and_c - (a + a) * not_b_and_c
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for KeccakChiGate {
+    fn id(&self) -> String {
+        format!("{self:?}")
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_usize(self.num_ops)
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let num_ops = src.read_usize()?;
+        Ok(Self { num_ops })
+    }
+
+    fn eval_unfiltered(&self, vars: EvaluationVars<F, D>) -> Vec<F::Extension> {
+        let mut constraints = Vec::with_capacity(self.num_ops);
+        for i in 0..self.num_ops {
+            let a = vars.local_wires[Self::wire_ith_a(i)];
+            let b = vars.local_wires[Self::wire_ith_b(i)];
+            let c = vars.local_wires[Self::wire_ith_c(i)];
+            let output = vars.local_wires[Self::wire_ith_output(i)];
+
+            constraints.push(output - chi(a, b, c));
+        }
+
+        constraints
+    }
+
+    fn eval_unfiltered_base_one(
+        &self,
+        _vars: EvaluationVarsBase<F>,
+        _yield_constr: StridedConstraintConsumer<F>,
+    ) {
+        panic!("use eval_unfiltered_base_packed instead");
+    }
+
+    fn eval_unfiltered_base_batch(&self, vars_base: EvaluationVarsBaseBatch<F>) -> Vec<F> {
+        self.eval_unfiltered_base_batch_packed(vars_base)
+    }
+
+    fn eval_unfiltered_circuit(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        vars: EvaluationTargets<D>,
+    ) -> Vec<ExtensionTarget<D>> {
+        let mut constraints = Vec::with_capacity(self.num_ops);
+        for i in 0..self.num_ops {
+            let a = vars.local_wires[Self::wire_ith_a(i)];
+            let b = vars.local_wires[Self::wire_ith_b(i)];
+            let c = vars.local_wires[Self::wire_ith_c(i)];
+            let output = vars.local_wires[Self::wire_ith_output(i)];
+
+            let b_and_c = builder.mul_extension(b, c);
+            let not_b_and_c = builder.sub_extension(c, b_and_c);
+            let sum = builder.add_extension(a, not_b_and_c);
+            let a_xor_not_b_and_c =
+                builder.arithmetic_extension(-F::TWO, F::ONE, a, not_b_and_c, sum);
+
+            constraints.push(builder.sub_extension(output, a_xor_not_b_and_c));
+        }
+
+        constraints
+    }
+
+    fn generators(&self, row: usize, _local_constants: &[F]) -> Vec<WitnessGeneratorRef<F, D>> {
+        (0..self.num_ops)
+            .map(|i| WitnessGeneratorRef::new(KeccakChiGenerator { row, i }.adapter()))
+            .collect()
+    }
+
+    fn num_wires(&self) -> usize {
+        self.num_ops * 4
+    }
+
+    fn num_constants(&self) -> usize {
+        0
+    }
+
+    fn degree(&self) -> usize {
+        3
+    }
+
+    fn num_constraints(&self) -> usize {
+        self.num_ops
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> PackedEvaluableBase<F, D> for KeccakChiGate {
+    fn eval_unfiltered_base_packed<P: PackedField<Scalar = F>>(
+        &self,
+        vars: EvaluationVarsBasePacked<P>,
+        mut yield_constr: StridedConstraintConsumer<P>,
+    ) {
+        for i in 0..self.num_ops {
+            let a = vars.local_wires[Self::wire_ith_a(i)];
+            let b = vars.local_wires[Self::wire_ith_b(i)];
+            let c = vars.local_wires[Self::wire_ith_c(i)];
+            let output = vars.local_wires[Self::wire_ith_output(i)];
+
+            let not_b_and_c = c - b * c;
+            yield_constr.one(output - (a + not_b_and_c - (a + a) * not_b_and_c));
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct KeccakChiGenerator {
+    row: usize,
+    i: usize,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D> for KeccakChiGenerator {
+    fn id(&self) -> String {
+        "KeccakChiGenerator".to_string()
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        [
+            KeccakChiGate::wire_ith_a(self.i),
+            KeccakChiGate::wire_ith_b(self.i),
+            KeccakChiGate::wire_ith_c(self.i),
+        ]
+        .iter()
+        .map(|&i| Target::wire(self.row, i))
+        .collect()
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let get_wire = |wire: usize| -> F { witness.get_target(Target::wire(self.row, wire)) };
+
+        let a = get_wire(KeccakChiGate::wire_ith_a(self.i));
+        let b = get_wire(KeccakChiGate::wire_ith_b(self.i));
+        let c = get_wire(KeccakChiGate::wire_ith_c(self.i));
+
+        let output_target = Target::wire(self.row, KeccakChiGate::wire_ith_output(self.i));
+        out_buffer.set_target(output_target, chi(a, b, c));
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_usize(self.row)?;
+        dst.write_usize(self.i)
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let row = src.read_usize()?;
+        let i = src.read_usize()?;
+        Ok(Self { row, i })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::field::goldilocks_field::GoldilocksField;
+    use crate::gates::gate_testing::{test_eval_fns, test_low_degree};
+    use crate::gates::keccak_chi::KeccakChiGate;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+
+    #[test]
+    fn low_degree() {
+        let gate = KeccakChiGate::new_from_config(&CircuitConfig::standard_recursion_config());
+        test_low_degree::<GoldilocksField, _, 4>(gate);
+    }
+
+    #[test]
+    fn eval_fns() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let gate = KeccakChiGate::new_from_config(&CircuitConfig::standard_recursion_config());
+        test_eval_fns::<F, C, _, D>(gate)
+    }
+}
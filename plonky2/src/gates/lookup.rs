@@ -1,12 +1,10 @@
 use alloc::string::{String, ToString};
+use alloc::sync::Arc;
 use alloc::vec::Vec;
 use alloc::{format, vec};
 use core::usize;
 
-use itertools::Itertools;
-use keccak_hash::keccak;
-
-use super::lookup_table::LookupTable;
+use super::lookup_table::WideLookupTable;
 use crate::field::extension::Extendable;
 use crate::field::packed::PackedField;
 use crate::gates::gate::Gate;
@@ -25,43 +23,43 @@ use crate::plonk::vars::{
 };
 use crate::util::serialization::{Buffer, IoResult, Read, Write};
 
-pub type Lookup = Vec<(Target, Target)>;
+/// The lookups made into a table, as `(inputs, output)` pairs of targets.
+pub type Lookup = Vec<(Vec<Target>, Target)>;
 
-/// A gate which stores (input, output) lookup pairs made elsewhere in the trace. It doesn't check any constraints itself.
+/// A gate which stores lookups made elsewhere in the trace. It doesn't check any constraints itself.
+/// Each slot holds `width` columns: the inputs, then the output, then zeros if the table is
+/// narrower than the widest table of the circuit.
 #[derive(Debug, Clone)]
 pub struct LookupGate {
     /// Number of lookups per gate.
     pub num_slots: usize,
+    /// Number of columns of each lookup, shared by all lookup tables of the circuit.
+    pub width: usize,
     /// LUT associated to the gate.
-    lut: LookupTable,
+    lut: Arc<WideLookupTable>,
     /// The Keccak hash of the lookup table.
     lut_hash: [u8; 32],
 }
 
 impl LookupGate {
-    pub fn new_from_table(config: &CircuitConfig, lut: LookupTable) -> Self {
-        let table_bytes = lut
-            .iter()
-            .flat_map(|(input, output)| [input.to_le_bytes(), output.to_le_bytes()].concat())
-            .collect_vec();
-
+    pub fn new_from_table(config: &CircuitConfig, lut: Arc<WideLookupTable>, width: usize) -> Self {
+        assert!(lut.width() <= width);
         Self {
-            num_slots: Self::num_slots(config),
+            num_slots: Self::num_slots(config, width),
+            width,
+            lut_hash: lut.keccak_hash(),
             lut,
-            lut_hash: keccak(table_bytes).0,
         }
     }
-    pub(crate) fn num_slots(config: &CircuitConfig) -> usize {
-        let wires_per_lookup = 2;
+    pub(crate) fn num_slots(config: &CircuitConfig, width: usize) -> usize {
+        let wires_per_lookup = width;
         config.num_routed_wires / wires_per_lookup
     }
 
-    pub fn wire_ith_looking_inp(i: usize) -> usize {
-        2 * i
-    }
-
-    pub fn wire_ith_looking_out(i: usize) -> usize {
-        2 * i + 1
+    /// Wire for the `col`-th column of the `i`-th lookup.
+    pub fn wire_ith_looking_col(width: usize, i: usize, col: usize) -> usize {
+        debug_assert!(col < width);
+        width * i + col
     }
 }
 
@@ -69,13 +67,14 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for LookupGate {
     fn id(&self) -> String {
         // Custom implementation to not have the entire lookup table
         format!(
-            "LookupGate {{num_slots: {}, lut_hash: {:?}}}",
-            self.num_slots, self.lut_hash
+            "LookupGate {{num_slots: {}, width: {}, lut_hash: {:?}}}",
+            self.num_slots, self.width, self.lut_hash
         )
     }
 
     fn serialize(&self, dst: &mut Vec<u8>, common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
         dst.write_usize(self.num_slots)?;
+        dst.write_usize(self.width)?;
         for (i, lut) in common_data.luts.iter().enumerate() {
             if lut == &self.lut {
                 dst.write_usize(i)?;
@@ -88,12 +87,14 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for LookupGate {
 
     fn deserialize(src: &mut Buffer, common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
         let num_slots = src.read_usize()?;
+        let width = src.read_usize()?;
         let lut_index = src.read_usize()?;
         let mut lut_hash = [0u8; 32];
         src.read_exact(&mut lut_hash)?;
 
         Ok(Self {
             num_slots,
+            width,
             lut: common_data.luts[lut_index].clone(),
             lut_hash,
         })
@@ -132,6 +133,7 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for LookupGate {
                     LookupGenerator {
                         row,
                         lut: self.lut.clone(),
+                        width: self.width,
                         slot_nb: i,
                     }
                     .adapter(),
@@ -141,7 +143,7 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for LookupGate {
     }
 
     fn num_wires(&self) -> usize {
-        self.num_slots * 2
+        self.num_slots * self.width
     }
 
     fn num_constants(&self) -> usize {
@@ -169,7 +171,8 @@ impl<F: RichField + Extendable<D>, const D: usize> PackedEvaluableBase<F, D> for
 #[derive(Clone, Debug, Default)]
 pub struct LookupGenerator {
     row: usize,
-    lut: LookupTable,
+    lut: Arc<WideLookupTable>,
+    width: usize,
     slot_nb: usize,
 }
 
@@ -179,39 +182,37 @@ impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D> for Loo
     }
 
     fn dependencies(&self) -> Vec<Target> {
-        vec![Target::wire(
-            self.row,
-            LookupGate::wire_ith_looking_inp(self.slot_nb),
-        )]
+        (0..self.lut.num_inputs())
+            .map(|col| {
+                Target::wire(
+                    self.row,
+                    LookupGate::wire_ith_looking_col(self.width, self.slot_nb, col),
+                )
+            })
+            .collect()
     }
 
     fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
-        let get_wire = |wire: usize| -> F { witness.get_target(Target::wire(self.row, wire)) };
-
-        let input_val = get_wire(LookupGate::wire_ith_looking_inp(self.slot_nb));
-        let (input, output) = self.lut[input_val.to_canonical_u64() as usize];
-        if input_val == F::from_canonical_u16(input) {
-            let output_val = F::from_canonical_u16(output);
-
-            let out_wire = Target::wire(self.row, LookupGate::wire_ith_looking_out(self.slot_nb));
-            out_buffer.set_target(out_wire, output_val);
-        } else {
-            for (input, output) in self.lut.iter() {
-                if input_val == F::from_canonical_u16(*input) {
-                    let output_val = F::from_canonical_u16(*output);
-
-                    let out_wire =
-                        Target::wire(self.row, LookupGate::wire_ith_looking_out(self.slot_nb));
-                    out_buffer.set_target(out_wire, output_val);
-                    return;
-                }
-            }
-            panic!("Incorrect input value provided");
-        };
+        let inputs = witness.get_targets(&SimpleGenerator::<F, D>::dependencies(self));
+        let row = self
+            .lut
+            .find(&inputs)
+            .expect("Incorrect input value provided");
+
+        let num_inputs = self.lut.num_inputs();
+        let out_wire = Target::wire(
+            self.row,
+            LookupGate::wire_ith_looking_col(self.width, self.slot_nb, num_inputs),
+        );
+        out_buffer.set_target(
+            out_wire,
+            F::from_canonical_u64(self.lut.row(row)[num_inputs]),
+        );
     }
 
     fn serialize(&self, dst: &mut Vec<u8>, common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
         dst.write_usize(self.row)?;
+        dst.write_usize(self.width)?;
         dst.write_usize(self.slot_nb)?;
         for (i, lut) in common_data.luts.iter().enumerate() {
             if lut == &self.lut {
@@ -224,12 +225,14 @@ impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D> for Loo
 
     fn deserialize(src: &mut Buffer, common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
         let row = src.read_usize()?;
+        let width = src.read_usize()?;
         let slot_nb = src.read_usize()?;
         let lut_index = src.read_usize()?;
 
         Ok(Self {
             row,
             lut: common_data.luts[lut_index].clone(),
+            width,
             slot_nb,
         })
     }
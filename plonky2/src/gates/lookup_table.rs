@@ -4,12 +4,14 @@ use alloc::vec::Vec;
 use alloc::{format, vec};
 use core::usize;
 
-use itertools::Itertools;
+use hashbrown::HashMap;
 use keccak_hash::keccak;
 use plonky2_util::ceil_div_usize;
+use serde::Serialize;
 
 use crate::field::extension::Extendable;
 use crate::field::packed::PackedField;
+use crate::field::types::{Field, PrimeField64};
 use crate::gates::gate::Gate;
 use crate::gates::packed_util::PackedEvaluableBase;
 use crate::gates::util::StridedConstraintConsumer;
@@ -26,15 +28,168 @@ use crate::plonk::vars::{
 };
 use crate::util::serialization::{Buffer, IoResult, Read, Write};
 
+/// A lookup table of `(input, output)` pairs of 16-bit values.
 pub type LookupTable = Arc<Vec<(u16, u16)>>;
 
-/// A gate which stores the set of (input, output) value pairs of a lookup table, and their multiplicities.
+/// A lookup table whose rows are made of `num_inputs` input columns followed by a single output
+/// column. Entries are arbitrary field elements, stored by their canonical `u64` representation.
+///
+/// A `LookupTable` is the special case of a `WideLookupTable` with a single 16-bit input column.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct WideLookupTable {
+    num_inputs: usize,
+    /// The rows of the table, flattened.
+    entries: Vec<u64>,
+    /// The index of the first row with the given inputs.
+    #[serde(skip)]
+    row_by_inputs: HashMap<Vec<u64>, usize>,
+}
+
+impl WideLookupTable {
+    /// Builds a table from rows of `num_inputs + 1` canonical field values, the last of which is
+    /// the output.
+    pub fn new(num_inputs: usize, rows: Vec<Vec<u64>>) -> Self {
+        assert!(
+            num_inputs > 0,
+            "A lookup table needs at least one input column"
+        );
+        assert!(!rows.is_empty(), "A lookup table needs at least one row");
+
+        let mut entries = Vec::with_capacity(rows.len() * (num_inputs + 1));
+        let mut row_by_inputs = HashMap::with_capacity(rows.len());
+        for (i, row) in rows.into_iter().enumerate() {
+            assert_eq!(
+                row.len(),
+                num_inputs + 1,
+                "Row {} of the lookup table has the wrong width",
+                i
+            );
+            row_by_inputs.entry(row[..num_inputs].to_vec()).or_insert(i);
+            entries.extend(row);
+        }
+
+        Self {
+            num_inputs,
+            entries,
+            row_by_inputs,
+        }
+    }
+
+    /// Builds a table from `(inputs, output)` rows of field elements.
+    pub fn from_field_rows<F: PrimeField64>(rows: &[(Vec<F>, F)]) -> Self {
+        let num_inputs = rows.first().map_or(0, |(inputs, _)| inputs.len());
+        let rows = rows
+            .iter()
+            .map(|(inputs, output)| {
+                inputs
+                    .iter()
+                    .chain([output])
+                    .map(|x| x.to_canonical_u64())
+                    .collect()
+            })
+            .collect();
+        Self::new(num_inputs, rows)
+    }
+
+    /// Builds a table by evaluating `f` on each tuple of `inputs`.
+    pub fn from_fn<F: PrimeField64>(f: fn(&[F]) -> F, inputs: &[Vec<F>]) -> Self {
+        let rows: Vec<_> = inputs
+            .iter()
+            .map(|inputs| (inputs.clone(), f(inputs)))
+            .collect();
+        Self::from_field_rows(&rows)
+    }
+
+    /// The number of input columns.
+    pub fn num_inputs(&self) -> usize {
+        self.num_inputs
+    }
+
+    /// The number of columns, i.e. the inputs and the output.
+    pub fn width(&self) -> usize {
+        self.num_inputs + 1
+    }
+
+    /// The number of rows.
+    pub fn len(&self) -> usize {
+        self.entries.len() / self.width()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The `i`-th row, inputs first.
+    pub fn row(&self, i: usize) -> &[u64] {
+        &self.entries[i * self.width()..(i + 1) * self.width()]
+    }
+
+    /// The `i`-th row as field elements, padded with zeros to `width` columns.
+    pub fn padded_row<F: Field>(&self, i: usize, width: usize) -> impl Iterator<Item = F> + '_ {
+        debug_assert!(width >= self.width());
+        self.row(i)
+            .iter()
+            .map(|&x| F::from_canonical_u64(x))
+            .chain(core::iter::repeat(F::ZERO))
+            .take(width)
+    }
+
+    /// Returns the index of the first row with the given inputs, if any.
+    pub fn find<F: PrimeField64>(&self, inputs: &[F]) -> Option<usize> {
+        let inputs: Vec<u64> = inputs.iter().map(|x| x.to_canonical_u64()).collect();
+        self.row_by_inputs.get(&inputs).copied()
+    }
+
+    /// The Keccak hash of the table, used to identify lookup gates without listing the whole table.
+    pub(crate) fn keccak_hash(&self) -> [u8; 32] {
+        let table_bytes: Vec<u8> = core::iter::once(self.num_inputs as u64)
+            .chain(self.entries.iter().copied())
+            .flat_map(u64::to_le_bytes)
+            .collect();
+        keccak(table_bytes).0
+    }
+}
+
+impl PartialEq for WideLookupTable {
+    fn eq(&self, other: &Self) -> bool {
+        self.num_inputs == other.num_inputs && self.entries == other.entries
+    }
+}
+
+impl Eq for WideLookupTable {}
+
+impl From<&[(u16, u16)]> for WideLookupTable {
+    fn from(pairs: &[(u16, u16)]) -> Self {
+        let rows = pairs
+            .iter()
+            .map(|&(input, output)| vec![input as u64, output as u64])
+            .collect();
+        Self::new(1, rows)
+    }
+}
+
+impl From<LookupTable> for WideLookupTable {
+    fn from(table: LookupTable) -> Self {
+        table.as_slice().into()
+    }
+}
+
+/// The number of columns of every lookup slot in a circuit using the given tables. Narrower tables
+/// are padded with zero columns to this width, so that a single lookup argument covers all of them.
+pub fn lookup_width(luts: &[Arc<WideLookupTable>]) -> usize {
+    luts.iter().map(|lut| lut.width()).max().unwrap_or(2)
+}
+
+/// A gate which stores the set of rows of a lookup table, and their multiplicities. Each slot holds
+/// `width` columns followed by the multiplicity.
 #[derive(Debug, Clone)]
 pub struct LookupTableGate {
     /// Number of lookup entries per gate.
     pub num_slots: usize,
+    /// Number of columns of each entry, shared by all lookup tables of the circuit.
+    pub width: usize,
     /// Lookup table associated to the gate.
-    pub lut: LookupTable,
+    pub lut: Arc<WideLookupTable>,
     /// The Keccak hash of the lookup table.
     lut_hash: [u8; 32],
     /// First row of the lookup table.
@@ -42,38 +197,36 @@ pub struct LookupTableGate {
 }
 
 impl LookupTableGate {
-    pub fn new_from_table(config: &CircuitConfig, lut: LookupTable, last_lut_row: usize) -> Self {
-        let table_bytes = lut
-            .iter()
-            .flat_map(|(input, output)| [input.to_le_bytes(), output.to_le_bytes()].concat())
-            .collect_vec();
-
+    pub fn new_from_table(
+        config: &CircuitConfig,
+        lut: Arc<WideLookupTable>,
+        width: usize,
+        last_lut_row: usize,
+    ) -> Self {
+        assert!(lut.width() <= width);
         Self {
-            num_slots: Self::num_slots(config),
+            num_slots: Self::num_slots(config, width),
+            width,
+            lut_hash: lut.keccak_hash(),
             lut,
-            lut_hash: keccak(table_bytes).0,
             last_lut_row,
         }
     }
 
-    pub(crate) fn num_slots(config: &CircuitConfig) -> usize {
-        let wires_per_entry = 3;
+    pub(crate) fn num_slots(config: &CircuitConfig, width: usize) -> usize {
+        let wires_per_entry = width + 1;
         config.num_routed_wires / wires_per_entry
     }
 
-    /// Wire for the looked input.
-    pub fn wire_ith_looked_inp(i: usize) -> usize {
-        3 * i
-    }
-
-    // Wire for the looked output.
-    pub fn wire_ith_looked_out(i: usize) -> usize {
-        3 * i + 1
+    /// Wire for the `col`-th column of the looked entry.
+    pub fn wire_ith_looked_col(width: usize, i: usize, col: usize) -> usize {
+        debug_assert!(col < width);
+        (width + 1) * i + col
     }
 
     /// Wire for the multiplicity. Set after the trace has been generated.
-    pub fn wire_ith_multiplicity(i: usize) -> usize {
-        3 * i + 2
+    pub fn wire_ith_multiplicity(width: usize, i: usize) -> usize {
+        (width + 1) * i + width
     }
 }
 
@@ -81,13 +234,14 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for LookupTableGat
     fn id(&self) -> String {
         // Custom implementation to not have the entire lookup table
         format!(
-            "LookupTableGate {{num_slots: {}, lut_hash: {:?}, last_lut_row: {}}}",
-            self.num_slots, self.lut_hash, self.last_lut_row
+            "LookupTableGate {{num_slots: {}, width: {}, lut_hash: {:?}, last_lut_row: {}}}",
+            self.num_slots, self.width, self.lut_hash, self.last_lut_row
         )
     }
 
     fn serialize(&self, dst: &mut Vec<u8>, common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
         dst.write_usize(self.num_slots)?;
+        dst.write_usize(self.width)?;
         dst.write_usize(self.last_lut_row)?;
         for (i, lut) in common_data.luts.iter().enumerate() {
             if lut == &self.lut {
@@ -101,6 +255,7 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for LookupTableGat
 
     fn deserialize(src: &mut Buffer, common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
         let num_slots = src.read_usize()?;
+        let width = src.read_usize()?;
         let last_lut_row = src.read_usize()?;
         let lut_index = src.read_usize()?;
         let mut lut_hash = [0u8; 32];
@@ -108,6 +263,7 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for LookupTableGat
 
         Ok(Self {
             num_slots,
+            width,
             lut: common_data.luts[lut_index].clone(),
             lut_hash,
             last_lut_row,
@@ -149,6 +305,7 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for LookupTableGat
                         lut: self.lut.clone(),
                         slot_nb: i,
                         num_slots: self.num_slots,
+                        width: self.width,
                         last_lut_row: self.last_lut_row,
                     }
                     .adapter(),
@@ -158,7 +315,7 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for LookupTableGat
     }
 
     fn num_wires(&self) -> usize {
-        self.num_slots * 3
+        self.num_slots * (self.width + 1)
     }
 
     fn num_constants(&self) -> usize {
@@ -186,9 +343,10 @@ impl<F: RichField + Extendable<D>, const D: usize> PackedEvaluableBase<F, D> for
 #[derive(Clone, Debug, Default)]
 pub struct LookupTableGenerator {
     row: usize,
-    lut: LookupTable,
+    lut: Arc<WideLookupTable>,
     slot_nb: usize,
     num_slots: usize,
+    width: usize,
     last_lut_row: usize,
 }
 
@@ -205,19 +363,27 @@ impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D> for Loo
         let first_row = self.last_lut_row + ceil_div_usize(self.lut.len(), self.num_slots) - 1;
         let slot = (first_row - self.row) * self.num_slots + self.slot_nb;
 
-        let slot_input_target =
-            Target::wire(self.row, LookupTableGate::wire_ith_looked_inp(self.slot_nb));
-        let slot_output_target =
-            Target::wire(self.row, LookupTableGate::wire_ith_looked_out(self.slot_nb));
+        let slot_targets = (0..self.width)
+            .map(|col| {
+                Target::wire(
+                    self.row,
+                    LookupTableGate::wire_ith_looked_col(self.width, self.slot_nb, col),
+                )
+            })
+            .collect::<Vec<_>>();
 
         if slot < self.lut.len() {
-            let (input, output) = self.lut[slot];
-            out_buffer.set_target(slot_input_target, F::from_canonical_usize(input as usize));
-            out_buffer.set_target(slot_output_target, F::from_canonical_usize(output as usize));
+            for (target, value) in slot_targets
+                .into_iter()
+                .zip(self.lut.padded_row(slot, self.width))
+            {
+                out_buffer.set_target(target, value);
+            }
         } else {
             // Pad with zeros.
-            out_buffer.set_target(slot_input_target, F::ZERO);
-            out_buffer.set_target(slot_output_target, F::ZERO);
+            for target in slot_targets {
+                out_buffer.set_target(target, F::ZERO);
+            }
         }
     }
 
@@ -225,6 +391,7 @@ impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D> for Loo
         dst.write_usize(self.row)?;
         dst.write_usize(self.slot_nb)?;
         dst.write_usize(self.num_slots)?;
+        dst.write_usize(self.width)?;
         dst.write_usize(self.last_lut_row)?;
         for (i, lut) in common_data.luts.iter().enumerate() {
             if lut == &self.lut {
@@ -239,6 +406,7 @@ impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D> for Loo
         let row = src.read_usize()?;
         let slot_nb = src.read_usize()?;
         let num_slots = src.read_usize()?;
+        let width = src.read_usize()?;
         let last_lut_row = src.read_usize()?;
         let lut_index = src.read_usize()?;
 
@@ -247,6 +415,7 @@ impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D> for Loo
             lut: common_data.luts[lut_index].clone(),
             slot_nb,
             num_slots,
+            width,
             last_lut_row,
         })
     }
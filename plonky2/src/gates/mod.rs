@@ -7,12 +7,14 @@ pub mod constant;
 pub mod coset_interpolation;
 pub mod exponentiation;
 pub mod gate;
+pub mod keccak_chi;
 pub mod lookup;
 pub mod lookup_table;
 pub mod multiplication_extension;
 pub mod noop;
 pub mod packed_util;
 pub mod poseidon;
+pub mod poseidon2;
 pub mod poseidon_mds;
 pub mod public_input;
 pub mod random_access;
@@ -20,6 +22,7 @@ pub mod reducing;
 pub mod reducing_extension;
 pub(crate) mod selectors;
 pub mod util;
+pub mod xor;
 
 // Can't use #[cfg(test)] here because it needs to be visible to other crates.
 // See https://github.com/rust-lang/cargo/issues/8379
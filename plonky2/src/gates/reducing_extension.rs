@@ -49,7 +49,7 @@ impl<const D: usize> ReducingExtensionGate<D> {
     fn start_accs(&self) -> usize {
         Self::START_COEFFS + self.num_coeffs * D
     }
-    fn wires_accs(&self, i: usize) -> Range<usize> {
+    pub(crate) fn wires_accs(&self, i: usize) -> Range<usize> {
         debug_assert!(i < self.num_coeffs);
         if i == self.num_coeffs - 1 {
             // The last accumulator is the output.
@@ -0,0 +1,276 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::field::extension::Extendable;
+use crate::field::packed::PackedField;
+use crate::field::types::Field;
+use crate::gates::gate::Gate;
+use crate::gates::packed_util::PackedEvaluableBase;
+use crate::gates::util::StridedConstraintConsumer;
+use crate::hash::hash_types::RichField;
+use crate::iop::ext_target::ExtensionTarget;
+use crate::iop::generator::{GeneratedValues, SimpleGenerator, WitnessGeneratorRef};
+use crate::iop::target::Target;
+use crate::iop::witness::{PartitionWitness, Witness, WitnessWrite};
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::circuit_data::{CircuitConfig, CommonCircuitData};
+use crate::plonk::vars::{
+    EvaluationTargets, EvaluationVars, EvaluationVarsBase, EvaluationVarsBaseBatch,
+    EvaluationVarsBasePacked,
+};
+use crate::util::serialization::{Buffer, IoResult, Read, Write};
+
+/// A gate which computes the XOR of `num_inputs` bits. If the config supports enough routed wires,
+/// it can support several such operations in one gate.
+///
+/// The inputs are assumed to be boolean. The output is constrained to be boolean, and to differ
+/// from the sum of the inputs by an even number, so the gate has degree `num_inputs / 2 + 1`.
+#[derive(Debug, Clone, Default)]
+pub struct XorGate {
+    /// Number of bits XORed together by each operation.
+    pub num_inputs: usize,
+    /// Number of XOR operations performed by an XOR gate.
+    pub num_ops: usize,
+}
+
+impl XorGate {
+    pub fn new_from_config(config: &CircuitConfig, num_inputs: usize) -> Self {
+        Self {
+            num_inputs,
+            num_ops: Self::num_ops(config, num_inputs),
+        }
+    }
+
+    /// Determine the maximum number of operations that can fit in one gate for the given config.
+    pub(crate) fn num_ops(config: &CircuitConfig, num_inputs: usize) -> usize {
+        let wires_per_op = num_inputs + 1;
+        config.num_routed_wires / wires_per_op
+    }
+
+    pub fn wire_ith_input(&self, i: usize, j: usize) -> usize {
+        debug_assert!(j < self.num_inputs);
+        (self.num_inputs + 1) * i + j
+    }
+
+    pub fn wire_ith_output(&self, i: usize) -> usize {
+        (self.num_inputs + 1) * i + self.num_inputs
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for XorGate {
+    fn id(&self) -> String {
+        format!("{self:?}")
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_usize(self.num_inputs)?;
+        dst.write_usize(self.num_ops)
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let num_inputs = src.read_usize()?;
+        let num_ops = src.read_usize()?;
+        Ok(Self {
+            num_inputs,
+            num_ops,
+        })
+    }
+
+    fn eval_unfiltered(&self, vars: EvaluationVars<F, D>) -> Vec<F::Extension> {
+        let mut constraints = Vec::with_capacity(self.num_ops * 2);
+        for i in 0..self.num_ops {
+            let sum: F::Extension = (0..self.num_inputs)
+                .map(|j| vars.local_wires[self.wire_ith_input(i, j)])
+                .sum();
+            let output = vars.local_wires[self.wire_ith_output(i)];
+
+            constraints.push(output * (output - F::Extension::ONE));
+            let diff = sum - output;
+            constraints.push(
+                (0..=self.num_inputs / 2)
+                    .map(|k| diff - F::Extension::from_canonical_usize(2 * k))
+                    .product(),
+            );
+        }
+
+        constraints
+    }
+
+    fn eval_unfiltered_base_one(
+        &self,
+        _vars: EvaluationVarsBase<F>,
+        _yield_constr: StridedConstraintConsumer<F>,
+    ) {
+        panic!("use eval_unfiltered_base_packed instead");
+    }
+
+    fn eval_unfiltered_base_batch(&self, vars_base: EvaluationVarsBaseBatch<F>) -> Vec<F> {
+        self.eval_unfiltered_base_batch_packed(vars_base)
+    }
+
+    fn eval_unfiltered_circuit(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        vars: EvaluationTargets<D>,
+    ) -> Vec<ExtensionTarget<D>> {
+        let mut constraints = Vec::with_capacity(self.num_ops * 2);
+        for i in 0..self.num_ops {
+            let inputs: Vec<_> = (0..self.num_inputs)
+                .map(|j| vars.local_wires[self.wire_ith_input(i, j)])
+                .collect();
+            let sum = builder.add_many_extension(inputs);
+            let output = vars.local_wires[self.wire_ith_output(i)];
+
+            constraints.push(builder.mul_sub_extension(output, output, output));
+            let diff = builder.sub_extension(sum, output);
+            let factors: Vec<_> = (0..=self.num_inputs / 2)
+                .map(|k| builder.add_const_extension(diff, -F::from_canonical_usize(2 * k)))
+                .collect();
+            constraints.push(builder.mul_many_extension(factors));
+        }
+
+        constraints
+    }
+
+    fn generators(&self, row: usize, _local_constants: &[F]) -> Vec<WitnessGeneratorRef<F, D>> {
+        (0..self.num_ops)
+            .map(|i| {
+                WitnessGeneratorRef::new(
+                    XorGenerator {
+                        row,
+                        gate: self.clone(),
+                        i,
+                    }
+                    .adapter(),
+                )
+            })
+            .collect()
+    }
+
+    fn num_wires(&self) -> usize {
+        self.num_ops * (self.num_inputs + 1)
+    }
+
+    fn num_constants(&self) -> usize {
+        0
+    }
+
+    fn degree(&self) -> usize {
+        (self.num_inputs / 2 + 1).max(2)
+    }
+
+    fn num_constraints(&self) -> usize {
+        self.num_ops * 2
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> PackedEvaluableBase<F, D> for XorGate {
+    fn eval_unfiltered_base_packed<P: PackedField<Scalar = F>>(
+        &self,
+        vars: EvaluationVarsBasePacked<P>,
+        mut yield_constr: StridedConstraintConsumer<P>,
+    ) {
+        for i in 0..self.num_ops {
+            let sum: P = (0..self.num_inputs)
+                .map(|j| vars.local_wires[self.wire_ith_input(i, j)])
+                .sum();
+            let output = vars.local_wires[self.wire_ith_output(i)];
+
+            yield_constr.one(output * (output - F::ONE));
+            let diff = sum - output;
+            yield_constr.one(
+                (0..=self.num_inputs / 2)
+                    .map(|k| diff - F::from_canonical_usize(2 * k))
+                    .product(),
+            );
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct XorGenerator {
+    row: usize,
+    gate: XorGate,
+    i: usize,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D> for XorGenerator {
+    fn id(&self) -> String {
+        "XorGenerator".to_string()
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        (0..self.gate.num_inputs)
+            .map(|j| Target::wire(self.row, self.gate.wire_ith_input(self.i, j)))
+            .collect()
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let parity = (0..self.gate.num_inputs)
+            .map(|j| {
+                witness
+                    .get_target(Target::wire(self.row, self.gate.wire_ith_input(self.i, j)))
+                    .to_canonical_u64()
+            })
+            .fold(0, |acc, bit| acc ^ bit);
+
+        let output_target = Target::wire(self.row, self.gate.wire_ith_output(self.i));
+        out_buffer.set_target(output_target, F::from_canonical_u64(parity));
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_usize(self.row)?;
+        dst.write_usize(self.gate.num_inputs)?;
+        dst.write_usize(self.gate.num_ops)?;
+        dst.write_usize(self.i)
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let row = src.read_usize()?;
+        let num_inputs = src.read_usize()?;
+        let num_ops = src.read_usize()?;
+        let i = src.read_usize()?;
+        Ok(Self {
+            row,
+            gate: XorGate {
+                num_inputs,
+                num_ops,
+            },
+            i,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::field::goldilocks_field::GoldilocksField;
+    use crate::gates::gate_testing::{test_eval_fns, test_low_degree};
+    use crate::gates::xor::XorGate;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+
+    #[test]
+    fn low_degree() {
+        let config = CircuitConfig::standard_recursion_config();
+        for num_inputs in [2, 3, 5] {
+            let gate = XorGate::new_from_config(&config, num_inputs);
+            test_low_degree::<GoldilocksField, _, 4>(gate);
+        }
+    }
+
+    #[test]
+    fn eval_fns() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let config = CircuitConfig::standard_recursion_config();
+        for num_inputs in [2, 3, 5] {
+            let gate = XorGate::new_from_config(&config, num_inputs);
+            test_eval_fns::<F, C, _, D>(gate)?;
+        }
+        Ok(())
+    }
+}
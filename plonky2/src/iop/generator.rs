@@ -1,8 +1,9 @@
 use alloc::boxed::Box;
+use alloc::collections::VecDeque;
 use alloc::string::{String, ToString};
 use alloc::vec;
 use alloc::vec::Vec;
-use core::fmt::Debug;
+use core::fmt::{Debug, Display, Formatter};
 use core::marker::PhantomData;
 
 use crate::field::extension::Extendable;
@@ -16,8 +17,50 @@ use crate::plonk::circuit_data::{CommonCircuitData, ProverOnlyCircuitData};
 use crate::plonk::config::GenericConfig;
 use crate::util::serialization::{Buffer, IoResult, Read, Write};
 
+/// A generator which was never able to finish during witness generation.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StuckGenerator {
+    /// The index of the generator in `ProverOnlyCircuitData::generators`.
+    pub index: usize,
+    /// The generator's `WitnessGenerator::id`.
+    pub id: String,
+    /// The targets in the generator's watch list which were never populated.
+    pub unset_watches: Vec<Target>,
+}
+
+/// Returned by `generate_partial_witness` when witness generation stalls, i.e. when no generator
+/// can make progress but some of them have not finished yet.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WitnessGenerationError {
+    pub stuck_generators: Vec<StuckGenerator>,
+}
+
+impl Display for WitnessGenerationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{} generators weren't run", self.stuck_generators.len())?;
+        for stuck in &self.stuck_generators {
+            write!(
+                f,
+                "\n  generator {} ({}) is waiting on {:?}",
+                stuck.index, stuck.id, stuck.unset_watches
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for WitnessGenerationError {}
+
 /// Given a `PartitionWitness` that has only inputs set, populates the rest of the witness using the
 /// given set of generators.
+///
+/// Generators are kept in a work queue, driven by their watch lists: a generator is queued again
+/// whenever one of the targets it watches gets populated. With the `parallel` feature, each queued
+/// generator is spawned on the thread pool right away, and the values it generates are merged into
+/// the witness as soon as it returns, so it never waits on unrelated generators. Each target can
+/// only take one value, so the order in which generators run doesn't affect the witness, and it
+/// matches the one `generate_partial_witness_serial` produces save for random values.
 pub fn generate_partial_witness<
     'a,
     F: RichField + Extendable<D>,
@@ -27,76 +70,279 @@ pub fn generate_partial_witness<
     inputs: PartialWitness<F>,
     prover_data: &'a ProverOnlyCircuitData<F, C, D>,
     common_data: &'a CommonCircuitData<F, D>,
-) -> PartitionWitness<'a, F> {
-    let config = &common_data.config;
+) -> Result<PartitionWitness<'a, F>, WitnessGenerationError> {
+    #[cfg(all(feature = "parallel", feature = "std"))]
+    {
+        let witness = initial_witness(inputs, prover_data, common_data, &mut None);
+        let (witness, generator_is_expired) = concurrent::run_generators(witness, prover_data);
+        check_generators_expired(witness, &prover_data.generators, &generator_is_expired)
+    }
+
+    #[cfg(not(all(feature = "parallel", feature = "std")))]
+    generate_partial_witness_serial(inputs, prover_data, common_data)
+}
+
+/// Like `generate_partial_witness`, but runs the generators one at a time on the current thread.
+pub fn generate_partial_witness_serial<
+    'a,
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    inputs: PartialWitness<F>,
+    prover_data: &'a ProverOnlyCircuitData<F, C, D>,
+    common_data: &'a CommonCircuitData<F, D>,
+) -> Result<PartitionWitness<'a, F>, WitnessGenerationError> {
+    generate_partial_witness_with_conflicts(inputs, prover_data, common_data, None)
+}
+
+/// Like `generate_partial_witness_serial`, but if `conflicts` is given, a value which disagrees
+/// with the value already assigned to its target's partition is recorded there as
+/// `(target, existing_value, new_value)` instead of causing a panic. The existing value is kept.
+pub(crate) fn generate_partial_witness_with_conflicts<
+    'a,
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    inputs: PartialWitness<F>,
+    prover_data: &'a ProverOnlyCircuitData<F, C, D>,
+    common_data: &'a CommonCircuitData<F, D>,
+    mut conflicts: Option<&mut Vec<(Target, F, F)>>,
+) -> Result<PartitionWitness<'a, F>, WitnessGenerationError> {
     let generators = &prover_data.generators;
     let generator_indices_by_watches = &prover_data.generator_indices_by_watches;
 
+    let mut witness = initial_witness(inputs, prover_data, common_data, &mut conflicts);
+
+    // Build a queue of "pending" generators which are waiting to be run. Initially, all generators
+    // are queued. `generator_is_pending` prevents a generator from being queued twice.
+    let mut pending_generator_indices: VecDeque<_> = (0..generators.len()).collect();
+    let mut generator_is_pending = vec![true; generators.len()];
+
+    // We also track a list of "expired" generators which have already returned true.
+    let mut generator_is_expired = vec![false; generators.len()];
+
+    // Keep running generators until the queue runs dry.
+    while let Some(generator_idx) = pending_generator_indices.pop_front() {
+        generator_is_pending[generator_idx] = false;
+
+        let mut buffer = GeneratedValues::empty();
+        if generators[generator_idx].0.run(&witness, &mut buffer) {
+            generator_is_expired[generator_idx] = true;
+        }
+
+        // Merge any generated values into our witness, and enqueue the unfinished generators that
+        // were watching one of the newly populated targets.
+        let new_target_reps = buffer
+            .target_values
+            .into_iter()
+            .flat_map(|(t, v)| set_target_or_record_conflict(&mut witness, &mut conflicts, t, v));
+        for watch in new_target_reps {
+            for &watching_generator_idx in generator_indices_by_watches
+                .get(&watch)
+                .into_iter()
+                .flatten()
+            {
+                if !generator_is_expired[watching_generator_idx]
+                    && !generator_is_pending[watching_generator_idx]
+                {
+                    generator_is_pending[watching_generator_idx] = true;
+                    pending_generator_indices.push_back(watching_generator_idx);
+                }
+            }
+        }
+    }
+
+    check_generators_expired(witness, generators, &generator_is_expired)
+}
+
+/// Builds a witness for the circuit with the given inputs set.
+fn initial_witness<'a, F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>(
+    inputs: PartialWitness<F>,
+    prover_data: &'a ProverOnlyCircuitData<F, C, D>,
+    common_data: &'a CommonCircuitData<F, D>,
+    conflicts: &mut Option<&mut Vec<(Target, F, F)>>,
+) -> PartitionWitness<'a, F> {
     let mut witness = PartitionWitness::new(
-        config.num_wires,
+        common_data.config.num_wires,
         common_data.degree(),
         &prover_data.representative_map,
     );
-
     for (t, v) in inputs.target_values.into_iter() {
-        witness.set_target(t, v);
+        set_target_or_record_conflict(&mut witness, conflicts, t, v);
     }
+    witness
+}
 
-    // Build a list of "pending" generators which are queued to be run. Initially, all generators
-    // are queued.
-    let mut pending_generator_indices: Vec<_> = (0..generators.len()).collect();
+/// Returns the witness if every generator has finished, and the generators which haven't otherwise.
+fn check_generators_expired<'a, F: RichField + Extendable<D>, const D: usize>(
+    witness: PartitionWitness<'a, F>,
+    generators: &[WitnessGeneratorRef<F, D>],
+    generator_is_expired: &[bool],
+) -> Result<PartitionWitness<'a, F>, WitnessGenerationError> {
+    let stuck_generators: Vec<_> = generators
+        .iter()
+        .enumerate()
+        .filter(|&(generator_idx, _)| !generator_is_expired[generator_idx])
+        .map(|(index, generator)| StuckGenerator {
+            index,
+            id: generator.0.id(),
+            unset_watches: generator
+                .0
+                .watch_list()
+                .into_iter()
+                .filter(|&t| witness.try_get_target(t).is_none())
+                .collect(),
+        })
+        .collect();
 
-    // We also track a list of "expired" generators which have already returned false.
-    let mut generator_is_expired = vec![false; generators.len()];
-    let mut remaining_generators = generators.len();
+    if stuck_generators.is_empty() {
+        Ok(witness)
+    } else {
+        Err(WitnessGenerationError { stuck_generators })
+    }
+}
 
-    let mut buffer = GeneratedValues::empty();
+#[cfg(all(feature = "parallel", feature = "std"))]
+mod concurrent {
+    use alloc::vec;
+    use alloc::vec::Vec;
+    use std::sync::{Mutex, RwLock};
+
+    use plonky2_maybe_rayon::rayon;
+
+    use super::GeneratedValues;
+    use crate::field::extension::Extendable;
+    use crate::hash::hash_types::RichField;
+    use crate::iop::witness::PartitionWitness;
+    use crate::plonk::circuit_data::ProverOnlyCircuitData;
+    use crate::plonk::config::GenericConfig;
+
+    /// The state shared by the generators running on the thread pool.
+    struct WorkQueue<
+        'a,
+        'p,
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+        const D: usize,
+    > {
+        prover_data: &'p ProverOnlyCircuitData<F, C, D>,
+        witness: RwLock<PartitionWitness<'a, F>>,
+        status: Mutex<GeneratorStatus>,
+    }
 
-    // Keep running generators until we fail to make progress.
-    while !pending_generator_indices.is_empty() {
-        let mut next_pending_generator_indices = Vec::new();
+    struct GeneratorStatus {
+        /// Prevents a generator from being queued twice.
+        is_pending: Vec<bool>,
+        /// Marks the generators which have already returned true.
+        is_expired: Vec<bool>,
+    }
 
-        for &generator_idx in &pending_generator_indices {
-            if generator_is_expired[generator_idx] {
-                continue;
+    /// Runs the generators until the queue runs dry, spawning each one as soon as it is queued.
+    /// Returns the witness along with which generators have finished.
+    pub(super) fn run_generators<
+        'a,
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+        const D: usize,
+    >(
+        witness: PartitionWitness<'a, F>,
+        prover_data: &ProverOnlyCircuitData<F, C, D>,
+    ) -> (PartitionWitness<'a, F>, Vec<bool>) {
+        let num_generators = prover_data.generators.len();
+        let queue = WorkQueue {
+            prover_data,
+            witness: RwLock::new(witness),
+            status: Mutex::new(GeneratorStatus {
+                is_pending: vec![true; num_generators],
+                is_expired: vec![false; num_generators],
+            }),
+        };
+        rayon::scope(|scope| {
+            let queue = &queue;
+            for generator_idx in 0..num_generators {
+                scope.spawn(move |scope| queue.run(scope, generator_idx));
             }
+        });
+        let status = queue.status.into_inner().unwrap();
+        (queue.witness.into_inner().unwrap(), status.is_expired)
+    }
 
-            let finished = generators[generator_idx].0.run(&witness, &mut buffer);
-            if finished {
-                generator_is_expired[generator_idx] = true;
-                remaining_generators -= 1;
+    impl<'a, 'p, F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
+        WorkQueue<'a, 'p, F, C, D>
+    {
+        fn run<'s>(&'s self, scope: &rayon::Scope<'s>, generator_idx: usize) {
+            {
+                let mut status = self.status.lock().unwrap();
+                // Unqueue the generator before it runs, so that it is queued again if one of its
+                // watches gets populated in the meantime.
+                status.is_pending[generator_idx] = false;
+                // A run queued earlier may have finished since this one was queued.
+                if status.is_expired[generator_idx] {
+                    return;
+                }
             }
 
-            // Merge any generated values into our witness, and get a list of newly-populated
-            // targets' representatives.
-            let new_target_reps = buffer
-                .target_values
-                .drain(..)
-                .flat_map(|(t, v)| witness.set_target_returning_rep(t, v));
-
-            // Enqueue unfinished generators that were watching one of the newly populated targets.
-            for watch in new_target_reps {
-                let opt_watchers = generator_indices_by_watches.get(&watch);
-                if let Some(watchers) = opt_watchers {
-                    for &watching_generator_idx in watchers {
-                        if !generator_is_expired[watching_generator_idx] {
-                            next_pending_generator_indices.push(watching_generator_idx);
+            let mut buffer = GeneratedValues::empty();
+            let finished = self.prover_data.generators[generator_idx]
+                .0
+                .run(&self.witness.read().unwrap(), &mut buffer);
+
+            let mut ready_generator_indices = Vec::new();
+            {
+                let mut witness = self.witness.write().unwrap();
+                let mut status = self.status.lock().unwrap();
+                if finished {
+                    status.is_expired[generator_idx] = true;
+                }
+
+                // Merge any generated values into the witness, and enqueue the unfinished
+                // generators that were watching one of the newly populated targets.
+                let new_target_reps = buffer
+                    .target_values
+                    .into_iter()
+                    .flat_map(|(t, v)| witness.set_target_returning_rep(t, v));
+                for watch in new_target_reps {
+                    for &watching_generator_idx in self
+                        .prover_data
+                        .generator_indices_by_watches
+                        .get(&watch)
+                        .into_iter()
+                        .flatten()
+                    {
+                        if !status.is_expired[watching_generator_idx]
+                            && !status.is_pending[watching_generator_idx]
+                        {
+                            status.is_pending[watching_generator_idx] = true;
+                            ready_generator_indices.push(watching_generator_idx);
                         }
                     }
                 }
             }
-        }
 
-        pending_generator_indices = next_pending_generator_indices;
+            for watching_generator_idx in ready_generator_indices {
+                scope.spawn(move |scope| self.run(scope, watching_generator_idx));
+            }
+        }
     }
+}
 
-    assert_eq!(
-        remaining_generators, 0,
-        "{} generators weren't run",
-        remaining_generators,
-    );
-
-    witness
+fn set_target_or_record_conflict<F: Field>(
+    witness: &mut PartitionWitness<F>,
+    conflicts: &mut Option<&mut Vec<(Target, F, F)>>,
+    target: Target,
+    value: F,
+) -> Option<usize> {
+    match conflicts {
+        Some(conflicts) => witness
+            .try_set_target_returning_rep(target, value)
+            .unwrap_or_else(|old_value| {
+                conflicts.push((target, old_value, value));
+                None
+            }),
+        None => witness.set_target_returning_rep(target, value),
+    }
 }
 
 /// A generator participates in the generation of the witness.
@@ -415,3 +661,104 @@ impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D> for Con
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::types::Sample;
+    use crate::iop::witness::PartialWitness;
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::PoseidonGoldilocksConfig;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    #[test]
+    fn test_dependent_and_independent_generators() {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        // A long chain where each step depends on the previous one, next to many independent
+        // products which can all run concurrently.
+        let x = builder.add_virtual_target();
+        let mut acc = x;
+        for _ in 0..50 {
+            acc = builder.mul_add(acc, x, x);
+        }
+        let ys = builder.add_virtual_targets(50);
+        let products: Vec<_> = ys.iter().map(|&y| builder.mul(y, x)).collect();
+
+        let data = builder.build_prover::<C>();
+
+        let x_value = F::rand();
+        let y_values = F::rand_vec(ys.len());
+        let mut pw = PartialWitness::new();
+        pw.set_target(x, x_value);
+        pw.set_target_arr(&ys, &y_values);
+
+        let witness =
+            generate_partial_witness(pw.clone(), &data.prover_only, &data.common).unwrap();
+        let serial_witness =
+            generate_partial_witness_serial(pw, &data.prover_only, &data.common).unwrap();
+
+        let mut expected = x_value;
+        for _ in 0..50 {
+            expected = expected * x_value + x_value;
+        }
+        for witness in [&witness, &serial_witness] {
+            assert_eq!(witness.get_target(acc), expected);
+            for (&p, &y) in products.iter().zip(&y_values) {
+                assert_eq!(witness.get_target(p), y * x_value);
+            }
+        }
+
+        // Both runs populate the same targets. Their values may only differ where they are random.
+        let is_set = |witness: &PartitionWitness<F>| {
+            witness
+                .values
+                .iter()
+                .map(Option::is_some)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(is_set(&witness), is_set(&serial_witness));
+    }
+
+    #[test]
+    fn test_stalled_generation_reports_unset_watches() {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let x = builder.add_virtual_target();
+        let y = builder.add_virtual_target();
+        let z = builder.mul(x, y);
+        builder.register_public_input(z);
+
+        let data = builder.build_prover::<C>();
+
+        // Only `x` is set, so the multiplication can never run.
+        let mut pw = PartialWitness::new();
+        pw.set_target(x, F::TWO);
+
+        let err = generate_partial_witness(pw, &data.prover_only, &data.common)
+            .err()
+            .expect("witness generation should stall");
+        assert!(!err.stuck_generators.is_empty());
+        assert!(err
+            .stuck_generators
+            .iter()
+            .all(|stuck| !stuck.unset_watches.is_empty()));
+
+        // The generator computing `z` must be among the stuck ones, waiting on a copy of `y`.
+        let rep = |t: Target| {
+            data.prover_only.representative_map
+                [t.index(data.common.config.num_wires, data.common.degree())]
+        };
+        assert!(err
+            .stuck_generators
+            .iter()
+            .flat_map(|stuck| &stuck.unset_watches)
+            .any(|&t| rep(t) == rep(y)));
+    }
+}
@@ -3,17 +3,23 @@ use alloc::vec::Vec;
 
 use hashbrown::HashMap;
 use itertools::{zip_eq, Itertools};
+use num::BigUint;
 
 use crate::field::extension::{Extendable, FieldExtension};
-use crate::field::types::Field;
+use crate::field::types::{Field, PrimeField, PrimeField64};
 use crate::fri::structure::{FriOpenings, FriOpeningsTarget};
 use crate::fri::witness_util::set_fri_proof_target;
+use crate::gadgets::biguint::{biguint_from_limb_values, biguint_to_limbs, BigUintTarget};
+use crate::gadgets::mpt::MptProofTarget;
+use crate::gadgets::nonnative::NonNativeTarget;
 use crate::hash::hash_types::{HashOut, HashOutTarget, MerkleCapTarget, RichField};
 use crate::hash::merkle_tree::MerkleCap;
 use crate::iop::ext_target::ExtensionTarget;
 use crate::iop::target::{BoolTarget, Target};
 use crate::iop::wire::Wire;
-use crate::plonk::circuit_data::{VerifierCircuitTarget, VerifierOnlyCircuitData};
+use crate::plonk::circuit_data::{
+    VerifierCircuitTarget, VerifierDataSetTarget, VerifierOnlyCircuitData,
+};
 use crate::plonk::config::{AlgebraicHasher, GenericConfig, Hasher};
 use crate::plonk::proof::{Proof, ProofTarget, ProofWithPublicInputs, ProofWithPublicInputsTarget};
 
@@ -67,6 +73,40 @@ pub trait WitnessWrite<F: Field> {
         self.set_target(target.target, F::from_bool(value))
     }
 
+    fn set_biguint_target(&mut self, target: &BigUintTarget, value: &BigUint) {
+        let limbs = biguint_to_limbs(value, target.num_limbs());
+        for (&t, limb) in target.limbs.iter().zip(limbs) {
+            self.set_target(t, F::from_canonical_u64(limb));
+        }
+    }
+
+    fn set_nonnative_target<FF: PrimeField>(&mut self, target: &NonNativeTarget<FF>, value: FF) {
+        self.set_biguint_target(target.value(), &value.to_canonical_biguint())
+    }
+
+    /// Set the targets in an `MptProofTarget` to the given RLP-encoded nodes, padding them and the
+    /// list of nodes with zeros.
+    fn set_mpt_proof_target(&mut self, proof_target: &MptProofTarget, nodes: &[Vec<u8>]) {
+        assert!(
+            nodes.len() <= proof_target.nodes.len(),
+            "The proof has too many nodes"
+        );
+        for (i, (node_target, &len_target)) in proof_target
+            .nodes
+            .iter()
+            .zip(&proof_target.node_lens)
+            .enumerate()
+        {
+            let node = nodes.get(i).map_or(&[][..], |node| node);
+            assert!(node.len() <= node_target.len(), "A node is too long");
+            for (j, &t) in node_target.iter().enumerate() {
+                self.set_target(t, F::from_canonical_u8(node.get(j).copied().unwrap_or(0)));
+            }
+            self.set_target(len_target, F::from_canonical_usize(node.len()));
+        }
+        self.set_target(proof_target.depth, F::from_canonical_usize(nodes.len()));
+    }
+
     /// Set the targets in a `ProofWithPublicInputsTarget` to their corresponding values in a
     /// `ProofWithPublicInputs`.
     fn set_proof_with_pis_target<C: GenericConfig<D, F = F>, const D: usize>(
@@ -146,6 +186,19 @@ pub trait WitnessWrite<F: Field> {
         self.set_hash_target(vdt.circuit_digest, vd.circuit_digest);
     }
 
+    fn set_verifier_data_set_target<C: GenericConfig<D, F = F>, const D: usize>(
+        &mut self,
+        vdst: &VerifierDataSetTarget,
+        vds: &[VerifierOnlyCircuitData<C, D>],
+    ) where
+        F: RichField + Extendable<D>,
+        C::Hasher: AlgebraicHasher<F>,
+    {
+        for (vdt, vd) in zip_eq(&vdst.verifier_data, vds) {
+            self.set_verifier_data_target(vdt, vd);
+        }
+    }
+
     fn set_wire(&mut self, wire: Wire, value: F) {
         self.set_target(Target::Wire(wire), value)
     }
@@ -216,6 +269,20 @@ pub trait Witness<F: Field>: WitnessWrite<F> {
         panic!("not a bool")
     }
 
+    fn get_biguint_target(&self, target: &BigUintTarget) -> BigUint
+    where
+        F: PrimeField64,
+    {
+        biguint_from_limb_values(&self.get_targets(&target.limbs))
+    }
+
+    fn get_nonnative_target<FF: PrimeField>(&self, target: &NonNativeTarget<FF>) -> FF
+    where
+        F: PrimeField64,
+    {
+        FF::from_noncanonical_biguint(self.get_biguint_target(target.value()))
+    }
+
     fn get_hash_target(&self, ht: HashOutTarget) -> HashOut<F> {
         HashOut {
             elements: self.get_targets(&ht.elements).try_into().unwrap(),
@@ -318,18 +385,31 @@ impl<'a, F: Field> PartitionWitness<'a, F> {
     /// Set a `Target`. On success, returns the representative index of the newly-set target. If the
     /// target was already set, returns `None`.
     pub fn set_target_returning_rep(&mut self, target: Target, value: F) -> Option<usize> {
+        self.try_set_target_returning_rep(target, value)
+            .unwrap_or_else(|old_value| {
+                panic!(
+                    "Partition containing {:?} was set twice with different values: {} != {}",
+                    target, old_value, value
+                )
+            })
+    }
+
+    /// Like `set_target_returning_rep`, but if the target's partition was already set to a
+    /// different value, leaves it unchanged and returns the existing value as an error.
+    pub fn try_set_target_returning_rep(
+        &mut self,
+        target: Target,
+        value: F,
+    ) -> Result<Option<usize>, F> {
         let rep_index = self.representative_map[self.target_index(target)];
         let rep_value = &mut self.values[rep_index];
-        if let Some(old_value) = *rep_value {
-            assert_eq!(
-                value, old_value,
-                "Partition containing {:?} was set twice with different values: {} != {}",
-                target, old_value, value
-            );
-            None
-        } else {
-            *rep_value = Some(value);
-            Some(rep_index)
+        match *rep_value {
+            Some(old_value) if old_value != value => Err(old_value),
+            Some(_) => Ok(None),
+            None => {
+                *rep_value = Some(value);
+                Ok(Some(rep_index))
+            }
         }
     }
 
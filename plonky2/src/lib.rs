@@ -7,6 +7,8 @@ pub extern crate alloc;
 #[doc(inline)]
 pub use plonky2_field as field;
 
+pub mod curve;
+pub mod export;
 pub mod fri;
 pub mod gadgets;
 pub mod gates;
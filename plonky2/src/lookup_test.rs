@@ -581,6 +581,123 @@ fn test_many_lookups_on_big_lut() -> anyhow::Result<()> {
     data.verify(proof)
 }
 
+// Tests lookups into an 8-bit XOR table and into a table with arbitrary field entries, next to a
+// lookup into a (u16, u16) table.
+#[test]
+fn test_wide_lookups() -> anyhow::Result<()> {
+    use crate::field::types::{Field, PrimeField64};
+    use crate::gates::lookup_table::WideLookupTable;
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    LOGGER_INITIALIZED.call_once(|| init_logger().unwrap());
+    let config = CircuitConfig::standard_recursion_config();
+    let mut builder = CircuitBuilder::<F, D>::new(config);
+
+    let xor_inputs: Vec<Vec<F>> = (0..256u64)
+        .cartesian_product(0..256u64)
+        .map(|(a, b)| vec![F::from_canonical_u64(a), F::from_canonical_u64(b)])
+        .collect();
+    let xor_index = builder.add_wide_lookup_table_from_fn(
+        |x| F::from_canonical_u64(x[0].to_canonical_u64() ^ x[1].to_canonical_u64()),
+        &xor_inputs,
+    );
+
+    // An `(opcode, x) -> y` table whose entries aren't small integers.
+    let opcode_rows: Vec<(Vec<F>, F)> = (0..4u64)
+        .cartesian_product(0..8u64)
+        .map(|(op, x)| {
+            let x = F::from_canonical_u64(x) - F::ONE;
+            let y = x.exp_u64(op + 3) + F::NEG_ONE;
+            (vec![F::from_canonical_u64(op), x], y)
+        })
+        .collect();
+    let opcode_index =
+        builder.add_wide_lookup_table(WideLookupTable::from_field_rows(&opcode_rows));
+
+    let tip5_table: LookupTable = Arc::new((0..256).zip_eq(TIP5_TABLE).collect());
+    let tip5_index = builder.add_lookup_table_from_pairs(tip5_table);
+
+    let a = builder.add_virtual_target();
+    let b = builder.add_virtual_target();
+    let op = builder.add_virtual_target();
+    let x = builder.add_virtual_target();
+
+    let a_xor_b = builder.add_wide_lookup_from_index(&[a, b], xor_index);
+    let b_xor_a = builder.add_wide_lookup_from_index(&[b, a], xor_index);
+    let y = builder.add_wide_lookup_from_index(&[op, x], opcode_index);
+    let tip5_a = builder.add_lookup_from_index(a, tip5_index);
+
+    builder.register_public_input(a_xor_b);
+    builder.register_public_input(b_xor_a);
+    builder.register_public_input(y);
+    builder.register_public_input(tip5_a);
+
+    let data = builder.build::<C>();
+
+    let (a_val, b_val) = (0xa5u64, 0x3cu64);
+    let (op_val, x_val) = (2, F::NEG_ONE);
+    let mut pw = PartialWitness::new();
+    pw.set_target(a, F::from_canonical_u64(a_val));
+    pw.set_target(b, F::from_canonical_u64(b_val));
+    pw.set_target(op, F::from_canonical_u64(op_val));
+    pw.set_target(x, x_val);
+
+    let mut timing = TimingTree::new("prove wide lookups", Level::Debug);
+    let proof = prove(&data.prover_only, &data.common, pw, &mut timing)?;
+    timing.print();
+    data.verify(proof.clone())?;
+
+    assert_eq!(proof.public_inputs[0], F::from_canonical_u64(a_val ^ b_val));
+    assert_eq!(proof.public_inputs[1], F::from_canonical_u64(a_val ^ b_val));
+    assert_eq!(
+        proof.public_inputs[2],
+        x_val.exp_u64(op_val + 3) + F::NEG_ONE
+    );
+    assert_eq!(
+        proof.public_inputs[3],
+        F::from_canonical_u16(TIP5_TABLE[a_val as usize])
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_read_malformed_lut() {
+    use crate::gates::lookup_table::WideLookupTable;
+    use crate::util::serialization::{Buffer, Read, Write};
+
+    let lut = WideLookupTable::new(2, vec![vec![1, 2, 3], vec![4, 5, 6]]);
+    let mut bytes = Vec::new();
+    bytes.write_lut(&lut).unwrap();
+    let read_lut = Buffer::new(&bytes).read_lut().unwrap();
+    assert_eq!(read_lut.num_inputs(), 2);
+    assert_eq!(read_lut.len(), 2);
+    assert_eq!(read_lut.row(1), lut.row(1));
+
+    let header = |num_inputs: u64, length: u64| {
+        let mut bytes = num_inputs.to_le_bytes().to_vec();
+        bytes.extend(length.to_le_bytes());
+        bytes
+    };
+    // No input column, no row, a huge row count and a truncated row are all rejected.
+    for bad_bytes in [
+        header(0, 2),
+        header(2, 0),
+        header(2, u64::MAX),
+        header(u64::MAX, 1),
+        [header(2, 1), 1u64.to_le_bytes().to_vec()].concat(),
+    ] {
+        assert!(Buffer::new(&bad_bytes).read_lut().is_err());
+    }
+}
+
 fn init_logger() -> anyhow::Result<()> {
     let mut builder = env_logger::Builder::from_default_env();
     builder.format_timestamp(None);
@@ -0,0 +1,297 @@
+//! Checks a witness against every constraint of a circuit directly, without generating a proof.
+//!
+//! Proving a bad witness only tells us that the quotient polynomial doesn't exist, which gives no
+//! hint as to which gate, copy constraint or lookup is at fault. `check_witness` instead evaluates
+//! each constraint row by row, and reports every failure along with the `push_context` scopes that
+//! were open when the offending gate was added.
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter};
+
+use crate::field::extension::Extendable;
+use crate::field::types::Field;
+use crate::hash::hash_types::RichField;
+use crate::iop::generator::{generate_partial_witness_with_conflicts, WitnessGenerationError};
+use crate::iop::target::Target;
+use crate::iop::witness::{PartialWitness, Witness};
+use crate::plonk::circuit_data::{CommonCircuitData, ProverOnlyCircuitData};
+use crate::plonk::config::{GenericConfig, Hasher};
+use crate::plonk::prover::set_lookup_wires;
+use crate::plonk::vars::EvaluationVarsBaseBatch;
+
+/// A constraint which the witness fails to satisfy.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum WitnessCheckFailure<F: Field> {
+    /// Witness generation stalled before every generator finished, so the witness is incomplete
+    /// and no further checks were made.
+    Stalled(WitnessGenerationError),
+    /// A target was assigned two different values, either directly or through targets connected
+    /// to it by copy constraints. The first value is kept for the remaining checks.
+    CopyConstraint {
+        target: Target,
+        existing_value: F,
+        new_value: F,
+        /// The context of the gate owning `target`, or an empty string for virtual targets.
+        context: String,
+    },
+    /// The inputs of a lookup don't appear in its table, or its output disagrees with the table.
+    Lookup {
+        lut_index: usize,
+        inputs: Vec<F>,
+        output: F,
+    },
+    /// A gate constraint which doesn't vanish.
+    Gate {
+        row: usize,
+        gate_id: String,
+        constraint_index: usize,
+        value: F,
+        /// The `push_context` scopes that were open when the gate was added.
+        context: String,
+    },
+}
+
+impl<F: Field> Display for WitnessCheckFailure<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Stalled(err) => write!(f, "witness generation stalled: {}", err),
+            Self::CopyConstraint {
+                target,
+                existing_value,
+                new_value,
+                context,
+            } => write!(
+                f,
+                "{:?} was set twice with different values: {} != {} (in {})",
+                target, existing_value, new_value, context
+            ),
+            Self::Lookup {
+                lut_index,
+                inputs,
+                output,
+            } => write!(
+                f,
+                "lookup {:?} -> {} is not in lookup table {}",
+                inputs, output, lut_index
+            ),
+            Self::Gate {
+                row,
+                gate_id,
+                constraint_index,
+                value,
+                context,
+            } => write!(
+                f,
+                "constraint {} of {} in row {} evaluates to {} (in {})",
+                constraint_index, gate_id, row, value, context
+            ),
+        }
+    }
+}
+
+/// Runs witness generation on `inputs`, then checks that the resulting witness satisfies every
+/// copy constraint, lookup and gate constraint of the circuit. Returns all failures found.
+///
+/// This is meant for debugging: it is much slower than it needs to be, but is still cheaper than
+/// proving, and it pinpoints the failing rows.
+pub fn check_witness<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>(
+    prover_data: &ProverOnlyCircuitData<F, C, D>,
+    common_data: &CommonCircuitData<F, D>,
+    inputs: PartialWitness<F>,
+) -> Result<(), Vec<WitnessCheckFailure<F>>> {
+    let mut conflicts = Vec::new();
+    let witness = generate_partial_witness_with_conflicts(
+        inputs,
+        prover_data,
+        common_data,
+        Some(&mut conflicts),
+    );
+
+    let mut failures: Vec<_> = conflicts
+        .into_iter()
+        .map(
+            |(target, existing_value, new_value)| WitnessCheckFailure::CopyConstraint {
+                target,
+                existing_value,
+                new_value,
+                context: match target {
+                    Target::Wire(wire) => prover_data.gate_context(wire.row).to_string(),
+                    Target::VirtualTarget { .. } => String::new(),
+                },
+            },
+        )
+        .collect();
+
+    let mut witness = match witness {
+        Ok(witness) => witness,
+        Err(err) => {
+            failures.push(WitnessCheckFailure::Stalled(err));
+            return Err(failures);
+        }
+    };
+
+    let mut lookups_ok = true;
+    for (lut_index, lookups) in prover_data.lut_to_lookups.iter().enumerate() {
+        let lut = &common_data.luts[lut_index];
+        for (input_targets, output_target) in lookups {
+            let inputs = witness.get_targets(input_targets);
+            let output = witness.get_target(*output_target);
+            let found = lut
+                .find(&inputs)
+                .is_some_and(|row| lut.row(row)[lut.num_inputs()] == output.to_canonical_u64());
+            if !found {
+                lookups_ok = false;
+                failures.push(WitnessCheckFailure::Lookup {
+                    lut_index,
+                    inputs,
+                    output,
+                });
+            }
+        }
+    }
+    // Multiplicities can only be computed if every lookup is in its table.
+    if lookups_ok {
+        set_lookup_wires(prover_data, common_data, &mut witness);
+    }
+
+    let public_inputs = witness.get_targets(&prover_data.public_inputs);
+    let public_inputs_hash = C::InnerHasher::hash_no_pad(&public_inputs);
+    let wire_values = witness.full_witness().wire_values;
+    let constant_values: Vec<Vec<F>> = prover_data.constants_sigmas_commitment.polynomials
+        [..common_data.num_constants]
+        .iter()
+        .map(|poly| poly.clone().fft().values)
+        .collect();
+
+    let num_selectors = common_data.selectors_info.num_selectors();
+    for row in 0..common_data.degree() {
+        let local_constants: Vec<F> = constant_values.iter().map(|c| c[row]).collect();
+        let local_wires: Vec<F> = wire_values.iter().map(|w| w[row]).collect();
+
+        // Each row's gate is the one whose selector takes the value of its index.
+        let gate = common_data.gates.iter().enumerate().find(|&(i, _)| {
+            let selector_index = common_data.selectors_info.selector_indices[i];
+            local_constants[selector_index] == F::from_canonical_usize(i)
+        });
+        let gate = match gate {
+            Some((_, gate)) => gate,
+            None => continue,
+        };
+
+        let vars = EvaluationVarsBaseBatch::new(
+            1,
+            &local_constants[num_selectors + common_data.num_lookup_selectors..],
+            &local_wires,
+            &public_inputs_hash,
+        );
+        for (constraint_index, value) in gate
+            .0
+            .eval_unfiltered_base_batch(vars)
+            .into_iter()
+            .enumerate()
+        {
+            if value != F::ZERO {
+                failures.push(WitnessCheckFailure::Gate {
+                    row,
+                    gate_id: gate.0.id(),
+                    constraint_index,
+                    value,
+                    context: prover_data.gate_context(row).to_string(),
+                });
+            }
+        }
+    }
+
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(failures)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::sync::Arc;
+    use alloc::vec;
+
+    use super::*;
+    use crate::iop::witness::WitnessWrite;
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::PoseidonGoldilocksConfig;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    #[test]
+    fn test_check_witness() {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let x = builder.add_virtual_target();
+        builder.push_context(log::Level::Debug, "square");
+        let y = builder.mul(x, x);
+        builder.pop_context();
+        builder.register_public_input(y);
+        let data = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_target(x, F::from_canonical_u64(3));
+        assert_eq!(data.check_witness(pw), Ok(()));
+
+        // Force the wrong square: the generator's value conflicts with it, and the arithmetic
+        // constraint no longer holds.
+        let mut pw = PartialWitness::new();
+        pw.set_target(x, F::from_canonical_u64(3));
+        pw.set_target(y, F::from_canonical_u64(10));
+        let failures = data.check_witness(pw).unwrap_err();
+
+        assert!(failures.iter().any(|failure| matches!(
+            failure,
+            WitnessCheckFailure::CopyConstraint { existing_value, new_value, context, .. }
+                if *existing_value == F::from_canonical_u64(10)
+                    && *new_value == F::from_canonical_u64(9)
+                    && context == "root > square"
+        )));
+        let gate_failures: Vec<_> = failures
+            .iter()
+            .filter_map(|failure| match failure {
+                WitnessCheckFailure::Gate {
+                    gate_id, context, ..
+                } => Some((gate_id, context)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(gate_failures.len(), 1);
+        assert!(gate_failures[0].0.starts_with("ArithmeticGate"));
+        assert_eq!(gate_failures[0].1, "root > square");
+    }
+
+    #[test]
+    fn test_check_witness_lookup() {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let table = Arc::new(vec![(0u16, 1u16), (1, 2), (2, 3)]);
+        let lut_index = builder.add_lookup_table_from_pairs(table);
+        let x = builder.add_virtual_target();
+        let out = builder.add_lookup_from_index(x, lut_index);
+        let data = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_target(x, F::ONE);
+        assert_eq!(data.check_witness(pw), Ok(()));
+
+        let mut pw = PartialWitness::new();
+        pw.set_target(x, F::ONE);
+        pw.set_target(out, F::from_canonical_u64(5));
+        let failures = data.check_witness(pw).unwrap_err();
+        assert!(failures.contains(&WitnessCheckFailure::Lookup {
+            lut_index,
+            inputs: vec![F::ONE],
+            output: F::from_canonical_u64(5),
+        }));
+    }
+}
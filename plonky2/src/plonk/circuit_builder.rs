@@ -21,12 +21,13 @@ use crate::fri::{FriConfig, FriParams};
 use crate::gadgets::arithmetic::BaseArithmeticOperation;
 use crate::gadgets::arithmetic_extension::ExtensionArithmeticOperation;
 use crate::gadgets::polynomial::PolynomialCoeffsExtTarget;
+use crate::gadgets::uint32::U32Lut;
 use crate::gates::arithmetic_base::ArithmeticGate;
 use crate::gates::arithmetic_extension::ArithmeticExtensionGate;
 use crate::gates::constant::ConstantGate;
 use crate::gates::gate::{CurrentSlot, Gate, GateInstance, GateRef};
 use crate::gates::lookup::{Lookup, LookupGate};
-use crate::gates::lookup_table::LookupTable;
+use crate::gates::lookup_table::{lookup_width, LookupTable, WideLookupTable};
 use crate::gates::noop::NoopGate;
 use crate::gates::public_input::PublicInputGate;
 use crate::gates::selectors::{selector_ends_lookups, selector_polynomials, selectors_lookup};
@@ -41,7 +42,8 @@ use crate::iop::target::{BoolTarget, Target};
 use crate::iop::wire::Wire;
 use crate::plonk::circuit_data::{
     CircuitConfig, CircuitData, CommonCircuitData, MockCircuitData, ProverCircuitData,
-    ProverOnlyCircuitData, VerifierCircuitData, VerifierCircuitTarget, VerifierOnlyCircuitData,
+    ProverOnlyCircuitData, VerifierCircuitData, VerifierCircuitTarget, VerifierDataSetTarget,
+    VerifierOnlyCircuitData,
 };
 use crate::plonk::config::{AlgebraicHasher, GenericConfig, GenericHashOut, Hasher};
 use crate::plonk::copy_constraint::CopyConstraint;
@@ -103,7 +105,7 @@ pub struct CircuitBuilder<F: RichField + Extendable<D>, const D: usize> {
     /// The next available index for a `VirtualTarget`.
     virtual_target_index: usize,
 
-    copy_constraints: Vec<CopyConstraint>,
+    pub(crate) copy_constraints: Vec<CopyConstraint>,
 
     /// A tree of named scopes, used for debugging.
     context_log: ContextTree,
@@ -132,8 +134,11 @@ pub struct CircuitBuilder<F: RichField + Extendable<D>, const D: usize> {
     /// For each LUT index, vector of `(looking_in, looking_out)` pairs.
     lut_to_lookups: Vec<Lookup>,
 
-    // Lookup tables in the form of `Vec<(input_value, output_value)>`.
-    luts: Vec<LookupTable>,
+    // Lookup tables, whose rows are the inputs followed by the output.
+    luts: Vec<Arc<WideLookupTable>>,
+
+    /// Memoized indices of the lookup tables used by the `u32` gadgets.
+    pub(crate) u32_luts: HashMap<U32Lut, usize>,
 
     /// Optional common data. When it is `Some(goal_data)`, the `build` function panics if the resulting
     /// common data doesn't equal `goal_data`.
@@ -143,6 +148,10 @@ pub struct CircuitBuilder<F: RichField + Extendable<D>, const D: usize> {
     /// Optional verifier data that is registered as public inputs.
     /// This is used in cyclic recursion to hold the circuit's own verifier key.
     pub(crate) verifier_data_public_input: Option<VerifierCircuitTarget>,
+
+    /// Optional verifier data of a set of circuits, whose digest is registered as public inputs.
+    /// This is used in cyclic recursion over several circuits to hold the verifier keys of the set.
+    pub(crate) verifier_data_set_public_input: Option<VerifierDataSetTarget>,
 }
 
 impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
@@ -166,8 +175,10 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
             lookup_rows: Vec::new(),
             lut_to_lookups: Vec::new(),
             luts: Vec::new(),
+            u32_luts: HashMap::new(),
             goal_common_data: None,
             verifier_data_public_input: None,
+            verifier_data_set_public_input: None,
         };
         builder.check_config();
         builder
@@ -233,8 +244,13 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         });
     }
 
-    /// Adds a looking (input, output) pair to the corresponding LUT.
-    pub fn update_lookups(&mut self, looking_in: Target, looking_out: Target, lut_index: usize) {
+    /// Adds a looking (inputs, output) pair to the corresponding LUT.
+    pub fn update_lookups(
+        &mut self,
+        looking_in: Vec<Target>,
+        looking_out: Target,
+        lut_index: usize,
+    ) {
         assert!(
             lut_index < self.lut_to_lookups.len(),
             "The LUT with index {} has not been created. The last LUT is at index {}",
@@ -248,7 +264,7 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         self.lut_to_lookups.len()
     }
 
-    pub fn get_lut_lookups(&self, lut_index: usize) -> &[(Target, Target)] {
+    pub fn get_lut_lookups(&self, lut_index: usize) -> &[(Vec<Target>, Target)] {
         &self.lut_to_lookups[lut_index]
     }
 
@@ -588,12 +604,12 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
     }
 
     /// Checks whether a LUT is already stored in `self.luts`
-    pub fn is_stored(&self, lut: LookupTable) -> Option<usize> {
-        self.luts.iter().position(|elt| *elt == lut)
+    pub fn is_stored(&self, lut: &WideLookupTable) -> Option<usize> {
+        self.luts.iter().position(|elt| **elt == *lut)
     }
 
     /// Returns the LUT at index `idx`.
-    pub fn get_lut(&self, idx: usize) -> LookupTable {
+    pub fn get_lut(&self, idx: usize) -> Arc<WideLookupTable> {
         assert!(
             idx < self.luts.len(),
             "index idx: {} greater than the total number of created LUTS: {}",
@@ -603,6 +619,11 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         self.luts[idx].clone()
     }
 
+    /// The number of columns of every lookup slot, given the LUTs added so far.
+    pub fn get_lookup_width(&self) -> usize {
+        lookup_width(&self.luts)
+    }
+
     /// Generates a LUT from a function.
     pub fn get_lut_from_fn<T>(f: fn(T) -> T, inputs: &[T]) -> Vec<(T, T)>
     where
@@ -613,17 +634,8 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
 
     /// Given a function `f: fn(u16) -> u16`, adds a LUT to the circuit builder.
     pub fn update_luts_from_fn(&mut self, f: fn(u16) -> u16, inputs: &[u16]) -> usize {
-        let lut = Arc::new(Self::get_lut_from_fn::<u16>(f, inputs));
-
-        // If the LUT `lut` is already stored in `self.luts`, return its index. Otherwise, append `table` to `self.luts` and return its index.
-        if let Some(idx) = self.is_stored(lut.clone()) {
-            idx
-        } else {
-            self.luts.push(lut);
-            self.lut_to_lookups.push(vec![]);
-            assert!(self.luts.len() == self.lut_to_lookups.len());
-            self.luts.len() - 1
-        }
+        let lut = Self::get_lut_from_fn::<u16>(f, inputs);
+        self.update_wide_luts(lut.as_slice().into())
     }
 
     /// Adds a table to the vector of LUTs in the circuit builder, given a list of inputs and table values.
@@ -634,31 +646,26 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
             inputs.len(),
             table.len()
         );
-        let pairs = inputs
+        let pairs: Vec<_> = inputs
             .iter()
             .copied()
             .zip_eq(table.iter().copied())
             .collect();
-        let lut: LookupTable = Arc::new(pairs);
-
-        // If the LUT `lut` is already stored in `self.luts`, return its index. Otherwise, append `table` to `self.luts` and return its index.
-        if let Some(idx) = self.is_stored(lut.clone()) {
-            idx
-        } else {
-            self.luts.push(lut);
-            self.lut_to_lookups.push(vec![]);
-            assert!(self.luts.len() == self.lut_to_lookups.len());
-            self.luts.len() - 1
-        }
+        self.update_wide_luts(pairs.as_slice().into())
     }
 
     /// Adds a table to the vector of LUTs in the circuit builder.
     pub fn update_luts_from_pairs(&mut self, table: LookupTable) -> usize {
+        self.update_wide_luts(table.into())
+    }
+
+    /// Adds a table with any number of input columns to the vector of LUTs in the circuit builder.
+    pub fn update_wide_luts(&mut self, table: WideLookupTable) -> usize {
         // If the LUT `table` is already stored in `self.luts`, return its index. Otherwise, append `table` to `self.luts` and return its index.
-        if let Some(idx) = self.is_stored(table.clone()) {
+        if let Some(idx) = self.is_stored(&table) {
             idx
         } else {
-            self.luts.push(table);
+            self.luts.push(Arc::new(table));
             self.lut_to_lookups.push(vec![]);
             assert!(self.luts.len() == self.lut_to_lookups.len());
             self.luts.len() - 1
@@ -1094,7 +1101,10 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
             0
         } else {
             // There is 1 RE polynomial and multiple Sum/LDC polynomials.
-            ceil_div_usize(LookupGate::num_slots(&self.config), lookup_degree) + 1
+            ceil_div_usize(
+                LookupGate::num_slots(&self.config, self.get_lookup_width()),
+                lookup_degree,
+            ) + 1
         };
         let constants_sigmas_cap = constants_sigmas_commitment.merkle_tree.cap.clone();
         let domain_separator = self.domain_separator.unwrap_or_default();
@@ -1141,6 +1151,7 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
             circuit_digest,
             lookup_rows: self.lookup_rows.clone(),
             lut_to_lookups: self.lut_to_lookups.clone(),
+            gate_contexts: self.context_log.gate_contexts(),
         };
 
         let verifier_only = VerifierOnlyCircuitData::<C, D> {
@@ -1,4 +1,6 @@
 use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::sync::Arc;
 use alloc::vec;
 use alloc::vec::Vec;
 use core::ops::{Range, RangeFrom};
@@ -19,14 +21,17 @@ use crate::fri::structure::{
 use crate::fri::{FriConfig, FriParams};
 use crate::gates::gate::GateRef;
 use crate::gates::lookup::Lookup;
-use crate::gates::lookup_table::LookupTable;
+use crate::gates::lookup_table::{lookup_width, WideLookupTable};
 use crate::gates::selectors::SelectorsInfo;
 use crate::hash::hash_types::{HashOutTarget, MerkleCapTarget, RichField};
 use crate::hash::merkle_tree::MerkleCap;
 use crate::iop::ext_target::ExtensionTarget;
-use crate::iop::generator::{generate_partial_witness, WitnessGeneratorRef};
+use crate::iop::generator::{
+    generate_partial_witness, WitnessGenerationError, WitnessGeneratorRef,
+};
 use crate::iop::target::Target;
 use crate::iop::witness::{PartialWitness, PartitionWitness};
+use crate::plonk::check_witness::{check_witness, WitnessCheckFailure};
 use crate::plonk::circuit_builder::CircuitBuilder;
 use crate::plonk::config::{GenericConfig, Hasher};
 use crate::plonk::plonk_common::PlonkOracle;
@@ -122,7 +127,10 @@ pub struct MockCircuitData<F: RichField + Extendable<D>, C: GenericConfig<D, F =
 impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
     MockCircuitData<F, C, D>
 {
-    pub fn generate_witness(&self, inputs: PartialWitness<F>) -> PartitionWitness<F> {
+    pub fn generate_witness(
+        &self,
+        inputs: PartialWitness<F>,
+    ) -> Result<PartitionWitness<F>, WitnessGenerationError> {
         generate_partial_witness::<F, C, D>(inputs, &self.prover_only, &self.common)
     }
 }
@@ -166,6 +174,15 @@ impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
         )
     }
 
+    /// Runs witness generation and checks every constraint of the circuit directly against the
+    /// resulting witness, without generating a proof. See `check_witness` for details.
+    pub fn check_witness(
+        &self,
+        inputs: PartialWitness<F>,
+    ) -> Result<(), Vec<WitnessCheckFailure<F>>> {
+        check_witness::<F, C, D>(&self.prover_only, &self.common, inputs)
+    }
+
     pub fn verify(&self, proof_with_pis: ProofWithPublicInputs<F, C, D>) -> Result<()> {
         verify::<F, C, D>(proof_with_pis, &self.verifier_only, &self.common)
     }
@@ -262,6 +279,15 @@ impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
             &mut TimingTree::default(),
         )
     }
+
+    /// Runs witness generation and checks every constraint of the circuit directly against the
+    /// resulting witness, without generating a proof. See `check_witness` for details.
+    pub fn check_witness(
+        &self,
+        inputs: PartialWitness<F>,
+    ) -> Result<(), Vec<WitnessCheckFailure<F>>> {
+        check_witness::<F, C, D>(&self.prover_only, &self.common, inputs)
+    }
 }
 
 /// Circuit data required by the prover.
@@ -335,6 +361,9 @@ pub struct ProverOnlyCircuitData<
     pub lookup_rows: Vec<LookupWire>,
     /// A vector of (looking_in, looking_out) pairs for for each lookup table index.
     pub lut_to_lookups: Vec<Lookup>,
+    /// The `push_context` scopes that were open when each gate was added, as `(first_row, path)`
+    /// pairs sorted by row. Used to make witness-checking failures easier to locate.
+    pub gate_contexts: Vec<(usize, String)>,
 }
 
 impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
@@ -358,6 +387,18 @@ impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
         let mut buffer = Buffer::new(bytes);
         buffer.read_prover_only_circuit_data(generator_serializer, common_data)
     }
+
+    /// The stack of `push_context` scopes that were open when the gate in `row` was added.
+    pub fn gate_context(&self, row: usize) -> &str {
+        let i = self
+            .gate_contexts
+            .partition_point(|&(first_row, _)| first_row <= row);
+        if i == 0 {
+            ""
+        } else {
+            &self.gate_contexts[i - 1].1
+        }
+    }
 }
 
 /// Circuit data required by the verifier, but not the prover.
@@ -420,7 +461,7 @@ pub struct CommonCircuitData<F: RichField + Extendable<D>, const D: usize> {
     pub num_lookup_selectors: usize,
 
     /// The stored lookup tables.
-    pub luts: Vec<LookupTable>,
+    pub luts: Vec<Arc<WideLookupTable>>,
 }
 
 impl<F: RichField + Extendable<D>, const D: usize> CommonCircuitData<F, D> {
@@ -446,6 +487,11 @@ impl<F: RichField + Extendable<D>, const D: usize> CommonCircuitData<F, D> {
         1 << self.degree_bits()
     }
 
+    /// The number of columns of every lookup slot, i.e. the width of the widest lookup table.
+    pub fn lookup_width(&self) -> usize {
+        lookup_width(&self.luts)
+    }
+
     pub fn lde_size(&self) -> usize {
         self.fri_params.lde_size()
     }
@@ -646,3 +692,14 @@ pub struct VerifierCircuitTarget {
     /// seed Fiat-Shamir.
     pub circuit_digest: HashOutTarget,
 }
+
+/// The verifier data of a set of mutually recursive circuits, together with its digest, which is
+/// registered as public inputs. This is used in cyclic recursion over several circuits, where a
+/// proof may verify earlier proofs of any circuit in the set.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VerifierDataSetTarget {
+    /// The verifier data of each circuit in the set.
+    pub verifier_data: Vec<VerifierCircuitTarget>,
+    /// A hash of all the verifier data in the set.
+    pub digest: HashOutTarget,
+}
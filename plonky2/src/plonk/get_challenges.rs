@@ -1,13 +1,10 @@
 use alloc::vec;
 use alloc::vec::Vec;
 
-use hashbrown::HashSet;
-
 use super::circuit_builder::NUM_COINS_LOOKUP;
 use crate::field::extension::Extendable;
 use crate::field::polynomial::PolynomialCoeffs;
-use crate::fri::proof::{CompressedFriProof, FriChallenges, FriProof, FriProofTarget};
-use crate::fri::verifier::{compute_evaluation, fri_combine_initial, PrecomputedReducedOpenings};
+use crate::fri::proof::{CompressedFriProof, FriProof, FriProofTarget};
 use crate::gadgets::polynomial::PolynomialCoeffsExtTarget;
 use crate::hash::hash_types::{HashOutTarget, MerkleCapTarget, RichField};
 use crate::hash::merkle_tree::MerkleCap;
@@ -21,7 +18,6 @@ use crate::plonk::proof::{
     OpeningSetTarget, Proof, ProofChallenges, ProofChallengesTarget, ProofTarget,
     ProofWithPublicInputs, ProofWithPublicInputsTarget,
 };
-use crate::util::reverse_bits;
 
 fn get_challenges<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>(
     public_inputs_hash: <<C as GenericConfig<D>>::InnerHasher as Hasher<F>>::Hash,
@@ -183,73 +179,12 @@ impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
         challenges: &ProofChallenges<F, D>,
         common_data: &CommonCircuitData<F, D>,
     ) -> FriInferredElements<F, D> {
-        let ProofChallenges {
-            plonk_zeta,
-            fri_challenges:
-                FriChallenges {
-                    fri_alpha,
-                    fri_betas,
-                    fri_query_indices,
-                    ..
-                },
-            ..
-        } = challenges;
-        let mut fri_inferred_elements = Vec::new();
-        // Holds the indices that have already been seen at each reduction depth.
-        let mut seen_indices_by_depth =
-            vec![HashSet::new(); common_data.fri_params.reduction_arity_bits.len()];
-        let precomputed_reduced_evals = PrecomputedReducedOpenings::from_os_and_alpha(
+        self.proof.opening_proof.get_inferred_elements::<C>(
+            &common_data.get_fri_instance(challenges.plonk_zeta),
             &self.proof.openings.to_fri_openings(),
-            *fri_alpha,
-        );
-        let log_n = common_data.degree_bits() + common_data.config.fri_config.rate_bits;
-        // Simulate the proof verification and collect the inferred elements.
-        // The content of the loop is basically the same as the `fri_verifier_query_round` function.
-        for &(mut x_index) in fri_query_indices {
-            let mut subgroup_x = F::MULTIPLICATIVE_GROUP_GENERATOR
-                * F::primitive_root_of_unity(log_n).exp_u64(reverse_bits(x_index, log_n) as u64);
-            let mut old_eval = fri_combine_initial::<F, C, D>(
-                &common_data.get_fri_instance(*plonk_zeta),
-                &self
-                    .proof
-                    .opening_proof
-                    .query_round_proofs
-                    .initial_trees_proofs[&x_index],
-                *fri_alpha,
-                subgroup_x,
-                &precomputed_reduced_evals,
-                &common_data.fri_params,
-            );
-            for (i, &arity_bits) in common_data
-                .fri_params
-                .reduction_arity_bits
-                .iter()
-                .enumerate()
-            {
-                let coset_index = x_index >> arity_bits;
-                if !seen_indices_by_depth[i].insert(coset_index) {
-                    // If this index has already been seen, we can skip the rest of the reductions.
-                    break;
-                }
-                fri_inferred_elements.push(old_eval);
-                let arity = 1 << arity_bits;
-                let mut evals = self.proof.opening_proof.query_round_proofs.steps[i][&coset_index]
-                    .evals
-                    .clone();
-                let x_index_within_coset = x_index & (arity - 1);
-                evals.insert(x_index_within_coset, old_eval);
-                old_eval = compute_evaluation(
-                    subgroup_x,
-                    x_index_within_coset,
-                    arity_bits,
-                    &evals,
-                    fri_betas[i],
-                );
-                subgroup_x = subgroup_x.exp_power_of_2(arity_bits);
-                x_index = coset_index;
-            }
-        }
-        FriInferredElements(fri_inferred_elements)
+            &challenges.fri_challenges,
+            &common_data.fri_params,
+        )
     }
 }
 
@@ -1,3 +1,4 @@
+pub mod check_witness;
 pub mod circuit_builder;
 pub mod circuit_data;
 pub mod config;
@@ -7,7 +8,7 @@ pub(crate) mod permutation_argument;
 pub mod plonk_common;
 pub mod proof;
 pub mod prover;
-mod validate_shape;
+pub(crate) mod validate_shape;
 pub(crate) mod vanishing_poly;
 pub mod vars;
 pub mod verifier;
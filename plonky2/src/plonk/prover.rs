@@ -4,7 +4,6 @@ use core::cmp::min;
 use core::mem::swap;
 
 use anyhow::{ensure, Result};
-use hashbrown::HashMap;
 use plonky2_maybe_rayon::*;
 
 use super::circuit_builder::{LookupChallenges, LookupWire};
@@ -24,7 +23,7 @@ use crate::iop::witness::{MatrixWitness, PartialWitness, PartitionWitness, Witne
 use crate::plonk::circuit_builder::NUM_COINS_LOOKUP;
 use crate::plonk::circuit_data::{CommonCircuitData, ProverOnlyCircuitData};
 use crate::plonk::config::{GenericConfig, Hasher};
-use crate::plonk::plonk_common::PlonkOracle;
+use crate::plonk::plonk_common::{reduce_with_powers, PlonkOracle};
 use crate::plonk::proof::{OpeningSet, Proof, ProofWithPublicInputs};
 use crate::plonk::vanishing_poly::{eval_vanishing_poly_base_batch, get_lut_poly};
 use crate::plonk::vars::EvaluationVarsBaseBatch;
@@ -54,40 +53,34 @@ pub fn set_lookup_wires<
         },
     ) in prover_data.lookup_rows.iter().enumerate()
     {
-        let lut_len = common_data.luts[lut_index].len();
-        let num_entries = LookupGate::num_slots(&common_data.config);
-        let num_lut_entries = LookupTableGate::num_slots(&common_data.config);
+        let lut = &common_data.luts[lut_index];
+        let lut_len = lut.len();
+        let width = common_data.lookup_width();
+        let num_entries = LookupGate::num_slots(&common_data.config, width);
+        let num_lut_entries = LookupTableGate::num_slots(&common_data.config, width);
 
         // Compute multiplicities.
         let mut multiplicities = vec![0; lut_len];
 
-        let table_value_to_idx: HashMap<u16, usize> = common_data.luts[lut_index]
-            .iter()
-            .enumerate()
-            .map(|(i, (inp_target, _))| (*inp_target, i))
-            .collect();
-
-        for (inp_target, _) in prover_data.lut_to_lookups[lut_index].iter() {
-            let inp_value = pw.get_target(*inp_target);
-            let idx = table_value_to_idx
-                .get(&u16::try_from(inp_value.to_canonical_u64()).unwrap())
-                .unwrap();
+        for (inp_targets, _) in prover_data.lut_to_lookups[lut_index].iter() {
+            let inp_values = pw.get_targets(inp_targets);
+            let idx = lut.find(&inp_values).unwrap();
 
-            multiplicities[*idx] += 1;
+            multiplicities[idx] += 1;
         }
 
         // Pad the last `LookupGate` with the first entry from the LUT.
         let remaining_slots = (num_entries
             - (prover_data.lut_to_lookups[lut_index].len() % num_entries))
             % num_entries;
-        let (first_inp_value, first_out_value) = common_data.luts[lut_index][0];
         for slot in (num_entries - remaining_slots)..num_entries {
-            let inp_target =
-                Target::wire(last_lut_gate - 1, LookupGate::wire_ith_looking_inp(slot));
-            let out_target =
-                Target::wire(last_lut_gate - 1, LookupGate::wire_ith_looking_out(slot));
-            pw.set_target(inp_target, F::from_canonical_u16(first_inp_value));
-            pw.set_target(out_target, F::from_canonical_u16(first_out_value));
+            for (col, value) in lut.padded_row(0, width).enumerate() {
+                let target = Target::wire(
+                    last_lut_gate - 1,
+                    LookupGate::wire_ith_looking_col(width, slot, col),
+                );
+                pw.set_target(target, value);
+            }
 
             multiplicities[0] += 1;
         }
@@ -97,7 +90,7 @@ pub fn set_lookup_wires<
             let row = first_lut_gate - lut_entry / num_lut_entries;
             let col = lut_entry % num_lut_entries;
 
-            let mul_target = Target::wire(row, LookupTableGate::wire_ith_multiplicity(col));
+            let mul_target = Target::wire(row, LookupTableGate::wire_ith_multiplicity(width, col));
 
             pw.set_target(
                 mul_target,
@@ -121,7 +114,8 @@ where
         timing,
         &format!("run {} generators", prover_data.generators.len()),
         generate_partial_witness(inputs, prover_data, common_data)
-    );
+    )
+    .map_err(anyhow::Error::msg)?;
 
     prove_with_partition_witness(prover_data, common_data, partition_witness, timing)
 }
@@ -458,10 +452,11 @@ fn compute_lookup_polys<
     common_data: &CommonCircuitData<F, D>,
 ) -> Vec<PolynomialValues<F>> {
     let degree = common_data.degree();
-    let num_lu_slots = LookupGate::num_slots(&common_data.config);
+    let width = common_data.lookup_width();
+    let num_lu_slots = LookupGate::num_slots(&common_data.config, width);
     let max_lookup_degree = common_data.config.max_quotient_degree_factor - 1;
     let num_partial_lookups = ceil_div_usize(num_lu_slots, max_lookup_degree);
-    let num_lut_slots = LookupTableGate::num_slots(&common_data.config);
+    let num_lut_slots = LookupTableGate::num_slots(&common_data.config, width);
     let max_lookup_table_degree = ceil_div_usize(num_lut_slots, num_partial_lookups);
 
     // First poly is RE, the rest are partial SLDCs.
@@ -479,12 +474,19 @@ fn compute_lookup_polys<
         // Set values for partial Sums and RE.
         for row in (last_lut_row..(first_lut_row + 1)).rev() {
             // Get combos for Sum.
+            let looked_entry = |s| {
+                (0..width)
+                    .map(|col| {
+                        witness.get_wire(row, LookupTableGate::wire_ith_looked_col(width, s, col))
+                    })
+                    .collect::<Vec<_>>()
+            };
             let looked_combos: Vec<F> = (0..num_lut_slots)
                 .map(|s| {
-                    let looked_inp = witness.get_wire(row, LookupTableGate::wire_ith_looked_inp(s));
-                    let looked_out = witness.get_wire(row, LookupTableGate::wire_ith_looked_out(s));
-
-                    looked_inp + deltas[LookupChallenges::ChallengeA as usize] * looked_out
+                    reduce_with_powers(
+                        &looked_entry(s),
+                        deltas[LookupChallenges::ChallengeA as usize],
+                    )
                 })
                 .collect();
             // Get (alpha - combo).
@@ -497,10 +499,10 @@ fn compute_lookup_polys<
             // Get lookup combos, used to check the well formation of the LUT.
             let lookup_combos: Vec<F> = (0..num_lut_slots)
                 .map(|s| {
-                    let looked_inp = witness.get_wire(row, LookupTableGate::wire_ith_looked_inp(s));
-                    let looked_out = witness.get_wire(row, LookupTableGate::wire_ith_looked_out(s));
-
-                    looked_inp + deltas[LookupChallenges::ChallengeB as usize] * looked_out
+                    reduce_with_powers(
+                        &looked_entry(s),
+                        deltas[LookupChallenges::ChallengeB as usize],
+                    )
                 })
                 .collect();
 
@@ -522,7 +524,8 @@ fn compute_lookup_polys<
                 let sum = (slot * max_lookup_table_degree
                     ..min((slot + 1) * max_lookup_table_degree, num_lut_slots))
                     .fold(prev, |acc, s| {
-                        acc + witness.get_wire(row, LookupTableGate::wire_ith_multiplicity(s))
+                        acc + witness
+                            .get_wire(row, LookupTableGate::wire_ith_multiplicity(width, s))
                             * looked_combo_inverses[s]
                     });
                 final_poly_vecs[slot + 1].values[row] = sum;
@@ -534,10 +537,15 @@ fn compute_lookup_polys<
             // Get looking combos.
             let looking_combos: Vec<F> = (0..num_lu_slots)
                 .map(|s| {
-                    let looking_in = witness.get_wire(row, LookupGate::wire_ith_looking_inp(s));
-                    let looking_out = witness.get_wire(row, LookupGate::wire_ith_looking_out(s));
-
-                    looking_in + deltas[LookupChallenges::ChallengeA as usize] * looking_out
+                    let looking_entry: Vec<F> = (0..width)
+                        .map(|col| {
+                            witness.get_wire(row, LookupGate::wire_ith_looking_col(width, s, col))
+                        })
+                        .collect();
+                    reduce_with_powers(
+                        &looking_entry,
+                        deltas[LookupChallenges::ChallengeA as usize],
+                    )
                 })
                 .collect();
             // Get (alpha - combo).
@@ -642,7 +650,8 @@ fn compute_quotient_polys<
     // and are the same each time in check_lookup_constraints_batched.
     // lut_poly_evals[i][j] gives the eval for the i'th challenge and the j'th lookup table
     let lut_re_poly_evals: Vec<Vec<F>> = if has_lookup {
-        let num_lut_slots = LookupTableGate::num_slots(&common_data.config);
+        let num_lut_slots =
+            LookupTableGate::num_slots(&common_data.config, common_data.lookup_width());
         (0..num_challenges)
             .map(move |i| {
                 let cur_deltas = &deltas[NUM_COINS_LOOKUP * i..NUM_COINS_LOOKUP * (i + 1)];
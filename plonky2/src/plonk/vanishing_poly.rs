@@ -35,10 +35,13 @@ pub(crate) fn get_lut_poly<F: RichField + Extendable<D>, const D: usize>(
     degree: usize,
 ) -> PolynomialCoeffs<F> {
     let b = deltas[LookupChallenges::ChallengeB as usize];
-    let mut coeffs = Vec::with_capacity(common_data.luts[lut_index].len());
-    let n = common_data.luts[lut_index].len();
-    for (input, output) in common_data.luts[lut_index].iter() {
-        coeffs.push(F::from_canonical_u16(*input) + b * F::from_canonical_u16(*output));
+    let width = common_data.lookup_width();
+    let lut = &common_data.luts[lut_index];
+    let n = lut.len();
+    let mut coeffs = Vec::with_capacity(n);
+    for i in 0..n {
+        let row: Vec<F> = lut.padded_row(i, width).collect();
+        coeffs.push(plonk_common::reduce_with_powers(&row, b));
     }
     coeffs.append(&mut vec![F::ZERO; degree - n]);
     coeffs.reverse();
@@ -328,8 +331,8 @@ pub(crate) fn eval_vanishing_poly_base_batch<F: RichField + Extendable<D>, const
 ///
 /// There are three polynomials to check:
 /// - RE ensures the well formation of lookup tables;
-/// - Sum is a running sum of m_i/(X - (input_i + a * output_i)) where (input_i, output_i) are input pairs in the lookup table (LUT);
-/// - LDC is a running sum of 1/(X - (input_i + a * output_i)) where (input_i, output_i) are input pairs that look in the LUT.
+/// - Sum is a running sum of m_i/(X - combo_i) where combo_i = sum_j a^j col_ij is the combination of the columns (inputs, then output) of the i-th entry of the lookup table (LUT);
+/// - LDC is a running sum of 1/(X - combo_i) where combo_i is the combination of the columns of the i-th tuple that looks in the LUT.
 /// Sum and LDC are broken down in partial polynomials to lower the constraint degree, similarly to the permutation argument.
 /// They also share the same partial SLDC polynomials, so that the last SLDC value is Sum(end) - LDC(end). The final constraint
 /// Sum(end) = LDC(end) becomes simply SLDC(end) = 0, and we can remove the LDC initial constraint.
@@ -341,8 +344,9 @@ pub fn check_lookup_constraints<F: RichField + Extendable<D>, const D: usize>(
     lookup_selectors: &[F::Extension],
     deltas: &[F; 4],
 ) -> Vec<F::Extension> {
-    let num_lu_slots = LookupGate::num_slots(&common_data.config);
-    let num_lut_slots = LookupTableGate::num_slots(&common_data.config);
+    let width = common_data.lookup_width();
+    let num_lu_slots = LookupGate::num_slots(&common_data.config, width);
+    let num_lut_slots = LookupTableGate::num_slots(&common_data.config, width);
     let lu_degree = common_data.quotient_degree_factor - 1;
     let num_sldc_polys = local_lookup_zs.len() - 1;
     let lut_degree = ceil_div_usize(num_lut_slots, num_sldc_polys);
@@ -360,30 +364,30 @@ pub fn check_lookup_constraints<F: RichField + Extendable<D>, const D: usize>(
     let delta_challenge_a = F::Extension::from(deltas[LookupChallenges::ChallengeA as usize]);
     let delta_challenge_b = F::Extension::from(deltas[LookupChallenges::ChallengeB as usize]);
 
+    // The entries of each slot, combined with powers of a challenge.
+    let looked_entry = |s| -> Vec<F::Extension> {
+        (0..width)
+            .map(|col| vars.local_wires[LookupTableGate::wire_ith_looked_col(width, s, col)])
+            .collect()
+    };
+    let looking_entry = |s| -> Vec<F::Extension> {
+        (0..width)
+            .map(|col| vars.local_wires[LookupGate::wire_ith_looking_col(width, s, col)])
+            .collect()
+    };
+
     // Compute all current looked and looking combos, i.e. the combos we need for the SLDC polynomials.
     let current_looked_combos: Vec<F::Extension> = (0..num_lut_slots)
-        .map(|s| {
-            let input_wire = vars.local_wires[LookupTableGate::wire_ith_looked_inp(s)];
-            let output_wire = vars.local_wires[LookupTableGate::wire_ith_looked_out(s)];
-            input_wire + delta_challenge_a * output_wire
-        })
+        .map(|s| plonk_common::reduce_with_powers(&looked_entry(s), delta_challenge_a))
         .collect();
 
     let current_looking_combos: Vec<F::Extension> = (0..num_lu_slots)
-        .map(|s| {
-            let input_wire = vars.local_wires[LookupGate::wire_ith_looking_inp(s)];
-            let output_wire = vars.local_wires[LookupGate::wire_ith_looking_out(s)];
-            input_wire + delta_challenge_a * output_wire
-        })
+        .map(|s| plonk_common::reduce_with_powers(&looking_entry(s), delta_challenge_a))
         .collect();
 
     // Compute all current lookup combos, i.e. the combos used to check that the LUT is correct.
     let current_lookup_combos: Vec<F::Extension> = (0..num_lut_slots)
-        .map(|s| {
-            let input_wire = vars.local_wires[LookupTableGate::wire_ith_looked_inp(s)];
-            let output_wire = vars.local_wires[LookupTableGate::wire_ith_looked_out(s)];
-            input_wire + delta_challenge_b * output_wire
-        })
+        .map(|s| plonk_common::reduce_with_powers(&looked_entry(s), delta_challenge_b))
         .collect();
 
     // Check last LDC constraint.
@@ -480,7 +484,8 @@ pub fn check_lookup_constraints<F: RichField + Extendable<D>, const D: usize>(
         let lut_sum_prods_with_mul = (poly * lut_degree
             ..min((poly + 1) * lut_degree, num_lut_slots))
             .fold(F::Extension::ZERO, |acc, i| {
-                acc + vars.local_wires[LookupTableGate::wire_ith_multiplicity(i)] * lut_prod_i(i)
+                acc + vars.local_wires[LookupTableGate::wire_ith_multiplicity(width, i)]
+                    * lut_prod_i(i)
             });
 
         // The previous element is the previous poly of the current row or the last poly of the next row.
@@ -515,8 +520,9 @@ pub fn check_lookup_constraints_batch<F: RichField + Extendable<D>, const D: usi
     deltas: &[F; 4],
     lut_re_poly_evals: &[F],
 ) -> Vec<F> {
-    let num_lu_slots = LookupGate::num_slots(&common_data.config);
-    let num_lut_slots = LookupTableGate::num_slots(&common_data.config);
+    let width = common_data.lookup_width();
+    let num_lu_slots = LookupGate::num_slots(&common_data.config, width);
+    let num_lut_slots = LookupTableGate::num_slots(&common_data.config, width);
     let lu_degree = common_data.quotient_degree_factor - 1;
     let num_sldc_polys = local_lookup_zs.len() - 1;
     let lut_degree = ceil_div_usize(num_lut_slots, num_sldc_polys);
@@ -531,29 +537,44 @@ pub fn check_lookup_constraints_batch<F: RichField + Extendable<D>, const D: usi
     let z_x_lookup_sldcs = &local_lookup_zs[1..num_sldc_polys + 1];
     let z_gx_lookup_sldcs = &next_lookup_zs[1..num_sldc_polys + 1];
 
+    // The entries of each slot, combined with powers of a challenge.
+    let looked_entry = |s| -> Vec<F> {
+        (0..width)
+            .map(|col| vars.local_wires[LookupTableGate::wire_ith_looked_col(width, s, col)])
+            .collect()
+    };
+    let looking_entry = |s| -> Vec<F> {
+        (0..width)
+            .map(|col| vars.local_wires[LookupGate::wire_ith_looking_col(width, s, col)])
+            .collect()
+    };
+
     // Compute all current looked and looking combos, i.e. the combos we need for the SLDC polynomials.
     let current_looked_combos: Vec<F> = (0..num_lut_slots)
         .map(|s| {
-            let input_wire = vars.local_wires[LookupTableGate::wire_ith_looked_inp(s)];
-            let output_wire = vars.local_wires[LookupTableGate::wire_ith_looked_out(s)];
-            input_wire + deltas[LookupChallenges::ChallengeA as usize] * output_wire
+            plonk_common::reduce_with_powers(
+                &looked_entry(s),
+                deltas[LookupChallenges::ChallengeA as usize],
+            )
         })
         .collect();
 
     let current_looking_combos: Vec<F> = (0..num_lu_slots)
         .map(|s| {
-            let input_wire = vars.local_wires[LookupGate::wire_ith_looking_inp(s)];
-            let output_wire = vars.local_wires[LookupGate::wire_ith_looking_out(s)];
-            input_wire + deltas[LookupChallenges::ChallengeA as usize] * output_wire
+            plonk_common::reduce_with_powers(
+                &looking_entry(s),
+                deltas[LookupChallenges::ChallengeA as usize],
+            )
         })
         .collect();
 
     // Compute all current lookup combos, i.e. the combos used to check that the LUT is correct.
     let current_lookup_combos: Vec<F> = (0..num_lut_slots)
         .map(|s| {
-            let input_wire = vars.local_wires[LookupTableGate::wire_ith_looked_inp(s)];
-            let output_wire = vars.local_wires[LookupTableGate::wire_ith_looked_out(s)];
-            input_wire + deltas[LookupChallenges::ChallengeB as usize] * output_wire
+            plonk_common::reduce_with_powers(
+                &looked_entry(s),
+                deltas[LookupChallenges::ChallengeB as usize],
+            )
         })
         .collect();
 
@@ -633,7 +654,8 @@ pub fn check_lookup_constraints_batch<F: RichField + Extendable<D>, const D: usi
         let lut_sum_prods_with_mul = (poly * lut_degree
             ..min((poly + 1) * lut_degree, num_lut_slots))
             .fold(F::ZERO, |acc, i| {
-                acc + vars.local_wires[LookupTableGate::wire_ith_multiplicity(i)] * lut_prod_i(i)
+                acc + vars.local_wires[LookupTableGate::wire_ith_multiplicity(width, i)]
+                    * lut_prod_i(i)
             });
 
         // The previous element is the previous poly of the current row or the last poly of the next row.
@@ -756,12 +778,22 @@ pub(crate) fn get_lut_poly_circuit<F: RichField + Extendable<D>, const D: usize>
 ) -> Target {
     let b = deltas[LookupChallenges::ChallengeB as usize];
     let delta = deltas[LookupChallenges::ChallengeDelta as usize];
-    let n = common_data.luts[lut_index].len();
-    let mut coeffs: Vec<Target> = common_data.luts[lut_index]
-        .iter()
-        .map(|(input, output)| {
-            let temp = builder.mul_const(F::from_canonical_u16(*output), b);
-            builder.add_const(temp, F::from_canonical_u16(*input))
+    let lut = &common_data.luts[lut_index];
+    let n = lut.len();
+    let mut coeffs: Vec<Target> = (0..n)
+        .map(|i| {
+            // Horner's method on the row's columns. The zero padding up to `width` doesn't
+            // contribute to the combination.
+            let row: Vec<F> = lut.padded_row(i, lut.width()).collect();
+            let (&output, inputs) = row.split_last().unwrap();
+            let (&last_input, inputs) = inputs.split_last().unwrap();
+            let temp = builder.mul_const(output, b);
+            let mut acc = builder.add_const(temp, last_input);
+            for &c in inputs.iter().rev() {
+                let temp = builder.mul(acc, b);
+                acc = builder.add_const(temp, c);
+            }
+            acc
         })
         .collect();
     for _ in n..degree {
@@ -927,8 +959,9 @@ pub fn check_lookup_constraints_circuit<F: RichField + Extendable<D>, const D: u
     lookup_selectors: &[ExtensionTarget<D>],
     deltas: &[Target],
 ) -> Vec<ExtensionTarget<D>> {
-    let num_lu_slots = LookupGate::num_slots(&common_data.config);
-    let num_lut_slots = LookupTableGate::num_slots(&common_data.config);
+    let width = common_data.lookup_width();
+    let num_lu_slots = LookupGate::num_slots(&common_data.config, width);
+    let num_lut_slots = LookupTableGate::num_slots(&common_data.config, width);
     let lu_degree = common_data.quotient_degree_factor - 1;
     let num_sldc_polys = local_lookup_zs.len() - 1;
     let lut_degree = ceil_div_usize(num_lut_slots, num_sldc_polys);
@@ -949,26 +982,33 @@ pub fn check_lookup_constraints_circuit<F: RichField + Extendable<D>, const D: u
         .map(|d| builder.convert_to_ext(*d))
         .collect::<Vec<_>>();
 
+    let looked_entry = |s| -> Vec<ExtensionTarget<D>> {
+        (0..width)
+            .map(|col| vars.local_wires[LookupTableGate::wire_ith_looked_col(width, s, col)])
+            .collect()
+    };
+    let looking_entry = |s| -> Vec<ExtensionTarget<D>> {
+        (0..width)
+            .map(|col| vars.local_wires[LookupGate::wire_ith_looking_col(width, s, col)])
+            .collect()
+    };
+
     // Computing all current looked and looking combos, i.e. the combos we need for the SLDC polynomials.
     let current_looked_combos = (0..num_lut_slots)
         .map(|s| {
-            let input_wire = vars.local_wires[LookupTableGate::wire_ith_looked_inp(s)];
-            let output_wire = vars.local_wires[LookupTableGate::wire_ith_looked_out(s)];
-            builder.mul_add_extension(
+            combine_lookup_entry_circuit(
+                builder,
+                &looked_entry(s),
                 ext_deltas[LookupChallenges::ChallengeA as usize],
-                output_wire,
-                input_wire,
             )
         })
         .collect::<Vec<_>>();
     let current_looking_combos = (0..num_lu_slots)
         .map(|s| {
-            let input_wire = vars.local_wires[LookupGate::wire_ith_looking_inp(s)];
-            let output_wire = vars.local_wires[LookupGate::wire_ith_looking_out(s)];
-            builder.mul_add_extension(
+            combine_lookup_entry_circuit(
+                builder,
+                &looking_entry(s),
                 ext_deltas[LookupChallenges::ChallengeA as usize],
-                output_wire,
-                input_wire,
             )
         })
         .collect::<Vec<_>>();
@@ -994,12 +1034,10 @@ pub fn check_lookup_constraints_circuit<F: RichField + Extendable<D>, const D: u
     // Computing all current lookup combos, i.e. the combos used to check that the LUT is correct.
     let current_lookup_combos = (0..num_lut_slots)
         .map(|s| {
-            let input_wire = vars.local_wires[LookupTableGate::wire_ith_looked_inp(s)];
-            let output_wire = vars.local_wires[LookupTableGate::wire_ith_looked_out(s)];
-            builder.mul_add_extension(
+            combine_lookup_entry_circuit(
+                builder,
+                &looked_entry(s),
                 ext_deltas[LookupChallenges::ChallengeB as usize],
-                output_wire,
-                input_wire,
             )
         })
         .collect::<Vec<_>>();
@@ -1097,7 +1135,7 @@ pub fn check_lookup_constraints_circuit<F: RichField + Extendable<D>, const D: u
                 }
                 builder.mul_add_extension(
                     prod_i,
-                    vars.local_wires[LookupTableGate::wire_ith_multiplicity(i)],
+                    vars.local_wires[LookupTableGate::wire_ith_multiplicity(width, i)],
                     acc,
                 )
             });
@@ -1128,3 +1166,15 @@ pub fn check_lookup_constraints_circuit<F: RichField + Extendable<D>, const D: u
     }
     constraints
 }
+
+/// Computes `sum_i challenge^i entry_i` for the columns of a lookup slot.
+fn combine_lookup_entry_circuit<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    entry: &[ExtensionTarget<D>],
+    challenge: ExtensionTarget<D>,
+) -> ExtensionTarget<D> {
+    let (&last, rest) = entry.split_last().expect("Empty lookup entry");
+    rest.iter().rev().fold(last, |acc, &col| {
+        builder.mul_add_extension(challenge, acc, col)
+    })
+}
@@ -0,0 +1,484 @@
+//! A generic recursive aggregation tree for plonky2 proofs.
+//!
+//! An [`AggregationCircuit`] is a single cyclic circuit with a fixed number of child slots. Each
+//! slot holds either a proof of some leaf circuit, a proof of the aggregation circuit itself, or
+//! nothing. Any number of leaf proofs can therefore be folded, level by level, into one proof which
+//! is checked against a single verifier key. How the public inputs of the children are combined
+//! into the public inputs of their parent is supplied by the caller when building the circuit.
+//!
+//! The public inputs of an aggregation proof are laid out as `[node public inputs..., cyclic
+//! verifier data...]`, where the node public inputs have the same length as the leaf circuit's
+//! public inputs.
+
+use alloc::vec::Vec;
+
+use anyhow::{ensure, Result};
+use hashbrown::HashMap;
+use plonky2_maybe_rayon::*;
+
+use crate::field::extension::Extendable;
+use crate::hash::hash_types::RichField;
+use crate::iop::target::{BoolTarget, Target};
+use crate::iop::witness::{PartialWitness, WitnessWrite};
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::circuit_data::{
+    CircuitConfig, CircuitData, CommonCircuitData, VerifierCircuitData, VerifierCircuitTarget,
+};
+use crate::plonk::config::{AlgebraicHasher, GenericConfig};
+use crate::plonk::proof::{ProofWithPublicInputs, ProofWithPublicInputsTarget};
+use crate::recursion::cyclic_recursion::check_cyclic_proof_verifier_data;
+use crate::recursion::dummy_circuit::{build_dummy_circuit, cyclic_base_proof, dummy_proof};
+
+/// Upper bound on the number of circuits built while searching for the aggregation circuit's
+/// `CommonCircuitData`. In practice the search converges after three or four rounds.
+const MAX_COMMON_DATA_ROUNDS: usize = 10;
+
+/// A child of an aggregation node, as seen by the combining function.
+#[derive(Clone, Debug)]
+pub struct AggregationChildTarget {
+    /// Whether this slot holds a proof. The first slot is always filled, and only trailing slots
+    /// may be empty.
+    pub is_present: BoolTarget,
+    /// The node public inputs of the child, i.e. the leaf's public inputs if the child is a leaf
+    /// proof. These are unconstrained if the slot is empty, so the combining function must not use
+    /// them without checking `is_present`.
+    pub public_inputs: Vec<Target>,
+}
+
+/// A proof to be placed in one slot of an aggregation node.
+#[derive(Clone, Copy, Debug)]
+pub enum AggregationChild<'a, F, C, const D: usize>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    /// A proof of the leaf circuit.
+    Leaf(&'a ProofWithPublicInputs<F, C, D>),
+    /// A proof of the aggregation circuit.
+    Node(&'a ProofWithPublicInputs<F, C, D>),
+}
+
+#[derive(Eq, PartialEq, Debug)]
+struct AggregationSlotTarget<const D: usize> {
+    is_leaf: BoolTarget,
+    is_node: BoolTarget,
+    leaf_proof: ProofWithPublicInputsTarget<D>,
+    node_proof: ProofWithPublicInputsTarget<D>,
+}
+
+/// A fixed-shape aggregation circuit which verifies up to `arity` children, each of which is
+/// either a leaf proof or a previous aggregation proof.
+#[derive(Debug)]
+pub struct AggregationCircuit<F, C, const D: usize>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    pub circuit: CircuitData<F, C, D>,
+    slots: Vec<AggregationSlotTarget<D>>,
+    cyclic_vk: VerifierCircuitTarget,
+    num_node_public_inputs: usize,
+    /// Witness for the leaf proof targets of slots which don't hold a leaf.
+    dummy_leaf_proof: ProofWithPublicInputs<F, C, D>,
+    /// Witness for the node proof targets of slots which don't hold a node. It carries the cyclic
+    /// verifier data in its public inputs, as required by cyclic verification.
+    dummy_node_proof: ProofWithPublicInputs<F, C, D>,
+}
+
+impl<F, C, const D: usize> AggregationCircuit<F, C, D>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F> + 'static,
+    C::Hasher: AlgebraicHasher<F>,
+{
+    /// Builds an aggregation circuit over proofs of the `leaf` circuit, with `arity` child slots.
+    ///
+    /// `combine` is called with the children of a node and must return the node's public inputs,
+    /// which must have the same length as the leaf circuit's public inputs.
+    ///
+    /// Slots which don't hold a leaf are filled with proofs of a dummy circuit made of `NoopGate`s
+    /// and sharing the leaf circuit's common data. The leaf circuit must therefore not be
+    /// zero-knowledge, and `NoopGate` must be part of its gate set, e.g. by adding a single
+    /// `NoopGate` to it. An error is returned otherwise.
+    pub fn new<CF>(
+        leaf: &VerifierCircuitData<F, C, D>,
+        config: CircuitConfig,
+        arity: usize,
+        combine: CF,
+    ) -> Result<Self>
+    where
+        CF: Fn(&mut CircuitBuilder<F, D>, &[AggregationChildTarget]) -> Vec<Target>,
+    {
+        assert!(
+            arity >= 2,
+            "An aggregation circuit needs at least two children"
+        );
+        let num_node_public_inputs = leaf.common.num_public_inputs;
+
+        ensure!(
+            !leaf.common.config.zero_knowledge,
+            "The leaf circuit of an aggregation circuit must not be zero-knowledge"
+        );
+        let dummy_leaf_circuit = build_dummy_circuit::<F, C, D>(&leaf.common);
+        ensure!(
+            dummy_leaf_circuit.common == leaf.common,
+            "The leaf circuit's common data doesn't match the dummy circuit used for empty slots; \
+             the leaf circuit must contain a NoopGate"
+        );
+
+        // Search for the fixed point of "the common data of an aggregation circuit whose node
+        // children have the given common data", starting from an empty circuit with the right
+        // public inputs.
+        let mut builder = CircuitBuilder::<F, D>::new(config.clone());
+        for _ in 0..num_node_public_inputs {
+            builder.add_virtual_public_input();
+        }
+        builder.add_verifier_data_public_inputs();
+        let mut common = builder.build::<C>().common;
+        let mut converged = false;
+        for _ in 0..MAX_COMMON_DATA_ROUNDS {
+            let mut builder = CircuitBuilder::<F, D>::new(config.clone());
+            Self::add_aggregation_targets(&mut builder, leaf, arity, &combine, &common, false)?;
+            let next_common = builder.build::<C>().common;
+            if next_common == common {
+                converged = true;
+                break;
+            }
+            common = next_common;
+        }
+        ensure!(
+            converged,
+            "Aggregation circuit size did not stabilize after {} rounds",
+            MAX_COMMON_DATA_ROUNDS
+        );
+
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let (slots, cyclic_vk) =
+            Self::add_aggregation_targets(&mut builder, leaf, arity, &combine, &common, true)?;
+        let circuit = builder.build::<C>();
+
+        let dummy_leaf_proof = dummy_proof::<F, C, D>(&dummy_leaf_circuit, HashMap::new())?;
+        let dummy_node_proof =
+            cyclic_base_proof(&circuit.common, &circuit.verifier_only, HashMap::new());
+
+        Ok(Self {
+            circuit,
+            slots,
+            cyclic_vk,
+            num_node_public_inputs,
+            dummy_leaf_proof,
+            dummy_node_proof,
+        })
+    }
+
+    /// Adds the slots, the combining logic and the public inputs of an aggregation circuit whose
+    /// node children have `node_common` as common data.
+    ///
+    /// With `cyclic` set, node children are verified against the circuit's own verifier data.
+    /// Otherwise they are verified against the (unchecked) verifier data public inputs and dummy
+    /// proofs are left virtual, which produces a circuit of the same shape without having to
+    /// build any dummy circuits; this is used while searching for `node_common`.
+    fn add_aggregation_targets<CF>(
+        builder: &mut CircuitBuilder<F, D>,
+        leaf: &VerifierCircuitData<F, C, D>,
+        arity: usize,
+        combine: &CF,
+        node_common: &CommonCircuitData<F, D>,
+        cyclic: bool,
+    ) -> Result<(Vec<AggregationSlotTarget<D>>, VerifierCircuitTarget)>
+    where
+        CF: Fn(&mut CircuitBuilder<F, D>, &[AggregationChildTarget]) -> Vec<Target>,
+    {
+        let num_node_public_inputs = leaf.common.num_public_inputs;
+        let public_inputs = (0..num_node_public_inputs)
+            .map(|_| builder.add_virtual_public_input())
+            .collect::<Vec<_>>();
+        let cyclic_vk = builder.add_verifier_data_public_inputs();
+        let leaf_vk = builder.constant_verifier_data(&leaf.verifier_only);
+
+        let ((dummy_leaf, dummy_leaf_vk), (dummy_node, dummy_node_vk)) = if cyclic {
+            (
+                builder.dummy_proof_and_vk::<C>(&leaf.common)?,
+                builder.dummy_proof_and_vk::<C>(node_common)?,
+            )
+        } else {
+            let cap_height = builder.config.fri_config.cap_height;
+            (
+                (
+                    builder.add_virtual_proof_with_pis(&leaf.common),
+                    builder.add_virtual_verifier_data(cap_height),
+                ),
+                (
+                    builder.add_virtual_proof_with_pis(node_common),
+                    builder.add_virtual_verifier_data(cap_height),
+                ),
+            )
+        };
+
+        let mut slots = Vec::with_capacity(arity);
+        let mut children = Vec::with_capacity(arity);
+        for i in 0..arity {
+            let is_leaf = builder.add_virtual_bool_target_safe();
+            let is_node = builder.add_virtual_bool_target_safe();
+            let is_present = BoolTarget::new_unsafe(builder.add(is_leaf.target, is_node.target));
+            builder.assert_bool(is_present);
+            if i == 0 {
+                builder.assert_one(is_present.target);
+            } else {
+                // An empty slot can only be followed by empty slots.
+                let previous: &AggregationChildTarget = &children[i - 1];
+                let both = builder.and(is_present, previous.is_present);
+                builder.connect(both.target, is_present.target);
+            }
+
+            let leaf_proof = builder.add_virtual_proof_with_pis(&leaf.common);
+            let node_proof = builder.add_virtual_proof_with_pis(node_common);
+            builder.conditionally_verify_proof::<C>(
+                is_leaf,
+                &leaf_proof,
+                &leaf_vk,
+                &dummy_leaf,
+                &dummy_leaf_vk,
+                &leaf.common,
+            );
+            if cyclic {
+                builder.conditionally_verify_cyclic_proof::<C>(
+                    is_node,
+                    &node_proof,
+                    &dummy_node,
+                    &dummy_node_vk,
+                    node_common,
+                )?;
+            } else {
+                builder.conditionally_verify_proof::<C>(
+                    is_node,
+                    &node_proof,
+                    &cyclic_vk,
+                    &dummy_node,
+                    &dummy_node_vk,
+                    node_common,
+                );
+            }
+
+            let child_public_inputs = node_proof.public_inputs[..num_node_public_inputs]
+                .iter()
+                .zip(&leaf_proof.public_inputs)
+                .map(|(&node_pi, &leaf_pi)| builder.select(is_node, node_pi, leaf_pi))
+                .collect();
+            children.push(AggregationChildTarget {
+                is_present,
+                public_inputs: child_public_inputs,
+            });
+            slots.push(AggregationSlotTarget {
+                is_leaf,
+                is_node,
+                leaf_proof,
+                node_proof,
+            });
+        }
+
+        if !cyclic {
+            // Cyclic verification adds every gate of `node_common` to the gate set.
+            for gate in &node_common.gates {
+                builder.add_gate_to_gate_set(gate.clone());
+            }
+        }
+
+        let combined = combine(builder, &children);
+        assert_eq!(
+            combined.len(),
+            num_node_public_inputs,
+            "The combined public inputs must have the same length as the leaf public inputs"
+        );
+        for (&pi, &value) in public_inputs.iter().zip(&combined) {
+            builder.connect(pi, value);
+        }
+
+        Ok((slots, cyclic_vk))
+    }
+
+    /// The number of child slots of each aggregation node.
+    pub fn arity(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Proves one aggregation node. `children` fill the first slots, and any remaining slots are
+    /// left empty.
+    pub fn prove_node(
+        &self,
+        children: &[AggregationChild<F, C, D>],
+    ) -> Result<ProofWithPublicInputs<F, C, D>> {
+        ensure!(
+            !children.is_empty() && children.len() <= self.arity(),
+            "An aggregation node needs between 1 and {} children, got {}",
+            self.arity(),
+            children.len()
+        );
+
+        let mut inputs = PartialWitness::new();
+        for (i, slot) in self.slots.iter().enumerate() {
+            let (is_leaf, is_node, leaf_proof, node_proof) = match children.get(i) {
+                Some(AggregationChild::Leaf(proof)) => {
+                    (true, false, *proof, &self.dummy_node_proof)
+                }
+                Some(AggregationChild::Node(proof)) => {
+                    (false, true, &self.dummy_leaf_proof, *proof)
+                }
+                None => (false, false, &self.dummy_leaf_proof, &self.dummy_node_proof),
+            };
+            inputs.set_bool_target(slot.is_leaf, is_leaf);
+            inputs.set_bool_target(slot.is_node, is_node);
+            inputs.set_proof_with_pis_target(&slot.leaf_proof, leaf_proof);
+            inputs.set_proof_with_pis_target(&slot.node_proof, node_proof);
+        }
+        inputs.set_verifier_data_target(&self.cyclic_vk, &self.circuit.verifier_only);
+
+        self.circuit.prove(inputs)
+    }
+
+    /// Folds `leaves` into a single aggregation proof. Each level groups consecutive proofs into
+    /// nodes of `arity` children, the last node of a level possibly having fewer, and the nodes of
+    /// a level are proven in parallel.
+    pub fn prove_tree(
+        &self,
+        leaves: Vec<ProofWithPublicInputs<F, C, D>>,
+    ) -> Result<ProofWithPublicInputs<F, C, D>> {
+        ensure!(
+            !leaves.is_empty(),
+            "Cannot aggregate an empty set of proofs"
+        );
+
+        let mut nodes = leaves
+            .par_chunks(self.arity())
+            .map(|chunk| {
+                let children = chunk.iter().map(AggregationChild::Leaf).collect::<Vec<_>>();
+                self.prove_node(&children)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        while nodes.len() > 1 {
+            nodes = nodes
+                .par_chunks(self.arity())
+                .map(|chunk| {
+                    let children = chunk.iter().map(AggregationChild::Node).collect::<Vec<_>>();
+                    self.prove_node(&children)
+                })
+                .collect::<Result<Vec<_>>>()?;
+        }
+
+        Ok(nodes.remove(0))
+    }
+
+    /// Verifies an aggregation proof, including the cyclic verifier data in its public inputs.
+    pub fn verify(&self, proof: &ProofWithPublicInputs<F, C, D>) -> Result<()> {
+        check_cyclic_proof_verifier_data(proof, &self.circuit.verifier_only, &self.circuit.common)?;
+        self.circuit.verify(proof.clone())
+    }
+
+    /// The node public inputs of an aggregation proof, i.e. without the cyclic verifier data.
+    pub fn node_public_inputs<'a>(&self, proof: &'a ProofWithPublicInputs<F, C, D>) -> &'a [F] {
+        &proof.public_inputs[..self.num_node_public_inputs]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use anyhow::Result;
+
+    use super::*;
+    use crate::field::ops::Square;
+    use crate::field::types::{Field, Sample};
+    use crate::gates::noop::NoopGate;
+    use crate::plonk::config::PoseidonGoldilocksConfig;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    /// A leaf circuit proving knowledge of `x` with public input `x^2`.
+    fn square_circuit() -> (CircuitData<F, C, D>, Target) {
+        square_circuit_with_noop(true)
+    }
+
+    fn square_circuit_with_noop(noop: bool) -> (CircuitData<F, C, D>, Target) {
+        let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+        let x = builder.add_virtual_target();
+        let x_squared = builder.square(x);
+        builder.register_public_input(x_squared);
+        // Dummy leaf proofs come from a circuit padded with `NoopGate`s, so the leaf circuit needs
+        // one too for their common data to match.
+        if noop {
+            builder.add_gate(NoopGate, vec![]);
+        }
+        (builder.build::<C>(), x)
+    }
+
+    /// Sums the public inputs of the present children.
+    fn sum_children(
+        builder: &mut CircuitBuilder<F, D>,
+        children: &[AggregationChildTarget],
+    ) -> Vec<Target> {
+        let zero = builder.zero();
+        let sum = children.iter().fold(zero, |acc, child| {
+            let value = builder.select(child.is_present, child.public_inputs[0], zero);
+            builder.add(acc, value)
+        });
+        vec![sum]
+    }
+
+    fn aggregate_squares(arity: usize, num_leaves: usize) -> Result<()> {
+        let (leaf, x) = square_circuit();
+        let aggregation = AggregationCircuit::new(
+            &leaf.verifier_data(),
+            CircuitConfig::standard_recursion_config(),
+            arity,
+            sum_children,
+        )?;
+
+        let xs = F::rand_vec(num_leaves);
+        let leaves = xs
+            .iter()
+            .map(|&value| {
+                let mut pw = PartialWitness::new();
+                pw.set_target(x, value);
+                leaf.prove(pw)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let root = aggregation.prove_tree(leaves)?;
+        aggregation.verify(&root)?;
+        let expected = xs.iter().map(|x| x.square()).sum::<F>();
+        assert_eq!(aggregation.node_public_inputs(&root), &[expected]);
+
+        // The root public inputs can't be changed without invalidating the proof.
+        let mut tampered = root;
+        tampered.public_inputs[0] += F::ONE;
+        assert!(aggregation.verify(&tampered).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_leaf_without_noop_gate() {
+        let (leaf, _) = square_circuit_with_noop(false);
+        assert!(AggregationCircuit::new(
+            &leaf.verifier_data(),
+            CircuitConfig::standard_recursion_config(),
+            2,
+            sum_children,
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_binary_aggregation_tree() -> Result<()> {
+        aggregate_squares(2, 5)
+    }
+
+    #[test]
+    #[ignore]
+    fn test_ternary_aggregation_tree() -> Result<()> {
+        aggregate_squares(3, 4)
+    }
+}
@@ -10,9 +10,9 @@ use crate::hash::merkle_tree::MerkleCap;
 use crate::iop::target::{BoolTarget, Target};
 use crate::plonk::circuit_builder::CircuitBuilder;
 use crate::plonk::circuit_data::{
-    CommonCircuitData, VerifierCircuitTarget, VerifierOnlyCircuitData,
+    CommonCircuitData, VerifierCircuitTarget, VerifierDataSetTarget, VerifierOnlyCircuitData,
 };
-use crate::plonk::config::{AlgebraicHasher, GenericConfig};
+use crate::plonk::config::{AlgebraicHasher, GenericConfig, Hasher};
 use crate::plonk::proof::{ProofWithPublicInputs, ProofWithPublicInputsTarget};
 use crate::util::serialization::{Buffer, IoResult, Read, Write};
 
@@ -173,6 +173,154 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         )?;
         Ok(())
     }
+
+    /// Add virtual verifier data for a set of `num_circuits` mutually recursive circuits, register
+    /// its digest as public inputs and set it to `self.verifier_data_set_public_input`.
+    ///
+    /// This replaces `add_verifier_data_public_inputs` in circuits which may verify proofs of
+    /// several circuits. All circuits in the set must register the same number of circuits, in the
+    /// same order, and have the same `CommonCircuitData`.
+    ///
+    /// WARNING: Do not register any public input after calling this!
+    pub fn add_verifier_data_set_public_inputs<C: GenericConfig<D, F = F>>(
+        &mut self,
+        num_circuits: usize,
+    ) -> VerifierDataSetTarget
+    where
+        C::Hasher: AlgebraicHasher<F>,
+    {
+        assert!(
+            self.verifier_data_public_input.is_none()
+                && self.verifier_data_set_public_input.is_none(),
+            "Cyclic verifier data public inputs only need to be added once"
+        );
+        assert!(num_circuits > 0, "A set of circuits can't be empty");
+
+        let cap_height = self.config.fri_config.cap_height;
+        let verifier_data = (0..num_circuits)
+            .map(|_| self.add_virtual_verifier_data(cap_height))
+            .collect::<Vec<_>>();
+        let elements = verifier_data
+            .iter()
+            .flat_map(|vd| {
+                vd.circuit_digest
+                    .elements
+                    .into_iter()
+                    .chain(vd.constants_sigmas_cap.0.iter().flat_map(|h| h.elements))
+            })
+            .collect();
+        let digest = self.hash_n_to_hash_no_pad::<C::Hasher>(elements);
+        self.register_public_inputs(&digest.elements);
+
+        let verifier_data_set = VerifierDataSetTarget {
+            verifier_data,
+            digest,
+        };
+        self.verifier_data_set_public_input = Some(verifier_data_set.clone());
+        verifier_data_set
+    }
+
+    /// If `condition` is true, recursively verify a proof of the circuit at `circuit_index` in the
+    /// set registered with `add_verifier_data_set_public_inputs`, which may be the circuit we're
+    /// currently building. Otherwise, verify `other_proof_with_pis`.
+    ///
+    /// As with `conditionally_verify_cyclic_proof`, the inner proof must commit to the same set of
+    /// verifier data, and verifiers must separately call `check_cyclic_proof_verifier_data_set` to
+    /// check that this set matches the real verifier data.
+    ///
+    /// WARNING: Do not register any public input after calling this!
+    pub fn conditionally_verify_cyclic_proof_in_set<C: GenericConfig<D, F = F>>(
+        &mut self,
+        condition: BoolTarget,
+        circuit_index: Target,
+        cyclic_proof_with_pis: &ProofWithPublicInputsTarget<D>,
+        other_proof_with_pis: &ProofWithPublicInputsTarget<D>,
+        other_verifier_data: &VerifierCircuitTarget,
+        common_data: &CommonCircuitData<F, D>,
+    ) -> Result<()>
+    where
+        C::Hasher: AlgebraicHasher<F>,
+    {
+        let verifier_data_set = self.verifier_data_set_public_input.clone().expect(
+            "Must call add_verifier_data_set_public_inputs before cyclic recursion in a set",
+        );
+
+        if let Some(existing_common_data) = self.goal_common_data.as_ref() {
+            assert_eq!(existing_common_data, common_data);
+        } else {
+            self.goal_common_data = Some(common_data.clone());
+        }
+
+        // Connect the previous set digest to the current one. This guarantees that every proof in
+        // the cycle uses the same set of verifier data.
+        let pis = &cyclic_proof_with_pis.public_inputs;
+        ensure!(pis.len() >= 4, "Not enough public inputs");
+        let inner_digest = HashOutTarget {
+            elements: core::array::from_fn(|i| pis[pis.len() - 4 + i]),
+        };
+        self.connect_hashes(inner_digest, verifier_data_set.digest);
+
+        let verifier_data =
+            self.select_verifier_data(circuit_index, verifier_data_set.verifier_data);
+        self.conditionally_verify_proof::<C>(
+            condition,
+            cyclic_proof_with_pis,
+            &verifier_data,
+            other_proof_with_pis,
+            other_verifier_data,
+            common_data,
+        );
+
+        // Make sure we have every gate to match `common_data`.
+        for g in &common_data.gates {
+            self.add_gate_to_gate_set(g.clone());
+        }
+
+        Ok(())
+    }
+
+    pub fn conditionally_verify_cyclic_proof_in_set_or_dummy<C: GenericConfig<D, F = F> + 'static>(
+        &mut self,
+        condition: BoolTarget,
+        circuit_index: Target,
+        cyclic_proof_with_pis: &ProofWithPublicInputsTarget<D>,
+        common_data: &CommonCircuitData<F, D>,
+    ) -> Result<()>
+    where
+        C::Hasher: AlgebraicHasher<F>,
+    {
+        let (dummy_proof_with_pis_target, dummy_verifier_data_target) =
+            self.dummy_proof_and_vk::<C>(common_data)?;
+        self.conditionally_verify_cyclic_proof_in_set::<C>(
+            condition,
+            circuit_index,
+            cyclic_proof_with_pis,
+            &dummy_proof_with_pis_target,
+            &dummy_verifier_data_target,
+            common_data,
+        )?;
+        Ok(())
+    }
+}
+
+/// Computes the digest of the verifier data of a set of mutually recursive circuits, as registered
+/// by `add_verifier_data_set_public_inputs`.
+pub fn verifier_data_set_digest<C: GenericConfig<D>, const D: usize>(
+    verifier_data: &[VerifierOnlyCircuitData<C, D>],
+) -> HashOut<C::F>
+where
+    C::Hasher: AlgebraicHasher<C::F>,
+{
+    let elements = verifier_data
+        .iter()
+        .flat_map(|vd| {
+            vd.circuit_digest
+                .elements
+                .into_iter()
+                .chain(vd.constants_sigmas_cap.flatten())
+        })
+        .collect::<Vec<_>>();
+    C::Hasher::hash_no_pad(&elements)
 }
 
 /// Additional checks to be performed on a cyclic recursive proof in addition to verifying the proof.
@@ -196,22 +344,53 @@ where
     Ok(())
 }
 
+/// Like `check_cyclic_proof_verifier_data`, for a proof of a circuit in a set of mutually recursive
+/// circuits: checks that the purported digest of the set's verifier data in the public inputs
+/// matches the real verifier data of the set.
+pub fn check_cyclic_proof_verifier_data_set<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    proof: &ProofWithPublicInputs<F, C, D>,
+    verifier_data: &[VerifierOnlyCircuitData<C, D>],
+) -> Result<()>
+where
+    C::Hasher: AlgebraicHasher<C::F>,
+{
+    let len = proof.public_inputs.len();
+    ensure!(len >= 4, "Not enough public inputs");
+    let digest = verifier_data_set_digest(verifier_data);
+    ensure!(proof.public_inputs[len - 4..] == digest.elements);
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use anyhow::Result;
 
     use crate::field::extension::Extendable;
     use crate::field::types::{Field, PrimeField64};
+    use crate::gates::gate::GateRef;
     use crate::gates::noop::NoopGate;
+    use crate::gates::random_access::RandomAccessGate;
     use crate::hash::hash_types::{HashOutTarget, RichField};
     use crate::hash::hashing::hash_n_to_hash_no_pad;
     use crate::hash::poseidon::{PoseidonHash, PoseidonPermutation};
+    use crate::iop::target::{BoolTarget, Target};
     use crate::iop::witness::{PartialWitness, WitnessWrite};
     use crate::plonk::circuit_builder::CircuitBuilder;
-    use crate::plonk::circuit_data::{CircuitConfig, CommonCircuitData};
+    use crate::plonk::circuit_data::{
+        CircuitConfig, CircuitData, CommonCircuitData, VerifierDataSetTarget,
+    };
     use crate::plonk::config::{AlgebraicHasher, GenericConfig, PoseidonGoldilocksConfig};
-    use crate::recursion::cyclic_recursion::check_cyclic_proof_verifier_data;
-    use crate::recursion::dummy_circuit::cyclic_base_proof;
+    use crate::plonk::proof::{ProofWithPublicInputs, ProofWithPublicInputsTarget};
+    use crate::recursion::cyclic_recursion::{
+        check_cyclic_proof_verifier_data, check_cyclic_proof_verifier_data_set,
+    };
+    use crate::recursion::dummy_circuit::{cyclic_base_proof, cyclic_set_base_proof};
+    use crate::recursion::universal_verifier::universal_common_data;
 
     // Generates `CommonCircuitData` usable for recursion.
     fn common_data_for_recursion<
@@ -364,6 +543,150 @@ mod tests {
         cyclic_circuit_data.verify(proof)
     }
 
+    /// A circuit in a set of two mutually recursive circuits, which applies `op` to the output of
+    /// an earlier proof of either circuit, or to the initial value in the base case.
+    /// The circuit has the following public input structure:
+    /// - Initial value (1)
+    /// - Output (1)
+    /// - Digest of the verifier data of the set (4)
+    struct SetStepCircuit<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize> {
+        data: CircuitData<F, C, D>,
+        condition: BoolTarget,
+        inner_index: Target,
+        inner_proof_with_pis: ProofWithPublicInputsTarget<D>,
+        verifier_data_set: VerifierDataSetTarget,
+    }
+
+    fn set_step_circuit<F, C, const D: usize>(
+        common_data: &CommonCircuitData<F, D>,
+        op: fn(&mut CircuitBuilder<F, D>, Target) -> Target,
+    ) -> Result<SetStepCircuit<F, C, D>>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F> + 'static,
+        C::Hasher: AlgebraicHasher<F>,
+    {
+        let mut builder = CircuitBuilder::<F, D>::new(common_data.config.clone());
+        let initial = builder.add_virtual_public_input();
+        let output = builder.add_virtual_public_input();
+        let verifier_data_set = builder.add_verifier_data_set_public_inputs::<C>(2);
+
+        let condition = builder.add_virtual_bool_target_safe();
+        let inner_index = builder.add_virtual_target();
+        let inner_proof_with_pis = builder.add_virtual_proof_with_pis(common_data);
+        let inner_pis = &inner_proof_with_pis.public_inputs;
+        builder.connect(initial, inner_pis[0]);
+        let input = builder.select(condition, inner_pis[1], initial);
+        let new_output = op(&mut builder, input);
+        builder.connect(output, new_output);
+
+        builder.conditionally_verify_cyclic_proof_in_set_or_dummy::<C>(
+            condition,
+            inner_index,
+            &inner_proof_with_pis,
+            common_data,
+        )?;
+        builder.pad_to_common_data(common_data);
+
+        Ok(SetStepCircuit {
+            data: builder.build::<C>(),
+            condition,
+            inner_index,
+            inner_proof_with_pis,
+            verifier_data_set,
+        })
+    }
+
+    /// Uses cyclic recursion over a set of two circuits, one incrementing and one doubling a
+    /// value, where each proof may verify an earlier proof of either circuit.
+    #[test]
+    fn test_cyclic_recursion_in_set() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        // Both circuits share this common data, which also has the gate used to select the inner
+        // verifier data from the set.
+        let config = CircuitConfig::standard_recursion_config();
+        let recursion_common_data = common_data_for_recursion::<F, C, D>();
+        let mut gates = recursion_common_data.gates.clone();
+        gates.push(GateRef::new(RandomAccessGate::<F, D>::new_from_config(
+            &config, 1,
+        )));
+        let common_data = universal_common_data::<F, C, D>(
+            config,
+            recursion_common_data.degree_bits(),
+            &gates,
+            6,
+        );
+
+        let increment = set_step_circuit::<F, C, D>(&common_data, |builder, x| {
+            let one = builder.one();
+            builder.add(x, one)
+        })?;
+        let double = set_step_circuit::<F, C, D>(&common_data, |builder, x| builder.add(x, x))?;
+        let verifier_data = [
+            increment.data.verifier_only.clone(),
+            double.data.verifier_only.clone(),
+        ];
+
+        let prove_step =
+            |circuit: &SetStepCircuit<F, C, D>,
+             inner: Option<(usize, &ProofWithPublicInputs<F, C, D>)>| {
+                let mut pw = PartialWitness::new();
+                pw.set_verifier_data_set_target(&circuit.verifier_data_set, &verifier_data);
+                match inner {
+                    Some((index, proof)) => {
+                        pw.set_bool_target(circuit.condition, true);
+                        pw.set_target(circuit.inner_index, F::from_canonical_usize(index));
+                        pw.set_proof_with_pis_target(&circuit.inner_proof_with_pis, proof);
+                    }
+                    None => {
+                        pw.set_bool_target(circuit.condition, false);
+                        pw.set_target(circuit.inner_index, F::ZERO);
+                        pw.set_proof_with_pis_target::<C, D>(
+                            &circuit.inner_proof_with_pis,
+                            &cyclic_set_base_proof(
+                                &common_data,
+                                &verifier_data,
+                                [(0, F::from_canonical_usize(3))].into_iter().collect(),
+                            ),
+                        );
+                    }
+                }
+                pw
+            };
+
+        // 3 -> 4 -> 8 -> 9
+        let proof = increment.data.prove(prove_step(&increment, None))?;
+        check_cyclic_proof_verifier_data_set(&proof, &verifier_data)?;
+        increment.data.verify(proof.clone())?;
+        assert_eq!(proof.public_inputs[1], F::from_canonical_usize(4));
+
+        let proof = double.data.prove(prove_step(&double, Some((0, &proof))))?;
+        check_cyclic_proof_verifier_data_set(&proof, &verifier_data)?;
+        double.data.verify(proof.clone())?;
+        assert_eq!(proof.public_inputs[1], F::from_canonical_usize(8));
+
+        // The inner proof must be checked against the verifier data of the circuit it is for.
+        assert!(increment
+            .data
+            .check_witness(prove_step(&increment, Some((0, &proof))))
+            .is_err());
+
+        let proof = increment
+            .data
+            .prove(prove_step(&increment, Some((1, &proof))))?;
+        check_cyclic_proof_verifier_data_set(&proof, &verifier_data)?;
+        assert_eq!(proof.public_inputs[0], F::from_canonical_usize(3));
+        assert_eq!(proof.public_inputs[1], F::from_canonical_usize(9));
+
+        // The digest in the public inputs commits to the verifier data of the whole set.
+        assert!(check_cyclic_proof_verifier_data_set(&proof, &verifier_data[..1]).is_err());
+
+        increment.data.verify(proof)
+    }
+
     fn iterate_poseidon<F: RichField>(initial_state: [F; 4], n: usize) -> [F; 4] {
         let mut current = initial_state;
         for _ in 0..n {
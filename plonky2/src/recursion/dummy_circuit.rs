@@ -24,6 +24,7 @@ use crate::plonk::proof::{
     OpeningSet, OpeningSetTarget, Proof, ProofTarget, ProofWithPublicInputs,
     ProofWithPublicInputsTarget,
 };
+use crate::recursion::cyclic_recursion::verifier_data_set_digest;
 use crate::util::serialization::{Buffer, IoResult, Read, Write};
 
 /// Creates a dummy proof which is suitable for use as a base proof in a cyclic recursion tree.
@@ -62,6 +63,29 @@ where
     .unwrap()
 }
 
+/// Like `cyclic_base_proof`, for cyclic recursion over a set of circuits: the public inputs which
+/// encode the digest of the set's verifier data are set properly.
+pub fn cyclic_set_base_proof<F, C, const D: usize>(
+    common_data: &CommonCircuitData<F, D>,
+    verifier_data: &[VerifierOnlyCircuitData<C, D>],
+    mut nonzero_public_inputs: HashMap<usize, F>,
+) -> ProofWithPublicInputs<F, C, D>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    C::Hasher: AlgebraicHasher<C::F>,
+{
+    let start_digest_pis = common_data.num_public_inputs - 4;
+    let digest = verifier_data_set_digest(verifier_data);
+    nonzero_public_inputs.extend((start_digest_pis..).zip(digest.elements));
+
+    dummy_proof::<F, C, D>(
+        &dummy_circuit::<F, C, D>(common_data),
+        nonzero_public_inputs,
+    )
+    .unwrap()
+}
+
 /// Generate a proof for a dummy circuit. The `public_inputs` parameter let the caller specify
 /// certain public inputs (identified by their indices) which should be given specific values.
 /// The rest will default to zero.
@@ -90,6 +114,21 @@ pub(crate) fn dummy_circuit<
     const D: usize,
 >(
     common_data: &CommonCircuitData<F, D>,
+) -> CircuitData<F, C, D> {
+    let circuit = build_dummy_circuit::<F, C, D>(common_data);
+    assert_eq!(&circuit.common, common_data);
+    circuit
+}
+
+/// Generate a circuit of `NoopGate`s with the size, gate set and public inputs of a given
+/// `CommonCircuitData`. Its common data only matches `common_data` if the gate set of the latter
+/// already contains `NoopGate`, which callers must check.
+pub(crate) fn build_dummy_circuit<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    common_data: &CommonCircuitData<F, D>,
 ) -> CircuitData<F, C, D> {
     let config = common_data.config.clone();
     assert!(
@@ -113,9 +152,7 @@ pub(crate) fn dummy_circuit<
         builder.add_virtual_public_input();
     }
 
-    let circuit = builder.build::<C>();
-    assert_eq!(&circuit.common, common_data);
-    circuit
+    builder.build::<C>()
 }
 
 impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
@@ -1,4 +1,6 @@
+pub mod aggregation;
 pub mod conditional_recursive_verifier;
 pub mod cyclic_recursion;
 pub mod dummy_circuit;
 pub mod recursive_verifier;
+pub mod universal_verifier;
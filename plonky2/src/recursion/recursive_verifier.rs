@@ -205,7 +205,10 @@ mod tests {
     use crate::gates::noop::NoopGate;
     use crate::iop::witness::{PartialWitness, WitnessWrite};
     use crate::plonk::circuit_data::{CircuitConfig, VerifierOnlyCircuitData};
-    use crate::plonk::config::{GenericConfig, KeccakGoldilocksConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::config::{
+        GenericConfig, KeccakGoldilocksConfig, Poseidon2GoldilocksConfig,
+        PoseidonBN254GoldilocksConfig, PoseidonGoldilocksConfig,
+    };
     use crate::plonk::proof::{CompressedProofWithPublicInputs, ProofWithPublicInputs};
     use crate::plonk::prover::prove;
     use crate::util::timing::TimingTree;
@@ -226,6 +229,22 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_recursive_verifier_poseidon2() -> Result<()> {
+        init_logger();
+        const D: usize = 2;
+        type C = Poseidon2GoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let config = CircuitConfig::standard_recursion_config();
+
+        let (proof, vd, common_data) = dummy_proof::<F, C, D>(&config, 4_000)?;
+        let (proof, vd, common_data) =
+            recursive_proof::<F, C, C, D>(proof, vd, common_data, &config, None, true, true)?;
+        test_serialization(&proof, &vd, &common_data)?;
+
+        Ok(())
+    }
+
     #[test]
     fn test_recursive_verifier_one_lookup() -> Result<()> {
         init_logger();
@@ -258,6 +277,22 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_recursive_verifier_wide_lookup() -> Result<()> {
+        init_logger();
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let config = CircuitConfig::standard_recursion_config();
+
+        let (proof, vd, common_data) = dummy_wide_lookup_proof::<F, C, D>(&config)?;
+        let (proof, vd, common_data) =
+            recursive_proof::<F, C, C, D>(proof, vd, common_data, &config, None, true, true)?;
+        test_serialization(&proof, &vd, &common_data)?;
+
+        Ok(())
+    }
+
     #[test]
     fn test_recursive_verifier_too_many_rows() -> Result<()> {
         init_logger();
@@ -402,6 +437,80 @@ mod tests {
         Ok(())
     }
 
+    /// Wraps a small Goldilocks proof in a proof committed with Poseidon over BN254. Both layers
+    /// use few FRI queries and no grinding, trading security for a wrapping circuit of degree 2^10
+    /// whose BN254 Merkle trees are cheap enough to build in CI.
+    #[test]
+    fn test_recursive_verifier_bn254_wrap_small() -> Result<()> {
+        init_logger();
+        const D: usize = 2;
+        type PC = PoseidonGoldilocksConfig;
+        type BC = PoseidonBN254GoldilocksConfig;
+        type F = <PC as GenericConfig<D>>::F;
+
+        let standard_config = CircuitConfig::standard_recursion_config();
+        let fri_config = FriConfig {
+            cap_height: 0,
+            proof_of_work_bits: 0,
+            num_query_rounds: 4,
+            ..standard_config.fri_config.clone()
+        };
+        let inner_config = CircuitConfig {
+            security_bits: 20,
+            fri_config: FriConfig {
+                rate_bits: 5,
+                ..fri_config.clone()
+            },
+            ..standard_config.clone()
+        };
+        let wrap_config = CircuitConfig {
+            security_bits: 12,
+            fri_config,
+            ..standard_config
+        };
+        let (proof, vd, common_data) = dummy_proof::<F, PC, D>(&inner_config, 100)?;
+
+        let (proof, vd, common_data) = recursive_proof::<F, BC, PC, D>(
+            proof,
+            vd,
+            common_data,
+            &wrap_config,
+            None,
+            false,
+            false,
+        )?;
+        test_serialization(&proof, &vd, &common_data)?;
+
+        Ok(())
+    }
+
+    /// Wraps a recursive Goldilocks proof in a proof committed with Poseidon over BN254. Ignored
+    /// by default as the BN254 Merkle trees are slow to build natively.
+    #[test]
+    #[ignore]
+    fn test_recursive_verifier_bn254_wrap() -> Result<()> {
+        init_logger();
+        const D: usize = 2;
+        type PC = PoseidonGoldilocksConfig;
+        type BC = PoseidonBN254GoldilocksConfig;
+        type F = <PC as GenericConfig<D>>::F;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let (proof, vd, common_data) = dummy_proof::<F, PC, D>(&config, 4_000)?;
+
+        // Inner layers stay Goldilocks-native and are checked with `verify_proof`.
+        let (proof, vd, common_data) =
+            recursive_proof::<F, PC, PC, D>(proof, vd, common_data, &config, None, false, false)?;
+        test_serialization(&proof, &vd, &common_data)?;
+
+        // The wrapping layer commits with Poseidon over BN254.
+        let (proof, vd, common_data) =
+            recursive_proof::<F, BC, PC, D>(proof, vd, common_data, &config, None, false, false)?;
+        test_serialization(&proof, &vd, &common_data)?;
+
+        Ok(())
+    }
+
     type Proof<F, C, const D: usize> = (
         ProofWithPublicInputs<F, C, D>,
         VerifierOnlyCircuitData<C, D>,
@@ -484,6 +593,48 @@ mod tests {
         Ok((proof, data.verifier_only, data.common))
     }
 
+    /// Creates a dummy lookup proof which does lookups into a two-input LUT and a `(u16, u16)` LUT.
+    fn dummy_wide_lookup_proof<
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+        const D: usize,
+    >(
+        config: &CircuitConfig,
+    ) -> Result<Proof<F, C, D>> {
+        let mut builder = CircuitBuilder::<F, D>::new(config.clone());
+        let a = builder.add_virtual_target();
+        let b = builder.add_virtual_target();
+
+        let nibble_pairs: Vec<Vec<F>> = (0..16u64)
+            .cartesian_product(0..16u64)
+            .map(|(a, b)| vec![F::from_canonical_u64(a), F::from_canonical_u64(b)])
+            .collect();
+        let xor_index = builder.add_wide_lookup_table_from_fn(
+            |x| F::from_canonical_u64(x[0].to_canonical_u64() ^ x[1].to_canonical_u64()),
+            &nibble_pairs,
+        );
+        let tip5_table: LookupTable = Arc::new((0..256).zip_eq(TIP5_TABLE).collect());
+        let tip5_index = builder.add_lookup_table_from_pairs(tip5_table);
+
+        let a_xor_b = builder.add_wide_lookup_from_index(&[a, b], xor_index);
+        let tip5_a = builder.add_lookup_from_index(a, tip5_index);
+        builder.register_public_input(a_xor_b);
+        builder.register_public_input(tip5_a);
+
+        let data = builder.build::<C>();
+        let mut inputs = PartialWitness::new();
+        inputs.set_target(a, F::from_canonical_u64(9));
+        inputs.set_target(b, F::from_canonical_u64(5));
+
+        let proof = data.prove(inputs)?;
+        data.verify(proof.clone())?;
+
+        assert_eq!(proof.public_inputs[0], F::from_canonical_u64(9 ^ 5));
+        assert_eq!(proof.public_inputs[1], F::from_canonical_u16(TIP5_TABLE[9]));
+
+        Ok((proof, data.verifier_only, data.common))
+    }
+
     /// Creates a dummy lookup proof which does one lookup to two different LUTs.
     fn dummy_two_luts_proof<
         F: RichField + Extendable<D>,
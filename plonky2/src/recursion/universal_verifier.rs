@@ -0,0 +1,603 @@
+//! Verification of proofs from heterogeneous circuits in a single recursive slot.
+//!
+//! `verify_proof` needs the `CommonCircuitData` of the inner circuit when the outer circuit is
+//! built. A [`UniversalVerifier`] instead checks proofs of any circuit of a bounded family, whose
+//! members may differ in degree, gate set and number of public inputs. The circuit, and hence its
+//! shape, is selected in-circuit by an index: the verifier data of the selected circuit is picked
+//! from a table with `random_access_verifier_data`, and the proof is checked by the recursive
+//! verifier of its shape, while the verifiers of the other shapes check dummy proofs. Since the
+//! verifier data commits to the circuit's constants, sigmas and degree, the selected entry
+//! determines which circuit the proof is for. The cost of the universal verifier grows with the
+//! number of distinct shapes, one recursive verifier per shape.
+//!
+//! To keep that number small, proofs of several circuits can be brought to a single canonical
+//! shape. Circuits can be built directly in the canonical shape with
+//! [`CircuitBuilder::pad_to_common_data`], and proofs of any other circuit whose recursive verifier
+//! fits in the canonical degree and gate set are wrapped into it by a [`CanonicalWrapper`]. Proofs
+//! of a single shape are checked by [`CircuitBuilder::verify_proof_universal`].
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use anyhow::{ensure, Result};
+use hashbrown::{HashMap, HashSet};
+
+use crate::field::extension::Extendable;
+use crate::gates::gate::GateRef;
+use crate::gates::noop::NoopGate;
+use crate::hash::hash_types::RichField;
+use crate::iop::target::Target;
+use crate::iop::witness::{PartialWitness, WitnessWrite};
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::circuit_data::{
+    CircuitConfig, CircuitData, CommonCircuitData, VerifierCircuitData, VerifierCircuitTarget,
+    VerifierOnlyCircuitData,
+};
+use crate::plonk::config::{AlgebraicHasher, GenericConfig};
+use crate::plonk::proof::{ProofWithPublicInputs, ProofWithPublicInputsTarget};
+use crate::recursion::dummy_circuit::{build_dummy_circuit, dummy_proof};
+
+/// Returns the `CommonCircuitData` of circuits built with `config`, of degree `2^degree_bits`,
+/// with the given gate set and number of public inputs.
+pub fn universal_common_data<F, C, const D: usize>(
+    config: CircuitConfig,
+    degree_bits: usize,
+    gates: &[GateRef<F, D>],
+    num_public_inputs: usize,
+) -> CommonCircuitData<F, D>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    let mut builder = CircuitBuilder::<F, D>::new(config);
+    builder.pad_to_shape(degree_bits, gates, num_public_inputs);
+    let common = builder.build::<C>().common;
+    assert_eq!(
+        common.degree_bits(),
+        degree_bits,
+        "The public inputs don't fit in a circuit of the given degree"
+    );
+    common
+}
+
+/// Returns the smallest canonical `CommonCircuitData` which can wrap proofs of all the `inner`
+/// circuits, with `num_public_inputs` public inputs.
+pub fn universal_common_data_for<F, C, const D: usize>(
+    config: CircuitConfig,
+    inner: &[&CommonCircuitData<F, D>],
+    num_public_inputs: usize,
+) -> CommonCircuitData<F, D>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    C::Hasher: AlgebraicHasher<F>,
+{
+    let mut gates = Vec::new();
+    let mut seen = HashSet::new();
+    let mut degree_bits = 0;
+    for inner_common in inner {
+        let mut builder = CircuitBuilder::<F, D>::new(config.clone());
+        let proof = builder.add_virtual_proof_with_pis(inner_common);
+        let verifier_data =
+            builder.add_virtual_verifier_data(inner_common.config.fri_config.cap_height);
+        builder.verify_proof::<C>(&proof, &verifier_data, inner_common);
+        builder.register_public_inputs(&proof.public_inputs);
+        builder.pad_to_shape(0, &[], num_public_inputs);
+        let common = builder.build::<C>().common;
+
+        degree_bits = degree_bits.max(common.degree_bits());
+        for gate in common.gates {
+            if seen.insert(gate.clone()) {
+                gates.push(gate);
+            }
+        }
+    }
+
+    universal_common_data::<F, C, D>(config, degree_bits, &gates, num_public_inputs)
+}
+
+/// A circuit which verifies proofs of some inner circuit and is padded to a canonical shape, so
+/// that its own proofs can be checked by a universal verifier. The inner public inputs are
+/// forwarded, followed by zeros up to the canonical number of public inputs.
+#[derive(Debug)]
+pub struct CanonicalWrapper<F, C, const D: usize>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    pub circuit: CircuitData<F, C, D>,
+    proof_with_pis: ProofWithPublicInputsTarget<D>,
+}
+
+impl<F, C, const D: usize> CanonicalWrapper<F, C, D>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    C::Hasher: AlgebraicHasher<F>,
+{
+    /// Builds a wrapper for proofs of `inner` with `canonical` as common data.
+    pub fn new(
+        inner: &VerifierCircuitData<F, C, D>,
+        canonical: &CommonCircuitData<F, D>,
+    ) -> Result<Self> {
+        ensure!(
+            inner.common.num_public_inputs <= canonical.num_public_inputs,
+            "The inner circuit has {} public inputs, but the canonical shape only has {}",
+            inner.common.num_public_inputs,
+            canonical.num_public_inputs
+        );
+
+        let mut builder = CircuitBuilder::<F, D>::new(canonical.config.clone());
+        let proof_with_pis = builder.add_virtual_proof_with_pis(&inner.common);
+        let inner_verifier_data = builder.constant_verifier_data(&inner.verifier_only);
+        builder.verify_proof::<C>(&proof_with_pis, &inner_verifier_data, &inner.common);
+        builder.register_public_inputs(&proof_with_pis.public_inputs);
+        builder.pad_to_common_data(canonical);
+        let circuit = builder.build::<C>();
+        ensure!(
+            &circuit.common == canonical,
+            "The recursive verifier of the inner circuit doesn't fit the canonical shape"
+        );
+
+        Ok(Self {
+            circuit,
+            proof_with_pis,
+        })
+    }
+
+    /// Wraps a proof of the inner circuit into a proof of canonical shape.
+    pub fn wrap(
+        &self,
+        proof_with_pis: &ProofWithPublicInputs<F, C, D>,
+    ) -> Result<ProofWithPublicInputs<F, C, D>> {
+        let mut inputs = PartialWitness::new();
+        inputs.set_proof_with_pis_target(&self.proof_with_pis, proof_with_pis);
+        self.circuit.prove(inputs)
+    }
+}
+
+/// Targets of a proof checked by a [`UniversalVerifier`].
+#[derive(Clone, Debug)]
+pub struct UniversalProofTarget<const D: usize> {
+    /// One proof per shape. Only the one of the selected circuit's shape is checked against the
+    /// selected verifier data, the others hold dummy proofs.
+    proofs: Vec<ProofWithPublicInputsTarget<D>>,
+    /// The public inputs of the selected proof, followed by zeros up to the largest number of
+    /// public inputs of the verifier's circuits.
+    pub public_inputs: Vec<Target>,
+}
+
+/// A verifier for proofs of any circuit of a fixed set, whose shapes, i.e. their
+/// `CommonCircuitData`, may differ in degree, gate set and number of public inputs.
+#[derive(Debug)]
+pub struct UniversalVerifier<F, C, const D: usize>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    /// The distinct shapes of the circuits.
+    shapes: Vec<CommonCircuitData<F, D>>,
+    /// The index in `shapes` of the shape of each circuit.
+    circuit_shapes: Vec<usize>,
+    verifier_data: Vec<VerifierOnlyCircuitData<C, D>>,
+    /// A proof of a dummy circuit of each shape, which fills the proof targets of the shapes
+    /// which aren't selected.
+    dummy_proofs: Vec<ProofWithPublicInputs<F, C, D>>,
+}
+
+impl<F, C, const D: usize> UniversalVerifier<F, C, D>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F> + 'static,
+    C::Hasher: AlgebraicHasher<F>,
+{
+    /// Builds a universal verifier for proofs of `circuits`, the `i`-th of which is selected by
+    /// the circuit index `i`. Circuits with the same common data share a shape.
+    ///
+    /// The proof targets of the shapes which aren't selected are filled with proofs of dummy
+    /// circuits made of `NoopGate`s, so no circuit may be zero-knowledge, and `NoopGate` must be
+    /// part of the gate set of each of them. An error is returned otherwise.
+    pub fn new(circuits: &[&VerifierCircuitData<F, C, D>]) -> Result<Self> {
+        ensure!(!circuits.is_empty(), "No circuits to verify");
+
+        let mut shapes: Vec<CommonCircuitData<F, D>> = Vec::new();
+        let mut circuit_shapes = Vec::with_capacity(circuits.len());
+        let mut dummy_proofs = Vec::new();
+        for (i, circuit) in circuits.iter().enumerate() {
+            ensure!(
+                circuit.common.config == circuits[0].common.config,
+                "Circuit {} has a different config than circuit 0",
+                i
+            );
+            if let Some(shape) = shapes.iter().position(|shape| shape == &circuit.common) {
+                circuit_shapes.push(shape);
+                continue;
+            }
+
+            ensure!(
+                !circuit.common.config.zero_knowledge,
+                "Circuit {} is zero-knowledge",
+                i
+            );
+            let dummy_circuit = build_dummy_circuit::<F, C, D>(&circuit.common);
+            ensure!(
+                dummy_circuit.common == circuit.common,
+                "Circuit {} doesn't match the dummy circuit of its shape; it must contain a \
+                 NoopGate",
+                i
+            );
+            dummy_proofs.push(dummy_proof::<F, C, D>(&dummy_circuit, HashMap::new())?);
+            circuit_shapes.push(shapes.len());
+            shapes.push(circuit.common.clone());
+        }
+
+        Ok(Self {
+            shapes,
+            circuit_shapes,
+            verifier_data: circuits
+                .iter()
+                .map(|circuit| circuit.verifier_only.clone())
+                .collect(),
+            dummy_proofs,
+        })
+    }
+
+    /// The number of distinct shapes, i.e. of recursive verifiers in the universal verifier.
+    pub fn num_shapes(&self) -> usize {
+        self.shapes.len()
+    }
+
+    /// The number of public inputs exposed by [`UniversalProofTarget`], i.e. the largest number
+    /// of public inputs of the circuits.
+    pub fn num_public_inputs(&self) -> usize {
+        self.shapes
+            .iter()
+            .map(|shape| shape.num_public_inputs)
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// Adds targets for a proof of the circuit at `circuit_index`, and checks the proof. The
+    /// index is range-checked against the number of circuits rounded up to a power of two, and
+    /// any index past the last circuit selects the last circuit.
+    pub fn verify_proof(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        circuit_index: Target,
+    ) -> Result<UniversalProofTarget<D>> {
+        let mut circuit_shapes = self
+            .circuit_shapes
+            .iter()
+            .map(|&shape| builder.constant(F::from_canonical_usize(shape)))
+            .collect::<Vec<_>>();
+        let last = circuit_shapes[circuit_shapes.len() - 1];
+        circuit_shapes.resize(circuit_shapes.len().next_power_of_two(), last);
+        let shape_index = builder.random_access(circuit_index, circuit_shapes);
+
+        let verifier_data = self
+            .verifier_data
+            .iter()
+            .map(|verifier_data| builder.constant_verifier_data(verifier_data))
+            .collect();
+        let selected_verifier_data = builder.select_verifier_data(circuit_index, verifier_data);
+
+        let mut shape_flags = Vec::with_capacity(self.shapes.len());
+        let mut proofs = Vec::with_capacity(self.shapes.len());
+        for (i, shape) in self.shapes.iter().enumerate() {
+            let shape_constant = builder.constant(F::from_canonical_usize(i));
+            let is_selected = builder.is_equal(shape_index, shape_constant);
+            let proof = builder.add_virtual_proof_with_pis(shape);
+            builder.conditionally_verify_proof_or_dummy::<C>(
+                is_selected,
+                &proof,
+                &selected_verifier_data,
+                shape,
+            )?;
+            shape_flags.push(is_selected);
+            proofs.push(proof);
+        }
+
+        // Exactly one shape is selected, so the public inputs are the sum of the public inputs of
+        // each shape weighted by its flag.
+        let zero = builder.zero();
+        let mut public_inputs = vec![zero; self.num_public_inputs()];
+        for (flag, proof) in shape_flags.iter().zip(&proofs) {
+            for (acc, &pi) in public_inputs.iter_mut().zip(&proof.public_inputs) {
+                *acc = builder.mul_add(flag.target, pi, *acc);
+            }
+        }
+
+        Ok(UniversalProofTarget {
+            proofs,
+            public_inputs,
+        })
+    }
+
+    /// Sets the witness of `target` to a proof of the circuit at `circuit_index`. The circuit
+    /// index target passed to [`Self::verify_proof`] must be set separately.
+    pub fn set_proof_target(
+        &self,
+        witness: &mut PartialWitness<F>,
+        target: &UniversalProofTarget<D>,
+        circuit_index: usize,
+        proof_with_pis: &ProofWithPublicInputs<F, C, D>,
+    ) -> Result<()> {
+        ensure!(
+            circuit_index < self.circuit_shapes.len(),
+            "Circuit index {} is out of range",
+            circuit_index
+        );
+        let shape = self.circuit_shapes[circuit_index];
+        ensure!(
+            proof_with_pis.public_inputs.len() == self.shapes[shape].num_public_inputs,
+            "The proof doesn't have the public inputs of circuit {}",
+            circuit_index
+        );
+        for (i, proof_target) in target.proofs.iter().enumerate() {
+            let proof = if i == shape {
+                proof_with_pis
+            } else {
+                &self.dummy_proofs[i]
+            };
+            witness.set_proof_with_pis_target(proof_target, proof);
+        }
+        Ok(())
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
+    /// Verifies a proof with `common_data` as common data, e.g. a canonical shape, for the circuit
+    /// whose verifier data is at `circuit_index` in `verifier_data`. The table is padded to a power of two by repeating its
+    /// last entry, so any index which passes the range check selects one of the given circuits.
+    pub fn verify_proof_universal<C: GenericConfig<D, F = F>>(
+        &mut self,
+        proof_with_pis: &ProofWithPublicInputsTarget<D>,
+        circuit_index: Target,
+        verifier_data: Vec<VerifierCircuitTarget>,
+        common_data: &CommonCircuitData<F, D>,
+    ) where
+        C::Hasher: AlgebraicHasher<F>,
+    {
+        let selected_verifier_data = self.select_verifier_data(circuit_index, verifier_data);
+        self.verify_proof::<C>(proof_with_pis, &selected_verifier_data, common_data);
+    }
+
+    /// Like `random_access_verifier_data`, but pads the table to a power of two by repeating its
+    /// last entry.
+    pub(crate) fn select_verifier_data(
+        &mut self,
+        index: Target,
+        mut verifier_data: Vec<VerifierCircuitTarget>,
+    ) -> VerifierCircuitTarget {
+        assert!(!verifier_data.is_empty(), "No verifier data to select from");
+        let last = verifier_data[verifier_data.len() - 1].clone();
+        verifier_data.resize(verifier_data.len().next_power_of_two(), last);
+        self.random_access_verifier_data(index, verifier_data)
+    }
+
+    /// Pads the circuit being built so that it ends up with `common_data` as common data, provided
+    /// that it uses a subset of its gates, has at most its number of public inputs and fits in its
+    /// degree. Extra public inputs are set to zero.
+    ///
+    /// WARNING: Do not add any gate or public input after calling this!
+    pub fn pad_to_common_data(&mut self, common_data: &CommonCircuitData<F, D>) {
+        assert_eq!(
+            self.config, common_data.config,
+            "Can't pad to common data with a different config"
+        );
+        self.pad_to_shape(
+            common_data.degree_bits(),
+            &common_data.gates,
+            common_data.num_public_inputs,
+        );
+    }
+
+    fn pad_to_shape(
+        &mut self,
+        degree_bits: usize,
+        gates: &[GateRef<F, D>],
+        num_public_inputs: usize,
+    ) {
+        let num_missing_public_inputs = num_public_inputs
+            .checked_sub(self.num_public_inputs())
+            .expect("Too many public inputs");
+        if num_missing_public_inputs > 0 {
+            let zero = self.zero();
+            for _ in 0..num_missing_public_inputs {
+                self.register_public_input(zero);
+            }
+        }
+        for gate in gates {
+            self.add_gate_to_gate_set(gate.clone());
+        }
+        // Having more than half of the rows filled makes the circuit at least of the given degree.
+        // Public input hashing and constant gates can only add rows.
+        if degree_bits > 0 {
+            while self.num_gates() <= 1 << (degree_bits - 1) {
+                self.add_gate(NoopGate, Vec::new());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use anyhow::Result;
+
+    use super::*;
+    use crate::field::types::{Field, Sample};
+    use crate::hash::poseidon::PoseidonHash;
+    use crate::plonk::config::PoseidonGoldilocksConfig;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    #[test]
+    fn test_universal_verifier_shapes() -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+
+        // Two circuits with different gate sets, degrees and numbers of public inputs. Both need
+        // a `NoopGate` for the dummy proofs of their shape.
+        let mut builder = CircuitBuilder::<F, D>::new(config.clone());
+        let x = builder.add_virtual_target();
+        let x_cubed = builder.exp_u64(x, 3);
+        builder.register_public_input(x_cubed);
+        builder.add_gate(NoopGate, vec![]);
+        let cube_circuit = builder.build::<C>();
+
+        // A different circuit of the same shape.
+        let mut builder = CircuitBuilder::<F, D>::new(config.clone());
+        let y = builder.add_virtual_target();
+        let y_fifth = builder.exp_u64(y, 5);
+        builder.register_public_input(y_fifth);
+        builder.add_gate(NoopGate, vec![]);
+        let fifth_power_circuit = builder.build::<C>();
+        assert_eq!(cube_circuit.common, fifth_power_circuit.common);
+
+        let mut builder = CircuitBuilder::<F, D>::new(config.clone());
+        let preimage = builder.add_virtual_targets(4);
+        let hash = builder.hash_n_to_hash_no_pad::<PoseidonHash>(preimage.clone());
+        builder.register_public_inputs(&hash.elements);
+        for _ in 0..1000 {
+            builder.add_gate(NoopGate, vec![]);
+        }
+        let hash_circuit = builder.build::<C>();
+        assert_ne!(
+            cube_circuit.common.degree_bits(),
+            hash_circuit.common.degree_bits()
+        );
+        assert_ne!(cube_circuit.common.gates, hash_circuit.common.gates);
+
+        let cube_data = cube_circuit.verifier_data();
+        let hash_data = hash_circuit.verifier_data();
+        let fifth_power_data = fifth_power_circuit.verifier_data();
+        let universal = UniversalVerifier::new(&[&cube_data, &hash_data, &fifth_power_data])?;
+        assert_eq!(universal.num_shapes(), 2);
+        assert_eq!(universal.num_public_inputs(), 4);
+
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let circuit_index = builder.add_virtual_public_input();
+        let proof_target = universal.verify_proof(&mut builder, circuit_index)?;
+        builder.register_public_inputs(&proof_target.public_inputs);
+        let universal_circuit = builder.build::<C>();
+
+        let universal_witness = |index: usize, proof: &ProofWithPublicInputs<F, C, D>| {
+            let mut pw = PartialWitness::new();
+            pw.set_target(circuit_index, F::from_canonical_usize(index));
+            universal.set_proof_target(&mut pw, &proof_target, index, proof)?;
+            Ok::<_, anyhow::Error>(pw)
+        };
+
+        let x_value = F::rand();
+        let mut pw = PartialWitness::new();
+        pw.set_target(x, x_value);
+        let cube_proof = cube_circuit.prove(pw)?;
+        let universal_proof = universal_circuit.prove(universal_witness(0, &cube_proof)?)?;
+        assert_eq!(universal_proof.public_inputs[1], x_value.exp_u64(3));
+        assert_eq!(universal_proof.public_inputs[2..], [F::ZERO; 3]);
+        universal_circuit.verify(universal_proof)?;
+
+        let mut pw = PartialWitness::new();
+        let preimage_value = F::rand_vec(4);
+        pw.set_target_arr(&preimage, &preimage_value);
+        let hash_proof = hash_circuit.prove(pw)?;
+        let universal_proof = universal_circuit.prove(universal_witness(1, &hash_proof)?)?;
+        assert_eq!(
+            universal_proof.public_inputs[1..],
+            hash_proof.public_inputs[..]
+        );
+        universal_circuit.verify(universal_proof)?;
+
+        // A proof doesn't verify against another circuit of the same shape, nor when a circuit of
+        // another shape is selected.
+        for (claimed_index, index) in [(2, 2), (1, 0)] {
+            let mut pw = PartialWitness::new();
+            pw.set_target(circuit_index, F::from_canonical_usize(claimed_index));
+            universal.set_proof_target(&mut pw, &proof_target, index, &cube_proof)?;
+            assert!(universal_circuit.check_witness(pw).is_err());
+        }
+        assert!(universal
+            .set_proof_target(&mut PartialWitness::new(), &proof_target, 1, &cube_proof)
+            .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_universal_verifier() -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+
+        // Two circuits with different gate sets, degrees and numbers of public inputs.
+        let mut builder = CircuitBuilder::<F, D>::new(config.clone());
+        let x = builder.add_virtual_target();
+        let x_cubed = builder.exp_u64(x, 3);
+        builder.register_public_input(x_cubed);
+        let cube_circuit = builder.build::<C>();
+
+        let mut builder = CircuitBuilder::<F, D>::new(config.clone());
+        let preimage = builder.add_virtual_targets(4);
+        let hash = builder.hash_n_to_hash_no_pad::<PoseidonHash>(preimage.clone());
+        builder.register_public_inputs(&hash.elements);
+        for _ in 0..1000 {
+            builder.add_gate(NoopGate, vec![]);
+        }
+        let hash_circuit = builder.build::<C>();
+        assert_ne!(cube_circuit.common, hash_circuit.common);
+
+        let canonical = universal_common_data_for::<F, C, D>(
+            config.clone(),
+            &[&cube_circuit.common, &hash_circuit.common],
+            4,
+        );
+        let cube_wrapper = CanonicalWrapper::new(&cube_circuit.verifier_data(), &canonical)?;
+        let hash_wrapper = CanonicalWrapper::new(&hash_circuit.verifier_data(), &canonical)?;
+
+        // The universal verifier checks a canonical proof of either circuit, selected by index.
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let proof_with_pis = builder.add_virtual_proof_with_pis(&canonical);
+        let circuit_index = builder.add_virtual_public_input();
+        let verifier_data = [&cube_wrapper, &hash_wrapper]
+            .iter()
+            .map(|wrapper| builder.constant_verifier_data(&wrapper.circuit.verifier_only))
+            .collect();
+        builder.verify_proof_universal::<C>(
+            &proof_with_pis,
+            circuit_index,
+            verifier_data,
+            &canonical,
+        );
+        builder.register_public_inputs(&proof_with_pis.public_inputs);
+        let universal_circuit = builder.build::<C>();
+
+        let universal_witness = |index: usize, proof: &ProofWithPublicInputs<F, C, D>| {
+            let mut pw = PartialWitness::new();
+            pw.set_target(circuit_index, F::from_canonical_usize(index));
+            pw.set_proof_with_pis_target(&proof_with_pis, proof);
+            pw
+        };
+
+        let x_value = F::rand();
+        let mut pw = PartialWitness::new();
+        pw.set_target(x, x_value);
+        let cube_proof = cube_wrapper.wrap(&cube_circuit.prove(pw)?)?;
+        let universal_proof = universal_circuit.prove(universal_witness(0, &cube_proof))?;
+        assert_eq!(universal_proof.public_inputs[0], F::ZERO);
+        assert_eq!(universal_proof.public_inputs[1], x_value.exp_u64(3));
+        universal_circuit.verify(universal_proof)?;
+
+        let mut pw = PartialWitness::new();
+        pw.set_target_arr(&preimage, &F::rand_vec(4));
+        let hash_proof = hash_wrapper.wrap(&hash_circuit.prove(pw)?)?;
+        universal_circuit.verify(universal_circuit.prove(universal_witness(1, &hash_proof))?)?;
+
+        // A proof doesn't verify against another circuit's verifier data.
+        assert!(universal_circuit
+            .check_witness(universal_witness(0, &hash_proof))
+            .is_err());
+
+        Ok(())
+    }
+}
@@ -1,6 +1,6 @@
 use alloc::string::{String, ToString};
-use alloc::vec;
 use alloc::vec::Vec;
+use alloc::{format, vec};
 
 use log::{log, Level};
 
@@ -86,6 +86,44 @@ impl ContextTree {
         self.exit_gate_count = Some(current_gate_count);
     }
 
+    /// Flattens the tree into a list of `(first_row, path)` pairs, sorted by row, where `path` is
+    /// the stack of scopes (formatted as in `open_stack`) which were open when the gate at
+    /// `first_row` was added. Each path applies until the row of the next pair.
+    pub fn gate_contexts(&self) -> Vec<(usize, String)> {
+        let mut events = Vec::new();
+        self.gate_contexts_helper("", &mut events);
+
+        let mut flattened: Vec<(usize, String)> = Vec::new();
+        for (row, path) in events {
+            // A scope which didn't add any gates is superseded by whatever follows it.
+            if flattened
+                .last()
+                .is_some_and(|(last_row, _)| *last_row == row)
+            {
+                flattened.pop();
+            }
+            if flattened.last().map(|(_, last_path)| last_path) != Some(&path) {
+                flattened.push((row, path));
+            }
+        }
+        flattened
+    }
+
+    fn gate_contexts_helper(&self, prefix: &str, events: &mut Vec<(usize, String)>) {
+        let path = if prefix.is_empty() {
+            self.name.clone()
+        } else {
+            format!("{} > {}", prefix, self.name)
+        };
+        events.push((self.enter_gate_count, path.clone()));
+        for child in &self.children {
+            child.gate_contexts_helper(&path, events);
+            if let Some(exit_gate_count) = child.exit_gate_count {
+                events.push((exit_gate_count, path.clone()));
+            }
+        }
+    }
+
     fn gate_count_delta(&self, current_gate_count: usize) -> usize {
         self.exit_gate_count.unwrap_or(current_gate_count) - self.enter_gate_count
     }
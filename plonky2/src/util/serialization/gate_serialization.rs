@@ -98,39 +98,66 @@ pub mod default {
     use crate::gates::constant::ConstantGate;
     use crate::gates::coset_interpolation::CosetInterpolationGate;
     use crate::gates::exponentiation::ExponentiationGate;
+    use crate::gates::keccak_chi::KeccakChiGate;
     use crate::gates::lookup::LookupGate;
     use crate::gates::lookup_table::LookupTableGate;
     use crate::gates::multiplication_extension::MulExtensionGate;
     use crate::gates::noop::NoopGate;
     use crate::gates::poseidon::PoseidonGate;
+    use crate::gates::poseidon2::Poseidon2Gate;
     use crate::gates::poseidon_mds::PoseidonMdsGate;
     use crate::gates::public_input::PublicInputGate;
     use crate::gates::random_access::RandomAccessGate;
     use crate::gates::reducing::ReducingGate;
     use crate::gates::reducing_extension::ReducingExtensionGate;
+    use crate::gates::xor::XorGate;
     use crate::hash::hash_types::RichField;
+    use crate::hash::poseidon2::Poseidon2;
     use crate::util::serialization::GateSerializer;
 
+    /// Implements `GateSerializer` for `$target` with the gates of [`DefaultGateSerializer`],
+    /// followed by the given extra gates. Serializers supporting more gates are built on top of
+    /// the default one this way, so that the default gates keep their tags.
+    macro_rules! impl_default_gate_serializer {
+        ($target:ty $(, $extra_gate_types:ty)*) => {
+            impl_gate_serializer! {
+                $target,
+                ArithmeticGate,
+                ArithmeticExtensionGate<D>,
+                BaseSumGate<2>,
+                ConstantGate,
+                CosetInterpolationGate<F, D>,
+                ExponentiationGate<F, D>,
+                LookupGate,
+                LookupTableGate,
+                MulExtensionGate<D>,
+                NoopGate,
+                PoseidonMdsGate<F, D>,
+                PoseidonGate<F, D>,
+                PublicInputGate,
+                RandomAccessGate<F, D>,
+                ReducingExtensionGate<D>,
+                ReducingGate<D>,
+                XorGate,
+                KeccakChiGate
+                $(, $extra_gate_types)*
+            }
+        };
+    }
+
+    /// A gate serializer supporting the gates of this crate, except the `Poseidon2Gate` which is
+    /// only available for fields implementing `Poseidon2`. See [`Poseidon2GateSerializer`].
     pub struct DefaultGateSerializer;
     impl<F: RichField + Extendable<D>, const D: usize> GateSerializer<F, D> for DefaultGateSerializer {
-        impl_gate_serializer! {
-            DefaultGateSerializer,
-            ArithmeticGate,
-            ArithmeticExtensionGate<D>,
-            BaseSumGate<2>,
-            ConstantGate,
-            CosetInterpolationGate<F, D>,
-            ExponentiationGate<F, D>,
-            LookupGate,
-            LookupTableGate,
-            MulExtensionGate<D>,
-            NoopGate,
-            PoseidonMdsGate<F, D>,
-            PoseidonGate<F, D>,
-            PublicInputGate,
-            RandomAccessGate<F, D>,
-            ReducingExtensionGate<D>,
-            ReducingGate<D>
-        }
+        impl_default_gate_serializer! { DefaultGateSerializer }
+    }
+
+    /// A gate serializer supporting all the gates of [`DefaultGateSerializer`] as well as the
+    /// `Poseidon2Gate`, e.g. for circuits built with `Poseidon2GoldilocksConfig`.
+    pub struct Poseidon2GateSerializer;
+    impl<F: RichField + Poseidon2 + Extendable<D>, const D: usize> GateSerializer<F, D>
+        for Poseidon2GateSerializer
+    {
+        impl_default_gate_serializer! { Poseidon2GateSerializer, Poseidon2Gate<F, D> }
     }
 }
@@ -100,23 +100,31 @@ pub mod default {
 
     use crate::gadgets::arithmetic::EqualityGenerator;
     use crate::gadgets::arithmetic_extension::QuotientGeneratorExtension;
+    use crate::gadgets::biguint::{BigUintDivRemGenerator, LimbDecompositionGenerator};
+    use crate::gadgets::bn254_tower::Fp12DivisionGenerator;
+    use crate::gadgets::nonnative::NonNativeInverseGenerator;
     use crate::gadgets::range_check::LowHighGenerator;
     use crate::gadgets::split_base::BaseSumGenerator;
     use crate::gadgets::split_join::{SplitGenerator, WireSplitGenerator};
+    use crate::gadgets::uint32::ByteDecompositionGenerator;
     use crate::gates::arithmetic_base::ArithmeticBaseGenerator;
     use crate::gates::arithmetic_extension::ArithmeticExtensionGenerator;
     use crate::gates::base_sum::BaseSplitGenerator;
     use crate::gates::coset_interpolation::InterpolationGenerator;
     use crate::gates::exponentiation::ExponentiationGenerator;
+    use crate::gates::keccak_chi::KeccakChiGenerator;
     use crate::gates::lookup::LookupGenerator;
     use crate::gates::lookup_table::LookupTableGenerator;
     use crate::gates::multiplication_extension::MulExtensionGenerator;
     use crate::gates::poseidon::PoseidonGenerator;
+    use crate::gates::poseidon2::Poseidon2Generator;
     use crate::gates::poseidon_mds::PoseidonMdsGenerator;
     use crate::gates::random_access::RandomAccessGenerator;
     use crate::gates::reducing::ReducingGenerator;
     use crate::gates::reducing_extension::ReducingGenerator as ReducingExtensionGenerator;
+    use crate::gates::xor::XorGenerator;
     use crate::hash::hash_types::RichField;
+    use crate::hash::poseidon2::Poseidon2;
     use crate::iop::generator::{
         ConstantGenerator, CopyGenerator, NonzeroTestGenerator, RandomValueGenerator,
     };
@@ -124,6 +132,53 @@ pub mod default {
     use crate::recursion::dummy_circuit::DummyProofGenerator;
     use crate::util::serialization::WitnessGeneratorSerializer;
 
+    /// Implements `WitnessGeneratorSerializer` for `$target` with the generators of
+    /// [`DefaultGeneratorSerializer`], followed by the given extra generators. Serializers
+    /// supporting more generators are built on top of the default one this way, so that the
+    /// default generators keep their tags.
+    macro_rules! impl_default_generator_serializer {
+        ($target:ty $(, $extra_generator_types:ty)*) => {
+            impl_generator_serializer! {
+                $target,
+                ArithmeticBaseGenerator<F, D>,
+                ArithmeticExtensionGenerator<F, D>,
+                BaseSplitGenerator<2>,
+                BaseSumGenerator<2>,
+                ConstantGenerator<F>,
+                CopyGenerator,
+                DummyProofGenerator<F, C, D>,
+                EqualityGenerator,
+                ExponentiationGenerator<F, D>,
+                InterpolationGenerator<F, D>,
+                LookupGenerator,
+                LookupTableGenerator,
+                LowHighGenerator,
+                MulExtensionGenerator<F, D>,
+                NonzeroTestGenerator,
+                PoseidonGenerator<F, D>,
+                PoseidonMdsGenerator<D>,
+                QuotientGeneratorExtension<D>,
+                RandomAccessGenerator<F, D>,
+                RandomValueGenerator,
+                ReducingGenerator<D>,
+                ReducingExtensionGenerator<D>,
+                SplitGenerator,
+                WireSplitGenerator,
+                ByteDecompositionGenerator,
+                XorGenerator,
+                KeccakChiGenerator,
+                BigUintDivRemGenerator,
+                LimbDecompositionGenerator,
+                NonNativeInverseGenerator,
+                Fp12DivisionGenerator
+                $(, $extra_generator_types)*
+            }
+        };
+    }
+
+    /// A generator serializer supporting the generators of this crate, except the
+    /// `Poseidon2Generator` which is only available for fields implementing `Poseidon2`. See
+    /// [`Poseidon2GeneratorSerializer`].
     pub struct DefaultGeneratorSerializer<C: GenericConfig<D>, const D: usize> {
         pub _phantom: PhantomData<C>,
     }
@@ -134,32 +189,21 @@ pub mod default {
         C: GenericConfig<D, F = F> + 'static,
         C::Hasher: AlgebraicHasher<F>,
     {
-        impl_generator_serializer! {
-            DefaultGeneratorSerializer,
-            ArithmeticBaseGenerator<F, D>,
-            ArithmeticExtensionGenerator<F, D>,
-            BaseSplitGenerator<2>,
-            BaseSumGenerator<2>,
-            ConstantGenerator<F>,
-            CopyGenerator,
-            DummyProofGenerator<F, C, D>,
-            EqualityGenerator,
-            ExponentiationGenerator<F, D>,
-            InterpolationGenerator<F, D>,
-            LookupGenerator,
-            LookupTableGenerator,
-            LowHighGenerator,
-            MulExtensionGenerator<F, D>,
-            NonzeroTestGenerator,
-            PoseidonGenerator<F, D>,
-            PoseidonMdsGenerator<D>,
-            QuotientGeneratorExtension<D>,
-            RandomAccessGenerator<F, D>,
-            RandomValueGenerator,
-            ReducingGenerator<D>,
-            ReducingExtensionGenerator<D>,
-            SplitGenerator,
-            WireSplitGenerator
-        }
+        impl_default_generator_serializer! { DefaultGeneratorSerializer }
+    }
+
+    /// A generator serializer supporting all the generators of [`DefaultGeneratorSerializer`] as
+    /// well as the `Poseidon2Generator`, e.g. for circuits built with `Poseidon2GoldilocksConfig`.
+    pub struct Poseidon2GeneratorSerializer<C: GenericConfig<D>, const D: usize> {
+        pub _phantom: PhantomData<C>,
+    }
+
+    impl<F, C, const D: usize> WitnessGeneratorSerializer<F, D> for Poseidon2GeneratorSerializer<C, D>
+    where
+        F: RichField + Poseidon2 + Extendable<D>,
+        C: GenericConfig<D, F = F> + 'static,
+        C::Hasher: AlgebraicHasher<F>,
+    {
+        impl_default_generator_serializer! { Poseidon2GeneratorSerializer, Poseidon2Generator<F, D> }
     }
 }
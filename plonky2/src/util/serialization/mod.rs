@@ -5,6 +5,7 @@ pub mod generator_serialization;
 pub mod gate_serialization;
 
 use alloc::collections::BTreeMap;
+use alloc::string::String;
 use alloc::sync::Arc;
 use alloc::vec;
 use alloc::vec::Vec;
@@ -13,9 +14,11 @@ use core::fmt::{Debug, Display, Formatter};
 use core::mem::size_of;
 use core::ops::Range;
 
-pub use gate_serialization::default::DefaultGateSerializer;
+pub use gate_serialization::default::{DefaultGateSerializer, Poseidon2GateSerializer};
 pub use gate_serialization::GateSerializer;
-pub use generator_serialization::default::DefaultGeneratorSerializer;
+pub use generator_serialization::default::{
+    DefaultGeneratorSerializer, Poseidon2GeneratorSerializer,
+};
 pub use generator_serialization::WitnessGeneratorSerializer;
 use hashbrown::HashMap;
 
@@ -32,6 +35,7 @@ use crate::fri::{FriConfig, FriParams};
 use crate::gadgets::polynomial::PolynomialCoeffsExtTarget;
 use crate::gates::gate::GateRef;
 use crate::gates::lookup::Lookup;
+use crate::gates::lookup_table::WideLookupTable;
 use crate::gates::selectors::SelectorsInfo;
 use crate::hash::hash_types::{HashOutTarget, MerkleCapTarget, RichField};
 use crate::hash::merkle_proofs::{MerkleProof, MerkleProofTarget};
@@ -76,6 +80,22 @@ pub trait Remaining: Read {
     }
 }
 
+/// The number of field elements in the leaves of each initial FRI tree of a Plonk proof.
+fn fri_initial_leaf_sizes<F: RichField + Extendable<D>, const D: usize>(
+    common_data: &CommonCircuitData<F, D>,
+) -> Vec<usize> {
+    let config = &common_data.config;
+    let salt = salt_size(common_data.fri_params.hiding);
+    vec![
+        common_data.num_constants + config.num_routed_wires,
+        config.num_wires + salt,
+        config.num_challenges
+            * (1 + common_data.num_partial_products + common_data.num_lookup_polys)
+            + salt,
+        config.num_challenges * common_data.quotient_degree_factor + salt,
+    ]
+}
+
 /// Similar to `std::io::Read`, but works with no_std.
 pub trait Read {
     /// Reads exactly the length of `bytes` from `self` and writes it to `bytes`.
@@ -131,6 +151,14 @@ pub trait Read {
         Ok(u32::from_le_bytes(buf))
     }
 
+    /// Reads a `u64` value from `self`.
+    #[inline]
+    fn read_u64(&mut self) -> IoResult<u64> {
+        let mut buf = [0; size_of::<u64>()];
+        self.read_exact(&mut buf)?;
+        Ok(u64::from_le_bytes(buf))
+    }
+
     /// Reads a `usize` value from `self`.
     #[inline]
     fn read_usize(&mut self) -> IoResult<usize> {
@@ -151,6 +179,15 @@ pub trait Read {
         Ok(res)
     }
 
+    /// Reads a length-prefixed UTF-8 string from `self`.
+    #[inline]
+    fn read_string(&mut self) -> IoResult<String> {
+        let len = self.read_usize()?;
+        let mut buf = vec![0; len];
+        self.read_exact(&mut buf)?;
+        String::from_utf8(buf).map_err(|_| IoError)
+    }
+
     /// Reads a element from the field `F` with size less than `2^64` from `self.`
     #[inline]
     fn read_field<F>(&mut self) -> IoResult<F>
@@ -436,32 +473,24 @@ pub trait Read {
         F: RichField + Extendable<D>,
         C: GenericConfig<D, F = F>,
     {
-        let config = &common_data.config;
-        let salt = salt_size(common_data.fri_params.hiding);
-        let mut evals_proofs = Vec::with_capacity(4);
-
-        let constants_sigmas_v =
-            self.read_field_vec(common_data.num_constants + config.num_routed_wires)?;
-        let constants_sigmas_p = self.read_merkle_proof()?;
-        evals_proofs.push((constants_sigmas_v, constants_sigmas_p));
-
-        let wires_v = self.read_field_vec(config.num_wires + salt)?;
-        let wires_p = self.read_merkle_proof()?;
-        evals_proofs.push((wires_v, wires_p));
-
-        let zs_partial_v = self.read_field_vec(
-            config.num_challenges
-                * (1 + common_data.num_partial_products + common_data.num_lookup_polys)
-                + salt,
-        )?;
-        let zs_partial_p = self.read_merkle_proof()?;
-        evals_proofs.push((zs_partial_v, zs_partial_p));
-
-        let quotient_v =
-            self.read_field_vec(config.num_challenges * common_data.quotient_degree_factor + salt)?;
-        let quotient_p = self.read_merkle_proof()?;
-        evals_proofs.push((quotient_v, quotient_p));
+        self.read_fri_initial_proof_with_leaf_sizes::<F, C, D>(&fri_initial_leaf_sizes(common_data))
+    }
 
+    /// Reads a value of type [`FriInitialTreeProof`] from `self`, where the leaves of the `i`-th
+    /// initial tree hold `leaf_sizes[i]` field elements.
+    #[inline]
+    fn read_fri_initial_proof_with_leaf_sizes<F, C, const D: usize>(
+        &mut self,
+        leaf_sizes: &[usize],
+    ) -> IoResult<FriInitialTreeProof<F, C::Hasher>>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+    {
+        let evals_proofs = leaf_sizes
+            .iter()
+            .map(|&leaf_size| Ok((self.read_field_vec(leaf_size)?, self.read_merkle_proof()?)))
+            .collect::<IoResult<Vec<_>>>()?;
         Ok(FriInitialTreeProof { evals_proofs })
     }
 
@@ -520,12 +549,31 @@ pub trait Read {
         F: RichField + Extendable<D>,
         C: GenericConfig<D, F = F>,
     {
-        let config = &common_data.config;
-        let mut fqrs = Vec::with_capacity(config.fri_config.num_query_rounds);
-        for _ in 0..config.fri_config.num_query_rounds {
-            let initial_trees_proof = self.read_fri_initial_proof::<F, C, D>(common_data)?;
-            let steps = common_data
-                .fri_params
+        self.read_fri_query_rounds_with_params::<F, C, D>(
+            &common_data.fri_params,
+            &fri_initial_leaf_sizes(common_data),
+        )
+    }
+
+    /// Reads a vector of [`FriQueryRound`]s from `self` with the given FRI parameters and initial
+    /// tree leaf sizes.
+    #[inline]
+    #[allow(clippy::type_complexity)]
+    fn read_fri_query_rounds_with_params<F, C, const D: usize>(
+        &mut self,
+        fri_params: &FriParams,
+        leaf_sizes: &[usize],
+    ) -> IoResult<Vec<FriQueryRound<F, C::Hasher, D>>>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+    {
+        let num_query_rounds = fri_params.config.num_query_rounds;
+        let mut fqrs = Vec::with_capacity(num_query_rounds);
+        for _ in 0..num_query_rounds {
+            let initial_trees_proof =
+                self.read_fri_initial_proof_with_leaf_sizes::<F, C, D>(leaf_sizes)?;
+            let steps = fri_params
                 .reduction_arity_bits
                 .iter()
                 .map(|&ar| self.read_fri_query_step::<F, C, D>(1 << ar, false))
@@ -569,14 +617,31 @@ pub trait Read {
         F: RichField + Extendable<D>,
         C: GenericConfig<D, F = F>,
     {
-        let config = &common_data.config;
-        let commit_phase_merkle_caps = (0..common_data.fri_params.reduction_arity_bits.len())
-            .map(|_| self.read_merkle_cap(config.fri_config.cap_height))
+        self.read_fri_proof_with_params::<F, C, D>(
+            &common_data.fri_params,
+            &fri_initial_leaf_sizes(common_data),
+        )
+    }
+
+    /// Reads a value of type [`FriProof`] from `self` with the given FRI parameters and initial
+    /// tree leaf sizes.
+    #[inline]
+    fn read_fri_proof_with_params<F, C, const D: usize>(
+        &mut self,
+        fri_params: &FriParams,
+        leaf_sizes: &[usize],
+    ) -> IoResult<FriProof<F, C::Hasher, D>>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+    {
+        let commit_phase_merkle_caps = (0..fri_params.reduction_arity_bits.len())
+            .map(|_| self.read_merkle_cap(fri_params.config.cap_height))
             .collect::<Result<Vec<_>, _>>()?;
-        let query_round_proofs = self.read_fri_query_rounds::<F, C, D>(common_data)?;
-        let final_poly = PolynomialCoeffs::new(
-            self.read_field_ext_vec::<F, D>(common_data.fri_params.final_poly_len())?,
-        );
+        let query_round_proofs =
+            self.read_fri_query_rounds_with_params::<F, C, D>(fri_params, leaf_sizes)?;
+        let final_poly =
+            PolynomialCoeffs::new(self.read_field_ext_vec::<F, D>(fri_params.final_poly_len())?);
         let pow_witness = self.read_field()?;
         Ok(FriProof {
             commit_phase_merkle_caps,
@@ -891,6 +956,12 @@ pub trait Read {
             lut_to_lookups.push(self.read_target_lut()?);
         }
 
+        let length = self.read_usize()?;
+        let mut gate_contexts = Vec::with_capacity(length);
+        for _ in 0..length {
+            gate_contexts.push((self.read_usize()?, self.read_string()?));
+        }
+
         Ok(ProverOnlyCircuitData {
             generators,
             generator_indices_by_watches,
@@ -903,6 +974,7 @@ pub trait Read {
             circuit_digest,
             lookup_rows,
             lut_to_lookups,
+            gate_contexts,
         })
     }
 
@@ -1049,8 +1121,25 @@ pub trait Read {
         F: RichField + Extendable<D>,
         C: GenericConfig<D, F = F>,
     {
-        let config = &common_data.config;
-        let original_indices = (0..config.fri_config.num_query_rounds)
+        self.read_compressed_fri_query_rounds_with_params::<F, C, D>(
+            &common_data.fri_params,
+            &fri_initial_leaf_sizes(common_data),
+        )
+    }
+
+    /// Reads a value of type [`CompressedFriQueryRounds`] from `self` with the given FRI
+    /// parameters and initial tree leaf sizes.
+    #[inline]
+    fn read_compressed_fri_query_rounds_with_params<F, C, const D: usize>(
+        &mut self,
+        fri_params: &FriParams,
+        leaf_sizes: &[usize],
+    ) -> IoResult<CompressedFriQueryRounds<F, C::Hasher, D>>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+    {
+        let original_indices = (0..fri_params.config.num_query_rounds)
             .map(|_| self.read_u32().map(|i| i as usize))
             .collect::<Result<Vec<_>, _>>()?;
         let mut indices = original_indices.clone();
@@ -1058,12 +1147,15 @@ pub trait Read {
         indices.dedup();
         let mut pairs = Vec::new();
         for &i in &indices {
-            pairs.push((i, self.read_fri_initial_proof::<F, C, D>(common_data)?));
+            pairs.push((
+                i,
+                self.read_fri_initial_proof_with_leaf_sizes::<F, C, D>(leaf_sizes)?,
+            ));
         }
         let initial_trees_proofs = HashMap::from_iter(pairs);
 
-        let mut steps = Vec::with_capacity(common_data.fri_params.reduction_arity_bits.len());
-        for &a in &common_data.fri_params.reduction_arity_bits {
+        let mut steps = Vec::with_capacity(fri_params.reduction_arity_bits.len());
+        for &a in &fri_params.reduction_arity_bits {
             indices.iter_mut().for_each(|x| {
                 *x >>= a;
             });
@@ -1097,14 +1189,31 @@ pub trait Read {
         F: RichField + Extendable<D>,
         C: GenericConfig<D, F = F>,
     {
-        let config = &common_data.config;
-        let commit_phase_merkle_caps = (0..common_data.fri_params.reduction_arity_bits.len())
-            .map(|_| self.read_merkle_cap(config.fri_config.cap_height))
+        self.read_compressed_fri_proof_with_params::<F, C, D>(
+            &common_data.fri_params,
+            &fri_initial_leaf_sizes(common_data),
+        )
+    }
+
+    /// Reads a value of type [`CompressedFriProof`] from `self` with the given FRI parameters and
+    /// initial tree leaf sizes.
+    #[inline]
+    fn read_compressed_fri_proof_with_params<F, C, const D: usize>(
+        &mut self,
+        fri_params: &FriParams,
+        leaf_sizes: &[usize],
+    ) -> IoResult<CompressedFriProof<F, C::Hasher, D>>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+    {
+        let commit_phase_merkle_caps = (0..fri_params.reduction_arity_bits.len())
+            .map(|_| self.read_merkle_cap(fri_params.config.cap_height))
             .collect::<Result<Vec<_>, _>>()?;
-        let query_round_proofs = self.read_compressed_fri_query_rounds::<F, C, D>(common_data)?;
-        let final_poly = PolynomialCoeffs::new(
-            self.read_field_ext_vec::<F, D>(common_data.fri_params.final_poly_len())?,
-        );
+        let query_round_proofs =
+            self.read_compressed_fri_query_rounds_with_params::<F, C, D>(fri_params, leaf_sizes)?;
+        let final_poly =
+            PolynomialCoeffs::new(self.read_field_ext_vec::<F, D>(fri_params.final_poly_len())?);
         let pow_witness = self.read_field()?;
         Ok(CompressedFriProof {
             commit_phase_merkle_caps,
@@ -1158,16 +1267,27 @@ pub trait Read {
         })
     }
 
-    /// Reads a lookup table stored as `Vec<(u16, u16)>` from `self`.
+    /// Reads a lookup table stored as a `WideLookupTable` from `self`.
     #[inline]
-    fn read_lut(&mut self) -> IoResult<Vec<(u16, u16)>> {
+    fn read_lut(&mut self) -> IoResult<WideLookupTable> {
+        let num_inputs = self.read_usize()?;
         let length = self.read_usize()?;
-        let mut lut = Vec::with_capacity(length);
+        // `WideLookupTable::new` panics on empty tables, so reject them here. Every row is read
+        // with the same `num_inputs + 1` width. Both sizes are untrusted, so the rows are not
+        // preallocated: a bogus size fails on the first missing entry instead.
+        if num_inputs == 0 || num_inputs == usize::MAX || length == 0 {
+            return Err(IoError);
+        }
+        let mut rows = Vec::new();
         for _ in 0..length {
-            lut.push((self.read_u16()?, self.read_u16()?));
+            rows.push(
+                (0..=num_inputs)
+                    .map(|_| self.read_u64())
+                    .collect::<IoResult<Vec<_>>>()?,
+            );
         }
 
-        Ok(lut)
+        Ok(WideLookupTable::new(num_inputs, rows))
     }
 
     /// Reads a target lookup table stored as `Lookup` from `self`.
@@ -1176,7 +1296,7 @@ pub trait Read {
         let length = self.read_usize()?;
         let mut lut = Vec::with_capacity(length);
         for _ in 0..length {
-            lut.push((self.read_target()?, self.read_target()?));
+            lut.push((self.read_target_vec()?, self.read_target()?));
         }
 
         Ok(lut)
@@ -1232,6 +1352,12 @@ pub trait Write {
         self.write_all(&x.to_le_bytes())
     }
 
+    /// Writes a `u64` value to `self`.
+    #[inline]
+    fn write_u64(&mut self, x: u64) -> IoResult<()> {
+        self.write_all(&x.to_le_bytes())
+    }
+
     /// Writes a word `x` to `self.`
     #[inline]
     fn write_usize(&mut self, x: usize) -> IoResult<()> {
@@ -1249,6 +1375,13 @@ pub trait Write {
         Ok(())
     }
 
+    /// Writes a length-prefixed UTF-8 string `s` to `self`.
+    #[inline]
+    fn write_string(&mut self, s: &str) -> IoResult<()> {
+        self.write_usize(s.len())?;
+        self.write_all(s.as_bytes())
+    }
+
     /// Writes an element `x` from the field `F` to `self`.
     #[inline]
     fn write_field<F>(&mut self, x: F) -> IoResult<()>
@@ -1853,6 +1986,7 @@ pub trait Write {
             circuit_digest,
             lookup_rows,
             lut_to_lookups,
+            gate_contexts,
         } = prover_only_circuit_data;
 
         self.write_usize(generators.len())?;
@@ -1903,6 +2037,12 @@ pub trait Write {
             self.write_target_lut(tlut)?;
         }
 
+        self.write_usize(gate_contexts.len())?;
+        for (first_row, path) in gate_contexts.iter() {
+            self.write_usize(*first_row)?;
+            self.write_string(path)?;
+        }
+
         Ok(())
     }
 
@@ -2112,11 +2252,13 @@ pub trait Write {
 
     /// Writes a lookup table to `self`.
     #[inline]
-    fn write_lut(&mut self, lut: &[(u16, u16)]) -> IoResult<()> {
+    fn write_lut(&mut self, lut: &WideLookupTable) -> IoResult<()> {
+        self.write_usize(lut.num_inputs())?;
         self.write_usize(lut.len())?;
-        for (a, b) in lut.iter() {
-            self.write_u16(*a)?;
-            self.write_u16(*b)?;
+        for i in 0..lut.len() {
+            for &x in lut.row(i) {
+                self.write_u64(x)?;
+            }
         }
 
         Ok(())
@@ -2124,10 +2266,10 @@ pub trait Write {
 
     /// Writes a target lookup table to `self`.
     #[inline]
-    fn write_target_lut(&mut self, lut: &[(Target, Target)]) -> IoResult<()> {
+    fn write_target_lut(&mut self, lut: &[(Vec<Target>, Target)]) -> IoResult<()> {
         self.write_usize(lut.len())?;
         for (a, b) in lut.iter() {
-            self.write_target(*a)?;
+            self.write_target_vec(a)?;
             self.write_target(*b)?;
         }
 
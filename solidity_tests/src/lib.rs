@@ -0,0 +1,3 @@
+//! Tests compiling the Solidity verifier contracts generated by `plonky2::export::solidity` and
+//! running them on `revm`. They live in their own crate so that `plonky2` doesn't depend on the
+//! EVM.
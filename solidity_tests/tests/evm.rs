@@ -0,0 +1,180 @@
+use std::io::Write;
+use std::iter::once;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use anyhow::{anyhow, bail, ensure, Result};
+use plonky2::export::solidity::{
+    generate_solidity_verifier, generate_test_vectors, SolidityTestVector,
+};
+use plonky2::field::types::Field;
+use plonky2::iop::witness::{PartialWitness, WitnessWrite};
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+use plonky2::plonk::circuit_data::CircuitConfig;
+use plonky2::plonk::config::{GenericConfig, KeccakGoldilocksConfig};
+use revm::db::{CacheDB, EmptyDB};
+use revm::primitives::{AccountInfo, Address, Bytecode, ExecutionResult, Output, TxKind, U256};
+use revm::Evm;
+
+const D: usize = 2;
+type C = KeccakGoldilocksConfig;
+type F = <C as GenericConfig<D>>::F;
+
+/// The `solc` release used unless `$SOLC` is set.
+const SOLC_VERSION: &str = "0.8.24";
+
+/// Returns the path of the `solc` binary in `$SOLC` or, if unset, of a static build of
+/// `SOLC_VERSION`, downloaded into the target directory on first use.
+fn solc() -> Result<PathBuf> {
+    if let Ok(solc) = std::env::var("SOLC") {
+        return Ok(solc.into());
+    }
+    let path = Path::new(env!("CARGO_TARGET_TMPDIR")).join(format!("solc-{SOLC_VERSION}"));
+    if !path.exists() {
+        download_solc(&path)?;
+    }
+    let version = Command::new(&path).arg("--version").output()?;
+    ensure!(
+        String::from_utf8_lossy(&version.stdout).contains(&format!("Version: {SOLC_VERSION}+")),
+        "{} is not solc {SOLC_VERSION}",
+        path.display()
+    );
+    Ok(path)
+}
+
+#[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+fn download_solc(path: &Path) -> Result<()> {
+    use std::fs::{rename, set_permissions, Permissions};
+    use std::os::unix::fs::PermissionsExt;
+
+    let url = format!(
+        "https://github.com/ethereum/solidity/releases/download/v{SOLC_VERSION}/solc-static-linux"
+    );
+    let download = path.with_file_name(format!("solc-{SOLC_VERSION}.download"));
+    let status = Command::new("curl")
+        .args(["-sSfL", "-o"])
+        .arg(&download)
+        .arg(&url)
+        .status()?;
+    ensure!(status.success(), "Failed to download {url}");
+    set_permissions(&download, Permissions::from_mode(0o755))?;
+    rename(&download, path)?;
+    Ok(())
+}
+
+#[cfg(not(all(target_os = "linux", target_arch = "x86_64")))]
+fn download_solc(_path: &Path) -> Result<()> {
+    bail!("No static solc build for this platform, set $SOLC to a solc {SOLC_VERSION} binary")
+}
+
+/// Compiles `contract` with `solc`, returning the runtime bytecode of `contract_name`.
+fn compile(contract: &str, contract_name: &str) -> Result<Vec<u8>> {
+    let input = serde_json::json!({
+        "language": "Solidity",
+        "sources": { "Verifier.sol": { "content": contract } },
+        "settings": {
+            "optimizer": { "enabled": true, "runs": 200 },
+            "outputSelection": { "*": { "*": ["evm.deployedBytecode.object"] } },
+        },
+    });
+    let mut child = Command::new(solc()?)
+        .arg("--standard-json")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()?;
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.to_string().as_bytes())?;
+    let output: serde_json::Value = serde_json::from_slice(&child.wait_with_output()?.stdout)?;
+
+    if let Some(errors) = output["errors"].as_array() {
+        let errors = errors
+            .iter()
+            .filter(|e| e["severity"] == "error")
+            .map(|e| e["formattedMessage"].to_string())
+            .collect::<Vec<_>>();
+        ensure!(errors.is_empty(), "solc failed: {}", errors.join("\n"));
+    }
+    let bytecode = output["contracts"]["Verifier.sol"][contract_name]["evm"]["deployedBytecode"]
+        ["object"]
+        .as_str()
+        .ok_or_else(|| anyhow!("solc returned no bytecode"))?;
+    ensure!(bytecode.len() % 2 == 0, "Odd-length bytecode");
+    (0..bytecode.len())
+        .step_by(2)
+        .map(|i| Ok(u8::from_str_radix(&bytecode[i..i + 2], 16)?))
+        .collect()
+}
+
+/// An ABI-encoded `uint256`.
+fn word(x: usize) -> [u8; 32] {
+    let mut word = [0; 32];
+    word[24..].copy_from_slice(&(x as u64).to_be_bytes());
+    word
+}
+
+/// ABI-encodes a call to `verify(uint64[],uint64[])`.
+fn verify_call(vector: &SolidityTestVector) -> Vec<u8> {
+    let array = |xs: &[u64]| {
+        once(word(xs.len()))
+            .chain(xs.iter().map(|&x| word(x as usize)))
+            .flatten()
+            .collect::<Vec<u8>>()
+    };
+    let proof = array(&vector.proof);
+    let public_inputs = array(&vector.public_inputs);
+
+    let mut data = revm::primitives::keccak256("verify(uint64[],uint64[])")[..4].to_vec();
+    data.extend(word(64));
+    data.extend(word(64 + proof.len()));
+    data.extend(proof);
+    data.extend(public_inputs);
+    data
+}
+
+#[test]
+fn test_solidity_verifier_on_evm() -> Result<()> {
+    let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+    let x = builder.add_virtual_target();
+    let y = builder.exp_u64(x, 5);
+    let z = builder.mul_add(x, y, x);
+    builder.register_public_inputs(&[x, z]);
+    let data = builder.build::<C>();
+    let mut pw = PartialWitness::new();
+    pw.set_target(x, F::from_canonical_u64(12345));
+    let proof = data.prove(pw)?;
+
+    let contract = generate_solidity_verifier(&data.verifier_data(), "TestVerifier")?;
+    let bytecode = compile(&contract, "TestVerifier")?;
+    let address = Address::repeat_byte(0x42);
+    let mut db = CacheDB::new(EmptyDB::default());
+    let code = Bytecode::new_raw(bytecode.into());
+    db.insert_account_info(
+        address,
+        AccountInfo::new(U256::ZERO, 0, code.hash_slow(), code),
+    );
+
+    let vectors = generate_test_vectors(&proof, &data.common)?;
+    for vector in &vectors {
+        let mut evm = Evm::builder()
+            .with_ref_db(&db)
+            .modify_tx_env(|tx| {
+                tx.transact_to = TxKind::Call(address);
+                tx.data = verify_call(vector).into();
+            })
+            .build();
+        let accepted = match evm.transact()?.result {
+            ExecutionResult::Success {
+                output: Output::Call(output),
+                ..
+            } => output[..] == word(1),
+            ExecutionResult::Revert { .. } => false,
+            result => bail!("Unexpected result {result:?}"),
+        };
+        assert_eq!(accepted, vector.valid);
+    }
+
+    Ok(())
+}
@@ -9,6 +9,11 @@ pub struct StarkConfig {
     pub num_challenges: usize,
 
     pub fri_config: FriConfig,
+
+    /// Whether proofs should hide the trace. If set, all oracles are committed to with salted
+    /// Merkle leaves, and the trace and auxiliary polynomials are randomized outside of the trace
+    /// domain, which doubles their degree.
+    pub zero_knowledge: bool,
 }
 
 impl StarkConfig {
@@ -25,10 +30,27 @@ impl StarkConfig {
                 reduction_strategy: FriReductionStrategy::ConstantArityBits(4, 5),
                 num_query_rounds: 84,
             },
+            zero_knowledge: false,
         }
     }
 
-    pub(crate) fn fri_params(&self, degree_bits: usize) -> FriParams {
-        self.fri_config.fri_params(degree_bits, false)
+    /// Same as `standard_fast_config`, but with zero-knowledge enabled. The randomized trace
+    /// polynomials have twice the degree, so a degree-3 STARK has a quotient of 5 chunks, which
+    /// needs a rate of 4. The number of queries is halved accordingly.
+    pub fn standard_fast_zk_config() -> Self {
+        let mut config = Self::standard_fast_config();
+        config.fri_config.rate_bits = 2;
+        config.fri_config.num_query_rounds = 42;
+        config.zero_knowledge = true;
+        config
+    }
+
+    /// The FRI parameters for a trace of `2^degree_bits` rows. In zero-knowledge mode, the
+    /// committed polynomials have degree less than `2^(degree_bits + 1)`.
+    pub fn fri_params(&self, degree_bits: usize) -> FriParams {
+        self.fri_config.fri_params(
+            degree_bits + self.zero_knowledge as usize,
+            self.zero_knowledge,
+        )
     }
 }
@@ -1,4 +1,4 @@
-//! This crate provides support for cross-table lookups.
+//! This module provides support for cross-table lookups.
 //!
 //! If a STARK S_1 calls an operation that is carried out by another STARK S_2,
 //! S_1 provides the inputs to S_2 and reads the output from S_1. To ensure that
@@ -19,20 +19,27 @@
 //! - Z(gw) = Z(w) * combine(w) where combine(w) is the column combination at point w.
 //! - Z(g^(n-1)) = combine(1).
 //! - The verifier also checks that the product of looking table Z polynomials is equal
-//! to the associated looked table Z polynomial.
+//!   to the associated looked table Z polynomial.
+//!
 //! Note that the first two checks are written that way because Z polynomials are computed
 //! upside down for convenience.
 //!
 //! Additionally, we support cross-table lookups over two rows. The permutation principle
 //! is similar, but we provide not only `local_values` but also `next_values` -- corresponding to
 //! the current and next row values -- when computing the linear combinations.
+//!
+//! Tables are identified by their index in the array of STARKs given to
+//! [`prove_all`](crate::prover::prove_all), typically obtained by casting a user-defined
+//! `enum Table` to [`TableIdx`].
 
-use std::borrow::Borrow;
-use std::fmt::Debug;
-use std::iter::repeat;
+use alloc::collections::BTreeSet;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::fmt::Debug;
+use core::iter::{once, repeat};
 
 use anyhow::{ensure, Result};
-use itertools::Itertools;
 use plonky2::field::extension::{Extendable, FieldExtension};
 use plonky2::field::packed::PackedField;
 use plonky2::field::polynomial::PolynomialValues;
@@ -48,19 +55,21 @@ use plonky2::plonk::plonk_common::{
 };
 use plonky2::util::serialization::{Buffer, IoResult, Read, Write};
 
-use crate::all_stark::{Table, NUM_TABLES};
 use crate::config::StarkConfig;
 use crate::constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer};
 use crate::evaluation_frame::StarkEvaluationFrame;
-use crate::proof::{StarkProofTarget, StarkProofWithMetadata};
+use crate::proof::{StarkProofTarget, StarkProofWithPublicInputs};
 use crate::stark::Stark;
 
+/// The index of a table in a multi-table STARK system.
+pub type TableIdx = usize;
+
 /// Represent two linear combination of columns, corresponding to the current and next row values.
 /// Each linear combination is represented as:
 /// - a vector of `(usize, F)` corresponding to the column number and the associated multiplicand
 /// - the constant of the linear combination.
 #[derive(Clone, Debug)]
-pub(crate) struct Column<F: Field> {
+pub struct Column<F: Field> {
     linear_combination: Vec<(usize, F)>,
     next_row_linear_combination: Vec<(usize, F)>,
     constant: F,
@@ -68,7 +77,7 @@ pub(crate) struct Column<F: Field> {
 
 impl<F: Field> Column<F> {
     /// Returns the representation of a single column in the current row.
-    pub(crate) fn single(c: usize) -> Self {
+    pub fn single(c: usize) -> Self {
         Self {
             linear_combination: vec![(c, F::ONE)],
             next_row_linear_combination: vec![],
@@ -77,14 +86,14 @@ impl<F: Field> Column<F> {
     }
 
     /// Returns multiple single columns in the current row.
-    pub(crate) fn singles<I: IntoIterator<Item = impl Borrow<usize>>>(
+    pub fn singles<I: IntoIterator<Item = impl Borrow<usize>>>(
         cs: I,
     ) -> impl Iterator<Item = Self> {
         cs.into_iter().map(|c| Self::single(*c.borrow()))
     }
 
     /// Returns the representation of a single column in the next row.
-    pub(crate) fn single_next_row(c: usize) -> Self {
+    pub fn single_next_row(c: usize) -> Self {
         Self {
             linear_combination: vec![],
             next_row_linear_combination: vec![(c, F::ONE)],
@@ -93,14 +102,14 @@ impl<F: Field> Column<F> {
     }
 
     /// Returns multiple single columns for the next row.
-    pub(crate) fn singles_next_row<I: IntoIterator<Item = impl Borrow<usize>>>(
+    pub fn singles_next_row<I: IntoIterator<Item = impl Borrow<usize>>>(
         cs: I,
     ) -> impl Iterator<Item = Self> {
         cs.into_iter().map(|c| Self::single_next_row(*c.borrow()))
     }
 
     /// Returns a linear combination corresponding to a constant.
-    pub(crate) fn constant(constant: F) -> Self {
+    pub fn constant(constant: F) -> Self {
         Self {
             linear_combination: vec![],
             next_row_linear_combination: vec![],
@@ -109,24 +118,24 @@ impl<F: Field> Column<F> {
     }
 
     /// Returns a linear combination corresponding to 0.
-    pub(crate) fn zero() -> Self {
+    pub fn zero() -> Self {
         Self::constant(F::ZERO)
     }
 
     /// Returns a linear combination corresponding to 1.
-    pub(crate) fn one() -> Self {
+    pub fn one() -> Self {
         Self::constant(F::ONE)
     }
 
     /// Given an iterator of `(usize, F)` and a constant, returns the association linear combination of columns for the current row.
-    pub(crate) fn linear_combination_with_constant<I: IntoIterator<Item = (usize, F)>>(
+    pub fn linear_combination_with_constant<I: IntoIterator<Item = (usize, F)>>(
         iter: I,
         constant: F,
     ) -> Self {
         let v = iter.into_iter().collect::<Vec<_>>();
         assert!(!v.is_empty());
         debug_assert_eq!(
-            v.iter().map(|(c, _)| c).unique().count(),
+            v.iter().map(|(c, _)| c).collect::<BTreeSet<_>>().len(),
             v.len(),
             "Duplicate columns."
         );
@@ -138,9 +147,7 @@ impl<F: Field> Column<F> {
     }
 
     /// Given an iterator of `(usize, F)` and a constant, returns the associated linear combination of columns for the current and the next rows.
-    pub(crate) fn linear_combination_and_next_row_with_constant<
-        I: IntoIterator<Item = (usize, F)>,
-    >(
+    pub fn linear_combination_and_next_row_with_constant<I: IntoIterator<Item = (usize, F)>>(
         iter: I,
         next_row_iter: I,
         constant: F,
@@ -150,12 +157,16 @@ impl<F: Field> Column<F> {
 
         assert!(!v.is_empty() || !next_row_v.is_empty());
         debug_assert_eq!(
-            v.iter().map(|(c, _)| c).unique().count(),
+            v.iter().map(|(c, _)| c).collect::<BTreeSet<_>>().len(),
             v.len(),
             "Duplicate columns."
         );
         debug_assert_eq!(
-            next_row_v.iter().map(|(c, _)| c).unique().count(),
+            next_row_v
+                .iter()
+                .map(|(c, _)| c)
+                .collect::<BTreeSet<_>>()
+                .len(),
             next_row_v.len(),
             "Duplicate columns."
         );
@@ -168,20 +179,20 @@ impl<F: Field> Column<F> {
     }
 
     /// Returns a linear combination of columns, with no additional constant.
-    pub(crate) fn linear_combination<I: IntoIterator<Item = (usize, F)>>(iter: I) -> Self {
+    pub fn linear_combination<I: IntoIterator<Item = (usize, F)>>(iter: I) -> Self {
         Self::linear_combination_with_constant(iter, F::ZERO)
     }
 
     /// Given an iterator of columns (c_0, ..., c_n) containing bits in little endian order:
     /// returns the representation of c_0 + 2 * c_1 + ... + 2^n * c_n.
-    pub(crate) fn le_bits<I: IntoIterator<Item = impl Borrow<usize>>>(cs: I) -> Self {
+    pub fn le_bits<I: IntoIterator<Item = impl Borrow<usize>>>(cs: I) -> Self {
         Self::linear_combination(cs.into_iter().map(|c| *c.borrow()).zip(F::TWO.powers()))
     }
 
     /// Given an iterator of columns (c_0, ..., c_n) containing bits in little endian order:
     /// returns the representation of c_0 + 2 * c_1 + ... + 2^n * c_n + k where `k` is an
     /// additional constant.
-    pub(crate) fn le_bits_with_constant<I: IntoIterator<Item = impl Borrow<usize>>>(
+    pub fn le_bits_with_constant<I: IntoIterator<Item = impl Borrow<usize>>>(
         cs: I,
         constant: F,
     ) -> Self {
@@ -193,7 +204,7 @@ impl<F: Field> Column<F> {
 
     /// Given an iterator of columns (c_0, ..., c_n) containing bytes in little endian order:
     /// returns the representation of c_0 + 256 * c_1 + ... + 256^n * c_n.
-    pub(crate) fn le_bytes<I: IntoIterator<Item = impl Borrow<usize>>>(cs: I) -> Self {
+    pub fn le_bytes<I: IntoIterator<Item = impl Borrow<usize>>>(cs: I) -> Self {
         Self::linear_combination(
             cs.into_iter()
                 .map(|c| *c.borrow())
@@ -202,12 +213,12 @@ impl<F: Field> Column<F> {
     }
 
     /// Given an iterator of columns, returns the representation of their sum.
-    pub(crate) fn sum<I: IntoIterator<Item = impl Borrow<usize>>>(cs: I) -> Self {
+    pub fn sum<I: IntoIterator<Item = impl Borrow<usize>>>(cs: I) -> Self {
         Self::linear_combination(cs.into_iter().map(|c| *c.borrow()).zip(repeat(F::ONE)))
     }
 
     /// Given the column values for the current row, returns the evaluation of the linear combination.
-    pub(crate) fn eval<FE, P, const D: usize>(&self, v: &[P]) -> P
+    pub fn eval<FE, P, const D: usize>(&self, v: &[P]) -> P
     where
         FE: FieldExtension<D, BaseField = F>,
         P: PackedField<Scalar = FE>,
@@ -220,7 +231,7 @@ impl<F: Field> Column<F> {
     }
 
     /// Given the column values for the current and next rows, evaluates the current and next linear combinations and returns their sum.
-    pub(crate) fn eval_with_next<FE, P, const D: usize>(&self, v: &[P], next_v: &[P]) -> P
+    pub fn eval_with_next<FE, P, const D: usize>(&self, v: &[P], next_v: &[P]) -> P
     where
         FE: FieldExtension<D, BaseField = F>,
         P: PackedField<Scalar = FE>,
@@ -238,7 +249,7 @@ impl<F: Field> Column<F> {
     }
 
     /// Evaluate on a row of a table given in column-major form.
-    pub(crate) fn eval_table(&self, table: &[PolynomialValues<F>], row: usize) -> F {
+    pub fn eval_table(&self, table: &[PolynomialValues<F>], row: usize) -> F {
         let mut res = self
             .linear_combination
             .iter()
@@ -260,7 +271,7 @@ impl<F: Field> Column<F> {
     }
 
     /// Circuit version of `eval`: Given a row's targets, returns their linear combination.
-    pub(crate) fn eval_circuit<const D: usize>(
+    pub fn eval_circuit<const D: usize>(
         &self,
         builder: &mut CircuitBuilder<F, D>,
         v: &[ExtensionTarget<D>],
@@ -284,7 +295,7 @@ impl<F: Field> Column<F> {
 
     /// Circuit version of `eval_with_next`:
     /// Given the targets of the current and next row, returns the sum of their linear combinations.
-    pub(crate) fn eval_with_next_circuit<const D: usize>(
+    pub fn eval_with_next_circuit<const D: usize>(
         &self,
         builder: &mut CircuitBuilder<F, D>,
         v: &[ExtensionTarget<D>],
@@ -315,23 +326,19 @@ impl<F: Field> Column<F> {
     }
 }
 
-/// A `Table` with a linear combination of columns and a filter.
-/// `filter_column` is used to determine the rows to select in `Table`.
-/// `columns` represents linear combinations of the columns of `Table`.
+/// A table with a linear combination of columns and a filter.
+/// `filter_column` is used to determine the rows to select in `table`.
+/// `columns` represents linear combinations of the columns of `table`.
 #[derive(Clone, Debug)]
-pub(crate) struct TableWithColumns<F: Field> {
-    table: Table,
+pub struct TableWithColumns<F: Field> {
+    table: TableIdx,
     columns: Vec<Column<F>>,
-    pub(crate) filter_column: Option<Column<F>>,
+    pub filter_column: Option<Column<F>>,
 }
 
 impl<F: Field> TableWithColumns<F> {
-    /// Generates a new `TableWithColumns` given a `Table`, a linear combination of columns `columns` and a `filter_column`.
-    pub(crate) fn new(
-        table: Table,
-        columns: Vec<Column<F>>,
-        filter_column: Option<Column<F>>,
-    ) -> Self {
+    /// Generates a new `TableWithColumns` given a table index, a linear combination of columns `columns` and a `filter_column`.
+    pub fn new(table: TableIdx, columns: Vec<Column<F>>, filter_column: Option<Column<F>>) -> Self {
         Self {
             table,
             columns,
@@ -343,17 +350,17 @@ impl<F: Field> TableWithColumns<F> {
 /// Cross-table lookup data consisting in the lookup table (`looked_table`) and all the tables that look into `looked_table` (`looking_tables`).
 /// Each `looking_table` corresponds to a STARK's table whose rows have been filtered out and whose columns have been through a linear combination (see `eval_table`). The concatenation of those smaller tables should result in the `looked_table`.
 #[derive(Clone)]
-pub(crate) struct CrossTableLookup<F: Field> {
+pub struct CrossTableLookup<F: Field> {
     /// Column linear combinations for all tables that are looking into the current table.
-    pub(crate) looking_tables: Vec<TableWithColumns<F>>,
+    pub looking_tables: Vec<TableWithColumns<F>>,
     /// Column linear combination for the current table.
-    pub(crate) looked_table: TableWithColumns<F>,
+    pub looked_table: TableWithColumns<F>,
 }
 
 impl<F: Field> CrossTableLookup<F> {
     /// Creates a new `CrossTableLookup` given some looking tables and a looked table.
     /// All tables should have the same width.
-    pub(crate) fn new(
+    pub fn new(
         looking_tables: Vec<TableWithColumns<F>>,
         looked_table: TableWithColumns<F>,
     ) -> Self {
@@ -366,12 +373,12 @@ impl<F: Field> CrossTableLookup<F> {
         }
     }
 
-    /// Given a `Table` t and the number of challenges, returns the number of Cross-table lookup polynomials associated to t,
+    /// Given a table index t and the number of challenges, returns the number of Cross-table lookup polynomials associated to t,
     /// i.e. the number of looking and looked tables among all CTLs whose columns are taken from t.
-    pub(crate) fn num_ctl_zs(ctls: &[Self], table: Table, num_challenges: usize) -> usize {
+    pub fn num_ctl_zs(ctls: &[Self], table: TableIdx, num_challenges: usize) -> usize {
         let mut num_ctls = 0;
         for ctl in ctls {
-            let all_tables = std::iter::once(&ctl.looked_table).chain(&ctl.looking_tables);
+            let all_tables = once(&ctl.looked_table).chain(&ctl.looking_tables);
             num_ctls += all_tables.filter(|twc| twc.table == table).count();
         }
         num_ctls * num_challenges
@@ -380,37 +387,37 @@ impl<F: Field> CrossTableLookup<F> {
 
 /// Cross-table lookup data for one table.
 #[derive(Clone, Default)]
-pub(crate) struct CtlData<F: Field> {
+pub struct CtlData<F: Field> {
     /// Data associated with all Z(x) polynomials for one table.
-    pub(crate) zs_columns: Vec<CtlZData<F>>,
+    pub zs_columns: Vec<CtlZData<F>>,
 }
 
 /// Cross-table lookup data associated with one Z(x) polynomial.
 #[derive(Clone)]
-pub(crate) struct CtlZData<F: Field> {
+pub struct CtlZData<F: Field> {
     /// Z polynomial values.
-    pub(crate) z: PolynomialValues<F>,
+    pub z: PolynomialValues<F>,
     /// Cross-table lookup challenge.
-    pub(crate) challenge: GrandProductChallenge<F>,
+    pub challenge: GrandProductChallenge<F>,
     /// Column linear combination for the current table.
-    pub(crate) columns: Vec<Column<F>>,
+    pub columns: Vec<Column<F>>,
     /// Filter column for the current table. It evaluates to either 1 or 0.
-    pub(crate) filter_column: Option<Column<F>>,
+    pub filter_column: Option<Column<F>>,
 }
 
 impl<F: Field> CtlData<F> {
     /// Returns the number of cross-table lookup polynomials.
-    pub(crate) fn len(&self) -> usize {
+    pub fn len(&self) -> usize {
         self.zs_columns.len()
     }
 
     /// Returns whether there are no cross-table lookups.
-    pub(crate) fn is_empty(&self) -> bool {
+    pub fn is_empty(&self) -> bool {
         self.zs_columns.is_empty()
     }
 
     /// Returns all the cross-table lookup polynomials.
-    pub(crate) fn z_polys(&self) -> Vec<PolynomialValues<F>> {
+    pub fn z_polys(&self) -> Vec<PolynomialValues<F>> {
         self.zs_columns
             .iter()
             .map(|zs_columns| zs_columns.z.clone())
@@ -420,18 +427,15 @@ impl<F: Field> CtlData<F> {
 
 /// Randomness for a single instance of a permutation check protocol.
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
-pub(crate) struct GrandProductChallenge<T: Copy + Eq + PartialEq + Debug> {
+pub struct GrandProductChallenge<T: Copy + Eq + PartialEq + Debug> {
     /// Randomness used to combine multiple columns into one.
-    pub(crate) beta: T,
+    pub beta: T,
     /// Random offset that's added to the beta-reduced column values.
-    pub(crate) gamma: T,
+    pub gamma: T,
 }
 
 impl<F: Field> GrandProductChallenge<F> {
-    pub(crate) fn combine<'a, FE, P, T: IntoIterator<Item = &'a P>, const D2: usize>(
-        &self,
-        terms: T,
-    ) -> P
+    pub fn combine<'a, FE, P, T: IntoIterator<Item = &'a P>, const D2: usize>(&self, terms: T) -> P
     where
         FE: FieldExtension<D2, BaseField = F>,
         P: PackedField<Scalar = FE>,
@@ -442,7 +446,7 @@ impl<F: Field> GrandProductChallenge<F> {
 }
 
 impl GrandProductChallenge<Target> {
-    pub(crate) fn combine_circuit<F: RichField + Extendable<D>, const D: usize>(
+    pub fn combine_circuit<F: RichField + Extendable<D>, const D: usize>(
         &self,
         builder: &mut CircuitBuilder<F, D>,
         terms: &[ExtensionTarget<D>],
@@ -454,7 +458,7 @@ impl GrandProductChallenge<Target> {
 }
 
 impl GrandProductChallenge<Target> {
-    pub(crate) fn combine_base_circuit<F: RichField + Extendable<D>, const D: usize>(
+    pub fn combine_base_circuit<F: RichField + Extendable<D>, const D: usize>(
         &self,
         builder: &mut CircuitBuilder<F, D>,
         terms: &[Target],
@@ -466,12 +470,12 @@ impl GrandProductChallenge<Target> {
 
 /// Like `PermutationChallenge`, but with `num_challenges` copies to boost soundness.
 #[derive(Clone, Eq, PartialEq, Debug)]
-pub(crate) struct GrandProductChallengeSet<T: Copy + Eq + PartialEq + Debug> {
-    pub(crate) challenges: Vec<GrandProductChallenge<T>>,
+pub struct GrandProductChallengeSet<T: Copy + Eq + PartialEq + Debug> {
+    pub challenges: Vec<GrandProductChallenge<T>>,
 }
 
 impl GrandProductChallengeSet<Target> {
-    pub(crate) fn to_buffer(&self, buffer: &mut Vec<u8>) -> IoResult<()> {
+    pub fn to_buffer(&self, buffer: &mut Vec<u8>) -> IoResult<()> {
         buffer.write_usize(self.challenges.len())?;
         for challenge in &self.challenges {
             buffer.write_target(challenge.beta)?;
@@ -480,7 +484,7 @@ impl GrandProductChallengeSet<Target> {
         Ok(())
     }
 
-    pub(crate) fn from_buffer(buffer: &mut Buffer) -> IoResult<Self> {
+    pub fn from_buffer(buffer: &mut Buffer) -> IoResult<Self> {
         let length = buffer.read_usize()?;
         let mut challenges = Vec::with_capacity(length);
         for _ in 0..length {
@@ -502,7 +506,7 @@ fn get_grand_product_challenge<F: RichField, H: Hasher<F>>(
     GrandProductChallenge { beta, gamma }
 }
 
-pub(crate) fn get_grand_product_challenge_set<F: RichField, H: Hasher<F>>(
+pub fn get_grand_product_challenge_set<F: RichField, H: Hasher<F>>(
     challenger: &mut Challenger<F, H>,
     num_challenges: usize,
 ) -> GrandProductChallengeSet<F> {
@@ -525,7 +529,7 @@ fn get_grand_product_challenge_target<
     GrandProductChallenge { beta, gamma }
 }
 
-pub(crate) fn get_grand_product_challenge_set_target<
+pub fn get_grand_product_challenge_set_target<
     F: RichField + Extendable<D>,
     H: AlgebraicHasher<F>,
     const D: usize,
@@ -544,13 +548,14 @@ pub(crate) fn get_grand_product_challenge_set_target<
 /// - `trace_poly_values` corresponds to the trace values for all tables.
 /// - `cross_table_lookups` corresponds to all the cross-table lookups, i.e. the looked and looking tables, as described in `CrossTableLookup`.
 /// - `ctl_challenges` corresponds to the challenges used for CTLs.
+///
 /// For each `CrossTableLookup`, and each looking/looked table, the partial products for the CTL are computed, and added to the said table's `CtlZData`.
-pub(crate) fn cross_table_lookup_data<F: RichField, const D: usize>(
-    trace_poly_values: &[Vec<PolynomialValues<F>>; NUM_TABLES],
+pub fn cross_table_lookup_data<F: RichField, const D: usize, const N: usize>(
+    trace_poly_values: &[Vec<PolynomialValues<F>>; N],
     cross_table_lookups: &[CrossTableLookup<F>],
     ctl_challenges: &GrandProductChallengeSet<F>,
-) -> [CtlData<F>; NUM_TABLES] {
-    let mut ctl_data_per_table = [0; NUM_TABLES].map(|_| CtlData::default());
+) -> [CtlData<F>; N] {
+    let mut ctl_data_per_table = core::array::from_fn(|_| CtlData::default());
     for CrossTableLookup {
         looking_tables,
         looked_table,
@@ -560,29 +565,27 @@ pub(crate) fn cross_table_lookup_data<F: RichField, const D: usize>(
         for &challenge in &ctl_challenges.challenges {
             let zs_looking = looking_tables.iter().map(|table| {
                 partial_products(
-                    &trace_poly_values[table.table as usize],
+                    &trace_poly_values[table.table],
                     &table.columns,
                     &table.filter_column,
                     challenge,
                 )
             });
             let z_looked = partial_products(
-                &trace_poly_values[looked_table.table as usize],
+                &trace_poly_values[looked_table.table],
                 &looked_table.columns,
                 &looked_table.filter_column,
                 challenge,
             );
             for (table, z) in looking_tables.iter().zip(zs_looking) {
-                ctl_data_per_table[table.table as usize]
-                    .zs_columns
-                    .push(CtlZData {
-                        z,
-                        challenge,
-                        columns: table.columns.clone(),
-                        filter_column: table.filter_column.clone(),
-                    });
+                ctl_data_per_table[table.table].zs_columns.push(CtlZData {
+                    z,
+                    challenge,
+                    columns: table.columns.clone(),
+                    filter_column: table.filter_column.clone(),
+                });
             }
-            ctl_data_per_table[looked_table.table as usize]
+            ctl_data_per_table[looked_table.table]
                 .zs_columns
                 .push(CtlZData {
                     z: z_looked,
@@ -635,48 +638,64 @@ fn partial_products<F: Field>(
 
 /// Data necessary to check the cross-table lookups of a given table.
 #[derive(Clone)]
-pub(crate) struct CtlCheckVars<'a, F, FE, P, const D2: usize>
+pub struct CtlCheckVars<'a, F, FE, P, const D2: usize>
 where
     F: Field,
     FE: FieldExtension<D2, BaseField = F>,
     P: PackedField<Scalar = FE>,
 {
     /// Evaluation of the trace polynomials at point `zeta`.
-    pub(crate) local_z: P,
+    pub local_z: P,
     /// Evaluation of the trace polynomials at point `g * zeta`
-    pub(crate) next_z: P,
+    pub next_z: P,
     /// Cross-table lookup challenges.
-    pub(crate) challenges: GrandProductChallenge<F>,
+    pub challenges: GrandProductChallenge<F>,
     /// Column linear combinations of the `CrossTableLookup`s.
-    pub(crate) columns: &'a [Column<F>],
+    pub columns: &'a [Column<F>],
     /// Column linear combination that evaluates to either 1 or 0.
-    pub(crate) filter_column: &'a Option<Column<F>>,
+    pub filter_column: &'a Option<Column<F>>,
 }
 
 impl<'a, F: RichField + Extendable<D>, const D: usize>
     CtlCheckVars<'a, F, F::Extension, F::Extension, D>
 {
     /// Extracts the `CtlCheckVars` for each STARK.
-    pub(crate) fn from_proofs<C: GenericConfig<D, F = F>>(
-        proofs: &[StarkProofWithMetadata<F, C, D>; NUM_TABLES],
+    pub fn from_proofs<C: GenericConfig<D, F = F>, const N: usize>(
+        proofs: &'a [StarkProofWithPublicInputs<F, C, D>; N],
+        cross_table_lookups: &'a [CrossTableLookup<F>],
+        ctl_challenges: &'a GrandProductChallengeSet<F>,
+    ) -> [Vec<Self>; N] {
+        // Get all cross-table lookup polynomial openings for each STARK proof. They are the last
+        // auxiliary polynomials, after those of the permutation and lookup arguments.
+        let ctl_zs = core::array::from_fn(|i| {
+            let openings = &proofs[i].proof.openings;
+            let num_ctl_zs = openings.ctl_zs_first.as_ref().map_or(0, |zs| zs.len());
+            let auxiliary_polys = openings.auxiliary_polys.as_deref().unwrap_or(&[]);
+            let auxiliary_polys_next = openings.auxiliary_polys_next.as_deref().unwrap_or(&[]);
+            let num_lookup_columns = auxiliary_polys.len() - num_ctl_zs;
+            (
+                &auxiliary_polys[num_lookup_columns..],
+                &auxiliary_polys_next[num_lookup_columns..],
+            )
+        });
+
+        Self::from_ctl_zs_openings(ctl_zs, cross_table_lookups, ctl_challenges)
+    }
+
+    /// Extracts the `CtlCheckVars` for each STARK, given the openings at `zeta` and `g * zeta`
+    /// of the CTL `Z` polynomials of each table.
+    pub fn from_ctl_zs_openings<const N: usize>(
+        ctl_zs: [(&'a [F::Extension], &'a [F::Extension]); N],
         cross_table_lookups: &'a [CrossTableLookup<F>],
         ctl_challenges: &'a GrandProductChallengeSet<F>,
-        num_lookup_columns: &[usize; NUM_TABLES],
-    ) -> [Vec<Self>; NUM_TABLES] {
-        // Get all cross-table lookup polynomial openings for each STARK proof.
-        let mut ctl_zs = proofs
+    ) -> [Vec<Self>; N] {
+        let mut ctl_zs = ctl_zs
             .iter()
-            .zip(num_lookup_columns)
-            .map(|(p, &num_lookup)| {
-                let openings = &p.proof.openings;
-                let ctl_zs = openings.auxiliary_polys.iter().skip(num_lookup);
-                let ctl_zs_next = openings.auxiliary_polys_next.iter().skip(num_lookup);
-                ctl_zs.zip(ctl_zs_next)
-            })
+            .map(|(zs, zs_next)| zs.iter().zip(zs_next.iter()))
             .collect::<Vec<_>>();
 
         // Put each cross-table lookup polynomial into the correct table data: if a CTL polynomial is extracted from looking/looked table t, then we add it to the `CtlCheckVars` of table t.
-        let mut ctl_vars_per_table = [0; NUM_TABLES].map(|_| vec![]);
+        let mut ctl_vars_per_table = core::array::from_fn(|_| vec![]);
         for CrossTableLookup {
             looking_tables,
             looked_table,
@@ -684,8 +703,8 @@ impl<'a, F: RichField + Extendable<D>, const D: usize>
         {
             for &challenges in &ctl_challenges.challenges {
                 for table in looking_tables {
-                    let (looking_z, looking_z_next) = ctl_zs[table.table as usize].next().unwrap();
-                    ctl_vars_per_table[table.table as usize].push(Self {
+                    let (looking_z, looking_z_next) = ctl_zs[table.table].next().unwrap();
+                    ctl_vars_per_table[table.table].push(Self {
                         local_z: *looking_z,
                         next_z: *looking_z_next,
                         challenges,
@@ -694,8 +713,8 @@ impl<'a, F: RichField + Extendable<D>, const D: usize>
                     });
                 }
 
-                let (looked_z, looked_z_next) = ctl_zs[looked_table.table as usize].next().unwrap();
-                ctl_vars_per_table[looked_table.table as usize].push(Self {
+                let (looked_z, looked_z_next) = ctl_zs[looked_table.table].next().unwrap();
+                ctl_vars_per_table[looked_table.table].push(Self {
                     local_z: *looked_z,
                     next_z: *looked_z_next,
                     challenges,
@@ -711,11 +730,12 @@ impl<'a, F: RichField + Extendable<D>, const D: usize>
 /// Checks the cross-table lookup Z polynomials for each table:
 /// - Checks that the CTL `Z` partial products are correctly updated.
 /// - Checks that the final value of the CTL product is the combination of all STARKs' CTL polynomials.
+///
 /// CTL `Z` partial products are upside down: the complete product is on the first row, and
 /// the first term is on the last row. This allows the transition constraint to be:
 /// Z(w) = Z(gw) * combine(w) where combine is called on the local row
 /// and not the next. This enables CTLs across two rows.
-pub(crate) fn eval_cross_table_lookup_checks<F, FE, P, S, const D: usize, const D2: usize>(
+pub fn eval_cross_table_lookup_checks<F, FE, P, S, const D: usize, const D2: usize>(
     vars: &S::EvaluationFrame<FE, P, D2>,
     ctl_vars: &[CtlCheckVars<F, FE, P, D2>],
     consumer: &mut ConstraintConsumer<P>,
@@ -760,38 +780,54 @@ pub(crate) fn eval_cross_table_lookup_checks<F, FE, P, S, const D: usize, const
 
 /// Circuit version of `CtlCheckVars`. Data necessary to check the cross-table lookups of a given table.
 #[derive(Clone)]
-pub(crate) struct CtlCheckVarsTarget<'a, F: Field, const D: usize> {
+pub struct CtlCheckVarsTarget<'a, F: Field, const D: usize> {
     /// Evaluation of the trace polynomials at point `zeta`.
-    pub(crate) local_z: ExtensionTarget<D>,
+    pub local_z: ExtensionTarget<D>,
     /// Evaluation of the trace polynomials at point `g * zeta`.
-    pub(crate) next_z: ExtensionTarget<D>,
+    pub next_z: ExtensionTarget<D>,
     /// Cross-table lookup challenges.
-    pub(crate) challenges: GrandProductChallenge<Target>,
+    pub challenges: GrandProductChallenge<Target>,
     /// Column linear combinations of the `CrossTableLookup`s.
-    pub(crate) columns: &'a [Column<F>],
+    pub columns: &'a [Column<F>],
     /// Column linear combination that evaluates to either 1 or 0.
-    pub(crate) filter_column: &'a Option<Column<F>>,
+    pub filter_column: &'a Option<Column<F>>,
 }
 
 impl<'a, F: Field, const D: usize> CtlCheckVarsTarget<'a, F, D> {
     /// Circuit version of `from_proofs`. Extracts the `CtlCheckVarsTarget` for each STARK.
-    pub(crate) fn from_proof(
-        table: Table,
+    pub fn from_proof(
+        table: TableIdx,
         proof: &StarkProofTarget<D>,
         cross_table_lookups: &'a [CrossTableLookup<F>],
         ctl_challenges: &'a GrandProductChallengeSet<Target>,
-        num_lookup_columns: usize,
     ) -> Vec<Self> {
-        // Get all cross-table lookup polynomial openings for each STARK proof.
-        let mut ctl_zs = {
-            let openings = &proof.openings;
-            let ctl_zs = openings.auxiliary_polys.iter().skip(num_lookup_columns);
-            let ctl_zs_next = openings
-                .auxiliary_polys_next
-                .iter()
-                .skip(num_lookup_columns);
-            ctl_zs.zip(ctl_zs_next)
-        };
+        // Get all cross-table lookup polynomial openings for each STARK proof. They are the last
+        // auxiliary polynomials, after those of the permutation and lookup arguments.
+        let openings = &proof.openings;
+        let num_ctl_zs = openings.ctl_zs_first.as_ref().map_or(0, |zs| zs.len());
+        let auxiliary_polys = openings.auxiliary_polys.as_deref().unwrap_or(&[]);
+        let auxiliary_polys_next = openings.auxiliary_polys_next.as_deref().unwrap_or(&[]);
+        let num_lookup_columns = auxiliary_polys.len() - num_ctl_zs;
+
+        Self::from_ctl_zs_openings(
+            table,
+            &auxiliary_polys[num_lookup_columns..],
+            &auxiliary_polys_next[num_lookup_columns..],
+            cross_table_lookups,
+            ctl_challenges,
+        )
+    }
+
+    /// Circuit version of `from_ctl_zs_openings`. Extracts the `CtlCheckVarsTarget` of `table`,
+    /// given the openings at `zeta` and `g * zeta` of its CTL `Z` polynomials.
+    pub fn from_ctl_zs_openings(
+        table: TableIdx,
+        ctl_zs: &[ExtensionTarget<D>],
+        ctl_zs_next: &[ExtensionTarget<D>],
+        cross_table_lookups: &'a [CrossTableLookup<F>],
+        ctl_challenges: &'a GrandProductChallengeSet<Target>,
+    ) -> Vec<Self> {
+        let mut ctl_zs = ctl_zs.iter().zip(ctl_zs_next);
 
         // Put each cross-table lookup polynomial into the correct table data: if a CTL polynomial is extracted from looking/looked table t, then we add it to the `CtlCheckVars` of table t.
         let mut ctl_vars = vec![];
@@ -834,11 +870,12 @@ impl<'a, F: Field, const D: usize> CtlCheckVarsTarget<'a, F, D> {
 /// Circuit version of `eval_cross_table_lookup_checks`. Checks the cross-table lookups for each table:
 /// - Checks that the CTL `Z` partial products are correctly updated.
 /// - Checks that the final value of the CTL product is the combination of all STARKs' CTL polynomials.
+///
 /// CTL `Z` partial products are upside down: the complete product is on the first row, and
 /// the first term is on the last row. This allows the transition constraint to be:
 /// Z(w) = Z(gw) * combine(w) where combine is called on the local row
 /// and not the next. This enables CTLs across two rows.
-pub(crate) fn eval_cross_table_lookup_checks_circuit<
+pub fn eval_cross_table_lookup_checks_circuit<
     S: Stark<F, D>,
     F: RichField + Extendable<D>,
     const D: usize,
@@ -862,7 +899,7 @@ pub(crate) fn eval_cross_table_lookup_checks_circuit<
 
         let one = builder.one_extension();
         let local_filter = if let Some(column) = filter_column {
-            column.eval_circuit(builder, local_values)
+            column.eval_with_next_circuit(builder, local_values, next_values)
         } else {
             one
         };
@@ -896,10 +933,14 @@ pub(crate) fn eval_cross_table_lookup_checks_circuit<
 }
 
 /// Verifies all cross-table lookups.
-pub(crate) fn verify_cross_table_lookups<F: RichField + Extendable<D>, const D: usize>(
+/// - `ctl_zs_first` holds the openings at 1 of the CTL `Z` polynomials of each table.
+/// - `ctl_extra_looking_products`, if given, holds for each table and each challenge the product
+///   of the combined rows looking into that table which are not part of any STARK trace, e.g.
+///   values taken from public inputs.
+pub fn verify_cross_table_lookups<F: RichField + Extendable<D>, const D: usize, const N: usize>(
     cross_table_lookups: &[CrossTableLookup<F>],
-    ctl_zs_first: [Vec<F>; NUM_TABLES],
-    ctl_extra_looking_products: Vec<Vec<F>>,
+    ctl_zs_first: [Vec<F>; N],
+    ctl_extra_looking_products: Option<&[Vec<F>]>,
     config: &StarkConfig,
 ) -> Result<()> {
     let mut ctl_zs_openings = ctl_zs_first.iter().map(|v| v.iter()).collect::<Vec<_>>();
@@ -912,17 +953,18 @@ pub(crate) fn verify_cross_table_lookups<F: RichField + Extendable<D>, const D:
     ) in cross_table_lookups.iter().enumerate()
     {
         // Get elements looking into `looked_table` that are not associated to any STARK.
-        let extra_product_vec = &ctl_extra_looking_products[looked_table.table as usize];
+        let extra_product_vec =
+            ctl_extra_looking_products.map(|products| &products[looked_table.table]);
         for c in 0..config.num_challenges {
             // Compute the combination of all looking table CTL polynomial openings.
             let looking_zs_prod = looking_tables
                 .iter()
-                .map(|table| *ctl_zs_openings[table.table as usize].next().unwrap())
+                .map(|table| *ctl_zs_openings[table.table].next().unwrap())
                 .product::<F>()
-                * extra_product_vec[c];
+                * extra_product_vec.map_or(F::ONE, |products| products[c]);
 
             // Get the looked table CTL polynomial opening.
-            let looked_z = *ctl_zs_openings[looked_table.table as usize].next().unwrap();
+            let looked_z = *ctl_zs_openings[looked_table.table].next().unwrap();
             // Ensure that the combination of looking table openings is equal to the looked table opening.
             ensure!(
                 looking_zs_prod == looked_z,
@@ -937,33 +979,40 @@ pub(crate) fn verify_cross_table_lookups<F: RichField + Extendable<D>, const D:
 }
 
 /// Circuit version of `verify_cross_table_lookups`. Verifies all cross-table lookups.
-pub(crate) fn verify_cross_table_lookups_circuit<F: RichField + Extendable<D>, const D: usize>(
+pub fn verify_cross_table_lookups_circuit<
+    F: RichField + Extendable<D>,
+    const D: usize,
+    const N: usize,
+>(
     builder: &mut CircuitBuilder<F, D>,
-    cross_table_lookups: Vec<CrossTableLookup<F>>,
-    ctl_zs_first: [Vec<Target>; NUM_TABLES],
-    ctl_extra_looking_products: Vec<Vec<Target>>,
+    cross_table_lookups: &[CrossTableLookup<F>],
+    ctl_zs_first: [Vec<Target>; N],
+    ctl_extra_looking_products: Option<&[Vec<Target>]>,
     inner_config: &StarkConfig,
 ) {
     let mut ctl_zs_openings = ctl_zs_first.iter().map(|v| v.iter()).collect::<Vec<_>>();
     for CrossTableLookup {
         looking_tables,
         looked_table,
-    } in cross_table_lookups.into_iter()
+    } in cross_table_lookups
     {
         // Get elements looking into `looked_table` that are not associated to any STARK.
-        let extra_product_vec = &ctl_extra_looking_products[looked_table.table as usize];
+        let extra_product_vec =
+            ctl_extra_looking_products.map(|products| &products[looked_table.table]);
         for c in 0..inner_config.num_challenges {
             // Compute the combination of all looking table CTL polynomial openings.
             let mut looking_zs_prod = builder.mul_many(
                 looking_tables
                     .iter()
-                    .map(|table| *ctl_zs_openings[table.table as usize].next().unwrap()),
+                    .map(|table| *ctl_zs_openings[table.table].next().unwrap()),
             );
 
-            looking_zs_prod = builder.mul(looking_zs_prod, extra_product_vec[c]);
+            if let Some(extra_products) = extra_product_vec {
+                looking_zs_prod = builder.mul(looking_zs_prod, extra_products[c]);
+            }
 
             // Get the looked table CTL polynomial opening.
-            let looked_z = *ctl_zs_openings[looked_table.table as usize].next().unwrap();
+            let looked_z = *ctl_zs_openings[looked_table.table].next().unwrap();
             // Verify that the combination of looking table openings is equal to the looked table opening.
             builder.connect(looked_z, looking_zs_prod);
         }
@@ -971,34 +1020,37 @@ pub(crate) fn verify_cross_table_lookups_circuit<F: RichField + Extendable<D>, c
     debug_assert!(ctl_zs_openings.iter_mut().all(|iter| iter.next().is_none()));
 }
 
-#[cfg(test)]
-pub(crate) mod testutils {
-    use std::collections::HashMap;
+/// Debugging utilities, to check that traces satisfy their cross-table lookups before proving.
+pub mod debug_utils {
+    use alloc::collections::BTreeMap;
+    use alloc::vec;
+    use alloc::vec::Vec;
 
     use plonky2::field::polynomial::PolynomialValues;
-    use plonky2::field::types::Field;
+    use plonky2::field::types::PrimeField64;
 
-    use crate::all_stark::Table;
-    use crate::cross_table_lookup::{CrossTableLookup, TableWithColumns};
+    use super::{CrossTableLookup, TableIdx, TableWithColumns};
 
-    type MultiSet<F> = HashMap<Vec<F>, Vec<(Table, usize)>>;
+    type MultiSet = BTreeMap<Vec<u64>, Vec<(TableIdx, usize)>>;
 
     /// Check that the provided traces and cross-table lookups are consistent.
-    pub(crate) fn check_ctls<F: Field>(
+    /// `extra_looking_values` holds, for some looked tables, rows looking into them which are not
+    /// part of any trace, e.g. values taken from public inputs.
+    pub fn check_ctls<F: PrimeField64>(
         trace_poly_values: &[Vec<PolynomialValues<F>>],
         cross_table_lookups: &[CrossTableLookup<F>],
-        extra_memory_looking_values: &[Vec<F>],
+        extra_looking_values: &BTreeMap<TableIdx, Vec<Vec<F>>>,
     ) {
         for (i, ctl) in cross_table_lookups.iter().enumerate() {
-            check_ctl(trace_poly_values, ctl, i, extra_memory_looking_values);
+            check_ctl(trace_poly_values, ctl, i, extra_looking_values);
         }
     }
 
-    fn check_ctl<F: Field>(
+    fn check_ctl<F: PrimeField64>(
         trace_poly_values: &[Vec<PolynomialValues<F>>],
         ctl: &CrossTableLookup<F>,
         ctl_index: usize,
-        extra_memory_looking_values: &[Vec<F>],
+        extra_looking_values: &BTreeMap<TableIdx, Vec<Vec<F>>>,
     ) {
         let CrossTableLookup {
             looking_tables,
@@ -1007,23 +1059,22 @@ pub(crate) mod testutils {
 
         // Maps `m` with `(table, i) in m[row]` iff the `i`-th row of `table` is equal to `row` and
         // the filter is 1. Without default values, the CTL check holds iff `looking_multiset == looked_multiset`.
-        let mut looking_multiset = MultiSet::<F>::new();
-        let mut looked_multiset = MultiSet::<F>::new();
+        let mut looking_multiset = MultiSet::new();
+        let mut looked_multiset = MultiSet::new();
 
         for table in looking_tables {
             process_table(trace_poly_values, table, &mut looking_multiset);
         }
         process_table(trace_poly_values, looked_table, &mut looked_multiset);
 
-        // Extra looking values for memory
-        if ctl_index == Table::Memory as usize {
-            for row in extra_memory_looking_values.iter() {
+        if let Some(rows) = extra_looking_values.get(&looked_table.table) {
+            for row in rows {
                 // The table and the row index don't matter here, as we just want to enforce
-                // that the special extra values do appear when looking against the Memory table.
+                // that the special extra values do appear in the looked table.
                 looking_multiset
-                    .entry(row.to_vec())
+                    .entry(row.iter().map(F::to_canonical_u64).collect())
                     .or_default()
-                    .push((Table::Cpu, 0));
+                    .push((looked_table.table, 0));
             }
         }
 
@@ -1040,12 +1091,12 @@ pub(crate) mod testutils {
         }
     }
 
-    fn process_table<F: Field>(
+    fn process_table<F: PrimeField64>(
         trace_poly_values: &[Vec<PolynomialValues<F>>],
         table: &TableWithColumns<F>,
-        multiset: &mut MultiSet<F>,
+        multiset: &mut MultiSet,
     ) {
-        let trace = &trace_poly_values[table.table as usize];
+        let trace = &trace_poly_values[table.table];
         for i in 0..trace[0].len() {
             let filter = if let Some(column) = &table.filter_column {
                 column.eval_table(trace, i)
@@ -1056,7 +1107,7 @@ pub(crate) mod testutils {
                 let row = table
                     .columns
                     .iter()
-                    .map(|c| c.eval_table(trace, i))
+                    .map(|c| c.eval_table(trace, i).to_canonical_u64())
                     .collect::<Vec<_>>();
                 multiset.entry(row).or_default().push((table.table, i));
             } else {
@@ -1065,11 +1116,11 @@ pub(crate) mod testutils {
         }
     }
 
-    fn check_locations<F: Field>(
-        looking_locations: &[(Table, usize)],
-        looked_locations: &[(Table, usize)],
+    fn check_locations(
+        looking_locations: &[(TableIdx, usize)],
+        looked_locations: &[(TableIdx, usize)],
         ctl_index: usize,
-        row: &[F],
+        row: &[u64],
     ) {
         if looking_locations.len() != looked_locations.len() {
             panic!(
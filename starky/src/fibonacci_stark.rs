@@ -62,7 +62,8 @@ const COLUMNS: usize = 4;
 const PUBLIC_INPUTS: usize = 3;
 
 impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for FibonacciStark<F, D> {
-    type EvaluationFrame<FE, P, const D2: usize> = StarkFrame<P, P::Scalar, COLUMNS, PUBLIC_INPUTS>
+    type EvaluationFrame<FE, P, const D2: usize>
+        = StarkFrame<P, P::Scalar, COLUMNS, PUBLIC_INPUTS>
     where
         FE: FieldExtension<D2, BaseField = F>,
         P: PackedField<Scalar = FE>;
@@ -146,7 +147,7 @@ mod tests {
 
     use crate::config::StarkConfig;
     use crate::fibonacci_stark::FibonacciStark;
-    use crate::proof::StarkProofWithPublicInputs;
+    use crate::proof::{CompressedStarkProofWithPublicInputs, StarkProofWithPublicInputs};
     use crate::prover::prove;
     use crate::recursive_verifier::{
         add_virtual_stark_proof_with_pis, set_stark_proof_with_pis_target,
@@ -183,6 +184,91 @@ mod tests {
         verify_stark_proof(stark, proof, &config)
     }
 
+    #[test]
+    fn test_fibonacci_stark_zk() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = FibonacciStark<F, D>;
+
+        let config = StarkConfig::standard_fast_zk_config();
+        let num_rows = 1 << 5;
+        let public_inputs = [F::ZERO, F::ONE, fibonacci(num_rows - 1, F::ZERO, F::ONE)];
+        let stark = S::new(num_rows);
+        let prove_fibonacci = || {
+            let trace = stark.generate_trace(public_inputs[0], public_inputs[1]);
+            prove::<F, C, S, D>(
+                stark,
+                &config,
+                trace,
+                &public_inputs,
+                &mut TimingTree::default(),
+            )
+        };
+        let proof = prove_fibonacci()?;
+        // The trace commitment is randomized.
+        assert_ne!(proof.proof.trace_cap, prove_fibonacci()?.proof.trace_cap);
+        assert_eq!(proof.proof.recover_degree_bits(&config), 5);
+
+        let bytes = proof.to_bytes(&config);
+        let read_proof = StarkProofWithPublicInputs::from_bytes(bytes, &stark, &config)?;
+        assert_eq!(proof, read_proof);
+        let decompressed_proof = proof
+            .clone()
+            .compress(&stark, &config)
+            .decompress(&stark, &config);
+        assert_eq!(proof, decompressed_proof);
+
+        verify_stark_proof(stark, proof, &config)
+    }
+
+    #[test]
+    fn test_fibonacci_stark_serialization() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = FibonacciStark<F, D>;
+
+        let config = StarkConfig::standard_fast_config();
+        let num_rows = 1 << 5;
+        let public_inputs = [F::ZERO, F::ONE, fibonacci(num_rows - 1, F::ZERO, F::ONE)];
+        let stark = S::new(num_rows);
+        let trace = stark.generate_trace(public_inputs[0], public_inputs[1]);
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace,
+            &public_inputs,
+            &mut TimingTree::default(),
+        )?;
+
+        let bytes = proof.to_bytes(&config);
+        let read_proof = StarkProofWithPublicInputs::from_bytes(bytes, &stark, &config)?;
+        assert_eq!(proof, read_proof);
+
+        let compressed_proof = proof.clone().compress(&stark, &config);
+        let compressed_bytes = compressed_proof.to_bytes();
+        assert!(compressed_bytes.len() < proof.to_bytes(&config).len());
+        let read_compressed_proof =
+            CompressedStarkProofWithPublicInputs::from_bytes(compressed_bytes, &stark, &config)?;
+        assert_eq!(compressed_proof, read_compressed_proof);
+
+        let decompressed_proof = read_compressed_proof.decompress(&stark, &config);
+        assert_eq!(proof, decompressed_proof);
+
+        // A proof claiming an out-of-range trace degree is rejected rather than panicking.
+        for degree_bits in [u64::MAX, 64, F::TWO_ADICITY as u64] {
+            let mut bad_bytes = proof.to_bytes(&config);
+            bad_bytes[..8].copy_from_slice(&degree_bits.to_le_bytes());
+            assert!(
+                StarkProofWithPublicInputs::<F, C, D>::from_bytes(bad_bytes, &stark, &config)
+                    .is_err()
+            );
+        }
+
+        verify_stark_proof(stark, decompressed_proof, &config)
+    }
+
     #[test]
     fn test_fibonacci_stark_degree() -> Result<()> {
         const D: usize = 2;
@@ -232,6 +318,31 @@ mod tests {
         recursive_proof::<F, C, S, C, D>(stark, proof, &config, true)
     }
 
+    #[test]
+    fn test_recursive_stark_verifier_zk() -> Result<()> {
+        init_logger();
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = FibonacciStark<F, D>;
+
+        let config = StarkConfig::standard_fast_zk_config();
+        let num_rows = 1 << 5;
+        let public_inputs = [F::ZERO, F::ONE, fibonacci(num_rows - 1, F::ZERO, F::ONE)];
+        let stark = S::new(num_rows);
+        let trace = stark.generate_trace(public_inputs[0], public_inputs[1]);
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace,
+            &public_inputs,
+            &mut TimingTree::default(),
+        )?;
+        verify_stark_proof(stark, proof.clone(), &config)?;
+
+        recursive_proof::<F, C, S, C, D>(stark, proof, &config, false)
+    }
+
     fn recursive_proof<
         F: RichField + Extendable<D>,
         C: GenericConfig<D, F = F>,
@@ -2,7 +2,7 @@ use alloc::vec::Vec;
 
 use plonky2::field::extension::Extendable;
 use plonky2::field::polynomial::PolynomialCoeffs;
-use plonky2::fri::proof::{FriProof, FriProofTarget};
+use plonky2::fri::proof::{CompressedFriProof, FriProof, FriProofTarget};
 use plonky2::gadgets::polynomial::PolynomialCoeffsExtTarget;
 use plonky2::hash::hash_types::{MerkleCapTarget, RichField};
 use plonky2::hash::merkle_tree::MerkleCap;
@@ -10,6 +10,7 @@ use plonky2::iop::challenger::{Challenger, RecursiveChallenger};
 use plonky2::iop::target::Target;
 use plonky2::plonk::circuit_builder::CircuitBuilder;
 use plonky2::plonk::config::{AlgebraicHasher, GenericConfig};
+use plonky2::plonk::proof::FriInferredElements;
 
 use crate::config::StarkConfig;
 use crate::permutation::{
@@ -18,10 +19,14 @@ use crate::permutation::{
 use crate::proof::*;
 use crate::stark::Stark;
 
+/// Computes all Fiat-Shamir challenges of a STARK proof, drawing them from `challenger`. If
+/// `trace_cap` is `None`, the trace cap must already have been observed by `challenger`, as is the
+/// case for the tables of a multi-table proof.
 fn get_challenges<F, C, S, const D: usize>(
     stark: &S,
-    trace_cap: &MerkleCap<F, C::Hasher>,
-    permutation_zs_cap: Option<&MerkleCap<F, C::Hasher>>,
+    challenger: &mut Challenger<F, C::Hasher>,
+    trace_cap: Option<&MerkleCap<F, C::Hasher>>,
+    auxiliary_polys_cap: Option<&MerkleCap<F, C::Hasher>>,
     quotient_polys_cap: &MerkleCap<F, C::Hasher>,
     openings: &StarkOpeningSet<F, D>,
     commit_phase_merkle_caps: &[MerkleCap<F, C::Hasher>],
@@ -37,19 +42,19 @@ where
 {
     let num_challenges = config.num_challenges;
 
-    let mut challenger = Challenger::<F, C::Hasher>::new();
-
-    challenger.observe_cap(trace_cap);
+    if let Some(cap) = trace_cap {
+        challenger.observe_cap(cap);
+    }
 
-    let permutation_challenge_sets = permutation_zs_cap.map(|permutation_zs_cap| {
-        let tmp = get_n_permutation_challenge_sets(
-            &mut challenger,
-            num_challenges,
-            stark.permutation_batch_size(),
-        );
-        challenger.observe_cap(permutation_zs_cap);
-        tmp
+    let permutation_challenge_sets = stark.uses_permutation_args().then(|| {
+        get_n_permutation_challenge_sets(challenger, num_challenges, stark.permutation_batch_size())
     });
+    let lookup_challenges = stark
+        .uses_lookups()
+        .then(|| challenger.get_n_challenges(num_challenges));
+    if let Some(cap) = auxiliary_polys_cap {
+        challenger.observe_cap(cap);
+    }
 
     let stark_alphas = challenger.get_n_challenges(num_challenges);
 
@@ -60,13 +65,14 @@ where
 
     StarkProofChallenges {
         permutation_challenge_sets,
+        lookup_challenges,
         stark_alphas,
         stark_zeta,
         fri_challenges: challenger.fri_challenges::<C, D>(
             commit_phase_merkle_caps,
             final_poly,
             pow_witness,
-            degree_bits,
+            config.fri_params(degree_bits).degree_bits,
             &config.fri_config,
         ),
     }
@@ -77,8 +83,6 @@ where
     F: RichField + Extendable<D>,
     C: GenericConfig<D, F = F>,
 {
-    // TODO: Should be used later in compression?
-    #![allow(dead_code)]
     pub(crate) fn fri_query_indices<S: Stark<F, D>>(
         &self,
         stark: &S,
@@ -96,10 +100,25 @@ where
         stark: &S,
         config: &StarkConfig,
         degree_bits: usize,
+    ) -> StarkProofChallenges<F, D> {
+        let mut challenger = Challenger::<F, C::Hasher>::new();
+        self.get_challenges_with_challenger(stark, &mut challenger, false, config, degree_bits)
+    }
+
+    /// Computes all Fiat-Shamir challenges used in the STARK proof, drawing them from
+    /// `challenger`. If `trace_cap_observed` is set, `challenger` must already have observed the
+    /// trace cap, as is the case for the tables of a multi-table proof.
+    pub(crate) fn get_challenges_with_challenger<S: Stark<F, D>>(
+        &self,
+        stark: &S,
+        challenger: &mut Challenger<F, C::Hasher>,
+        trace_cap_observed: bool,
+        config: &StarkConfig,
+        degree_bits: usize,
     ) -> StarkProofChallenges<F, D> {
         let StarkProof {
             trace_cap,
-            permutation_zs_cap,
+            auxiliary_polys_cap,
             quotient_polys_cap,
             openings,
             opening_proof:
@@ -113,8 +132,9 @@ where
 
         get_challenges::<F, C, S, D>(
             stark,
-            trace_cap,
-            permutation_zs_cap.as_ref(),
+            challenger,
+            (!trace_cap_observed).then_some(trace_cap),
+            auxiliary_polys_cap.as_ref(),
             quotient_polys_cap,
             openings,
             commit_phase_merkle_caps,
@@ -135,8 +155,9 @@ pub(crate) fn get_challenges_target<
 >(
     builder: &mut CircuitBuilder<F, D>,
     stark: &S,
-    trace_cap: &MerkleCapTarget,
-    permutation_zs_cap: Option<&MerkleCapTarget>,
+    challenger: &mut RecursiveChallenger<F, C::Hasher, D>,
+    trace_cap: Option<&MerkleCapTarget>,
+    auxiliary_polys_cap: Option<&MerkleCapTarget>,
     quotient_polys_cap: &MerkleCapTarget,
     openings: &StarkOpeningSetTarget<D>,
     commit_phase_merkle_caps: &[MerkleCapTarget],
@@ -149,30 +170,36 @@ where
 {
     let num_challenges = config.num_challenges;
 
-    let mut challenger = RecursiveChallenger::<F, C::Hasher, D>::new(builder);
-
-    challenger.observe_cap(trace_cap);
+    if let Some(cap) = trace_cap {
+        challenger.observe_cap(cap);
+    }
 
-    let permutation_challenge_sets = permutation_zs_cap.map(|permutation_zs_cap| {
-        let tmp = get_n_permutation_challenge_sets_target(
+    let permutation_challenge_sets = stark.uses_permutation_args().then(|| {
+        get_n_permutation_challenge_sets_target(
             builder,
-            &mut challenger,
+            challenger,
             num_challenges,
             stark.permutation_batch_size(),
-        );
-        challenger.observe_cap(permutation_zs_cap);
-        tmp
+        )
     });
+    let lookup_challenges = stark
+        .uses_lookups()
+        .then(|| challenger.get_n_challenges(builder, num_challenges));
+    if let Some(cap) = auxiliary_polys_cap {
+        challenger.observe_cap(cap);
+    }
 
     let stark_alphas = challenger.get_n_challenges(builder, num_challenges);
 
     challenger.observe_cap(quotient_polys_cap);
     let stark_zeta = challenger.get_extension_challenge(builder);
 
-    challenger.observe_openings(&openings.to_fri_openings());
+    let zero = builder.zero();
+    challenger.observe_openings(&openings.to_fri_openings(zero));
 
     StarkProofChallengesTarget {
         permutation_challenge_sets,
+        lookup_challenges,
         stark_alphas,
         stark_zeta,
         fri_challenges: challenger.fri_challenges(
@@ -196,12 +223,38 @@ impl<const D: usize> StarkProofWithPublicInputsTarget<D> {
         stark: &S,
         config: &StarkConfig,
     ) -> StarkProofChallengesTarget<D>
+    where
+        C::Hasher: AlgebraicHasher<F>,
+    {
+        let mut challenger = RecursiveChallenger::<F, C::Hasher, D>::new(builder);
+        self.get_challenges_with_challenger::<F, C, S>(
+            builder,
+            stark,
+            &mut challenger,
+            false,
+            config,
+        )
+    }
+
+    /// Circuit version of `StarkProofWithPublicInputs::get_challenges_with_challenger`.
+    pub(crate) fn get_challenges_with_challenger<
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+        S: Stark<F, D>,
+    >(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        stark: &S,
+        challenger: &mut RecursiveChallenger<F, C::Hasher, D>,
+        trace_cap_observed: bool,
+        config: &StarkConfig,
+    ) -> StarkProofChallengesTarget<D>
     where
         C::Hasher: AlgebraicHasher<F>,
     {
         let StarkProofTarget {
             trace_cap,
-            permutation_zs_cap,
+            auxiliary_polys_cap,
             quotient_polys_cap,
             openings,
             opening_proof:
@@ -216,8 +269,9 @@ impl<const D: usize> StarkProofWithPublicInputsTarget<D> {
         get_challenges_target::<F, C, S, D>(
             builder,
             stark,
-            trace_cap,
-            permutation_zs_cap.as_ref(),
+            challenger,
+            (!trace_cap_observed).then_some(trace_cap),
+            auxiliary_polys_cap.as_ref(),
             quotient_polys_cap,
             openings,
             commit_phase_merkle_caps,
@@ -228,110 +282,65 @@ impl<const D: usize> StarkProofWithPublicInputsTarget<D> {
     }
 }
 
-// TODO: Deal with the compressed stuff.
-// impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
-//     CompressedProofWithPublicInputs<F, C, D>
-// {
-//     /// Computes all Fiat-Shamir challenges used in the Plonk proof.
-//     pub(crate) fn get_challenges(
-//         &self,
-//         common_data: &CommonCircuitData<F, C, D>,
-//     ) -> anyhow::Result<ProofChallenges<F, D>> {
-//         let CompressedProof {
-//             wires_cap,
-//             plonk_zs_partial_products_cap,
-//             quotient_polys_cap,
-//             openings,
-//             opening_proof:
-//                 CompressedFriProof {
-//                     commit_phase_merkle_caps,
-//                     final_poly,
-//                     pow_witness,
-//                     ..
-//                 },
-//         } = &self.proof;
-//
-//         get_challenges(
-//             self.get_public_inputs_hash(),
-//             wires_cap,
-//             plonk_zs_partial_products_cap,
-//             quotient_polys_cap,
-//             openings,
-//             commit_phase_merkle_caps,
-//             final_poly,
-//             *pow_witness,
-//             common_data,
-//         )
-//     }
-//
-//     /// Computes all coset elements that can be inferred in the FRI reduction steps.
-//     pub(crate) fn get_inferred_elements(
-//         &self,
-//         challenges: &ProofChallenges<F, D>,
-//         common_data: &CommonCircuitData<F, C, D>,
-//     ) -> FriInferredElements<F, D> {
-//         let ProofChallenges {
-//             plonk_zeta,
-//             fri_alpha,
-//             fri_betas,
-//             fri_query_indices,
-//             ..
-//         } = challenges;
-//         let mut fri_inferred_elements = Vec::new();
-//         // Holds the indices that have already been seen at each reduction depth.
-//         let mut seen_indices_by_depth =
-//             vec![HashSet::new(); common_data.fri_params.reduction_arity_bits.len()];
-//         let precomputed_reduced_evals = PrecomputedReducedOpenings::from_os_and_alpha(
-//             &self.proof.openings.to_fri_openings(),
-//             *fri_alpha,
-//         );
-//         let log_n = common_data.degree_bits + common_data.config.fri_config.rate_bits;
-//         // Simulate the proof verification and collect the inferred elements.
-//         // The content of the loop is basically the same as the `fri_verifier_query_round` function.
-//         for &(mut x_index) in fri_query_indices {
-//             let mut subgroup_x = F::MULTIPLICATIVE_GROUP_GENERATOR
-//                 * F::primitive_root_of_unity(log_n).exp_u64(reverse_bits(x_index, log_n) as u64);
-//             let mut old_eval = fri_combine_initial::<F, C, D>(
-//                 &common_data.get_fri_instance(*plonk_zeta),
-//                 &self
-//                     .proof
-//                     .opening_proof
-//                     .query_round_proofs
-//                     .initial_trees_proofs[&x_index],
-//                 *fri_alpha,
-//                 subgroup_x,
-//                 &precomputed_reduced_evals,
-//                 &common_data.fri_params,
-//             );
-//             for (i, &arity_bits) in common_data
-//                 .fri_params
-//                 .reduction_arity_bits
-//                 .iter()
-//                 .enumerate()
-//             {
-//                 let coset_index = x_index >> arity_bits;
-//                 if !seen_indices_by_depth[i].insert(coset_index) {
-//                     // If this index has already been seen, we can skip the rest of the reductions.
-//                     break;
-//                 }
-//                 fri_inferred_elements.push(old_eval);
-//                 let arity = 1 << arity_bits;
-//                 let mut evals = self.proof.opening_proof.query_round_proofs.steps[i][&coset_index]
-//                     .evals
-//                     .clone();
-//                 let x_index_within_coset = x_index & (arity - 1);
-//                 evals.insert(x_index_within_coset, old_eval);
-//                 old_eval = compute_evaluation(
-//                     subgroup_x,
-//                     x_index_within_coset,
-//                     arity_bits,
-//                     &evals,
-//                     fri_betas[i],
-//                 );
-//                 subgroup_x = subgroup_x.exp_power_of_2(arity_bits);
-//                 x_index = coset_index;
-//             }
-//         }
-//         FriInferredElements(fri_inferred_elements)
-//     }
-// }
+impl<F, C, const D: usize> CompressedStarkProofWithPublicInputs<F, C, D>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    /// Computes all Fiat-Shamir challenges used in the STARK proof.
+    pub(crate) fn get_challenges<S: Stark<F, D>>(
+        &self,
+        stark: &S,
+        config: &StarkConfig,
+    ) -> StarkProofChallenges<F, D> {
+        let CompressedStarkProof {
+            degree_bits,
+            trace_cap,
+            auxiliary_polys_cap,
+            quotient_polys_cap,
+            openings,
+            opening_proof:
+                CompressedFriProof {
+                    commit_phase_merkle_caps,
+                    final_poly,
+                    pow_witness,
+                    ..
+                },
+        } = &self.proof;
+
+        get_challenges::<F, C, S, D>(
+            stark,
+            &mut Challenger::new(),
+            Some(trace_cap),
+            auxiliary_polys_cap.as_ref(),
+            quotient_polys_cap,
+            openings,
+            commit_phase_merkle_caps,
+            final_poly,
+            *pow_witness,
+            config,
+            *degree_bits,
+        )
+    }
+
+    /// Computes all coset elements that can be inferred in the FRI reduction steps.
+    pub(crate) fn get_inferred_elements<S: Stark<F, D>>(
+        &self,
+        stark: &S,
+        challenges: &StarkProofChallenges<F, D>,
+        config: &StarkConfig,
+    ) -> FriInferredElements<F, D> {
+        let degree_bits = self.proof.degree_bits;
+        self.proof.opening_proof.get_inferred_elements::<C>(
+            &stark.fri_instance(
+                challenges.stark_zeta,
+                F::primitive_root_of_unity(degree_bits),
+                0,
+                config,
+            ),
+            &self.proof.openings.to_fri_openings(),
+            &challenges.fri_challenges,
+            &config.fri_params(degree_bits),
+        )
+    }
+}
@@ -8,7 +8,9 @@ mod get_challenges;
 
 pub mod config;
 pub mod constraint_consumer;
+pub mod cross_table_lookup;
 pub mod evaluation_frame;
+pub mod lookup;
 pub mod permutation;
 pub mod proof;
 pub mod prover;
@@ -21,3 +23,5 @@ pub mod verifier;
 
 #[cfg(test)]
 pub mod fibonacci_stark;
+#[cfg(test)]
+pub mod multi_table_stark;
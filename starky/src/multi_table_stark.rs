@@ -0,0 +1,391 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use plonky2::field::extension::{Extendable, FieldExtension};
+use plonky2::field::packed::PackedField;
+use plonky2::field::polynomial::PolynomialValues;
+use plonky2::hash::hash_types::RichField;
+use plonky2::iop::ext_target::ExtensionTarget;
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+
+use crate::constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer};
+use crate::cross_table_lookup::{Column, CrossTableLookup, TableIdx, TableWithColumns};
+use crate::evaluation_frame::{StarkEvaluationFrame, StarkFrame};
+use crate::lookup::Lookup;
+use crate::stark::Stark;
+use crate::util::trace_rows_to_poly_values;
+
+/// Toy multi-table STARK system used for testing.
+/// The `Squares` table holds pairs `(x, x^2)`, with each `x` range-checked against a counter
+/// column using a lookup. The `Caller` table holds pairs `(y, y^2)` on its filtered rows, which are
+/// checked against the `Squares` table using a cross-table lookup.
+#[derive(Copy, Clone, Debug)]
+enum Table {
+    Squares = 0,
+    Caller = 1,
+}
+
+const NUM_TABLES: usize = 2;
+
+const SQUARES_COLUMNS: usize = 4;
+const SQUARES_X: usize = 0;
+const SQUARES_X_SQUARED: usize = 1;
+const SQUARES_RANGE: usize = 2;
+const SQUARES_FREQUENCIES: usize = 3;
+
+const CALLER_COLUMNS: usize = 3;
+const CALLER_Y: usize = 0;
+const CALLER_Y_SQUARED: usize = 1;
+const CALLER_FILTER: usize = 2;
+
+/// Computes `x^2` for `x` in `0..num_rows / 2`, each value appearing twice.
+#[derive(Copy, Clone)]
+struct SquaresStark<F: RichField + Extendable<D>, const D: usize> {
+    num_rows: usize,
+    _phantom: PhantomData<F>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SquaresStark<F, D> {
+    fn new(num_rows: usize) -> Self {
+        Self {
+            num_rows,
+            _phantom: PhantomData,
+        }
+    }
+
+    fn generate_trace(&self) -> Vec<PolynomialValues<F>> {
+        let trace_rows = (0..self.num_rows)
+            .map(|i| {
+                let x = F::from_canonical_usize(i / 2);
+                let frequency = if i < self.num_rows / 2 { 2 } else { 0 };
+                [
+                    x,
+                    x * x,
+                    F::from_canonical_usize(i),
+                    F::from_canonical_usize(frequency),
+                ]
+            })
+            .collect::<Vec<_>>();
+        trace_rows_to_poly_values(trace_rows)
+    }
+
+    fn ctl_columns() -> Vec<Column<F>> {
+        Column::singles([SQUARES_X, SQUARES_X_SQUARED]).collect()
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for SquaresStark<F, D> {
+    type EvaluationFrame<FE, P, const D2: usize>
+        = StarkFrame<P, P::Scalar, SQUARES_COLUMNS, 0>
+    where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>;
+
+    type EvaluationFrameTarget =
+        StarkFrame<ExtensionTarget<D>, ExtensionTarget<D>, SQUARES_COLUMNS, 0>;
+
+    fn eval_packed_generic<FE, P, const D2: usize>(
+        &self,
+        vars: &Self::EvaluationFrame<FE, P, D2>,
+        yield_constr: &mut ConstraintConsumer<P>,
+    ) where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>,
+    {
+        let local_values = vars.get_local_values();
+        let next_values = vars.get_next_values();
+
+        // x^2 = x * x
+        yield_constr.constraint(
+            local_values[SQUARES_X_SQUARED] - local_values[SQUARES_X] * local_values[SQUARES_X],
+        );
+        // The range column counts from 0.
+        yield_constr.constraint_first_row(local_values[SQUARES_RANGE]);
+        yield_constr.constraint_transition(
+            next_values[SQUARES_RANGE] - local_values[SQUARES_RANGE] - FE::ONE,
+        );
+    }
+
+    fn eval_ext_circuit(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        vars: &Self::EvaluationFrameTarget,
+        yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    ) {
+        let local_values = vars.get_local_values();
+        let next_values = vars.get_next_values();
+        let one = builder.one_extension();
+
+        // x^2 = x * x
+        let square = builder.square_extension(local_values[SQUARES_X]);
+        let square_constraint = builder.sub_extension(local_values[SQUARES_X_SQUARED], square);
+        yield_constr.constraint(builder, square_constraint);
+        // The range column counts from 0.
+        yield_constr.constraint_first_row(builder, local_values[SQUARES_RANGE]);
+        let range_constraint = {
+            let tmp =
+                builder.sub_extension(next_values[SQUARES_RANGE], local_values[SQUARES_RANGE]);
+            builder.sub_extension(tmp, one)
+        };
+        yield_constr.constraint_transition(builder, range_constraint);
+    }
+
+    fn constraint_degree(&self) -> usize {
+        2
+    }
+
+    fn lookups(&self) -> Vec<Lookup> {
+        vec![Lookup {
+            columns: vec![SQUARES_X],
+            table_column: SQUARES_RANGE,
+            frequencies_column: SQUARES_FREQUENCIES,
+        }]
+    }
+}
+
+/// Looks up `(y, y^2)` pairs in the `Squares` table on the rows where the filter is set.
+#[derive(Copy, Clone)]
+struct CallerStark<F: RichField + Extendable<D>, const D: usize> {
+    num_rows: usize,
+    _phantom: PhantomData<F>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CallerStark<F, D> {
+    fn new(num_rows: usize) -> Self {
+        Self {
+            num_rows,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Generates a trace looking up each row of a `Squares` trace with `num_rows / 2` rows, in
+    /// reverse order and interleaved with filtered-out rows.
+    fn generate_trace(&self) -> Vec<PolynomialValues<F>> {
+        let num_calls = self.num_rows / 2;
+        let trace_rows = (0..self.num_rows)
+            .map(|i| {
+                if i % 2 == 0 {
+                    let y = F::from_canonical_usize((num_calls - 1 - i / 2) / 2);
+                    [y, y * y, F::ONE]
+                } else {
+                    [F::from_canonical_usize(i), F::ZERO, F::ZERO]
+                }
+            })
+            .collect::<Vec<_>>();
+        trace_rows_to_poly_values(trace_rows)
+    }
+
+    fn ctl_columns() -> Vec<Column<F>> {
+        Column::singles([CALLER_Y, CALLER_Y_SQUARED]).collect()
+    }
+
+    fn ctl_filter() -> Column<F> {
+        Column::single(CALLER_FILTER)
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for CallerStark<F, D> {
+    type EvaluationFrame<FE, P, const D2: usize>
+        = StarkFrame<P, P::Scalar, CALLER_COLUMNS, 0>
+    where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>;
+
+    type EvaluationFrameTarget =
+        StarkFrame<ExtensionTarget<D>, ExtensionTarget<D>, CALLER_COLUMNS, 0>;
+
+    fn eval_packed_generic<FE, P, const D2: usize>(
+        &self,
+        vars: &Self::EvaluationFrame<FE, P, D2>,
+        yield_constr: &mut ConstraintConsumer<P>,
+    ) where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>,
+    {
+        let filter = vars.get_local_values()[CALLER_FILTER];
+        // The filter is boolean.
+        yield_constr.constraint(filter * filter - filter);
+    }
+
+    fn eval_ext_circuit(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        vars: &Self::EvaluationFrameTarget,
+        yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    ) {
+        let filter = vars.get_local_values()[CALLER_FILTER];
+        // The filter is boolean.
+        let constraint = builder.mul_sub_extension(filter, filter, filter);
+        yield_constr.constraint(builder, constraint);
+    }
+
+    fn constraint_degree(&self) -> usize {
+        // The filtered cross-table lookup constraints have degree 3.
+        3
+    }
+}
+
+fn cross_table_lookups<F: RichField + Extendable<D>, const D: usize>() -> Vec<CrossTableLookup<F>> {
+    vec![CrossTableLookup::new(
+        vec![TableWithColumns::new(
+            Table::Caller as TableIdx,
+            CallerStark::<F, D>::ctl_columns(),
+            Some(CallerStark::<F, D>::ctl_filter()),
+        )],
+        TableWithColumns::new(
+            Table::Squares as TableIdx,
+            SquaresStark::<F, D>::ctl_columns(),
+            None,
+        ),
+    )]
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use plonky2::field::types::Field;
+    use plonky2::iop::witness::PartialWitness;
+    use plonky2::plonk::circuit_builder::CircuitBuilder;
+    use plonky2::plonk::circuit_data::CircuitConfig;
+    use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use plonky2::util::timing::TimingTree;
+
+    use super::{cross_table_lookups, CallerStark, SquaresStark, Table, NUM_TABLES};
+    use crate::config::StarkConfig;
+    use crate::prover::prove_all;
+    use crate::recursive_verifier::{
+        add_virtual_multi_proof, set_multi_proof_target, verify_all_circuit,
+    };
+    use crate::stark::StarkTable;
+    use crate::stark_testing::{test_stark_circuit_constraints, test_stark_low_degree};
+    use crate::verifier::verify_all;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    const SQUARES_ROWS: usize = 1 << 5;
+    const CALLER_ROWS: usize = 1 << 6;
+
+    #[test]
+    fn test_multi_table_stark() -> Result<()> {
+        let config = StarkConfig::standard_fast_config();
+        let squares = SquaresStark::<F, D>::new(SQUARES_ROWS);
+        let caller = CallerStark::<F, D>::new(CALLER_ROWS);
+        let starks: [&dyn StarkTable<F, C, D>; NUM_TABLES] = [&squares, &caller];
+        let ctls = cross_table_lookups::<F, D>();
+
+        let proof = prove_all::<F, C, D, NUM_TABLES>(
+            starks,
+            &ctls,
+            &config,
+            [squares.generate_trace(), caller.generate_trace()],
+            [vec![], vec![]],
+            &mut TimingTree::default(),
+        )?;
+
+        verify_all(starks, &ctls, &proof, &config)
+    }
+
+    #[test]
+    fn test_multi_table_stark_zk() -> Result<()> {
+        let config = StarkConfig::standard_fast_zk_config();
+        let squares = SquaresStark::<F, D>::new(SQUARES_ROWS);
+        let caller = CallerStark::<F, D>::new(CALLER_ROWS);
+        let starks: [&dyn StarkTable<F, C, D>; NUM_TABLES] = [&squares, &caller];
+        let ctls = cross_table_lookups::<F, D>();
+
+        let prove = |caller_trace| {
+            prove_all::<F, C, D, NUM_TABLES>(
+                starks,
+                &ctls,
+                &config,
+                [squares.generate_trace(), caller_trace],
+                [vec![], vec![]],
+                &mut TimingTree::default(),
+            )
+        };
+        let proof = prove(caller.generate_trace())?;
+        verify_all(starks, &ctls, &proof, &config)?;
+
+        let mut bad_caller_trace = caller.generate_trace();
+        bad_caller_trace[super::CALLER_Y_SQUARED].values[0] += F::ONE;
+        let bad_proof = prove(bad_caller_trace)?;
+        assert!(verify_all(starks, &ctls, &bad_proof, &config).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_multi_table_stark_bad_ctl() -> Result<()> {
+        let config = StarkConfig::standard_fast_config();
+        let squares = SquaresStark::<F, D>::new(SQUARES_ROWS);
+        let caller = CallerStark::<F, D>::new(CALLER_ROWS);
+        let starks: [&dyn StarkTable<F, C, D>; NUM_TABLES] = [&squares, &caller];
+        let ctls = cross_table_lookups::<F, D>();
+
+        // Look up a pair which is not in the `Squares` table.
+        let mut caller_trace = caller.generate_trace();
+        caller_trace[super::CALLER_Y_SQUARED].values[0] += F::ONE;
+
+        let proof = prove_all::<F, C, D, NUM_TABLES>(
+            starks,
+            &ctls,
+            &config,
+            [squares.generate_trace(), caller_trace],
+            [vec![], vec![]],
+            &mut TimingTree::default(),
+        )?;
+
+        assert!(verify_all(starks, &ctls, &proof, &config).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_multi_table_stark_degree() -> Result<()> {
+        test_stark_low_degree(SquaresStark::<F, D>::new(SQUARES_ROWS))?;
+        test_stark_low_degree(CallerStark::<F, D>::new(CALLER_ROWS))
+    }
+
+    #[test]
+    fn test_multi_table_stark_circuit() -> Result<()> {
+        test_stark_circuit_constraints::<F, C, _, D>(SquaresStark::<F, D>::new(SQUARES_ROWS))?;
+        test_stark_circuit_constraints::<F, C, _, D>(CallerStark::<F, D>::new(CALLER_ROWS))
+    }
+
+    #[test]
+    fn test_recursive_multi_table_verifier() -> Result<()> {
+        let config = StarkConfig::standard_fast_config();
+        let squares = SquaresStark::<F, D>::new(SQUARES_ROWS);
+        let caller = CallerStark::<F, D>::new(CALLER_ROWS);
+        let starks: [&dyn StarkTable<F, C, D>; NUM_TABLES] = [&squares, &caller];
+        let ctls = cross_table_lookups::<F, D>();
+
+        let inner_proof = prove_all::<F, C, D, NUM_TABLES>(
+            starks,
+            &ctls,
+            &config,
+            [squares.generate_trace(), caller.generate_trace()],
+            [vec![], vec![]],
+            &mut TimingTree::default(),
+        )?;
+        verify_all(starks, &ctls, &inner_proof, &config)?;
+
+        let circuit_config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(circuit_config);
+        let mut pw = PartialWitness::new();
+        let degree_bits = inner_proof.recover_degree_bits(&config);
+        assert_eq!(
+            degree_bits[Table::Caller as usize],
+            degree_bits[Table::Squares as usize] + 1
+        );
+        let pt = add_virtual_multi_proof(&mut builder, starks, &ctls, &config, degree_bits);
+        set_multi_proof_target(&mut pw, &pt, &inner_proof);
+
+        verify_all_circuit::<F, C, D, NUM_TABLES>(&mut builder, starks, &ctls, &pt, &config);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        data.verify(proof)
+    }
+}
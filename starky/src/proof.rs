@@ -10,22 +10,28 @@ use plonky2::fri::proof::{
 use plonky2::fri::structure::{
     FriOpeningBatch, FriOpeningBatchTarget, FriOpenings, FriOpeningsTarget,
 };
+use plonky2::fri::FriParams;
 use plonky2::hash::hash_types::{MerkleCapTarget, RichField};
 use plonky2::hash::merkle_tree::MerkleCap;
 use plonky2::iop::ext_target::ExtensionTarget;
 use plonky2::iop::target::Target;
 use plonky2::plonk::config::GenericConfig;
+use plonky2::plonk::proof::FriInferredElements;
+use plonky2::util::serialization::Buffer;
 use plonky2_maybe_rayon::*;
 
 use crate::config::StarkConfig;
 use crate::permutation::PermutationChallengeSet;
+use crate::stark::Stark;
+use crate::util::serialization::{StarkRead, StarkWrite};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Eq, PartialEq)]
 pub struct StarkProof<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize> {
     /// Merkle cap of LDEs of trace values.
     pub trace_cap: MerkleCap<F, C::Hasher>,
-    /// Merkle cap of LDEs of permutation Z values.
-    pub permutation_zs_cap: Option<MerkleCap<F, C::Hasher>>,
+    /// Merkle cap of LDEs of the auxiliary polynomials: permutation `Z`s, lookup helper columns
+    /// and cross-table lookup `Z`s, if any.
+    pub auxiliary_polys_cap: Option<MerkleCap<F, C::Hasher>>,
     /// Merkle cap of LDEs of trace values.
     pub quotient_polys_cap: MerkleCap<F, C::Hasher>,
     /// Purported values of each polynomial at the challenge point.
@@ -42,13 +48,34 @@ impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize> S
             .evals_proofs[0]
             .1;
         let lde_bits = config.fri_config.cap_height + initial_merkle_proof.siblings.len();
-        lde_bits - config.fri_config.rate_bits
+        lde_bits - config.fri_config.rate_bits - config.zero_knowledge as usize
+    }
+
+    /// Compress all the Merkle paths in the opening proof, given the FRI query indices.
+    pub fn compress(self, indices: &[usize], params: &FriParams) -> CompressedStarkProof<F, C, D> {
+        let StarkProof {
+            trace_cap,
+            auxiliary_polys_cap,
+            quotient_polys_cap,
+            openings,
+            opening_proof,
+        } = self;
+
+        CompressedStarkProof {
+            // In zero-knowledge mode the FRI degree is one more than the trace degree.
+            degree_bits: params.degree_bits - params.hiding as usize,
+            trace_cap,
+            auxiliary_polys_cap,
+            quotient_polys_cap,
+            openings,
+            opening_proof: opening_proof.compress(indices, params),
+        }
     }
 }
 
 pub struct StarkProofTarget<const D: usize> {
     pub trace_cap: MerkleCapTarget,
-    pub permutation_zs_cap: Option<MerkleCapTarget>,
+    pub auxiliary_polys_cap: Option<MerkleCapTarget>,
     pub quotient_polys_cap: MerkleCapTarget,
     pub openings: StarkOpeningSetTarget<D>,
     pub opening_proof: FriProofTarget<D>,
@@ -62,11 +89,11 @@ impl<const D: usize> StarkProofTarget<D> {
             .evals_proofs[0]
             .1;
         let lde_bits = config.fri_config.cap_height + initial_merkle_proof.siblings.len();
-        lde_bits - config.fri_config.rate_bits
+        lde_bits - config.fri_config.rate_bits - config.zero_knowledge as usize
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Eq, PartialEq)]
 pub struct StarkProofWithPublicInputs<
     F: RichField + Extendable<D>,
     C: GenericConfig<D, F = F>,
@@ -77,24 +104,107 @@ pub struct StarkProofWithPublicInputs<
     pub public_inputs: Vec<F>,
 }
 
+impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
+    StarkProofWithPublicInputs<F, C, D>
+{
+    pub fn compress<S: Stark<F, D>>(
+        self,
+        stark: &S,
+        config: &StarkConfig,
+    ) -> CompressedStarkProofWithPublicInputs<F, C, D> {
+        let degree_bits = self.proof.recover_degree_bits(config);
+        let indices = self.fri_query_indices(stark, config, degree_bits);
+        let compressed_proof = self
+            .proof
+            .compress(&indices, &config.fri_params(degree_bits));
+        CompressedStarkProofWithPublicInputs {
+            proof: compressed_proof,
+            public_inputs: self.public_inputs,
+        }
+    }
+
+    pub fn to_bytes(&self, config: &StarkConfig) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        buffer
+            .write_stark_proof_with_public_inputs(self, config)
+            .expect("Writing to a byte-vector cannot fail.");
+        buffer
+    }
+
+    pub fn from_bytes<S: Stark<F, D>>(
+        bytes: Vec<u8>,
+        stark: &S,
+        config: &StarkConfig,
+    ) -> anyhow::Result<Self> {
+        let mut buffer = Buffer::new(&bytes);
+        let proof = buffer
+            .read_stark_proof_with_public_inputs(stark, config)
+            .map_err(anyhow::Error::msg)?;
+        Ok(proof)
+    }
+}
+
 pub struct StarkProofWithPublicInputsTarget<const D: usize> {
     pub proof: StarkProofTarget<D>,
     pub public_inputs: Vec<Target>,
 }
 
+#[derive(Debug, Clone, Eq, PartialEq)]
 pub struct CompressedStarkProof<
     F: RichField + Extendable<D>,
     C: GenericConfig<D, F = F>,
     const D: usize,
 > {
+    /// The log of the trace length. Unlike in `StarkProof`, it can't be recovered from the Merkle
+    /// proofs once they are compressed.
+    pub degree_bits: usize,
     /// Merkle cap of LDEs of trace values.
     pub trace_cap: MerkleCap<F, C::Hasher>,
+    /// Merkle cap of LDEs of the auxiliary polynomials: permutation `Z`s, lookup helper columns
+    /// and cross-table lookup `Z`s, if any.
+    pub auxiliary_polys_cap: Option<MerkleCap<F, C::Hasher>>,
+    /// Merkle cap of LDEs of trace values.
+    pub quotient_polys_cap: MerkleCap<F, C::Hasher>,
     /// Purported values of each polynomial at the challenge point.
     pub openings: StarkOpeningSet<F, D>,
     /// A batch FRI argument for all openings.
     pub opening_proof: CompressedFriProof<F, C::Hasher, D>,
 }
 
+impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
+    CompressedStarkProof<F, C, D>
+{
+    /// Decompress the proof.
+    pub(crate) fn decompress(
+        self,
+        challenges: &StarkProofChallenges<F, D>,
+        fri_inferred_elements: FriInferredElements<F, D>,
+        params: &FriParams,
+    ) -> StarkProof<F, C, D> {
+        let CompressedStarkProof {
+            trace_cap,
+            auxiliary_polys_cap,
+            quotient_polys_cap,
+            openings,
+            opening_proof,
+            ..
+        } = self;
+
+        StarkProof {
+            trace_cap,
+            auxiliary_polys_cap,
+            quotient_polys_cap,
+            openings,
+            opening_proof: opening_proof.decompress(
+                &challenges.fri_challenges,
+                fri_inferred_elements,
+                params,
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
 pub struct CompressedStarkProofWithPublicInputs<
     F: RichField + Extendable<D>,
     C: GenericConfig<D, F = F>,
@@ -104,10 +214,54 @@ pub struct CompressedStarkProofWithPublicInputs<
     pub public_inputs: Vec<F>,
 }
 
+impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
+    CompressedStarkProofWithPublicInputs<F, C, D>
+{
+    pub fn decompress<S: Stark<F, D>>(
+        self,
+        stark: &S,
+        config: &StarkConfig,
+    ) -> StarkProofWithPublicInputs<F, C, D> {
+        let challenges = self.get_challenges(stark, config);
+        let fri_inferred_elements = self.get_inferred_elements(stark, &challenges, config);
+        let fri_params = config.fri_params(self.proof.degree_bits);
+        let decompressed_proof =
+            self.proof
+                .decompress(&challenges, fri_inferred_elements, &fri_params);
+        StarkProofWithPublicInputs {
+            proof: decompressed_proof,
+            public_inputs: self.public_inputs,
+        }
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        buffer
+            .write_compressed_stark_proof_with_public_inputs(self)
+            .expect("Writing to a byte-vector cannot fail.");
+        buffer
+    }
+
+    pub fn from_bytes<S: Stark<F, D>>(
+        bytes: Vec<u8>,
+        stark: &S,
+        config: &StarkConfig,
+    ) -> anyhow::Result<Self> {
+        let mut buffer = Buffer::new(&bytes);
+        let proof = buffer
+            .read_compressed_stark_proof_with_public_inputs(stark, config)
+            .map_err(anyhow::Error::msg)?;
+        Ok(proof)
+    }
+}
+
 pub(crate) struct StarkProofChallenges<F: RichField + Extendable<D>, const D: usize> {
     /// Randomness used in any permutation arguments.
     pub permutation_challenge_sets: Option<Vec<PermutationChallengeSet<F>>>,
 
+    /// Randomness used in any lookup arguments.
+    pub lookup_challenges: Option<Vec<F>>,
+
     /// Random values used to combine STARK constraints.
     pub stark_alphas: Vec<F>,
 
@@ -119,28 +273,40 @@ pub(crate) struct StarkProofChallenges<F: RichField + Extendable<D>, const D: us
 
 pub(crate) struct StarkProofChallengesTarget<const D: usize> {
     pub permutation_challenge_sets: Option<Vec<PermutationChallengeSet<Target>>>,
+    pub lookup_challenges: Option<Vec<Target>>,
     pub stark_alphas: Vec<Target>,
     pub stark_zeta: ExtensionTarget<D>,
     pub fri_challenges: FriChallengesTarget<D>,
 }
 
 /// Purported values of each polynomial at the challenge point.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Eq, PartialEq)]
 pub struct StarkOpeningSet<F: RichField + Extendable<D>, const D: usize> {
+    /// Openings of trace polynomials at `zeta`.
     pub local_values: Vec<F::Extension>,
+    /// Openings of trace polynomials at `g * zeta`.
     pub next_values: Vec<F::Extension>,
-    pub permutation_zs: Option<Vec<F::Extension>>,
-    pub permutation_zs_next: Option<Vec<F::Extension>>,
+    /// Openings of auxiliary polynomials at `zeta`.
+    pub auxiliary_polys: Option<Vec<F::Extension>>,
+    /// Openings of auxiliary polynomials at `g * zeta`.
+    pub auxiliary_polys_next: Option<Vec<F::Extension>>,
+    /// Openings of cross-table lookup `Z` polynomials at 1.
+    pub ctl_zs_first: Option<Vec<F>>,
+    /// Openings of quotient polynomials at `zeta`.
     pub quotient_polys: Vec<F::Extension>,
 }
 
 impl<F: RichField + Extendable<D>, const D: usize> StarkOpeningSet<F, D> {
+    /// Returns a `StarkOpeningSet` given all the polynomial commitments, the number of
+    /// cross-table lookup `Z` polynomials, the evaluation point and a generator `g`.
+    /// The cross-table lookup `Z` polynomials are the last auxiliary polynomials.
     pub fn new<C: GenericConfig<D, F = F>>(
         zeta: F::Extension,
         g: F,
         trace_commitment: &PolynomialBatch<F, C, D>,
-        permutation_zs_commitment: Option<&PolynomialBatch<F, C, D>>,
+        auxiliary_polys_commitment: Option<&PolynomialBatch<F, C, D>>,
         quotient_commitment: &PolynomialBatch<F, C, D>,
+        num_ctl_zs: usize,
     ) -> Self {
         let eval_commitment = |z: F::Extension, c: &PolynomialBatch<F, C, D>| {
             c.polynomials
@@ -148,22 +314,35 @@ impl<F: RichField + Extendable<D>, const D: usize> StarkOpeningSet<F, D> {
                 .map(|p| p.to_extension().eval(z))
                 .collect::<Vec<_>>()
         };
+        let eval_commitment_base = |z: F, c: &PolynomialBatch<F, C, D>| {
+            c.polynomials
+                .par_iter()
+                .map(|p| p.eval(z))
+                .collect::<Vec<_>>()
+        };
         let zeta_next = zeta.scalar_mul(g);
         Self {
             local_values: eval_commitment(zeta, trace_commitment),
             next_values: eval_commitment(zeta_next, trace_commitment),
-            permutation_zs: permutation_zs_commitment.map(|c| eval_commitment(zeta, c)),
-            permutation_zs_next: permutation_zs_commitment.map(|c| eval_commitment(zeta_next, c)),
+            auxiliary_polys: auxiliary_polys_commitment.map(|c| eval_commitment(zeta, c)),
+            auxiliary_polys_next: auxiliary_polys_commitment.map(|c| eval_commitment(zeta_next, c)),
+            ctl_zs_first: (num_ctl_zs > 0).then(|| {
+                let c = auxiliary_polys_commitment.expect("Missing auxiliary polynomials.");
+                let total_num_helper_cols = c.polynomials.len() - num_ctl_zs;
+                eval_commitment_base(F::ONE, c)[total_num_helper_cols..].to_vec()
+            }),
             quotient_polys: eval_commitment(zeta, quotient_commitment),
         }
     }
 
+    /// Constructs the openings required by FRI.
+    /// All openings but `ctl_zs_first` are grouped together.
     pub(crate) fn to_fri_openings(&self) -> FriOpenings<F, D> {
         let zeta_batch = FriOpeningBatch {
             values: self
                 .local_values
                 .iter()
-                .chain(self.permutation_zs.iter().flatten())
+                .chain(self.auxiliary_polys.iter().flatten())
                 .chain(&self.quotient_polys)
                 .copied()
                 .collect_vec(),
@@ -172,31 +351,41 @@ impl<F: RichField + Extendable<D>, const D: usize> StarkOpeningSet<F, D> {
             values: self
                 .next_values
                 .iter()
-                .chain(self.permutation_zs_next.iter().flatten())
+                .chain(self.auxiliary_polys_next.iter().flatten())
                 .copied()
                 .collect_vec(),
         };
-        FriOpenings {
-            batches: vec![zeta_batch, zeta_next_batch],
+        let mut batches = vec![zeta_batch, zeta_next_batch];
+        if let Some(ctl_zs_first) = &self.ctl_zs_first {
+            batches.push(FriOpeningBatch {
+                values: ctl_zs_first
+                    .iter()
+                    .copied()
+                    .map(F::Extension::from_basefield)
+                    .collect(),
+            });
         }
+        FriOpenings { batches }
     }
 }
 
 pub struct StarkOpeningSetTarget<const D: usize> {
     pub local_values: Vec<ExtensionTarget<D>>,
     pub next_values: Vec<ExtensionTarget<D>>,
-    pub permutation_zs: Option<Vec<ExtensionTarget<D>>>,
-    pub permutation_zs_next: Option<Vec<ExtensionTarget<D>>>,
+    pub auxiliary_polys: Option<Vec<ExtensionTarget<D>>>,
+    pub auxiliary_polys_next: Option<Vec<ExtensionTarget<D>>>,
+    pub ctl_zs_first: Option<Vec<Target>>,
     pub quotient_polys: Vec<ExtensionTarget<D>>,
 }
 
 impl<const D: usize> StarkOpeningSetTarget<D> {
-    pub(crate) fn to_fri_openings(&self) -> FriOpeningsTarget<D> {
+    /// Circuit version of `to_fri_openings`.
+    pub(crate) fn to_fri_openings(&self, zero: Target) -> FriOpeningsTarget<D> {
         let zeta_batch = FriOpeningBatchTarget {
             values: self
                 .local_values
                 .iter()
-                .chain(self.permutation_zs.iter().flatten())
+                .chain(self.auxiliary_polys.iter().flatten())
                 .chain(&self.quotient_polys)
                 .copied()
                 .collect_vec(),
@@ -205,12 +394,46 @@ impl<const D: usize> StarkOpeningSetTarget<D> {
             values: self
                 .next_values
                 .iter()
-                .chain(self.permutation_zs_next.iter().flatten())
+                .chain(self.auxiliary_polys_next.iter().flatten())
                 .copied()
                 .collect_vec(),
         };
-        FriOpeningsTarget {
-            batches: vec![zeta_batch, zeta_next_batch],
+        let mut batches = vec![zeta_batch, zeta_next_batch];
+        if let Some(ctl_zs_first) = &self.ctl_zs_first {
+            batches.push(FriOpeningBatchTarget {
+                values: ctl_zs_first
+                    .iter()
+                    .map(|&t| t.to_ext_target(zero))
+                    .collect(),
+            });
         }
+        FriOpeningsTarget { batches }
     }
 }
+
+/// Proofs of all the tables of a multi-table STARK system, which are tied together by
+/// cross-table lookups. `N` is the number of tables.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct MultiProof<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+    const N: usize,
+> {
+    /// The proof of each table, in the order of the tables' indices.
+    pub stark_proofs: [StarkProofWithPublicInputs<F, C, D>; N],
+}
+
+impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize, const N: usize>
+    MultiProof<F, C, D, N>
+{
+    /// Returns the degree (i.e. the trace length) of each table.
+    pub fn recover_degree_bits(&self, config: &StarkConfig) -> [usize; N] {
+        core::array::from_fn(|i| self.stark_proofs[i].proof.recover_degree_bits(config))
+    }
+}
+
+/// Circuit version of `MultiProof`.
+pub struct MultiProofTarget<const D: usize, const N: usize> {
+    pub stark_proofs: [StarkProofWithPublicInputsTarget<D>; N],
+}
@@ -1,3 +1,4 @@
+use alloc::vec;
 use alloc::vec::Vec;
 use core::iter::once;
 
@@ -20,13 +21,18 @@ use plonky2_maybe_rayon::*;
 
 use crate::config::StarkConfig;
 use crate::constraint_consumer::ConstraintConsumer;
+use crate::cross_table_lookup::{
+    cross_table_lookup_data, get_grand_product_challenge_set, CrossTableLookup, CtlCheckVars,
+    CtlData,
+};
 use crate::evaluation_frame::StarkEvaluationFrame;
+use crate::lookup::{lookup_helper_columns, LookupCheckVars};
 use crate::permutation::{
     compute_permutation_z_polys, get_n_permutation_challenge_sets, PermutationChallengeSet,
     PermutationCheckVars,
 };
-use crate::proof::{StarkOpeningSet, StarkProof, StarkProofWithPublicInputs};
-use crate::stark::Stark;
+use crate::proof::{MultiProof, StarkOpeningSet, StarkProof, StarkProofWithPublicInputs};
+use crate::stark::{Stark, StarkTable};
 use crate::vanishing_poly::eval_vanishing_poly;
 
 pub fn prove<F, C, S, const D: usize>(
@@ -41,78 +47,220 @@ where
     C: GenericConfig<D, F = F>,
     S: Stark<F, D>,
 {
-    let degree = trace_poly_values[0].len();
-    let degree_bits = log2_strict(degree);
-    let fri_params = config.fri_params(degree_bits);
-    let rate_bits = config.fri_config.rate_bits;
-    let cap_height = config.fri_config.cap_height;
-    assert!(
-        fri_params.total_arities() <= degree_bits + rate_bits - cap_height,
-        "FRI total reduction arity is too large.",
-    );
-
     let trace_commitment = timed!(
         timing,
         "compute trace commitment",
-        PolynomialBatch::<F, C, D>::from_values(
+        commit_trace_polys::<F, C, D>(
             // TODO: Cloning this isn't great; consider having `from_values` accept a reference,
             // or having `compute_permutation_z_polys` read trace values from the `PolynomialBatch`.
             trace_poly_values.clone(),
-            rate_bits,
-            false,
-            cap_height,
+            config,
             timing,
-            None,
         )
     );
 
-    let trace_cap = trace_commitment.merkle_tree.cap.clone();
     let mut challenger = Challenger::new();
-    challenger.observe_cap(&trace_cap);
+    challenger.observe_cap(&trace_commitment.merkle_tree.cap);
+
+    prove_with_commitment(
+        &stark,
+        config,
+        &trace_poly_values,
+        &trace_commitment,
+        None,
+        public_inputs,
+        &mut challenger,
+        timing,
+    )
+}
+
+/// Generates a proof for each STARK of a multi-table system, along with the cross-table lookups
+/// between them. The `i`-th STARK is the table of index `i` in `cross_table_lookups`.
+pub fn prove_all<F, C, const D: usize, const N: usize>(
+    starks: [&dyn StarkTable<F, C, D>; N],
+    cross_table_lookups: &[CrossTableLookup<F>],
+    config: &StarkConfig,
+    trace_poly_values: [Vec<PolynomialValues<F>>; N],
+    public_inputs: [Vec<F>; N],
+    timing: &mut TimingTree,
+) -> Result<MultiProof<F, C, D, N>>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    let trace_commitments = timed!(
+        timing,
+        "compute all trace commitments",
+        trace_poly_values
+            .iter()
+            .map(|trace| commit_trace_polys::<F, C, D>(trace.clone(), config, timing))
+            .collect::<Vec<_>>()
+    );
+
+    let mut challenger = Challenger::<F, C::Hasher>::new();
+    for trace_commitment in &trace_commitments {
+        challenger.observe_cap(&trace_commitment.merkle_tree.cap);
+    }
+
+    let ctl_challenges = get_grand_product_challenge_set(&mut challenger, config.num_challenges);
+    let ctl_data_per_table = timed!(
+        timing,
+        "compute CTL data",
+        cross_table_lookup_data::<F, D, N>(
+            &trace_poly_values,
+            cross_table_lookups,
+            &ctl_challenges
+        )
+    );
+
+    // Every table continues from the same challenger state, so that the tables can be proven and
+    // verified independently once the CTL challenges are drawn.
+    challenger.compact();
+
+    let stark_proofs = starks
+        .iter()
+        .zip_eq(&trace_poly_values)
+        .zip_eq(&trace_commitments)
+        .zip_eq(&ctl_data_per_table)
+        .zip_eq(&public_inputs)
+        .map(
+            |((((stark, trace_poly_values), trace_commitment), ctl_data), public_inputs)| {
+                stark.prove_with_commitment(
+                    config,
+                    trace_poly_values,
+                    trace_commitment,
+                    ctl_data,
+                    public_inputs,
+                    &mut challenger.clone(),
+                    timing,
+                )
+            },
+        )
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(MultiProof {
+        stark_proofs: stark_proofs
+            .try_into()
+            .expect("There should be one proof per STARK."),
+    })
+}
+
+/// Generates a proof for a single STARK table, whose trace has already been committed to and
+/// observed by `challenger`. If `ctl_data` is provided, the table's cross-table lookup `Z`
+/// polynomials are committed to along with the other auxiliary polynomials.
+pub(crate) fn prove_with_commitment<F, C, S, const D: usize>(
+    stark: &S,
+    config: &StarkConfig,
+    trace_poly_values: &[PolynomialValues<F>],
+    trace_commitment: &PolynomialBatch<F, C, D>,
+    ctl_data: Option<&CtlData<F>>,
+    public_inputs: &[F],
+    challenger: &mut Challenger<F, C::Hasher>,
+    timing: &mut TimingTree,
+) -> Result<StarkProofWithPublicInputs<F, C, D>>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    S: Stark<F, D>,
+{
+    let degree = trace_poly_values[0].len();
+    let degree_bits = log2_strict(degree);
+    let fri_params = config.fri_params(degree_bits);
+    let rate_bits = config.fri_config.rate_bits;
+    let cap_height = config.fri_config.cap_height;
+    assert!(
+        fri_params.total_arities() <= fri_params.degree_bits + rate_bits - cap_height,
+        "FRI total reduction arity is too large.",
+    );
+    // The quotient is evaluated on the LDE of the committed polynomials, which have twice the
+    // degree in zero-knowledge mode.
+    let num_quotient_chunks = stark.num_quotient_chunks(config);
+    let min_rate_bits =
+        log2_ceil(num_quotient_chunks).saturating_sub(config.zero_knowledge as usize);
+    ensure!(
+        rate_bits >= min_rate_bits,
+        "A quotient of {} chunks needs rate_bits >= {}, got {}",
+        num_quotient_chunks,
+        min_rate_bits,
+        rate_bits
+    );
+
+    let trace_cap = trace_commitment.merkle_tree.cap.clone();
 
     // Permutation arguments.
-    let permutation_zs_commitment_challenges = stark.uses_permutation_args().then(|| {
-        let permutation_challenge_sets = get_n_permutation_challenge_sets(
-            &mut challenger,
+    let permutation_challenge_sets = stark.uses_permutation_args().then(|| {
+        get_n_permutation_challenge_sets(
+            challenger,
             config.num_challenges,
             stark.permutation_batch_size(),
-        );
-        let permutation_z_polys = compute_permutation_z_polys::<F, S, D>(
-            &stark,
-            config,
-            &trace_poly_values,
-            &permutation_challenge_sets,
-        );
+        )
+    });
+    let permutation_z_polys = permutation_challenge_sets.as_ref().map(|challenge_sets| {
+        compute_permutation_z_polys::<F, S, D>(stark, config, trace_poly_values, challenge_sets)
+    });
 
-        let permutation_zs_commitment = timed!(
+    // Lookup arguments.
+    let constraint_degree = stark.constraint_degree();
+    let lookup_challenges = stark
+        .uses_lookups()
+        .then(|| challenger.get_n_challenges(config.num_challenges));
+    let lookup_helper_columns = lookup_challenges.as_ref().map(|challenges| {
+        timed!(timing, "compute lookup helper columns", {
+            let mut columns = vec![];
+            for lookup in &stark.lookups() {
+                for &challenge in challenges {
+                    columns.extend(lookup_helper_columns(
+                        lookup,
+                        trace_poly_values,
+                        challenge,
+                        constraint_degree,
+                    ));
+                }
+            }
+            columns
+        })
+    });
+
+    // The auxiliary polynomials are the permutation `Z`s, then the lookup helper columns, then
+    // the cross-table lookup `Z`s.
+    let num_permutation_zs = permutation_z_polys.as_ref().map_or(0, Vec::len);
+    let num_lookup_columns = lookup_helper_columns.as_ref().map_or(0, Vec::len);
+    let num_ctl_zs = ctl_data.map_or(0, CtlData::len);
+    let auxiliary_polys = permutation_z_polys
+        .into_iter()
+        .flatten()
+        .chain(lookup_helper_columns.into_iter().flatten())
+        .chain(ctl_data.into_iter().flat_map(CtlData::z_polys))
+        .collect_vec();
+    debug_assert_eq!(
+        auxiliary_polys.len(),
+        stark.num_auxiliary_polys(num_ctl_zs, config)
+    );
+
+    let auxiliary_polys_commitment = (!auxiliary_polys.is_empty()).then(|| {
+        timed!(
             timing,
-            "compute permutation Z commitments",
-            PolynomialBatch::from_values(
-                permutation_z_polys,
-                rate_bits,
-                false,
-                config.fri_config.cap_height,
-                timing,
-                None,
-            )
-        );
-        (permutation_zs_commitment, permutation_challenge_sets)
+            "compute auxiliary polynomials commitment",
+            commit_trace_polys(auxiliary_polys, config, timing)
+        )
     });
-    let permutation_zs_commitment = permutation_zs_commitment_challenges
-        .as_ref()
-        .map(|(comm, _)| comm);
-    let permutation_zs_cap = permutation_zs_commitment
+    let auxiliary_polys_cap = auxiliary_polys_commitment
         .as_ref()
         .map(|commit| commit.merkle_tree.cap.clone());
-    if let Some(cap) = &permutation_zs_cap {
+    if let Some(cap) = &auxiliary_polys_cap {
         challenger.observe_cap(cap);
     }
 
     let alphas = challenger.get_n_challenges(config.num_challenges);
     let quotient_polys = compute_quotient_polys::<F, <F as Packable>::Packing, C, S, D>(
-        &stark,
-        &trace_commitment,
-        &permutation_zs_commitment_challenges,
+        stark,
+        trace_commitment,
+        auxiliary_polys_commitment.as_ref(),
+        permutation_challenge_sets.as_deref(),
+        lookup_challenges.as_deref(),
+        ctl_data,
+        num_permutation_zs,
+        num_lookup_columns,
         public_inputs,
         alphas,
         degree_bits,
@@ -122,10 +270,15 @@ where
         .into_par_iter()
         .flat_map(|mut quotient_poly| {
             quotient_poly
-                .trim_to_len(degree * stark.quotient_degree_factor())
+                .trim_to_len(degree * stark.num_quotient_chunks(config))
                 .expect("Quotient has failed, the vanishing polynomial is not divisible by Z_H");
             // Split quotient into degree-n chunks.
-            quotient_poly.chunks(degree)
+            let chunks = quotient_poly.chunks(degree);
+            if config.zero_knowledge {
+                blind_quotient_chunks(chunks)
+            } else {
+                chunks
+            }
         })
         .collect();
     let quotient_commitment = timed!(
@@ -134,8 +287,8 @@ where
         PolynomialBatch::from_coeffs(
             all_quotient_chunks,
             rate_bits,
-            false,
-            config.fri_config.cap_height,
+            config.zero_knowledge,
+            cap_height,
             timing,
             None,
         )
@@ -155,14 +308,15 @@ where
     let openings = StarkOpeningSet::new(
         zeta,
         g,
-        &trace_commitment,
-        permutation_zs_commitment,
+        trace_commitment,
+        auxiliary_polys_commitment.as_ref(),
         &quotient_commitment,
+        num_ctl_zs,
     );
     challenger.observe_openings(&openings.to_fri_openings());
 
-    let initial_merkle_trees = once(&trace_commitment)
-        .chain(permutation_zs_commitment)
+    let initial_merkle_trees = once(trace_commitment)
+        .chain(&auxiliary_polys_commitment)
         .chain(once(&quotient_commitment))
         .collect_vec();
 
@@ -170,16 +324,16 @@ where
         timing,
         "compute openings proof",
         PolynomialBatch::prove_openings(
-            &stark.fri_instance(zeta, g, config),
+            &stark.fri_instance(zeta, g, num_ctl_zs, config),
             &initial_merkle_trees,
-            &mut challenger,
+            challenger,
             &fri_params,
             timing,
         )
     );
     let proof = StarkProof {
         trace_cap,
-        permutation_zs_cap,
+        auxiliary_polys_cap,
         quotient_polys_cap,
         openings,
         opening_proof,
@@ -191,15 +345,82 @@ where
     })
 }
 
+/// Commits to polynomials given by their values on the trace domain. In zero-knowledge mode, the
+/// polynomials are randomized and the Merkle leaves salted.
+pub(crate) fn commit_trace_polys<F, C, const D: usize>(
+    values: Vec<PolynomialValues<F>>,
+    config: &StarkConfig,
+    timing: &mut TimingTree,
+) -> PolynomialBatch<F, C, D>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    let rate_bits = config.fri_config.rate_bits;
+    let cap_height = config.fri_config.cap_height;
+    if config.zero_knowledge {
+        PolynomialBatch::from_coeffs(
+            randomize_polys(values),
+            rate_bits,
+            true,
+            cap_height,
+            timing,
+            None,
+        )
+    } else {
+        PolynomialBatch::from_values(values, rate_bits, false, cap_height, timing, None)
+    }
+}
+
+/// Interpolates each of `values` into a polynomial `p` of degree less than `n = values.len()`,
+/// and returns `p(X) + (X^n - 1) r(X)` for a random `r` of degree less than `n`. The result agrees
+/// with `p` on the trace domain, while its evaluations outside of it reveal nothing about `p`.
+fn randomize_polys<F: Field>(values: Vec<PolynomialValues<F>>) -> Vec<PolynomialCoeffs<F>> {
+    values
+        .into_par_iter()
+        .map(|values| {
+            let mut coeffs = values.ifft().coeffs;
+            let r = F::rand_vec(coeffs.len());
+            for (c, &r) in coeffs.iter_mut().zip(&r) {
+                *c -= r;
+            }
+            coeffs.extend(r);
+            PolynomialCoeffs::new(coeffs)
+        })
+        .collect()
+}
+
+/// Blinds the degree-`n` chunks `c_i` of a quotient polynomial, replacing `c_i` with
+/// `c_i + X^n s_i` and `c_{i+1}` with `c_{i+1} - s_i` for random `s_i` of degree less than `n`.
+/// This leaves `sum_i X^(i n) c_i` unchanged. All the chunks are padded to `2n` coefficients.
+fn blind_quotient_chunks<F: Field>(
+    mut chunks: Vec<PolynomialCoeffs<F>>,
+) -> Vec<PolynomialCoeffs<F>> {
+    let n = chunks[0].len();
+    for chunk in &mut chunks {
+        chunk.pad(2 * n).unwrap();
+    }
+    for i in 1..chunks.len() {
+        let s = F::rand_vec(n);
+        for (j, &s) in s.iter().enumerate() {
+            chunks[i - 1].coeffs[n + j] += s;
+            chunks[i].coeffs[j] -= s;
+        }
+    }
+    chunks
+}
+
 /// Computes the quotient polynomials `(sum alpha^i C_i(x)) / Z_H(x)` for `alpha` in `alphas`,
 /// where the `C_i`s are the Stark constraints.
 fn compute_quotient_polys<'a, F, P, C, S, const D: usize>(
     stark: &S,
     trace_commitment: &'a PolynomialBatch<F, C, D>,
-    permutation_zs_commitment_challenges: &'a Option<(
-        PolynomialBatch<F, C, D>,
-        Vec<PermutationChallengeSet<F>>,
-    )>,
+    auxiliary_polys_commitment: Option<&'a PolynomialBatch<F, C, D>>,
+    permutation_challenge_sets: Option<&[PermutationChallengeSet<F>]>,
+    lookup_challenges: Option<&[F]>,
+    ctl_data: Option<&CtlData<F>>,
+    num_permutation_zs: usize,
+    num_lookup_columns: usize,
     public_inputs: &[F],
     alphas: Vec<F>,
     degree_bits: usize,
@@ -214,12 +435,15 @@ where
     let degree = 1 << degree_bits;
     let rate_bits = config.fri_config.rate_bits;
 
-    let quotient_degree_bits = log2_ceil(stark.quotient_degree_factor());
+    // In zero-knowledge mode, the committed polynomials have twice the degree, so their LDEs
+    // have twice as many points.
+    let blowup_bits = trace_commitment.degree_log + rate_bits - degree_bits;
+    let quotient_degree_bits = log2_ceil(stark.num_quotient_chunks(config));
     assert!(
-        quotient_degree_bits <= rate_bits,
+        quotient_degree_bits <= blowup_bits,
         "Having constraints of degree higher than the rate is not supported yet."
     );
-    let step = 1 << (rate_bits - quotient_degree_bits);
+    let step = 1 << (blowup_bits - quotient_degree_bits);
     // When opening the `Z`s polys at the "next" point, need to look at the point `next_step` steps away.
     let next_step = 1 << quotient_degree_bits;
 
@@ -269,18 +493,48 @@ where
                 &get_trace_values_packed(i_next_start),
                 public_inputs,
             );
-            let permutation_check_data = permutation_zs_commitment_challenges.as_ref().map(
-                |(permutation_zs_commitment, permutation_challenge_sets)| PermutationCheckVars {
-                    local_zs: permutation_zs_commitment.get_lde_values_packed(i_start, step),
-                    next_zs: permutation_zs_commitment.get_lde_values_packed(i_next_start, step),
-                    permutation_challenge_sets: permutation_challenge_sets.to_vec(),
-                },
-            );
+            let auxiliary_values = auxiliary_polys_commitment.map(|commitment| {
+                (
+                    commitment.get_lde_values_packed(i_start, step),
+                    commitment.get_lde_values_packed(i_next_start, step),
+                )
+            });
+            let (local_auxiliary, next_auxiliary) = auxiliary_values.unwrap_or_default();
+            let permutation_end = num_permutation_zs;
+            let lookup_end = permutation_end + num_lookup_columns;
+
+            let permutation_check_data =
+                permutation_challenge_sets.map(|challenge_sets| PermutationCheckVars {
+                    local_zs: local_auxiliary[..permutation_end].to_vec(),
+                    next_zs: next_auxiliary[..permutation_end].to_vec(),
+                    permutation_challenge_sets: challenge_sets.to_vec(),
+                });
+            let lookup_vars = lookup_challenges.map(|challenges| LookupCheckVars {
+                local_values: local_auxiliary[permutation_end..lookup_end].to_vec(),
+                next_values: next_auxiliary[permutation_end..lookup_end].to_vec(),
+                challenges: challenges.to_vec(),
+            });
+            let ctl_vars = ctl_data.map(|ctl_data| {
+                ctl_data
+                    .zs_columns
+                    .iter()
+                    .enumerate()
+                    .map(|(i, zs_columns)| CtlCheckVars::<F, F, P, 1> {
+                        local_z: local_auxiliary[lookup_end + i],
+                        next_z: next_auxiliary[lookup_end + i],
+                        challenges: zs_columns.challenge,
+                        columns: &zs_columns.columns,
+                        filter_column: &zs_columns.filter_column,
+                    })
+                    .collect::<Vec<_>>()
+            });
             eval_vanishing_poly::<F, F, P, S, D, 1>(
                 stark,
                 config,
                 &vars,
                 permutation_check_data,
+                lookup_vars,
+                ctl_vars.as_deref(),
                 &mut consumer,
             );
 
@@ -1,3 +1,4 @@
+use alloc::format;
 use alloc::vec::Vec;
 use core::iter::once;
 
@@ -7,22 +8,29 @@ use plonky2::field::extension::Extendable;
 use plonky2::field::types::Field;
 use plonky2::fri::witness_util::set_fri_proof_target;
 use plonky2::hash::hash_types::RichField;
+use plonky2::iop::challenger::RecursiveChallenger;
 use plonky2::iop::ext_target::ExtensionTarget;
 use plonky2::iop::witness::Witness;
 use plonky2::plonk::circuit_builder::CircuitBuilder;
 use plonky2::plonk::config::{AlgebraicHasher, GenericConfig};
+use plonky2::plonk::plonk_common::salt_size;
 use plonky2::util::reducing::ReducingFactorTarget;
 use plonky2::with_context;
 
 use crate::config::StarkConfig;
 use crate::constraint_consumer::RecursiveConstraintConsumer;
+use crate::cross_table_lookup::{
+    get_grand_product_challenge_set_target, verify_cross_table_lookups_circuit, CrossTableLookup,
+    CtlCheckVarsTarget,
+};
 use crate::evaluation_frame::StarkEvaluationFrame;
+use crate::lookup::LookupCheckVarsTarget;
 use crate::permutation::PermutationCheckDataTarget;
 use crate::proof::{
-    StarkOpeningSetTarget, StarkProof, StarkProofChallengesTarget, StarkProofTarget,
-    StarkProofWithPublicInputs, StarkProofWithPublicInputsTarget,
+    MultiProof, MultiProofTarget, StarkOpeningSetTarget, StarkProof, StarkProofChallengesTarget,
+    StarkProofTarget, StarkProofWithPublicInputs, StarkProofWithPublicInputsTarget,
 };
-use crate::stark::Stark;
+use crate::stark::{Stark, StarkTable};
 use crate::vanishing_poly::eval_vanishing_poly_circuit;
 
 pub fn verify_stark_proof_circuit<
@@ -39,20 +47,116 @@ pub fn verify_stark_proof_circuit<
     C::Hasher: AlgebraicHasher<F>,
 {
     assert_eq!(proof_with_pis.public_inputs.len(), S::PUBLIC_INPUTS);
-    let degree_bits = proof_with_pis.proof.recover_degree_bits(inner_config);
     let challenges = with_context!(
         builder,
         "compute challenges",
         proof_with_pis.get_challenges::<F, C, S>(builder, &stark, inner_config)
     );
 
+    verify_stark_proof_with_challenges_circuit::<F, C, S, D>(
+        builder,
+        &stark,
+        &proof_with_pis,
+        challenges,
+        None,
+        inner_config,
+    );
+}
+
+/// Circuit version of [`verify_all`](crate::verifier::verify_all). Recursively verifies a proof
+/// generated by [`prove_all`](crate::prover::prove_all).
+pub fn verify_all_circuit<F, C, const D: usize, const N: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    starks: [&dyn StarkTable<F, C, D>; N],
+    cross_table_lookups: &[CrossTableLookup<F>],
+    multi_proof: &MultiProofTarget<D, N>,
+    inner_config: &StarkConfig,
+) where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    C::Hasher: AlgebraicHasher<F>,
+{
+    let mut challenger = RecursiveChallenger::<F, C::Hasher, D>::new(builder);
+    for proof in &multi_proof.stark_proofs {
+        challenger.observe_cap(&proof.proof.trace_cap);
+    }
+    let ctl_challenges = get_grand_product_challenge_set_target(
+        builder,
+        &mut challenger,
+        inner_config.num_challenges,
+    );
+    let state = challenger.compact(builder);
+
+    for (i, (stark, proof)) in starks.iter().zip_eq(&multi_proof.stark_proofs).enumerate() {
+        let ctl_vars =
+            CtlCheckVarsTarget::from_proof(i, &proof.proof, cross_table_lookups, &ctl_challenges);
+        let mut challenger = RecursiveChallenger::from_state(state);
+        with_context!(
+            builder,
+            &format!("verify table {}", i),
+            stark.verify_circuit_with_challenger(
+                builder,
+                proof,
+                &ctl_vars,
+                &mut challenger,
+                inner_config,
+            )
+        );
+    }
+
+    verify_cross_table_lookups_circuit::<F, D, N>(
+        builder,
+        cross_table_lookups,
+        core::array::from_fn(|i| {
+            multi_proof.stark_proofs[i]
+                .proof
+                .openings
+                .ctl_zs_first
+                .clone()
+                .unwrap_or_default()
+        }),
+        None,
+        inner_config,
+    );
+}
+
+/// Recursively verifies the proof of a single table of a multi-table system. `challenger` must
+/// have observed the trace caps of all tables and drawn the cross-table lookup challenges.
+pub(crate) fn verify_stark_proof_with_challenger_circuit<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    S: Stark<F, D>,
+    const D: usize,
+>(
+    builder: &mut CircuitBuilder<F, D>,
+    stark: &S,
+    proof_with_pis: &StarkProofWithPublicInputsTarget<D>,
+    ctl_vars: &[CtlCheckVarsTarget<F, D>],
+    challenger: &mut RecursiveChallenger<F, C::Hasher, D>,
+    inner_config: &StarkConfig,
+) where
+    C::Hasher: AlgebraicHasher<F>,
+{
+    assert_eq!(proof_with_pis.public_inputs.len(), S::PUBLIC_INPUTS);
+    let challenges = with_context!(
+        builder,
+        "compute challenges",
+        proof_with_pis.get_challenges_with_challenger::<F, C, S>(
+            builder,
+            stark,
+            challenger,
+            true,
+            inner_config,
+        )
+    );
+
     verify_stark_proof_with_challenges_circuit::<F, C, S, D>(
         builder,
         stark,
         proof_with_pis,
         challenges,
+        Some(ctl_vars),
         inner_config,
-        degree_bits,
     );
 }
 
@@ -64,26 +168,28 @@ fn verify_stark_proof_with_challenges_circuit<
     const D: usize,
 >(
     builder: &mut CircuitBuilder<F, D>,
-    stark: S,
-    proof_with_pis: StarkProofWithPublicInputsTarget<D>,
+    stark: &S,
+    proof_with_pis: &StarkProofWithPublicInputsTarget<D>,
     challenges: StarkProofChallengesTarget<D>,
+    ctl_vars: Option<&[CtlCheckVarsTarget<F, D>]>,
     inner_config: &StarkConfig,
-    degree_bits: usize,
 ) where
     C::Hasher: AlgebraicHasher<F>,
 {
-    check_permutation_options(&stark, &proof_with_pis, &challenges).unwrap();
+    check_challenges_options(stark, &challenges).unwrap();
     let one = builder.one_extension();
 
     let StarkProofWithPublicInputsTarget {
         proof,
         public_inputs,
     } = proof_with_pis;
+    let degree_bits = proof.recover_degree_bits(inner_config);
     let StarkOpeningSetTarget {
         local_values,
         next_values,
-        permutation_zs,
-        permutation_zs_next,
+        auxiliary_polys,
+        auxiliary_polys_next,
+        ctl_zs_first: _,
         quotient_polys,
     } = &proof.openings;
 
@@ -91,8 +197,8 @@ fn verify_stark_proof_with_challenges_circuit<
         local_values,
         next_values,
         &public_inputs
-            .into_iter()
-            .map(|t| builder.convert_to_ext(t))
+            .iter()
+            .map(|&t| builder.convert_to_ext(t))
             .collect::<Vec<_>>(),
     );
 
@@ -112,12 +218,26 @@ fn verify_stark_proof_with_challenges_circuit<
         l_last,
     );
 
-    let permutation_data = stark
-        .uses_permutation_args()
-        .then(|| PermutationCheckDataTarget {
-            local_zs: permutation_zs.as_ref().unwrap().clone(),
-            next_zs: permutation_zs_next.as_ref().unwrap().clone(),
-            permutation_challenge_sets: challenges.permutation_challenge_sets.unwrap(),
+    // The auxiliary polynomials are the permutation `Z`s, then the lookup helper columns, then
+    // the cross-table lookup `Z`s.
+    let auxiliary_polys = auxiliary_polys.as_deref().unwrap_or_default();
+    let auxiliary_polys_next = auxiliary_polys_next.as_deref().unwrap_or_default();
+    let permutation_end = stark.num_permutation_batches(inner_config);
+    let lookup_end = permutation_end + stark.num_lookup_helper_columns(inner_config);
+    let permutation_data =
+        challenges
+            .permutation_challenge_sets
+            .map(|challenge_sets| PermutationCheckDataTarget {
+                local_zs: auxiliary_polys[..permutation_end].to_vec(),
+                next_zs: auxiliary_polys_next[..permutation_end].to_vec(),
+                permutation_challenge_sets: challenge_sets,
+            });
+    let lookup_vars = challenges
+        .lookup_challenges
+        .map(|lookup_challenges| LookupCheckVarsTarget {
+            local_values: auxiliary_polys[permutation_end..lookup_end].to_vec(),
+            next_values: auxiliary_polys_next[permutation_end..lookup_end].to_vec(),
+            challenges: lookup_challenges,
         });
 
     with_context!(
@@ -125,10 +245,12 @@ fn verify_stark_proof_with_challenges_circuit<
         "evaluate vanishing polynomial",
         eval_vanishing_poly_circuit::<F, S, D>(
             builder,
-            &stark,
+            stark,
             inner_config,
             &vars,
             permutation_data,
+            lookup_vars,
+            ctl_vars,
             &mut consumer,
         )
     );
@@ -137,7 +259,7 @@ fn verify_stark_proof_with_challenges_circuit<
     // Check each polynomial identity, of the form `vanishing(x) = Z_H(x) quotient(x)`, at zeta.
     let mut scale = ReducingFactorTarget::new(zeta_pow_deg);
     for (i, chunk) in quotient_polys
-        .chunks(stark.quotient_degree_factor())
+        .chunks(stark.num_quotient_chunks(inner_config))
         .enumerate()
     {
         let recombined_quotient = scale.reduce(chunk, builder);
@@ -145,20 +267,23 @@ fn verify_stark_proof_with_challenges_circuit<
         builder.connect_extension(vanishing_polys_zeta[i], computed_vanishing_poly);
     }
 
-    let merkle_caps = once(proof.trace_cap)
-        .chain(proof.permutation_zs_cap)
-        .chain(once(proof.quotient_polys_cap))
+    let merkle_caps = once(proof.trace_cap.clone())
+        .chain(proof.auxiliary_polys_cap.clone())
+        .chain(once(proof.quotient_polys_cap.clone()))
         .collect_vec();
 
+    let num_ctl_zs = ctl_vars.map_or(0, |ctl_vars| ctl_vars.len());
     let fri_instance = stark.fri_instance_target(
         builder,
         challenges.stark_zeta,
         F::primitive_root_of_unity(degree_bits),
+        num_ctl_zs,
         inner_config,
     );
+    let zero = builder.zero();
     builder.verify_fri_proof::<C>(
         &fri_instance,
-        &proof.openings.to_fri_openings(),
+        &proof.openings.to_fri_openings(zero),
         &challenges.fri_challenges,
         &merkle_caps,
         &proof.opening_proof,
@@ -195,7 +320,53 @@ pub fn add_virtual_stark_proof_with_pis<
     config: &StarkConfig,
     degree_bits: usize,
 ) -> StarkProofWithPublicInputsTarget<D> {
-    let proof = add_virtual_stark_proof::<F, S, D>(builder, stark, config, degree_bits);
+    add_virtual_table_proof_with_pis::<F, S, D>(builder, &stark, config, degree_bits, 0)
+}
+
+pub fn add_virtual_stark_proof<F: RichField + Extendable<D>, S: Stark<F, D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    stark: S,
+    config: &StarkConfig,
+    degree_bits: usize,
+) -> StarkProofTarget<D> {
+    add_virtual_table_proof::<F, S, D>(builder, &stark, config, degree_bits, 0)
+}
+
+/// Adds the targets of a [`MultiProof`](crate::proof::MultiProof), given the degree of each of
+/// its tables.
+pub fn add_virtual_multi_proof<F, C, const D: usize, const N: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    starks: [&dyn StarkTable<F, C, D>; N],
+    cross_table_lookups: &[CrossTableLookup<F>],
+    config: &StarkConfig,
+    degree_bits: [usize; N],
+) -> MultiProofTarget<D, N>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    MultiProofTarget {
+        stark_proofs: core::array::from_fn(|i| {
+            let num_ctl_zs =
+                CrossTableLookup::num_ctl_zs(cross_table_lookups, i, config.num_challenges);
+            starks[i].add_virtual_stark_proof_with_pis(builder, config, degree_bits[i], num_ctl_zs)
+        }),
+    }
+}
+
+/// Adds the targets of the proof of a table with `num_ctl_zs` cross-table lookup polynomials.
+pub(crate) fn add_virtual_table_proof_with_pis<
+    F: RichField + Extendable<D>,
+    S: Stark<F, D>,
+    const D: usize,
+>(
+    builder: &mut CircuitBuilder<F, D>,
+    stark: &S,
+    config: &StarkConfig,
+    degree_bits: usize,
+    num_ctl_zs: usize,
+) -> StarkProofWithPublicInputsTarget<D> {
+    let proof = add_virtual_table_proof::<F, S, D>(builder, stark, config, degree_bits, num_ctl_zs);
     let public_inputs = builder.add_virtual_targets(S::PUBLIC_INPUTS);
     StarkProofWithPublicInputsTarget {
         proof,
@@ -203,54 +374,69 @@ pub fn add_virtual_stark_proof_with_pis<
     }
 }
 
-pub fn add_virtual_stark_proof<F: RichField + Extendable<D>, S: Stark<F, D>, const D: usize>(
+fn add_virtual_table_proof<F: RichField + Extendable<D>, S: Stark<F, D>, const D: usize>(
     builder: &mut CircuitBuilder<F, D>,
-    stark: S,
+    stark: &S,
     config: &StarkConfig,
     degree_bits: usize,
+    num_ctl_zs: usize,
 ) -> StarkProofTarget<D> {
     let fri_params = config.fri_params(degree_bits);
     let cap_height = fri_params.config.cap_height;
+    let num_auxiliary = stark.num_auxiliary_polys(num_ctl_zs, config);
 
+    let salt = salt_size(config.zero_knowledge);
     let num_leaves_per_oracle = once(S::COLUMNS)
-        .chain(
-            stark
-                .uses_permutation_args()
-                .then(|| stark.num_permutation_batches(config)),
-        )
-        .chain(once(stark.quotient_degree_factor() * config.num_challenges))
+        .chain((num_auxiliary > 0).then_some(num_auxiliary))
+        .chain(once(stark.num_quotient_polys(config)))
+        .map(|num_polys| num_polys + salt)
         .collect_vec();
 
-    let permutation_zs_cap = stark
-        .uses_permutation_args()
-        .then(|| builder.add_virtual_cap(cap_height));
+    let auxiliary_polys_cap = (num_auxiliary > 0).then(|| builder.add_virtual_cap(cap_height));
 
     StarkProofTarget {
         trace_cap: builder.add_virtual_cap(cap_height),
-        permutation_zs_cap,
+        auxiliary_polys_cap,
         quotient_polys_cap: builder.add_virtual_cap(cap_height),
-        openings: add_stark_opening_set_target::<F, S, D>(builder, stark, config),
+        openings: add_stark_opening_set_target::<F, S, D>(builder, stark, num_ctl_zs, config),
         opening_proof: builder.add_virtual_fri_proof(&num_leaves_per_oracle, &fri_params),
     }
 }
 
 fn add_stark_opening_set_target<F: RichField + Extendable<D>, S: Stark<F, D>, const D: usize>(
     builder: &mut CircuitBuilder<F, D>,
-    stark: S,
+    stark: &S,
+    num_ctl_zs: usize,
     config: &StarkConfig,
 ) -> StarkOpeningSetTarget<D> {
-    let num_challenges = config.num_challenges;
+    let num_auxiliary = stark.num_auxiliary_polys(num_ctl_zs, config);
     StarkOpeningSetTarget {
         local_values: builder.add_virtual_extension_targets(S::COLUMNS),
         next_values: builder.add_virtual_extension_targets(S::COLUMNS),
-        permutation_zs: stark
-            .uses_permutation_args()
-            .then(|| builder.add_virtual_extension_targets(stark.num_permutation_batches(config))),
-        permutation_zs_next: stark
-            .uses_permutation_args()
-            .then(|| builder.add_virtual_extension_targets(stark.num_permutation_batches(config))),
-        quotient_polys: builder
-            .add_virtual_extension_targets(stark.quotient_degree_factor() * num_challenges),
+        auxiliary_polys: (num_auxiliary > 0)
+            .then(|| builder.add_virtual_extension_targets(num_auxiliary)),
+        auxiliary_polys_next: (num_auxiliary > 0)
+            .then(|| builder.add_virtual_extension_targets(num_auxiliary)),
+        ctl_zs_first: (num_ctl_zs > 0).then(|| builder.add_virtual_targets(num_ctl_zs)),
+        quotient_polys: builder.add_virtual_extension_targets(stark.num_quotient_polys(config)),
+    }
+}
+
+pub fn set_multi_proof_target<F, C: GenericConfig<D, F = F>, W, const D: usize, const N: usize>(
+    witness: &mut W,
+    multi_proof_target: &MultiProofTarget<D, N>,
+    multi_proof: &MultiProof<F, C, D, N>,
+) where
+    F: RichField + Extendable<D>,
+    C::Hasher: AlgebraicHasher<F>,
+    W: Witness<F>,
+{
+    for (proof_target, proof) in multi_proof_target
+        .stark_proofs
+        .iter()
+        .zip_eq(&multi_proof.stark_proofs)
+    {
+        set_stark_proof_with_pis_target(witness, proof_target, proof);
     }
 }
 
@@ -292,38 +478,53 @@ pub fn set_stark_proof_target<F, C: GenericConfig<D, F = F>, W, const D: usize>(
     witness.set_cap_target(&proof_target.trace_cap, &proof.trace_cap);
     witness.set_cap_target(&proof_target.quotient_polys_cap, &proof.quotient_polys_cap);
 
-    witness.set_fri_openings(
-        &proof_target.openings.to_fri_openings(),
-        &proof.openings.to_fri_openings(),
-    );
-
-    if let (Some(permutation_zs_cap_target), Some(permutation_zs_cap)) =
-        (&proof_target.permutation_zs_cap, &proof.permutation_zs_cap)
+    let openings_target = &proof_target.openings;
+    let openings = &proof.openings;
+    witness.set_extension_targets(&openings_target.local_values, &openings.local_values);
+    witness.set_extension_targets(&openings_target.next_values, &openings.next_values);
+    witness.set_extension_targets(&openings_target.quotient_polys, &openings.quotient_polys);
+    if let (Some(auxiliary_polys_target), Some(auxiliary_polys)) =
+        (&openings_target.auxiliary_polys, &openings.auxiliary_polys)
     {
-        witness.set_cap_target(permutation_zs_cap_target, permutation_zs_cap);
+        witness.set_extension_targets(auxiliary_polys_target, auxiliary_polys);
+    }
+    if let (Some(auxiliary_polys_next_target), Some(auxiliary_polys_next)) = (
+        &openings_target.auxiliary_polys_next,
+        &openings.auxiliary_polys_next,
+    ) {
+        witness.set_extension_targets(auxiliary_polys_next_target, auxiliary_polys_next);
+    }
+    if let (Some(ctl_zs_first_target), Some(ctl_zs_first)) =
+        (&openings_target.ctl_zs_first, &openings.ctl_zs_first)
+    {
+        for (&t, &x) in ctl_zs_first_target.iter().zip_eq(ctl_zs_first) {
+            witness.set_target(t, x);
+        }
+    }
+
+    if let (Some(auxiliary_polys_cap_target), Some(auxiliary_polys_cap)) = (
+        &proof_target.auxiliary_polys_cap,
+        &proof.auxiliary_polys_cap,
+    ) {
+        witness.set_cap_target(auxiliary_polys_cap_target, auxiliary_polys_cap);
     }
 
     set_fri_proof_target(witness, &proof_target.opening_proof, &proof.opening_proof);
 }
 
-/// Utility function to check that all permutation data wrapped in `Option`s are `Some` iff
-/// the Stark uses a permutation argument.
-fn check_permutation_options<F: RichField + Extendable<D>, S: Stark<F, D>, const D: usize>(
+/// Utility function to check that the permutation and lookup challenges are `Some` iff the Stark
+/// uses the corresponding argument.
+fn check_challenges_options<F: RichField + Extendable<D>, S: Stark<F, D>, const D: usize>(
     stark: &S,
-    proof_with_pis: &StarkProofWithPublicInputsTarget<D>,
     challenges: &StarkProofChallengesTarget<D>,
 ) -> Result<()> {
-    let options_is_some = [
-        proof_with_pis.proof.permutation_zs_cap.is_some(),
-        proof_with_pis.proof.openings.permutation_zs.is_some(),
-        proof_with_pis.proof.openings.permutation_zs_next.is_some(),
-        challenges.permutation_challenge_sets.is_some(),
-    ];
     ensure!(
-        options_is_some
-            .into_iter()
-            .all(|b| b == stark.uses_permutation_args()),
+        challenges.permutation_challenge_sets.is_some() == stark.uses_permutation_args(),
         "Permutation data doesn't match with Stark configuration."
     );
+    ensure!(
+        challenges.lookup_challenges.is_some() == stark.uses_lookups(),
+        "Lookup data doesn't match with Stark configuration."
+    );
     Ok(())
 }
@@ -1,21 +1,32 @@
 use alloc::vec;
 use alloc::vec::Vec;
 
+use anyhow::Result;
 use plonky2::field::extension::{Extendable, FieldExtension};
 use plonky2::field::packed::PackedField;
+use plonky2::field::polynomial::PolynomialValues;
+use plonky2::field::types::Field;
+use plonky2::fri::oracle::PolynomialBatch;
 use plonky2::fri::structure::{
     FriBatchInfo, FriBatchInfoTarget, FriInstanceInfo, FriInstanceInfoTarget, FriOracleInfo,
     FriPolynomialInfo,
 };
 use plonky2::hash::hash_types::RichField;
+use plonky2::iop::challenger::{Challenger, RecursiveChallenger};
 use plonky2::iop::ext_target::ExtensionTarget;
 use plonky2::plonk::circuit_builder::CircuitBuilder;
+use plonky2::plonk::config::{AlgebraicHasher, GenericConfig};
 use plonky2::util::ceil_div_usize;
+use plonky2::util::timing::TimingTree;
 
 use crate::config::StarkConfig;
 use crate::constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer};
+use crate::cross_table_lookup::{CtlCheckVars, CtlCheckVarsTarget, CtlData};
 use crate::evaluation_frame::StarkEvaluationFrame;
+use crate::lookup::Lookup;
 use crate::permutation::PermutationPair;
+use crate::proof::{StarkProofWithPublicInputs, StarkProofWithPublicInputsTarget};
+use crate::{prover, recursive_verifier, verifier};
 
 /// Represents a STARK system.
 pub trait Stark<F: RichField + Extendable<D>, const D: usize>: Sync {
@@ -83,8 +94,27 @@ pub trait Stark<F: RichField + Extendable<D>, const D: usize>: Sync {
         1.max(self.constraint_degree() - 1)
     }
 
+    /// The number of chunks of degree less than the trace length that each quotient polynomial
+    /// is split into. In zero-knowledge mode, the randomized trace polynomials have twice the
+    /// degree, which raises the degree of the quotient. There are then at least two chunks, as
+    /// the chunks are blinded by moving random terms from one chunk to the next.
+    fn num_quotient_chunks(&self, config: &StarkConfig) -> usize {
+        if config.zero_knowledge {
+            2.max(2 * self.constraint_degree() - 1)
+        } else {
+            self.quotient_degree_factor()
+        }
+    }
+
     fn num_quotient_polys(&self, config: &StarkConfig) -> usize {
-        self.quotient_degree_factor() * config.num_challenges
+        self.num_quotient_chunks(config) * config.num_challenges
+    }
+
+    /// The number of auxiliary polynomials committed to alongside the trace: the permutation `Z`
+    /// polynomials, the lookup helper columns and the `num_ctl_zs` cross-table lookup `Z`
+    /// polynomials, in that order.
+    fn num_auxiliary_polys(&self, num_ctl_zs: usize, config: &StarkConfig) -> usize {
+        self.num_permutation_batches(config) + self.num_lookup_helper_columns(config) + num_ctl_zs
     }
 
     /// Computes the FRI instance used to prove this Stark.
@@ -92,6 +122,7 @@ pub trait Stark<F: RichField + Extendable<D>, const D: usize>: Sync {
         &self,
         zeta: F::Extension,
         g: F,
+        num_ctl_zs: usize,
         config: &StarkConfig,
     ) -> FriInstanceInfo<F, D> {
         let mut oracles = vec![];
@@ -99,42 +130,52 @@ pub trait Stark<F: RichField + Extendable<D>, const D: usize>: Sync {
         let trace_info = FriPolynomialInfo::from_range(oracles.len(), 0..Self::COLUMNS);
         oracles.push(FriOracleInfo {
             num_polys: Self::COLUMNS,
-            blinding: false,
+            blinding: config.zero_knowledge,
         });
 
-        let permutation_zs_info = if self.uses_permutation_args() {
-            let num_z_polys = self.num_permutation_batches(config);
-            let polys = FriPolynomialInfo::from_range(oracles.len(), 0..num_z_polys);
+        let num_auxiliary_polys = self.num_auxiliary_polys(num_ctl_zs, config);
+        let (auxiliary_polys_info, ctl_zs_info) = if num_auxiliary_polys > 0 {
+            let polys = FriPolynomialInfo::from_range(oracles.len(), 0..num_auxiliary_polys);
+            let ctl_zs = FriPolynomialInfo::from_range(
+                oracles.len(),
+                num_auxiliary_polys - num_ctl_zs..num_auxiliary_polys,
+            );
             oracles.push(FriOracleInfo {
-                num_polys: num_z_polys,
-                blinding: false,
+                num_polys: num_auxiliary_polys,
+                blinding: config.zero_knowledge,
             });
-            polys
+            (polys, ctl_zs)
         } else {
-            vec![]
+            (vec![], vec![])
         };
 
-        let num_quotient_polys = self.quotient_degree_factor() * config.num_challenges;
+        let num_quotient_polys = self.num_quotient_polys(config);
         let quotient_info = FriPolynomialInfo::from_range(oracles.len(), 0..num_quotient_polys);
         oracles.push(FriOracleInfo {
             num_polys: num_quotient_polys,
-            blinding: false,
+            blinding: config.zero_knowledge,
         });
 
         let zeta_batch = FriBatchInfo {
             point: zeta,
             polynomials: [
                 trace_info.clone(),
-                permutation_zs_info.clone(),
+                auxiliary_polys_info.clone(),
                 quotient_info,
             ]
             .concat(),
         };
         let zeta_next_batch = FriBatchInfo {
             point: zeta.scalar_mul(g),
-            polynomials: [trace_info, permutation_zs_info].concat(),
+            polynomials: [trace_info, auxiliary_polys_info].concat(),
         };
-        let batches = vec![zeta_batch, zeta_next_batch];
+        let mut batches = vec![zeta_batch, zeta_next_batch];
+        if num_ctl_zs > 0 {
+            batches.push(FriBatchInfo {
+                point: F::Extension::ONE,
+                polynomials: ctl_zs_info,
+            });
+        }
 
         FriInstanceInfo { oracles, batches }
     }
@@ -145,6 +186,7 @@ pub trait Stark<F: RichField + Extendable<D>, const D: usize>: Sync {
         builder: &mut CircuitBuilder<F, D>,
         zeta: ExtensionTarget<D>,
         g: F,
+        num_ctl_zs: usize,
         config: &StarkConfig,
     ) -> FriInstanceInfoTarget<D> {
         let mut oracles = vec![];
@@ -152,33 +194,37 @@ pub trait Stark<F: RichField + Extendable<D>, const D: usize>: Sync {
         let trace_info = FriPolynomialInfo::from_range(oracles.len(), 0..Self::COLUMNS);
         oracles.push(FriOracleInfo {
             num_polys: Self::COLUMNS,
-            blinding: false,
+            blinding: config.zero_knowledge,
         });
 
-        let permutation_zs_info = if self.uses_permutation_args() {
-            let num_z_polys = self.num_permutation_batches(config);
-            let polys = FriPolynomialInfo::from_range(oracles.len(), 0..num_z_polys);
+        let num_auxiliary_polys = self.num_auxiliary_polys(num_ctl_zs, config);
+        let (auxiliary_polys_info, ctl_zs_info) = if num_auxiliary_polys > 0 {
+            let polys = FriPolynomialInfo::from_range(oracles.len(), 0..num_auxiliary_polys);
+            let ctl_zs = FriPolynomialInfo::from_range(
+                oracles.len(),
+                num_auxiliary_polys - num_ctl_zs..num_auxiliary_polys,
+            );
             oracles.push(FriOracleInfo {
-                num_polys: num_z_polys,
-                blinding: false,
+                num_polys: num_auxiliary_polys,
+                blinding: config.zero_knowledge,
             });
-            polys
+            (polys, ctl_zs)
         } else {
-            vec![]
+            (vec![], vec![])
         };
 
-        let num_quotient_polys = self.quotient_degree_factor() * config.num_challenges;
+        let num_quotient_polys = self.num_quotient_polys(config);
         let quotient_info = FriPolynomialInfo::from_range(oracles.len(), 0..num_quotient_polys);
         oracles.push(FriOracleInfo {
             num_polys: num_quotient_polys,
-            blinding: false,
+            blinding: config.zero_knowledge,
         });
 
         let zeta_batch = FriBatchInfoTarget {
             point: zeta,
             polynomials: [
                 trace_info.clone(),
-                permutation_zs_info.clone(),
+                auxiliary_polys_info.clone(),
                 quotient_info,
             ]
             .concat(),
@@ -186,9 +232,15 @@ pub trait Stark<F: RichField + Extendable<D>, const D: usize>: Sync {
         let zeta_next = builder.mul_const_extension(g, zeta);
         let zeta_next_batch = FriBatchInfoTarget {
             point: zeta_next,
-            polynomials: [trace_info, permutation_zs_info].concat(),
+            polynomials: [trace_info, auxiliary_polys_info].concat(),
         };
-        let batches = vec![zeta_batch, zeta_next_batch];
+        let mut batches = vec![zeta_batch, zeta_next_batch];
+        if num_ctl_zs > 0 {
+            batches.push(FriBatchInfoTarget {
+                point: builder.one_extension(),
+                polynomials: ctl_zs_info,
+            });
+        }
 
         FriInstanceInfoTarget { oracles, batches }
     }
@@ -222,4 +274,151 @@ pub trait Stark<F: RichField + Extendable<D>, const D: usize>: Sync {
             self.permutation_batch_size(),
         )
     }
+
+    /// The logUp lookups of this STARK. A lookup argument will be used for each of them. Empty by
+    /// default.
+    fn lookups(&self) -> Vec<Lookup> {
+        vec![]
+    }
+
+    fn uses_lookups(&self) -> bool {
+        !self.lookups().is_empty()
+    }
+
+    /// The number of lookup helper columns, over all lookups and challenges.
+    fn num_lookup_helper_columns(&self, config: &StarkConfig) -> usize {
+        self.lookups()
+            .iter()
+            .map(|lookup| lookup.num_helper_columns(self.constraint_degree()))
+            .sum::<usize>()
+            * config.num_challenges
+    }
+}
+
+/// A dyn-compatible view of a [`Stark`] as one table of a multi-table system, so that STARKs of
+/// different types can be proven together with [`prove_all`](crate::prover::prove_all).
+pub trait StarkTable<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>:
+    Sync
+{
+    /// Proves this table, whose trace has already been committed to and observed by `challenger`.
+    fn prove_with_commitment(
+        &self,
+        config: &StarkConfig,
+        trace_poly_values: &[PolynomialValues<F>],
+        trace_commitment: &PolynomialBatch<F, C, D>,
+        ctl_data: &CtlData<F>,
+        public_inputs: &[F],
+        challenger: &mut Challenger<F, C::Hasher>,
+        timing: &mut TimingTree,
+    ) -> Result<StarkProofWithPublicInputs<F, C, D>>;
+
+    /// Verifies the proof of this table, given its cross-table lookup data.
+    fn verify_with_challenger(
+        &self,
+        proof_with_pis: &StarkProofWithPublicInputs<F, C, D>,
+        ctl_vars: &[CtlCheckVars<F, F::Extension, F::Extension, D>],
+        challenger: &mut Challenger<F, C::Hasher>,
+        config: &StarkConfig,
+    ) -> Result<()>;
+
+    /// Adds the targets of a proof of this table with `num_ctl_zs` cross-table lookup polynomials.
+    fn add_virtual_stark_proof_with_pis(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        config: &StarkConfig,
+        degree_bits: usize,
+        num_ctl_zs: usize,
+    ) -> StarkProofWithPublicInputsTarget<D>;
+
+    /// Circuit version of `verify_with_challenger`.
+    fn verify_circuit_with_challenger(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        proof_with_pis: &StarkProofWithPublicInputsTarget<D>,
+        ctl_vars: &[CtlCheckVarsTarget<F, D>],
+        challenger: &mut RecursiveChallenger<F, C::Hasher, D>,
+        config: &StarkConfig,
+    ) where
+        C::Hasher: AlgebraicHasher<F>;
+}
+
+impl<F, C, S, const D: usize> StarkTable<F, C, D> for S
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    S: Stark<F, D>,
+{
+    fn prove_with_commitment(
+        &self,
+        config: &StarkConfig,
+        trace_poly_values: &[PolynomialValues<F>],
+        trace_commitment: &PolynomialBatch<F, C, D>,
+        ctl_data: &CtlData<F>,
+        public_inputs: &[F],
+        challenger: &mut Challenger<F, C::Hasher>,
+        timing: &mut TimingTree,
+    ) -> Result<StarkProofWithPublicInputs<F, C, D>> {
+        prover::prove_with_commitment(
+            self,
+            config,
+            trace_poly_values,
+            trace_commitment,
+            Some(ctl_data),
+            public_inputs,
+            challenger,
+            timing,
+        )
+    }
+
+    fn verify_with_challenger(
+        &self,
+        proof_with_pis: &StarkProofWithPublicInputs<F, C, D>,
+        ctl_vars: &[CtlCheckVars<F, F::Extension, F::Extension, D>],
+        challenger: &mut Challenger<F, C::Hasher>,
+        config: &StarkConfig,
+    ) -> Result<()> {
+        verifier::verify_stark_proof_with_challenger(
+            self,
+            proof_with_pis,
+            ctl_vars,
+            challenger,
+            config,
+        )
+    }
+
+    fn add_virtual_stark_proof_with_pis(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        config: &StarkConfig,
+        degree_bits: usize,
+        num_ctl_zs: usize,
+    ) -> StarkProofWithPublicInputsTarget<D> {
+        recursive_verifier::add_virtual_table_proof_with_pis::<F, S, D>(
+            builder,
+            self,
+            config,
+            degree_bits,
+            num_ctl_zs,
+        )
+    }
+
+    fn verify_circuit_with_challenger(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        proof_with_pis: &StarkProofWithPublicInputsTarget<D>,
+        ctl_vars: &[CtlCheckVarsTarget<F, D>],
+        challenger: &mut RecursiveChallenger<F, C::Hasher, D>,
+        config: &StarkConfig,
+    ) where
+        C::Hasher: AlgebraicHasher<F>,
+    {
+        recursive_verifier::verify_stark_proof_with_challenger_circuit::<F, C, S, D>(
+            builder,
+            self,
+            proof_with_pis,
+            ctl_vars,
+            challenger,
+            config,
+        )
+    }
 }
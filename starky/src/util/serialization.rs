@@ -0,0 +1,313 @@
+//! Byte serialization of STARK proofs, built on top of plonky2's `Read` and `Write` traits.
+//!
+//! As with Plonk proofs, the encoding carries no lengths which can be inferred from the shape of
+//! the STARK, so reading a proof requires the `Stark` and `StarkConfig` it was generated with. The
+//! only exception is the degree of the trace, which varies from proof to proof and is written
+//! first.
+//!
+//! Only proofs of a single STARK, as generated by [`prove`](crate::prover::prove), are supported:
+//! the cross-table lookup openings of the tables of a multi-table proof are not encoded.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use plonky2::field::extension::Extendable;
+use plonky2::fri::FriParams;
+use plonky2::hash::hash_types::RichField;
+use plonky2::plonk::config::GenericConfig;
+use plonky2::plonk::plonk_common::salt_size;
+use plonky2::util::serialization::{IoError, IoResult, Read, Write};
+
+use crate::config::StarkConfig;
+use crate::proof::{
+    CompressedStarkProof, CompressedStarkProofWithPublicInputs, StarkOpeningSet, StarkProof,
+    StarkProofWithPublicInputs,
+};
+use crate::stark::Stark;
+
+/// The number of field elements in the leaves of each initial FRI tree of a proof for `stark`.
+fn fri_initial_leaf_sizes<F: RichField + Extendable<D>, S: Stark<F, D>, const D: usize>(
+    stark: &S,
+    config: &StarkConfig,
+) -> Vec<usize> {
+    let salt = salt_size(config.zero_knowledge);
+    let mut leaf_sizes = vec![S::COLUMNS + salt];
+    let num_auxiliary = stark.num_auxiliary_polys(0, config);
+    if num_auxiliary > 0 {
+        leaf_sizes.push(num_auxiliary + salt);
+    }
+    leaf_sizes.push(stark.num_quotient_polys(config) + salt);
+    leaf_sizes
+}
+
+/// Reads the degree of the trace of a proof generated with `config` from `src`, and returns it
+/// together with the matching FRI parameters.
+///
+/// The degree comes from untrusted bytes, so it is rejected unless the LDE of the trace fits in
+/// the two-adic subgroup of `F` and the FRI reductions fit in the trace degree.
+fn read_degree_bits<F: RichField, R: Read + ?Sized>(
+    src: &mut R,
+    config: &StarkConfig,
+) -> IoResult<(usize, FriParams)> {
+    let degree_bits = src.read_usize()?;
+    let lde_bits = degree_bits
+        .checked_add(config.fri_config.rate_bits)
+        .ok_or(IoError)?;
+    if lde_bits > F::TWO_ADICITY || lde_bits < config.fri_config.cap_height {
+        return Err(IoError);
+    }
+    let fri_params = config.fri_params(degree_bits);
+    if fri_params.total_arities() > degree_bits {
+        return Err(IoError);
+    }
+    Ok((degree_bits, fri_params))
+}
+
+/// Reads STARK proofs, on top of plonky2's `Read`.
+pub trait StarkRead: Read {
+    /// Reads a value of type [`StarkOpeningSet`] from `self` with the given `stark` and `config`.
+    #[inline]
+    fn read_stark_opening_set<F, S, const D: usize>(
+        &mut self,
+        stark: &S,
+        config: &StarkConfig,
+    ) -> IoResult<StarkOpeningSet<F, D>>
+    where
+        F: RichField + Extendable<D>,
+        S: Stark<F, D>,
+    {
+        let local_values = self.read_field_ext_vec::<F, D>(S::COLUMNS)?;
+        let next_values = self.read_field_ext_vec::<F, D>(S::COLUMNS)?;
+        let num_auxiliary = stark.num_auxiliary_polys(0, config);
+        let (auxiliary_polys, auxiliary_polys_next) = if num_auxiliary > 0 {
+            (
+                Some(self.read_field_ext_vec::<F, D>(num_auxiliary)?),
+                Some(self.read_field_ext_vec::<F, D>(num_auxiliary)?),
+            )
+        } else {
+            (None, None)
+        };
+        let quotient_polys = self.read_field_ext_vec::<F, D>(stark.num_quotient_polys(config))?;
+        Ok(StarkOpeningSet {
+            local_values,
+            next_values,
+            auxiliary_polys,
+            auxiliary_polys_next,
+            ctl_zs_first: None,
+            quotient_polys,
+        })
+    }
+
+    /// Reads a value of type [`StarkProof`] from `self` with the given `stark` and `config`.
+    #[inline]
+    fn read_stark_proof<F, C, S, const D: usize>(
+        &mut self,
+        stark: &S,
+        config: &StarkConfig,
+    ) -> IoResult<StarkProof<F, C, D>>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+        S: Stark<F, D>,
+    {
+        let (_, fri_params) = read_degree_bits::<F, _>(self, config)?;
+        let cap_height = config.fri_config.cap_height;
+        let trace_cap = self.read_merkle_cap(cap_height)?;
+        let auxiliary_polys_cap = if stark.num_auxiliary_polys(0, config) > 0 {
+            Some(self.read_merkle_cap(cap_height)?)
+        } else {
+            None
+        };
+        let quotient_polys_cap = self.read_merkle_cap(cap_height)?;
+        let openings = self.read_stark_opening_set(stark, config)?;
+        let opening_proof = self.read_fri_proof_with_params::<F, C, D>(
+            &fri_params,
+            &fri_initial_leaf_sizes(stark, config),
+        )?;
+        Ok(StarkProof {
+            trace_cap,
+            auxiliary_polys_cap,
+            quotient_polys_cap,
+            openings,
+            opening_proof,
+        })
+    }
+
+    /// Reads a value of type [`StarkProofWithPublicInputs`] from `self` with the given `stark`
+    /// and `config`.
+    #[inline]
+    fn read_stark_proof_with_public_inputs<F, C, S, const D: usize>(
+        &mut self,
+        stark: &S,
+        config: &StarkConfig,
+    ) -> IoResult<StarkProofWithPublicInputs<F, C, D>>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+        S: Stark<F, D>,
+    {
+        let proof = self.read_stark_proof(stark, config)?;
+        let public_inputs = self.read_field_vec(S::PUBLIC_INPUTS)?;
+        Ok(StarkProofWithPublicInputs {
+            proof,
+            public_inputs,
+        })
+    }
+
+    /// Reads a value of type [`CompressedStarkProof`] from `self` with the given `stark` and
+    /// `config`.
+    #[inline]
+    fn read_compressed_stark_proof<F, C, S, const D: usize>(
+        &mut self,
+        stark: &S,
+        config: &StarkConfig,
+    ) -> IoResult<CompressedStarkProof<F, C, D>>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+        S: Stark<F, D>,
+    {
+        let (degree_bits, fri_params) = read_degree_bits::<F, _>(self, config)?;
+        let cap_height = config.fri_config.cap_height;
+        let trace_cap = self.read_merkle_cap(cap_height)?;
+        let auxiliary_polys_cap = if stark.num_auxiliary_polys(0, config) > 0 {
+            Some(self.read_merkle_cap(cap_height)?)
+        } else {
+            None
+        };
+        let quotient_polys_cap = self.read_merkle_cap(cap_height)?;
+        let openings = self.read_stark_opening_set(stark, config)?;
+        let opening_proof = self.read_compressed_fri_proof_with_params::<F, C, D>(
+            &fri_params,
+            &fri_initial_leaf_sizes(stark, config),
+        )?;
+        Ok(CompressedStarkProof {
+            degree_bits,
+            trace_cap,
+            auxiliary_polys_cap,
+            quotient_polys_cap,
+            openings,
+            opening_proof,
+        })
+    }
+
+    /// Reads a value of type [`CompressedStarkProofWithPublicInputs`] from `self` with the given
+    /// `stark` and `config`.
+    #[inline]
+    fn read_compressed_stark_proof_with_public_inputs<F, C, S, const D: usize>(
+        &mut self,
+        stark: &S,
+        config: &StarkConfig,
+    ) -> IoResult<CompressedStarkProofWithPublicInputs<F, C, D>>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+        S: Stark<F, D>,
+    {
+        let proof = self.read_compressed_stark_proof(stark, config)?;
+        let public_inputs = self.read_field_vec(S::PUBLIC_INPUTS)?;
+        Ok(CompressedStarkProofWithPublicInputs {
+            proof,
+            public_inputs,
+        })
+    }
+}
+
+impl<R: Read + ?Sized> StarkRead for R {}
+
+/// Writes STARK proofs, on top of plonky2's `Write`.
+pub trait StarkWrite: Write {
+    /// Writes a value `os` of type [`StarkOpeningSet`] to `self`.
+    #[inline]
+    fn write_stark_opening_set<F, const D: usize>(
+        &mut self,
+        os: &StarkOpeningSet<F, D>,
+    ) -> IoResult<()>
+    where
+        F: RichField + Extendable<D>,
+    {
+        self.write_field_ext_vec::<F, D>(&os.local_values)?;
+        self.write_field_ext_vec::<F, D>(&os.next_values)?;
+        if let Some(auxiliary_polys) = &os.auxiliary_polys {
+            self.write_field_ext_vec::<F, D>(auxiliary_polys)?;
+        }
+        if let Some(auxiliary_polys_next) = &os.auxiliary_polys_next {
+            self.write_field_ext_vec::<F, D>(auxiliary_polys_next)?;
+        }
+        self.write_field_ext_vec::<F, D>(&os.quotient_polys)
+    }
+
+    /// Writes a value `proof` of type [`StarkProof`], generated with the given `config`, to
+    /// `self`.
+    #[inline]
+    fn write_stark_proof<F, C, const D: usize>(
+        &mut self,
+        proof: &StarkProof<F, C, D>,
+        config: &StarkConfig,
+    ) -> IoResult<()>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+    {
+        self.write_usize(proof.recover_degree_bits(config))?;
+        self.write_merkle_cap(&proof.trace_cap)?;
+        if let Some(auxiliary_polys_cap) = &proof.auxiliary_polys_cap {
+            self.write_merkle_cap(auxiliary_polys_cap)?;
+        }
+        self.write_merkle_cap(&proof.quotient_polys_cap)?;
+        self.write_stark_opening_set(&proof.openings)?;
+        self.write_fri_proof::<F, C, D>(&proof.opening_proof)
+    }
+
+    /// Writes a value `proof_with_pis` of type [`StarkProofWithPublicInputs`], generated with the
+    /// given `config`, to `self`.
+    #[inline]
+    fn write_stark_proof_with_public_inputs<F, C, const D: usize>(
+        &mut self,
+        proof_with_pis: &StarkProofWithPublicInputs<F, C, D>,
+        config: &StarkConfig,
+    ) -> IoResult<()>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+    {
+        self.write_stark_proof(&proof_with_pis.proof, config)?;
+        self.write_field_vec(&proof_with_pis.public_inputs)
+    }
+
+    /// Writes a value `proof` of type [`CompressedStarkProof`] to `self`.
+    #[inline]
+    fn write_compressed_stark_proof<F, C, const D: usize>(
+        &mut self,
+        proof: &CompressedStarkProof<F, C, D>,
+    ) -> IoResult<()>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+    {
+        self.write_usize(proof.degree_bits)?;
+        self.write_merkle_cap(&proof.trace_cap)?;
+        if let Some(auxiliary_polys_cap) = &proof.auxiliary_polys_cap {
+            self.write_merkle_cap(auxiliary_polys_cap)?;
+        }
+        self.write_merkle_cap(&proof.quotient_polys_cap)?;
+        self.write_stark_opening_set(&proof.openings)?;
+        self.write_compressed_fri_proof::<F, C, D>(&proof.opening_proof)
+    }
+
+    /// Writes a value `proof_with_pis` of type [`CompressedStarkProofWithPublicInputs`] to `self`.
+    #[inline]
+    fn write_compressed_stark_proof_with_public_inputs<F, C, const D: usize>(
+        &mut self,
+        proof_with_pis: &CompressedStarkProofWithPublicInputs<F, C, D>,
+    ) -> IoResult<()>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+    {
+        self.write_compressed_stark_proof(&proof_with_pis.proof)?;
+        self.write_field_vec(&proof_with_pis.public_inputs)
+    }
+}
+
+impl<W: Write + ?Sized> StarkWrite for W {}
@@ -5,17 +5,28 @@ use plonky2::plonk::circuit_builder::CircuitBuilder;
 
 use crate::config::StarkConfig;
 use crate::constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer};
+use crate::cross_table_lookup::{
+    eval_cross_table_lookup_checks, eval_cross_table_lookup_checks_circuit, CtlCheckVars,
+    CtlCheckVarsTarget,
+};
+use crate::lookup::{
+    eval_ext_lookups_circuit, eval_packed_lookups_generic, LookupCheckVars, LookupCheckVarsTarget,
+};
 use crate::permutation::{
     eval_permutation_checks, eval_permutation_checks_circuit, PermutationCheckDataTarget,
     PermutationCheckVars,
 };
 use crate::stark::Stark;
 
+/// Evaluates all constraint, permutation, lookup and cross-table lookup polynomials
+/// of the current STARK at the local and next values.
 pub(crate) fn eval_vanishing_poly<F, FE, P, S, const D: usize, const D2: usize>(
     stark: &S,
     config: &StarkConfig,
     vars: &S::EvaluationFrame<FE, P, D2>,
     permutation_data: Option<PermutationCheckVars<F, FE, P, D2>>,
+    lookup_vars: Option<LookupCheckVars<F, FE, P, D2>>,
+    ctl_vars: Option<&[CtlCheckVars<F, FE, P, D2>]>,
     consumer: &mut ConstraintConsumer<P>,
 ) where
     F: RichField + Extendable<D>,
@@ -33,14 +44,29 @@ pub(crate) fn eval_vanishing_poly<F, FE, P, S, const D: usize, const D2: usize>(
             consumer,
         );
     }
+    if let Some(lookup_vars) = lookup_vars {
+        eval_packed_lookups_generic::<F, FE, P, S, D, D2>(
+            stark,
+            &stark.lookups(),
+            vars,
+            lookup_vars,
+            consumer,
+        );
+    }
+    if let Some(ctl_vars) = ctl_vars {
+        eval_cross_table_lookup_checks::<F, FE, P, S, D, D2>(vars, ctl_vars, consumer);
+    }
 }
 
+/// Circuit version of `eval_vanishing_poly`.
 pub(crate) fn eval_vanishing_poly_circuit<F, S, const D: usize>(
     builder: &mut CircuitBuilder<F, D>,
     stark: &S,
     config: &StarkConfig,
     vars: &S::EvaluationFrameTarget,
     permutation_data: Option<PermutationCheckDataTarget<D>>,
+    lookup_vars: Option<LookupCheckVarsTarget<D>>,
+    ctl_vars: Option<&[CtlCheckVarsTarget<F, D>]>,
     consumer: &mut RecursiveConstraintConsumer<F, D>,
 ) where
     F: RichField + Extendable<D>,
@@ -57,4 +83,10 @@ pub(crate) fn eval_vanishing_poly_circuit<F, S, const D: usize>(
             consumer,
         );
     }
+    if let Some(lookup_vars) = lookup_vars {
+        eval_ext_lookups_circuit::<F, S, D>(builder, stark, vars, lookup_vars, consumer);
+    }
+    if let Some(ctl_vars) = ctl_vars {
+        eval_cross_table_lookup_checks_circuit::<S, F, D>(builder, vars, ctl_vars, consumer);
+    }
 }
@@ -7,15 +7,22 @@ use plonky2::field::extension::{Extendable, FieldExtension};
 use plonky2::field::types::Field;
 use plonky2::fri::verifier::verify_fri_proof;
 use plonky2::hash::hash_types::RichField;
+use plonky2::iop::challenger::Challenger;
 use plonky2::plonk::config::GenericConfig;
 use plonky2::plonk::plonk_common::reduce_with_powers;
 
 use crate::config::StarkConfig;
 use crate::constraint_consumer::ConstraintConsumer;
+use crate::cross_table_lookup::{
+    get_grand_product_challenge_set, verify_cross_table_lookups, CrossTableLookup, CtlCheckVars,
+};
 use crate::evaluation_frame::StarkEvaluationFrame;
+use crate::lookup::LookupCheckVars;
 use crate::permutation::PermutationCheckVars;
-use crate::proof::{StarkOpeningSet, StarkProof, StarkProofChallenges, StarkProofWithPublicInputs};
-use crate::stark::Stark;
+use crate::proof::{
+    MultiProof, StarkOpeningSet, StarkProof, StarkProofChallenges, StarkProofWithPublicInputs,
+};
+use crate::stark::{Stark, StarkTable};
 use crate::vanishing_poly::eval_vanishing_poly;
 
 pub fn verify_stark_proof<
@@ -31,7 +38,94 @@ pub fn verify_stark_proof<
     ensure!(proof_with_pis.public_inputs.len() == S::PUBLIC_INPUTS);
     let degree_bits = proof_with_pis.proof.recover_degree_bits(config);
     let challenges = proof_with_pis.get_challenges(&stark, config, degree_bits);
-    verify_stark_proof_with_challenges(stark, proof_with_pis, challenges, degree_bits, config)
+    verify_stark_proof_with_challenges(&stark, &proof_with_pis, &challenges, None, config)
+}
+
+/// Verifies a proof generated by [`prove_all`](crate::prover::prove_all), including the
+/// cross-table lookups between its tables.
+pub fn verify_all<F, C, const D: usize, const N: usize>(
+    starks: [&dyn StarkTable<F, C, D>; N],
+    cross_table_lookups: &[CrossTableLookup<F>],
+    multi_proof: &MultiProof<F, C, D, N>,
+    config: &StarkConfig,
+) -> Result<()>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    let mut challenger = Challenger::<F, C::Hasher>::new();
+    for proof in &multi_proof.stark_proofs {
+        challenger.observe_cap(&proof.proof.trace_cap);
+    }
+    let ctl_challenges = get_grand_product_challenge_set(&mut challenger, config.num_challenges);
+    challenger.compact();
+
+    // Check the number of cross-table lookup openings of each table before extracting them.
+    for (i, proof) in multi_proof.stark_proofs.iter().enumerate() {
+        let openings = &proof.proof.openings;
+        let num_ctl_zs =
+            CrossTableLookup::num_ctl_zs(cross_table_lookups, i, config.num_challenges);
+        let num_auxiliary = openings.auxiliary_polys.as_ref().map_or(0, Vec::len);
+        ensure!(
+            openings.ctl_zs_first.as_ref().map_or(0, Vec::len) == num_ctl_zs,
+            "Wrong number of cross-table lookup openings for table {}.",
+            i
+        );
+        ensure!(
+            num_auxiliary >= num_ctl_zs
+                && openings.auxiliary_polys_next.as_ref().map_or(0, Vec::len) == num_auxiliary,
+            "Wrong number of auxiliary polynomial openings for table {}.",
+            i
+        );
+    }
+
+    let ctl_vars_per_table = CtlCheckVars::from_proofs(
+        &multi_proof.stark_proofs,
+        cross_table_lookups,
+        &ctl_challenges,
+    );
+    for ((stark, proof), ctl_vars) in starks
+        .iter()
+        .zip_eq(&multi_proof.stark_proofs)
+        .zip_eq(&ctl_vars_per_table)
+    {
+        stark.verify_with_challenger(proof, ctl_vars, &mut challenger.clone(), config)?;
+    }
+
+    verify_cross_table_lookups::<F, D, N>(
+        cross_table_lookups,
+        core::array::from_fn(|i| {
+            multi_proof.stark_proofs[i]
+                .proof
+                .openings
+                .ctl_zs_first
+                .clone()
+                .unwrap_or_default()
+        }),
+        None,
+        config,
+    )
+}
+
+/// Verifies the proof of a single table of a multi-table system. `challenger` must have observed
+/// the trace caps of all tables and drawn the cross-table lookup challenges.
+pub(crate) fn verify_stark_proof_with_challenger<F, C, S, const D: usize>(
+    stark: &S,
+    proof_with_pis: &StarkProofWithPublicInputs<F, C, D>,
+    ctl_vars: &[CtlCheckVars<F, F::Extension, F::Extension, D>],
+    challenger: &mut Challenger<F, C::Hasher>,
+    config: &StarkConfig,
+) -> Result<()>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    S: Stark<F, D>,
+{
+    ensure!(proof_with_pis.public_inputs.len() == S::PUBLIC_INPUTS);
+    let degree_bits = proof_with_pis.proof.recover_degree_bits(config);
+    let challenges =
+        proof_with_pis.get_challenges_with_challenger(stark, challenger, true, config, degree_bits);
+    verify_stark_proof_with_challenges(stark, proof_with_pis, &challenges, Some(ctl_vars), config)
 }
 
 pub(crate) fn verify_stark_proof_with_challenges<
@@ -40,23 +134,26 @@ pub(crate) fn verify_stark_proof_with_challenges<
     S: Stark<F, D>,
     const D: usize,
 >(
-    stark: S,
-    proof_with_pis: StarkProofWithPublicInputs<F, C, D>,
-    challenges: StarkProofChallenges<F, D>,
-    degree_bits: usize,
+    stark: &S,
+    proof_with_pis: &StarkProofWithPublicInputs<F, C, D>,
+    challenges: &StarkProofChallenges<F, D>,
+    ctl_vars: Option<&[CtlCheckVars<F, F::Extension, F::Extension, D>]>,
     config: &StarkConfig,
 ) -> Result<()> {
-    validate_proof_shape(&stark, &proof_with_pis, config)?;
-    check_permutation_options(&stark, &proof_with_pis, &challenges)?;
+    let num_ctl_zs = ctl_vars.map_or(0, |ctl_vars| ctl_vars.len());
+    validate_proof_shape(stark, proof_with_pis, num_ctl_zs, config)?;
+    check_challenges_options(stark, challenges)?;
     let StarkProofWithPublicInputs {
         proof,
         public_inputs,
     } = proof_with_pis;
+    let degree_bits = proof.recover_degree_bits(config);
     let StarkOpeningSet {
         local_values,
         next_values,
-        permutation_zs,
-        permutation_zs_next,
+        auxiliary_polys,
+        auxiliary_polys_next,
+        ctl_zs_first: _,
         quotient_polys,
     } = &proof.openings;
     let vars = S::EvaluationFrame::from_values(
@@ -81,16 +178,36 @@ pub(crate) fn verify_stark_proof_with_challenges<
         l_0,
         l_last,
     );
-    let permutation_data = stark.uses_permutation_args().then(|| PermutationCheckVars {
-        local_zs: permutation_zs.as_ref().unwrap().clone(),
-        next_zs: permutation_zs_next.as_ref().unwrap().clone(),
-        permutation_challenge_sets: challenges.permutation_challenge_sets.unwrap(),
-    });
+
+    // The auxiliary polynomials are the permutation `Z`s, then the lookup helper columns, then
+    // the cross-table lookup `Z`s.
+    let auxiliary_polys = auxiliary_polys.as_deref().unwrap_or_default();
+    let auxiliary_polys_next = auxiliary_polys_next.as_deref().unwrap_or_default();
+    let permutation_end = stark.num_permutation_batches(config);
+    let lookup_end = permutation_end + stark.num_lookup_helper_columns(config);
+    let permutation_data = challenges
+        .permutation_challenge_sets
+        .as_ref()
+        .map(|challenge_sets| PermutationCheckVars {
+            local_zs: auxiliary_polys[..permutation_end].to_vec(),
+            next_zs: auxiliary_polys_next[..permutation_end].to_vec(),
+            permutation_challenge_sets: challenge_sets.clone(),
+        });
+    let lookup_vars = challenges
+        .lookup_challenges
+        .as_ref()
+        .map(|lookup_challenges| LookupCheckVars {
+            local_values: auxiliary_polys[permutation_end..lookup_end].to_vec(),
+            next_values: auxiliary_polys_next[permutation_end..lookup_end].to_vec(),
+            challenges: lookup_challenges.clone(),
+        });
     eval_vanishing_poly::<F, F::Extension, F::Extension, S, D, D>(
-        &stark,
+        stark,
         config,
         &vars,
         permutation_data,
+        lookup_vars,
+        ctl_vars,
         &mut consumer,
     );
     let vanishing_polys_zeta = consumer.accumulators();
@@ -98,13 +215,13 @@ pub(crate) fn verify_stark_proof_with_challenges<
     // Check each polynomial identity, of the form `vanishing(x) = Z_H(x) quotient(x)`, at zeta.
     let zeta_pow_deg = challenges.stark_zeta.exp_power_of_2(degree_bits);
     let z_h_zeta = zeta_pow_deg - F::Extension::ONE;
-    // `quotient_polys_zeta` holds `num_challenges * quotient_degree_factor` evaluations.
-    // Each chunk of `quotient_degree_factor` holds the evaluations of `t_0(zeta),...,t_{quotient_degree_factor-1}(zeta)`
+    // `quotient_polys_zeta` holds `num_challenges * num_quotient_chunks` evaluations.
+    // Each chunk of `num_quotient_chunks` holds the evaluations of `t_0(zeta),...,t_{num_quotient_chunks-1}(zeta)`
     // where the "real" quotient polynomial is `t(X) = t_0(X) + t_1(X)*X^n + t_2(X)*X^{2n} + ...`.
     // So to reconstruct `t(zeta)` we can compute `reduce_with_powers(chunk, zeta^n)` for each
-    // `quotient_degree_factor`-sized chunk of the original evaluations.
+    // `num_quotient_chunks`-sized chunk of the original evaluations.
     for (i, chunk) in quotient_polys
-        .chunks(stark.quotient_degree_factor())
+        .chunks(stark.num_quotient_chunks(config))
         .enumerate()
     {
         ensure!(
@@ -113,15 +230,16 @@ pub(crate) fn verify_stark_proof_with_challenges<
         );
     }
 
-    let merkle_caps = once(proof.trace_cap)
-        .chain(proof.permutation_zs_cap)
-        .chain(once(proof.quotient_polys_cap))
+    let merkle_caps = once(proof.trace_cap.clone())
+        .chain(proof.auxiliary_polys_cap.clone())
+        .chain(once(proof.quotient_polys_cap.clone()))
         .collect_vec();
 
     verify_fri_proof::<F, C, D>(
         &stark.fri_instance(
             challenges.stark_zeta,
             F::primitive_root_of_unity(degree_bits),
+            num_ctl_zs,
             config,
         ),
         &proof.openings.to_fri_openings(),
@@ -137,6 +255,7 @@ pub(crate) fn verify_stark_proof_with_challenges<
 fn validate_proof_shape<F, C, S, const D: usize>(
     stark: &S,
     proof_with_pis: &StarkProofWithPublicInputs<F, C, D>,
+    num_ctl_zs: usize,
     config: &StarkConfig,
 ) -> anyhow::Result<()>
 where
@@ -152,7 +271,7 @@ where
 
     let StarkProof {
         trace_cap,
-        permutation_zs_cap,
+        auxiliary_polys_cap,
         quotient_polys_cap,
         openings,
         // The shape of the opening proof will be checked in the FRI verifier (see
@@ -163,8 +282,9 @@ where
     let StarkOpeningSet {
         local_values,
         next_values,
-        permutation_zs,
-        permutation_zs_next,
+        auxiliary_polys,
+        auxiliary_polys_next,
+        ctl_zs_first,
         quotient_polys,
     } = openings;
 
@@ -172,7 +292,7 @@ where
 
     let fri_params = config.fri_params(degree_bits);
     let cap_height = fri_params.config.cap_height;
-    let num_zs = stark.num_permutation_batches(config);
+    let num_auxiliary = stark.num_auxiliary_polys(num_ctl_zs, config);
 
     ensure!(trace_cap.height() == cap_height);
     ensure!(quotient_polys_cap.height() == cap_height);
@@ -181,24 +301,33 @@ where
     ensure!(next_values.len() == S::COLUMNS);
     ensure!(quotient_polys.len() == stark.num_quotient_polys(config));
 
-    if stark.uses_permutation_args() {
-        let permutation_zs_cap = permutation_zs_cap
+    if num_auxiliary > 0 {
+        let auxiliary_polys_cap = auxiliary_polys_cap
             .as_ref()
-            .ok_or_else(|| anyhow!("Missing Zs cap"))?;
-        let permutation_zs = permutation_zs
+            .ok_or_else(|| anyhow!("Missing auxiliary polynomials cap"))?;
+        let auxiliary_polys = auxiliary_polys
             .as_ref()
-            .ok_or_else(|| anyhow!("Missing permutation_zs"))?;
-        let permutation_zs_next = permutation_zs_next
+            .ok_or_else(|| anyhow!("Missing auxiliary_polys"))?;
+        let auxiliary_polys_next = auxiliary_polys_next
             .as_ref()
-            .ok_or_else(|| anyhow!("Missing permutation_zs_next"))?;
+            .ok_or_else(|| anyhow!("Missing auxiliary_polys_next"))?;
 
-        ensure!(permutation_zs_cap.height() == cap_height);
-        ensure!(permutation_zs.len() == num_zs);
-        ensure!(permutation_zs_next.len() == num_zs);
+        ensure!(auxiliary_polys_cap.height() == cap_height);
+        ensure!(auxiliary_polys.len() == num_auxiliary);
+        ensure!(auxiliary_polys_next.len() == num_auxiliary);
     } else {
-        ensure!(permutation_zs_cap.is_none());
-        ensure!(permutation_zs.is_none());
-        ensure!(permutation_zs_next.is_none());
+        ensure!(auxiliary_polys_cap.is_none());
+        ensure!(auxiliary_polys.is_none());
+        ensure!(auxiliary_polys_next.is_none());
+    }
+
+    if num_ctl_zs > 0 {
+        let ctl_zs_first = ctl_zs_first
+            .as_ref()
+            .ok_or_else(|| anyhow!("Missing ctl_zs_first"))?;
+        ensure!(ctl_zs_first.len() == num_ctl_zs);
+    } else {
+        ensure!(ctl_zs_first.is_none());
     }
 
     Ok(())
@@ -216,30 +345,20 @@ fn eval_l_0_and_l_last<F: Field>(log_n: usize, x: F) -> (F, F) {
     (z_x * invs[0], z_x * invs[1])
 }
 
-/// Utility function to check that all permutation data wrapped in `Option`s are `Some` iff
-/// the Stark uses a permutation argument.
-fn check_permutation_options<
-    F: RichField + Extendable<D>,
-    C: GenericConfig<D, F = F>,
-    S: Stark<F, D>,
-    const D: usize,
->(
+/// Utility function to check that the permutation and lookup challenges are `Some` iff the Stark
+/// uses the corresponding argument.
+fn check_challenges_options<F: RichField + Extendable<D>, S: Stark<F, D>, const D: usize>(
     stark: &S,
-    proof_with_pis: &StarkProofWithPublicInputs<F, C, D>,
     challenges: &StarkProofChallenges<F, D>,
 ) -> Result<()> {
-    let options_is_some = [
-        proof_with_pis.proof.permutation_zs_cap.is_some(),
-        proof_with_pis.proof.openings.permutation_zs.is_some(),
-        proof_with_pis.proof.openings.permutation_zs_next.is_some(),
-        challenges.permutation_challenge_sets.is_some(),
-    ];
     ensure!(
-        options_is_some
-            .into_iter()
-            .all(|b| b == stark.uses_permutation_args()),
+        challenges.permutation_challenge_sets.is_some() == stark.uses_permutation_args(),
         "Permutation data doesn't match with Stark configuration."
     );
+    ensure!(
+        challenges.lookup_challenges.is_some() == stark.uses_lookups(),
+        "Lookup data doesn't match with Stark configuration."
+    );
     Ok(())
 }
 
@@ -0,0 +1,61 @@
+//! Verification of serialized proofs.
+//!
+//! These functions are fixed to `PoseidonGoldilocksConfig` with `D = 2`, the configuration used by
+//! virtually all plonky2 and starky proofs, so that they can be called without any generics, e.g.
+//! from JavaScript. Verifier circuit data is read with the `DefaultGateSerializer`.
+
+use alloc::format;
+
+use anyhow::{anyhow, Result};
+use plonky2::field::goldilocks_field::GoldilocksField;
+use plonky2::plonk::circuit_data::VerifierCircuitData;
+use plonky2::plonk::config::PoseidonGoldilocksConfig;
+use plonky2::plonk::proof::{CompressedProofWithPublicInputs, ProofWithPublicInputs};
+use plonky2::util::serialization::DefaultGateSerializer;
+use starky::config::StarkConfig;
+use starky::proof::StarkProofWithPublicInputs;
+use starky::stark::Stark;
+use starky::verifier::verify_stark_proof;
+
+pub const D: usize = 2;
+pub type C = PoseidonGoldilocksConfig;
+pub type F = GoldilocksField;
+
+/// Reads serialized `VerifierCircuitData`, as written by `VerifierCircuitData::to_bytes` with the
+/// `DefaultGateSerializer`.
+pub fn read_verifier_circuit_data(bytes: &[u8]) -> Result<VerifierCircuitData<F, C, D>> {
+    VerifierCircuitData::from_bytes(bytes.to_vec(), &DefaultGateSerializer)
+        .map_err(|e| anyhow!(format!("Invalid verifier circuit data: {e}")))
+}
+
+/// Verifies a serialized plonky2 proof, as written by `ProofWithPublicInputs::to_bytes`, against
+/// serialized verifier circuit data.
+pub fn verify_proof_bytes(verifier_circuit_data: &[u8], proof: &[u8]) -> Result<()> {
+    let verifier_circuit_data = read_verifier_circuit_data(verifier_circuit_data)?;
+    let proof = ProofWithPublicInputs::from_bytes(proof.to_vec(), &verifier_circuit_data.common)?;
+    verifier_circuit_data.verify(proof)
+}
+
+/// Verifies a serialized compressed plonky2 proof, as written by
+/// `CompressedProofWithPublicInputs::to_bytes`, against serialized verifier circuit data.
+pub fn verify_compressed_proof_bytes(
+    verifier_circuit_data: &[u8],
+    compressed_proof: &[u8],
+) -> Result<()> {
+    let verifier_circuit_data = read_verifier_circuit_data(verifier_circuit_data)?;
+    let compressed_proof = CompressedProofWithPublicInputs::from_bytes(
+        compressed_proof.to_vec(),
+        &verifier_circuit_data.common,
+    )?;
+    verifier_circuit_data.verify_compressed(compressed_proof)
+}
+
+/// Verifies a serialized STARK proof, as written by `StarkProofWithPublicInputs::to_bytes`.
+pub fn verify_stark_proof_bytes<S: Stark<F, D>>(
+    stark: S,
+    config: &StarkConfig,
+    proof: &[u8],
+) -> Result<()> {
+    let proof = StarkProofWithPublicInputs::<F, C, D>::from_bytes(proof.to_vec(), &stark, config)?;
+    verify_stark_proof(stark, proof, config)
+}
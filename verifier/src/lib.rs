@@ -0,0 +1,24 @@
+//! A slim verifier for plonky2 and starky proofs.
+//!
+//! This crate only exposes proof verification, on top of `plonky2` and `starky` built without
+//! default features: no `std`, no rayon, and no compile-time RNG for `hashbrown`'s hasher. It
+//! compiles for `wasm32-unknown-unknown`, and the [`bytes`] module offers a byte-oriented API which
+//! can be exported with `wasm-bindgen` through the `wasm` feature.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+pub mod bytes;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+pub use plonky2::plonk::circuit_data::{
+    CommonCircuitData, VerifierCircuitData, VerifierOnlyCircuitData,
+};
+pub use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+pub use plonky2::plonk::proof::{CompressedProofWithPublicInputs, ProofWithPublicInputs};
+pub use starky::config::StarkConfig;
+pub use starky::proof::StarkProofWithPublicInputs;
+pub use starky::stark::Stark;
+pub use starky::verifier::verify_stark_proof;
@@ -0,0 +1,24 @@
+//! `wasm-bindgen` exports of the byte API. Errors are returned as strings, which become thrown
+//! JavaScript errors.
+
+use alloc::string::{String, ToString};
+
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::bytes::{verify_compressed_proof_bytes, verify_proof_bytes};
+
+/// Verifies a serialized plonky2 proof against serialized verifier circuit data.
+#[wasm_bindgen(js_name = verifyProof)]
+pub fn verify_proof(verifier_circuit_data: &[u8], proof: &[u8]) -> Result<(), String> {
+    verify_proof_bytes(verifier_circuit_data, proof).map_err(|e| e.to_string())
+}
+
+/// Verifies a serialized compressed plonky2 proof against serialized verifier circuit data.
+#[wasm_bindgen(js_name = verifyCompressedProof)]
+pub fn verify_compressed_proof(
+    verifier_circuit_data: &[u8],
+    compressed_proof: &[u8],
+) -> Result<(), String> {
+    verify_compressed_proof_bytes(verifier_circuit_data, compressed_proof)
+        .map_err(|e| e.to_string())
+}
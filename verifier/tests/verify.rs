@@ -0,0 +1,255 @@
+//! Verifies serialized fixture proofs with the byte API. Apart from `generate_fixtures`, which
+//! regenerates the fixtures natively, these tests don't run any prover, so they can also be run
+//! under a wasm runtime; see the crate README.
+
+use core::marker::PhantomData;
+
+use plonky2::field::extension::{Extendable, FieldExtension};
+use plonky2::field::packed::PackedField;
+use plonky2::field::polynomial::PolynomialValues;
+use plonky2::field::types::Field;
+use plonky2::hash::hash_types::RichField;
+use plonky2::iop::ext_target::ExtensionTarget;
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+use plonky2_verifier::bytes::{
+    read_verifier_circuit_data, verify_compressed_proof_bytes, verify_proof_bytes,
+    verify_stark_proof_bytes, F,
+};
+use plonky2_verifier::{Stark, StarkConfig};
+use starky::constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer};
+use starky::evaluation_frame::{StarkEvaluationFrame, StarkFrame};
+
+const VERIFIER_CIRCUIT_DATA: &[u8] = include_bytes!("fixtures/verifier_circuit_data.bin");
+const PROOF: &[u8] = include_bytes!("fixtures/proof.bin");
+const COMPRESSED_PROOF: &[u8] = include_bytes!("fixtures/compressed_proof.bin");
+const STARK_PROOF: &[u8] = include_bytes!("fixtures/stark_proof.bin");
+
+/// Number of steps of the Fibonacci sequence computed by the plonky2 fixture circuit.
+const NUM_CIRCUIT_STEPS: usize = 100;
+/// Number of rows of the STARK fixture trace.
+const NUM_STARK_ROWS: usize = 1 << 5;
+
+/// Computes a Fibonacci sequence with state `[x0, x1]` using the state transition
+/// `x0' <- x1, x1' <- x0 + x1`. The public inputs are `x0`, `x1` and the last `x1`.
+#[derive(Copy, Clone)]
+struct FibonacciStark<F: RichField + Extendable<D>, const D: usize> {
+    num_rows: usize,
+    _phantom: PhantomData<F>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> FibonacciStark<F, D> {
+    fn new(num_rows: usize) -> Self {
+        Self {
+            num_rows,
+            _phantom: PhantomData,
+        }
+    }
+
+    #[cfg(not(target_family = "wasm"))]
+    fn generate_trace(&self, x0: F, x1: F) -> Vec<PolynomialValues<F>> {
+        let trace_rows = (0..self.num_rows)
+            .scan([x0, x1], |acc, _| {
+                let tmp = *acc;
+                acc[0] = tmp[1];
+                acc[1] = tmp[0] + tmp[1];
+                Some(tmp)
+            })
+            .collect::<Vec<_>>();
+        starky::util::trace_rows_to_poly_values(trace_rows)
+    }
+}
+
+const COLUMNS: usize = 2;
+const PUBLIC_INPUTS: usize = 3;
+
+impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for FibonacciStark<F, D> {
+    type EvaluationFrame<FE, P, const D2: usize>
+        = StarkFrame<P, P::Scalar, COLUMNS, PUBLIC_INPUTS>
+    where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>;
+
+    type EvaluationFrameTarget =
+        StarkFrame<ExtensionTarget<D>, ExtensionTarget<D>, COLUMNS, PUBLIC_INPUTS>;
+
+    fn eval_packed_generic<FE, P, const D2: usize>(
+        &self,
+        vars: &Self::EvaluationFrame<FE, P, D2>,
+        yield_constr: &mut ConstraintConsumer<P>,
+    ) where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>,
+    {
+        let local_values = vars.get_local_values();
+        let next_values = vars.get_next_values();
+        let public_inputs = vars.get_public_inputs();
+
+        yield_constr.constraint_first_row(local_values[0] - public_inputs[0]);
+        yield_constr.constraint_first_row(local_values[1] - public_inputs[1]);
+        yield_constr.constraint_last_row(local_values[1] - public_inputs[2]);
+        yield_constr.constraint_transition(next_values[0] - local_values[1]);
+        yield_constr.constraint_transition(next_values[1] - local_values[0] - local_values[1]);
+    }
+
+    fn eval_ext_circuit(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        vars: &Self::EvaluationFrameTarget,
+        yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    ) {
+        let local_values = vars.get_local_values();
+        let next_values = vars.get_next_values();
+        let public_inputs = vars.get_public_inputs();
+
+        let constraint = builder.sub_extension(local_values[0], public_inputs[0]);
+        yield_constr.constraint_first_row(builder, constraint);
+        let constraint = builder.sub_extension(local_values[1], public_inputs[1]);
+        yield_constr.constraint_first_row(builder, constraint);
+        let constraint = builder.sub_extension(local_values[1], public_inputs[2]);
+        yield_constr.constraint_last_row(builder, constraint);
+        let constraint = builder.sub_extension(next_values[0], local_values[1]);
+        yield_constr.constraint_transition(builder, constraint);
+        let constraint = {
+            let tmp = builder.sub_extension(next_values[1], local_values[0]);
+            builder.sub_extension(tmp, local_values[1])
+        };
+        yield_constr.constraint_transition(builder, constraint);
+    }
+
+    fn constraint_degree(&self) -> usize {
+        2
+    }
+}
+
+fn fibonacci(x0: F, x1: F, steps: usize) -> F {
+    (0..steps).fold([x0, x1], |[a, b], _| [b, a + b])[0]
+}
+
+#[test]
+fn test_verify_proof() {
+    verify_proof_bytes(VERIFIER_CIRCUIT_DATA, PROOF).unwrap();
+
+    let verifier_circuit_data = read_verifier_circuit_data(VERIFIER_CIRCUIT_DATA).unwrap();
+    assert_eq!(verifier_circuit_data.common.num_public_inputs, 3);
+}
+
+#[test]
+fn test_verify_compressed_proof() {
+    verify_compressed_proof_bytes(VERIFIER_CIRCUIT_DATA, COMPRESSED_PROOF).unwrap();
+}
+
+#[test]
+fn test_reject_tampered_proof() {
+    let mut proof = PROOF.to_vec();
+    let last = proof.len() - 1;
+    proof[last] ^= 1;
+    assert!(verify_proof_bytes(VERIFIER_CIRCUIT_DATA, &proof).is_err());
+    assert!(verify_proof_bytes(VERIFIER_CIRCUIT_DATA, &PROOF[..PROOF.len() / 2]).is_err());
+}
+
+#[test]
+fn test_verify_stark_proof() {
+    let stark = FibonacciStark::<F, 2>::new(NUM_STARK_ROWS);
+    let config = StarkConfig::standard_fast_config();
+    verify_stark_proof_bytes(stark, &config, STARK_PROOF).unwrap();
+
+    let mut proof = STARK_PROOF.to_vec();
+    let last = proof.len() - 1;
+    proof[last] ^= 1;
+    assert!(verify_stark_proof_bytes(stark, &config, &proof).is_err());
+}
+
+/// Builds the plonky2 fixture circuit, returning it along with its two initial targets.
+#[cfg(not(target_family = "wasm"))]
+fn fixture_circuit() -> (
+    plonky2::plonk::circuit_data::CircuitData<F, plonky2_verifier::bytes::C, 2>,
+    [plonky2::iop::target::Target; 2],
+) {
+    use plonky2::plonk::circuit_data::CircuitConfig;
+
+    let mut builder = CircuitBuilder::<F, 2>::new(CircuitConfig::standard_recursion_config());
+    let initial_a = builder.add_virtual_target();
+    let initial_b = builder.add_virtual_target();
+    let mut prev_target = initial_a;
+    let mut cur_target = initial_b;
+    for _ in 0..NUM_CIRCUIT_STEPS {
+        let temp = builder.add(prev_target, cur_target);
+        prev_target = cur_target;
+        cur_target = temp;
+    }
+    builder.register_public_input(initial_a);
+    builder.register_public_input(initial_b);
+    builder.register_public_input(prev_target);
+    (builder.build(), [initial_a, initial_b])
+}
+
+/// Checks that the fixtures were generated with the current circuit data serialization, which
+/// changes e.g. when gates are added to the default gate serializer out of order.
+#[test]
+#[cfg(not(target_family = "wasm"))]
+fn test_fixtures_are_current() {
+    use plonky2::util::serialization::DefaultGateSerializer;
+
+    let (data, _) = fixture_circuit();
+    let verifier_circuit_data = data
+        .verifier_data()
+        .to_bytes(&DefaultGateSerializer)
+        .unwrap();
+    assert!(
+        verifier_circuit_data == VERIFIER_CIRCUIT_DATA,
+        "outdated fixtures, regenerate them with `generate_fixtures`"
+    );
+}
+
+/// Regenerates the fixtures with:
+/// `cargo test -p plonky2_verifier --release -- --ignored generate_fixtures`
+#[test]
+#[ignore]
+#[cfg(not(target_family = "wasm"))]
+fn generate_fixtures() -> anyhow::Result<()> {
+    use plonky2::iop::witness::{PartialWitness, WitnessWrite};
+    use plonky2::util::serialization::DefaultGateSerializer;
+    use plonky2::util::timing::TimingTree;
+    use plonky2_verifier::bytes::{C, D};
+    use starky::prover::prove;
+
+    let write = |name: &str, bytes: &[u8]| {
+        let path = format!("{}/tests/fixtures/{name}", env!("CARGO_MANIFEST_DIR"));
+        std::fs::write(path, bytes)
+    };
+
+    let (data, [initial_a, initial_b]) = fixture_circuit();
+
+    let mut pw = PartialWitness::new();
+    pw.set_target(initial_a, F::ZERO);
+    pw.set_target(initial_b, F::ONE);
+    let proof = data.prove(pw)?;
+    assert_eq!(
+        proof.public_inputs[2],
+        fibonacci(F::ZERO, F::ONE, NUM_CIRCUIT_STEPS)
+    );
+    let compressed_proof = data.compress(proof.clone())?;
+
+    let verifier_circuit_data = data
+        .verifier_data()
+        .to_bytes(&DefaultGateSerializer)
+        .map_err(|e| anyhow::anyhow!("{e}"))?;
+    write("verifier_circuit_data.bin", &verifier_circuit_data)?;
+    write("proof.bin", &proof.to_bytes())?;
+    write("compressed_proof.bin", &compressed_proof.to_bytes())?;
+
+    let stark = FibonacciStark::<F, D>::new(NUM_STARK_ROWS);
+    let config = StarkConfig::standard_fast_config();
+    let public_inputs = [F::ZERO, F::ONE, fibonacci(F::ZERO, F::ONE, NUM_STARK_ROWS)];
+    let trace = stark.generate_trace(public_inputs[0], public_inputs[1]);
+    let stark_proof = prove::<F, C, _, D>(
+        stark,
+        &config,
+        trace,
+        &public_inputs,
+        &mut TimingTree::default(),
+    )?;
+    write("stark_proof.bin", &stark_proof.to_bytes(&config))?;
+
+    Ok(())
+}